@@ -0,0 +1,57 @@
+// Sequential vs parallel batch verification of quantum-safe transaction proofs.
+//
+//     cargo bench --features parallel --bench batch_verify
+//
+// Every entry reuses one proof; verification cost does not depend on which
+// proof is checked, and proving hundreds of distinct ones would dominate the run.
+use std::time::{Duration, Instant};
+
+use axiom_core::crypto::{
+    QuantumSafeSignatures, QuantumTransactionBuilder, QuantumTransactionVerifier, SecurityLevel,
+};
+
+const SIZES: [usize; 2] = [64, 256];
+const ROUNDS: u32 = 3;
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        f();
+    }
+    start.elapsed() / ROUNDS
+}
+
+fn main() {
+    let (_, sk) = QuantumSafeSignatures::generate_keypair(SecurityLevel::Dilithium3).expect("keypair");
+    let proof = QuantumTransactionBuilder::new()
+        .create_transaction_proof(&[1u8; 32], &[2u8; 32], 100, 1, &sk)
+        .expect("proof");
+    let verifier = QuantumTransactionVerifier::new();
+
+    println!("{:>6} {:>14} {:>14} {:>8}", "proofs", "sequential", "parallel", "speedup");
+    for n in SIZES {
+        let proofs = vec![proof.clone(); n];
+        let senders = vec![[1u8; 32]; n];
+        let receivers = vec![[2u8; 32]; n];
+        let amounts = vec![100u64; n];
+        let nonces = vec![1u64; n];
+
+        let sequential = time(|| {
+            verifier
+                .batch_verify_transactions_sequential(&proofs, &senders, &receivers, &amounts, &nonces)
+                .unwrap();
+        });
+        let parallel = time(|| {
+            verifier
+                .batch_verify_transactions(&proofs, &senders, &receivers, &amounts, &nonces)
+                .unwrap();
+        });
+        println!(
+            "{:>6} {:>14?} {:>14?} {:>7.2}x",
+            n,
+            sequential,
+            parallel,
+            sequential.as_secs_f64() / parallel.as_secs_f64()
+        );
+    }
+}
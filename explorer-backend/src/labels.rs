@@ -0,0 +1,108 @@
+//! Known-address registry.
+//!
+//! Well-known addresses (exchanges, the bridge contract, the burn address)
+//! are annotated with a human name and tags. The bridge contract and the
+//! genesis miner are always labeled; anything else comes from an optional
+//! `labels.toml`:
+//!
+//! ```toml
+//! [labels."0x4f2a..."]
+//! name = "Example Exchange hot wallet"
+//! tags = ["exchange"]
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Mirrors `BridgeContract::BRIDGE_ADDRESS` in the node
+pub const BRIDGE_ADDRESS: &str = "0x8400000000000000000000000000000000000001";
+
+/// Name and tags attached to an address
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AddressLabel {
+    pub name: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Entry of the `/api/labels` listing
+#[derive(Debug, Clone, Serialize)]
+pub struct LabeledAddress {
+    pub address: String,
+    #[serde(flatten)]
+    pub label: AddressLabel,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct LabelsFile {
+    #[serde(default)]
+    labels: BTreeMap<String, AddressLabel>,
+}
+
+/// Labels keyed by normalized address (lowercase, no `0x`)
+#[derive(Debug, Default)]
+pub struct Labels {
+    entries: BTreeMap<String, AddressLabel>,
+}
+
+impl Labels {
+    /// The built-in labels: the bridge contract and `genesis_miner`
+    pub fn builtin(genesis_miner: &str) -> Self {
+        let mut labels = Self::default();
+        labels.insert(BRIDGE_ADDRESS, AddressLabel { name: "Bridge contract".to_string(), tags: vec!["bridge".to_string()] });
+        labels.insert(genesis_miner, AddressLabel { name: "Genesis miner".to_string(), tags: vec!["genesis".to_string()] });
+        labels
+    }
+
+    /// Built-in labels plus those in the TOML file at `path`, which take
+    /// precedence. A missing file is not an error; an unreadable or
+    /// malformed one is logged and skipped.
+    pub fn load(path: &Path, genesis_miner: &str) -> Self {
+        let mut labels = Self::builtin(genesis_miner);
+        match std::fs::read_to_string(path) {
+            Ok(contents) => {
+                if let Err(e) = labels.merge_toml(&contents) {
+                    log::warn!("Ignoring labels file {}: {}", path.display(), e);
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => log::warn!("Cannot read labels file {}: {}", path.display(), e),
+        }
+        labels
+    }
+
+    /// Add the labels of a `labels.toml` document
+    pub fn merge_toml(&mut self, contents: &str) -> Result<(), toml::de::Error> {
+        let file: LabelsFile = toml::from_str(contents)?;
+        for (address, label) in file.labels {
+            self.insert(&address, label);
+        }
+        Ok(())
+    }
+
+    pub fn insert(&mut self, address: &str, label: AddressLabel) {
+        self.entries.insert(normalize(address), label);
+    }
+
+    pub fn get(&self, address: &str) -> Option<&AddressLabel> {
+        self.entries.get(&normalize(address))
+    }
+
+    /// Display name of `address`, if labeled
+    pub fn name(&self, address: &str) -> Option<String> {
+        self.get(address).map(|label| label.name.clone())
+    }
+
+    pub fn list(&self) -> Vec<LabeledAddress> {
+        self.entries
+            .iter()
+            .map(|(address, label)| LabeledAddress { address: address.clone(), label: label.clone() })
+            .collect()
+    }
+}
+
+fn normalize(address: &str) -> String {
+    let address = address.trim();
+    address.strip_prefix("0x").unwrap_or(address).to_ascii_lowercase()
+}
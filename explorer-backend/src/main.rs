@@ -1,7 +1,13 @@
 use actix_web::{web, App, HttpResponse, HttpServer, Responder};
 use actix_cors::Cors;
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+mod labels;
+mod vdf;
+
+use labels::Labels;
+use vdf::{VdfProof, VdfProofError};
 
 /// Block data structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +25,36 @@ struct Block {
     vdf_proof: Option<String>,
     size: usize,
     reward: u64,
+    #[serde(default)]
+    status: BlockStatus,
+}
+
+impl Block {
+    /// The VDF output and proof, if present and well-formed
+    fn vdf_proof_typed(&self) -> Option<VdfProof> {
+        self.checked_vdf_proof().ok().flatten()
+    }
+
+    fn checked_vdf_proof(&self) -> Result<Option<VdfProof>, VdfProofError> {
+        VdfProof::from_fields(self.vdf_output.as_deref(), self.vdf_proof.as_deref())
+    }
+}
+
+/// Whether a block is on the main chain or was dropped by a reorg
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum BlockStatus {
+    #[default]
+    Canonical,
+    Orphaned,
+}
+
+/// A block that was replaced on a discarded fork
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct OrphanedBlock {
+    block: Block,
+    /// Hash of the main-chain block now at this height, once seen
+    replaced_by: Option<String>,
 }
 
 /// Transaction data structure
@@ -33,6 +69,7 @@ struct Transaction {
     signature: String,
     block_hash: Option<String>,
     block_index: Option<u64>,
+    /// Recomputed from the tip on every response; 0 while in the mempool
     confirmations: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     zk_proof: Option<String>,
@@ -73,6 +110,8 @@ struct AddressInfo {
     total_sent: u64,
     tx_count: u32,
     recent_transactions: Vec<Transaction>,
+    /// Name from the known-address registry
+    label: Option<String>,
 }
 
 /// Search result
@@ -82,13 +121,56 @@ enum SearchResult {
     Block { data: Block },
     Transaction { data: Transaction },
     Address { data: AddressInfo },
+    /// Several blocks, transactions or addresses share the searched prefix
+    Candidates { data: Vec<SearchCandidate> },
+    /// Blocks and transactions matching an amount or date query
+    Matches { blocks: Vec<BlockSummary>, transactions: Vec<Transaction> },
     NotFound,
 }
 
+/// One match of an ambiguous prefix search
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SearchCandidate {
+    /// "block", "transaction" or "address"
+    kind: String,
+    id: String,
+    /// Known-address label of an "address" candidate
+    label: Option<String>,
+}
+
+/// Shortest hash or address prefix the search will expand
+const MIN_PREFIX_LEN: usize = 6;
+/// Most candidates listed for an ambiguous prefix
+const MAX_CANDIDATES: usize = 20;
+/// Base units per AXM
+const UNITS_PER_AXM: u64 = 100_000_000;
+
 /// Shared application state
 struct AppState {
-    blocks: Mutex<Vec<Block>>,
-    transactions: Mutex<Vec<Transaction>>,
+    blocks: RwLock<Vec<Block>>,
+    transactions: RwLock<Vec<Transaction>>,
+    labels: Labels,
+    orphans: RwLock<Vec<OrphanedBlock>>,
+}
+
+/// Shared access to `lock`. A handler that panicked while holding it
+/// leaves it poisoned; the data is still served rather than failing every
+/// later request.
+fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(|poisoned| {
+        log::error!("Recovering lock poisoned by a panicked handler");
+        lock.clear_poison();
+        poisoned.into_inner()
+    })
+}
+
+/// Exclusive access to `lock`, recovering from poisoning like `read`
+fn write<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(|poisoned| {
+        log::error!("Recovering lock poisoned by a panicked handler");
+        lock.clear_poison();
+        poisoned.into_inner()
+    })
 }
 
 impl AppState {
@@ -108,6 +190,7 @@ impl AppState {
             vdf_proof: None,
             size: 285,
             reward: 5000000000, // 50 AXM
+            status: BlockStatus::Canonical,
         };
 
         // Sample blocks
@@ -130,7 +213,7 @@ impl AppState {
                         signature: format!("{:0128x}", i * 333),
                         block_hash: Some(format!("{:064x}", i * 123456789)),
                         block_index: Some(i),
-                        confirmations: (10 - i) as u32 + 1,
+                        confirmations: 0,
                         zk_proof: if i % 3 == 0 { Some("zkproof...".to_string()) } else { None },
                     },
                 ],
@@ -142,44 +225,91 @@ impl AppState {
                 vdf_proof: Some(format!("{:064x}", i * 777)),
                 size: 1024 + (i as usize * 256),
                 reward: 5000000000, // 50 AXM
+                status: BlockStatus::Canonical,
             };
             blocks.push(block);
         }
 
-        let transactions: Vec<Transaction> = blocks.iter()
-            .flat_map(|b| b.transactions.clone())
-            .collect();
+        let state = Self {
+            blocks: RwLock::new(Vec::new()),
+            transactions: RwLock::new(Vec::new()),
+            labels: Labels::load(&labels_path(), &blocks[0].miner),
+            orphans: RwLock::new(Vec::new()),
+        };
+        for block in blocks {
+            state.ingest_block(block);
+        }
+        state
+    }
 
-        Self {
-            blocks: Mutex::new(blocks),
-            transactions: Mutex::new(transactions),
+    /// Blocks and transactions under shared locks taken together, so both
+    /// reflect the same tip. Any number of readers hold these at once; only
+    /// `ingest_block` waits for them.
+    fn snapshot(&self) -> (RwLockReadGuard<'_, Vec<Block>>, RwLockReadGuard<'_, Vec<Transaction>>) {
+        (read(&self.blocks), read(&self.transactions))
+    }
+
+    /// Add a block from the node. A block at a height already indexed with
+    /// a different hash means the chain reorganized: that block and every
+    /// block above it are moved to the orphan list, along with their
+    /// transactions, and the new block takes the height. Blocks whose VDF
+    /// fields are not a well-formed proof are ignored; the rest are stored
+    /// with the proof in canonical lowercase hex.
+    fn ingest_block(&self, block: Block) {
+        let mut blocks = write(&self.blocks);
+        let mut transactions = write(&self.transactions);
+        let mut orphans = write(&self.orphans);
+        let height = block.index as usize;
+
+        if height > blocks.len() {
+            log::warn!("Ignoring block {} above the indexed tip {}", block.index, blocks.len() as i64 - 1);
+            return;
         }
+        if let Err(e) = block.checked_vdf_proof() {
+            log::warn!("Ignoring block {} at height {}: {}", block.hash, block.index, e);
+            return;
+        }
+        if height < blocks.len() {
+            if blocks[height].hash == block.hash {
+                return;
+            }
+            for mut orphan in blocks.drain(height..) {
+                log::info!("Block {} at height {} orphaned by a reorg", orphan.hash, orphan.index);
+                transactions.retain(|tx| tx.block_hash.as_ref() != Some(&orphan.hash));
+                orphan.status = BlockStatus::Orphaned;
+                orphans.push(OrphanedBlock { block: orphan, replaced_by: None });
+            }
+        }
+
+        for orphan in orphans.iter_mut().filter(|o| o.block.index == block.index && o.replaced_by.is_none()) {
+            orphan.replaced_by = Some(block.hash.clone());
+        }
+        transactions.extend(block.transactions.iter().cloned());
+        let vdf = block.vdf_proof_typed();
+        blocks.push(Block {
+            status: BlockStatus::Canonical,
+            vdf_output: vdf.map(|vdf| vdf.output_hex()),
+            vdf_proof: vdf.map(|vdf| vdf.proof_hex()),
+            ..block
+        });
     }
 }
 
 /// Get network statistics
 async fn get_stats(data: web::Data<AppState>) -> impl Responder {
-    let blocks = data.blocks.lock().unwrap();
-    let transactions = data.transactions.lock().unwrap();
+    let (blocks, transactions) = data.snapshot();
     
     let height = blocks.len() as u64 - 1;
     let latest_blocks: Vec<BlockSummary> = blocks.iter()
         .rev()
         .take(10)
-        .map(|b| BlockSummary {
-            index: b.index,
-            hash: b.hash.clone(),
-            timestamp: b.timestamp,
-            tx_count: b.transactions.len(),
-            miner: b.miner.clone(),
-            reward: b.reward,
-        })
+        .map(block_summary)
         .collect();
 
     let stats = NetworkStats {
         height,
         total_transactions: transactions.len() as u64,
-        total_supply: 124000000_00000000, // 124M AXM in satoshis
+        total_supply: 12_400_000_000_000_000, // 124M AXM in satoshis
         circulating_supply: height * 5000000000, // 50 AXM per block
         difficulty: blocks.last().map(|b| b.difficulty).unwrap_or(1000),
         hash_rate: 123456789.0, // Simulated
@@ -197,19 +327,25 @@ async fn get_block(
     path: web::Path<String>,
     data: web::Data<AppState>,
 ) -> impl Responder {
-    let blocks = data.blocks.lock().unwrap();
+    let blocks = read(&data.blocks);
     let identifier = path.into_inner();
+    let tip = tip_height(&blocks);
 
     // Try parsing as index first
     if let Ok(index) = identifier.parse::<u64>() {
         if let Some(block) = blocks.iter().find(|b| b.index == index) {
-            return HttpResponse::Ok().json(block);
+            return HttpResponse::Ok().json(block_with_confirmations(block.clone(), tip));
         }
     }
 
     // Try as hash
     if let Some(block) = blocks.iter().find(|b| b.hash == identifier) {
-        return HttpResponse::Ok().json(block);
+        return HttpResponse::Ok().json(block_with_confirmations(block.clone(), tip));
+    }
+
+    // An orphaned block is still served, marked as such and unconfirmed
+    if let Some(orphan) = read(&data.orphans).iter().find(|o| o.block.hash == identifier) {
+        return HttpResponse::Ok().json(block_with_confirmations(orphan.block.clone(), tip));
     }
 
     HttpResponse::NotFound().json(serde_json::json!({
@@ -222,13 +358,18 @@ async fn get_latest_blocks(
     query: web::Query<std::collections::HashMap<String, String>>,
     data: web::Data<AppState>,
 ) -> impl Responder {
-    let blocks = data.blocks.lock().unwrap();
+    let blocks = read(&data.blocks);
     let limit = query.get("limit")
         .and_then(|l| l.parse::<usize>().ok())
         .unwrap_or(20)
         .min(100);
 
-    let latest: Vec<&Block> = blocks.iter().rev().take(limit).collect();
+    let tip = tip_height(&blocks);
+    let latest: Vec<Block> = blocks.iter()
+        .rev()
+        .take(limit)
+        .map(|b| block_with_confirmations(b.clone(), tip))
+        .collect();
     HttpResponse::Ok().json(latest)
 }
 
@@ -237,11 +378,12 @@ async fn get_transaction(
     path: web::Path<String>,
     data: web::Data<AppState>,
 ) -> impl Responder {
-    let transactions = data.transactions.lock().unwrap();
+    let (blocks, transactions) = data.snapshot();
+    let tip = tip_height(&blocks);
     let hash = path.into_inner();
 
     if let Some(tx) = transactions.iter().find(|t| t.hash == hash) {
-        return HttpResponse::Ok().json(tx);
+        return HttpResponse::Ok().json(with_confirmations(tx.clone(), tip));
     }
 
     HttpResponse::NotFound().json(serde_json::json!({
@@ -254,13 +396,14 @@ async fn get_address(
     path: web::Path<String>,
     data: web::Data<AppState>,
 ) -> impl Responder {
-    let transactions = data.transactions.lock().unwrap();
+    let (blocks, transactions) = data.snapshot();
+    let tip = tip_height(&blocks);
     let address = path.into_inner();
 
     // Find all transactions involving this address
     let addr_txs: Vec<Transaction> = transactions.iter()
         .filter(|tx| tx.sender == address || tx.recipient == address)
-        .cloned()
+        .map(|tx| with_confirmations(tx.clone(), tip))
         .collect();
 
     if addr_txs.is_empty() {
@@ -269,32 +412,36 @@ async fn get_address(
         }));
     }
 
-    // Calculate balance and stats
-    let mut balance: i64 = 0;
-    let mut total_received: u64 = 0;
-    let mut total_sent: u64 = 0;
+    let mut info = address_info(&address, addr_txs);
+    info.label = data.labels.name(&address);
+    HttpResponse::Ok().json(info)
+}
+
+/// Balance and totals of `address` over `addr_txs`. Sums are widened to
+/// `u128` so large amounts cannot overflow, then clamped to `u64`.
+fn address_info(address: &str, addr_txs: Vec<Transaction>) -> AddressInfo {
+    let mut total_received: u128 = 0;
+    let mut total_sent: u128 = 0;
 
     for tx in &addr_txs {
         if tx.recipient == address {
-            balance += tx.amount as i64;
-            total_received += tx.amount;
+            total_received += tx.amount as u128;
         }
         if tx.sender == address {
-            balance -= (tx.amount + tx.fee) as i64;
-            total_sent += tx.amount + tx.fee;
+            total_sent += tx.amount as u128 + tx.fee as u128;
         }
     }
 
-    let info = AddressInfo {
-        address: address.clone(),
-        balance: balance.max(0) as u64,
-        total_received,
-        total_sent,
+    let clamp = |value: u128| u64::try_from(value).unwrap_or(u64::MAX);
+    AddressInfo {
+        address: address.to_string(),
+        balance: clamp(total_received.saturating_sub(total_sent)),
+        total_received: clamp(total_received),
+        total_sent: clamp(total_sent),
         tx_count: addr_txs.len() as u32,
         recent_transactions: addr_txs.into_iter().take(20).collect(),
-    };
-
-    HttpResponse::Ok().json(info)
+        label: None,
+    }
 }
 
 /// Search for block, transaction, or address
@@ -303,67 +450,220 @@ async fn search(
     data: web::Data<AppState>,
 ) -> impl Responder {
     let query = path.into_inner();
-    let blocks = data.blocks.lock().unwrap();
-    let transactions = data.transactions.lock().unwrap();
+    let (blocks, transactions) = data.snapshot();
 
+    match search_in(query.trim(), &blocks, &transactions) {
+        Ok(result) => {
+            let result = result.with_confirmations(tip_height(&blocks)).with_labels(&data.labels);
+            HttpResponse::Ok().json(result)
+        }
+        Err(error) => HttpResponse::BadRequest().json(serde_json::json!({ "error": error })),
+    }
+}
+
+/// Resolve `query` in order: block index, exact block hash, transaction
+/// hash or address, an AXM amount ("1.5 AXM"), a UTC date ("2020-09-13"),
+/// and finally a hash or address prefix of at least `MIN_PREFIX_LEN` hex
+/// characters. A prefix with a single match returns it like an exact hit.
+fn search_in(query: &str, blocks: &[Block], transactions: &[Transaction]) -> Result<SearchResult, String> {
     // Try as block index
     if let Ok(index) = query.parse::<u64>() {
         if let Some(block) = blocks.iter().find(|b| b.index == index) {
-            return HttpResponse::Ok().json(SearchResult::Block {
-                data: block.clone(),
-            });
+            return Ok(SearchResult::Block { data: block.clone() });
         }
     }
 
     // Try as block hash
     if let Some(block) = blocks.iter().find(|b| b.hash == query) {
-        return HttpResponse::Ok().json(SearchResult::Block {
-            data: block.clone(),
-        });
+        return Ok(SearchResult::Block { data: block.clone() });
     }
 
     // Try as transaction hash
     if let Some(tx) = transactions.iter().find(|t| t.hash == query) {
-        return HttpResponse::Ok().json(SearchResult::Transaction {
-            data: tx.clone(),
-        });
+        return Ok(SearchResult::Transaction { data: tx.clone() });
     }
 
     // Try as address
+    if let Some(result) = address_result(query, transactions) {
+        return Ok(result);
+    }
+
+    // Try as an amount
+    if let Some(amount) = parse_axm_amount(query) {
+        let transactions = transactions.iter().filter(|tx| tx.amount == amount).cloned().collect();
+        return Ok(SearchResult::Matches { blocks: vec![], transactions });
+    }
+
+    // Try as a date
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(query, "%Y-%m-%d") {
+        let start = date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp() as u64;
+        let day = start..start + 86_400;
+        return Ok(SearchResult::Matches {
+            blocks: blocks.iter().filter(|b| day.contains(&b.timestamp)).map(block_summary).collect(),
+            transactions: transactions.iter().filter(|tx| day.contains(&tx.timestamp)).cloned().collect(),
+        });
+    }
+
+    // Try as a prefix
+    let prefix = query.strip_prefix("0x").unwrap_or(query).to_ascii_lowercase();
+    if prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Ok(SearchResult::NotFound);
+    }
+    if prefix.len() < MIN_PREFIX_LEN {
+        return Err(format!("Prefix must be at least {} hex characters", MIN_PREFIX_LEN));
+    }
+
+    let mut candidates: Vec<SearchCandidate> = Vec::new();
+    let mut add = |kind: &str, id: &str| {
+        if id.starts_with(&prefix) && !candidates.iter().any(|c| c.kind == kind && c.id == id) {
+            candidates.push(SearchCandidate { kind: kind.to_string(), id: id.to_string(), label: None });
+        }
+    };
+    for block in blocks {
+        add("block", &block.hash);
+    }
+    for tx in transactions {
+        add("transaction", &tx.hash);
+    }
+    for tx in transactions {
+        add("address", &tx.sender);
+        add("address", &tx.recipient);
+    }
+
+    match candidates.as_slice() {
+        [] => Ok(SearchResult::NotFound),
+        [only] => Ok(match only.kind.as_str() {
+            "block" => SearchResult::Block { data: blocks.iter().find(|b| b.hash == only.id).unwrap().clone() },
+            "transaction" => SearchResult::Transaction {
+                data: transactions.iter().find(|t| t.hash == only.id).unwrap().clone(),
+            },
+            _ => address_result(&only.id, transactions).unwrap(),
+        }),
+        _ => {
+            candidates.truncate(MAX_CANDIDATES);
+            Ok(SearchResult::Candidates { data: candidates })
+        }
+    }
+}
+
+/// `Address` result for an address with at least one transaction
+fn address_result(address: &str, transactions: &[Transaction]) -> Option<SearchResult> {
     let addr_txs: Vec<Transaction> = transactions.iter()
-        .filter(|tx| tx.sender == query || tx.recipient == query)
+        .filter(|tx| tx.sender == address || tx.recipient == address)
         .cloned()
         .collect();
 
-    if !addr_txs.is_empty() {
-        let mut balance: i64 = 0;
-        let mut total_received: u64 = 0;
-        let mut total_sent: u64 = 0;
+    if addr_txs.is_empty() {
+        return None;
+    }
+    Some(SearchResult::Address { data: address_info(address, addr_txs) })
+}
 
-        for tx in &addr_txs {
-            if tx.recipient == query {
-                balance += tx.amount as i64;
-                total_received += tx.amount;
+/// Base units of an amount written as "1.5 AXM", "2axm" or "0.25". A bare
+/// integer without the unit is a block index, not an amount.
+fn parse_axm_amount(query: &str) -> Option<u64> {
+    let lower = query.to_ascii_lowercase();
+    let (number, has_unit) = match lower.strip_suffix("axm") {
+        Some(number) => (number.trim_end(), true),
+        None => (lower.as_str(), false),
+    };
+    if !has_unit && !number.contains('.') {
+        return None;
+    }
+
+    let (whole, frac) = number.split_once('.').unwrap_or((number, ""));
+    if whole.is_empty() && frac.is_empty() {
+        return None;
+    }
+    if frac.len() > 8 || !whole.chars().chain(frac.chars()).all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let whole: u64 = if whole.is_empty() { 0 } else { whole.parse().ok()? };
+    let frac: u64 = format!("{:0<8}", frac).parse().ok()?;
+    whole.checked_mul(UNITS_PER_AXM)?.checked_add(frac)
+}
+
+impl SearchResult {
+    /// Recompute the confirmations of every transaction in the result
+    fn with_confirmations(self, tip: u64) -> Self {
+        let txs = |txs: Vec<Transaction>| txs.into_iter().map(|tx| with_confirmations(tx, tip)).collect();
+        match self {
+            SearchResult::Block { data } => SearchResult::Block { data: block_with_confirmations(data, tip) },
+            SearchResult::Transaction { data } => SearchResult::Transaction { data: with_confirmations(data, tip) },
+            SearchResult::Address { mut data } => {
+                data.recent_transactions = txs(data.recent_transactions);
+                SearchResult::Address { data }
             }
-            if tx.sender == query {
-                balance -= (tx.amount + tx.fee) as i64;
-                total_sent += tx.amount + tx.fee;
+            SearchResult::Matches { blocks, transactions } => SearchResult::Matches { blocks, transactions: txs(transactions) },
+            other => other,
+        }
+    }
+
+    /// Attach known-address labels to address results
+    fn with_labels(mut self, labels: &Labels) -> Self {
+        match &mut self {
+            SearchResult::Address { data } => data.label = labels.name(&data.address),
+            SearchResult::Candidates { data } => {
+                for candidate in data.iter_mut().filter(|c| c.kind == "address") {
+                    candidate.label = labels.name(&candidate.id);
+                }
             }
+            _ => {}
         }
+        self
+    }
+}
 
-        let info = AddressInfo {
-            address: query.clone(),
-            balance: balance.max(0) as u64,
-            total_received,
-            total_sent,
-            tx_count: addr_txs.len() as u32,
-            recent_transactions: addr_txs.into_iter().take(20).collect(),
-        };
+/// Height of the newest block
+fn tip_height(blocks: &[Block]) -> u64 {
+    blocks.last().map(|b| b.index).unwrap_or(0)
+}
 
-        return HttpResponse::Ok().json(SearchResult::Address { data: info });
+/// `tip - block_index + 1` for a mined transaction, 0 for one still in the
+/// mempool
+fn confirmations(block_index: Option<u64>, tip: u64) -> u32 {
+    match block_index {
+        Some(index) if index <= tip => u32::try_from(tip - index + 1).unwrap_or(u32::MAX),
+        _ => 0,
     }
+}
+
+fn with_confirmations(mut tx: Transaction, tip: u64) -> Transaction {
+    tx.confirmations = confirmations(tx.block_index, tip);
+    tx
+}
+
+/// Transactions of an orphaned block have no confirmations
+fn block_with_confirmations(mut block: Block, tip: u64) -> Block {
+    let orphaned = block.status == BlockStatus::Orphaned;
+    block.transactions = block.transactions
+        .into_iter()
+        .map(|tx| if orphaned { Transaction { confirmations: 0, ..tx } } else { with_confirmations(tx, tip) })
+        .collect();
+    block
+}
 
-    HttpResponse::Ok().json(SearchResult::NotFound)
+fn block_summary(block: &Block) -> BlockSummary {
+    BlockSummary {
+        index: block.index,
+        hash: block.hash.clone(),
+        timestamp: block.timestamp,
+        tx_count: block.transactions.len(),
+        miner: block.miner.clone(),
+        reward: block.reward,
+    }
+}
+
+/// Blocks dropped by reorgs, most recent first
+async fn get_orphans(data: web::Data<AppState>) -> impl Responder {
+    let orphans = read(&data.orphans);
+    let latest: Vec<&OrphanedBlock> = orphans.iter().rev().collect();
+    HttpResponse::Ok().json(latest)
+}
+
+/// List the known-address registry
+async fn get_labels(data: web::Data<AppState>) -> impl Responder {
+    HttpResponse::Ok().json(data.labels.list())
 }
 
 /// Health check endpoint
@@ -375,6 +675,49 @@ async fn health() -> impl Responder {
     }))
 }
 
+/// Known-address labels file from `AXIOM_EXPLORER_LABELS`, default
+/// `labels.toml` in the working directory
+fn labels_path() -> std::path::PathBuf {
+    std::env::var("AXIOM_EXPLORER_LABELS")
+        .unwrap_or_else(|_| "labels.toml".to_string())
+        .into()
+}
+
+/// Allowed CORS origins from `AXIOM_CORS_ALLOWED_ORIGINS` (comma separated).
+/// Unset means `*`, matching the node's RPC default; an empty value denies
+/// all cross-origin requests.
+fn cors_allowed_origins() -> Vec<String> {
+    match std::env::var("AXIOM_CORS_ALLOWED_ORIGINS") {
+        Ok(list) => list
+            .split(',')
+            .map(str::trim)
+            .filter(|o| !o.is_empty())
+            .map(String::from)
+            .collect(),
+        Err(_) => vec!["*".to_string()],
+    }
+}
+
+/// Build the CORS layer the same way the node's RPC server does
+fn cors_layer(origins: &[String]) -> Cors {
+    let cors = Cors::default()
+        .allowed_methods(vec!["GET", "OPTIONS"])
+        .allowed_headers(vec![actix_web::http::header::CONTENT_TYPE, actix_web::http::header::ACCEPT])
+        .max_age(3600);
+
+    if origins.iter().any(|o| o == "*") {
+        return cors.allow_any_origin().send_wildcard();
+    }
+    origins.iter().fold(cors, |cors, origin| {
+        if origin.starts_with("http://") || origin.starts_with("https://") {
+            cors.allowed_origin(origin)
+        } else {
+            log::warn!("Ignoring invalid CORS origin: {:?}", origin);
+            cors
+        }
+    })
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
@@ -382,12 +725,12 @@ async fn main() -> std::io::Result<()> {
     log::info!("Starting Axiom Explorer Backend...");
 
     let app_state = web::Data::new(AppState::new());
+    let origins = cors_allowed_origins();
+    log::info!("CORS allowed origins: {:?}", origins);
 
     HttpServer::new(move || {
-        let cors = Cors::permissive(); // Allow all origins for development
-
         App::new()
-            .wrap(cors)
+            .wrap(cors_layer(&origins))
             .app_data(app_state.clone())
             .route("/health", web::get().to(health))
             .route("/api/stats", web::get().to(get_stats))
@@ -396,6 +739,8 @@ async fn main() -> std::io::Result<()> {
             .route("/api/transaction/{hash}", web::get().to(get_transaction))
             .route("/api/address/{address}", web::get().to(get_address))
             .route("/api/search/{query}", web::get().to(search))
+            .route("/api/labels", web::get().to(get_labels))
+            .route("/api/orphans", web::get().to(get_orphans))
     })
     .bind(("0.0.0.0", 8080))?
     .run()
@@ -409,8 +754,365 @@ mod tests {
     #[test]
     fn test_app_state_initialization() {
         let state = AppState::new();
-        let blocks = state.blocks.lock().unwrap();
+        let blocks = read(&state.blocks);
         assert!(!blocks.is_empty());
         assert_eq!(blocks[0].index, 0); // Genesis block
     }
+
+    #[test]
+    fn test_search_prefix() {
+        let state = AppState::new();
+        let blocks = read(&state.blocks);
+        let transactions = read(&state.transactions);
+        let search = |query: &str| search_in(query, &blocks, &transactions);
+
+        // A unique prefix resolves like the full hash
+        let hash = &blocks[1].hash;
+        match search(&hash[..60]).unwrap() {
+            SearchResult::Block { data } => assert_eq!(&data.hash, hash),
+            other => panic!("expected block, got {:?}", other),
+        }
+        match search(&format!("0x{}", &transactions[2].hash[..62])).unwrap() {
+            SearchResult::Transaction { data } => assert_eq!(data.hash, transactions[2].hash),
+            other => panic!("expected transaction, got {:?}", other),
+        }
+
+        // Block 8's hash and transaction 1's hash both start ...3ade68
+        let shared = format!("{}3ade68", "0".repeat(56));
+        match search(&shared).unwrap() {
+            SearchResult::Candidates { data } => {
+                assert_eq!(data.len(), 2);
+                assert!(data.contains(&SearchCandidate { kind: "block".into(), id: blocks[8].hash.clone(), label: None }));
+                assert!(data.contains(&SearchCandidate { kind: "transaction".into(), id: transactions[0].hash.clone(), label: None }));
+            }
+            other => panic!("expected candidates, got {:?}", other),
+        }
+
+        assert!(search("3ade6").is_err());
+        assert!(matches!(search("ffffff").unwrap(), SearchResult::NotFound));
+        assert!(matches!(search("not-a-hash").unwrap(), SearchResult::NotFound));
+    }
+
+    #[test]
+    fn test_search_amount_and_date() {
+        let state = AppState::new();
+        let blocks = read(&state.blocks);
+        let transactions = read(&state.transactions);
+
+        assert_eq!(parse_axm_amount("1.5 AXM"), Some(150_000_000));
+        assert_eq!(parse_axm_amount("0.25"), Some(25_000_000));
+        assert_eq!(parse_axm_amount("3"), None);
+        assert_eq!(parse_axm_amount("1.123456789"), None);
+
+        match search_in("3 AXM", &blocks, &transactions).unwrap() {
+            SearchResult::Matches { transactions: found, .. } => {
+                assert_eq!(found.len(), 1);
+                assert_eq!(found[0].block_index, Some(3));
+            }
+            other => panic!("expected matches, got {:?}", other),
+        }
+
+        match search_in("2020-09-13", &blocks, &transactions).unwrap() {
+            SearchResult::Matches { blocks: found_blocks, transactions: found_txs } => {
+                assert_eq!(found_blocks.len(), blocks.len());
+                assert_eq!(found_txs.len(), transactions.len());
+            }
+            other => panic!("expected matches, got {:?}", other),
+        }
+        match search_in("2021-01-01", &blocks, &transactions).unwrap() {
+            SearchResult::Matches { blocks, transactions } => assert!(blocks.is_empty() && transactions.is_empty()),
+            other => panic!("expected matches, got {:?}", other),
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_confirmations_follow_tip() {
+        use actix_web::test;
+
+        let state = web::Data::new(AppState::new());
+        let app = test::init_service(
+            App::new()
+                .app_data(state.clone())
+                .route("/api/transaction/{hash}", web::get().to(get_transaction))
+                .route("/api/block/{id}", web::get().to(get_block)),
+        )
+        .await;
+
+        // Block 7's transaction is 3 blocks below the tip at 10
+        let hash = read(&state.blocks)[7].transactions[0].hash.clone();
+        let req = test::TestRequest::get().uri(&format!("/api/transaction/{}", hash)).to_request();
+        let tx: Transaction = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(tx.confirmations, 4);
+
+        // Mine another block on top
+        {
+            let mut blocks = write(&state.blocks);
+            let mut next = blocks.last().unwrap().clone();
+            next.index += 1;
+            next.previous_hash = next.hash.clone();
+            next.hash = format!("{:064x}", 11 * 123456789);
+            next.transactions.clear();
+            blocks.push(next);
+        }
+        let req = test::TestRequest::get().uri(&format!("/api/transaction/{}", hash)).to_request();
+        let tx: Transaction = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(tx.confirmations, 5);
+
+        let req = test::TestRequest::get().uri("/api/block/10").to_request();
+        let block: Block = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(block.transactions[0].confirmations, 2);
+
+        // Not yet in a block
+        assert_eq!(confirmations(None, 11), 0);
+    }
+
+    #[actix_web::test]
+    async fn test_reorg_records_orphan() {
+        use actix_web::test;
+
+        let state = web::Data::new(AppState::new());
+        let app = test::init_service(
+            App::new()
+                .app_data(state.clone())
+                .route("/api/block/{id}", web::get().to(get_block))
+                .route("/api/orphans", web::get().to(get_orphans)),
+        )
+        .await;
+
+        // A competing block 9 replaces blocks 9 and 10
+        let (old_9, old_10, mut fork) = {
+            let blocks = read(&state.blocks);
+            (blocks[9].clone(), blocks[10].clone(), blocks[9].clone())
+        };
+        fork.hash = format!("{:064x}", 0xf0f0_u64);
+        fork.transactions.clear();
+        state.ingest_block(fork.clone());
+        // Re-ingesting the canonical block changes nothing
+        state.ingest_block(fork.clone());
+
+        assert_eq!(read(&state.blocks).len(), 10);
+        assert!(read(&state.transactions).iter().all(|tx| tx.block_index < Some(9)));
+
+        let req = test::TestRequest::get().uri("/api/orphans").to_request();
+        let orphans: Vec<OrphanedBlock> = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(orphans.len(), 2);
+        assert_eq!(orphans[1].block.hash, old_9.hash);
+        assert_eq!(orphans[1].replaced_by.as_deref(), Some(fork.hash.as_str()));
+        assert_eq!(orphans[0].block.hash, old_10.hash);
+        assert_eq!(orphans[0].replaced_by, None);
+
+        let req = test::TestRequest::get().uri(&format!("/api/block/{}", old_9.hash)).to_request();
+        let block: Block = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(block.status, BlockStatus::Orphaned);
+        assert_eq!(block.transactions[0].confirmations, 0);
+
+        let req = test::TestRequest::get().uri("/api/block/9").to_request();
+        let block: Block = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(block.hash, fork.hash);
+        assert_eq!(block.status, BlockStatus::Canonical);
+
+        // The new chain reaching height 10 fills in the replacement
+        let mut next = fork.clone();
+        next.index = 10;
+        next.previous_hash = fork.hash.clone();
+        next.hash = format!("{:064x}", 0xf1f1_u64);
+        state.ingest_block(next.clone());
+        assert_eq!(read(&state.orphans)[1].replaced_by.as_deref(), Some(next.hash.as_str()));
+    }
+
+    #[test]
+    fn test_malformed_vdf_block_not_indexed() {
+        let state = AppState::new();
+        let tip = read(&state.blocks).last().cloned().unwrap();
+        let mut next = tip.clone();
+        next.index = tip.index + 1;
+        next.previous_hash = tip.hash.clone();
+        next.hash = format!("{:064x}", 0xabcd_u64);
+        next.transactions.clear();
+
+        let mut truncated = next.clone();
+        truncated.vdf_proof = Some("abc".to_string());
+        let mut half = next.clone();
+        half.vdf_output = None;
+        for bad in [truncated, half] {
+            assert!(bad.vdf_proof_typed().is_none());
+            state.ingest_block(bad);
+            assert_eq!(read(&state.blocks).len() as u64, next.index);
+        }
+
+        // A well-formed proof is accepted and stored canonically
+        next.vdf_proof = Some(format!("0x{}", "AB".repeat(32)));
+        let typed = next.vdf_proof_typed().unwrap();
+        assert_eq!(typed.proof, [0xab; 32]);
+        state.ingest_block(next.clone());
+        let blocks = read(&state.blocks);
+        assert_eq!(blocks.last().unwrap().hash, next.hash);
+        assert_eq!(blocks.last().unwrap().vdf_proof, Some("ab".repeat(32)));
+        assert_eq!(blocks.last().unwrap().vdf_proof_typed(), Some(typed));
+        assert!(blocks[0].vdf_proof_typed().is_none());
+    }
+
+    #[actix_web::test]
+    async fn test_panicked_handler_does_not_wedge_requests() {
+        use actix_web::test;
+
+        let state = web::Data::new(AppState::new());
+        let app = test::init_service(
+            App::new()
+                .app_data(state.clone())
+                .route("/api/stats", web::get().to(get_stats))
+                .route("/api/block/{id}", web::get().to(get_block)),
+        )
+        .await;
+
+        // A handler panics while holding the block and transaction locks
+        let poisoner = state.clone();
+        let panicked = std::thread::spawn(move || {
+            let _blocks = write(&poisoner.blocks);
+            let _transactions = read(&poisoner.transactions);
+            panic!("handler bug");
+        })
+        .join();
+        assert!(panicked.is_err());
+        assert!(state.blocks.is_poisoned());
+
+        // Later requests are still served
+        for _ in 0..2 {
+            let req = test::TestRequest::get().uri("/api/stats").to_request();
+            assert!(test::call_service(&app, req).await.status().is_success());
+            let req = test::TestRequest::get().uri("/api/block/10").to_request();
+            let block: Block = test::call_and_read_body_json(&app, req).await;
+            assert_eq!(block.index, 10);
+        }
+        assert!(!state.blocks.is_poisoned());
+
+        // ... and new blocks still come in
+        let mut next = read(&state.blocks).last().unwrap().clone();
+        next.index += 1;
+        next.hash = format!("{:064x}", 11 * 123456789);
+        state.ingest_block(next);
+        assert_eq!(read(&state.blocks).len(), 12);
+    }
+
+    #[test]
+    fn test_reads_run_concurrently() {
+        use std::sync::{mpsc, Arc, Barrier};
+        use std::time::Duration;
+
+        // Every reader holds its locks until all of them have them; under
+        // exclusive locks the second reader would never get in
+        const READERS: usize = 16;
+        let state = Arc::new(AppState::new());
+        let barrier = Arc::new(Barrier::new(READERS));
+        let (done_tx, done_rx) = mpsc::channel();
+        for _ in 0..READERS {
+            let (state, barrier, done_tx) = (state.clone(), barrier.clone(), done_tx.clone());
+            std::thread::spawn(move || {
+                let (blocks, _transactions) = state.snapshot();
+                barrier.wait();
+                done_tx.send(blocks.len()).unwrap();
+            });
+        }
+        for _ in 0..READERS {
+            assert_eq!(done_rx.recv_timeout(Duration::from_secs(5)).unwrap(), 11);
+        }
+    }
+
+    #[test]
+    fn test_snapshot_is_consistent_during_ingestion() {
+        use std::sync::Arc;
+
+        let state = Arc::new(AppState::new());
+        let writer = {
+            let state = state.clone();
+            std::thread::spawn(move || {
+                for i in 11..=200u64 {
+                    let mut next = read(&state.blocks).last().unwrap().clone();
+                    next.index = i;
+                    next.previous_hash = next.hash.clone();
+                    next.hash = format!("{:064x}", i * 123456789);
+                    next.transactions[0].hash = format!("{:064x}", i * 987654321);
+                    next.transactions[0].block_hash = Some(next.hash.clone());
+                    next.transactions[0].block_index = Some(i);
+                    state.ingest_block(next);
+                }
+            })
+        };
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let state = state.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..200 {
+                        // Each reader sees every transaction of exactly the blocks it sees
+                        let (blocks, transactions) = state.snapshot();
+                        let tip = tip_height(&blocks);
+                        assert_eq!(transactions.len(), blocks.iter().map(|b| b.transactions.len()).sum::<usize>());
+                        assert!(transactions.iter().all(|tx| tx.block_index.is_some_and(|index| index <= tip)));
+                    }
+                })
+            })
+            .collect();
+
+        writer.join().unwrap();
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert_eq!(tip_height(&read(&state.blocks)), 200);
+    }
+
+    #[test]
+    fn test_address_labels() {
+        let state = AppState::new();
+        let transactions = read(&state.transactions);
+        let mut labels = Labels::builtin("Genesis");
+        labels
+            .merge_toml(&format!(
+                "[labels.\"0x{}\"]\nname = \"Example Exchange\"\ntags = [\"exchange\"]\n",
+                transactions[0].recipient.to_uppercase()
+            ))
+            .unwrap();
+
+        let labeled = search_in(&transactions[0].recipient, &[], &transactions).unwrap().with_labels(&labels);
+        match labeled {
+            SearchResult::Address { data } => assert_eq!(data.label.as_deref(), Some("Example Exchange")),
+            other => panic!("expected address, got {:?}", other),
+        }
+        let unlabeled = search_in(&transactions[0].sender, &[], &transactions).unwrap().with_labels(&labels);
+        match unlabeled {
+            SearchResult::Address { data } => assert_eq!(data.label, None),
+            other => panic!("expected address, got {:?}", other),
+        }
+
+        assert_eq!(labels.name(labels::BRIDGE_ADDRESS).as_deref(), Some("Bridge contract"));
+        assert_eq!(labels.name("Genesis").as_deref(), Some("Genesis miner"));
+        assert_eq!(labels.list().len(), 3);
+        assert!(labels.merge_toml("labels = 1").is_err());
+    }
+
+    #[actix_web::test]
+    async fn test_cors_origins() {
+        use actix_web::{http::header, test};
+
+        async fn allow_origin(origins: &[&str], origin: &str) -> Option<String> {
+            let origins: Vec<String> = origins.iter().map(|o| o.to_string()).collect();
+            let app = test::init_service(
+                App::new().wrap(cors_layer(&origins)).route("/health", web::get().to(health)),
+            )
+            .await;
+            let req = test::TestRequest::get().uri("/health").insert_header(("Origin", origin)).to_request();
+            let resp = test::call_service(&app, req).await;
+            resp.headers()
+                .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+                .map(|v| v.to_str().unwrap().to_string())
+        }
+
+        let allowed = ["https://explorer.axiom.network"];
+        assert_eq!(
+            allow_origin(&allowed, "https://explorer.axiom.network").await.as_deref(),
+            Some("https://explorer.axiom.network")
+        );
+        assert_eq!(allow_origin(&allowed, "https://evil.example").await, None);
+        assert_eq!(allow_origin(&["*"], "https://evil.example").await.as_deref(), Some("*"));
+        assert_eq!(allow_origin(&[], "https://explorer.axiom.network").await, None);
+    }
 }
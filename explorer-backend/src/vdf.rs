@@ -0,0 +1,156 @@
+//! Typed VDF proofs.
+//!
+//! Blocks arrive with the VDF output and proof as hex strings. `VdfProof`
+//! holds them as bytes, so a block whose strings are not 32-byte hex values
+//! is caught when it is ingested instead of being served as-is.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Bytes in a VDF output and in its proof
+pub const VDF_BYTES: usize = 32;
+
+/// A VDF output and its proof. Serializes as
+/// `{"output": "<hex>", "proof": "<hex>"}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "VdfProofHex", into = "VdfProofHex")]
+pub struct VdfProof {
+    pub output: [u8; VDF_BYTES],
+    pub proof: [u8; VDF_BYTES],
+}
+
+#[derive(Serialize, Deserialize)]
+struct VdfProofHex {
+    output: String,
+    proof: String,
+}
+
+/// Why a VDF string was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VdfProofError {
+    /// `field` does not hold `VDF_BYTES` bytes of hex
+    WrongLength { field: &'static str, len: usize },
+    InvalidHex { field: &'static str },
+    /// Only one of the output and the proof is present
+    Incomplete,
+}
+
+impl fmt::Display for VdfProofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongLength { field, len } => {
+                write!(f, "VDF {} has {} hex digits, expected {}", field, len, VDF_BYTES * 2)
+            }
+            Self::InvalidHex { field } => write!(f, "VDF {} is not hex", field),
+            Self::Incomplete => write!(f, "VDF output and proof must be given together"),
+        }
+    }
+}
+
+impl std::error::Error for VdfProofError {}
+
+impl VdfProof {
+    /// Parse the hex strings of a block, with or without a `0x` prefix
+    pub fn from_hex(output: &str, proof: &str) -> Result<Self, VdfProofError> {
+        Ok(Self { output: parse_bytes("output", output)?, proof: parse_bytes("proof", proof)? })
+    }
+
+    /// The proof carried by a block's optional fields: `None` when both
+    /// are absent, an error when only one is
+    pub fn from_fields(output: Option<&str>, proof: Option<&str>) -> Result<Option<Self>, VdfProofError> {
+        match (output, proof) {
+            (None, None) => Ok(None),
+            (Some(output), Some(proof)) => Self::from_hex(output, proof).map(Some),
+            _ => Err(VdfProofError::Incomplete),
+        }
+    }
+
+    pub fn output_hex(&self) -> String {
+        to_hex(&self.output)
+    }
+
+    pub fn proof_hex(&self) -> String {
+        to_hex(&self.proof)
+    }
+}
+
+impl TryFrom<VdfProofHex> for VdfProof {
+    type Error = VdfProofError;
+
+    fn try_from(hex: VdfProofHex) -> Result<Self, Self::Error> {
+        Self::from_hex(&hex.output, &hex.proof)
+    }
+}
+
+impl From<VdfProof> for VdfProofHex {
+    fn from(proof: VdfProof) -> Self {
+        Self { output: proof.output_hex(), proof: proof.proof_hex() }
+    }
+}
+
+fn parse_bytes(field: &'static str, hex: &str) -> Result<[u8; VDF_BYTES], VdfProofError> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if hex.len() != VDF_BYTES * 2 {
+        return Err(VdfProofError::WrongLength { field, len: hex.len() });
+    }
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(VdfProofError::InvalidHex { field });
+    }
+    let mut bytes = [0u8; VDF_BYTES];
+    for (byte, pair) in bytes.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        // Both digits were checked above
+        *byte = u8::from_str_radix(std::str::from_utf8(pair).unwrap_or_default(), 16).unwrap_or_default();
+    }
+    Ok(bytes)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_well_formed_proof_parses() {
+        let output = format!("{:064x}", 0x29a_u64);
+        let proof = format!("0x{}", "AB".repeat(VDF_BYTES));
+        let parsed = VdfProof::from_hex(&output, &proof).unwrap();
+        assert_eq!(parsed.output[VDF_BYTES - 2..], [0x02, 0x9a]);
+        assert_eq!(parsed.proof, [0xab; VDF_BYTES]);
+
+        // Hex out is lowercase without a prefix, and round-trips through JSON
+        assert_eq!(parsed.output_hex(), output);
+        assert_eq!(parsed.proof_hex(), "ab".repeat(VDF_BYTES));
+        let json = serde_json::to_value(parsed).unwrap();
+        assert_eq!(json, serde_json::json!({ "output": output, "proof": "ab".repeat(VDF_BYTES) }));
+        assert_eq!(serde_json::from_value::<VdfProof>(json).unwrap(), parsed);
+
+        assert_eq!(VdfProof::from_fields(None, None), Ok(None));
+        assert_eq!(VdfProof::from_fields(Some(&output), Some(&proof)), Ok(Some(parsed)));
+    }
+
+    #[test]
+    fn test_malformed_proof_is_an_error() {
+        let good = "00".repeat(VDF_BYTES);
+        assert_eq!(
+            VdfProof::from_hex(&"0".repeat(63), &good),
+            Err(VdfProofError::WrongLength { field: "output", len: 63 })
+        );
+        assert_eq!(
+            VdfProof::from_hex(&good, &"0".repeat(66)),
+            Err(VdfProofError::WrongLength { field: "proof", len: 66 })
+        );
+        assert_eq!(
+            VdfProof::from_hex(&good, &format!("zz{}", "0".repeat(62))),
+            Err(VdfProofError::InvalidHex { field: "proof" })
+        );
+        // Multi-byte characters are not hex digits, even at the right length
+        assert!(VdfProof::from_hex(&format!("é{}", "0".repeat(62)), &good).is_err());
+        assert_eq!(VdfProof::from_fields(Some(&good), None), Err(VdfProofError::Incomplete));
+
+        let json = serde_json::json!({ "output": "proof...", "proof": good });
+        assert!(serde_json::from_value::<VdfProof>(json).is_err());
+    }
+}
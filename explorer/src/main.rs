@@ -1,35 +1,74 @@
 use actix_web::{web, App, HttpServer, HttpResponse};
 use axiom_core::block::Block;
+use axiom_core::rpc::cors;
 use axiom_core::state::State;
-use std::sync::Mutex;
+use axiom_core::storage;
+use std::sync::{RwLock, RwLockReadGuard};
 
 struct AppState {
-    state: Mutex<State>,
-    blocks: Mutex<Vec<Block>>,
+    state: RwLock<State>,
+    blocks: RwLock<Vec<Block>>,
+}
+
+/// Shared access to `lock`, still serving the data if a panicked handler
+/// poisoned it
+fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(|poisoned| {
+        eprintln!("⚠️  Recovering lock poisoned by a panicked handler");
+        lock.clear_poison();
+        poisoned.into_inner()
+    })
 }
 
 async fn get_blocks(data: web::Data<AppState>) -> HttpResponse {
-    let blocks = data.blocks.lock().unwrap();
+    let blocks = read(&data.blocks);
     HttpResponse::Ok().json(&*blocks)
 }
 
 async fn get_state(data: web::Data<AppState>) -> HttpResponse {
-    let state = data.state.lock().unwrap();
-    HttpResponse::Ok().json(&*state)
+    let state = read(&data.state);
+    HttpResponse::Ok().json(serde_json::json!({
+        "accounts": state.balances.len(),
+        "total_issued": state.total_issued,
+        "state_root": hex::encode(state.state_root()),
+    }))
+}
+
+/// Balance and nonce from the node's account index; no chain scan
+async fn get_address(path: web::Path<String>, data: web::Data<AppState>) -> HttpResponse {
+    let address = match hex::decode(path.into_inner().trim_start_matches("0x"))
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+    {
+        Some(address) => address,
+        None => return HttpResponse::BadRequest().json(serde_json::json!({ "error": "invalid address" })),
+    };
+    let state = read(&data.state);
+    HttpResponse::Ok().json(serde_json::json!({
+        "address": hex::encode(address),
+        "balance": state.balance(&address),
+        "nonce": state.nonce(&address),
+    }))
 }
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     let app_state = web::Data::new(AppState {
-        state: Mutex::new(State::new()),
-        blocks: Mutex::new(vec![]),
+        state: RwLock::new(storage::load_state().map(|index| index.state).unwrap_or_default()),
+        blocks: RwLock::new(vec![]),
     });
 
+    let origins = axiom_core::AxiomConfig::load()
+        .map(|config| config.rpc.cors_allowed_origins)
+        .unwrap_or_default();
+
     HttpServer::new(move || {
         App::new()
+            .wrap(cors::layer(&origins))
             .app_data(app_state.clone())
             .route("/blocks", web::get().to(get_blocks))
             .route("/state", web::get().to(get_state))
+            .route("/address/{address}", web::get().to(get_address))
     })
     .bind(("127.0.0.1", 8080))?
     .run()
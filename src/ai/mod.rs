@@ -6,5 +6,7 @@ pub use oracle::{
     OracleResponse,
     OracleConsensus,
     OracleNode,
+    OracleBackend,
+    OracleStakeLedger,
     OracleConsensusManager,
 };
@@ -4,8 +4,12 @@
 use serde::{Serialize, Deserialize};
 use reqwest;
 use sha2::{Sha256, Digest};
+use futures::stream::{FuturesUnordered, StreamExt};
 use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::config::AIConfig;
+use crate::error::{AxiomError, Result as AxiomResult};
 
 /// Oracle query submitted by users
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,6 +44,13 @@ pub struct OracleConsensus {
     pub dissenting_oracles: Vec<[u8; 32]>,
 }
 
+/// Anything that can answer an oracle query (a live Claude-backed node,
+/// a remote peer, or a mock in tests)
+#[async_trait::async_trait]
+pub trait OracleBackend: Send + Sync {
+    async fn process_query(&self, query: &OracleQuery) -> Result<OracleResponse, String>;
+}
+
 /// AI Oracle node that processes queries
 pub struct OracleNode {
     pub address: [u8; 32],
@@ -147,6 +158,13 @@ impl OracleNode {
     }
 }
 
+#[async_trait::async_trait]
+impl OracleBackend for OracleNode {
+    async fn process_query(&self, query: &OracleQuery) -> Result<OracleResponse, String> {
+        OracleNode::process_query(self, query).await
+    }
+}
+
 /// Oracle consensus manager
 pub struct OracleConsensusManager {
     pub minimum_oracles: usize,
@@ -161,6 +179,60 @@ impl OracleConsensusManager {
         }
     }
     
+    /// Fan a query out to all oracles concurrently and collect their responses.
+    ///
+    /// Each oracle gets `timeout` to answer; slow or failing oracles are dropped.
+    /// Returns as soon as `minimum_oracles` responses are in, or when every
+    /// oracle has either answered or timed out.
+    pub async fn gather_responses(
+        &self,
+        query: &OracleQuery,
+        oracles: &[Arc<dyn OracleBackend>],
+        timeout: Duration,
+    ) -> Vec<OracleResponse> {
+        let mut pending: FuturesUnordered<_> = oracles
+            .iter()
+            .map(|oracle| {
+                let oracle = Arc::clone(oracle);
+                async move { tokio::time::timeout(timeout, oracle.process_query(query)).await }
+            })
+            .collect();
+
+        let mut responses = Vec::with_capacity(oracles.len());
+        while let Some(result) = pending.next().await {
+            match result {
+                Ok(Ok(response)) if response.query_id == query.query_id => {
+                    responses.push(response);
+                    if responses.len() >= self.minimum_oracles {
+                        break;
+                    }
+                }
+                Ok(Ok(_)) => eprintln!("⚠️  Oracle answered a different query, ignoring"),
+                Ok(Err(e)) => eprintln!("⚠️  Oracle failed: {}", e),
+                Err(_) => eprintln!("⚠️  Oracle timed out after {:?}", timeout),
+            }
+        }
+
+        responses
+    }
+
+    /// Find consensus among responses from oracles that pass `is_eligible`
+    /// (e.g. `|addr| ledger.is_eligible(addr)`)
+    pub fn find_consensus_filtered<F>(
+        &self,
+        responses: Vec<OracleResponse>,
+        is_eligible: F,
+    ) -> Result<OracleConsensus, String>
+    where
+        F: Fn(&[u8; 32]) -> bool,
+    {
+        let eligible = responses
+            .into_iter()
+            .filter(|r| is_eligible(&r.oracle_address))
+            .collect();
+        self.find_consensus(eligible)
+    }
+
     /// Find consensus among oracle responses
     pub fn find_consensus(
         &self,
@@ -279,6 +351,101 @@ impl OracleConsensusManager {
     }
 }
 
+/// Consensus confidence at or above which dissenters get slashed
+pub const SLASH_CONFIDENCE_THRESHOLD: f64 = 0.66;
+
+/// Fraction of the minimum stake removed per slashing event (1/10)
+pub const SLASH_DIVISOR: u64 = 10;
+
+/// Bonded oracle stakes with slashing for dissent
+///
+/// An oracle that dissents from a high-confidence consensus loses
+/// `slash_amount`; once its stake drops below `min_stake` it is no longer
+/// eligible to participate in consensus.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OracleStakeLedger {
+    stakes: HashMap<[u8; 32], u64>,
+    pub min_stake: u64,
+    pub slash_amount: u64,
+    pub total_slashed: u64,
+}
+
+impl OracleStakeLedger {
+    pub fn new(min_stake: u64, slash_amount: u64) -> Self {
+        Self {
+            stakes: HashMap::new(),
+            min_stake,
+            slash_amount,
+            total_slashed: 0,
+        }
+    }
+
+    /// Ledger using `min_oracle_stake` from the AI config
+    pub fn from_config(config: &AIConfig) -> Self {
+        Self::new(config.min_oracle_stake, config.min_oracle_stake / SLASH_DIVISOR)
+    }
+
+    /// Bond additional stake for an oracle
+    pub fn bond(&mut self, oracle: [u8; 32], amount: u64) {
+        let stake = self.stakes.entry(oracle).or_insert(0);
+        *stake = stake.saturating_add(amount);
+    }
+
+    /// Current bonded stake (0 if unknown)
+    pub fn stake_of(&self, oracle: &[u8; 32]) -> u64 {
+        self.stakes.get(oracle).copied().unwrap_or(0)
+    }
+
+    /// Whether the oracle has enough stake to take part in consensus
+    pub fn is_eligible(&self, oracle: &[u8; 32]) -> bool {
+        self.stake_of(oracle) >= self.min_stake
+    }
+
+    /// Check eligibility, returning the shortfall as an error
+    pub fn require_eligible(&self, oracle: &[u8; 32]) -> AxiomResult<()> {
+        let have = self.stake_of(oracle);
+        if have < self.min_stake {
+            return Err(AxiomError::InsufficientStake { have, need: self.min_stake });
+        }
+        Ok(())
+    }
+
+    /// Slash every dissenter of a high-confidence consensus.
+    /// Returns the oracles slashed and the amount taken from each.
+    pub fn apply_consensus(&mut self, consensus: &OracleConsensus) -> Vec<([u8; 32], u64)> {
+        if consensus.confidence < SLASH_CONFIDENCE_THRESHOLD {
+            return Vec::new();
+        }
+
+        let mut slashed = Vec::new();
+        for oracle in &consensus.dissenting_oracles {
+            if let Some(stake) = self.stakes.get_mut(oracle) {
+                let amount = self.slash_amount.min(*stake);
+                *stake -= amount;
+                self.total_slashed = self.total_slashed.saturating_add(amount);
+                slashed.push((*oracle, amount));
+            }
+        }
+        slashed
+    }
+
+    /// Persist the ledger (atomic write via temp file + rename)
+    pub fn save_to_file(&self, path: &str) -> AxiomResult<()> {
+        let encoded = bincode::serialize(self)?;
+        let temp_path = format!("{}.tmp", path);
+        std::fs::write(&temp_path, encoded)?;
+        std::fs::rename(temp_path, path)?;
+        Ok(())
+    }
+
+    /// Load a previously persisted ledger
+    pub fn load_from_file(path: &str) -> AxiomResult<Self> {
+        let data = std::fs::read(path)?;
+        bincode::deserialize(&data)
+            .map_err(|e| AxiomError::DeserializationError(e.to_string()))
+    }
+}
+
 /// Simple Levenshtein distance
 fn levenshtein_distance(a: &str, b: &str) -> usize {
     let a_chars: Vec<char> = a.chars().collect();
@@ -393,6 +560,181 @@ mod tests {
         println!("✓ Reward distribution works!");
     }
     
+    fn response(address: u8, text: &str) -> OracleResponse {
+        OracleResponse {
+            query_id: [1u8; 32],
+            response_text: text.to_string(),
+            model: "claude-3-5-sonnet".to_string(),
+            oracle_address: [address; 32],
+            signature: vec![],
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_repeated_dissent_slashes_below_minimum() {
+        let manager = OracleConsensusManager::new(3, 0.8);
+        let mut ledger = OracleStakeLedger::new(1000, 400);
+        for addr in 1..=4u8 {
+            ledger.bond([addr; 32], 1000);
+        }
+
+        let round = || vec![
+            response(1, "The answer is 42"),
+            response(2, "The answer is 42"),
+            response(3, "The answer is 42"),
+            response(4, "Wrong answer: 99"),
+        ];
+
+        // One dissent is enough to drop below the minimum
+        let consensus = manager.find_consensus(round()).unwrap();
+        let slashed = ledger.apply_consensus(&consensus);
+        assert_eq!(slashed, vec![([4u8; 32], 400)]);
+        assert_eq!(ledger.stake_of(&[4u8; 32]), 600);
+        assert!(!ledger.is_eligible(&[4u8; 32]));
+        assert!(ledger.require_eligible(&[4u8; 32]).is_err());
+
+        // Repeated dissent keeps slashing until nothing is left
+        for _ in 0..2 {
+            let consensus = manager.find_consensus(round()).unwrap();
+            ledger.apply_consensus(&consensus);
+        }
+        assert_eq!(ledger.stake_of(&[4u8; 32]), 0);
+        assert_eq!(ledger.total_slashed, 1000);
+        assert_eq!(ledger.stake_of(&[1u8; 32]), 1000);
+
+        // The slashed oracle no longer counts towards consensus
+        let consensus = manager
+            .find_consensus_filtered(round(), |addr| ledger.is_eligible(addr))
+            .unwrap();
+        assert!(consensus.dissenting_oracles.is_empty());
+        assert!(!consensus.participating_oracles.contains(&[4u8; 32]));
+        assert_eq!(consensus.confidence, 1.0);
+    }
+
+    #[test]
+    fn test_low_confidence_consensus_does_not_slash() {
+        let manager = OracleConsensusManager::new(2, 0.8);
+        let mut ledger = OracleStakeLedger::new(1000, 400);
+        ledger.bond([1u8; 32], 1000);
+        ledger.bond([2u8; 32], 1000);
+
+        let consensus = manager
+            .find_consensus(vec![response(1, "yes"), response(2, "absolutely not")])
+            .unwrap();
+        assert_eq!(consensus.confidence, 0.5);
+        assert!(ledger.apply_consensus(&consensus).is_empty());
+    }
+
+    #[test]
+    fn test_stake_ledger_persistence() {
+        let mut ledger = OracleStakeLedger::from_config(&AIConfig::default());
+        ledger.bond([9u8; 32], 75_000_000_000);
+
+        let path = std::env::temp_dir().join(format!("axiom_oracle_ledger_{}.dat", std::process::id()));
+        let path = path.to_str().unwrap();
+        ledger.save_to_file(path).unwrap();
+        let loaded = OracleStakeLedger::load_from_file(path).unwrap();
+        let _ = std::fs::remove_file(path);
+
+        assert_eq!(loaded.stake_of(&[9u8; 32]), 75_000_000_000);
+        assert_eq!(loaded.min_stake, ledger.min_stake);
+        assert_eq!(loaded.slash_amount, ledger.slash_amount);
+    }
+
+    /// Mock oracle that answers after `delay` (or fails when `fail` is set)
+    struct MockOracle {
+        address: [u8; 32],
+        answer: String,
+        delay: Duration,
+        fail: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl OracleBackend for MockOracle {
+        async fn process_query(&self, query: &OracleQuery) -> Result<OracleResponse, String> {
+            tokio::time::sleep(self.delay).await;
+            if self.fail {
+                return Err("mock failure".to_string());
+            }
+            Ok(OracleResponse {
+                query_id: query.query_id,
+                response_text: self.answer.clone(),
+                model: "mock".to_string(),
+                oracle_address: self.address,
+                signature: vec![],
+                timestamp: 0,
+            })
+        }
+    }
+
+    fn mock(address: u8, answer: &str, delay_ms: u64, fail: bool) -> Arc<dyn OracleBackend> {
+        Arc::new(MockOracle {
+            address: [address; 32],
+            answer: answer.to_string(),
+            delay: Duration::from_millis(delay_ms),
+            fail,
+        })
+    }
+
+    fn test_query() -> OracleQuery {
+        OracleQuery {
+            query_id: [7u8; 32],
+            prompt: "What is 6*7?".to_string(),
+            requester: [0u8; 32],
+            max_tokens: 10,
+            temperature: 0.0,
+            reward: 1000,
+            timestamp: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gather_excludes_hanging_oracle() {
+        let manager = OracleConsensusManager::new(3, 0.8);
+        let oracles = vec![
+            mock(1, "The answer is 42", 0, false),
+            mock(2, "The answer is 42", 10, false),
+            mock(3, "The answer is 42", 20, false),
+            mock(4, "The answer is 42", 60_000, false), // hangs
+            mock(5, "ignored", 0, true),                // fails
+        ];
+
+        let start = std::time::Instant::now();
+        let responses = manager
+            .gather_responses(&test_query(), &oracles, Duration::from_millis(500))
+            .await;
+
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(responses.len(), 3);
+        assert!(responses.iter().all(|r| r.oracle_address != [4u8; 32]));
+        assert!(responses.iter().all(|r| r.oracle_address != [5u8; 32]));
+
+        let consensus = manager.find_consensus(responses)
+            .expect("Consensus should form without the hanging oracle");
+        assert_eq!(consensus.agreed_response, "The answer is 42");
+        assert_eq!(consensus.participating_oracles.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_gather_returns_at_deadline() {
+        // Minimum can never be reached, so gathering stops at the timeout
+        let manager = OracleConsensusManager::new(3, 0.8);
+        let oracles = vec![
+            mock(1, "The answer is 42", 0, false),
+            mock(2, "The answer is 42", 0, false),
+            mock(3, "The answer is 42", 60_000, false),
+        ];
+
+        let responses = manager
+            .gather_responses(&test_query(), &oracles, Duration::from_millis(100))
+            .await;
+
+        assert_eq!(responses.len(), 2);
+        let manager = OracleConsensusManager::new(2, 0.8);
+        assert!(manager.find_consensus(responses).is_ok());
+    }
+
     #[tokio::test]
     #[ignore] // Requires ANTHROPIC_API_KEY env var
     async fn test_claude_api_integration() {
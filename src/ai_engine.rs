@@ -131,6 +131,12 @@ impl NeuralGuardian {
         println!("--------------------------------\n");
     }
 
+    /// Persist the current stats as JSON
+    pub fn save_stats(&self, path: &str) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(&self.stats)?;
+        std::fs::write(path, json)
+    }
+
     pub fn report_false_positive(&mut self) {
         self.stats.false_positives += 1;
         println!("⚠️  AI: False positive reported. Total: {}", self.stats.false_positives);
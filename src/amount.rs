@@ -0,0 +1,208 @@
+// src/amount.rs - Fixed-point AXM amounts
+//
+// An `Amount` is a count of smallest units (10^-8 AXM). Arithmetic is
+// checked, parsing reads the decimal string digit by digit and formatting
+// splits whole and fractional units, so no value ever passes through a
+// float. Amounts serialize as decimal AXM strings ("1.50000000"), which JSON
+// clients can read without losing precision above 2^53.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::chain::DECIMALS;
+use crate::economics::SMALLEST_UNIT;
+use crate::error::{AxiomError, Result as AxiomResult};
+
+/// An amount of AXM in smallest units
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Amount(u64);
+
+impl Amount {
+    pub const ZERO: Amount = Amount(0);
+    pub const MAX: Amount = Amount(u64::MAX);
+
+    pub const fn from_sats(sats: u64) -> Self {
+        Amount(sats)
+    }
+
+    pub const fn as_sats(self) -> u64 {
+        self.0
+    }
+
+    /// `axm` whole coins, or `None` on overflow
+    pub fn from_axm(axm: u64) -> Option<Self> {
+        axm.checked_mul(SMALLEST_UNIT).map(Amount)
+    }
+
+    /// Parse a decimal AXM amount such as "1.5", "0.00000001" or "42".
+    /// Signs, exponents, more than `DECIMALS` fractional digits and values
+    /// that do not fit in a `u64` are rejected.
+    pub fn from_axm_str(s: &str) -> AxiomResult<Self> {
+        let invalid = |reason: &str| AxiomError::InvalidAmount(format!("{:?}: {}", s, reason));
+        let (whole, frac) = s.split_once('.').unwrap_or((s, ""));
+        if whole.is_empty() && frac.is_empty() {
+            return Err(invalid("no digits"));
+        }
+        if !whole.chars().chain(frac.chars()).all(|c| c.is_ascii_digit()) {
+            return Err(invalid("not a decimal number"));
+        }
+        if frac.len() > DECIMALS as usize {
+            return Err(invalid(&format!("more than {} decimal places", DECIMALS)));
+        }
+
+        let overflow = || invalid("too large");
+        let whole: u64 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| overflow())? };
+        let frac: u64 = format!("{:0<width$}", frac, width = DECIMALS as usize).parse().map_err(|_| overflow())?;
+        whole
+            .checked_mul(SMALLEST_UNIT)
+            .and_then(|units| units.checked_add(frac))
+            .map(Amount)
+            .ok_or_else(overflow)
+    }
+
+    pub fn checked_add(self, other: Amount) -> Option<Amount> {
+        self.0.checked_add(other.0).map(Amount)
+    }
+
+    pub fn checked_sub(self, other: Amount) -> Option<Amount> {
+        self.0.checked_sub(other.0).map(Amount)
+    }
+
+    pub fn checked_mul(self, factor: u64) -> Option<Amount> {
+        self.0.checked_mul(factor).map(Amount)
+    }
+
+    pub fn checked_div(self, divisor: u64) -> Option<Amount> {
+        self.0.checked_div(divisor).map(Amount)
+    }
+
+    pub fn saturating_add(self, other: Amount) -> Amount {
+        Amount(self.0.saturating_add(other.0))
+    }
+
+    pub fn saturating_sub(self, other: Amount) -> Amount {
+        Amount(self.0.saturating_sub(other.0))
+    }
+}
+
+impl From<u64> for Amount {
+    fn from(sats: u64) -> Self {
+        Amount(sats)
+    }
+}
+
+impl From<Amount> for u64 {
+    fn from(amount: Amount) -> Self {
+        amount.0
+    }
+}
+
+/// Whole AXM with all eight decimals, e.g. "1.50000000"
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{:0width$}",
+            self.0 / SMALLEST_UNIT,
+            self.0 % SMALLEST_UNIT,
+            width = DECIMALS as usize
+        )
+    }
+}
+
+impl FromStr for Amount {
+    type Err = AxiomError;
+
+    fn from_str(s: &str) -> AxiomResult<Self> {
+        Self::from_axm_str(s)
+    }
+}
+
+impl Serialize for Amount {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.to_string().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Amount {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Self::from_axm_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_axm_strings() {
+        assert_eq!(Amount::from_axm_str("1.5").unwrap(), Amount::from_sats(150_000_000));
+        assert_eq!(Amount::from_axm_str("42").unwrap(), Amount::from_axm(42).unwrap());
+        assert_eq!(Amount::from_axm_str("0.00000001").unwrap(), Amount::from_sats(1));
+        assert_eq!(Amount::from_axm_str(".25").unwrap(), Amount::from_sats(25_000_000));
+        assert_eq!(Amount::from_axm_str("7.").unwrap(), Amount::from_sats(700_000_000));
+        assert_eq!("0.1".parse::<Amount>().unwrap(), Amount::from_sats(10_000_000));
+
+        // Largest representable amount, and one unit past it
+        assert_eq!(Amount::from_axm_str("184467440737.09551615").unwrap(), Amount::MAX);
+        for bad in [
+            "184467440737.09551616",
+            "18446744073709551616",
+            "1.123456789",
+            "0.000000001",
+            "",
+            ".",
+            "-1",
+            "+1",
+            "1e8",
+            " 1",
+            "1,5",
+            "1.2.3",
+            "NaN",
+        ] {
+            assert!(matches!(Amount::from_axm_str(bad), Err(AxiomError::InvalidAmount(_))), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_exact_formatting_and_serde() {
+        assert_eq!(Amount::ZERO.to_string(), "0.00000000");
+        assert_eq!(Amount::from_sats(1).to_string(), "0.00000001");
+        assert_eq!(Amount::from_sats(150_000_000).to_string(), "1.50000000");
+        // f64 would print 184467440737.09552 here
+        assert_eq!(Amount::MAX.to_string(), "184467440737.09551615");
+        assert_eq!(Amount::from_sats(crate::economics::TOTAL_SUPPLY).to_string(), "1240000000.00000000");
+
+        // Every amount round-trips through its string form
+        for sats in [0, 1, 99_999_999, 100_000_000, 123_456_789_012_345, u64::MAX] {
+            let amount = Amount::from_sats(sats);
+            assert_eq!(Amount::from_axm_str(&amount.to_string()).unwrap(), amount);
+        }
+
+        let json = serde_json::to_string(&Amount::from_sats(9_007_199_254_740_993)).unwrap();
+        assert_eq!(json, "\"90071992.54740993\"");
+        assert_eq!(serde_json::from_str::<Amount>(&json).unwrap().as_sats(), 9_007_199_254_740_993);
+        assert!(serde_json::from_str::<Amount>("\"1.000000001\"").is_err());
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let one = Amount::from_axm(1).unwrap();
+        assert_eq!(one.checked_add(one), Amount::from_axm(2));
+        assert_eq!(Amount::MAX.checked_add(Amount::from_sats(1)), None);
+        assert_eq!(Amount::ZERO.checked_sub(Amount::from_sats(1)), None);
+        assert_eq!(one.checked_mul(3), Amount::from_axm(3));
+        assert_eq!(Amount::MAX.checked_mul(2), None);
+        assert_eq!(one.checked_div(0), None);
+        assert_eq!(Amount::from_axm(u64::MAX), None);
+        assert_eq!(Amount::ZERO.saturating_sub(one), Amount::ZERO);
+    }
+}
@@ -1,5 +1,7 @@
 use std::fs;
 
+use axiom_core::amount::Amount;
+
 fn main() {
     // Load chain from storage
     let mut chain_path = std::path::PathBuf::from("./axiom_chain.dat");
@@ -44,16 +46,15 @@ fn main() {
     let percent = (total_mined as f64 / max_supply as f64) * 100.0;
 
     // Format to AXM (8 decimals)
-    let decimals = 8u32;
-    let mined_axm = total_mined as f64 / (10_u64.pow(decimals) as f64);
-    let remaining_axm = remaining as f64 / (10_u64.pow(decimals) as f64);
-    let max_axm = max_supply as f64 / (10_u64.pow(decimals) as f64);
+    let mined_axm = Amount::from_sats(total_mined);
+    let remaining_axm = Amount::from_sats(remaining);
+    let max_axm = Amount::from_sats(max_supply);
 
     println!("\n💰 AXIOM SUPPLY STATUS");
     println!("======================");
-    println!("Total Mined:        {:.8} AXM", mined_axm);
-    println!("Total Remaining:    {:.8} AXM", remaining_axm);
-    println!("Max Supply:         {:.8} AXM", max_axm);
+    println!("Total Mined:        {} AXM", mined_axm);
+    println!("Total Remaining:    {} AXM", remaining_axm);
+    println!("Max Supply:         {} AXM", max_axm);
     println!("Percentage Mined:   {:.2}%", percent);
     println!("Current Height:     {}", blocks.len());
     println!("======================\n");
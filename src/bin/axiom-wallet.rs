@@ -1,6 +1,8 @@
 use std::fs;
 use std::env;
 
+use axiom_core::amount::Amount;
+
 fn main() {
     let args: Vec<String> = env::args().collect();
     
@@ -15,20 +17,15 @@ fn main() {
 
     let command = &args[1];
 
-    // Load wallet
-    let wallet_data = match fs::read("wallet.dat") {
-        Ok(data) => data,
-        Err(_) => {
+    // Load wallet (encrypted wallets are unlocked with AXIOM_WALLET_PASSWORD)
+    let wallet = match axiom_core::wallet::Wallet::open(axiom_core::wallet::WALLET_FILE) {
+        Ok(w) => w,
+        Err(axiom_core::error::AxiomError::WalletNotFound(_)) => {
             eprintln!("❌ Error: wallet.dat not found. Run the axiom node first to generate a wallet.");
             std::process::exit(1);
         }
-    };
-
-    // Deserialize wallet
-    let wallet: axiom_core::wallet::Wallet = match bincode::deserialize(&wallet_data) {
-        Ok(w) => w,
         Err(e) => {
-            eprintln!("❌ Error deserializing wallet: {}", e);
+            eprintln!("❌ Error loading wallet: {}", e);
             std::process::exit(1);
         }
     };
@@ -69,22 +66,21 @@ fn main() {
                 if block.miner == wallet.address {
                     let halvings = block.slot / 2_100_000;
                     let reward = 50_000_000_000u64 >> halvings;
-                    balance += reward;
+                    balance = balance.saturating_add(reward);
                 }
 
                 // Process transactions
                 for tx in &block.transactions {
                     if tx.to == wallet.address {
-                        balance += tx.amount;
+                        balance = balance.saturating_add(tx.amount);
                     }
                     if tx.from == wallet.address {
-                        balance -= tx.amount + tx.fee;
+                        balance = balance.saturating_sub(tx.amount.saturating_add(tx.fee));
                     }
                 }
             }
 
-            let axm_balance = balance as f64 / 100_000_000.0;
-            println!("💰 Balance: {:.8} AXM", axm_balance);
+            println!("💰 Balance: {} AXM", Amount::from_sats(balance));
         }
         "send" => {
             if args.len() < 5 {
@@ -93,24 +89,32 @@ fn main() {
             }
 
             let to_hex = &args[2];
-            let amount_axm: f64 = match args[3].parse() {
-                Ok(a) => a,
-                Err(_) => {
+            // Convert to smallest units exactly, rejecting malformed or
+            // above-supply input
+            let parse_axm = |arg: &str| {
+                Amount::from_axm_str(arg)
+                    .ok()
+                    .map(Amount::as_sats)
+                    .filter(|&sats| sats <= axiom_core::economics::TOTAL_SUPPLY)
+            };
+            let amount = match parse_axm(&args[3]) {
+                Some(a) => a,
+                None => {
                     eprintln!("❌ Invalid amount");
                     std::process::exit(1);
                 }
             };
-            let fee_axm: f64 = match args[4].parse() {
-                Ok(f) => f,
-                Err(_) => {
+            let fee = match parse_axm(&args[4]) {
+                Some(f) => f,
+                None => {
                     eprintln!("❌ Invalid fee");
                     std::process::exit(1);
                 }
             };
-
-            // Convert to smallest units
-            let amount = (amount_axm * 100_000_000.0) as u64;
-            let fee = (fee_axm * 100_000_000.0) as u64;
+            if amount.checked_add(fee).is_none() {
+                eprintln!("❌ Amount plus fee overflows");
+                std::process::exit(1);
+            }
 
             // Decode recipient address
             let to_address = match hex::decode(to_hex) {
@@ -129,8 +133,11 @@ fn main() {
             let current_balance = 1_000_000_000_000; // Placeholder - should load from chain
             let nonce = 0; // Placeholder - should track per address
 
+            // Sign for the configured network so no other network accepts it
+            let chain_id = axiom_core::AxiomConfig::load().unwrap_or_default().network.network_id;
+
             // Create transaction
-            match wallet.create_transaction(to_address, amount, fee, nonce, current_balance) {
+            match wallet.create_transaction_for(chain_id, to_address, amount, fee, nonce, current_balance) {
                 Ok(tx) => {
                     // Save transaction to file for broadcasting
                     let tx_data = match bincode::serialize(&tx) {
@@ -147,8 +154,8 @@ fn main() {
                             println!("📤 Run the axiom node to broadcast this transaction");
                             println!("From: {}", hex::encode(tx.from));
                             println!("To: {}", hex::encode(tx.to));
-                            println!("Amount: {:.8} AXM", amount as f64 / 100_000_000.0);
-                            println!("Fee: {:.8} AXM", fee as f64 / 100_000_000.0);
+                            println!("Amount: {} AXM", Amount::from_sats(amount));
+                            println!("Fee: {} AXM", Amount::from_sats(fee));
                         }
                         Err(e) => {
                             eprintln!("❌ Error saving transaction: {}", e);
@@ -27,7 +27,7 @@ impl Block {
         }
 
         // 2. PoW check
-        if !self.meets_difficulty(difficulty) {
+        if !self.meets_target_for(difficulty) {
             return Err("Block does not meet PoW difficulty");
         }
 
@@ -38,7 +38,7 @@ impl Block {
         }
 
         // 4. Transaction checks
-        for tx in &self.transactions {
+        for tx in self.user_transactions() {
             let sender_balance = state.balance(&tx.from);
             tx.validate(sender_balance)?;
             state.apply_tx(tx)?;
@@ -48,41 +48,80 @@ impl Block {
     }
 }
 use serde::{Serialize, Deserialize};
+use crate::consensus::lwma;
+use crate::merkle::{self, MerkleProof};
 use crate::transaction::{Transaction, Address};
+use num_bigint::BigUint;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+/// Position of a transaction in canonical block order; see
+/// `Block::sort_canonical`
+type CanonicalKey = (Reverse<u64>, Address, u64, [u8; 32]);
+
+fn canonical_keys(transactions: &[Transaction]) -> Vec<CanonicalKey> {
+    let mut by_sender: HashMap<Address, Vec<usize>> = HashMap::new();
+    for (i, tx) in transactions.iter().enumerate() {
+        by_sender.entry(tx.from).or_default().push(i);
+    }
+    // A sender's later nonces rank no higher than its earlier ones
+    let mut rates = vec![0u64; transactions.len()];
+    for indices in by_sender.values_mut() {
+        indices.sort_by_key(|&i| transactions[i].nonce);
+        let mut cap = u64::MAX;
+        for &i in indices.iter() {
+            cap = cap.min(transactions[i].fee_rate());
+            rates[i] = cap;
+        }
+    }
+    transactions
+        .iter()
+        .zip(rates)
+        .map(|(tx, rate)| (Reverse(rate), tx.from, tx.nonce, tx.hash()))
+        .collect()
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Block {
     pub parent: [u8; 32],
     pub slot: u64,
+    /// Unix time the miner built the block; LWMA retargets from these
+    pub timestamp: u64,
+    /// Difficulty the block is mined at, checked by `Timechain::add_block`
+    pub difficulty: u64,
     pub miner: Address,
     pub transactions: Vec<Transaction>,
+    /// Merkle root of the transaction hashes (see [`crate::merkle`])
+    pub merkle_root: [u8; 32],
     pub vdf_proof: [u8; 32],
     pub zk_proof: Vec<u8>,
     pub nonce: u64, // The PoW layer for Hash Power
 }
 
 impl Block {
-    /// Computes the cryptographic hash of the block using Blake3
+    /// Computes the cryptographic hash of the block using Blake3, under the
+    /// `hashing::BLOCK` tag
     pub fn hash(&self) -> [u8; 32] {
         let serialized = bincode::serialize(self).expect("Serialization failed");
-        blake3::hash(&serialized).into()
-    }
-
-    /// Checks if the block meets the dynamic network difficulty (Hash Power check)
-    pub fn meets_difficulty(&self, difficulty: u64) -> bool {
-        let h = self.hash();
-        // Convert first 8 bytes to u64 for numerical comparison
-        // Safe conversion with proper error handling
-        let val = match <[u8; 8]>::try_from(&h[0..8]) {
-            Ok(bytes) => u64::from_be_bytes(bytes),
-            Err(_) => {
-                eprintln!("⚠️  Block hash conversion failed");
-                return false;
-            }
-        };
-        
-        // Difficulty formula: higher difficulty results in a smaller target range
-        val < (u64::MAX / difficulty.max(1))
+        crate::hashing::tagged_blake3(crate::hashing::BLOCK, &[&serialized])
+    }
+
+    /// Serialized (bincode) size in bytes, as limited by `max_block_size`
+    pub fn size(&self) -> usize {
+        bincode::serialized_size(self).expect("Serialization failed") as usize
+    }
+
+    /// Whether the block's hash is within the target for the difficulty it
+    /// claims. This is the PoW check `Timechain::add_block` applies, so a
+    /// client can run it on a fetched block without the chain.
+    pub fn meets_difficulty(&self) -> bool {
+        self.meets_target_for(self.difficulty)
+    }
+
+    /// Whether the block's hash, read as a big-endian 256-bit number, is at
+    /// or below `lwma::difficulty_to_target(difficulty)`
+    pub fn meets_target_for(&self, difficulty: u64) -> bool {
+        lwma::meets_difficulty(&self.hash(), &BigUint::from(difficulty))
     }
 
     pub fn new(
@@ -97,11 +136,224 @@ impl Block {
         Self {
             parent,
             slot,
+            timestamp: 0,
+            difficulty: 0,
             miner,
+            merkle_root: Self::merkle_root_of(&transactions),
             transactions,
             vdf_proof,
             zk_proof,
             nonce,
         }
     }
+
+    /// Stamp the block with unix time `timestamp`
+    pub fn with_timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Claim `difficulty` for the block
+    pub fn with_difficulty(mut self, difficulty: u64) -> Self {
+        self.difficulty = difficulty;
+        self
+    }
+
+    /// The coinbase, if the first transaction is one. Only meaningful after
+    /// genesis, whose transactions are all allocations.
+    pub fn coinbase(&self) -> Option<&Transaction> {
+        self.transactions.first().filter(|tx| tx.is_coinbase())
+    }
+
+    /// Transactions other than the coinbase
+    pub fn user_transactions(&self) -> &[Transaction] {
+        let skip = usize::from(self.coinbase().is_some());
+        &self.transactions[skip..]
+    }
+
+    /// Merkle root over the hashes of `transactions`
+    pub fn merkle_root_of(transactions: &[Transaction]) -> [u8; 32] {
+        let leaves: Vec<[u8; 32]> = transactions.iter().map(Transaction::hash).collect();
+        merkle::root(&leaves)
+    }
+
+    /// Recompute the Merkle root from the block's transactions
+    pub fn compute_merkle_root(&self) -> [u8; 32] {
+        Self::merkle_root_of(&self.transactions)
+    }
+
+    /// Inclusion proof for the transaction with `tx_hash`, if it is in this block
+    pub fn merkle_proof(&self, tx_hash: [u8; 32]) -> Option<MerkleProof> {
+        let leaves: Vec<[u8; 32]> = self.transactions.iter().map(Transaction::hash).collect();
+        let index = leaves.iter().position(|h| *h == tx_hash)?;
+        MerkleProof::generate(&leaves, index)
+    }
+
+    /// Check that `proof` places `tx_hash` under this block's stored root
+    pub fn verify_transaction_inclusion(&self, tx_hash: [u8; 32], proof: &MerkleProof) -> bool {
+        proof.verify(tx_hash, self.merkle_root)
+    }
+
+    /// Sort `transactions` into canonical block order: highest fee rate
+    /// first, then by sender, nonce and hash. Each transaction's rate is
+    /// capped at the rates of its sender's lower nonces, so a sender's
+    /// transactions always stay in nonce order. The result does not
+    /// depend on the input order.
+    pub fn sort_canonical(transactions: &mut Vec<Transaction>) {
+        let mut keyed: Vec<(CanonicalKey, Transaction)> =
+            canonical_keys(transactions).into_iter().zip(transactions.drain(..)).collect();
+        keyed.sort_by_key(|(key, _)| *key);
+        transactions.extend(keyed.into_iter().map(|(_, tx)| tx));
+    }
+
+    /// Whether the transactions after the coinbase are in canonical order
+    pub fn is_canonically_ordered(&self) -> bool {
+        canonical_keys(self.user_transactions()).windows(2).all(|pair| pair[0] <= pair[1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_with(n: u8) -> Block {
+        let txs = (0..n).map(|i| Transaction::new([i; 32], [9u8; 32], i as u64 + 1, 1, 0, vec![], vec![])).collect();
+        Block::new([1u8; 32], 1, [2u8; 32], txs, [0u8; 32], vec![], 0)
+    }
+
+    #[test]
+    fn test_merkle_root_computation() {
+        assert_eq!(block_with(0).merkle_root, [0u8; 32]);
+        let single = block_with(1);
+        assert_eq!(single.merkle_root, single.transactions[0].hash());
+
+        let block = block_with(3);
+        let h: Vec<[u8; 32]> = block.transactions.iter().map(Transaction::hash).collect();
+        let expected = merkle::hash_pair(&merkle::hash_pair(&h[0], &h[1]), &merkle::hash_pair(&h[2], &h[2]));
+        assert_eq!(block.merkle_root, expected);
+        assert_eq!(block.compute_merkle_root(), expected);
+    }
+
+    #[test]
+    fn test_transaction_inclusion_proof() {
+        let block = block_with(5);
+        let tx_hash = block.transactions[3].hash();
+        let proof = block.merkle_proof(tx_hash).unwrap();
+        assert!(block.verify_transaction_inclusion(tx_hash, &proof));
+
+        // Wrong transaction, or a block whose transactions were swapped out
+        assert!(!block.verify_transaction_inclusion(block.transactions[2].hash(), &proof));
+        let mut tampered = block.clone();
+        tampered.transactions[3].amount += 1;
+        assert_ne!(tampered.compute_merkle_root(), tampered.merkle_root);
+        assert!(tampered.merkle_proof([0xAB; 32]).is_none());
+    }
+
+    #[test]
+    fn test_canonical_order_is_total_and_stable() {
+        let tx = |from: u8, fee: u64, nonce: u64| Transaction::new([from; 32], [9u8; 32], 100, fee, nonce, vec![], vec![]);
+        // Sender 1's nonce 1 pays the most but must wait behind its nonce 0,
+        // which then ties with sender 3 and goes first by address
+        let txs = vec![
+            tx(1, 500, 0),
+            tx(1, 9_000, 1),
+            tx(2, 3_000, 0),
+            tx(3, 500, 0),
+            tx(4, 100, 7),
+            tx(4, 100, 8),
+        ];
+        let mut expected = txs.clone();
+        Block::sort_canonical(&mut expected);
+        let nonces: Vec<(u8, u64)> = expected.iter().map(|tx| (tx.from[0], tx.nonce)).collect();
+        assert_eq!(nonces, vec![(2, 0), (1, 0), (1, 1), (3, 0), (4, 7), (4, 8)]);
+
+        // Every input order lands on the same sequence
+        for shift in 0..txs.len() {
+            for reversed in [false, true] {
+                let mut shuffled = txs.clone();
+                shuffled.rotate_left(shift);
+                if reversed {
+                    shuffled.reverse();
+                }
+                Block::sort_canonical(&mut shuffled);
+                assert_eq!(shuffled, expected);
+            }
+        }
+
+        let block = |txs: Vec<Transaction>| Block::new([1u8; 32], 1, [2u8; 32], txs, [0u8; 32], vec![], 0);
+        assert!(block(expected.clone()).is_canonically_ordered());
+        let mut swapped = expected.clone();
+        swapped.swap(0, 1);
+        assert!(!block(swapped).is_canonically_ordered());
+        // The coinbase is not ranked
+        let mut with_coinbase = vec![Transaction::coinbase([2u8; 32], 1, 0)];
+        with_coinbase.extend(expected);
+        assert!(block(with_coinbase).is_canonically_ordered());
+    }
+
+    /// First nonce from 0 for which `block` does (or does not) meet its
+    /// claimed difficulty
+    fn nonce_where(mut block: Block, meets: bool) -> Block {
+        block.nonce = (0..).find(|&n| { block.nonce = n; block.meets_difficulty() == meets }).unwrap();
+        block
+    }
+
+    #[test]
+    fn test_meets_difficulty_compares_hash_to_target() {
+        let block = block_with(2).with_difficulty(1_000);
+        let target = lwma::difficulty_to_target(&BigUint::from(1_000u64));
+
+        let below = nonce_where(block.clone(), true);
+        assert!(BigUint::from_bytes_be(&below.hash()) <= target);
+        let above = nonce_where(block.clone(), false);
+        assert!(BigUint::from_bytes_be(&above.hash()) > target);
+        assert!(!above.meets_difficulty());
+
+        // The check reads the block's own claim
+        assert!(above.clone().with_difficulty(0).meets_difficulty());
+        assert_eq!(below.meets_target_for(1_000), below.meets_difficulty());
+    }
+
+    #[test]
+    fn test_meets_difficulty_agrees_with_add_block() {
+        use crate::chain::Timechain;
+        use crate::wallet::Wallet;
+
+        let mut chain = Timechain::new(crate::genesis::genesis());
+        let mut block = chain.next_block(&Wallet::from_secret_key([2u8; 32]), vec![]);
+        block.vdf_proof = crate::main_helper::compute_vdf(
+            crate::vdf::evaluate(block.parent, block.slot),
+            chain.vdf_steps,
+        );
+        assert_eq!(block.difficulty, chain.difficulty);
+
+        let rejected = nonce_where(block.clone(), false);
+        assert_eq!(chain.add_block(rejected, crate::chain::TARGET_TIME), Err("Block doesn't meet difficulty requirement"));
+        let accepted = nonce_where(block, true);
+        chain.add_block(accepted, crate::chain::TARGET_TIME).unwrap();
+        assert_eq!(chain.blocks.len(), 2);
+    }
+
+    #[test]
+    fn test_json_and_bincode_forms_agree() {
+        // Every field populated, with values at the edges JSON numbers
+        // commonly lose (above 2^53)
+        let txs = vec![
+            Transaction::coinbase([2u8; 32], 1, 5),
+            Transaction::new([3u8; 32], [4u8; 32], u64::MAX, 1 << 60, u64::MAX - 1, vec![0xAB; 48], vec![0xCD; 64]),
+            Transaction::new([5u8; 32], [6u8; 32], 1, 0, 0, vec![], vec![]),
+        ];
+        let block = Block::new([1u8; 32], u64::MAX, [2u8; 32], txs, [0xEE; 32], vec![0x5A; 128], u64::MAX);
+
+        // JSON -> struct -> bincode -> struct
+        let json = serde_json::to_string(&block).unwrap();
+        let from_json: Block = serde_json::from_str(&json).unwrap();
+        let from_bincode: Block = bincode::deserialize(&bincode::serialize(&from_json).unwrap()).unwrap();
+        assert_eq!(from_bincode, block);
+        assert_eq!(from_bincode.hash(), block.hash());
+
+        // bincode -> struct -> JSON gives back the same document
+        let gossiped: Block = bincode::deserialize(&bincode::serialize(&block).unwrap()).unwrap();
+        assert_eq!(serde_json::to_string(&gossiped).unwrap(), json);
+    }
 }
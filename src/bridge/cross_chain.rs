@@ -2,8 +2,15 @@
 // Supports: Ethereum, BSC, Polygon, Arbitrum, Optimism
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use sha2::{Sha256, Digest};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::Proof;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use super::tokens::TokenRegistry;
+use crate::hashing;
+use crate::zk::circuit::{bytes_to_fr, ZkProofSystem};
 
 /// Supported blockchain networks for cross-chain operations
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -67,8 +74,8 @@ pub struct BridgeTransaction {
     pub to_chain: ChainId,
     pub sender: String,             // Address on source chain
     pub recipient: String,          // Address on destination chain
-    pub amount: u64,
-    pub token: String,              // "AXM" or wrapped token
+    pub amount: u64,                // Canonical units (see `TokenRegistry`)
+    pub token: String,              // Canonical token ID, e.g. "AXM"
     pub status: BridgeStatus,
     pub timestamp: u64,
     pub confirmations: u32,
@@ -85,28 +92,130 @@ pub enum BridgeStatus {
     Failed { reason: String },
 }
 
+/// Private data authorizing a lock, known only to the locker
+#[derive(Debug, Clone)]
+pub struct LockWitness {
+    pub secret_key: [u8; 32],
+    pub balance: u64,
+    pub nonce: u64,
+}
+
+/// IDs of bridges already minted. `mint_wrapped` records an ID, and saves
+/// the ledger, before minting, so a mint interrupted by a crash is never
+/// repeated after a restart. (The failure mode is a bridge stuck unminted,
+/// never one minted twice.)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct MintLedger {
+    minted: HashSet<[u8; 32]>,
+    #[serde(skip)]
+    path: Option<String>,
+}
+
+impl MintLedger {
+    /// Ledger backed by the file at `path`, starting from its contents
+    /// if it exists
+    pub fn open(path: &str) -> Result<Self, String> {
+        let mut ledger = match std::fs::read(path) {
+            Ok(data) => bincode::deserialize::<MintLedger>(&data)
+                .map_err(|e| format!("Corrupt mint ledger {}: {}", path, e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => return Err(format!("Cannot read mint ledger {}: {}", path, e)),
+        };
+        ledger.path = Some(path.to_string());
+        Ok(ledger)
+    }
+
+    pub fn contains(&self, bridge_id: &[u8; 32]) -> bool {
+        self.minted.contains(bridge_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.minted.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.minted.is_empty()
+    }
+
+    /// Record `bridge_id` as minted and persist. Fails if it already was.
+    fn record(&mut self, bridge_id: [u8; 32]) -> Result<(), String> {
+        if !self.minted.insert(bridge_id) {
+            return Err("Bridge already minted".to_string());
+        }
+        if let Err(e) = self.save() {
+            self.minted.remove(&bridge_id);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Write the ledger to its file (temp file + rename)
+    fn save(&self) -> Result<(), String> {
+        let Some(path) = &self.path else { return Ok(()) };
+        let encoded = bincode::serialize(self).map_err(|e| format!("Mint ledger encoding failed: {}", e))?;
+        let temp_path = format!("{}.tmp", path);
+        std::fs::write(&temp_path, encoded)
+            .and_then(|_| std::fs::rename(&temp_path, path))
+            .map_err(|e| format!("Cannot save mint ledger {}: {}", path, e))
+    }
+}
+
 /// Bridge contract on EVM chains (deployed via CREATE2 for same address)
 pub struct BridgeContract {
     pub address: String,            // Same on all EVM chains (CREATE2)
     pub chain: ChainId,
+    /// Groth16 keys for lock proofs; without them locks and mints fail
+    pub zk: Option<Arc<ZkProofSystem>>,
+    /// Bridgeable tokens and their decimals on each chain
+    pub tokens: Arc<TokenRegistry>,
 }
 
 impl BridgeContract {
     /// Canonical bridge address (same on all chains via CREATE2)
     pub const BRIDGE_ADDRESS: &'static str = "0x8400000000000000000000000000000000000001";
+
+    pub fn new(chain: ChainId) -> Self {
+        Self {
+            address: Self::BRIDGE_ADDRESS.to_string(),
+            chain,
+            zk: None,
+            tokens: Arc::new(TokenRegistry::with_axm()),
+        }
+    }
+
+    pub fn with_zk_system(mut self, zk: Arc<ZkProofSystem>) -> Self {
+        self.zk = Some(zk);
+        self
+    }
+
+    pub fn with_token_registry(mut self, tokens: Arc<TokenRegistry>) -> Self {
+        self.tokens = tokens;
+        self
+    }
     
-    /// Lock tokens on source chain
+    /// Lock `source_amount` of `token` (in this chain's decimals) on source
+    /// chain, proving with `witness` that the locker owns enough to cover
+    /// it. Only the part representable in canonical units is locked.
     pub async fn lock_tokens(
         &self,
         sender: String,
-        amount: u64,
+        token: &str,
+        source_amount: u128,
         destination_chain: ChainId,
         recipient: String,
+        witness: &LockWitness,
     ) -> Result<BridgeTransaction, String> {
-        println!("🔒 Locking {} AXM on {:?} for {:?}", amount, self.chain, destination_chain);
+        let amount = self.tokens.to_canonical(token, &self.chain, source_amount)?;
+        if amount == 0 {
+            return Err("Amount too small to bridge".to_string());
+        }
+        if self.tokens.deployment(token, &destination_chain).is_none() {
+            return Err(format!("Token {} is not bridgeable on {:?}", token, destination_chain));
+        }
+        println!("🔒 Locking {} {} on {:?} for {:?}", amount, token, self.chain, destination_chain);
         
         // Generate ZK proof of lock
-        let zk_proof = self.generate_lock_proof(sender.clone(), amount)?;
+        let zk_proof = self.generate_lock_proof(witness, amount)?;
         
         Ok(BridgeTransaction {
             id: Self::generate_bridge_id(&sender, amount, &destination_chain),
@@ -115,7 +224,7 @@ impl BridgeContract {
             sender,
             recipient,
             amount,
-            token: "AXM".to_string(),
+            token: token.to_string(),
             status: BridgeStatus::Pending,
             timestamp: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
@@ -127,39 +236,60 @@ impl BridgeContract {
         })
     }
     
-    /// Mint wrapped tokens on destination chain
+    /// Mint wrapped tokens on destination chain, at most once per bridge
+    /// ID across restarts (see `MintLedger`)
     pub async fn mint_wrapped(
         &self,
-        bridge_tx: &BridgeTransaction,
+        bridge_tx: &mut BridgeTransaction,
+        ledger: &mut MintLedger,
     ) -> Result<String, String> {
         if bridge_tx.to_chain != self.chain {
             return Err("Wrong destination chain".to_string());
         }
         
+        if ledger.contains(&bridge_tx.id) {
+            bridge_tx.status = BridgeStatus::Minted;
+            return Err("Bridge already minted".to_string());
+        }
+        
         if bridge_tx.status != BridgeStatus::ReadyToMint {
             return Err("Bridge transaction not ready to mint".to_string());
         }
         
         // Verify ZK proof
-        if !self.verify_bridge_proof(&bridge_tx.zk_proof)? {
+        if !self.verify_bridge_proof(&bridge_tx.zk_proof, bridge_tx.amount)? {
             return Err("Invalid bridge proof".to_string());
         }
         
-        println!("🌉 Minting {} wAXM on {:?} to {}", 
-                 bridge_tx.amount, self.chain, bridge_tx.recipient);
+        let amount = self.destination_amount(bridge_tx)?;
+        ledger.record(bridge_tx.id)?;
+        println!("🌉 Minting {} {} on {:?} to {}", 
+                 amount, bridge_tx.token, self.chain, bridge_tx.recipient);
+        bridge_tx.status = BridgeStatus::Minted;
         
         Ok(format!("0x{}", hex::encode(bridge_tx.id)))
     }
     
-    /// Burn wrapped tokens and unlock on source chain
+    /// Amount of `bridge_tx` in this chain's decimals for its token
+    pub fn destination_amount(&self, bridge_tx: &BridgeTransaction) -> Result<u128, String> {
+        self.tokens.from_canonical(&bridge_tx.token, &self.chain, bridge_tx.amount)
+    }
+    
+    /// Burn wrapped `token` (`source_amount` in this chain's decimals) and
+    /// unlock on source chain
     pub async fn burn_and_unlock(
         &self,
-        amount: u64,
+        token: &str,
+        source_amount: u128,
         source_chain: ChainId,
         recipient: String,
     ) -> Result<BridgeTransaction, String> {
-        println!("🔥 Burning {} wAXM on {:?}, unlocking on {:?}", 
-                 amount, self.chain, source_chain);
+        let amount = self.tokens.to_canonical(token, &self.chain, source_amount)?;
+        if amount == 0 {
+            return Err("Amount too small to bridge".to_string());
+        }
+        println!("🔥 Burning {} {} on {:?}, unlocking on {:?}", 
+                 amount, token, self.chain, source_chain);
         
         Ok(BridgeTransaction {
             id: Self::generate_bridge_id(&recipient, amount, &source_chain),
@@ -168,7 +298,7 @@ impl BridgeContract {
             sender: "wrapped_contract".to_string(),
             recipient,
             amount,
-            token: "wAXM".to_string(),
+            token: token.to_string(),
             status: BridgeStatus::Pending,
             timestamp: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
@@ -194,35 +324,67 @@ impl BridgeContract {
     }
     
     fn generate_bridge_id(sender: &str, amount: u64, chain: &ChainId) -> [u8; 32] {
-        let mut hasher = Sha256::new();
-        hasher.update(sender.as_bytes());
-        hasher.update(amount.to_le_bytes());
-        hasher.update(chain.chain_id().to_le_bytes());
-        hasher.update(
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs()
-                .to_le_bytes()
-        );
-        hasher.finalize().into()
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        hashing::tagged_sha256(
+            hashing::BRIDGE_ID,
+            &[
+                sender.as_bytes(),
+                &amount.to_le_bytes(),
+                &chain.chain_id().to_le_bytes(),
+                &now.to_le_bytes(),
+            ],
+        )
     }
     
-    fn generate_lock_proof(&self, _sender: String, _amount: u64) -> Result<Vec<u8>, String> {
-        // Generate ZK-SNARK proof that:
-        // 1. User has sufficient balance
-        // 2. Lock is authorized
-        // 3. Amount is valid
-        // Without revealing actual balance
-        
-        Ok(vec![0u8; 200]) // Placeholder for actual ZK proof
+    fn zk_system(&self) -> Result<&ZkProofSystem, String> {
+        self.zk.as_deref().ok_or_else(|| "Bridge proof system not loaded".to_string())
+    }
+
+    /// Groth16 proof that the locker knows the key behind its balance
+    /// commitment and that the balance covers `amount`, without revealing
+    /// either. Serialized as the proof followed by its public inputs.
+    pub fn generate_lock_proof(&self, witness: &LockWitness, amount: u64) -> Result<Vec<u8>, String> {
+        let (proof, public_inputs) = self.zk_system()?.prove(
+            bytes_to_fr(&witness.secret_key),
+            Fr::from(witness.balance),
+            Fr::from(witness.nonce),
+            Fr::from(amount),
+            Fr::from(0u64),
+        )?;
+
+        let mut bytes = Vec::new();
+        proof
+            .serialize_compressed(&mut bytes)
+            .map_err(|e| format!("Proof serialization failed: {:?}", e))?;
+        public_inputs
+            .serialize_compressed(&mut bytes)
+            .map_err(|e| format!("Proof serialization failed: {:?}", e))?;
+        Ok(bytes)
     }
     
-    fn verify_bridge_proof(&self, proof: &[u8]) -> Result<bool, String> {
-        // Verify ZK-SNARK proof on destination chain
-        // This is fast (~10ms) even though proof generation is slow
-        
-        Ok(!proof.is_empty()) // Placeholder verification
+    /// Check a lock proof against the bridged `amount`. Malformed proofs,
+    /// and proofs of a different amount, are rejected.
+    pub fn verify_bridge_proof(&self, proof: &[u8], amount: u64) -> Result<bool, String> {
+        let zk = self.zk_system()?;
+        let mut reader = proof;
+        let Ok(groth16) = Proof::<Bls12_381>::deserialize_compressed(&mut reader) else {
+            return Ok(false);
+        };
+        let Ok(public_inputs) = Vec::<Fr>::deserialize_compressed(&mut reader) else {
+            return Ok(false);
+        };
+        // Inputs are (commitment, amount, fee, new balance commitment)
+        if !reader.is_empty()
+            || public_inputs.len() != 4
+            || public_inputs[1] != Fr::from(amount)
+            || public_inputs[2] != Fr::from(0u64)
+        {
+            return Ok(false);
+        }
+        Ok(zk.verify(&groth16, &public_inputs).unwrap_or(false))
     }
 }
 
@@ -230,6 +392,8 @@ impl BridgeContract {
 pub struct BridgeOracle {
     pub contracts: HashMap<ChainId, BridgeContract>,
     pub pending_bridges: Vec<BridgeTransaction>,
+    /// Bridges already minted; in memory unless set with `with_mint_ledger`
+    pub minted: MintLedger,
 }
 
 impl Default for BridgeOracle {
@@ -250,20 +414,36 @@ impl BridgeOracle {
             ChainId::Arbitrum,
             ChainId::Optimism,
         ] {
-            contracts.insert(
-                chain.clone(),
-                BridgeContract {
-                    address: BridgeContract::BRIDGE_ADDRESS.to_string(),
-                    chain,
-                }
-            );
+            contracts.insert(chain.clone(), BridgeContract::new(chain));
         }
         
         Self {
             contracts,
             pending_bridges: Vec::new(),
+            minted: MintLedger::default(),
         }
     }
+
+    pub fn with_mint_ledger(mut self, ledger: MintLedger) -> Self {
+        self.minted = ledger;
+        self
+    }
+
+    /// Prove and verify bridge locks with `zk` on every chain
+    pub fn with_zk_system(mut self, zk: Arc<ZkProofSystem>) -> Self {
+        for contract in self.contracts.values_mut() {
+            contract.zk = Some(zk.clone());
+        }
+        self
+    }
+
+    /// Bridge the tokens in `tokens` on every chain
+    pub fn with_token_registry(mut self, tokens: Arc<TokenRegistry>) -> Self {
+        for contract in self.contracts.values_mut() {
+            contract.tokens = tokens.clone();
+        }
+        self
+    }
     
     /// Monitor source chain for lock events
     pub async fn monitor_locks(&mut self) -> Result<(), String> {
@@ -304,20 +484,26 @@ impl BridgeOracle {
         Ok(())
     }
     
-    /// Execute minting on destination chain
-    pub async fn execute_minting(&mut self) -> Result<(), String> {
-        let ready_bridges: Vec<_> = self.pending_bridges.iter()
-            .filter(|b| b.status == BridgeStatus::ReadyToMint)
-            .cloned()
-            .collect();
-        
-        for bridge in ready_bridges {
+    /// Execute minting on destination chain. Bridges already in the mint
+    /// ledger are marked `Minted` and skipped. Returns how many were minted.
+    pub async fn execute_minting(&mut self) -> Result<usize, String> {
+        let mut minted = 0;
+        for bridge in &mut self.pending_bridges {
+            if bridge.status != BridgeStatus::ReadyToMint {
+                continue;
+            }
+            if self.minted.contains(&bridge.id) {
+                bridge.status = BridgeStatus::Minted;
+                continue;
+            }
+            
             let dest_contract = self.contracts.get(&bridge.to_chain)
                 .ok_or("Destination chain not supported")?;
             
-            match dest_contract.mint_wrapped(&bridge).await {
+            match dest_contract.mint_wrapped(bridge, &mut self.minted).await {
                 Ok(tx_hash) => {
                     println!("🎉 Minted on {:?}: {}", bridge.to_chain, tx_hash);
+                    minted += 1;
                 }
                 Err(e) => {
                     eprintln!("❌ Minting failed: {}", e);
@@ -325,7 +511,7 @@ impl BridgeOracle {
             }
         }
         
-        Ok(())
+        Ok(minted)
     }
     
     pub async fn get_block_number(&self, chain: &ChainId) -> Result<u64, String> {
@@ -355,13 +541,25 @@ impl AxiomBridge {
             oracle: BridgeOracle::new(),
         }
     }
+
+    pub fn with_zk_system(mut self, zk: Arc<ZkProofSystem>) -> Self {
+        self.oracle = self.oracle.with_zk_system(zk);
+        self
+    }
+
+    pub fn with_token_registry(mut self, tokens: Arc<TokenRegistry>) -> Self {
+        self.oracle = self.oracle.with_token_registry(tokens);
+        self
+    }
     
-    /// Bridge AXM from Axiom to another chain
+    /// Bridge `token` (`amount` in Axiom units) from Axiom to another chain
     pub async fn bridge_to(
         &mut self,
+        token: &str,
         amount: u64,
         destination: ChainId,
         recipient: String, // EVM address on destination
+        witness: &LockWitness,
     ) -> Result<BridgeTransaction, String> {
         let axiom_contract = self.oracle.contracts.get(&ChainId::Axiom)
             .ok_or("Axiom bridge not available")?;
@@ -369,9 +567,11 @@ impl AxiomBridge {
         // Lock tokens on Axiom chain
         let bridge_tx = axiom_contract.lock_tokens(
             recipient.clone(),
-            amount,
+            token,
+            amount as u128,
             destination.clone(),
             recipient.clone(),
+            witness,
         ).await?;
         
         self.oracle.pending_bridges.push(bridge_tx.clone());
@@ -379,10 +579,12 @@ impl AxiomBridge {
         Ok(bridge_tx)
     }
     
-    /// Bridge from another chain back to Axiom
+    /// Bridge `token` (`amount` in the source chain's decimals) from
+    /// another chain back to Axiom
     pub async fn bridge_from(
         &mut self,
-        amount: u64,
+        token: &str,
+        amount: u128,
         source_chain: ChainId,
         recipient: String, // Axiom address
     ) -> Result<BridgeTransaction, String> {
@@ -391,6 +593,7 @@ impl AxiomBridge {
         
         // Burn wrapped tokens on source chain
         let bridge_tx = source_contract.burn_and_unlock(
+            token,
             amount,
             ChainId::Axiom,
             recipient,
@@ -443,14 +646,21 @@ impl AxiomBridge {
 mod tests {
     use super::*;
     
+    fn witness(balance: u64) -> LockWitness {
+        LockWitness { secret_key: [7u8; 32], balance, nonce: 3 }
+    }
+    
     #[tokio::test]
     async fn test_bridge_to_ethereum() {
-        let mut bridge = AxiomBridge::new();
+        let zk = Arc::new(ZkProofSystem::setup().unwrap());
+        let mut bridge = AxiomBridge::new().with_zk_system(zk);
         
         let result = bridge.bridge_to(
+            "AXM",
             100_000_000_000, // 100 AXM
             ChainId::Ethereum,
             "0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb".to_string(),
+            &witness(250_000_000_000),
         ).await;
         
         assert!(result.is_ok());
@@ -473,4 +683,125 @@ mod tests {
         // Should be 0.1% + gas
         assert!(fee > 1_000_000_000); // > 1 AXM
     }
+
+    #[test]
+    fn test_lock_proof_verifies_and_forgery_rejected() {
+        let zk = Arc::new(ZkProofSystem::setup().unwrap());
+        let contract = BridgeContract::new(ChainId::Axiom).with_zk_system(zk);
+
+        let proof = contract.generate_lock_proof(&witness(500), 200).unwrap();
+        assert!(contract.verify_bridge_proof(&proof, 200).unwrap());
+        // Bound to the locked amount
+        assert!(!contract.verify_bridge_proof(&proof, 201).unwrap());
+
+        let forged: Vec<u8> = (0..200).map(|_| rand::random::<u8>()).collect();
+        assert!(!contract.verify_bridge_proof(&forged, 200).unwrap());
+        assert!(!contract.verify_bridge_proof(&[0u8; 200], 200).unwrap());
+
+        // A locker who cannot cover the amount gets no proof at all
+        assert!(contract.generate_lock_proof(&witness(100), 200).is_err());
+        assert!(BridgeContract::new(ChainId::Axiom).verify_bridge_proof(&proof, 200).is_err());
+    }
+
+    async fn ready_bridge(oracle: &BridgeOracle) -> BridgeTransaction {
+        let mut bridge_tx = oracle.contracts[&ChainId::Axiom]
+            .lock_tokens("locker".to_string(), "AXM", 200, ChainId::Ethereum, "0xrecipient".to_string(), &witness(500))
+            .await
+            .unwrap();
+        bridge_tx.status = BridgeStatus::ReadyToMint;
+        bridge_tx
+    }
+
+    #[tokio::test]
+    async fn test_minting_is_idempotent() {
+        let zk = Arc::new(ZkProofSystem::setup().unwrap());
+        let mut oracle = BridgeOracle::new().with_zk_system(zk);
+        let bridge_tx = ready_bridge(&oracle).await;
+
+        // Queued twice, as if a relay raced a status update
+        oracle.pending_bridges.push(bridge_tx.clone());
+        oracle.pending_bridges.push(bridge_tx);
+        assert_eq!(oracle.execute_minting().await.unwrap(), 1);
+        assert_eq!(oracle.execute_minting().await.unwrap(), 0);
+        assert_eq!(oracle.minted.len(), 1);
+        assert!(oracle.pending_bridges.iter().all(|b| b.status == BridgeStatus::Minted));
+    }
+
+    #[tokio::test]
+    async fn test_mint_ledger_survives_restart() {
+        let path = std::env::temp_dir().join(format!("axiom_mint_ledger_{}", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let _ = std::fs::remove_file(&path);
+        let zk = Arc::new(ZkProofSystem::setup().unwrap());
+
+        let mut oracle = BridgeOracle::new()
+            .with_zk_system(zk.clone())
+            .with_mint_ledger(MintLedger::open(&path).unwrap());
+        let bridge_tx = ready_bridge(&oracle).await;
+        oracle.pending_bridges.push(bridge_tx.clone());
+        assert_eq!(oracle.execute_minting().await.unwrap(), 1);
+
+        // Crash before the Minted status was saved: the bridge comes back ready
+        let mut restarted = BridgeOracle::new()
+            .with_zk_system(zk)
+            .with_mint_ledger(MintLedger::open(&path).unwrap());
+        assert!(restarted.minted.contains(&bridge_tx.id));
+        restarted.pending_bridges.push(bridge_tx.clone());
+        assert_eq!(restarted.execute_minting().await.unwrap(), 0);
+        assert_eq!(restarted.pending_bridges[0].status, BridgeStatus::Minted);
+
+        let mut retry = bridge_tx;
+        let result = restarted.contracts[&ChainId::Ethereum].mint_wrapped(&mut retry, &mut restarted.minted).await;
+        assert_eq!(result.unwrap_err(), "Bridge already minted");
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_erc20_round_trip_through_axiom() {
+        use crate::bridge::tokens::TokenDeployment;
+        const WEI: u128 = 1_000_000_000_000_000_000;
+
+        let mut tokens = TokenRegistry::with_axm();
+        tokens.register("USDX", ChainId::Axiom, TokenDeployment { address: None, decimals: 8 });
+        tokens.register("USDX", ChainId::Ethereum, TokenDeployment { address: Some("0xa0b8".to_string()), decimals: 18 });
+        let zk = Arc::new(ZkProofSystem::setup().unwrap());
+        let mut oracle = BridgeOracle::new().with_zk_system(zk).with_token_registry(Arc::new(tokens));
+
+        // 12 tokens (18 decimals) in, 12.00000000 on Axiom
+        let mut inbound = oracle.contracts[&ChainId::Ethereum]
+            .lock_tokens("0xlocker".to_string(), "USDX", 12 * WEI, ChainId::Axiom, "axiom1".to_string(), &witness(2_000_000_000))
+            .await
+            .unwrap();
+        assert_eq!(inbound.amount, 1_200_000_000);
+        assert_eq!(oracle.contracts[&ChainId::Axiom].destination_amount(&inbound).unwrap(), 1_200_000_000);
+        inbound.status = BridgeStatus::ReadyToMint;
+
+        // And back out to exactly 12 * 10^18
+        let mut outbound = oracle.contracts[&ChainId::Axiom]
+            .lock_tokens("axiom1".to_string(), "USDX", 1_200_000_000, ChainId::Ethereum, "0xlocker".to_string(), &witness(2_000_000_000))
+            .await
+            .unwrap();
+        assert_eq!(oracle.contracts[&ChainId::Ethereum].destination_amount(&outbound).unwrap(), 12 * WEI);
+        outbound.status = BridgeStatus::ReadyToMint;
+
+        oracle.pending_bridges.extend([inbound, outbound]);
+        assert_eq!(oracle.execute_minting().await.unwrap(), 2);
+
+        // Dust below 10^-8 is not locked; dust alone cannot be bridged
+        let contract = &oracle.contracts[&ChainId::Ethereum];
+        let dusty = contract
+            .lock_tokens("0xlocker".to_string(), "USDX", WEI + 1, ChainId::Axiom, "axiom1".to_string(), &witness(2_000_000_000))
+            .await
+            .unwrap();
+        assert_eq!(dusty.amount, 100_000_000);
+        let dust_only = contract
+            .lock_tokens("0xlocker".to_string(), "USDX", 1, ChainId::Axiom, "axiom1".to_string(), &witness(2_000_000_000))
+            .await;
+        assert_eq!(dust_only.unwrap_err(), "Amount too small to bridge");
+        assert!(contract
+            .lock_tokens("0xlocker".to_string(), "USDX", WEI, ChainId::Polygon, "0xother".to_string(), &witness(2_000_000_000))
+            .await
+            .is_err());
+    }
 }
@@ -2,9 +2,13 @@
 
 pub mod cross_chain;
 pub mod atomic_swap;
+pub mod tokens;
 
 pub use cross_chain::{
     AxiomBridge, BridgeContract, BridgeOracle, BridgeStatus, BridgeTransaction, ChainId,
+    LockWitness, MintLedger,
 };
 
+pub use tokens::{TokenDeployment, TokenRegistry};
+
 pub use atomic_swap::{BridgeLock, BridgeSecret};
@@ -0,0 +1,130 @@
+// src/bridge/tokens.rs - Bridgeable token registry
+//
+// Every bridged token has a canonical ID (its symbol, e.g. "AXM" or "USDC")
+// and a deployment per chain: the contract address (none for a chain's
+// native asset) and the decimals it uses there. Bridge transactions carry
+// amounts in the token's canonical base, which is Axiom's 8 decimals, so
+// the ZK lock proof and the mint ledger see one unit across chains.
+//
+// Scaling up (8 -> 18 decimals) is exact. Scaling down truncates: a lock of
+// 1.000000000000000001 tokens on an 18-decimal chain bridges 1.00000000,
+// and the sub-unit remainder is never locked, so it stays with the sender.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::cross_chain::{BridgeContract, ChainId};
+use crate::chain::DECIMALS;
+
+/// A token's contract on one chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenDeployment {
+    /// Contract address; `None` for the chain's native asset
+    pub address: Option<String>,
+    pub decimals: u32,
+}
+
+/// Canonical token IDs mapped to their per-chain deployments
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TokenRegistry {
+    tokens: HashMap<String, HashMap<ChainId, TokenDeployment>>,
+}
+
+impl TokenRegistry {
+    /// Empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// AXM: native on Axiom, wrapped at the bridge address on every other
+    /// supported chain, 8 decimals everywhere
+    pub fn with_axm() -> Self {
+        let mut registry = Self::new();
+        registry.register("AXM", ChainId::Axiom, TokenDeployment { address: None, decimals: DECIMALS });
+        for chain in [ChainId::Ethereum, ChainId::BSC, ChainId::Polygon, ChainId::Arbitrum, ChainId::Optimism] {
+            registry.register(
+                "AXM",
+                chain,
+                TokenDeployment { address: Some(BridgeContract::BRIDGE_ADDRESS.to_string()), decimals: DECIMALS },
+            );
+        }
+        registry
+    }
+
+    pub fn register(&mut self, token: &str, chain: ChainId, deployment: TokenDeployment) {
+        self.tokens.entry(token.to_string()).or_default().insert(chain, deployment);
+    }
+
+    pub fn deployment(&self, token: &str, chain: &ChainId) -> Option<&TokenDeployment> {
+        self.tokens.get(token)?.get(chain)
+    }
+
+    fn decimals(&self, token: &str, chain: &ChainId) -> Result<u32, String> {
+        self.deployment(token, chain)
+            .map(|d| d.decimals)
+            .ok_or_else(|| format!("Token {} is not bridgeable on {:?}", token, chain))
+    }
+
+    /// `amount` of `token` in `chain`'s units, in canonical units, rounded
+    /// down
+    pub fn to_canonical(&self, token: &str, chain: &ChainId, amount: u128) -> Result<u64, String> {
+        let canonical = convert_amount(amount, self.decimals(token, chain)?, DECIMALS)?;
+        u64::try_from(canonical).map_err(|_| "Amount overflow".to_string())
+    }
+
+    /// Canonical `amount` of `token` in `chain`'s units, rounded down
+    pub fn from_canonical(&self, token: &str, chain: &ChainId, amount: u64) -> Result<u128, String> {
+        convert_amount(amount as u128, DECIMALS, self.decimals(token, chain)?)
+    }
+}
+
+/// Rescale `amount` from `from` decimals to `to` decimals. Scaling down
+/// drops the remainder below one unit of the coarser base.
+pub fn convert_amount(amount: u128, from: u32, to: u32) -> Result<u128, String> {
+    let scale = |digits: u32| 10u128.checked_pow(digits).ok_or_else(|| "Unsupported decimals".to_string());
+    if to >= from {
+        amount.checked_mul(scale(to - from)?).ok_or_else(|| "Amount overflow".to_string())
+    } else {
+        Ok(amount / scale(from - to)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WEI: u128 = 1_000_000_000_000_000_000;
+
+    fn registry() -> TokenRegistry {
+        let mut registry = TokenRegistry::with_axm();
+        registry.register("USDX", ChainId::Axiom, TokenDeployment { address: None, decimals: 8 });
+        registry.register(
+            "USDX",
+            ChainId::Ethereum,
+            TokenDeployment { address: Some("0xa0b8".to_string()), decimals: 18 },
+        );
+        registry
+    }
+
+    #[test]
+    fn test_round_amounts_convert_exactly() {
+        let registry = registry();
+        let canonical = registry.to_canonical("USDX", &ChainId::Ethereum, 5 * WEI).unwrap();
+        assert_eq!(canonical, 500_000_000);
+        assert_eq!(registry.from_canonical("USDX", &ChainId::Axiom, canonical).unwrap(), 500_000_000);
+        assert_eq!(registry.from_canonical("USDX", &ChainId::Ethereum, canonical).unwrap(), 5 * WEI);
+    }
+
+    #[test]
+    fn test_sub_unit_remainder_truncated() {
+        let registry = registry();
+        // 1.234567891234567891 tokens: the last ten digits don't fit 8 decimals
+        let canonical = registry.to_canonical("USDX", &ChainId::Ethereum, 1_234_567_891_234_567_891).unwrap();
+        assert_eq!(canonical, 123_456_789);
+        assert_eq!(registry.from_canonical("USDX", &ChainId::Ethereum, canonical).unwrap(), 1_234_567_890_000_000_000);
+        assert_eq!(registry.to_canonical("USDX", &ChainId::Ethereum, 9_999_999_999).unwrap(), 0);
+
+        assert!(registry.to_canonical("USDX", &ChainId::Polygon, WEI).is_err());
+        assert!(registry.to_canonical("USDX", &ChainId::Ethereum, u128::MAX).is_err());
+    }
+}
@@ -0,0 +1,283 @@
+//! Content-addressed block and transaction cache.
+//!
+//! Reading a block from the log means a seek, a bincode decode and a hash
+//! check, and finding a transaction means hashing every transaction of
+//! every block until one matches. The `BlockCache` remembers decoded blocks
+//! by hash, which height they were read at, and where each of their
+//! transactions sits, in bounded LRU maps. A reorg replaces blocks at
+//! existing heights, so everything from the fork height up is dropped with
+//! `invalidate_from`; lookups through a chain also check the cached hash
+//! against the block now at that height. The node holds its blocks in
+//! memory, so the RPC transaction lookups use the cache to skip the scan;
+//! there is no log read to save.
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::sync::Arc;
+
+use crate::block::Block;
+use crate::metrics;
+use crate::transaction::Transaction;
+
+/// Default `storage.block_cache_capacity`, in blocks
+pub const DEFAULT_BLOCK_CACHE_CAPACITY: usize = 1024;
+
+/// Transactions located per cached block, on average
+const TXS_PER_BLOCK: usize = 16;
+
+/// Map holding at most `capacity` entries, evicting the least recently used
+pub struct LruCache<K, V> {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<K, (V, u64)>,
+    /// Last-use tick of every key, oldest first
+    order: BTreeMap<u64, K>,
+}
+
+impl<K: Clone + Eq + Hash, V> LruCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity: capacity.max(1), tick: 0, entries: HashMap::new(), order: BTreeMap::new() }
+    }
+
+    /// Look up `key`, marking it most recently used
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        self.tick += 1;
+        let (_, used) = self.entries.get_mut(key)?;
+        self.order.remove(used);
+        *used = self.tick;
+        self.order.insert(self.tick, key.clone());
+        self.entries.get(key).map(|(value, _)| value)
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        self.remove(&key);
+        if self.entries.len() >= self.capacity {
+            if let Some((_, oldest)) = self.order.pop_first() {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.tick += 1;
+        self.order.insert(self.tick, key.clone());
+        self.entries.insert(key, (value, self.tick));
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let (value, used) = self.entries.remove(key)?;
+        self.order.remove(&used);
+        Some(value)
+    }
+
+    /// Drop every entry for which `keep` returns false
+    pub fn retain(&mut self, mut keep: impl FnMut(&K, &V) -> bool) {
+        let order = &mut self.order;
+        self.entries.retain(|key, (value, used)| {
+            let kept = keep(key, value);
+            if !kept {
+                order.remove(used);
+            }
+            kept
+        });
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Where a cached transaction is: block, height and position in the block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TxLocation {
+    pub block_hash: [u8; 32],
+    pub height: u64,
+    pub index: usize,
+}
+
+/// Hit and miss counts since startup
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub blocks: usize,
+    pub transactions: usize,
+}
+
+/// Decoded blocks by hash, plus height and transaction indexes into them
+pub struct BlockCache {
+    blocks: LruCache<[u8; 32], Arc<Block>>,
+    heights: HashMap<u64, [u8; 32]>,
+    transactions: LruCache<[u8; 32], TxLocation>,
+    hits: u64,
+    misses: u64,
+}
+
+impl Default for BlockCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_BLOCK_CACHE_CAPACITY)
+    }
+}
+
+impl BlockCache {
+    /// Cache up to `capacity` blocks
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            blocks: LruCache::new(capacity),
+            heights: HashMap::new(),
+            transactions: LruCache::new(capacity.max(1) * TXS_PER_BLOCK),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Remember `block`, read at `height`, and index its transactions
+    pub fn insert(&mut self, height: u64, block: Arc<Block>) -> Arc<Block> {
+        let hash = block.hash();
+        for (index, tx) in block.transactions.iter().enumerate() {
+            self.transactions.insert(tx.hash(), TxLocation { block_hash: hash, height, index });
+        }
+        self.heights.insert(height, hash);
+        self.blocks.insert(hash, block.clone());
+        self.heights.retain(|_, hash| self.blocks.contains(hash));
+        block
+    }
+
+    pub fn block(&mut self, hash: &[u8; 32]) -> Option<Arc<Block>> {
+        let block = self.blocks.get(hash).cloned();
+        self.count(block.is_some());
+        block
+    }
+
+    pub fn block_at(&mut self, height: u64) -> Option<Arc<Block>> {
+        let block = self.heights.get(&height).and_then(|hash| self.blocks.get(hash)).cloned();
+        self.count(block.is_some());
+        block
+    }
+
+    /// Location of a transaction in a cached block
+    pub fn locate(&mut self, tx_hash: &[u8; 32]) -> Option<TxLocation> {
+        let location = self.transactions.get(tx_hash).copied();
+        self.count(location.is_some());
+        location
+    }
+
+    /// A transaction and the block holding it, if that block is cached
+    pub fn transaction(&mut self, tx_hash: &[u8; 32]) -> Option<(Arc<Block>, Transaction)> {
+        let location = self.transactions.get(tx_hash).copied();
+        let found = location.and_then(|at| {
+            let block = self.blocks.get(&at.block_hash)?.clone();
+            let tx = block.transactions.get(at.index)?.clone();
+            Some((block, tx))
+        });
+        self.count(found.is_some());
+        found
+    }
+
+    /// Forget every block at `height` or above, and their transactions.
+    /// Call when a reorg replaces the chain from `height`.
+    pub fn invalidate_from(&mut self, height: u64) {
+        let stale: Vec<[u8; 32]> = self.heights.iter().filter(|(h, _)| **h >= height).map(|(_, hash)| *hash).collect();
+        self.heights.retain(|h, _| *h < height);
+        for hash in &stale {
+            self.blocks.remove(hash);
+        }
+        self.transactions.retain(|_, at| at.height < height);
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            blocks: self.blocks.len(),
+            transactions: self.transactions.len(),
+        }
+    }
+
+    fn count(&mut self, hit: bool) {
+        if hit {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+        metrics::record_cache_lookup(hit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(len: u64, branch: u8) -> Vec<Block> {
+        let mut blocks: Vec<Block> = Vec::new();
+        for slot in 0..len {
+            let parent = blocks.last().map(|b| b.hash()).unwrap_or([0u8; 32]);
+            // Blocks past height 2 differ between branches
+            let tag = if slot > 2 { branch } else { 3 };
+            let tx = Transaction::new([1u8; 32], [2u8; 32], 10 * tag as u64 + slot, 1, slot, vec![], vec![]);
+            blocks.push(Block::new(parent, slot, [tag; 32], vec![tx], [0u8; 32], vec![0u8; 128], slot));
+        }
+        blocks
+    }
+
+    #[test]
+    fn test_lru_evicts_least_recently_used() {
+        let mut lru = LruCache::new(2);
+        lru.insert("a", 1);
+        lru.insert("b", 2);
+        assert_eq!(lru.get(&"a"), Some(&1));
+        lru.insert("c", 3);
+        assert!(!lru.contains(&"b"));
+        assert!(lru.contains(&"a") && lru.contains(&"c"));
+        assert_eq!(lru.len(), 2);
+    }
+
+    #[test]
+    fn test_second_fetch_served_from_cache() {
+        let mut cache = BlockCache::new(8);
+        let blocks = chain(5, 4);
+        assert!(cache.block_at(3).is_none());
+        cache.insert(3, Arc::new(blocks[3].clone()));
+
+        let first = cache.block_at(3).unwrap();
+        let second = cache.block(&blocks[3].hash()).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        // Its transactions are indexed, so finding one needs no scan
+        let tx_hash = blocks[3].transactions[0].hash();
+        let (block, tx) = cache.transaction(&tx_hash).unwrap();
+        assert_eq!(block.hash(), blocks[3].hash());
+        assert_eq!(tx.hash(), tx_hash);
+        assert_eq!(cache.locate(&tx_hash), Some(TxLocation { block_hash: blocks[3].hash(), height: 3, index: 0 }));
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (4, 1));
+    }
+
+    #[test]
+    fn test_reorg_evicts_replaced_blocks() {
+        let mut cache = BlockCache::new(8);
+        let old = chain(5, 4);
+        for (height, block) in old.iter().enumerate() {
+            cache.insert(height as u64, Arc::new(block.clone()));
+        }
+
+        // A competing branch replaces heights 3 and 4
+        cache.invalidate_from(3);
+        assert!(cache.transaction(&old[4].transactions[0].hash()).is_none());
+        assert!(cache.transaction(&old[2].transactions[0].hash()).is_some());
+        assert!(cache.block(&old[3].hash()).is_none());
+        assert!(cache.block_at(4).is_none());
+
+        // Blocks below the fork are still cached
+        assert_eq!(cache.block_at(2).unwrap().hash(), old[2].hash());
+        let new = chain(5, 5);
+        cache.insert(4, Arc::new(new[4].clone()));
+        assert_eq!(cache.block_at(4).unwrap().hash(), new[4].hash());
+        assert_eq!(cache.stats().blocks, 4);
+    }
+}
@@ -1,17 +1,36 @@
 use crate::block::Block;
 use crate::transaction::{Transaction, Address};
-use crate::state::State;
+use crate::state::{State, StateUndo, SupplyMismatch, TxLocation};
 use crate::economics;
-use std::collections::HashSet;
+use crate::consensus::lwma;
+use num_traits::ToPrimitive;
+use crate::merkle::MerkleAccumulator;
+use crate::error::{AxiomError, Result as AxiomResult};
+use crate::snapshot::Snapshot;
+use crate::storage::{latest_trusted, Checkpoint, StateIndex, StoredBlock};
+use crate::wallet::Wallet;
+use ed25519_dalek::SigningKey;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 
 pub const TARGET_TIME: u64 = 1800; // 30 Minute Time-Lock (VDF)
 pub const HALVING_INTERVAL: u64 = 2_100_000;
 pub const INITIAL_REWARD: u64 = 50_000_000_000; // 500 AXM (8 decimals)
 pub const MAX_SUPPLY: u64 = 124_000_000_000_000_000; // 124M AXM in smallest units
 pub const DECIMALS: u32 = 8;
+/// Most recent blocks whose state changes are kept for `undo_last_block`
+pub const UNDO_DEPTH: usize = 1_000;
+/// Default `consensus.max_block_size` (serialized bytes)
+pub const MAX_BLOCK_SIZE: usize = 1_000_000;
+/// Default `consensus.max_transactions_per_block`, coinbase not counted
+pub const MAX_TRANSACTIONS_PER_BLOCK: usize = 10_000;
+/// Block VDF iterations until `with_vdf_steps` applies `consensus.vdf_steps`
+pub const DEFAULT_VDF_STEPS: u32 = 1_000;
+/// Length of a miner's ZK pass
+const ZK_PASS_SIZE: usize = 128;
 
-/// THE SOVEREIGN ANCHOR: Hardcoded from your 2026-01-11 solo mine.
-pub const GENESIS_ANCHOR: &str = "7876d9aac11b1197474167b7485626bf535e551a21865c6264f07f614281298c";
+/// THE SOVEREIGN ANCHOR: Hardcoded from your 2026-01-11 solo mine; the
+/// mainnet genesis header hash since it was tagged (`hashing::GENESIS`).
+pub const GENESIS_ANCHOR: &str = "8d855999961d98d750f9523d9106f5cd76435f5ba72111dde0b684a0ba5eec51";
 
 pub struct Timechain {
     pub blocks: Vec<Block>,
@@ -19,6 +38,68 @@ pub struct Timechain {
     pub difficulty: u64,
     seen_hashes: HashSet<[u8; 32]>, // Injection Protection
     pub total_issued: u64,
+    /// Fraction of each block's fees that is burned instead of paid to the miner
+    pub fee_burn_fraction: f64,
+    /// Cumulative fees removed from circulation
+    pub total_burned: u64,
+    /// Difficulty set by the genesis config, restored when a sync is undone
+    pub initial_difficulty: u64,
+    /// Target seconds between blocks (`consensus.block_time_seconds`).
+    /// `add_block` raises difficulty when `elapsed` is shorter and lowers it
+    /// when longer; replays pass exactly this value so difficulty is unchanged.
+    /// At `lwma::TARGET_BLOCK_TIME` the LWMA of the block timestamps takes
+    /// over once there is a full window (see `lwma_window`).
+    pub block_time: u64,
+    /// Sequential iterations of each block's VDF (`consensus.vdf_steps`);
+    /// `add_block` recomputes the proof from the parent and slot with this many
+    pub vdf_steps: u32,
+    /// Smallest amount `validate_transaction` accepts (`consensus.dust_limit`)
+    pub dust_limit: u64,
+    /// Let zero-amount transactions through despite `dust_limit`
+    pub allow_zero_amount: bool,
+    /// Smallest fee per serialized byte a transaction must pay
+    /// (`consensus.min_fee_per_byte`), checked on entry and in blocks
+    pub min_fee_per_byte: u64,
+    /// Largest serialized block `add_block` accepts (`consensus.max_block_size`)
+    pub max_block_size: usize,
+    /// Most user transactions a block may carry
+    /// (`consensus.max_transactions_per_block`)
+    pub max_transactions_per_block: usize,
+    /// Blocks buried this deep under the tip become final
+    /// (`consensus.confirmation_depth`); 0 leaves finality to checkpoints
+    pub confirmation_depth: u64,
+    /// Highest final block: the trusted checkpoint synced from, or the
+    /// deepest block past `confirmation_depth`. Only ever rises;
+    /// `rollback_to` and `check_reorg` never go below it.
+    pub finalized_height: u64,
+    /// Network this chain belongs to (`network.network_id`); transactions
+    /// signed for any other chain id are rejected
+    pub network_id: u8,
+    /// State changes of the most recent blocks, newest last
+    undo_log: VecDeque<BlockUndo>,
+    /// State installed at a checkpoint by a snapshot or header sync, which
+    /// `rebuild_state` replays from instead of genesis
+    base: Option<StateBase>,
+    /// Tests only: take transfers without a ZK proof, which cannot be made
+    /// without the Groth16 keys (see `without_tx_proofs`)
+    #[cfg(test)]
+    skip_tx_proofs: bool,
+}
+
+/// State at the finalized checkpoint a chain was synced from
+struct StateBase {
+    height: u64,
+    state: State,
+    total_issued: u64,
+    total_burned: u64,
+}
+
+/// Everything needed to take one block back off the chain
+struct BlockUndo {
+    state: StateUndo,
+    total_issued: u64,
+    total_burned: u64,
+    difficulty: u64,
 }
 
 impl Timechain {
@@ -33,37 +114,457 @@ impl Timechain {
             );
         }
 
+        Self::from_genesis_block(genesis, 1000)
+    }
+
+    /// Chain rooted at the genesis described by `config`. Mainnet is still
+    /// checked against `GENESIS_ANCHOR`; other networks are trusted as given.
+    pub fn with_genesis(config: &crate::genesis::GenesisConfig) -> AxiomResult<Self> {
+        let genesis = crate::genesis::from_config(config)?;
+        if config.is_mainnet() {
+            return Ok(Self::new(genesis));
+        }
+        Ok(Self::from_genesis_block(genesis, config.difficulty))
+    }
+
+    fn from_genesis_block(genesis: Block, difficulty: u64) -> Self {
         let mut tc = Timechain {
             blocks: vec![genesis],
             state: State::new(),
-            difficulty: 1000,
+            difficulty,
             seen_hashes: HashSet::new(),
             total_issued: 0,
+            fee_burn_fraction: 0.0,
+            total_burned: 0,
+            initial_difficulty: difficulty,
+            block_time: TARGET_TIME,
+            vdf_steps: DEFAULT_VDF_STEPS,
+            dust_limit: 0,
+            allow_zero_amount: false,
+            min_fee_per_byte: 0,
+            max_block_size: MAX_BLOCK_SIZE,
+            max_transactions_per_block: MAX_TRANSACTIONS_PER_BLOCK,
+            confirmation_depth: 0,
+            finalized_height: 0,
+            network_id: crate::config::MAINNET_NETWORK_ID,
+            undo_log: VecDeque::new(),
+            base: None,
+            #[cfg(test)]
+            skip_tx_proofs: false,
         };
         tc.rebuild_state();
         tc
     }
 
-    /// Rebuild state from all blocks
+    /// Use `seconds` as the block-time target instead of `TARGET_TIME`
+    pub fn with_block_time(mut self, seconds: u64) -> Self {
+        self.block_time = seconds;
+        self
+    }
+
+    /// Run each block's VDF for `steps` iterations instead of `DEFAULT_VDF_STEPS`
+    pub fn with_vdf_steps(mut self, steps: u32) -> Self {
+        self.vdf_steps = steps;
+        self
+    }
+
+    /// Reject transfers below `limit` from the mempool and mined blocks
+    pub fn with_dust_limit(mut self, limit: u64, allow_zero_amount: bool) -> Self {
+        self.dust_limit = limit;
+        self.allow_zero_amount = allow_zero_amount;
+        self
+    }
+
+    /// Require every transaction to pay at least `fee_per_byte` per byte
+    pub fn with_min_fee_per_byte(mut self, fee_per_byte: u64) -> Self {
+        self.min_fee_per_byte = fee_per_byte;
+        self
+    }
+
+    /// Reject blocks larger than `max_size` bytes or with more than
+    /// `max_transactions` transactions besides the coinbase
+    pub fn with_block_limits(mut self, max_size: usize, max_transactions: usize) -> Self {
+        self.max_block_size = max_size;
+        self.max_transactions_per_block = max_transactions;
+        self
+    }
+
+    /// Make blocks `depth` deep final, so no reorg can replace them
+    pub fn with_confirmation_depth(mut self, depth: u64) -> Self {
+        self.confirmation_depth = depth;
+        self.advance_finality();
+        self
+    }
+
+    /// Burn `fraction` (0.0..=1.0) of each block's fees instead of paying
+    /// them to the miner
+    pub fn with_fee_burn_fraction(mut self, fraction: f64) -> Self {
+        self.fee_burn_fraction = fraction;
+        self
+    }
+
+    /// Accept only transactions signed for `network_id`
+    pub fn with_network_id(mut self, network_id: u8) -> Self {
+        self.network_id = network_id;
+        self
+    }
+
+    /// Fresh chain sharing this chain's genesis, e.g. to validate a peer's
+    /// blocks without touching the local chain
+    pub fn genesis_only(&self) -> Self {
+        let chain = Self::from_genesis_block(self.blocks[0].clone(), self.initial_difficulty)
+            .with_block_time(self.block_time)
+            .with_vdf_steps(self.vdf_steps)
+            .with_dust_limit(self.dust_limit, self.allow_zero_amount)
+            .with_min_fee_per_byte(self.min_fee_per_byte)
+            .with_block_limits(self.max_block_size, self.max_transactions_per_block)
+            .with_confirmation_depth(self.confirmation_depth)
+            .with_fee_burn_fraction(self.fee_burn_fraction)
+            .with_network_id(self.network_id);
+        #[cfg(test)]
+        let chain = Self { skip_tx_proofs: self.skip_tx_proofs, ..chain };
+        chain
+    }
+
+    /// Accept transfers whose ZK proof does not verify, in blocks and on
+    /// entry, so tests can move funds without the Groth16 keys
+    #[cfg(test)]
+    pub(crate) fn without_tx_proofs(mut self) -> Self {
+        self.skip_tx_proofs = true;
+        self
+    }
+
+    /// Rebuild state from all blocks, or from the blocks after the synced
+    /// checkpoint when bodies below it are not available
     pub fn rebuild_state(&mut self) {
         self.state = State::new();
         self.total_issued = 0;
+        self.total_burned = 0;
+        let mut base_height = None;
+        if let Some(base) = &self.base {
+            self.state = base.state.clone();
+            self.total_issued = base.total_issued;
+            self.total_burned = base.total_burned;
+            base_height = Some(base.height);
+        }
 
-        for block in &self.blocks {
-            // Process mining reward
-            let reward = economics::block_reward(block.slot, self.total_issued);
-            if reward > 0 && block.miner != [0u8; 32] {
-                self.state.credit(block.miner, reward);
-                self.total_issued += reward;
+        self.undo_log.clear();
+
+        let blocks = std::mem::take(&mut self.blocks);
+        for block in &blocks {
+            // The base state already holds these blocks, history included
+            if base_height.is_some_and(|height| block.slot <= height) {
+                continue;
+            }
+            // Genesis allocations are unsigned credits from the zero address
+            if block.slot == 0 {
+                for tx in &block.transactions {
+                    self.state.credit(tx.to, tx.amount);
+                }
+                self.index_block(block);
+                continue;
             }
 
-            // Process transactions
-            for tx in &block.transactions {
+            let undo = self.begin_undo();
+
+            // Process transactions, then pay the coinbase: reward + fees
+            let mut fees = 0u64;
+            for tx in block.user_transactions() {
                 if self.state.apply_tx(tx).is_ok() {
-                    // Transaction successful
+                    fees = fees.saturating_add(tx.fee);
                 }
             }
+            self.pay_coinbase(block, fees);
+            self.index_block(block);
+            self.push_undo(undo);
+        }
+        self.blocks = blocks;
+    }
+
+    /// Add every transaction in `block` to the address history index
+    fn index_block(&mut self, block: &Block) {
+        for (index, tx) in block.transactions.iter().enumerate() {
+            self.state.index_tx(tx, TxLocation { height: block.slot, index: index as u32 });
+        }
+    }
+
+    /// Start journaling a block's state changes; finish with `push_undo`
+    fn begin_undo(&mut self) -> BlockUndo {
+        self.state.begin_journal();
+        BlockUndo {
+            state: StateUndo::default(),
+            total_issued: self.total_issued,
+            total_burned: self.total_burned,
+            difficulty: self.difficulty,
+        }
+    }
+
+    fn push_undo(&mut self, mut undo: BlockUndo) {
+        undo.state = self.state.take_journal();
+        if self.undo_log.len() == UNDO_DEPTH {
+            self.undo_log.pop_front();
+        }
+        self.undo_log.push_back(undo);
+    }
+
+    fn apply_undo(&mut self, undo: BlockUndo) {
+        self.state.revert(&undo.state);
+        self.total_issued = undo.total_issued;
+        self.total_burned = undo.total_burned;
+        self.difficulty = undo.difficulty;
+    }
+
+    /// Take the tip block back off the chain, restoring balances, nonces,
+    /// supply and difficulty to what they were before it was applied.
+    /// Returns `None` at genesis. Blocks older than `UNDO_DEPTH` are undone
+    /// by replaying the chain from genesis.
+    pub fn undo_last_block(&mut self) -> Option<Block> {
+        if self.blocks.len() <= 1 {
+            return None;
+        }
+        let block = self.blocks.pop()?;
+        self.seen_hashes.remove(&block.calculate_hash());
+        match self.undo_log.pop_back() {
+            Some(undo) => self.apply_undo(undo),
+            None => self.rebuild_state(),
         }
+        Some(block)
+    }
+
+    /// Take every block above `height` back off the chain, as repeated
+    /// `undo_last_block` calls would, and return them oldest first so they
+    /// can be re-applied or their transactions returned to the mempool.
+    /// Refuses to go below `finalized_height`.
+    pub fn rollback_to(&mut self, height: u64) -> AxiomResult<Vec<Block>> {
+        if height < self.finalized_height {
+            return Err(AxiomError::ConsensusFailed(format!(
+                "Cannot roll back to height {}: finalized through {}",
+                height, self.finalized_height
+            )));
+        }
+        let tip = self.blocks.len() as u64 - 1;
+        if height > tip {
+            return Err(AxiomError::InvalidBlockHeight { expected: tip, actual: height });
+        }
+
+        let mut removed = Vec::with_capacity((tip - height) as usize);
+        while self.blocks.len() as u64 > height + 1 {
+            removed.extend(self.undo_last_block());
+        }
+        removed.reverse();
+        Ok(removed)
+    }
+
+    /// Raise `finalized_height` to the block `confirmation_depth` below the tip
+    fn advance_finality(&mut self) {
+        if self.confirmation_depth > 0 {
+            let buried = (self.blocks.len() as u64 - 1).saturating_sub(self.confirmation_depth);
+            self.finalized_height = self.finalized_height.max(buried);
+        }
+    }
+
+    /// Height of the last block that `blocks`, a chain from genesis, shares
+    /// with this one; `None` if even the genesis blocks differ
+    pub fn common_ancestor(&self, blocks: &[Block]) -> Option<u64> {
+        // Blocks below a header-synced base may be pruned; the whole block
+        // at the base stands for them through its parent links
+        let skip = self.base.as_ref().map_or(0, |base| base.height as usize);
+        if skip > 0 && blocks.get(skip).map(Block::hash) != Some(self.blocks[skip].hash()) {
+            return (blocks.first().map(Block::hash) == Some(self.blocks[0].hash())).then_some(0);
+        }
+        let shared = self.blocks[skip..]
+            .iter()
+            .zip(&blocks[skip..])
+            .take_while(|(ours, theirs)| ours.hash() == theirs.hash())
+            .count();
+        (skip as u64 + shared as u64).checked_sub(1)
+    }
+
+    /// Check that switching to `blocks`, a chain from genesis, would keep
+    /// every final block
+    pub fn check_reorg(&self, blocks: &[Block]) -> AxiomResult<()> {
+        let ancestor = self
+            .common_ancestor(blocks)
+            .ok_or_else(|| AxiomError::InvalidBlock("Different genesis block".to_string()))?;
+        if ancestor < self.finalized_height {
+            return Err(AxiomError::ConsensusFailed(format!(
+                "Fork at height {} rewrites blocks finalized through {}",
+                ancestor + 1,
+                self.finalized_height
+            )));
+        }
+        Ok(())
+    }
+
+    /// Credit a block's coinbase, which holds the reward and the miner's
+    /// share of `total_fees`; the rest of the fees is burned
+    fn pay_coinbase(&mut self, block: &Block, total_fees: u64) {
+        let (_, burned) = economics::apply_fee_policy(total_fees, self.fee_burn_fraction);
+        if let Some(coinbase) = block.coinbase().filter(|tx| tx.to != [0u8; 32]) {
+            self.state.credit(coinbase.to, coinbase.amount);
+            self.total_issued += economics::block_reward(block.slot, self.total_issued);
+        }
+        self.total_burned = self.total_burned.saturating_add(burned);
+    }
+
+    /// Coinbase for a block at `height` mined by `miner` whose other
+    /// transactions pay `fees` in total: the subsidy plus the miner's share
+    /// of the fees after `fee_burn_fraction`
+    pub fn expected_coinbase(&self, miner: Address, height: u64, fees: u64) -> Transaction {
+        let (miner_portion, _) = economics::apply_fee_policy(fees, self.fee_burn_fraction);
+        Transaction::coinbase(miner, height, miner_portion).with_chain_id(self.network_id)
+    }
+
+    /// Coinbase for the next block, mined by `miner` with `transactions`
+    pub fn coinbase_for(&self, miner: Address, transactions: &[Transaction]) -> Transaction {
+        let fees = transactions.iter().fold(0u64, |acc, tx| acc.saturating_add(tx.fee));
+        self.expected_coinbase(miner, self.blocks.len() as u64, fees)
+    }
+
+    /// Unsealed next block mined by `miner`: its coinbase followed by
+    /// `transactions`, carrying the miner's ZK pass. The VDF proof and
+    /// nonce are left zero for whoever seals it.
+    pub fn next_block(&self, miner: &Wallet, transactions: Vec<Transaction>) -> Block {
+        self.next_block_rewarding(miner, miner.address, transactions)
+    }
+
+    /// Like `next_block`, with the block and its coinbase credited to
+    /// `reward_address` (e.g. a pool or custodial address) rather than to
+    /// the wallet that signs the ZK pass
+    pub fn next_block_rewarding(&self, miner: &Wallet, reward_address: Address, transactions: Vec<Transaction>) -> Block {
+        let mut block = self.block_template(reward_address, transactions);
+        block.zk_proof = crate::genesis::generate_zk_pass(miner, block.parent);
+        block
+    }
+
+    /// Like `next_block` for a miner whose keys are elsewhere: the ZK pass
+    /// is left empty too. Transactions are put in canonical order
+    /// (`Block::sort_canonical`) and those past the block limits dropped.
+    pub fn block_template(&self, miner: Address, mut transactions: Vec<Transaction>) -> Block {
+        Block::sort_canonical(&mut transactions);
+        transactions.truncate(self.fitting_transactions(miner, &transactions));
+        let tip = self.blocks.last().unwrap();
+        let (parent, slot) = (tip.hash(), self.blocks.len() as u64);
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        // The Merkle root grows with each transaction; the coinbase leaf in
+        // front is filled in once the fees are known
+        let mut merkle = MerkleAccumulator::new();
+        merkle.push([0u8; 32]);
+        let mut fees = 0u64;
+        for tx in &transactions {
+            merkle.push(tx.hash());
+            fees = fees.saturating_add(tx.fee);
+        }
+        let coinbase = self.expected_coinbase(miner, slot, fees);
+        merkle.set_first(coinbase.hash());
+
+        let mut block_txs = vec![coinbase];
+        block_txs.extend(transactions);
+        Block {
+            parent,
+            slot,
+            timestamp: now.max(tip.timestamp),
+            difficulty: self.difficulty,
+            miner,
+            transactions: block_txs,
+            merkle_root: merkle.root(),
+            vdf_proof: [0u8; 32],
+            zk_proof: vec![],
+            nonce: 0,
+        }
+    }
+
+    /// Split `transactions` into those that can be applied in order on top
+    /// of the tip (each sender affords them all and its nonces run on
+    /// without gaps) and the rest, which may become valid later. Accounts
+    /// are loaded once for the whole batch.
+    pub fn affordable_transactions(&self, transactions: Vec<Transaction>) -> (Vec<Transaction>, Vec<Transaction>) {
+        let senders: Vec<Address> = transactions.iter().map(|tx| tx.from).collect();
+        let mut accounts = self.state.get_accounts(&senders);
+        let (mut affordable, mut deferred) = (Vec::new(), Vec::new());
+        for tx in transactions {
+            let account = accounts.get_mut(&tx.from).expect("sender prefetched");
+            match tx.amount.checked_add(tx.fee) {
+                Some(cost) if cost <= account.balance && tx.nonce == account.nonce => {
+                    account.balance -= cost;
+                    account.nonce += 1;
+                    affordable.push(tx);
+                }
+                _ => deferred.push(tx),
+            }
+        }
+        (affordable, deferred)
+    }
+
+    /// What `miner`'s next block can carry from `candidates`, taken best
+    /// first as `Mempool::get_for_mining` ranks them: transactions that pass
+    /// `validate_transaction` and that their senders afford together, each
+    /// behind its sender's lower nonces, until the block limits are reached.
+    /// The invalid candidates come back second, for the caller to evict.
+    /// `block_template` puts the selection in canonical order.
+    pub fn select_transactions(&self, miner: Address, candidates: Vec<Transaction>) -> (Vec<Transaction>, Vec<Transaction>) {
+        let (valid, invalid): (Vec<_>, Vec<_>) =
+            candidates.into_iter().partition(|tx| self.validate_transaction(tx).is_ok());
+        let senders: Vec<Address> = valid.iter().map(|tx| tx.from).collect();
+        let mut accounts = self.state.get_accounts(&senders);
+        // Ranked ahead of a lower nonce of their sender, so held back until it
+        let mut waiting: HashMap<Address, BTreeMap<u64, Transaction>> = HashMap::new();
+        let mut selected = Vec::new();
+        for tx in valid {
+            let (from, account) = (tx.from, accounts.get_mut(&tx.from).expect("sender prefetched"));
+            let queue = waiting.entry(from).or_default();
+            queue.insert(tx.nonce, tx);
+            while let Some(tx) = queue.remove(&account.nonce) {
+                match tx.amount.checked_add(tx.fee) {
+                    Some(cost) if cost <= account.balance => {
+                        account.balance -= cost;
+                        account.nonce += 1;
+                        selected.push(tx);
+                    }
+                    _ => break,
+                }
+            }
+        }
+        selected.truncate(self.fitting_transactions(miner, &selected));
+        (selected, invalid)
+    }
+
+    /// How many of `transactions`, taken in order, fit in `miner`'s next
+    /// block alongside its coinbase and ZK pass without breaking
+    /// `max_transactions_per_block` or `max_block_size`
+    pub fn fitting_transactions(&self, miner: Address, transactions: &[Transaction]) -> usize {
+        let parent = self.blocks.last().unwrap().hash();
+        let slot = self.blocks.len() as u64;
+        let coinbase = self.coinbase_for(miner, &[]);
+        let mut size = Block::new(parent, slot, miner, vec![coinbase], [0u8; 32], vec![0u8; ZK_PASS_SIZE], 0).size();
+        for (count, tx) in transactions.iter().enumerate() {
+            size += tx.size();
+            if count >= self.max_transactions_per_block || size > self.max_block_size {
+                return count;
+            }
+        }
+        transactions.len()
+    }
+
+    /// Enforce `max_block_size` on the serialized block and
+    /// `max_transactions_per_block` on its non-coinbase transactions
+    pub fn check_block_limits(&self, block: &Block) -> AxiomResult<()> {
+        let size = block.size();
+        if size > self.max_block_size {
+            return Err(AxiomError::BlockTooLarge { size, max: self.max_block_size });
+        }
+        let count = block.user_transactions().len();
+        if count > self.max_transactions_per_block {
+            return Err(AxiomError::InvalidBlock(format!(
+                "{} transactions (max: {})",
+                count, self.max_transactions_per_block
+            )));
+        }
+        Ok(())
     }
 
     /// The Core Consensus Logic: VDF + PoW + Self-Healing
@@ -83,24 +584,65 @@ impl Timechain {
             return Err("Invalid block slot");
         }
 
+        if self.check_block_limits(&block).is_err() {
+            return Err("Block exceeds size limits");
+        }
+
         // 3. VALIDATE VDF PROOF
         let expected_vdf = crate::main_helper::compute_vdf(
             crate::vdf::evaluate(block.parent, block.slot),
-            self.difficulty as u32
+            self.vdf_steps,
         );
         if block.vdf_proof != expected_vdf {
             return Err("Invalid VDF proof");
         }
 
-        // 4. VALIDATE POW
-        if !block.meets_difficulty(self.difficulty) {
+        // 4. VALIDATE CLAIMED DIFFICULTY & POW
+        if !self.accepts_difficulty(block.difficulty) {
+            return Err("Block claims the wrong difficulty");
+        }
+        if !block.meets_difficulty() {
             return Err("Block doesn't meet difficulty requirement");
         }
 
         // 5. VALIDATE TRANSACTIONS
-        for tx in &block.transactions {
-            let sender_balance = self.state.balance(&tx.from);
-            tx.validate(sender_balance)?;
+        if block.merkle_root != block.compute_merkle_root() {
+            return Err("Merkle root mismatch");
+        }
+        let user_txs = block.user_transactions();
+        if user_txs.iter().any(Transaction::is_coinbase) {
+            return Err("Coinbase must be the first transaction");
+        }
+        if !block.is_canonically_ordered() {
+            return Err("Transactions not in canonical order");
+        }
+        let fees = user_txs.iter().fold(0u64, |acc, tx| acc.saturating_add(tx.fee));
+        let coinbase = block.coinbase().ok_or("First transaction must be a coinbase")?;
+        let expected = self.expected_coinbase(block.miner, block.slot, fees);
+        if coinbase.to != expected.to {
+            return Err("Coinbase does not pay the block's miner");
+        }
+        if coinbase.amount != expected.amount {
+            return Err("Coinbase amount does not match reward plus fees");
+        }
+        if coinbase.nonce != expected.nonce || coinbase.chain_id != expected.chain_id {
+            return Err("Coinbase is not for this block");
+        }
+        let senders: Vec<Address> = user_txs.iter().map(|tx| tx.from).collect();
+        let accounts = self.state.get_accounts(&senders);
+        for tx in user_txs {
+            if tx.chain_id != self.network_id {
+                return Err("Transaction signed for another chain");
+            }
+            if tx.fee < tx.min_fee(self.min_fee_per_byte) {
+                return Err("Transaction fee rate too low");
+            }
+            #[cfg(test)]
+            if self.skip_tx_proofs {
+                tx.validate_unproven(accounts[&tx.from].balance)?;
+                continue;
+            }
+            tx.validate(accounts[&tx.from].balance)?;
         }
 
         // 6. VALIDATE ZK PASS FOR MINER
@@ -112,59 +654,1702 @@ impl Timechain {
         self.seen_hashes.insert(block_hash);
         self.blocks.push(block.clone());
 
-        // 8. UPDATE STATE
-        let reward = economics::block_reward(block.slot, self.total_issued);
-        if reward > 0 && block.miner != [0u8; 32] {
-            self.state.credit(block.miner, reward);
-            self.total_issued += reward;
-        }
-
-        for tx in &block.transactions {
+        // 8. UPDATE STATE (journaled so the block can be undone)
+        let undo = self.begin_undo();
+        for tx in block.user_transactions() {
             if self.state.apply_tx(tx).is_err() {
-                // This shouldn't happen since we validated above
+                // e.g. a nonce gap; leave the chain exactly as it was
+                let mut undo = undo;
+                undo.state = self.state.take_journal();
+                self.apply_undo(undo);
+                self.blocks.pop();
+                self.seen_hashes.remove(&block_hash);
                 return Err("Transaction application failed");
             }
         }
+        self.pay_coinbase(&block, fees);
+        self.index_block(&block);
 
         // 9. ADJUST DIFFICULTY
         self.adjust_difficulty(elapsed);
+        self.push_undo(undo);
+        self.advance_finality();
+
+        Ok(())
+    }
+
+    /// Signed checkpoint of the current tip
+    pub fn checkpoint(&self, key: &SigningKey) -> Checkpoint {
+        let tip = self.blocks.last().unwrap();
+        Checkpoint::sign(tip.slot, tip.hash(), self.state.state_root(), self.difficulty, key)
+    }
+
+    /// Fast sync from genesis using a trusted checkpoint.
+    ///
+    /// Blocks up to `checkpoint.height` are only checked for parent/slot linkage;
+    /// VDF, PoW and transaction validation are skipped and the state is replayed
+    /// directly, then compared against the checkpoint's block hash and state root.
+    /// Blocks after the checkpoint go through full `add_block` validation.
+    /// On any failure the chain is reset to genesis.
+    pub fn fast_sync_from_checkpoint(
+        &mut self,
+        checkpoint: &Checkpoint,
+        trusted_signers: &[[u8; 32]],
+        blocks: Vec<Block>,
+    ) -> Result<(), &'static str> {
+        let records = blocks.into_iter().map(StoredBlock::new).collect();
+        self.sync_from_trusted(checkpoint, trusted_signers, records, None)
+    }
+
+    /// Snap sync from genesis: like `fast_sync_from_checkpoint`, but the
+    /// blocks up to the checkpoint are not replayed at all. `state`, assembled
+    /// from a peer's snapshot, is installed in their place once it matches
+    /// the checkpoint's state root; issued and burned totals are derived from
+    /// the blocks' rewards and the state's balances.
+    pub fn sync_from_snapshot(
+        &mut self,
+        checkpoint: &Checkpoint,
+        trusted_signers: &[[u8; 32]],
+        state: State,
+        blocks: Vec<Block>,
+    ) -> Result<(), &'static str> {
+        let records = blocks.into_iter().map(StoredBlock::new).collect();
+        self.sync_from_trusted(checkpoint, trusted_signers, records, Some(state))
+    }
+
+    /// Header sync from genesis: like `sync_from_snapshot`, but blocks
+    /// below the checkpoint may be headers whose bodies were pruned. Their
+    /// linkage is checked through the hashes recorded with them, ending at
+    /// the checkpoint's block, which must be whole. Blocks after it are
+    /// validated in full.
+    pub fn sync_from_headers(
+        &mut self,
+        checkpoint: &Checkpoint,
+        trusted_signers: &[[u8; 32]],
+        state: State,
+        records: Vec<StoredBlock>,
+    ) -> Result<(), &'static str> {
+        self.sync_from_trusted(checkpoint, trusted_signers, records, Some(state))
+    }
+
+    /// Restore a chain saved in the block log, starting from genesis.
+    /// A pruned log is header-synced to the checkpoint of the saved
+    /// `snapshot`, which must cover every pruned block; a whole one is
+    /// fast-synced from the latest trusted checkpoint, or replayed in full
+    /// if there is none or it fails. Returns the height of the checkpoint
+    /// used, if any.
+    pub fn restore(
+        &mut self,
+        records: Vec<StoredBlock>,
+        checkpoints: &[Checkpoint],
+        trusted_signers: &[[u8; 32]],
+        snapshot: Option<&Snapshot>,
+    ) -> Result<Option<u64>, &'static str> {
+        let tip = records.len().saturating_sub(1) as u64;
+        if let Some(deepest) = records.iter().filter(|r| r.pruned).map(|r| r.block.slot).max() {
+            let snapshot = snapshot.ok_or("Pruned log needs a state snapshot")?;
+            let checkpoint = &snapshot.manifest.checkpoint;
+            if checkpoint.height < deepest || checkpoint.height > tip {
+                return Err("Snapshot does not cover the pruned blocks");
+            }
+            let state = snapshot.state().map_err(|_| "Snapshot does not match its checkpoint")?;
+            self.sync_from_headers(checkpoint, trusted_signers, state, records)?;
+            return Ok(Some(checkpoint.height));
+        }
+
+        let blocks: Vec<Block> = records.into_iter().map(|r| r.block).collect();
+        if let Some(checkpoint) = latest_trusted(checkpoints, trusted_signers, tip) {
+            if self.fast_sync_from_checkpoint(checkpoint, trusted_signers, blocks.clone()).is_ok() {
+                return Ok(Some(checkpoint.height));
+            }
+        }
+        for block in blocks.into_iter().skip_while(|b| b.slot == 0) {
+            if self.add_block(block, self.block_time).is_err() {
+                break;
+            }
+        }
+        Ok(None)
+    }
+
+    /// Resume a whole saved chain from the account index persisted with it
+    /// (`storage::save_state`) instead of replaying every block. The blocks
+    /// are only checked for linkage, as below a checkpoint, and `index` must
+    /// be the state at their tip. On failure the chain is reset to genesis.
+    pub fn resume(&mut self, records: Vec<StoredBlock>, index: StateIndex) -> Result<(), &'static str> {
+        if self.blocks.len() != 1 {
+            return Err("Resume must start from genesis");
+        }
+        let result = self.resume_from_index(records, index);
+        if result.is_err() {
+            self.reset_to_genesis();
+        }
+        result
+    }
+
+    fn resume_from_index(&mut self, records: Vec<StoredBlock>, index: StateIndex) -> Result<(), &'static str> {
+        let tip = records.last().ok_or("No saved blocks")?;
+        if tip.block.slot != index.height || tip.hash != index.block_hash {
+            return Err("State index is not at the saved tip");
+        }
+        let mut last_hash = self.blocks[0].hash();
+        for record in records.into_iter().skip_while(|r| r.block.slot == 0) {
+            if record.pruned {
+                return Err("Pruned log needs a state snapshot");
+            }
+            if !record.body_matches() {
+                return Err("Block does not match its recorded hash");
+            }
+            if record.block.parent != last_hash {
+                return Err("Invalid parent hash");
+            }
+            if record.block.slot != self.blocks.len() as u64 {
+                return Err("Invalid block slot");
+            }
+            last_hash = record.hash;
+            self.seen_hashes.insert(record.block.calculate_hash());
+            self.blocks.push(record.block);
+        }
+        self.install_snapshot(index.state)?;
+        self.advance_finality();
+        Ok(())
+    }
+
+    fn sync_from_trusted(
+        &mut self,
+        checkpoint: &Checkpoint,
+        trusted_signers: &[[u8; 32]],
+        records: Vec<StoredBlock>,
+        snapshot: Option<State>,
+    ) -> Result<(), &'static str> {
+        if self.blocks.len() != 1 {
+            return Err("Fast sync must start from genesis");
+        }
+        if !checkpoint.is_trusted(trusted_signers) {
+            return Err("Untrusted checkpoint");
+        }
+
+        let result = self.sync_through_checkpoint(checkpoint, records, snapshot);
+        if result.is_err() {
+            self.reset_to_genesis();
+        }
+        result
+    }
+
+    fn reset_to_genesis(&mut self) {
+        self.blocks.truncate(1);
+        self.seen_hashes.clear();
+        self.difficulty = self.initial_difficulty;
+        self.finalized_height = 0;
+        self.base = None;
+        self.rebuild_state();
+    }
+
+    fn sync_through_checkpoint(
+        &mut self,
+        checkpoint: &Checkpoint,
+        records: Vec<StoredBlock>,
+        snapshot: Option<State>,
+    ) -> Result<(), &'static str> {
+        let mut records = records.into_iter().skip_while(|r| r.block.slot == 0);
+
+        // Pre-checkpoint: linkage only, through the recorded hashes
+        let mut last_hash = self.blocks[0].hash();
+        let mut pruned = false;
+        while (self.blocks.len() as u64) <= checkpoint.height {
+            let record = records.next().ok_or("Missing blocks below checkpoint")?;
+            if !record.body_matches() {
+                return Err("Block does not match its recorded hash");
+            }
+            if record.block.parent != last_hash {
+                return Err("Invalid parent hash");
+            }
+            if record.block.slot != self.blocks.len() as u64 {
+                return Err("Invalid block slot");
+            }
+            pruned |= record.pruned;
+            last_hash = record.hash;
+            self.seen_hashes.insert(record.block.calculate_hash());
+            self.blocks.push(record.block);
+        }
+
+        if last_hash != checkpoint.block_hash {
+            return Err("Checkpoint block hash mismatch");
+        }
+        if self.blocks.last().unwrap().hash() != last_hash {
+            return Err("Checkpoint block is pruned");
+        }
+        match snapshot {
+            Some(state) => self.install_snapshot(state)?,
+            None => self.rebuild_state(),
+        }
+        if self.state.state_root() != checkpoint.state_root {
+            return Err("Checkpoint state root mismatch");
+        }
+        self.difficulty = checkpoint.difficulty;
+        self.finalized_height = checkpoint.height;
+        if pruned {
+            self.base = Some(StateBase {
+                height: checkpoint.height,
+                state: self.state.clone(),
+                total_issued: self.total_issued,
+                total_burned: self.total_burned,
+            });
+        }
 
+        // Post-checkpoint: full validation
+        for record in records {
+            if record.pruned {
+                return Err("Pruned block above checkpoint");
+            }
+            self.add_block(record.block, self.block_time)?;
+        }
         Ok(())
     }
 
+    /// Take `state` as the state at the tip without replaying any block.
+    /// The totals replay would have accumulated follow from the blocks'
+    /// rewards and the balances: every coin allocated or issued is either
+    /// held or burned.
+    fn install_snapshot(&mut self, state: State) -> Result<(), &'static str> {
+        let issued = self.blocks[1..]
+            .iter()
+            .filter(|b| b.miner != [0u8; 32])
+            .fold(0u64, |acc, b| acc.saturating_add(economics::block_reward(b.slot, acc)));
+        let created = issued as u128 + self.premine() as u128;
+        let burned = created.checked_sub(state.total_balance()).ok_or("Snapshot holds more than was issued")?;
+
+        self.state = state;
+        self.total_issued = issued;
+        self.total_burned = u64::try_from(burned).map_err(|_| "Snapshot burn total overflows")?;
+        self.undo_log.clear();
+        let blocks = std::mem::take(&mut self.blocks);
+        for block in &blocks {
+            self.index_block(block);
+        }
+        self.blocks = blocks;
+        Ok(())
+    }
+
+    /// Headers of the last `LWMA_WINDOW + 1` blocks, oldest first, once the
+    /// chain has that many and targets LWMA's block time. `None` while the
+    /// per-block adjustment applies.
+    fn lwma_window(&self) -> Option<Vec<lwma::BlockHeader>> {
+        if self.block_time != lwma::TARGET_BLOCK_TIME || self.blocks.len() <= lwma::LWMA_WINDOW {
+            return None;
+        }
+        let start = self.blocks.len() - (lwma::LWMA_WINDOW + 1);
+        let headers = self.blocks[start..]
+            .iter()
+            .map(|block| lwma::BlockHeader::new(block.timestamp, block.difficulty).with_height(block.slot))
+            .collect();
+        Some(headers)
+    }
+
+    /// Whether the next block may claim `difficulty`: what LWMA prescribes
+    /// for the window behind it, within its tolerance, or before there is
+    /// a window exactly the current difficulty
+    fn accepts_difficulty(&self, difficulty: u64) -> bool {
+        match self.lwma_window() {
+            Some(window) => u32::try_from(difficulty).is_ok_and(|claimed| lwma::validate_next_difficulty(&window, claimed)),
+            None => difficulty == self.difficulty,
+        }
+    }
+
     /// Adjust difficulty based on block time
     fn adjust_difficulty(&mut self, elapsed: u64) {
+        if let Some(window) = self.lwma_window() {
+            self.difficulty = lwma::calculate_lwma_difficulty(&window).to_u64().unwrap_or(lwma::MAX_DIFFICULTY);
+            return;
+        }
         // Simple difficulty adjustment
-        if elapsed < TARGET_TIME {
+        if elapsed < self.block_time {
             self.difficulty = self.difficulty.saturating_add(1);
-        } else if elapsed > TARGET_TIME {
+        } else if elapsed > self.block_time {
             self.difficulty = self.difficulty.saturating_sub(1).max(1);
         }
     }
 
+    /// Check the supply invariant at the tip (see `State::audit_supply`)
+    pub fn audit_supply(&self) -> Result<(), SupplyMismatch> {
+        let height = self.blocks.len() as u64 - 1;
+        self.state.audit_supply(height, self.total_burned, self.premine())
+    }
+
+    /// Balances allocated by the genesis block
+    pub fn premine(&self) -> u64 {
+        self.blocks[0].transactions.iter().fold(0u64, |acc, tx| acc.saturating_add(tx.amount))
+    }
+
     /// Get current balance for address
     pub fn balance(&self, address: &Address) -> u64 {
         self.state.balance(address)
     }
 
-    /// Get supply information
+    /// Get supply information: mined so far, what is left to mine after
+    /// the premine, and the share of `MAX_SUPPLY` in existence
     pub fn supply_info(&self) -> (u64, u64, f64) {
         let mined = self.total_issued;
-        let remaining = MAX_SUPPLY.saturating_sub(mined);
-        let percent = (mined as f64 / MAX_SUPPLY as f64) * 100.0;
+        let existing = mined.saturating_add(self.premine());
+        let remaining = MAX_SUPPLY.saturating_sub(existing);
+        let percent = (existing as f64 / MAX_SUPPLY as f64) * 100.0;
         (mined, remaining, percent)
     }
 
+    /// Up to `limit` transactions sent or received by `address`, newest
+    /// first, skipping the `offset` newest
+    pub fn address_history(&self, address: &Address, offset: usize, limit: usize) -> Vec<(TxLocation, &Transaction)> {
+        self.state
+            .address_history(address, offset, limit)
+            .into_iter()
+            .filter_map(|loc| Some((loc, self.blocks.get(loc.height as usize)?.transactions.get(loc.index as usize)?)))
+            .collect()
+    }
+
     /// Format amount to AXM with decimals
     pub fn format_axm(amount: u64) -> String {
-        let whole = amount / 10u64.pow(DECIMALS);
-        let fractional = amount % 10u64.pow(DECIMALS);
-        format!("{}.{:08}", whole, fractional)
+        crate::amount::Amount::from_sats(amount).to_string()
+    }
+
+    /// Validate a transaction against the current state before it enters
+    /// the mempool: the chain id, the dust limit and fee rate, sender
+    /// authorization (see
+    /// [`check_transaction_authorization`](Self::check_transaction_authorization))
+    /// followed by the ZK proof.
+    pub fn validate_transaction(&self, tx: &Transaction) -> AxiomResult<()> {
+        self.check_chain_id(tx)?;
+        tx.check_dust(self.dust_limit, self.allow_zero_amount)?;
+        self.check_fee_rate(tx)?;
+        self.check_transaction_authorization(tx)?;
+        #[cfg(test)]
+        if self.skip_tx_proofs {
+            return Ok(());
+        }
+        check_transaction_proof(tx)
+    }
+
+    /// Signature, nonce and balance checks. The signature must verify
+    /// against the sender's key, the nonce must not already be used, and the
+    /// sender must hold `amount + fee`.
+    pub fn check_transaction_authorization(&self, tx: &Transaction) -> AxiomResult<()> {
+        tx.verify_signature()?;
+        self.check_nonce(tx)?;
+        self.check_funds(tx)
+    }
+
+    /// Run every check `validate_transaction` makes, without stopping at
+    /// the first failure, and work out the balance changes applying `tx`
+    /// would cause. Nothing is modified. A transaction that carries no ZK
+    /// proof yet skips the proof check, so senders can try it before
+    /// spending the time to prove it.
+    pub fn simulate_transaction(&self, tx: &Transaction) -> TransactionSimulation {
+        let mut checks = vec![
+            ("signature", tx.verify_signature()),
+            ("nonce", self.check_nonce(tx)),
+            ("balance", self.check_funds(tx)),
+            ("dust", tx.check_dust(self.dust_limit, self.allow_zero_amount)),
+            ("fee", self.check_fee_rate(tx)),
+            ("chain_id", self.check_chain_id(tx)),
+        ];
+        if !tx.zk_proof.is_empty() {
+            checks.push(("zk_proof", check_transaction_proof(tx)));
+        }
+
+        let fee = -(tx.fee as i128);
+        let balance_changes = if tx.from == tx.to {
+            vec![(tx.from, fee)]
+        } else {
+            vec![(tx.from, fee - tx.amount as i128), (tx.to, tx.amount as i128)]
+        };
+        TransactionSimulation { checks, balance_changes }
+    }
+
+    fn check_chain_id(&self, tx: &Transaction) -> AxiomResult<()> {
+        if tx.chain_id != self.network_id {
+            return Err(AxiomError::WrongChainId { expected: self.network_id, actual: tx.chain_id });
+        }
+        Ok(())
     }
 
-    /// Validate and add transaction to mempool (placeholder for now)
-    pub fn validate_transaction(&self, tx: &Transaction) -> Result<(), &'static str> {
-        let sender_balance = self.state.balance(&tx.from);
-        tx.validate(sender_balance)
+    fn check_fee_rate(&self, tx: &Transaction) -> AxiomResult<()> {
+        let min_fee = tx.min_fee(self.min_fee_per_byte);
+        if tx.fee < min_fee {
+            return Err(AxiomError::FeeTooLow { min: min_fee, actual: tx.fee });
+        }
+        Ok(())
+    }
+
+    fn check_nonce(&self, tx: &Transaction) -> AxiomResult<()> {
+        let expected = self.state.nonce(&tx.from);
+        if tx.nonce < expected {
+            return Err(AxiomError::InvalidNonce { expected, actual: tx.nonce });
+        }
+        Ok(())
+    }
+
+    fn check_funds(&self, tx: &Transaction) -> AxiomResult<()> {
+        let required = tx.amount.checked_add(tx.fee)
+            .ok_or_else(|| AxiomError::InvalidTransaction("amount + fee overflows".to_string()))?;
+        let available = self.state.balance(&tx.from);
+        if available < required {
+            return Err(AxiomError::InsufficientBalance { available, required });
+        }
+        Ok(())
+    }
+}
+
+fn check_transaction_proof(tx: &Transaction) -> AxiomResult<()> {
+    match crate::genesis::verify_transaction_proof(&tx.zk_proof, &tx.from, tx.amount, tx.fee) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(AxiomError::ProofVerificationFailed("invalid transaction proof".to_string())),
+        Err(e) => Err(AxiomError::ProofVerificationFailed(e.to_string())),
+    }
+}
+
+/// Outcome of `Timechain::simulate_transaction`
+#[derive(Debug)]
+pub struct TransactionSimulation {
+    /// Each check by name (`signature`, `nonce`, `balance`, `dust`, `fee`,
+    /// `chain_id` and, for a proven transaction, `zk_proof`) and its result
+    pub checks: Vec<(&'static str, AxiomResult<()>)>,
+    /// Signed balance change per affected address if the transaction applies
+    pub balance_changes: Vec<(Address, i128)>,
+}
+
+impl TransactionSimulation {
+    pub fn would_succeed(&self) -> bool {
+        self.checks.iter().all(|(_, result)| result.is_ok())
+    }
+
+    /// The first failed check and its error
+    pub fn failure(&self) -> Option<(&'static str, &AxiomError)> {
+        self.checks.iter().find_map(|(name, result)| result.as_ref().err().map(|e| (*name, e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genesis;
+
+    fn mine_next(chain: &Timechain, miner: Address) -> Block {
+        let parent = chain.blocks.last().unwrap().hash();
+        let slot = chain.blocks.len() as u64;
+        let vdf_proof = crate::main_helper::compute_vdf(
+            crate::vdf::evaluate(parent, slot),
+            chain.vdf_steps,
+        );
+        let coinbase = chain.coinbase_for(miner, &[]);
+        let mut block =
+            Block::new(parent, slot, miner, vec![coinbase], vdf_proof, vec![1u8; 128], 0).with_difficulty(chain.difficulty);
+        while !block.meets_difficulty() {
+            block.nonce += 1;
+        }
+        block
+    }
+
+    fn full_chain(len: usize) -> Timechain {
+        let mut chain = Timechain::new(genesis::genesis());
+        for i in 0..len {
+            let block = mine_next(&chain, [(i % 3 + 1) as u8; 32]);
+            chain.add_block(block, TARGET_TIME).unwrap();
+        }
+        chain
+    }
+
+    #[test]
+    fn test_custom_genesis_allocations_and_sync() {
+        let config = genesis::GenesisConfig {
+            timestamp: 1_800_000_000,
+            difficulty: 10,
+            message: "private testnet".to_string(),
+            allocations: vec![genesis::GenesisAllocation { address: hex::encode([5u8; 32]), amount: 1_000 }],
+        };
+        let mut chain = Timechain::with_genesis(&config).unwrap();
+        assert_eq!(chain.difficulty, 10);
+        assert_eq!(chain.state.balance(&[5u8; 32]), 1_000);
+
+        let block = mine_next(&chain, [1u8; 32]);
+        chain.add_block(block, TARGET_TIME).unwrap();
+        assert_eq!(chain.state.balance(&[5u8; 32]), 1_000);
+
+        // A chain from another genesis cannot extend this one
+        let mut mainnet = Timechain::new(genesis::genesis());
+        assert!(mainnet.add_block(chain.blocks[1].clone(), TARGET_TIME).is_err());
+
+        let fresh = chain.genesis_only();
+        assert_eq!(fresh.blocks.len(), 1);
+        assert_eq!(fresh.blocks[0].hash(), chain.blocks[0].hash());
+        assert_eq!(fresh.difficulty, 10);
+    }
+
+    #[test]
+    fn test_configured_block_time_drives_retargeting() {
+        let mut chain = Timechain::new(genesis::genesis()).with_block_time(600);
+        let start = chain.difficulty;
+
+        // On target: unchanged
+        chain.add_block(mine_next(&chain, [1u8; 32]), 600).unwrap();
+        assert_eq!(chain.difficulty, start);
+
+        // 900s is slow against a 600s target, though fast against the default
+        chain.add_block(mine_next(&chain, [1u8; 32]), 900).unwrap();
+        assert_eq!(chain.difficulty, start - 1);
+        chain.add_block(mine_next(&chain, [1u8; 32]), 300).unwrap();
+        assert_eq!(chain.difficulty, start);
+
+        // A replay keeps the target, so the rebuilt chain accepts the same blocks
+        let mut replay = chain.genesis_only();
+        assert_eq!(replay.block_time, 600);
+        let block = mine_next(&replay, [1u8; 32]);
+        assert!(replay.add_block(block, replay.block_time).is_ok());
+        assert_eq!(replay.difficulty, start);
+    }
+
+    #[test]
+    fn test_undo_last_block_restores_state() {
+        let mut chain = full_chain(3);
+        let before = (chain.state.state_root(), chain.total_issued, chain.difficulty);
+        let miner = [9u8; 32];
+
+        let block = mine_next(&chain, miner);
+        chain.add_block(block.clone(), 60).unwrap();
+        assert!(chain.balance(&miner) > 0);
+        assert_ne!(chain.difficulty, before.2);
+
+        // A reorg takes the block back off: balances, supply and difficulty revert
+        assert_eq!(chain.undo_last_block().unwrap().hash(), block.hash());
+        assert_eq!(chain.balance(&miner), 0);
+        assert_eq!((chain.state.state_root(), chain.total_issued, chain.difficulty), before);
+        // ... and the same block can be applied again
+        chain.add_block(block, 60).unwrap();
+
+        // Blocks restored by a replay are undone through the same log
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        let sender = key.verifying_key().to_bytes();
+        let config = genesis::GenesisConfig {
+            timestamp: 1_800_000_000,
+            difficulty: 10,
+            message: "undo".to_string(),
+            allocations: vec![genesis::GenesisAllocation { address: hex::encode(sender), amount: 1_000 }],
+        };
+        let mut replayed = Timechain::with_genesis(&config).unwrap();
+        let parent = replayed.blocks[0].hash();
+        replayed.blocks.push(Block::new(parent, 1, [4u8; 32], vec![signed_tx(&key, 300, 10, 0)], [0u8; 32], vec![1u8; 128], 0));
+        replayed.rebuild_state();
+        assert_eq!(replayed.balance(&sender), 690);
+        assert_eq!(replayed.balance(&[2u8; 32]), 300);
+
+        replayed.undo_last_block().unwrap();
+        assert_eq!(replayed.balance(&sender), 1_000);
+        assert_eq!(replayed.balance(&[2u8; 32]), 0);
+        assert_eq!(replayed.state.nonce(&sender), 0);
+        assert_eq!(replayed.total_issued, 0);
+        assert!(replayed.undo_last_block().is_none());
+    }
+
+    #[test]
+    fn test_audit_supply_on_chain() {
+        let mut chain = full_chain(3);
+        assert_eq!(chain.audit_supply(), Ok(()));
+
+        // An extra-credited balance is inflation of exactly that amount
+        chain.state.credit([1u8; 32], 12_345);
+        let mismatch = chain.audit_supply().unwrap_err();
+        assert_eq!(mismatch.height, 3);
+        assert_eq!(mismatch.delta, 12_345);
+
+        // Genesis allocations count toward the expected supply
+        let config = genesis::GenesisConfig {
+            timestamp: 1_800_000_000,
+            difficulty: 10,
+            message: "audit".to_string(),
+            allocations: vec![genesis::GenesisAllocation { address: hex::encode([5u8; 32]), amount: 1_000 }],
+        };
+        let mut custom = Timechain::with_genesis(&config).unwrap();
+        custom.add_block(mine_next(&custom, [1u8; 32]), TARGET_TIME).unwrap();
+        assert_eq!(custom.audit_supply(), Ok(()));
+    }
+
+    #[test]
+    fn test_premine_in_genesis_state_and_audit() {
+        let allocations = [([5u8; 32], 2_000_000), ([6u8; 32], 500)];
+        let config = genesis::GenesisConfig {
+            timestamp: 1_800_000_000,
+            difficulty: 10,
+            message: "premine".to_string(),
+            allocations: allocations
+                .iter()
+                .map(|(address, amount)| genesis::GenesisAllocation { address: hex::encode(address), amount: *amount })
+                .collect(),
+        };
+        let mut chain = Timechain::with_genesis(&config).unwrap();
+        assert_eq!(chain.premine(), 2_000_500);
+        for (address, amount) in allocations {
+            assert_eq!(chain.balance(&address), amount);
+        }
+        assert_eq!(chain.state.total_balance(), 2_000_500);
+        assert_eq!(chain.audit_supply(), Ok(()));
+
+        chain.add_block(mine_next(&chain, [1u8; 32]), TARGET_TIME).unwrap();
+        assert_eq!(chain.audit_supply(), Ok(()));
+        assert_eq!(
+            chain.state.total_balance(),
+            economics::calculate_total_supply_with_premine(2, chain.premine()) as u128 - economics::get_mining_reward(0) as u128
+        );
+        let (mined, remaining, _) = chain.supply_info();
+        assert_eq!(mined, economics::get_mining_reward(1));
+        assert_eq!(remaining, MAX_SUPPLY - mined - 2_000_500);
+
+        // Coins credited outside the genesis block are still caught
+        chain.state.credit([6u8; 32], 1);
+        assert_eq!(chain.audit_supply().unwrap_err().delta, 1);
+    }
+
+    #[test]
+    fn test_fast_sync_matches_full_replay() {
+        let full = full_chain(6);
+        let key = SigningKey::from_bytes(&[5u8; 32]);
+        let signer = key.verifying_key().to_bytes();
+
+        // Checkpoint taken by a node that had synced to height 3
+        let mut at_three = Timechain::new(genesis::genesis());
+        for block in &full.blocks[1..=3] {
+            at_three.add_block(block.clone(), TARGET_TIME).unwrap();
+        }
+        let checkpoint = at_three.checkpoint(&key);
+        assert_eq!(checkpoint.height, 3);
+
+        let mut synced = Timechain::new(genesis::genesis());
+        synced
+            .fast_sync_from_checkpoint(&checkpoint, &[signer], full.blocks.clone())
+            .unwrap();
+
+        assert_eq!(synced.blocks.len(), full.blocks.len());
+        assert_eq!(synced.blocks.last().unwrap().hash(), full.blocks.last().unwrap().hash());
+        assert_eq!(synced.state.state_root(), full.state.state_root());
+        assert_eq!(synced.total_issued, full.total_issued);
+        assert_eq!(synced.difficulty, full.difficulty);
+        assert_eq!(synced.finalized_height, 3);
+
+        // Synced node keeps extending normally
+        let next = mine_next(&synced, [9u8; 32]);
+        assert!(synced.add_block(next, TARGET_TIME).is_ok());
+    }
+
+    #[test]
+    fn test_fast_sync_rejects_bad_checkpoints() {
+        let full = full_chain(4);
+        let key = SigningKey::from_bytes(&[5u8; 32]);
+        let signer = key.verifying_key().to_bytes();
+        let tip = full.blocks.last().unwrap();
+
+        // Untrusted signer
+        let mut chain = Timechain::new(genesis::genesis());
+        let checkpoint = full.checkpoint(&key);
+        assert!(chain
+            .fast_sync_from_checkpoint(&checkpoint, &[[1u8; 32]], full.blocks.clone())
+            .is_err());
+
+        // Wrong state root: chain is reset to genesis
+        let bad_root = Checkpoint::sign(tip.slot, tip.hash(), [0u8; 32], full.difficulty, &key);
+        assert_eq!(
+            chain.fast_sync_from_checkpoint(&bad_root, &[signer], full.blocks.clone()),
+            Err("Checkpoint state root mismatch")
+        );
+        assert_eq!(chain.blocks.len(), 1);
+        assert_eq!(chain.total_issued, 0);
+
+        // Wrong block hash
+        let bad_hash = Checkpoint::sign(tip.slot, [7u8; 32], full.state.state_root(), full.difficulty, &key);
+        assert!(chain
+            .fast_sync_from_checkpoint(&bad_hash, &[signer], full.blocks.clone())
+            .is_err());
+
+        // Too few blocks to reach the checkpoint
+        assert_eq!(
+            chain.fast_sync_from_checkpoint(&checkpoint, &[signer], full.blocks[..2].to_vec()),
+            Err("Missing blocks below checkpoint")
+        );
+    }
+
+    #[test]
+    fn test_snap_sync_matches_full_replay() {
+        use crate::snapshot::{Snapshot, SnapshotAssembler};
+
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        let sender = key.verifying_key().to_bytes();
+        let config = genesis::GenesisConfig {
+            timestamp: 1_800_000_000,
+            difficulty: 10,
+            message: "snap sync".to_string(),
+            allocations: vec![genesis::GenesisAllocation { address: hex::encode(sender), amount: 10_000 }],
+        };
+        let new_chain = || Timechain::with_genesis(&config).unwrap().with_fee_burn_fraction(0.5);
+        let checkpoint_key = SigningKey::from_bytes(&[5u8; 32]);
+        let signer = checkpoint_key.verifying_key().to_bytes();
+
+        // Four coinbase-only blocks from three miners, as transfers without
+        // a ZK proof cannot be mined; the serving node snapshots at height 3
+        let mut full = new_chain();
+        let mut snapshot = None;
+        for i in 0..4u8 {
+            let miner = [i % 3 + 1; 32];
+            let coinbase = full.coinbase_for(miner, &[]);
+            let block = with_transactions(mine_next(&full, miner), vec![coinbase]);
+            full.add_block(block, TARGET_TIME).unwrap();
+            if full.blocks.len() == 4 {
+                snapshot = Some(Snapshot::new(full.checkpoint(&checkpoint_key), &full.state));
+            }
+        }
+        let snapshot = snapshot.unwrap();
+        let checkpoint = snapshot.manifest.checkpoint.clone();
+        assert_eq!(checkpoint.height, 3);
+
+        let mut assembler = SnapshotAssembler::new(snapshot.manifest.clone(), &[signer]).unwrap();
+        for index in assembler.missing() {
+            let (chunk, proof) = snapshot.chunk(index).unwrap();
+            assembler.add_chunk(chunk, &proof).unwrap();
+        }
+        let state = assembler.into_state().unwrap();
+
+        // Snapshot plus the one later block lands where full replay did
+        let mut synced = new_chain();
+        synced.sync_from_snapshot(&checkpoint, &[signer], state.clone(), full.blocks.clone()).unwrap();
+        assert_eq!(synced.blocks.len(), 5);
+        assert_eq!(synced.state.state_root(), full.state.state_root());
+        assert_eq!(synced.total_issued, full.total_issued);
+        assert_eq!(synced.total_burned, full.total_burned);
+        assert_eq!(synced.difficulty, full.difficulty);
+        assert_eq!(synced.finalized_height, 3);
+        assert_eq!(synced.audit_supply(), Ok(()));
+        assert_eq!(synced.address_history(&[1u8; 32], 0, 10), full.address_history(&[1u8; 32], 0, 10));
+        assert_eq!(synced.address_history(&[1u8; 32], 0, 10).len(), 2);
+
+        // A state that misses the checkpoint's root is refused and the chain reset
+        let mut tampered = state;
+        tampered.debit(sender, 1).unwrap();
+        tampered.credit([1u8; 32], 1);
+        let mut rejected = new_chain();
+        assert_eq!(
+            rejected.sync_from_snapshot(&checkpoint, &[signer], tampered, full.blocks.clone()),
+            Err("Checkpoint state root mismatch")
+        );
+        assert_eq!(rejected.blocks.len(), 1);
+        assert_eq!(rejected.state.balance(&sender), 10_000);
+        assert_eq!(rejected.total_burned, 0);
+    }
+
+    #[test]
+    fn test_header_sync_matches_full_replay() {
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        let sender = key.verifying_key().to_bytes();
+        let config = genesis::GenesisConfig {
+            timestamp: 1_800_000_000,
+            difficulty: 10,
+            message: "header sync".to_string(),
+            allocations: vec![genesis::GenesisAllocation { address: hex::encode(sender), amount: 10_000 }],
+        };
+        let new_chain = || Timechain::with_genesis(&config).unwrap();
+        let checkpoint_key = SigningKey::from_bytes(&[5u8; 32]);
+        let signer = checkpoint_key.verifying_key().to_bytes();
+
+        // Coinbase-only blocks: transfers without a ZK proof cannot be mined
+        let mut full = new_chain();
+        let mut at_checkpoint = None;
+        for i in 0..5u8 {
+            let miner = [i % 3 + 1; 32];
+            let coinbase = full.coinbase_for(miner, &[]);
+            let block = with_transactions(mine_next(&full, miner), vec![coinbase]);
+            full.add_block(block, TARGET_TIME).unwrap();
+            if full.blocks.len() == 4 {
+                at_checkpoint = Some((full.checkpoint(&checkpoint_key), full.state.clone()));
+            }
+        }
+        let (checkpoint, state) = at_checkpoint.unwrap();
+
+        // Bodies below the checkpoint are gone; the checkpoint block and
+        // everything after it are whole
+        let records = |pruned_below: u64| -> Vec<StoredBlock> {
+            full.blocks
+                .iter()
+                .cloned()
+                .map(|block| {
+                    let mut record = StoredBlock::new(block);
+                    if record.block.slot > 0 && record.block.slot < pruned_below {
+                        record.block.transactions.clear();
+                        record.pruned = true;
+                    }
+                    record
+                })
+                .collect()
+        };
+
+        let mut synced = new_chain();
+        synced.sync_from_headers(&checkpoint, &[signer], state.clone(), records(3)).unwrap();
+        assert_eq!(synced.blocks.len(), full.blocks.len());
+        assert_eq!(synced.blocks.last().unwrap().hash(), full.blocks.last().unwrap().hash());
+        assert_eq!(synced.state.state_root(), full.state.state_root());
+        assert_eq!((synced.total_issued, synced.difficulty), (full.total_issued, full.difficulty));
+        assert_eq!(synced.finalized_height, 3);
+
+        // Replaying without the pruned bodies starts from the checkpoint
+        synced.rebuild_state();
+        assert_eq!(synced.state.state_root(), full.state.state_root());
+        assert_eq!(synced.rollback_to(3).unwrap().len(), 2);
+        assert_eq!(synced.state.state_root(), state.state_root());
+
+        // The checkpoint block itself must keep its body
+        let mut rejected = new_chain();
+        assert_eq!(
+            rejected.sync_from_headers(&checkpoint, &[signer], state.clone(), records(4)),
+            Err("Checkpoint block is pruned")
+        );
+        assert_eq!(rejected.blocks.len(), 1);
+
+        // A header whose body was altered, or a pruned header with a forged
+        // hash, breaks the linkage to the checkpoint
+        let mut altered = records(3);
+        altered[3].block.transactions.pop();
+        assert_eq!(
+            rejected.sync_from_headers(&checkpoint, &[signer], state.clone(), altered),
+            Err("Block does not match its recorded hash")
+        );
+        let mut forged = records(3);
+        forged[1].hash = [1u8; 32];
+        assert_eq!(
+            rejected.sync_from_headers(&checkpoint, &[signer], state, forged),
+            Err("Invalid parent hash")
+        );
+    }
+
+    #[test]
+    fn test_restore_from_pruned_log() {
+        use crate::config::PruningMode;
+        use crate::storage::{prune_depth, BlockStore};
+
+        let config = genesis::GenesisConfig {
+            timestamp: 1_800_000_000,
+            difficulty: 10,
+            message: "restore".to_string(),
+            allocations: vec![genesis::GenesisAllocation { address: hex::encode([9u8; 32]), amount: 10_000 }],
+        };
+        let new_chain = || Timechain::with_genesis(&config).unwrap();
+        let checkpoint_key = SigningKey::from_bytes(&[5u8; 32]);
+        let signer = checkpoint_key.verifying_key().to_bytes();
+
+        // Six blocks from three miners, snapshotted at height 4 as the node
+        // does at checkpoints
+        let mut full = new_chain();
+        let mut snapshot = None;
+        for i in 0..6u8 {
+            let miner = [i % 3 + 1; 32];
+            let coinbase = full.coinbase_for(miner, &[]);
+            let block = with_transactions(mine_next(&full, miner), vec![coinbase]);
+            full.add_block(block, TARGET_TIME).unwrap();
+            if full.blocks.len() == 5 {
+                snapshot = Some(Snapshot::new(full.checkpoint(&checkpoint_key), &full.state));
+            }
+        }
+        let snapshot = snapshot.unwrap();
+        let checkpoint = snapshot.manifest.checkpoint.clone();
+
+        let path = std::env::temp_dir().join(format!("axiom_restore_{}.log", std::process::id()));
+        let store = BlockStore::open(&path);
+        store.write_all(&full.blocks).unwrap();
+
+        // A whole log fast-syncs from the checkpoint, or replays without one
+        let mut restored = new_chain();
+        assert_eq!(restored.restore(store.load().unwrap(), std::slice::from_ref(&checkpoint), &[signer], None), Ok(Some(4)));
+        assert_eq!(restored.state.state_root(), full.state.state_root());
+        let mut replayed = new_chain();
+        assert_eq!(replayed.restore(store.load().unwrap(), &[], &[signer], None), Ok(None));
+        assert_eq!(replayed.blocks.len(), full.blocks.len());
+        assert_eq!(replayed.state.state_root(), full.state.state_root());
+
+        // Pruning stops at the snapshot's checkpoint
+        let tip = full.blocks.len() as u64 - 1;
+        assert_eq!(store.prune(PruningMode::Full, prune_depth(tip, 1, Some(checkpoint.height))).unwrap(), 4);
+        let records = store.load().unwrap();
+        assert!(records[3].pruned && !records[4].pruned);
+
+        // The pruned log restarts from the snapshot, not from the bodies
+        let mut restored = new_chain();
+        assert_eq!(restored.restore(records.clone(), &[], &[signer], Some(&snapshot)), Ok(Some(4)));
+        assert_eq!(restored.blocks.len(), full.blocks.len());
+        assert_eq!(restored.blocks.last().unwrap().hash(), full.blocks.last().unwrap().hash());
+        assert_eq!(restored.state.state_root(), full.state.state_root());
+        assert_eq!((restored.total_issued, restored.total_burned), (full.total_issued, full.total_burned));
+        assert_eq!(restored.finalized_height, 4);
+        assert_eq!(restored.common_ancestor(&full.blocks), Some(tip));
+
+        // ... and saving it again keeps the recorded hashes of the headers
+        store.write_all(&restored.blocks).unwrap();
+        assert_eq!(store.verify_headers().unwrap(), tip);
+
+        // Without a snapshot, or with one that leaves pruned blocks above
+        // its checkpoint, a pruned log cannot be restored
+        let mut rejected = new_chain();
+        assert_eq!(rejected.restore(records.clone(), &[], &[signer], None), Err("Pruned log needs a state snapshot"));
+        let early = Snapshot::new(Checkpoint::sign(2, full.blocks[2].hash(), [0u8; 32], 10, &checkpoint_key), &State::new());
+        assert_eq!(
+            rejected.restore(records, &[], &[signer], Some(&early)),
+            Err("Snapshot does not cover the pruned blocks")
+        );
+        assert_eq!(rejected.blocks.len(), 1);
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(path.with_extension("idx"));
+    }
+
+    #[test]
+    fn test_resume_from_state_index() {
+        let mut full = Timechain::new(genesis::genesis());
+        for i in 0..5u8 {
+            let miner = [i % 3 + 1; 32];
+            let coinbase = full.coinbase_for(miner, &[]);
+            let block = with_transactions(mine_next(&full, miner), vec![coinbase]);
+            full.add_block(block, TARGET_TIME).unwrap();
+        }
+        let records = || full.blocks.iter().cloned().map(StoredBlock::new).collect::<Vec<_>>();
+        // As read back from the state file, without the history
+        let saved = |height: usize| StateIndex {
+            height: height as u64,
+            block_hash: full.blocks[height].hash(),
+            state: bincode::deserialize(&bincode::serialize(&full.state).unwrap()).unwrap(),
+        };
+        let tip = full.blocks.len() - 1;
+
+        let mut resumed = Timechain::new(genesis::genesis()).with_confirmation_depth(2);
+        resumed.resume(records(), saved(tip)).unwrap();
+        assert_eq!(resumed.blocks.len(), full.blocks.len());
+        assert_eq!(resumed.state.state_root(), full.state.state_root());
+        assert_eq!((resumed.total_issued, resumed.total_burned), (full.total_issued, full.total_burned));
+        assert_eq!(resumed.address_history(&[1u8; 32], 0, 10), full.address_history(&[1u8; 32], 0, 10));
+        assert_eq!(resumed.finalized_height, tip as u64 - 2);
+        let next = mine_next(&resumed, [9u8; 32]);
+        assert!(resumed.add_block(next, TARGET_TIME).is_ok());
+
+        // An index saved at another height is not the state at the tip
+        let mut stale = Timechain::new(genesis::genesis());
+        assert_eq!(stale.resume(records(), saved(tip - 1)), Err("State index is not at the saved tip"));
+        assert_eq!(stale.blocks.len(), 1);
+        assert_eq!(stale.total_issued, 0);
+    }
+
+    fn signed_tx(key: &SigningKey, amount: u64, fee: u64, nonce: u64) -> Transaction {
+        signed_tx_for(crate::config::MAINNET_NETWORK_ID, key, amount, fee, nonce)
+    }
+
+    fn signed_tx_for(chain_id: u8, key: &SigningKey, amount: u64, fee: u64, nonce: u64) -> Transaction {
+        use ed25519_dalek::Signer;
+        let mut tx = Transaction::new(key.verifying_key().to_bytes(), [2u8; 32], amount, fee, nonce, vec![], vec![])
+            .with_chain_id(chain_id);
+        tx.signature = key.sign(&tx.signing_message()).to_bytes().to_vec();
+        tx
+    }
+
+    #[test]
+    fn test_rollback_then_reapply_restores_tip() {
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        let mut chain = full_chain(2).with_fee_burn_fraction(0.5).without_tx_proofs();
+        chain.state.credit(key.verifying_key().to_bytes(), 1_000);
+        let snapshot = |c: &Timechain| (c.state.state_root(), c.total_issued, c.total_burned, c.difficulty);
+        let at_two = snapshot(&chain);
+
+        // Three blocks on top, two of them carrying transfers
+        let miner = [7u8; 32];
+        let batches = [
+            vec![signed_tx(&key, 100, 10, 0), signed_tx(&key, 100, 10, 1)],
+            vec![],
+            vec![signed_tx(&key, 50, 4, 2)],
+        ];
+        for txs in batches {
+            let mut block_txs = vec![chain.coinbase_for(miner, &txs)];
+            block_txs.extend(txs);
+            let block = with_transactions(mine_next(&chain, miner), block_txs);
+            chain.add_block(block, 60).unwrap();
+        }
+        let tip = snapshot(&chain);
+        let tip_blocks = chain.blocks[3..].to_vec();
+
+        let removed = chain.rollback_to(2).unwrap();
+        let hashes = |blocks: &[Block]| blocks.iter().map(Block::hash).collect::<Vec<_>>();
+        assert_eq!(hashes(&removed), hashes(&tip_blocks));
+        assert_eq!(chain.blocks.len(), 3);
+        assert_eq!(snapshot(&chain), at_two);
+        assert_eq!(chain.state.nonce(&key.verifying_key().to_bytes()), 0);
+
+        // Their transfers go back to the mempool, coinbases do not
+        let mut mempool = crate::mempool::Mempool::new();
+        for tx in removed.iter().flat_map(|block| block.user_transactions()) {
+            mempool.add(tx.clone(), &chain.state).unwrap();
+        }
+        assert_eq!(mempool.len(), 3);
+        assert!(removed.iter().flat_map(|block| block.user_transactions()).all(|tx| mempool.contains(&tx.hash())));
+
+        // Re-applying the same blocks lands on the same tip
+        for block in removed {
+            chain.add_block(block, 60).unwrap();
+        }
+        assert_eq!(snapshot(&chain), tip);
+    }
+
+    #[test]
+    fn test_address_history_newest_first_and_undone() {
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        let sender = key.verifying_key().to_bytes();
+        let mut chain = full_chain(1).without_tx_proofs();
+        chain.state.credit(sender, 1_000);
+
+        let miner = [7u8; 32];
+        let batches = [
+            vec![signed_tx(&key, 100, 10, 0), signed_tx(&key, 100, 10, 1)],
+            vec![],
+            vec![signed_tx(&key, 50, 4, 2)],
+        ];
+        for txs in batches {
+            let mut block_txs = vec![chain.coinbase_for(miner, &txs)];
+            block_txs.extend(txs);
+            let block = with_transactions(mine_next(&chain, miner), block_txs);
+            chain.add_block(block, TARGET_TIME).unwrap();
+        }
+
+        let nonces =
+            |history: Vec<(TxLocation, &Transaction)>| history.iter().map(|(_, tx)| tx.nonce).collect::<Vec<_>>();
+        assert_eq!(chain.state.history_len(&sender), 3);
+        assert_eq!(nonces(chain.address_history(&sender, 0, 10)), vec![2, 1, 0]);
+        assert_eq!(nonces(chain.address_history(&sender, 1, 1)), vec![1]);
+        assert_eq!(nonces(chain.address_history(&sender, 2, 10)), vec![0]);
+        assert!(chain.address_history(&sender, 3, 10).is_empty());
+        assert_eq!(nonces(chain.address_history(&[2u8; 32], 0, 10)), vec![2, 1, 0]);
+
+        let newest = chain.address_history(&sender, 0, 1)[0].0;
+        assert_eq!(newest, TxLocation { height: 4, index: 1 });
+        // Coinbases are indexed for their miner only
+        assert_eq!(chain.state.history_len(&miner), 3);
+        assert_eq!(chain.state.history_len(&crate::transaction::COINBASE_SENDER), 0);
+
+        // Undoing a block drops its entries; a rebuild reproduces the rest
+        chain.undo_last_block().unwrap();
+        assert_eq!(nonces(chain.address_history(&sender, 0, 10)), vec![1, 0]);
+        let mut rebuilt = chain.genesis_only();
+        rebuilt.blocks = chain.blocks.clone();
+        rebuilt.rebuild_state();
+        assert_eq!(rebuilt.state.address_history(&miner, 0, 10), chain.state.address_history(&miner, 0, 10));
+    }
+
+    #[test]
+    fn test_fork_below_confirmation_depth_rejected() {
+        let chain = full_chain(8).with_confirmation_depth(3);
+        assert_eq!(chain.finalized_height, 5);
+
+        // Mine a competing chain on top of our first `at` blocks
+        let fork_from = |at: usize, len: usize| {
+            let mut fork = chain.genesis_only();
+            for block in &chain.blocks[1..=at] {
+                fork.add_block(block.clone(), TARGET_TIME).unwrap();
+            }
+            while fork.blocks.len() < len {
+                fork.add_block(mine_next(&fork, [9u8; 32]), TARGET_TIME).unwrap();
+            }
+            fork.blocks
+        };
+
+        // Forking at height 3 rewrites final blocks, however long the fork
+        let deep = fork_from(2, 20);
+        assert_eq!(chain.common_ancestor(&deep), Some(2));
+        assert!(matches!(chain.check_reorg(&deep), Err(AxiomError::ConsensusFailed(_))));
+
+        // Forking above the final blocks is still a candidate
+        let shallow = fork_from(6, 12);
+        assert_eq!(chain.common_ancestor(&shallow), Some(6));
+        assert!(chain.check_reorg(&shallow).is_ok());
+
+        // Finality only rises, even as blocks are rolled back
+        let mut chain = chain;
+        assert_eq!(chain.rollback_to(5).unwrap().len(), 3);
+        assert_eq!(chain.finalized_height, 5);
+        assert!(chain.rollback_to(4).is_err());
+
+        // A chain from another genesis shares nothing
+        let mut other = chain.blocks.clone();
+        other[0].nonce += 1;
+        assert!(matches!(chain.check_reorg(&other), Err(AxiomError::InvalidBlock(_))));
+    }
+
+    #[test]
+    fn test_rollback_bounds() {
+        let mut chain = full_chain(5);
+        assert!(matches!(chain.rollback_to(6), Err(AxiomError::InvalidBlockHeight { expected: 5, actual: 6 })));
+        assert!(chain.rollback_to(5).unwrap().is_empty());
+
+        // Blocks at or below a finalized checkpoint stay
+        chain.finalized_height = 4;
+        assert!(matches!(chain.rollback_to(3), Err(AxiomError::ConsensusFailed(_))));
+        assert_eq!(chain.blocks.len(), 6);
+        assert_eq!(chain.rollback_to(4).unwrap().len(), 1);
+        assert_eq!(chain.blocks.len(), 5);
+    }
+
+    #[test]
+    fn test_simulate_transaction() {
+        let mut chain = Timechain::new(genesis::genesis());
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        let sender = key.verifying_key().to_bytes();
+        chain.state.credit(sender, 1_000);
+        chain.state.nonces.insert(sender, 3);
+        let root = chain.state.state_root();
+
+        // Unproven but otherwise valid: every check passes
+        let ok = chain.simulate_transaction(&signed_tx(&key, 900, 100, 3));
+        assert!(ok.would_succeed());
+        assert_eq!(ok.checks.iter().map(|(name, _)| *name).collect::<Vec<_>>(), ["signature", "nonce", "balance", "dust", "fee", "chain_id"]);
+        assert_eq!(ok.balance_changes, vec![(sender, -1_000), ([2u8; 32], 900)]);
+
+        let broke = chain.simulate_transaction(&signed_tx(&key, 901, 100, 3));
+        assert!(!broke.would_succeed());
+        assert!(matches!(
+            broke.failure(),
+            Some(("balance", AxiomError::InsufficientBalance { available: 1_000, required: 1_001 }))
+        ));
+
+        let replayed = chain.simulate_transaction(&signed_tx(&key, 10, 1, 2));
+        assert!(matches!(replayed.failure(), Some(("nonce", AxiomError::InvalidNonce { expected: 3, actual: 2 }))));
+        // Only the nonce is wrong
+        assert_eq!(replayed.checks.iter().filter(|(_, result)| result.is_err()).count(), 1);
+
+        // A proof, once attached, is checked too
+        let mut proven = signed_tx(&key, 900, 100, 3);
+        proven.zk_proof = vec![1u8; 32];
+        let checked = chain.simulate_transaction(&proven);
+        assert!(matches!(checked.checks.last(), Some(("zk_proof", Err(AxiomError::ProofVerificationFailed(_))))));
+
+        // Nothing was applied
+        assert_eq!(chain.state.state_root(), root);
+    }
+
+    #[test]
+    fn test_transaction_authorization() {
+        let mut chain = Timechain::new(genesis::genesis());
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        let sender = key.verifying_key().to_bytes();
+        chain.state.credit(sender, 1_000);
+        chain.state.nonces.insert(sender, 3);
+
+        // Valid: signed by the sender, fresh nonce, funded
+        let tx = signed_tx(&key, 900, 100, 3);
+        assert!(chain.check_transaction_authorization(&tx).is_ok());
+        // Only the ZK proof remains, and an empty one cannot verify
+        assert!(matches!(chain.validate_transaction(&tx), Err(AxiomError::ProofVerificationFailed(_))));
+
+        // Wrong signature: signed by someone else, or tampered after signing
+        let mut forged = signed_tx(&SigningKey::from_bytes(&[8u8; 32]), 900, 100, 3);
+        forged.from = sender;
+        assert!(matches!(chain.validate_transaction(&forged), Err(AxiomError::InvalidSignature(_))));
+        let mut tampered = tx.clone();
+        tampered.amount = 1;
+        assert!(matches!(chain.validate_transaction(&tampered), Err(AxiomError::InvalidSignature(_))));
+        let mut unsigned = tx.clone();
+        unsigned.signature.clear();
+        assert!(matches!(chain.validate_transaction(&unsigned), Err(AxiomError::InvalidSignature(_))));
+
+        // Nonce already used
+        assert!(matches!(
+            chain.validate_transaction(&signed_tx(&key, 10, 1, 2)),
+            Err(AxiomError::InvalidNonce { expected: 3, actual: 2 })
+        ));
+
+        // Not enough to cover amount + fee
+        assert!(matches!(
+            chain.validate_transaction(&signed_tx(&key, 901, 100, 3)),
+            Err(AxiomError::InsufficientBalance { available: 1_000, required: 1_001 })
+        ));
+        assert!(matches!(
+            chain.validate_transaction(&signed_tx(&key, u64::MAX, 1, 3)),
+            Err(AxiomError::InvalidTransaction(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_transaction_dust_limit() {
+        let mut chain = Timechain::new(genesis::genesis()).with_dust_limit(100, false);
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        chain.state.credit(key.verifying_key().to_bytes(), 1_000);
+
+        assert!(matches!(
+            chain.validate_transaction(&signed_tx(&key, 99, 1, 0)),
+            Err(AxiomError::Dust { amount: 99, limit: 100 })
+        ));
+        // At the limit the dust check passes and the proof check is reached
+        assert!(matches!(
+            chain.validate_transaction(&signed_tx(&key, 100, 1, 0)),
+            Err(AxiomError::ProofVerificationFailed(_))
+        ));
+        assert!(matches!(chain.genesis_only().validate_transaction(&signed_tx(&key, 99, 1, 0)), Err(AxiomError::Dust { .. })));
+    }
+
+    #[test]
+    fn test_fee_burn_fraction_survives_genesis_only() {
+        let chain = Timechain::new(genesis::genesis()).with_fee_burn_fraction(0.25);
+        assert_eq!(chain.genesis_only().fee_burn_fraction, 0.25);
+    }
+
+    #[test]
+    fn test_transaction_only_valid_on_its_chain() {
+        use crate::config::{MAINNET_NETWORK_ID, REGTEST_NETWORK_ID};
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        let mut mainnet = Timechain::new(genesis::genesis());
+        mainnet.state.credit(key.verifying_key().to_bytes(), 1_000);
+        let mut regtest = mainnet.genesis_only().with_network_id(REGTEST_NETWORK_ID);
+        regtest.state.credit(key.verifying_key().to_bytes(), 1_000);
+
+        let for_mainnet = signed_tx_for(MAINNET_NETWORK_ID, &key, 100, 1, 0);
+        let for_regtest = signed_tx_for(REGTEST_NETWORK_ID, &key, 100, 1, 0);
+
+        // On the matching chain both get past the chain id and signature
+        // checks to the proof check
+        assert!(matches!(mainnet.validate_transaction(&for_mainnet), Err(AxiomError::ProofVerificationFailed(_))));
+        assert!(matches!(regtest.validate_transaction(&for_regtest), Err(AxiomError::ProofVerificationFailed(_))));
+
+        // Replayed on the other chain, each is turned away
+        assert!(matches!(
+            regtest.validate_transaction(&for_mainnet),
+            Err(AxiomError::WrongChainId { expected: REGTEST_NETWORK_ID, actual: MAINNET_NETWORK_ID })
+        ));
+        assert!(matches!(
+            mainnet.validate_transaction(&for_regtest),
+            Err(AxiomError::WrongChainId { expected: MAINNET_NETWORK_ID, actual: REGTEST_NETWORK_ID })
+        ));
+        let failed: Vec<&str> = regtest.simulate_transaction(&for_mainnet).checks.iter()
+            .filter(|(_, result)| result.is_err())
+            .map(|(name, _)| *name)
+            .collect();
+        assert_eq!(failed, ["chain_id"]);
+
+        // Restamping the chain id invalidates the signature
+        let restamped = for_mainnet.clone().with_chain_id(REGTEST_NETWORK_ID);
+        assert!(matches!(regtest.validate_transaction(&restamped), Err(AxiomError::InvalidSignature(_))));
+
+        // Nor can a block carry it onto the other chain
+        let mut block = mine_next(&regtest, [1u8; 32]);
+        block.transactions = vec![regtest.coinbase_for([1u8; 32], std::slice::from_ref(&for_mainnet)), for_mainnet];
+        block.merkle_root = block.compute_merkle_root();
+        while !block.meets_difficulty() {
+            block.nonce += 1;
+        }
+        assert_eq!(regtest.add_block(block, TARGET_TIME), Err("Transaction signed for another chain"));
+        assert_eq!(regtest.blocks.len(), 1);
+    }
+
+    #[test]
+    fn test_add_block_rejects_merkle_mismatch() {
+        let mut chain = Timechain::new(genesis::genesis());
+        let mut block = mine_next(&chain, [1u8; 32]);
+        block.merkle_root = [0xAA; 32];
+        while !block.meets_difficulty() {
+            block.nonce += 1;
+        }
+        assert_eq!(chain.add_block(block, TARGET_TIME), Err("Merkle root mismatch"));
+        assert_eq!(chain.blocks.len(), 1);
+    }
+
+    #[test]
+    fn test_add_block_checks_vdf_from_parent_and_slot() {
+        let mut chain = Timechain::new(genesis::genesis());
+        let genuine = mine_next(&chain, [1u8; 32]);
+        let remine = |mut block: Block| {
+            block.nonce = 0;
+            while !block.meets_difficulty() {
+                block.nonce += 1;
+            }
+            block
+        };
+
+        let mut tampered = genuine.clone();
+        tampered.vdf_proof[0] ^= 1;
+        let mut absent = genuine.clone();
+        absent.vdf_proof = [0u8; 32];
+        // A correctly computed VDF, but seeded from the wrong slot
+        let mut wrong_seed = genuine.clone();
+        wrong_seed.vdf_proof = crate::main_helper::compute_vdf(
+            crate::vdf::evaluate(genuine.parent, genuine.slot + 1),
+            chain.vdf_steps,
+        );
+        for block in [tampered, absent, wrong_seed] {
+            assert_eq!(chain.add_block(remine(block), TARGET_TIME), Err("Invalid VDF proof"));
+        }
+
+        chain.add_block(genuine, TARGET_TIME).unwrap();
+        assert_eq!(chain.blocks.len(), 2);
+    }
+
+    #[test]
+    fn test_add_block_checks_vdf_against_configured_steps() {
+        let mut chain = Timechain::new(genesis::genesis()).with_vdf_steps(50);
+        assert_eq!(chain.genesis_only().vdf_steps, 50);
+        let genuine = mine_next(&chain, [1u8; 32]);
+
+        // Run for the difficulty's length, as blocks used to be, the proof is wrong
+        let mut stale = genuine.clone();
+        stale.vdf_proof = crate::main_helper::compute_vdf(
+            crate::vdf::evaluate(genuine.parent, genuine.slot),
+            chain.difficulty as u32,
+        );
+        assert_eq!(chain.add_block(stale, TARGET_TIME), Err("Invalid VDF proof"));
+
+        chain.add_block(genuine, TARGET_TIME).unwrap();
+        assert_eq!(chain.blocks.len(), 2);
+    }
+
+    #[test]
+    fn test_fee_rate_enforced_on_entry_and_in_blocks() {
+        let mut chain = Timechain::new(genesis::genesis()).with_min_fee_per_byte(10);
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        chain.state.credit(key.verifying_key().to_bytes(), 1_000_000);
+
+        let cheap = signed_tx(&key, 100, 1, 0);
+        let min = cheap.min_fee(10);
+        assert_eq!(min, 10 * cheap.size() as u64);
+        assert!(matches!(
+            chain.validate_transaction(&cheap),
+            Err(AxiomError::FeeTooLow { min: m, actual: 1 }) if m == min
+        ));
+        // Paying the minimum gets past the fee check to the proof check
+        assert!(matches!(
+            chain.validate_transaction(&signed_tx(&key, 100, min, 0)),
+            Err(AxiomError::ProofVerificationFailed(_))
+        ));
+
+        let mut block = mine_next(&chain, [1u8; 32]);
+        block.transactions = vec![chain.coinbase_for([1u8; 32], std::slice::from_ref(&cheap)), cheap];
+        block.merkle_root = block.compute_merkle_root();
+        while !block.meets_difficulty() {
+            block.nonce += 1;
+        }
+        assert_eq!(chain.add_block(block, TARGET_TIME), Err("Transaction fee rate too low"));
+        assert_eq!(chain.blocks.len(), 1);
+    }
+
+    fn with_transactions(mut block: Block, transactions: Vec<Transaction>) -> Block {
+        block.transactions = transactions;
+        block.merkle_root = block.compute_merkle_root();
+        while !block.meets_difficulty() {
+            block.nonce += 1;
+        }
+        block
+    }
+
+    #[test]
+    fn test_add_block_accepts_exact_coinbase() {
+        let mut chain = Timechain::new(genesis::genesis());
+        let miner = [1u8; 32];
+        let coinbase = chain.coinbase_for(miner, &[]);
+        assert!(coinbase.is_coinbase());
+        assert_eq!(coinbase.amount, economics::get_mining_reward(1));
+
+        let block = with_transactions(mine_next(&chain, miner), vec![coinbase]);
+        chain.add_block(block, TARGET_TIME).unwrap();
+        // The coinbase records the reward; it is paid once, not twice
+        assert_eq!(chain.balance(&miner), economics::get_mining_reward(1));
+        assert_eq!(chain.audit_supply(), Ok(()));
+
+        // Paid to someone other than the block's miner
+        let block = with_transactions(mine_next(&chain, miner), vec![chain.coinbase_for([7u8; 32], &[])]);
+        assert_eq!(chain.add_block(block, TARGET_TIME), Err("Coinbase does not pay the block's miner"));
+    }
+
+    #[test]
+    fn test_add_block_rejects_inflated_coinbase() {
+        let mut chain = Timechain::new(genesis::genesis());
+        let miner = [1u8; 32];
+        let mut coinbase = chain.coinbase_for(miner, &[]);
+        coinbase.amount += 1;
+
+        let block = with_transactions(mine_next(&chain, miner), vec![coinbase.clone()]);
+        assert_eq!(chain.add_block(block, TARGET_TIME), Err("Coinbase amount does not match reward plus fees"));
+        assert_eq!(chain.blocks.len(), 1);
+        assert_eq!(chain.balance(&miner), 0);
+
+        // A second coinbase cannot hide further down the block either
+        let block = with_transactions(
+            mine_next(&chain, miner),
+            vec![chain.coinbase_for(miner, &[]), chain.coinbase_for(miner, &[])],
+        );
+        assert_eq!(chain.add_block(block, TARGET_TIME), Err("Coinbase must be the first transaction"));
+    }
+
+    #[test]
+    fn test_add_block_requires_coinbase_for_this_block() {
+        let mut chain = Timechain::new(genesis::genesis());
+        let miner = [1u8; 32];
+        let block = with_transactions(mine_next(&chain, miner), vec![]);
+        assert_eq!(chain.add_block(block, TARGET_TIME), Err("First transaction must be a coinbase"));
+
+        // Right miner and amount, but minted for another height or chain
+        let stale = Transaction::coinbase(miner, 2, 0).with_chain_id(chain.network_id);
+        let foreign = Transaction::coinbase(miner, 1, 0).with_chain_id(chain.network_id.wrapping_add(1));
+        assert_eq!(stale.amount, chain.coinbase_for(miner, &[]).amount);
+        for coinbase in [stale, foreign] {
+            let block = with_transactions(mine_next(&chain, miner), vec![coinbase]);
+            assert_eq!(chain.add_block(block, TARGET_TIME), Err("Coinbase is not for this block"));
+        }
+        assert_eq!(chain.blocks.len(), 1);
+
+        // The reward is paid to the coinbase's recipient, in replay too
+        chain.add_block(mine_next(&chain, miner), TARGET_TIME).unwrap();
+        assert_eq!(chain.state.balance(&miner), economics::get_mining_reward(1));
+        chain.rebuild_state();
+        assert_eq!(chain.state.balance(&miner), economics::get_mining_reward(1));
+    }
+
+    #[test]
+    fn test_coinbase_includes_fees() {
+        let mut chain = Timechain::new(genesis::genesis());
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        chain.state.credit(key.verifying_key().to_bytes(), 1_000);
+        let miner = [1u8; 32];
+        let txs = vec![signed_tx(&key, 100, 7, 0), signed_tx(&key, 100, 5, 1)];
+
+        let coinbase = chain.coinbase_for(miner, &txs);
+        assert_eq!(coinbase.amount, economics::get_mining_reward(1) + 12);
+        assert_eq!(chain.expected_coinbase(miner, 1, 12), coinbase);
+
+        // Leaving the fees out is as wrong as inflating the reward
+        let reward_only = chain.coinbase_for(miner, &[]);
+        let mut block_txs = vec![reward_only];
+        block_txs.extend(txs.iter().cloned());
+        let block = with_transactions(mine_next(&chain, miner), block_txs);
+        assert_eq!(chain.add_block(block, TARGET_TIME), Err("Coinbase amount does not match reward plus fees"));
+
+        // With the fees, the coinbase passes and validation moves on to the transactions
+        let mut block_txs = vec![coinbase];
+        block_txs.extend(txs.iter().cloned());
+        let block = with_transactions(mine_next(&chain, miner), block_txs);
+        assert!(!chain.add_block(block, TARGET_TIME).unwrap_err().starts_with("Coinbase"));
+
+        // Only the miner's share survives a fee burn
+        chain.fee_burn_fraction = 0.5;
+        assert_eq!(chain.coinbase_for(miner, &txs).amount, economics::get_mining_reward(1) + 6);
+    }
+
+    #[test]
+    fn test_block_over_byte_limit_rejected() {
+        let miner = [1u8; 32];
+        let chain = Timechain::new(genesis::genesis());
+        let block = with_transactions(mine_next(&chain, miner), vec![chain.coinbase_for(miner, &[])]);
+        let size = block.size();
+
+        let mut chain = chain.with_block_limits(size - 1, MAX_TRANSACTIONS_PER_BLOCK);
+        assert!(matches!(
+            chain.check_block_limits(&block),
+            Err(AxiomError::BlockTooLarge { size: s, max }) if s == size && max == size - 1
+        ));
+        assert_eq!(chain.add_block(block, TARGET_TIME), Err("Block exceeds size limits"));
+    }
+
+    #[test]
+    fn test_block_over_transaction_limit_rejected() {
+        let miner = [1u8; 32];
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        let mut chain = Timechain::new(genesis::genesis()).with_block_limits(MAX_BLOCK_SIZE, 1);
+        chain.state.credit(key.verifying_key().to_bytes(), 1_000);
+        let txs = vec![signed_tx(&key, 100, 7, 0), signed_tx(&key, 100, 5, 1)];
+
+        let mut block_txs = vec![chain.coinbase_for(miner, &txs)];
+        block_txs.extend(txs.iter().cloned());
+        let block = with_transactions(mine_next(&chain, miner), block_txs);
+        assert!(matches!(chain.check_block_limits(&block), Err(AxiomError::InvalidBlock(_))));
+        assert_eq!(chain.add_block(block, TARGET_TIME), Err("Block exceeds size limits"));
+
+        // Block construction stops at the limit; the coinbase does not count
+        assert_eq!(chain.fitting_transactions(miner, &txs), 1);
+        assert_eq!(chain.block_template(miner, txs).transactions.len(), 2);
+    }
+
+    #[test]
+    fn test_block_at_limits_accepted() {
+        let miner = [1u8; 32];
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        let chain = Timechain::new(genesis::genesis());
+        let txs = vec![signed_tx(&key, 100, 7, 0), signed_tx(&key, 100, 5, 1)];
+        let mut block_txs = vec![chain.coinbase_for(miner, &txs)];
+        block_txs.extend(txs.iter().cloned());
+        let block = with_transactions(mine_next(&chain, miner), block_txs);
+
+        let mut chain = chain.with_block_limits(block.size(), 2).without_tx_proofs();
+        chain.state.credit(key.verifying_key().to_bytes(), 1_000);
+        assert_eq!(chain.fitting_transactions(miner, &txs), 2);
+        chain.max_block_size -= 1;
+        assert_eq!(chain.fitting_transactions(miner, &txs), 1);
+        chain.max_block_size += 1;
+
+        // Exactly at both limits
+        assert_eq!(chain.add_block(block, TARGET_TIME), Ok(()));
+        assert_eq!(chain.blocks.len(), 2);
+        assert_eq!(chain.state.nonce(&key.verifying_key().to_bytes()), 2);
+    }
+
+    #[test]
+    fn test_add_block_enforces_canonical_order() {
+        let miner = [1u8; 32];
+        let cheap = SigningKey::from_bytes(&[9u8; 32]);
+        let generous = SigningKey::from_bytes(&[8u8; 32]);
+        let mut chain = Timechain::new(genesis::genesis()).without_tx_proofs();
+        chain.state.credit(cheap.verifying_key().to_bytes(), 1_000);
+        chain.state.credit(generous.verifying_key().to_bytes(), 1_000);
+        let txs = vec![signed_tx(&cheap, 100, 5, 0), signed_tx(&generous, 100, 50, 0)];
+
+        // Higher fee rate first; the template sorts whatever it is given
+        let template = chain.block_template(miner, txs.clone());
+        assert_eq!(template.user_transactions(), &[txs[1].clone(), txs[0].clone()]);
+
+        let mut block_txs = vec![chain.coinbase_for(miner, &txs)];
+        block_txs.extend(txs.iter().cloned());
+        let misordered = with_transactions(mine_next(&chain, miner), block_txs);
+        assert_eq!(chain.add_block(misordered, TARGET_TIME), Err("Transactions not in canonical order"));
+
+        let ordered = with_transactions(mine_next(&chain, miner), template.transactions);
+        assert_eq!(chain.add_block(ordered, TARGET_TIME), Ok(()));
+        assert_eq!(chain.blocks.len(), 2);
+    }
+
+    #[test]
+    fn test_configured_reward_address_gets_coinbase() {
+        let mut chain = Timechain::new(genesis::genesis());
+        let wallet = Wallet::from_secret_key([6u8; 32]);
+        let config = crate::config::MiningConfig {
+            miner_address: Some(hex::encode([4u8; 32])),
+            ..Default::default()
+        };
+        let reward_address = config.reward_address().unwrap().unwrap_or(wallet.address);
+
+        let mut block = chain.next_block_rewarding(&wallet, reward_address, vec![]);
+        block.vdf_proof = crate::main_helper::compute_vdf(
+            crate::vdf::evaluate(block.parent, block.slot),
+            chain.vdf_steps,
+        );
+        while !block.meets_difficulty() {
+            block.nonce += 1;
+        }
+        chain.add_block(block.clone(), TARGET_TIME).unwrap();
+        assert_eq!(block.miner, [4u8; 32]);
+        assert_eq!(chain.balance(&[4u8; 32]), economics::get_mining_reward(1));
+        assert_eq!(chain.balance(&wallet.address), 0);
+
+        // Unset, the node wallet is paid as before
+        assert_eq!(chain.next_block(&wallet, vec![]).transactions[0].to, wallet.address);
+    }
+
+    #[test]
+    fn test_template_merkle_root_matches_recompute() {
+        let miner = [1u8; 32];
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        let mut chain = Timechain::new(genesis::genesis());
+        chain.state.credit(key.verifying_key().to_bytes(), 1_000_000);
+        for count in 0..=9u64 {
+            let txs: Vec<Transaction> = (0..count).map(|nonce| signed_tx(&key, 100, 10 + nonce, nonce)).collect();
+            let template = chain.block_template(miner, txs);
+            assert_eq!(template.transactions.len() as u64, count + 1);
+            assert_eq!(template.merkle_root, template.compute_merkle_root(), "{} transactions", count);
+        }
+    }
+
+    #[test]
+    fn test_affordable_transactions_track_running_balance() {
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        let other = SigningKey::from_bytes(&[8u8; 32]);
+        let mut chain = Timechain::new(genesis::genesis());
+        chain.state.credit(key.verifying_key().to_bytes(), 500);
+        chain.state.credit(other.verifying_key().to_bytes(), 500);
+
+        // Each passes alone; together the third overspends, and nonce 5 leaves a gap
+        let txs = vec![
+            signed_tx(&key, 200, 10, 0),
+            signed_tx(&other, 100, 10, 0),
+            signed_tx(&key, 200, 10, 1),
+            signed_tx(&key, 200, 10, 2),
+            signed_tx(&other, 100, 10, 5),
+        ];
+        let (affordable, deferred) = chain.affordable_transactions(txs.clone());
+        assert_eq!(affordable, vec![txs[0].clone(), txs[1].clone(), txs[2].clone()]);
+        assert_eq!(deferred, vec![txs[3].clone(), txs[4].clone()]);
+
+        // Of two transactions spending the same nonce only the first is kept
+        let conflicting = vec![signed_tx(&key, 100, 10, 0), signed_tx(&key, 50, 10, 0)];
+        let (affordable, deferred) = chain.affordable_transactions(conflicting.clone());
+        assert_eq!(affordable, conflicting[..1]);
+        assert_eq!(deferred, conflicting[1..]);
+    }
+
+    #[test]
+    fn test_mined_selection_follows_mempool_priority() {
+        let (early, key, unfunded) =
+            (SigningKey::from_bytes(&[9u8; 32]), SigningKey::from_bytes(&[8u8; 32]), SigningKey::from_bytes(&[7u8; 32]));
+        let mut chain = full_chain(1).without_tx_proofs();
+        chain.max_transactions_per_block = 2;
+        chain.state.credit(early.verifying_key().to_bytes(), 1_000);
+        chain.state.credit(key.verifying_key().to_bytes(), 1_000);
+
+        // First in, but the lowest fee rate; nonce 1 outbids its own nonce 0
+        let mut mempool = crate::mempool::Mempool::new();
+        let (low, first, second) = (signed_tx(&early, 100, 1, 0), signed_tx(&key, 100, 50, 0), signed_tx(&key, 100, 60, 1));
+        for tx in [&low, &first, &second] {
+            mempool.add(tx.clone(), &chain.state).unwrap();
+        }
+        let mut candidates = mempool.get_for_mining(usize::MAX);
+        assert_eq!(candidates, vec![second.clone(), first.clone(), low.clone()]);
+        let broke = signed_tx(&unfunded, 100, 10, 0);
+        candidates.push(broke.clone());
+
+        let miner = [4u8; 32];
+        let (selected, invalid) = chain.select_transactions(miner, candidates);
+        assert_eq!(selected, vec![first.clone(), second.clone()]);
+        assert_eq!(invalid, vec![broke]);
+
+        let block = with_transactions(mine_next(&chain, miner), chain.block_template(miner, selected).transactions);
+        assert_eq!(chain.add_block(block.clone(), TARGET_TIME), Ok(()));
+        mempool.remove_mined(&block);
+        assert_eq!(mempool.get_for_mining(usize::MAX), vec![low]);
+    }
+
+    /// `mine_next`, stamped `seconds` after the tip and mined at `difficulty`
+    fn mine_after(chain: &Timechain, seconds: u64, difficulty: u64) -> Block {
+        let tip = chain.blocks.last().unwrap().timestamp;
+        let mut block = mine_next(chain, [1u8; 32]).with_timestamp(tip + seconds).with_difficulty(difficulty);
+        block.nonce = 0;
+        while !block.meets_difficulty() {
+            block.nonce += 1;
+        }
+        block
+    }
+
+    #[test]
+    fn test_difficulty_claim_follows_lwma() {
+        let mut chain = Timechain::new(genesis::genesis());
+        let start = chain.difficulty;
+        // Before there is a window, only the current difficulty will do
+        let wrong = mine_after(&chain, TARGET_TIME, start + 1);
+        assert_eq!(chain.add_block(wrong, TARGET_TIME), Err("Block claims the wrong difficulty"));
+
+        // A full window of blocks right on target leaves difficulty where it is
+        while chain.blocks.len() <= lwma::LWMA_WINDOW {
+            chain.add_block(mine_after(&chain, TARGET_TIME, chain.difficulty), TARGET_TIME).unwrap();
+        }
+        assert_eq!(chain.difficulty, start);
+
+        // Claims within the tolerance pass; further off they do not
+        let tolerance = start * lwma::DIFFICULTY_TOLERANCE_PERCENT / 100;
+        for off in [start - tolerance - 1, start + tolerance + 1] {
+            let block = mine_after(&chain, TARGET_TIME, off);
+            assert_eq!(chain.add_block(block, TARGET_TIME), Err("Block claims the wrong difficulty"), "{}", off);
+        }
+        let within = mine_after(&chain, TARGET_TIME, start + tolerance);
+        assert_eq!(chain.add_block(within, TARGET_TIME), Ok(()));
+
+        // Blocks coming in far too fast push the prescribed difficulty up
+        for _ in 0..10 {
+            chain.add_block(mine_after(&chain, 60, chain.difficulty), TARGET_TIME).unwrap();
+        }
+        assert!(chain.difficulty > start);
     }
 }
@@ -0,0 +1,334 @@
+//! Command-line interface of the `axiom` node binary.
+//!
+//! With no subcommand the node runs as before. Every other subcommand is a
+//! one-off action that writes its report to the given output and exits;
+//! `dispatch` is the single entry point so commands can be driven directly
+//! from tests.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, Subcommand};
+
+use crate::block::Block;
+use crate::config::AxiomConfig;
+use crate::error::{AxiomError, Result};
+use crate::node_identity::{NodeIdentity, ROTATION_GRACE_SECS};
+use crate::wallet::{Wallet, WALLET_FILE};
+use crate::{economics, genesis, main_helper, storage, vdf};
+
+#[derive(Debug, Parser)]
+#[command(name = "axiom", version, about = "AXIOM Protocol node")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Accepted for older launch scripts; the node always joins mainnet
+    #[arg(long, global = true, hide = true)]
+    pub mainnet: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the node (default)
+    Run,
+    /// Manage the local wallet
+    Wallet {
+        #[command(subcommand)]
+        command: WalletCommand,
+    },
+    /// Manage node configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Print supply statistics and the chain tip
+    Info,
+    /// Check the emission schedule against the protocol constants
+    ValidateEconomics,
+    /// Benchmark the VDF or compute a single proof
+    Vdf {
+        #[command(subcommand)]
+        command: VdfCommand,
+    },
+    /// Replace the node's peer key; the old PeerId points at the new one for a week
+    RotateIdentity {
+        /// Directory holding the node key
+        #[arg(long, default_value = ".")]
+        dir: PathBuf,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum WalletCommand {
+    /// Generate a new wallet, encrypted if AXIOM_WALLET_PASSWORD is set
+    New {
+        #[arg(long, default_value = WALLET_FILE)]
+        path: PathBuf,
+        /// Replace an existing wallet file
+        #[arg(long)]
+        force: bool,
+    },
+    /// Print the address of an existing wallet
+    Address {
+        #[arg(long, default_value = WALLET_FILE)]
+        path: PathBuf,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Write the default configuration as TOML
+    Init {
+        path: PathBuf,
+        /// Replace an existing file
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum VdfCommand {
+    /// Measure VDF evaluations per second at a given difficulty
+    Bench {
+        /// Sequential iterations per evaluation (the chain's `vdf_steps`)
+        #[arg(long, default_value_t = 1000)]
+        difficulty: u32,
+        /// Number of evaluations to time
+        #[arg(long, default_value_t = 10)]
+        iterations: u32,
+    },
+    /// Compute the VDF for a parent block hash and slot
+    Prove {
+        /// Parent block hash, 32 bytes of hex
+        #[arg(long)]
+        input: String,
+        #[arg(long, default_value_t = 0)]
+        slot: u64,
+        #[arg(long)]
+        difficulty: u32,
+    },
+}
+
+/// What the binary should do once `dispatch` returns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// Start the node
+    RunNode,
+    /// The command finished; exit
+    Done,
+}
+
+/// Execute `command`, writing its report to `out`
+pub fn dispatch(command: Command, out: &mut dyn Write) -> Result<Outcome> {
+    match command {
+        Command::Run => return Ok(Outcome::RunNode),
+        Command::Wallet { command: WalletCommand::New { path, force } } => {
+            refuse_overwrite(&path, force, AxiomError::WalletExists)?;
+            let wallet = Wallet::generate();
+            wallet.store(&path)?;
+            writeln!(out, "💳 New wallet: {}", wallet.address_hex())?;
+            writeln!(out, "📁 Saved to {} (keep safe!)", path.display())?;
+        }
+        Command::Wallet { command: WalletCommand::Address { path } } => {
+            let wallet = Wallet::open(&path)?;
+            writeln!(out, "{}", wallet.address_hex())?;
+        }
+        Command::Config { command: ConfigCommand::Init { path, force } } => {
+            refuse_overwrite(&path, force, AxiomError::InvalidConfig)?;
+            AxiomConfig::default().save_to_file(&path.to_string_lossy())?;
+            writeln!(out, "⚙️  Default configuration written to {}", path.display())?;
+        }
+        Command::Info => {
+            let blocks = storage::load_chain().unwrap_or_else(|| vec![genesis::genesis()]);
+            write_info(&blocks, out)?;
+        }
+        Command::ValidateEconomics => {
+            economics::validate_economics().map_err(AxiomError::ConsensusFailed)?;
+            writeln!(out, "✅ Economics valid: {} fixed supply", economics::format_axm(economics::TOTAL_SUPPLY))?;
+        }
+        Command::Vdf { command: VdfCommand::Bench { difficulty, iterations } } => {
+            let bench = main_helper::benchmark_vdf(difficulty, iterations);
+            writeln!(out, "⏱️  VDF difficulty {}: {:.2} evaluations/s over {} runs", difficulty, bench.evaluations_per_second, bench.evaluations)?;
+            writeln!(out, "Seconds per block: {:.4}", bench.seconds_per_block())?;
+            writeln!(
+                out,
+                "Blocks per {}s target: {:.1}",
+                crate::chain::TARGET_TIME,
+                bench.blocks_per_block_time(crate::chain::TARGET_TIME)
+            )?;
+        }
+        Command::Vdf { command: VdfCommand::Prove { input, slot, difficulty } } => {
+            let parent: [u8; 32] = hex::decode(&input)
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| AxiomError::VDFComputationFailed("--input must be 32 bytes of hex".to_string()))?;
+            let seed = vdf::evaluate(parent, slot);
+            let output = main_helper::compute_vdf(seed, difficulty);
+            writeln!(out, "Seed:   {}", hex::encode(seed))?;
+            writeln!(out, "Output: {}", hex::encode(output))?;
+        }
+        Command::RotateIdentity { dir } => {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            let notice = NodeIdentity::new(dir).rotate(now)?;
+            writeln!(out, "🔑 Old peer ID: {}", notice.old_peer_id()?)?;
+            writeln!(out, "🔑 New peer ID: {}", notice.new_peer_id()?)?;
+            writeln!(
+                out,
+                "The old ID resolves to the new one for {} days; restart the node to use it",
+                ROTATION_GRACE_SECS / (24 * 3600)
+            )?;
+        }
+    }
+    Ok(Outcome::Done)
+}
+
+fn refuse_overwrite(path: &Path, force: bool, error: fn(String) -> AxiomError) -> Result<()> {
+    if path.exists() && !force {
+        return Err(error(format!("{} exists; pass --force to replace it", path.display())));
+    }
+    Ok(())
+}
+
+fn write_info(blocks: &[Block], out: &mut dyn Write) -> Result<()> {
+    let tip = blocks.last().ok_or_else(|| AxiomError::BlockNotFound("chain is empty".to_string()))?;
+    writeln!(out, "{}", economics::format_supply_stats(tip.slot))?;
+    writeln!(out, "Tip height: {}", tip.slot)?;
+    writeln!(out, "Tip hash:   {}", hex::encode(tip.hash()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("axiom_cli_{}_{}", std::process::id(), name))
+    }
+
+    fn run(args: &[&str], out: &mut Vec<u8>) -> Result<Outcome> {
+        let cli = Cli::try_parse_from(std::iter::once("axiom").chain(args.iter().copied())).unwrap();
+        dispatch(cli.command.unwrap_or(Command::Run), out)
+    }
+
+    #[test]
+    fn test_no_subcommand_runs_node() {
+        assert_eq!(run(&[], &mut Vec::new()).unwrap(), Outcome::RunNode);
+        assert_eq!(run(&["run"], &mut Vec::new()).unwrap(), Outcome::RunNode);
+        assert_eq!(run(&["--mainnet"], &mut Vec::new()).unwrap(), Outcome::RunNode);
+    }
+
+    #[test]
+    fn test_wallet_new_and_address() {
+        let path = temp_path("wallet.dat");
+        let _ = std::fs::remove_file(&path);
+        let path_str = path.to_str().unwrap();
+
+        let mut out = Vec::new();
+        assert_eq!(run(&["wallet", "new", "--path", path_str], &mut out).unwrap(), Outcome::Done);
+
+        let wallet = Wallet::load_from(&path).unwrap();
+        let verifying_key = ed25519_dalek::SigningKey::from_bytes(&wallet.secret_key).verifying_key();
+        assert_eq!(verifying_key.to_bytes(), wallet.address);
+        assert!(String::from_utf8(out).unwrap().contains(&wallet.address_hex()));
+
+        let mut out = Vec::new();
+        run(&["wallet", "address", "--path", path_str], &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap().trim(), wallet.address_hex());
+
+        // An existing wallet is never replaced silently
+        assert!(matches!(
+            run(&["wallet", "new", "--path", path_str], &mut Vec::new()),
+            Err(AxiomError::WalletExists(_))
+        ));
+        run(&["wallet", "new", "--path", path_str, "--force"], &mut Vec::new()).unwrap();
+        assert_ne!(Wallet::load_from(&path).unwrap().address, wallet.address);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_config_init_writes_loadable_toml() {
+        let path = temp_path("axiom.toml");
+        let _ = std::fs::remove_file(&path);
+
+        run(&["config", "init", path.to_str().unwrap()], &mut Vec::new()).unwrap();
+        let loaded = AxiomConfig::load_from_file(path.to_str().unwrap()).unwrap();
+        let defaults = AxiomConfig::default();
+        assert_eq!(loaded.rpc.listen_address, defaults.rpc.listen_address);
+        assert_eq!(loaded.consensus.vdf_steps, defaults.consensus.vdf_steps);
+        assert_eq!(loaded.storage.zk_keys_dir, defaults.storage.zk_keys_dir);
+
+        assert!(run(&["config", "init", path.to_str().unwrap()], &mut Vec::new()).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_info_and_economics() {
+        let mut out = Vec::new();
+        write_info(&[genesis::genesis()], &mut out).unwrap();
+        let report = String::from_utf8(out).unwrap();
+        assert!(report.contains("Tip height: 0"));
+        assert!(report.contains(&hex::encode(genesis::genesis().hash())));
+
+        // The command reports exactly what the economics check finds
+        let mut out = Vec::new();
+        match (run(&["validate-economics"], &mut out), economics::validate_economics()) {
+            (Ok(Outcome::Done), Ok(())) => assert!(String::from_utf8(out).unwrap().contains("Economics valid")),
+            (Err(AxiomError::ConsensusFailed(reported)), Err(expected)) => assert_eq!(reported, expected),
+            (got, expected) => panic!("validate-economics returned {:?}, check gave {:?}", got, expected),
+        }
+    }
+
+    #[test]
+    fn test_vdf_prove_output_verifies() {
+        let parent = [7u8; 32];
+        let mut out = Vec::new();
+        run(&["vdf", "prove", "--input", &hex::encode(parent), "--slot", "3", "--difficulty", "50"], &mut out).unwrap();
+        let report = String::from_utf8(out).unwrap();
+        let output = report.lines().find_map(|line| line.strip_prefix("Output: ")).unwrap();
+        let proof: [u8; 32] = hex::decode(output).unwrap().try_into().unwrap();
+        assert!(vdf::verify_vdf(vdf::evaluate(parent, 3), 50, proof));
+        assert!(!vdf::verify_vdf(vdf::evaluate(parent, 4), 50, proof));
+
+        assert!(matches!(
+            run(&["vdf", "prove", "--input", "abcd", "--difficulty", "50"], &mut Vec::new()),
+            Err(AxiomError::VDFComputationFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_vdf_bench_reports_positive_rate() {
+        let bench = main_helper::benchmark_vdf(100, 5);
+        assert_eq!(bench.evaluations, 5);
+        assert!(bench.evaluations_per_second > 0.0 && bench.evaluations_per_second.is_finite());
+        assert!(bench.blocks_per_block_time(1800) > bench.blocks_per_block_time(60));
+
+        let mut out = Vec::new();
+        run(&["vdf", "bench", "--difficulty", "100", "--iterations", "3"], &mut out).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains("evaluations/s"));
+    }
+
+    #[test]
+    fn test_rotate_identity() {
+        let dir = temp_path("identity");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let dir_str = dir.to_str().unwrap();
+
+        // Nothing to rotate before the node has created its key
+        assert!(run(&["rotate-identity", "--dir", dir_str], &mut Vec::new()).is_err());
+
+        let old_peer = NodeIdentity::new(&dir).load_or_create().unwrap().public().to_peer_id();
+        let mut out = Vec::new();
+        run(&["rotate-identity", "--dir", dir_str], &mut out).unwrap();
+        let new_peer = NodeIdentity::new(&dir).load_or_create().unwrap().public().to_peer_id();
+        let report = String::from_utf8(out).unwrap();
+        assert!(report.contains(&format!("Old peer ID: {}", old_peer)));
+        assert!(report.contains(&format!("New peer ID: {}", new_peer)));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
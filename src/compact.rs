@@ -0,0 +1,193 @@
+//! Compact block relay.
+//!
+//! Instead of gossiping every transaction body with each block, a node
+//! announces a `CompactBlock`: the header fields, the coinbase (which no
+//! peer can have seen) and an 8-byte short ID per remaining transaction.
+//! Receivers match the short IDs against their mempool in a
+//! `PartialBlock`, fetch whatever is still missing from the announcing
+//! peer over the chain-sync request-response protocol
+//! (`ChainRequest::transactions`, keyed by merkle root since the block hash
+//! covers the missing bodies), and rebuild the full block, which must
+//! reproduce the announced merkle root.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::block::Block;
+use crate::transaction::{Address, Transaction};
+
+/// Per-block identifier of a transaction in a compact announcement
+pub type ShortId = [u8; 8];
+
+/// Short ID of the transaction hashed `tx_hash`, keyed by the block's
+/// merkle root so IDs differ from block to block
+pub fn short_id(merkle_root: &[u8; 32], tx_hash: &[u8; 32]) -> ShortId {
+    let hash = blake3::keyed_hash(merkle_root, tx_hash);
+    hash.as_bytes()[..8].try_into().unwrap()
+}
+
+/// A block announced without its transaction bodies
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompactBlock {
+    pub parent: [u8; 32],
+    pub slot: u64,
+    pub miner: Address,
+    pub merkle_root: [u8; 32],
+    pub vdf_proof: [u8; 32],
+    pub zk_proof: Vec<u8>,
+    pub nonce: u64,
+    /// Sent in full: it pays this block's miner, so no mempool holds it
+    pub coinbase: Option<Transaction>,
+    /// The other transactions, in block order
+    pub short_ids: Vec<ShortId>,
+}
+
+impl CompactBlock {
+    pub fn from_block(block: &Block) -> Self {
+        Self {
+            parent: block.parent,
+            slot: block.slot,
+            miner: block.miner,
+            merkle_root: block.merkle_root,
+            vdf_proof: block.vdf_proof,
+            zk_proof: block.zk_proof.clone(),
+            nonce: block.nonce,
+            coinbase: block.coinbase().cloned(),
+            short_ids: block
+                .user_transactions()
+                .iter()
+                .map(|tx| short_id(&block.merkle_root, &tx.hash()))
+                .collect(),
+        }
+    }
+}
+
+/// A compact block being rebuilt from the local mempool
+#[derive(Debug, Clone)]
+pub struct PartialBlock {
+    compact: CompactBlock,
+    transactions: Vec<Option<Transaction>>,
+}
+
+impl PartialBlock {
+    /// Fill in every transaction of `compact` found in `mempool`
+    pub fn new<'a>(compact: CompactBlock, mempool: impl IntoIterator<Item = &'a Transaction>) -> Self {
+        let known: HashMap<ShortId, &Transaction> = mempool
+            .into_iter()
+            .map(|tx| (short_id(&compact.merkle_root, &tx.hash()), tx))
+            .collect();
+        let transactions = compact.short_ids.iter().map(|id| known.get(id).map(|tx| (*tx).clone())).collect();
+        Self { compact, transactions }
+    }
+
+    /// Positions (among the non-coinbase transactions) still to be fetched
+    pub fn missing(&self) -> Vec<u32> {
+        self.transactions
+            .iter()
+            .enumerate()
+            .filter(|(_, tx)| tx.is_none())
+            .map(|(index, _)| index as u32)
+            .collect()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.transactions.iter().all(Option::is_some)
+    }
+
+    /// Slot in transactions answering a request for `missing()`, in the
+    /// same order
+    pub fn fill(&mut self, transactions: Vec<Transaction>) -> Result<(), &'static str> {
+        let missing = self.missing();
+        if transactions.len() != missing.len() {
+            return Err("Wrong number of missing transactions");
+        }
+        for (index, tx) in missing.into_iter().zip(transactions) {
+            if short_id(&self.compact.merkle_root, &tx.hash()) != self.compact.short_ids[index as usize] {
+                return Err("Transaction does not match its short ID");
+            }
+            self.transactions[index as usize] = Some(tx);
+        }
+        Ok(())
+    }
+
+    /// The full block, once every transaction is known. A short-ID
+    /// collision shows up as a merkle root mismatch.
+    pub fn into_block(self) -> Result<Block, &'static str> {
+        if !self.is_complete() {
+            return Err("Compact block still has missing transactions");
+        }
+        let compact = self.compact;
+        let mut transactions: Vec<Transaction> = compact.coinbase.into_iter().collect();
+        transactions.extend(self.transactions.into_iter().flatten());
+        let block = Block::new(
+            compact.parent,
+            compact.slot,
+            compact.miner,
+            transactions,
+            compact.vdf_proof,
+            compact.zk_proof,
+            compact.nonce,
+        );
+        if block.merkle_root != compact.merkle_root {
+            return Err("Reconstructed block does not match its merkle root");
+        }
+        Ok(block)
+    }
+}
+
+/// The non-coinbase transactions of `block` at `indexes`, to answer a
+/// peer rebuilding it. `None` if any index is out of range.
+pub fn block_transactions(block: &Block, indexes: &[u32]) -> Option<Vec<Transaction>> {
+    let txs = block.user_transactions();
+    indexes.iter().map(|&i| txs.get(i as usize).cloned()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx(nonce: u64) -> Transaction {
+        Transaction::new([1u8; 32], [2u8; 32], 1_000 + nonce, 10, nonce, vec![], vec![])
+    }
+
+    fn block() -> Block {
+        let mut transactions = vec![Transaction::coinbase([9u8; 32], 1, 30)];
+        transactions.extend((0..3).map(tx));
+        Block::new([4u8; 32], 1, [9u8; 32], transactions, [5u8; 32], vec![1u8; 128], 42)
+    }
+
+    #[test]
+    fn test_reconstruct_from_mempool() {
+        let block = block();
+        let compact = CompactBlock::from_block(&block);
+        assert_eq!(compact.short_ids.len(), 3);
+        assert!(bincode::serialize(&compact).unwrap().len() < bincode::serialize(&block).unwrap().len());
+
+        // Mempool order and unrelated transactions don't matter
+        let mempool = vec![tx(2), tx(7), tx(0), tx(1)];
+        let partial = PartialBlock::new(compact, &mempool);
+        assert!(partial.missing().is_empty());
+        assert_eq!(partial.into_block().unwrap(), block);
+    }
+
+    #[test]
+    fn test_fetch_only_missing_transaction() {
+        let block = block();
+        let mempool = vec![tx(0), tx(2)];
+        let mut partial = PartialBlock::new(CompactBlock::from_block(&block), &mempool);
+        assert!(!partial.is_complete());
+
+        // Exactly the one transaction this node never saw is requested
+        let missing = partial.missing();
+        assert_eq!(missing, vec![1]);
+        let fetched = block_transactions(&block, &missing).unwrap();
+        assert_eq!(fetched, vec![tx(1)]);
+
+        assert_eq!(partial.clone().fill(vec![tx(5)]), Err("Transaction does not match its short ID"));
+        partial.fill(fetched).unwrap();
+        assert_eq!(partial.into_block().unwrap(), block);
+
+        assert!(block_transactions(&block, &[3]).is_none());
+    }
+}
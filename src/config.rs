@@ -4,6 +4,24 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use crate::error::{AxiomError, Result};
+use crate::mempool::{BlacklistPolicy, MempoolPolicy, PerSenderCapPolicy, RequireZkPolicy};
+use crate::transaction::{Address, COINBASE_SENDER};
+
+/// `network.network_id` of the public network
+pub const MAINNET_NETWORK_ID: u8 = 1;
+/// `network.network_id` of a local regression-test network: blocks every few
+/// seconds at minimum difficulty, plus the `generate_blocks` RPC
+pub const REGTEST_NETWORK_ID: u8 = 3;
+/// Default `consensus.dust_limit`: 0.00001 AXM
+pub const DEFAULT_DUST_LIMIT: u64 = 1_000;
+/// Default `consensus.min_fee_per_byte`, in smallest units
+pub const DEFAULT_MIN_FEE_PER_BYTE: u64 = 10;
+/// Default `network.max_peers_per_subnet`
+pub const DEFAULT_MAX_PEERS_PER_SUBNET: usize = 4;
+/// Default `network.max_blocks_per_response`
+pub const DEFAULT_MAX_BLOCKS_PER_RESPONSE: usize = 500;
+/// Default `node.metrics_address`
+pub const DEFAULT_METRICS_ADDRESS: &str = "127.0.0.1:9615";
 
 /// Main configuration structure
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -15,6 +33,9 @@ pub struct AxiomConfig {
     pub network: NetworkConfig,
     /// Consensus parameters
     pub consensus: ConsensusConfig,
+    /// Mempool admission rules of this node
+    #[serde(default)]
+    pub mempool: MempoolConfig,
     /// Mining configuration
     pub mining: MiningConfig,
     /// Storage settings
@@ -33,8 +54,11 @@ pub struct NodeConfig {
     pub name: String,
     /// Node type (full, light, miner)
     pub node_type: NodeType,
-    /// Enable metrics collection
+    /// Enable metrics collection and the Prometheus `/metrics` endpoint
     pub metrics_enabled: bool,
+    /// Address the `/metrics` endpoint listens on
+    #[serde(default = "default_metrics_address")]
+    pub metrics_address: String,
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
@@ -58,6 +82,9 @@ pub struct NetworkConfig {
     pub max_inbound_peers: usize,
     /// Maximum outbound connections  
     pub max_outbound_peers: usize,
+    /// Maximum peers sharing one /24 (IPv4) or /48 (IPv6) subnet
+    #[serde(default = "default_max_peers_per_subnet")]
+    pub max_peers_per_subnet: usize,
     /// Enable mDNS local discovery
     pub enable_mdns: bool,
     /// Enable Kademlia DHT
@@ -68,15 +95,35 @@ pub struct NetworkConfig {
     pub gossip_heartbeat: u64,
     /// Network ID (mainnet=1)
     pub network_id: u8,
+    /// Hex Ed25519 keys whose checkpoints are trusted for snap sync, in
+    /// addition to the node's own
+    #[serde(default)]
+    pub trusted_checkpoint_signers: Vec<String>,
+    /// Most blocks sent in one sync response; peers further behind fetch
+    /// the rest in successive batches
+    #[serde(default = "default_max_blocks_per_response")]
+    pub max_blocks_per_response: usize,
+    /// Signed checkpoints a starting node may fast-sync from, besides the
+    /// ones it made itself; only those from a trusted signer are used
+    #[serde(default)]
+    pub trusted_checkpoints: Vec<CheckpointConfig>,
 }
 
+/// Consensus parameters.
+///
+/// Block timing: the miner waits `block_time_seconds` after the previous
+/// block before producing the next one, and the chain retargets its
+/// difficulty against the same value (up by one when a block comes sooner,
+/// down by one when later). Difficulty sets the PoW target. `vdf_steps` is
+/// the iteration count of every block's VDF, checked by `add_block`, and the
+/// time parameter of the Wesolowski VDF in `consensus::vdf`.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ConsensusConfig {
-    /// VDF time steps
+    /// Block VDF iterations and Wesolowski VDF time parameter (squarings)
     pub vdf_steps: u64,
     /// Initial PoW difficulty
     pub pow_difficulty: u64,
-    /// Target block time (seconds)
+    /// Target block time (seconds); also the mining time-lock
     pub block_time_seconds: u64,
     /// Difficulty adjustment interval (blocks)
     pub difficulty_adjustment_interval: u64,
@@ -86,8 +133,103 @@ pub struct ConsensusConfig {
     pub max_transactions_per_block: usize,
     /// Minimum transaction fee
     pub min_transaction_fee: u64,
-    /// Block confirmation depth
+    /// Blocks this deep under the tip are final and never reorged; 0 disables
     pub confirmation_depth: u64,
+    /// Fraction of transaction fees burned (0.0-1.0); the rest goes to the miner
+    #[serde(default)]
+    pub fee_burn_fraction: f64,
+    /// Smallest transfer amount relayed or mined (smallest units); 0 disables the check
+    #[serde(default = "default_dust_limit")]
+    pub dust_limit: u64,
+    /// Accept exact-zero amounts (data carriers) despite `dust_limit`
+    #[serde(default)]
+    pub allow_zero_amount: bool,
+    /// Smallest fee per serialized byte accepted into the mempool and blocks
+    #[serde(default = "default_min_fee_per_byte")]
+    pub min_fee_per_byte: u64,
+    /// How fast a waiting transaction gains mining priority over its fee
+    /// rate, per minute in the mempool; 0 orders by fee rate only
+    #[serde(default = "default_mempool_aging_rate")]
+    pub mempool_aging_rate: f64,
+}
+
+impl ConsensusConfig {
+    /// `vdf_steps` as the block VDF's iteration count for
+    /// `Timechain::with_vdf_steps`
+    pub fn block_vdf_steps(&self) -> Result<u32> {
+        u32::try_from(self.vdf_steps)
+            .ok()
+            .filter(|&steps| steps > 0)
+            .ok_or_else(|| AxiomError::InvalidConfig(format!("vdf_steps must be between 1 and {}", u32::MAX)))
+    }
+}
+
+fn default_max_peers_per_subnet() -> usize {
+    DEFAULT_MAX_PEERS_PER_SUBNET
+}
+
+fn default_max_blocks_per_response() -> usize {
+    DEFAULT_MAX_BLOCKS_PER_RESPONSE
+}
+
+fn default_metrics_address() -> String {
+    DEFAULT_METRICS_ADDRESS.to_string()
+}
+
+fn default_dust_limit() -> u64 {
+    DEFAULT_DUST_LIMIT
+}
+
+fn default_min_fee_per_byte() -> u64 {
+    DEFAULT_MIN_FEE_PER_BYTE
+}
+
+fn default_mempool_aging_rate() -> f64 {
+    crate::mempool::DEFAULT_AGING_RATE
+}
+
+/// Admission rules this node adds to the consensus dust and fee checks;
+/// they only decide what it relays and mines, never which blocks it accepts
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct MempoolConfig {
+    /// Hex addresses whose transactions, sent or received, are refused
+    #[serde(default)]
+    pub blacklist: Vec<String>,
+    /// Refuse transactions without a ZK proof
+    #[serde(default)]
+    pub require_zk: bool,
+    /// Most transactions a sender may have pending past its account nonce;
+    /// no cap when unset
+    #[serde(default)]
+    pub max_pending_per_sender: Option<u64>,
+}
+
+impl MempoolConfig {
+    /// The configured rules as one policy for `Mempool::with_policy`; with
+    /// none configured every transaction passes
+    pub fn policy(&self) -> Result<Vec<Box<dyn MempoolPolicy>>> {
+        let mut policy: Vec<Box<dyn MempoolPolicy>> = Vec::new();
+        if !self.blacklist.is_empty() {
+            let addresses = self
+                .blacklist
+                .iter()
+                .map(|hex_address| {
+                    hex::decode(hex_address.trim_start_matches("0x"))
+                        .ok()
+                        .and_then(|bytes| bytes.try_into().ok())
+                        .ok_or_else(|| AxiomError::InvalidConfig(format!("blacklist entry {:?} is not an address", hex_address)))
+                })
+                .collect::<Result<Vec<Address>>>()?;
+            policy.push(Box::new(BlacklistPolicy::new(addresses)));
+        }
+        if self.require_zk {
+            policy.push(Box::new(RequireZkPolicy));
+        }
+        if let Some(max_pending) = self.max_pending_per_sender {
+            policy.push(Box::new(PerSenderCapPolicy { max_pending }));
+        }
+        Ok(policy)
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -96,12 +238,16 @@ pub struct MiningConfig {
     pub enabled: bool,
     /// Number of mining threads
     pub threads: usize,
-    /// Miner address (receives block rewards)
+    /// Hex address credited with block rewards; the node wallet when unset
     pub miner_address: Option<String>,
     /// Mining intensity (1-100)
     pub intensity: u8,
     /// Pause mining if <N peers
     pub min_peers_to_mine: usize,
+    /// Mine without peers, ignoring `min_peers_to_mine` (single-node
+    /// networks; regtest always mines solo)
+    #[serde(default)]
+    pub solo: bool,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -116,6 +262,20 @@ pub struct StorageConfig {
     pub pruning: PruningMode,
     /// Maximum database size (GB)
     pub max_db_size_gb: u64,
+    /// Groth16 proving/verifying keys; generated here on first start
+    #[serde(default = "default_zk_keys_dir")]
+    pub zk_keys_dir: PathBuf,
+    /// Decoded blocks kept in the read cache
+    #[serde(default = "default_block_cache_capacity")]
+    pub block_cache_capacity: usize,
+}
+
+fn default_block_cache_capacity() -> usize {
+    crate::cache::DEFAULT_BLOCK_CACHE_CAPACITY
+}
+
+fn default_zk_keys_dir() -> PathBuf {
+    PathBuf::from("./zk_keys")
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
@@ -148,7 +308,7 @@ pub struct RpcConfig {
     pub enabled: bool,
     /// RPC listen address
     pub listen_address: String,
-    /// CORS allowed origins
+    /// CORS allowed origins ("*" for any, empty denies cross-origin requests)
     pub cors_allowed_origins: Vec<String>,
     /// Maximum concurrent connections
     pub max_connections: usize,
@@ -158,6 +318,27 @@ pub struct RpcConfig {
     pub websocket_enabled: bool,
     /// Rate limiting (requests per minute)
     pub rate_limit: u64,
+    /// Proofs `generate_zk_proof` may compute at once
+    #[serde(default = "default_max_concurrent_proofs")]
+    pub max_concurrent_proofs: usize,
+    /// Proof requests allowed to wait for a slot; beyond this they get `SERVER_BUSY`
+    #[serde(default = "default_max_queued_proofs")]
+    pub max_queued_proofs: usize,
+    /// Largest proof returned inline; bigger ones are fetched with `get_proof_chunk`
+    #[serde(default = "default_proof_chunk_size")]
+    pub proof_chunk_size: usize,
+}
+
+fn default_max_concurrent_proofs() -> usize {
+    crate::rpc::proof_queue::DEFAULT_MAX_CONCURRENT_PROOFS
+}
+
+fn default_max_queued_proofs() -> usize {
+    crate::rpc::proof_queue::DEFAULT_MAX_QUEUED_PROOFS
+}
+
+fn default_proof_chunk_size() -> usize {
+    crate::rpc::proof_store::DEFAULT_PROOF_CHUNK_SIZE
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -187,6 +368,7 @@ impl Default for NodeConfig {
             name: format!("axiom-node-{}", rand::random::<u16>()),
             node_type: NodeType::Full,
             metrics_enabled: true,
+            metrics_address: default_metrics_address(),
         }
     }
 }
@@ -199,15 +381,90 @@ impl Default for NetworkConfig {
             max_peers: 50,
             max_inbound_peers: 30,
             max_outbound_peers: 20,
+            max_peers_per_subnet: DEFAULT_MAX_PEERS_PER_SUBNET,
             enable_mdns: true,
             enable_kademlia: true,
             connection_timeout: 30,
             gossip_heartbeat: 1,
             network_id: 1,
+            trusted_checkpoint_signers: vec![],
+            max_blocks_per_response: DEFAULT_MAX_BLOCKS_PER_RESPONSE,
+            trusted_checkpoints: vec![],
+        }
+    }
+}
+
+/// A signed checkpoint as written in `[[network.trusted_checkpoints]]`,
+/// hashes and keys in hex
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CheckpointConfig {
+    pub height: u64,
+    pub block_hash: String,
+    pub state_root: String,
+    pub difficulty: u64,
+    pub signer: String,
+    pub signature: String,
+}
+
+impl CheckpointConfig {
+    /// The checkpoint, provided every field decodes and the signature is valid
+    pub fn checkpoint(&self) -> Result<crate::storage::Checkpoint> {
+        let invalid = || AxiomError::InvalidConfig(format!("checkpoint at height {} is invalid", self.height));
+        let bytes32 = |hex_value: &str| -> Result<[u8; 32]> {
+            hex::decode(hex_value.trim_start_matches("0x"))
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(invalid)
+        };
+        let checkpoint = crate::storage::Checkpoint {
+            height: self.height,
+            block_hash: bytes32(&self.block_hash)?,
+            state_root: bytes32(&self.state_root)?,
+            difficulty: self.difficulty,
+            signer: bytes32(&self.signer)?,
+            signature: hex::decode(self.signature.trim_start_matches("0x")).map_err(|_| invalid())?,
+        };
+        if !checkpoint.verify() {
+            return Err(invalid());
+        }
+        Ok(checkpoint)
+    }
+}
+
+impl From<&crate::storage::Checkpoint> for CheckpointConfig {
+    fn from(checkpoint: &crate::storage::Checkpoint) -> Self {
+        Self {
+            height: checkpoint.height,
+            block_hash: hex::encode(checkpoint.block_hash),
+            state_root: hex::encode(checkpoint.state_root),
+            difficulty: checkpoint.difficulty,
+            signer: hex::encode(checkpoint.signer),
+            signature: hex::encode(&checkpoint.signature),
         }
     }
 }
 
+impl NetworkConfig {
+    /// `trusted_checkpoints` decoded and signature-checked
+    pub fn checkpoints(&self) -> Result<Vec<crate::storage::Checkpoint>> {
+        self.trusted_checkpoints.iter().map(CheckpointConfig::checkpoint).collect()
+    }
+
+    /// `trusted_checkpoint_signers` decoded: 32 bytes of hex each,
+    /// optionally `0x`-prefixed
+    pub fn checkpoint_signers(&self) -> Result<Vec<[u8; 32]>> {
+        self.trusted_checkpoint_signers
+            .iter()
+            .map(|hex_key| {
+                hex::decode(hex_key.trim_start_matches("0x"))
+                    .ok()
+                    .and_then(|bytes| bytes.try_into().ok())
+                    .ok_or_else(|| AxiomError::InvalidConfig(format!("checkpoint signer {:?} is not a public key", hex_key)))
+            })
+            .collect()
+    }
+}
+
 impl Default for ConsensusConfig {
     fn default() -> Self {
         Self {
@@ -215,11 +472,35 @@ impl Default for ConsensusConfig {
             pow_difficulty: 1000,
             block_time_seconds: 1800,
             difficulty_adjustment_interval: 2016,
-            max_block_size: 1_000_000,
-            max_transactions_per_block: 10_000,
+            max_block_size: crate::chain::MAX_BLOCK_SIZE,
+            max_transactions_per_block: crate::chain::MAX_TRANSACTIONS_PER_BLOCK,
             min_transaction_fee: 100_000_000,
             confirmation_depth: 6,
+            fee_burn_fraction: 0.0,
+            dust_limit: DEFAULT_DUST_LIMIT,
+            allow_zero_amount: false,
+            min_fee_per_byte: DEFAULT_MIN_FEE_PER_BYTE,
+            mempool_aging_rate: crate::mempool::DEFAULT_AGING_RATE,
+        }
+    }
+}
+
+impl MiningConfig {
+    /// `miner_address` decoded: 32 bytes of hex, optionally `0x`-prefixed,
+    /// other than the coinbase sender. `None` when unset.
+    pub fn reward_address(&self) -> Result<Option<Address>> {
+        let Some(hex_address) = &self.miner_address else {
+            return Ok(None);
+        };
+        let invalid = || AxiomError::InvalidConfig(format!("miner_address {:?} is not a valid address", hex_address));
+        let address: Address = hex::decode(hex_address.trim_start_matches("0x"))
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(invalid)?;
+        if address == COINBASE_SENDER {
+            return Err(invalid());
         }
+        Ok(Some(address))
     }
 }
 
@@ -231,6 +512,7 @@ impl Default for MiningConfig {
             miner_address: None,
             intensity: 80,
             min_peers_to_mine: 3,
+            solo: false,
         }
     }
 }
@@ -243,6 +525,8 @@ impl Default for StorageConfig {
             compression: true,
             pruning: PruningMode::Full,
             max_db_size_gb: 0,
+            zk_keys_dir: default_zk_keys_dir(),
+            block_cache_capacity: crate::cache::DEFAULT_BLOCK_CACHE_CAPACITY,
         }
     }
 }
@@ -270,6 +554,9 @@ impl Default for RpcConfig {
             request_timeout: 30,
             websocket_enabled: true,
             rate_limit: 60,
+            max_concurrent_proofs: crate::rpc::proof_queue::DEFAULT_MAX_CONCURRENT_PROOFS,
+            max_queued_proofs: crate::rpc::proof_queue::DEFAULT_MAX_QUEUED_PROOFS,
+            proof_chunk_size: crate::rpc::proof_store::DEFAULT_PROOF_CHUNK_SIZE,
         }
     }
 }
@@ -323,16 +610,27 @@ impl AxiomConfig {
             return Err(AxiomError::InvalidConfig("max_peers must be > 0".to_string()));
         }
         
-        if self.consensus.vdf_steps == 0 {
-            return Err(AxiomError::InvalidConfig("vdf_steps must be > 0".to_string()));
+        self.validate_network()?;
+        
+        self.consensus.block_vdf_steps()?;
+        
+        if !(0.0..=1.0).contains(&self.consensus.fee_burn_fraction) {
+            return Err(AxiomError::InvalidConfig(
+                "fee_burn_fraction must be between 0.0 and 1.0".to_string()
+            ));
         }
         
-        if self.mining.enabled && self.mining.miner_address.is_none() {
+        if let Some(origin) = self.rpc.cors_allowed_origins.iter()
+            .find(|o| !crate::rpc::cors::is_valid_origin(o))
+        {
             return Err(AxiomError::InvalidConfig(
-                "miner_address required when mining enabled".to_string()
+                format!("invalid cors_allowed_origins entry: {:?}", origin)
             ));
         }
         
+        self.validate_mining()?;
+        self.validate_mempool()?;
+        
         if !self.storage.data_dir.exists() {
             std::fs::create_dir_all(&self.storage.data_dir)?;
         }
@@ -340,6 +638,61 @@ impl AxiomConfig {
         Ok(())
     }
     
+    /// Whether this node runs a local regtest network
+    pub fn is_regtest(&self) -> bool {
+        self.network.network_id == REGTEST_NETWORK_ID
+    }
+    
+    /// Mainnet must keep mainnet timing: a shortened block time, lowered
+    /// difficulty or different VDF length is only accepted on other networks. Trusted checkpoint
+    /// signers must be valid keys and trusted checkpoints validly signed.
+    pub fn validate_network(&self) -> Result<()> {
+        let mainnet = ConsensusConfig::default();
+        if self.network.network_id == MAINNET_NETWORK_ID
+            && (self.consensus.block_time_seconds < mainnet.block_time_seconds
+                || self.consensus.pow_difficulty < mainnet.pow_difficulty
+                || self.consensus.vdf_steps != mainnet.vdf_steps)
+        {
+            return Err(AxiomError::InvalidConfig(format!(
+                "network_id {} is mainnet; block_time_seconds >= {}, pow_difficulty >= {} and vdf_steps = {} \
+                 required (use network_id {} for regtest)",
+                MAINNET_NETWORK_ID, mainnet.block_time_seconds, mainnet.pow_difficulty, mainnet.vdf_steps, REGTEST_NETWORK_ID
+            )));
+        }
+        if self.network.max_blocks_per_response == 0 {
+            return Err(AxiomError::InvalidConfig("max_blocks_per_response must be at least 1".to_string()));
+        }
+        self.network.checkpoint_signers()?;
+        self.network.checkpoints().map(|_| ())
+    }
+    
+    /// Reject a `miner_address` that is set but not an address. An unset
+    /// one is fine: rewards go to the node wallet.
+    pub fn validate_mining(&self) -> Result<()> {
+        self.mining.reward_address().map(|_| ())
+    }
+    
+    /// Reject a mempool blacklist entry that is not an address
+    pub fn validate_mempool(&self) -> Result<()> {
+        self.mempool.policy().map(|_| ())
+    }
+    
+    /// Create regtest configuration: isolated local network, 2-second blocks
+    /// at minimum difficulty, mineable on demand over RPC
+    pub fn regtest() -> Self {
+        let mut config = Self::default();
+        config.network.network_id = REGTEST_NETWORK_ID;
+        config.network.bootstrap_peers = vec![];
+        config.network.enable_mdns = false;
+        config.network.enable_kademlia = false;
+        config.consensus.vdf_steps = 1;
+        config.consensus.block_time_seconds = 2;
+        config.consensus.pow_difficulty = 1;
+        config.mining.min_peers_to_mine = 0;
+        config.storage.data_dir = PathBuf::from("./axiom-regtest-data");
+        config
+    }
+    
     /// Create validator configuration (mainnet with archive mode)
     pub fn validator() -> Self {
         let mut config = Self::default();
@@ -386,4 +739,165 @@ mod tests {
         let config = AxiomConfig::default();
         assert!(config.validate().is_ok());
     }
+    
+    #[test]
+    fn test_fee_burn_fraction_range() {
+        let mut config = AxiomConfig::default();
+        for fraction in [0.0, 0.5, 1.0] {
+            config.consensus.fee_burn_fraction = fraction;
+            assert!(config.validate().is_ok(), "{}", fraction);
+        }
+        for fraction in [-0.1, 1.5, f64::NAN] {
+            config.consensus.fee_burn_fraction = fraction;
+            assert!(matches!(config.validate(), Err(AxiomError::InvalidConfig(_))), "{}", fraction);
+        }
+    }
+    
+    #[test]
+    fn test_vdf_steps_range() {
+        let mut config = AxiomConfig::regtest();
+        assert_eq!(config.consensus.block_vdf_steps().unwrap(), 1);
+        config.consensus.vdf_steps = u32::MAX as u64;
+        assert_eq!(config.consensus.block_vdf_steps().unwrap(), u32::MAX);
+        for steps in [0, u32::MAX as u64 + 1] {
+            config.consensus.vdf_steps = steps;
+            assert!(matches!(config.validate(), Err(AxiomError::InvalidConfig(_))), "{}", steps);
+        }
+
+        // Mainnet blocks are only valid at mainnet's VDF length
+        let mut mainnet = AxiomConfig::default();
+        mainnet.consensus.vdf_steps += 1;
+        assert!(matches!(mainnet.validate_network(), Err(AxiomError::InvalidConfig(_))));
+    }
+    
+    #[test]
+    fn test_regtest_guarded_from_mainnet() {
+        let regtest = AxiomConfig::regtest();
+        assert!(regtest.is_regtest());
+        assert!(regtest.validate_network().is_ok());
+        
+        let mut on_mainnet = regtest;
+        on_mainnet.network.network_id = MAINNET_NETWORK_ID;
+        assert!(!on_mainnet.is_regtest());
+        assert!(matches!(on_mainnet.validate_network(), Err(AxiomError::InvalidConfig(_))));
+    }
+    
+    #[test]
+    fn test_miner_address_validation() {
+        let mut config = AxiomConfig::default();
+        config.mining.enabled = true;
+        assert_eq!(config.mining.reward_address().unwrap(), None);
+        assert!(config.validate_mining().is_ok());
+
+        config.mining.miner_address = Some(format!("0x{}", "ab".repeat(32)));
+        assert_eq!(config.mining.reward_address().unwrap(), Some([0xab; 32]));
+        config.mining.miner_address = Some("cd".repeat(32));
+        assert_eq!(config.mining.reward_address().unwrap(), Some([0xcd; 32]));
+        assert!(config.validate().is_ok());
+
+        for bad in ["ab".repeat(31), "zz".repeat(32), "00".repeat(32), String::new()] {
+            config.mining.miner_address = Some(bad.clone());
+            assert!(matches!(config.validate_mining(), Err(AxiomError::InvalidConfig(_))), "{:?}", bad);
+            assert!(config.validate().is_err());
+        }
+    }
+    
+    #[test]
+    fn test_checkpoint_signers_validation() {
+        let mut config = AxiomConfig::default();
+        assert_eq!(config.network.checkpoint_signers().unwrap(), Vec::<[u8; 32]>::new());
+        config.network.trusted_checkpoint_signers = vec!["ab".repeat(32), format!("0x{}", "cd".repeat(32))];
+        assert_eq!(config.network.checkpoint_signers().unwrap(), vec![[0xab; 32], [0xcd; 32]]);
+        assert!(config.validate_network().is_ok());
+
+        config.network.trusted_checkpoint_signers.push("ab".repeat(31));
+        assert!(matches!(config.validate_network(), Err(AxiomError::InvalidConfig(_))));
+    }
+    
+    #[test]
+    fn test_mempool_policy_from_toml() {
+        use crate::state::State;
+        use crate::transaction::Transaction;
+        // Files written before the section accept everything
+        let toml_text = toml::to_string(&AxiomConfig::default()).unwrap();
+        let section = "[mempool]\nblacklist = []\nrequire_zk = false\n";
+        assert!(toml_text.contains(section));
+        let config: AxiomConfig = toml::from_str(&toml_text.replace(section, "")).unwrap();
+        assert!(config.mempool.policy().unwrap().is_empty());
+
+        let toml_text = toml_text.replace(
+            section,
+            &format!("[mempool]\nblacklist = [\"0x{}\"]\nrequire_zk = false\nmax_pending_per_sender = 1\n", "09".repeat(32)),
+        );
+        let mut config: AxiomConfig = toml::from_str(&toml_text).unwrap();
+        assert!(config.validate_mempool().is_ok());
+        let policy = config.mempool.policy().unwrap();
+        assert_eq!(policy.len(), 2);
+
+        let state = State::new();
+        let tx = |to: Address, nonce: u64| Transaction::new([1u8; 32], to, 100, 10, nonce, vec![], vec![]);
+        assert!(policy.accept(&tx([2u8; 32], 0), &state).is_ok());
+        for rejected in [tx([9u8; 32], 0), tx([2u8; 32], 1)] {
+            assert!(matches!(policy.accept(&rejected, &state), Err(AxiomError::PolicyRejected(_))));
+        }
+        config.mempool.require_zk = true;
+        assert!(matches!(config.mempool.policy().unwrap().accept(&tx([2u8; 32], 0), &state), Err(AxiomError::PolicyRejected(_))));
+
+        config.mempool.blacklist.push("ab".repeat(31));
+        assert!(matches!(config.validate_mempool(), Err(AxiomError::InvalidConfig(_))));
+        assert!(config.validate().is_err());
+    }
+    
+    #[test]
+    fn test_trusted_checkpoints_from_toml() {
+        let key = ed25519_dalek::SigningKey::from_bytes(&[4u8; 32]);
+        let checkpoint = crate::storage::Checkpoint::sign(1_000, [1u8; 32], [2u8; 32], 50, &key);
+        let mut config = AxiomConfig::default();
+        config.network.trusted_checkpoints.push(CheckpointConfig::from(&checkpoint));
+
+        let reloaded: AxiomConfig = toml::from_str(&toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(reloaded.network.checkpoints().unwrap(), vec![checkpoint]);
+        assert!(reloaded.validate_network().is_ok());
+
+        // A checkpoint edited after signing is refused
+        config.network.trusted_checkpoints[0].height = 1_001;
+        assert!(matches!(config.validate_network(), Err(AxiomError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_dust_limit_from_toml() {
+        let mut toml_text = toml::to_string(&AxiomConfig::default()).unwrap();
+        toml_text = toml_text.replace("dust_limit = 1000\n", "").replace("allow_zero_amount = false\n", "");
+        // Files written before the dust policy get the default
+        let config: AxiomConfig = toml::from_str(&toml_text).unwrap();
+        assert_eq!(config.consensus.dust_limit, DEFAULT_DUST_LIMIT);
+        assert!(!config.consensus.allow_zero_amount);
+        
+        let toml_text = toml_text.replace("[consensus]\n", "[consensus]\ndust_limit = 5000\nallow_zero_amount = true\n");
+        let config: AxiomConfig = toml::from_str(&toml_text).unwrap();
+        assert_eq!(config.consensus.dust_limit, 5000);
+        assert!(config.consensus.allow_zero_amount);
+    }
+
+    #[test]
+    fn test_mempool_aging_rate_from_toml() {
+        let toml_text = toml::to_string(&AxiomConfig::default()).unwrap().replace("mempool_aging_rate = 0.01\n", "");
+        let config: AxiomConfig = toml::from_str(&toml_text).unwrap();
+        assert_eq!(config.consensus.mempool_aging_rate, crate::mempool::DEFAULT_AGING_RATE);
+
+        let toml_text = toml_text.replace("[consensus]\n", "[consensus]\nmempool_aging_rate = 0.0\n");
+        let config: AxiomConfig = toml::from_str(&toml_text).unwrap();
+        assert_eq!(config.consensus.mempool_aging_rate, 0.0);
+    }
+
+    #[test]
+    fn test_max_blocks_per_response() {
+        let toml_text = toml::to_string(&AxiomConfig::default()).unwrap();
+        let toml_text = toml_text.replace(&format!("max_blocks_per_response = {}\n", DEFAULT_MAX_BLOCKS_PER_RESPONSE), "");
+        let mut config: AxiomConfig = toml::from_str(&toml_text).unwrap();
+        assert_eq!(config.network.max_blocks_per_response, DEFAULT_MAX_BLOCKS_PER_RESPONSE);
+
+        config.network.max_blocks_per_response = 0;
+        assert!(matches!(config.validate_network(), Err(AxiomError::InvalidConfig(_))));
+    }
 }
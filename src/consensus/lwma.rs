@@ -13,11 +13,16 @@ pub const LWMA_WINDOW: usize = 60;
 /// Minimum difficulty
 pub const MIN_DIFFICULTY: u64 = 1000;
 
-/// Maximum difficulty adjustment per block (300% = 3x)
-pub const MAX_ADJUSTMENT_FACTOR: f64 = 3.0;
+/// Maximum difficulty: 2^48, about 670 EH/s at the target block time
+pub const MAX_DIFFICULTY: u64 = 1 << 48;
 
-/// Minimum difficulty adjustment per block (33% = 1/3)
-pub const MIN_ADJUSTMENT_FACTOR: f64 = 0.33;
+/// Maximum change per retarget: at most 4x up or 1/4 down from the last
+/// block's difficulty, however skewed the window's timestamps are
+pub const MAX_RETARGET_FACTOR: u64 = 4;
+
+/// How far a claimed difficulty may stray from the LWMA value, in percent,
+/// to absorb rounding in other implementations of the calculation
+pub const DIFFICULTY_TOLERANCE_PERCENT: u64 = 1;
 
 /// Simple block header for difficulty calculation
 #[derive(Debug, Clone)]
@@ -27,6 +32,19 @@ pub struct BlockHeader {
     pub difficulty: BigUint,
 }
 
+impl BlockHeader {
+    /// Header at height 0; the calculation only reads timestamps and
+    /// difficulties, so windows built for it need no heights
+    pub fn new(timestamp: u64, difficulty: u64) -> Self {
+        Self { height: 0, timestamp, difficulty: BigUint::from(difficulty) }
+    }
+
+    pub fn with_height(mut self, height: u64) -> Self {
+        self.height = height;
+        self
+    }
+}
+
 /// Calculate next difficulty using LWMA
 pub fn calculate_lwma_difficulty(block_headers: &[BlockHeader]) -> BigUint {
     if block_headers.len() < LWMA_WINDOW + 1 {
@@ -36,6 +54,7 @@ pub fn calculate_lwma_difficulty(block_headers: &[BlockHeader]) -> BigUint {
     let start_idx = block_headers.len().saturating_sub(LWMA_WINDOW + 1);
     let window = &block_headers[start_idx..];
     
+    // Each gap counts at least 1s, so this is never zero
     let mut weighted_times: u64 = 0;
     let mut sum_difficulties = BigUint::zero();
     
@@ -58,19 +77,22 @@ pub fn calculate_lwma_difficulty(block_headers: &[BlockHeader]) -> BigUint {
     
     let avg_difficulty = sum_difficulties / LWMA_WINDOW;
     
-    let new_difficulty = if weighted_times == 0 || expected_times == 0 {
-        avg_difficulty
-    } else {
-        let adjustment = weighted_times as f64 / expected_times as f64;
-        let clamped_adjustment = adjustment
-            .max(MIN_ADJUSTMENT_FACTOR)
-            .min(MAX_ADJUSTMENT_FACTOR);
-        
-        let adjusted = avg_difficulty.to_f64().unwrap_or(MIN_DIFFICULTY as f64) * clamped_adjustment;
-        BigUint::from(adjusted as u64)
-    };
+    // Blocks faster than target raise difficulty, slower ones lower it
+    let new_difficulty = avg_difficulty * expected_times / weighted_times;
     
-    new_difficulty.max(BigUint::from(MIN_DIFFICULTY))
+    let previous = &window[LWMA_WINDOW].difficulty;
+    new_difficulty
+        .clamp(previous / MAX_RETARGET_FACTOR, previous * MAX_RETARGET_FACTOR)
+        .clamp(BigUint::from(MIN_DIFFICULTY), BigUint::from(MAX_DIFFICULTY))
+}
+
+/// Whether `claimed` is the difficulty LWMA prescribes after `window`,
+/// within `DIFFICULTY_TOLERANCE_PERCENT`
+pub fn validate_next_difficulty(window: &[BlockHeader], claimed: u32) -> bool {
+    let expected = calculate_lwma_difficulty(window);
+    let claimed = BigUint::from(claimed);
+    let deviation = if claimed > expected { &claimed - &expected } else { &expected - &claimed };
+    deviation * 100u32 <= expected * DIFFICULTY_TOLERANCE_PERCENT
 }
 
 /// Convert difficulty to target
@@ -194,6 +216,71 @@ mod tests {
         assert!(new_diff >= BigUint::from(MIN_DIFFICULTY));
     }
     
+    #[test]
+    fn test_extreme_timing_clamped_to_retarget_factor() {
+        // Every block one second apart: the raw LWMA would jump ~1800x
+        let fast = create_test_headers(100, 1, 100_000);
+        assert_eq!(calculate_lwma_difficulty(&fast), BigUint::from(400_000u64));
+        
+        // A timestamp pushed years ahead lowers it by at most 1/4
+        let mut skewed = create_test_headers(100, TARGET_BLOCK_TIME, 100_000);
+        skewed.last_mut().unwrap().timestamp += 100 * 365 * 24 * 3600;
+        assert_eq!(calculate_lwma_difficulty(&skewed), BigUint::from(25_000u64));
+        
+        // A timestamp before its parent counts as a one-second gap
+        let mut backwards = create_test_headers(100, TARGET_BLOCK_TIME, 100_000);
+        backwards.last_mut().unwrap().timestamp = 0;
+        let diff = calculate_lwma_difficulty(&backwards);
+        assert!(diff > BigUint::from(100_000u64) && diff <= BigUint::from(400_000u64));
+    }
+    
+    #[test]
+    fn test_difficulty_stays_within_bounds() {
+        let near_max = create_test_headers(100, 1, MAX_DIFFICULTY / 2);
+        assert_eq!(calculate_lwma_difficulty(&near_max), BigUint::from(MAX_DIFFICULTY));
+        
+        let mut headers = create_test_headers(100, TARGET_BLOCK_TIME, MAX_DIFFICULTY);
+        headers.last_mut().unwrap().difficulty = BigUint::from(u64::MAX) * 16u32;
+        assert_eq!(calculate_lwma_difficulty(&headers), BigUint::from(MAX_DIFFICULTY));
+        
+        // Hashrate collapsing retarget after retarget bottoms out at the floor
+        let mut difficulty = 100_000u64;
+        for _ in 0..10 {
+            let slow = create_test_headers(100, TARGET_BLOCK_TIME * 100, difficulty);
+            let next = calculate_lwma_difficulty(&slow).to_u64().unwrap();
+            assert!(next >= MIN_DIFFICULTY);
+            assert!(next >= difficulty / MAX_RETARGET_FACTOR);
+            difficulty = next;
+        }
+        assert_eq!(difficulty, MIN_DIFFICULTY);
+    }
+    
+    #[test]
+    fn test_validate_next_difficulty() {
+        // Blocks three times faster than target for the whole window
+        let window: Vec<BlockHeader> = (0..=LWMA_WINDOW as u64)
+            .map(|i| BlockHeader::new(1_700_000_000 + i * TARGET_BLOCK_TIME / 3, 100_000).with_height(i))
+            .collect();
+        let expected = calculate_lwma_difficulty(&window).to_u32().unwrap();
+        assert_eq!(expected, 300_000);
+        assert!(validate_next_difficulty(&window, expected));
+        assert!(validate_next_difficulty(&window, expected + expected / 100));
+        assert!(validate_next_difficulty(&window, expected - expected / 100));
+
+        // Just past the tolerance either way, or keeping the old difficulty
+        assert!(!validate_next_difficulty(&window, expected + expected / 100 + 1));
+        assert!(!validate_next_difficulty(&window, expected - expected / 100 - 1));
+        assert!(!validate_next_difficulty(&window, 100_000));
+
+        // Too little history prescribes the minimum
+        assert!(validate_next_difficulty(&window[..10], MIN_DIFFICULTY as u32));
+        assert!(!validate_next_difficulty(&window[..10], 300_000));
+
+        // No u32 claim matches a difficulty beyond its range
+        let hard = create_test_headers(100, TARGET_BLOCK_TIME, 1 << 40);
+        assert!(!validate_next_difficulty(&hard, u32::MAX));
+    }
+    
     #[test]
     fn test_flash_mining_detection() {
         let normal = create_test_headers(70, TARGET_BLOCK_TIME, 100_000);
@@ -2,9 +2,10 @@
 pub mod vdf;
 pub mod lwma;
 
-pub use vdf::{VDF, VDFProof, VDFBlockHeader};
+pub use vdf::{verify_vdf_local, VDF, VDFProof, VDFBlockHeader};
 pub use lwma::{
     calculate_lwma_difficulty,
+    validate_next_difficulty,
     detect_flash_mining,
     estimate_hashrate,
     format_hashrate,
@@ -14,4 +15,7 @@ pub use lwma::{
     TARGET_BLOCK_TIME,
     LWMA_WINDOW,
     MIN_DIFFICULTY,
+    MAX_DIFFICULTY,
+    MAX_RETARGET_FACTOR,
+    DIFFICULTY_TOLERANCE_PERCENT,
 };
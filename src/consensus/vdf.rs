@@ -1,5 +1,14 @@
 // src/consensus/vdf.rs - Verifiable Delay Function for AXIOM Protocol
 // Implements Wesolowski VDF for deterministic, sequential proof-of-time
+//
+// Construction (all integers big-endian when serialized):
+//   x  = SHA256(input) mod N                 N = RSA-2048 modulus
+//   y  = x^(2^T) mod N                       T = time_param (consensus.vdf_steps)
+//   l  = SHA256(x || y), forced odd          Fiat-Shamir challenge
+//   pi = x^floor(2^T / l) mod N
+// Verification checks pi^l * x^(2^T mod l) == y (mod N), which costs two
+// modular exponentiations instead of T squarings. `verify_vdf_local` runs
+// this check with no node involved.
 
 use num_bigint::BigUint;
 use num_traits::One;
@@ -7,6 +16,7 @@ use num_integer::Integer;
 use serde::{Serialize, Deserialize, Serializer, Deserializer};
 use sha2::{Sha256, Digest};
 use std::time::{Duration, Instant};
+use crate::config::ConsensusConfig;
 
 // Custom serialization for BigUint
 fn serialize_biguint<S>(value: &BigUint, serializer: S) -> Result<S::Ok, S::Error>
@@ -63,6 +73,11 @@ impl VDF {
         
         Self::new(modulus, time_param)
     }
+
+    /// VDF with the default modulus and the configured `vdf_steps`
+    pub fn from_config(consensus: &ConsensusConfig) -> Self {
+        Self::with_default_modulus(consensus.vdf_steps)
+    }
     
     /// Compute VDF: y = x^(2^T) mod N
     /// This is SLOW by design (sequential squaring)
@@ -193,6 +208,20 @@ impl VDF {
     }
 }
 
+/// Verify a Wesolowski proof locally against the default modulus, without
+/// trusting any node. `output` and `proof` are the big-endian encodings of
+/// `VDFProof::output` and `VDFProof::proof`.
+pub fn verify_vdf_local(output: &[u8], proof: &[u8], input: &[u8], time_param: u64) -> bool {
+    let vdf = VDF::with_default_modulus(time_param);
+    let output = BigUint::from_bytes_be(output);
+    let proof = BigUint::from_bytes_be(proof);
+    // Values outside the group would verify modulo N despite differing bytes
+    if output >= vdf.modulus || proof >= vdf.modulus {
+        return false;
+    }
+    vdf.verify(input, &VDFProof { output, proof }).unwrap_or(false)
+}
+
 /// VDF-based block header
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VDFBlockHeader {
@@ -209,11 +238,8 @@ impl VDFBlockHeader {
         timestamp: u64,
         vdf: &VDF,
     ) -> Result<Self, String> {
-        // VDF input = H(prev_hash || timestamp)
-        let mut hasher = Sha256::new();
-        hasher.update(prev_block_hash);
-        hasher.update(timestamp.to_le_bytes());
-        let vdf_input = hasher.finalize().to_vec();
+        // VDF input = H(prev_hash || timestamp), tagged like every VDF seed
+        let vdf_input = crate::hashing::tagged_sha256(crate::hashing::VDF, &[&prev_block_hash, &timestamp.to_le_bytes()]).to_vec();
         
         println!("Mining block with VDF...");
         let vdf_proof = vdf.compute(&vdf_input)?;
@@ -229,10 +255,8 @@ impl VDFBlockHeader {
     /// Verify block VDF proof
     pub fn verify(&self, vdf: &VDF) -> Result<bool, String> {
         // Recompute VDF input
-        let mut hasher = Sha256::new();
-        hasher.update(self.prev_block_hash);
-        hasher.update(self.timestamp.to_le_bytes());
-        let expected_input = hasher.finalize().to_vec();
+        let expected_input =
+            crate::hashing::tagged_sha256(crate::hashing::VDF, &[&self.prev_block_hash, &self.timestamp.to_le_bytes()]).to_vec();
         
         if self.vdf_input != expected_input {
             return Ok(false);
@@ -294,6 +318,38 @@ mod tests {
         println!("✓ VDF block valid!");
     }
     
+    #[test]
+    fn test_verify_vdf_local() {
+        let time_param = 2_000;
+        let input = b"axiom_block_input";
+        let proof = VDF::with_default_modulus(time_param).compute(input).expect("Compute failed");
+        let output = proof.output.to_bytes_be();
+        let pi = proof.proof.to_bytes_be();
+
+        assert!(verify_vdf_local(&output, &pi, input, time_param));
+
+        // Tampered output or proof, or a different input or time parameter
+        let mut bad_output = output.clone();
+        *bad_output.last_mut().unwrap() ^= 1;
+        assert!(!verify_vdf_local(&bad_output, &pi, input, time_param));
+        let mut bad_pi = pi.clone();
+        bad_pi[0] ^= 1;
+        assert!(!verify_vdf_local(&output, &bad_pi, input, time_param));
+        assert!(!verify_vdf_local(&output, &pi, b"other_input", time_param));
+        assert!(!verify_vdf_local(&output, &pi, input, time_param + 1));
+
+        // Same residue, shifted out of range by N
+        let vdf = VDF::with_default_modulus(time_param);
+        let aliased = (&proof.output + &vdf.modulus).to_bytes_be();
+        assert!(!verify_vdf_local(&aliased, &pi, input, time_param));
+    }
+
+    #[test]
+    fn test_vdf_from_config() {
+        let consensus = ConsensusConfig { vdf_steps: 1_234, ..ConsensusConfig::default() };
+        assert_eq!(VDF::from_config(&consensus).time_param, 1_234);
+    }
+
     #[test]
     #[ignore] // Slow test - run manually
     fn test_vdf_calibration() {
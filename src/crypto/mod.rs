@@ -10,16 +10,24 @@ pub mod quantum_signatures;
 
 pub use quantum_safe_stark::{
     QuantumSafeStarkProver,
+    CachingStarkProver,
+    ProofCacheStats,
     QuantumSafeStarkVerifier,
+    ProofLimits,
     StarkProof,
     TransactionWitness,
     PublicInputs,
     QuantumSafeHash,
     StarkError,
+    StarkHasher,
+    Blake3Hasher,
+    Sha3Hasher,
     quantum_safe_hash,
 };
 
 pub use quantum_signatures::{
+    AttestationBatch,
+    CompactSignature,
     QuantumSafeSignatures,
     PublicKey as QuantumPublicKey,
     SecretKey as QuantumSecretKey,
@@ -29,6 +37,27 @@ pub use quantum_signatures::{
 };
 
 use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Compare `a` and `b` in time that depends only on their lengths, not on
+/// where they first differ. Use it wherever one side is a secret or is
+/// derived from one, so a forger cannot learn how many leading bytes of a
+/// guess were right:
+///
+/// - the Dilithium challenge `c_tilde` in [`QuantumSafeSignatures::verify`]
+/// - the keystore MAC checked by `Wallet::from_keystore_json`
+/// - the hash fallback of `zk::verify_zk_pass`
+///
+/// AES-GCM tags (view keys, encrypted wallets) are checked in constant time
+/// by `aes-gcm` itself, and Ed25519 signatures by `ed25519-dalek`.
+/// Comparisons of public data, such as Merkle roots and block hashes, need
+/// not use this.
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    a.ct_eq(b).into()
+}
 
 /// Complete quantum-safe transaction proof
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -184,7 +213,9 @@ impl QuantumTransactionVerifier {
         Ok(stark_valid)
     }
     
-    /// Batch verify multiple transactions
+    /// Batch verify multiple transactions, one result per proof in input
+    /// order. A proof whose verification errors counts as invalid. With the
+    /// `parallel` feature the proofs are verified concurrently.
     pub fn batch_verify_transactions(
         &self,
         proofs: &[QuantumSafeTransactionProof],
@@ -193,28 +224,88 @@ impl QuantumTransactionVerifier {
         amounts: &[u64],
         nonces: &[u64],
     ) -> Result<Vec<bool>, String> {
-        if proofs.len() != senders.len() || 
-           proofs.len() != receivers.len() ||
-           proofs.len() != amounts.len() ||
-           proofs.len() != nonces.len() {
-            return Err("Mismatched input lengths".to_string());
-        }
+        check_batch_lengths(proofs.len(), senders, receivers, amounts, nonces)?;
         
-        let mut results = Vec::new();
-        
-        for i in 0..proofs.len() {
-            let valid = self.verify_transaction(
-                &proofs[i],
-                &senders[i],
-                &receivers[i],
-                amounts[i],
-                nonces[i],
-            )?;
-            results.push(valid);
-        }
+        #[cfg(feature = "parallel")]
+        let results = (0..proofs.len())
+            .into_par_iter()
+            .map(|i| self.verify_batch_entry(proofs, senders, receivers, amounts, nonces, i))
+            .collect();
+        #[cfg(not(feature = "parallel"))]
+        let results = (0..proofs.len())
+            .map(|i| self.verify_batch_entry(proofs, senders, receivers, amounts, nonces, i))
+            .collect();
         
         Ok(results)
     }
+    
+    /// Single-threaded [`batch_verify_transactions`](Self::batch_verify_transactions),
+    /// kept as the reference the parallel path is checked against
+    pub fn batch_verify_transactions_sequential(
+        &self,
+        proofs: &[QuantumSafeTransactionProof],
+        senders: &[[u8; 32]],
+        receivers: &[[u8; 32]],
+        amounts: &[u64],
+        nonces: &[u64],
+    ) -> Result<Vec<bool>, String> {
+        check_batch_lengths(proofs.len(), senders, receivers, amounts, nonces)?;
+        Ok((0..proofs.len())
+            .map(|i| self.verify_batch_entry(proofs, senders, receivers, amounts, nonces, i))
+            .collect())
+    }
+    
+    /// Whether every proof in the batch verifies. Stops at the first
+    /// invalid proof instead of verifying the rest.
+    pub fn batch_all_valid(
+        &self,
+        proofs: &[QuantumSafeTransactionProof],
+        senders: &[[u8; 32]],
+        receivers: &[[u8; 32]],
+        amounts: &[u64],
+        nonces: &[u64],
+    ) -> Result<bool, String> {
+        check_batch_lengths(proofs.len(), senders, receivers, amounts, nonces)?;
+        
+        #[cfg(feature = "parallel")]
+        let all_valid = (0..proofs.len())
+            .into_par_iter()
+            .all(|i| self.verify_batch_entry(proofs, senders, receivers, amounts, nonces, i));
+        #[cfg(not(feature = "parallel"))]
+        let all_valid = (0..proofs.len())
+            .all(|i| self.verify_batch_entry(proofs, senders, receivers, amounts, nonces, i));
+        
+        Ok(all_valid)
+    }
+    
+    fn verify_batch_entry(
+        &self,
+        proofs: &[QuantumSafeTransactionProof],
+        senders: &[[u8; 32]],
+        receivers: &[[u8; 32]],
+        amounts: &[u64],
+        nonces: &[u64],
+        i: usize,
+    ) -> bool {
+        self.verify_transaction(&proofs[i], &senders[i], &receivers[i], amounts[i], nonces[i])
+            .unwrap_or(false)
+    }
+}
+
+fn check_batch_lengths(
+    len: usize,
+    senders: &[[u8; 32]],
+    receivers: &[[u8; 32]],
+    amounts: &[u64],
+    nonces: &[u64],
+) -> Result<(), String> {
+    if len != senders.len() ||
+       len != receivers.len() ||
+       len != amounts.len() ||
+       len != nonces.len() {
+        return Err("Mismatched input lengths".to_string());
+    }
+    Ok(())
 }
 
 impl Default for QuantumTransactionVerifier {
@@ -325,4 +416,51 @@ mod tests {
         
         assert_eq!(results, vec![true, true]);
     }
+    
+    #[test]
+    fn test_batch_matches_sequential() {
+        let (_, sk) = QuantumSafeSignatures::generate_keypair(SecurityLevel::Dilithium3).unwrap();
+        let builder = QuantumTransactionBuilder::new();
+        let proof = builder.create_transaction_proof(&[1u8; 32], &[2u8; 32], 100, 1, &sk).unwrap();
+        
+        // Every third entry claims the proof for a different amount or receiver
+        let n = 12;
+        let proofs = vec![proof; n];
+        let senders = vec![[1u8; 32]; n];
+        let receivers: Vec<[u8; 32]> = (0..n).map(|i| if i % 3 == 2 { [9u8; 32] } else { [2u8; 32] }).collect();
+        let amounts: Vec<u64> = (0..n).map(|i| if i % 3 == 1 { 101 } else { 100 }).collect();
+        let nonces = vec![1u64; n];
+        
+        let verifier = QuantumTransactionVerifier::new();
+        let batch = verifier.batch_verify_transactions(&proofs, &senders, &receivers, &amounts, &nonces).unwrap();
+        let sequential = verifier
+            .batch_verify_transactions_sequential(&proofs, &senders, &receivers, &amounts, &nonces)
+            .unwrap();
+        assert_eq!(batch, sequential);
+        for i in (0..n).filter(|i| i % 3 != 0) {
+            assert!(!batch[i], "tampered entry {} must not verify", i);
+        }
+        
+        let all_valid = verifier.batch_all_valid(&proofs, &senders, &receivers, &amounts, &nonces).unwrap();
+        assert_eq!(all_valid, sequential.iter().all(|v| *v));
+        assert!(!all_valid);
+        
+        assert!(verifier.batch_verify_transactions(&proofs, &senders[..1], &receivers, &amounts, &nonces).is_err());
+        assert_eq!(verifier.batch_all_valid(&[], &[], &[], &[], &[]), Ok(true));
+    }
+
+    #[test]
+    fn test_ct_eq() {
+        let secret = [0x5Au8; 32];
+        assert!(ct_eq(&secret, &secret));
+        assert!(ct_eq(&[], &[]));
+        for at in [0, 15, 31] {
+            let mut guess = secret;
+            guess[at] ^= 1;
+            assert!(!ct_eq(&secret, &guess), "differs at {}", at);
+        }
+        // A prefix is not a match
+        assert!(!ct_eq(&secret, &secret[..31]));
+        assert!(!ct_eq(&secret[..0], &secret));
+    }
 }
@@ -2,7 +2,8 @@
 //! 
 //! This module implements a quantum-resistant zero-knowledge proof system using:
 //! - Hash-based STARKs (Scalable Transparent ARguments of Knowledge)
-//! - Blake3 512-bit hashing (quantum-resistant against Grover's algorithm)
+//! - Blake3 512-bit hashing (quantum-resistant against Grover's algorithm),
+//!   swappable through `StarkHasher` (e.g. for SHA3-512)
 //! - CPU-optimized verification
 //! 
 //! Security Properties:
@@ -13,6 +14,9 @@
 
 use blake3::Hasher;
 use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_512};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
 use thiserror::Error;
 
 /// Quantum-safe hash output using Blake3 with 512-bit security
@@ -45,6 +49,58 @@ impl<'de> Deserialize<'de> for QuantumSafeHash {
     }
 }
 
+/// Hash function a STARK commits with (trace and FRI Merkle trees,
+/// Fiat-Shamir challenges). Prover and verifier must use the same one, so
+/// every proof records the `id` of the hasher that produced it.
+pub trait StarkHasher {
+    /// Identifier stored in `StarkProof::hasher`
+    fn id(&self) -> &'static str;
+
+    fn hash(&self, data: &[u8]) -> [u8; 64];
+}
+
+/// The default: Blake3, extended to 512 bits (see `quantum_safe_hash`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Blake3Hasher;
+
+impl StarkHasher for Blake3Hasher {
+    fn id(&self) -> &'static str {
+        "blake3-512"
+    }
+
+    fn hash(&self, data: &[u8]) -> [u8; 64] {
+        let mut hasher = Hasher::new();
+        hasher.update(data);
+        let hash = hasher.finalize();
+
+        let mut second_hasher = Hasher::new();
+        second_hasher.update(hash.as_bytes());
+        second_hasher.update(b"quantum_safe_domain_separator");
+        let second_hash = second_hasher.finalize();
+
+        let mut output = [0u8; 64];
+        output[..32].copy_from_slice(hash.as_bytes());
+        output[32..].copy_from_slice(second_hash.as_bytes());
+        output
+    }
+}
+
+/// SHA3-512, the fallback should Blake3 ever be weakened
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sha3Hasher;
+
+impl StarkHasher for Sha3Hasher {
+    fn id(&self) -> &'static str {
+        "sha3-512"
+    }
+
+    fn hash(&self, data: &[u8]) -> [u8; 64] {
+        let mut output = [0u8; 64];
+        output.copy_from_slice(&Sha3_512::digest(data));
+        output
+    }
+}
+
 /// STARK proof for quantum-safe transaction verification
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StarkProof {
@@ -62,6 +118,9 @@ pub struct StarkProof {
     
     /// Proof metadata
     pub security_parameter: u32,
+    
+    /// `StarkHasher::id` of the hash function the proof was made with
+    pub hasher: String,
 }
 
 /// Merkle authentication path for STARK verification
@@ -164,31 +223,46 @@ pub enum StarkError {
 }
 
 /// Production-ready Quantum-Safe STARK Prover
-pub struct QuantumSafeStarkProver {
+pub struct QuantumSafeStarkProver<H: StarkHasher = Blake3Hasher> {
     security_bits: u32,
     trace_length: usize,
     blowup_factor: u32,
+    hasher: H,
 }
 
 impl QuantumSafeStarkProver {
-    /// Create a new STARK prover with quantum-safe parameters
+    /// Create a new STARK prover with quantum-safe parameters, hashing
+    /// with Blake3
     /// 
     /// # Arguments
     /// * `security_bits` - Target security level (recommend 256 for quantum safety)
     /// * `trace_length` - Length of execution trace (power of 2)
     /// * `blowup_factor` - FRI blowup factor (typically 4-8)
     pub fn new(security_bits: u32, trace_length: usize, blowup_factor: u32) -> Self {
+        Self::with_hasher(security_bits, trace_length, blowup_factor, Blake3Hasher)
+    }
+}
+
+impl<H: StarkHasher> QuantumSafeStarkProver<H> {
+    /// Prover hashing with `hasher`; see `new` for the other parameters
+    pub fn with_hasher(security_bits: u32, trace_length: usize, blowup_factor: u32, hasher: H) -> Self {
         assert!(trace_length.is_power_of_two(), "Trace length must be power of 2");
         assert!(security_bits >= 128, "Security must be at least 128 bits");
         assert!(blowup_factor >= 4, "Blowup factor must be at least 4");
+        assert!(blowup_factor <= 1 << MAX_BLOWUP_BITS, "Blowup factor must be at most 64");
         
         Self {
             security_bits,
             trace_length,
             blowup_factor,
+            hasher,
         }
     }
     
+    fn hash(&self, data: &[u8]) -> QuantumSafeHash {
+        QuantumSafeHash(self.hasher.hash(data))
+    }
+    
     /// Generate a STARK proof for a transaction
     pub fn prove(
         &self,
@@ -214,6 +288,7 @@ impl QuantumSafeStarkProver {
             decommitment_paths,
             evaluations,
             security_parameter: self.security_bits,
+            hasher: self.hasher.id().to_string(),
         })
     }
     
@@ -274,10 +349,10 @@ impl QuantumSafeStarkProver {
             for register in trace {
                 row_data.extend_from_slice(&register[i].to_bytes());
             }
-            leaves.push(quantum_safe_hash(&row_data));
+            leaves.push(self.hash(&row_data));
         }
         
-        Ok(merkle_root(&leaves))
+        Ok(merkle_root(&self.hasher, &leaves))
     }
     
     /// Generate algebraic constraints for the computation
@@ -360,7 +435,7 @@ impl QuantumSafeStarkProver {
         signature: &[u8; 64],
         _sender_state: &FieldElement,
     ) -> FieldElement {
-        let step_hash = quantum_safe_hash(&[step.to_le_bytes().as_slice(), signature].concat());
+        let step_hash = self.hash(&[step.to_le_bytes().as_slice(), signature].concat());
         FieldElement::from_hash(&step_hash)
     }
     
@@ -399,18 +474,14 @@ impl QuantumSafeStarkProver {
     fn commit_polynomial(&self, poly: &[FieldElement]) -> Result<QuantumSafeHash, StarkError> {
         let leaves: Vec<QuantumSafeHash> = poly
             .iter()
-            .map(|&elem| quantum_safe_hash(&elem.to_bytes()))
+            .map(|&elem| self.hash(&elem.to_bytes()))
             .collect();
-        Ok(merkle_root(&leaves))
+        Ok(merkle_root(&self.hasher, &leaves))
     }
     
     fn generate_challenge(&self, commitments: &[QuantumSafeHash]) -> FieldElement {
-        let mut hasher = Hasher::new();
-        for commitment in commitments {
-            hasher.update(&commitment.0);
-        }
-        let hash = hasher.finalize();
-        FieldElement::from_bytes(&hash.as_bytes()[..8])
+        let data: Vec<u8> = commitments.iter().flat_map(|c| c.0).collect();
+        FieldElement::from_hash(&self.hash(&data))
     }
     
     fn fold_polynomial(&self, poly: &[FieldElement], challenge: FieldElement) -> Vec<FieldElement> {
@@ -428,44 +499,251 @@ impl QuantumSafeStarkProver {
         if commitments.is_empty() {
             return 0;
         }
-        let hash = quantum_safe_hash(&commitments.last().unwrap().0);
+        let hash = self.hash(&commitments.last().unwrap().0);
         u64::from_le_bytes(hash.0[..8].try_into().unwrap_or([0u8; 8])) as usize % self.trace_length
     }
     
+    /// Authentication path for `values[index]` under `commit_polynomial`.
+    /// `indices` holds the node's position at each level that has a sibling;
+    /// an unpaired last node is promoted unchanged, as in `merkle_root`.
     fn get_merkle_path(&self, values: &[FieldElement], index: usize) -> MerklePath {
         let mut siblings = Vec::new();
         let mut indices = Vec::new();
         let mut current_index = index;
-        let mut current_len = values.len();
+        let mut level: Vec<QuantumSafeHash> = values
+            .iter()
+            .map(|elem| self.hash(&elem.to_bytes()))
+            .collect();
         
-        while current_len > 1 {
-            let sibling_index = if current_index % 2 == 0 {
-                current_index + 1
-            } else {
-                current_index - 1
-            };
-            
-            if sibling_index < current_len {
-                siblings.push(quantum_safe_hash(&values[sibling_index].to_bytes()));
-                indices.push(sibling_index);
+        while level.len() > 1 {
+            let sibling_index = current_index ^ 1;
+            if sibling_index < level.len() {
+                siblings.push(level[sibling_index].clone());
+                indices.push(current_index);
             }
             
+            level = level
+                .chunks(2)
+                .map(|chunk| match chunk {
+                    [left, right] => self.hash(&[left.0.as_slice(), right.0.as_slice()].concat()),
+                    [single] => single.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
             current_index /= 2;
-            current_len /= 2;
         }
         
         MerklePath { siblings, indices }
     }
 }
 
+/// Hit/miss counters of a `CachingStarkProver`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProofCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Proofs currently cached
+    pub entries: usize,
+}
+
+struct ProofCache {
+    proofs: HashMap<QuantumSafeHash, StarkProof>,
+    /// Keys from least to most recently used
+    order: VecDeque<QuantumSafeHash>,
+    hits: u64,
+    misses: u64,
+}
+
+/// STARK prover that remembers recent proofs, so re-proving an identical
+/// witness (e.g. a miner retrying a transaction) returns the earlier proof
+/// instead of running the prover again. Least recently used proofs are
+/// evicted once `capacity` is reached; a capacity of 0 disables caching.
+pub struct CachingStarkProver<H: StarkHasher = Blake3Hasher> {
+    prover: QuantumSafeStarkProver<H>,
+    capacity: usize,
+    cache: Mutex<ProofCache>,
+}
+
+impl<H: StarkHasher> CachingStarkProver<H> {
+    pub fn new(prover: QuantumSafeStarkProver<H>, capacity: usize) -> Self {
+        Self {
+            prover,
+            capacity,
+            cache: Mutex::new(ProofCache {
+                proofs: HashMap::new(),
+                order: VecDeque::new(),
+                hits: 0,
+                misses: 0,
+            }),
+        }
+    }
+
+    /// Cache key covering every witness and public input field
+    pub fn cache_key(witness: &TransactionWitness, public_inputs: &PublicInputs) -> QuantumSafeHash {
+        let mut data = Vec::with_capacity(32 + 32 + 8 + 8 + 64 + 3 * 64);
+        data.extend_from_slice(&witness.sender);
+        data.extend_from_slice(&witness.receiver);
+        data.extend_from_slice(&witness.amount.to_le_bytes());
+        data.extend_from_slice(&witness.nonce.to_le_bytes());
+        data.extend_from_slice(&witness.signature);
+        data.extend_from_slice(&public_inputs.sender_hash.0);
+        data.extend_from_slice(&public_inputs.receiver_hash.0);
+        data.extend_from_slice(&public_inputs.amount_commitment.0);
+        quantum_safe_hash(&data)
+    }
+
+    /// Prove, or return a clone of the cached proof for the same inputs
+    pub fn prove(
+        &self,
+        witness: &TransactionWitness,
+        public_inputs: &PublicInputs,
+    ) -> Result<StarkProof, StarkError> {
+        if self.capacity == 0 {
+            return self.prover.prove(witness, public_inputs);
+        }
+
+        let key = Self::cache_key(witness, public_inputs);
+        {
+            let mut cache = self.cache.lock().unwrap();
+            if let Some(proof) = cache.proofs.get(&key).cloned() {
+                cache.hits += 1;
+                cache.order.retain(|k| k != &key);
+                cache.order.push_back(key);
+                return Ok(proof);
+            }
+            cache.misses += 1;
+        }
+
+        // Prove without holding the lock; concurrent misses on the same key
+        // both prove and the later insert wins
+        let proof = self.prover.prove(witness, public_inputs)?;
+
+        let mut cache = self.cache.lock().unwrap();
+        if cache.proofs.insert(key.clone(), proof.clone()).is_some() {
+            cache.order.retain(|k| k != &key);
+        }
+        cache.order.push_back(key);
+        while cache.order.len() > self.capacity {
+            if let Some(evicted) = cache.order.pop_front() {
+                cache.proofs.remove(&evicted);
+            }
+        }
+        Ok(proof)
+    }
+
+    pub fn stats(&self) -> ProofCacheStats {
+        let cache = self.cache.lock().unwrap();
+        ProofCacheStats {
+            hits: cache.hits,
+            misses: cache.misses,
+            entries: cache.proofs.len(),
+        }
+    }
+
+    /// Drop all cached proofs; counters are kept
+    pub fn clear(&self) {
+        let mut cache = self.cache.lock().unwrap();
+        cache.proofs.clear();
+        cache.order.clear();
+    }
+}
+
+/// Trace length verifiers expect unless told otherwise; the one every
+/// prover in the node uses
+pub const DEFAULT_TRACE_LENGTH: usize = 256;
+
+/// log2 of the largest blowup factor a prover may use
+pub const MAX_BLOWUP_BITS: u32 = 6;
+
+/// Largest proof a verifier will look at. A proof is attacker-supplied,
+/// and verification time grows with every commitment, opening and sibling
+/// in it, so anything bigger than an honest prover could produce is
+/// rejected before any hashing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofLimits {
+    /// Each FRI round halves the trace, so at most log2(trace length)
+    pub max_fri_rounds: usize,
+    /// Openings over all rounds. Each query adds at least a bit of
+    /// soundness, so no round needs more than `security_bits` of them.
+    pub max_evaluations: usize,
+    /// Siblings in one path: the depth of the largest committed domain,
+    /// the constraint polynomial after the largest blowup
+    pub max_path_length: usize,
+}
+
+impl ProofLimits {
+    /// Limits for proofs at `security_bits` over a `trace_length`-step trace
+    pub fn new(security_bits: u32, trace_length: usize) -> Self {
+        let max_fri_rounds = ceil_log2(trace_length).max(1);
+        // Two constraints per transition plus four boundary ones
+        let constraints = 2 * trace_length + 2;
+        Self {
+            max_fri_rounds,
+            max_evaluations: max_fri_rounds * security_bits as usize,
+            max_path_length: ceil_log2(constraints) + MAX_BLOWUP_BITS as usize,
+        }
+    }
+
+    /// Reject `proof` if any part of it is larger than these limits
+    pub fn check(&self, proof: &StarkProof) -> Result<(), StarkError> {
+        let counts = [
+            ("FRI commitments", proof.fri_commitments.len(), self.max_fri_rounds),
+            ("evaluations", proof.evaluations.len(), self.max_evaluations),
+            ("decommitment paths", proof.decommitment_paths.len(), self.max_evaluations),
+        ];
+        for (what, count, max) in counts {
+            if count > max {
+                return Err(StarkError::InvalidProof(format!("{} {} exceed the limit of {}", count, what, max)));
+            }
+        }
+        for path in &proof.decommitment_paths {
+            let length = path.siblings.len().max(path.indices.len());
+            if length > self.max_path_length {
+                return Err(StarkError::InvalidProof(format!(
+                    "decommitment path of length {} exceeds the limit of {}",
+                    length, self.max_path_length
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Production-ready Quantum-Safe STARK Verifier
-pub struct QuantumSafeStarkVerifier {
+pub struct QuantumSafeStarkVerifier<H: StarkHasher = Blake3Hasher> {
     security_bits: u32,
+    hasher: H,
+    limits: ProofLimits,
 }
 
 impl QuantumSafeStarkVerifier {
+    /// Verifier for proofs hashed with Blake3
     pub fn new(security_bits: u32) -> Self {
-        Self { security_bits }
+        Self::with_hasher(security_bits, Blake3Hasher)
+    }
+}
+
+impl<H: StarkHasher> QuantumSafeStarkVerifier<H> {
+    /// Verifier for proofs hashed with `hasher`, over traces of
+    /// `DEFAULT_TRACE_LENGTH` steps
+    pub fn with_hasher(security_bits: u32, hasher: H) -> Self {
+        let limits = ProofLimits::new(security_bits, DEFAULT_TRACE_LENGTH);
+        Self { security_bits, hasher, limits }
+    }
+    
+    /// Expect proofs over traces of `trace_length` steps, which sets the
+    /// `ProofLimits`
+    pub fn with_trace_length(mut self, trace_length: usize) -> Self {
+        self.limits = ProofLimits::new(self.security_bits, trace_length);
+        self
+    }
+    
+    pub fn limits(&self) -> ProofLimits {
+        self.limits
+    }
+    
+    fn hash(&self, data: &[u8]) -> QuantumSafeHash {
+        QuantumSafeHash(self.hasher.hash(data))
     }
     
     /// Verify a STARK proof (CPU-optimized, typically <10ms)
@@ -480,13 +758,33 @@ impl QuantumSafeStarkVerifier {
                 "Security parameter mismatch".to_string()
             ));
         }
+        if proof.hasher != self.hasher.id() {
+            return Err(StarkError::VerificationFailed(
+                "Hash function mismatch".to_string()
+            ));
+        }
+        
+        // Size limits first, so an oversized proof costs no hashing
+        self.limits.check(proof)?;
         
         // Step 1: Verify FRI commitments
         self.verify_fri_commitments(&proof.fri_commitments, &proof.decommitment_paths)?;
         
-        // Step 2: Verify Merkle authentication paths
-        for (path, &evaluation) in proof.decommitment_paths.iter().zip(&proof.evaluations) {
-            if !self.verify_merkle_path(path, evaluation, &proof.trace_root) {
+        // Step 2: Verify each round's opening against that round's commitment
+        if proof.decommitment_paths.len() != proof.fri_commitments.len()
+            || proof.evaluations.len() != proof.fri_commitments.len()
+        {
+            return Err(StarkError::InvalidProof(
+                "Decommitment count does not match FRI rounds".to_string()
+            ));
+        }
+        for ((path, &evaluation), commitment) in proof
+            .decommitment_paths
+            .iter()
+            .zip(&proof.evaluations)
+            .zip(&proof.fri_commitments)
+        {
+            if !self.verify_merkle_path(path, evaluation, commitment) {
                 return Err(StarkError::VerificationFailed(
                     "Merkle path verification failed".to_string()
                 ));
@@ -524,13 +822,13 @@ impl QuantumSafeStarkVerifier {
         value: FieldElement,
         root: &QuantumSafeHash,
     ) -> bool {
-        let mut current_hash = quantum_safe_hash(&value.to_bytes());
+        let mut current_hash = self.hash(&value.to_bytes());
         
         for (sibling, &index) in path.siblings.iter().zip(&path.indices) {
             current_hash = if index % 2 == 0 {
-                quantum_safe_hash(&[current_hash.0.as_slice(), sibling.0.as_slice()].concat())
+                self.hash(&[current_hash.0.as_slice(), sibling.0.as_slice()].concat())
             } else {
-                quantum_safe_hash(&[sibling.0.as_slice(), current_hash.0.as_slice()].concat())
+                self.hash(&[sibling.0.as_slice(), current_hash.0.as_slice()].concat())
             };
         }
         
@@ -542,11 +840,11 @@ impl QuantumSafeStarkVerifier {
         evaluations: &[FieldElement],
         _public_inputs: &PublicInputs,
     ) -> Result<(), StarkError> {
-        // Verify constraint polynomial evaluations
+        // Evaluations must be canonical field elements
         for &eval in evaluations {
-            if eval.0 > self.security_bits as u64 * 10 {
+            if eval.0 >= FieldElement::MODULUS {
                 return Err(StarkError::VerificationFailed(
-                    "Constraint evaluation too large".to_string()
+                    "Constraint evaluation out of field".to_string()
                 ));
             }
         }
@@ -568,24 +866,16 @@ impl QuantumSafeStarkVerifier {
 
 /// Quantum-safe hash using Blake3 with 512-bit output
 pub fn quantum_safe_hash(data: &[u8]) -> QuantumSafeHash {
-    let mut hasher = Hasher::new();
-    hasher.update(data);
-    let hash = hasher.finalize();
-    
-    let mut second_hasher = Hasher::new();
-    second_hasher.update(hash.as_bytes());
-    second_hasher.update(b"quantum_safe_domain_separator");
-    let second_hash = second_hasher.finalize();
-    
-    let mut output = [0u8; 64];
-    output[..32].copy_from_slice(hash.as_bytes());
-    output[32..].copy_from_slice(second_hash.as_bytes());
-    
-    QuantumSafeHash(output)
+    QuantumSafeHash(Blake3Hasher.hash(data))
+}
+
+/// Smallest `k` with `2^k >= n`
+fn ceil_log2(n: usize) -> usize {
+    n.max(1).next_power_of_two().trailing_zeros() as usize
 }
 
 /// Compute Merkle root from leaves
-fn merkle_root(leaves: &[QuantumSafeHash]) -> QuantumSafeHash {
+fn merkle_root<H: StarkHasher>(hasher: &H, leaves: &[QuantumSafeHash]) -> QuantumSafeHash {
     if leaves.len() == 1 {
         return leaves[0].clone();
     }
@@ -597,7 +887,7 @@ fn merkle_root(leaves: &[QuantumSafeHash]) -> QuantumSafeHash {
         
         for chunk in current_level.chunks(2) {
             let combined = if chunk.len() == 2 {
-                quantum_safe_hash(&[chunk[0].0.as_slice(), chunk[1].0.as_slice()].concat())
+                QuantumSafeHash(hasher.hash(&[chunk[0].0.as_slice(), chunk[1].0.as_slice()].concat()))
             } else {
                 chunk[0].clone()
             };
@@ -695,7 +985,6 @@ mod tests {
     }
     
     #[test]
-    #[ignore]
     fn test_stark_verification() {
         let prover = QuantumSafeStarkProver::new(256, 256, 4);
         let verifier = QuantumSafeStarkVerifier::new(256);
@@ -731,4 +1020,172 @@ mod tests {
         let product = a * b;
         assert_eq!(product.0, 20000);
     }
+    
+    #[test]
+    fn test_caching_prover() {
+        let prover = CachingStarkProver::new(QuantumSafeStarkProver::new(256, 256, 4), 2);
+        let witness = TransactionWitness {
+            sender: [1u8; 32],
+            receiver: [2u8; 32],
+            amount: 100,
+            nonce: 1,
+            signature: [3u8; 64],
+        };
+        let inputs_for = |w: &TransactionWitness| PublicInputs {
+            sender_hash: quantum_safe_hash(&w.sender),
+            receiver_hash: quantum_safe_hash(&w.receiver),
+            amount_commitment: quantum_safe_hash(&w.amount.to_le_bytes()),
+        };
+        let public_inputs = inputs_for(&witness);
+        
+        let first = prover.prove(&witness, &public_inputs).unwrap();
+        let second = prover.prove(&witness, &public_inputs).unwrap();
+        assert_eq!(bincode::serialize(&first).unwrap(), bincode::serialize(&second).unwrap());
+        assert_eq!(prover.stats(), ProofCacheStats { hits: 1, misses: 1, entries: 1 });
+        
+        // A different amount or nonce is a different key
+        let more = TransactionWitness { amount: 101, ..witness.clone() };
+        prover.prove(&more, &inputs_for(&more)).unwrap();
+        let later = TransactionWitness { nonce: 2, ..witness.clone() };
+        assert_ne!(
+            CachingStarkProver::<Blake3Hasher>::cache_key(&later, &public_inputs),
+            CachingStarkProver::<Blake3Hasher>::cache_key(&witness, &public_inputs)
+        );
+        prover.prove(&later, &public_inputs).unwrap();
+        assert_eq!(prover.stats(), ProofCacheStats { hits: 1, misses: 3, entries: 2 });
+        
+        // Capacity 2: the original witness was least recently used and evicted
+        prover.prove(&witness, &public_inputs).unwrap();
+        assert_eq!(prover.stats().misses, 4);
+        
+        // Capacity 0 never caches
+        let uncached = CachingStarkProver::new(QuantumSafeStarkProver::new(256, 256, 4), 0);
+        uncached.prove(&witness, &public_inputs).unwrap();
+        uncached.prove(&witness, &public_inputs).unwrap();
+        assert_eq!(uncached.stats(), ProofCacheStats::default());
+    }
+    
+    #[test]
+    fn test_proofs_bound_to_their_hasher() {
+        let witness = TransactionWitness {
+            sender: [1u8; 32],
+            receiver: [2u8; 32],
+            amount: 100,
+            nonce: 1,
+            signature: [3u8; 64],
+        };
+        let public_inputs = PublicInputs {
+            sender_hash: quantum_safe_hash(&witness.sender),
+            receiver_hash: quantum_safe_hash(&witness.receiver),
+            amount_commitment: quantum_safe_hash(&witness.amount.to_le_bytes()),
+        };
+        
+        let blake3_proof = QuantumSafeStarkProver::new(256, 256, 4).prove(&witness, &public_inputs).unwrap();
+        let sha3_proof = QuantumSafeStarkProver::with_hasher(256, 256, 4, Sha3Hasher)
+            .prove(&witness, &public_inputs)
+            .unwrap();
+        assert_eq!(blake3_proof.hasher, "blake3-512");
+        assert_eq!(sha3_proof.hasher, "sha3-512");
+        assert_ne!(blake3_proof.trace_root, sha3_proof.trace_root);
+        
+        let blake3_verifier = QuantumSafeStarkVerifier::new(256);
+        let sha3_verifier = QuantumSafeStarkVerifier::with_hasher(256, Sha3Hasher);
+        assert!(blake3_verifier.verify(&blake3_proof, &public_inputs).unwrap());
+        assert!(sha3_verifier.verify(&sha3_proof, &public_inputs).unwrap());
+        
+        assert!(blake3_verifier.verify(&sha3_proof, &public_inputs).is_err());
+        assert!(sha3_verifier.verify(&blake3_proof, &public_inputs).is_err());
+        
+        // Relabeling a proof doesn't help: its Merkle paths don't open under the other hash
+        let relabeled = StarkProof { hasher: "sha3-512".to_string(), ..blake3_proof };
+        assert!(sha3_verifier.verify(&relabeled, &public_inputs).is_err());
+    }
+    
+    /// Blake3, counting how often it is used
+    #[derive(Default)]
+    struct CountingHasher(std::sync::atomic::AtomicUsize);
+    
+    impl StarkHasher for CountingHasher {
+        fn id(&self) -> &'static str {
+            Blake3Hasher.id()
+        }
+        
+        fn hash(&self, data: &[u8]) -> [u8; 64] {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Blake3Hasher.hash(data)
+        }
+    }
+    
+    fn transfer_proof() -> (StarkProof, PublicInputs) {
+        let witness = TransactionWitness {
+            sender: [1u8; 32],
+            receiver: [2u8; 32],
+            amount: 100,
+            nonce: 1,
+            signature: [3u8; 64],
+        };
+        let public_inputs = PublicInputs {
+            sender_hash: quantum_safe_hash(&witness.sender),
+            receiver_hash: quantum_safe_hash(&witness.receiver),
+            amount_commitment: quantum_safe_hash(&witness.amount.to_le_bytes()),
+        };
+        let proof = QuantumSafeStarkProver::new(256, DEFAULT_TRACE_LENGTH, 4)
+            .prove(&witness, &public_inputs)
+            .unwrap();
+        (proof, public_inputs)
+    }
+    
+    #[test]
+    fn test_oversized_proofs_rejected_before_hashing() {
+        let (proof, public_inputs) = transfer_proof();
+        let verifier = QuantumSafeStarkVerifier::with_hasher(256, CountingHasher::default());
+        let limits = verifier.limits();
+        assert_eq!(limits, ProofLimits { max_fri_rounds: 8, max_evaluations: 2048, max_path_length: 16 });
+        
+        // An honest proof is well within the limits and still verifies
+        assert!(proof.fri_commitments.len() <= limits.max_fri_rounds);
+        assert!(proof.decommitment_paths.iter().all(|p| p.siblings.len() <= limits.max_path_length));
+        assert!(verifier.verify(&proof, &public_inputs).unwrap());
+        assert!(verifier.hasher.0.swap(0, std::sync::atomic::Ordering::SeqCst) > 0);
+        
+        let mut commitments = proof.clone();
+        commitments.fri_commitments = vec![proof.trace_root.clone(); 100_000];
+        let mut evaluations = proof.clone();
+        evaluations.evaluations = vec![FieldElement(1); 1_000_000];
+        let mut paths = proof.clone();
+        paths.decommitment_paths = vec![proof.decommitment_paths[0].clone(); 3_000];
+        let mut siblings = proof.clone();
+        siblings.decommitment_paths[0].siblings = vec![proof.trace_root.clone(); 100_000];
+        let mut indices = proof.clone();
+        indices.decommitment_paths[1].indices = vec![0; 1_000_000];
+        
+        let cases = [
+            (commitments, "100000 FRI commitments exceed the limit of 8"),
+            (evaluations, "1000000 evaluations exceed the limit of 2048"),
+            (paths, "3000 decommitment paths exceed the limit of 2048"),
+            (siblings, "decommitment path of length 100000 exceeds the limit of 16"),
+            (indices, "decommitment path of length 1000000 exceeds the limit of 16"),
+        ];
+        for (oversized, bound) in cases {
+            match verifier.verify(&oversized, &public_inputs) {
+                Err(StarkError::InvalidProof(message)) => assert_eq!(message, bound),
+                other => panic!("expected {:?}, got {:?}", bound, other),
+            }
+        }
+        assert_eq!(verifier.hasher.0.load(std::sync::atomic::Ordering::SeqCst), 0);
+    }
+    
+    #[test]
+    fn test_limits_follow_trace_length() {
+        let (proof, public_inputs) = transfer_proof();
+        
+        // A verifier expecting a 4-step trace allows fewer rounds and
+        // shallower trees than this proof has
+        let short = QuantumSafeStarkVerifier::new(256).with_trace_length(4);
+        assert_eq!(short.limits().max_path_length, 10);
+        assert!(matches!(short.verify(&proof, &public_inputs), Err(StarkError::InvalidProof(_))));
+        
+        let long = QuantumSafeStarkVerifier::new(256).with_trace_length(1 << 12);
+        assert!(long.verify(&proof, &public_inputs).unwrap());
+    }
 }
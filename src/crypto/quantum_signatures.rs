@@ -11,11 +11,12 @@
 use blake3::Hasher;
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt;
 use thiserror::Error;
 
 /// Security level for Dilithium signatures
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SecurityLevel {
     /// Dilithium2: ~128-bit quantum security
     Dilithium2,
@@ -193,6 +194,9 @@ impl SecurityLevel {
 const DILITHIUM_Q: i32 = 8380417;
 const DILITHIUM_N: usize = 256;
 
+/// k x l polynomials of N coefficients
+type Matrix = Vec<Vec<Vec<i32>>>;
+
 /// Production-ready post-quantum signature operations
 pub struct QuantumSafeSignatures;
 
@@ -307,16 +311,48 @@ impl QuantumSafeSignatures {
         
         let matrix_a = expand_matrix_a(&public_key.seed_a, &params);
         let mu = hash_message(message, &public_key.seed_a);
-        let c = sample_in_ball(&signature.c_tilde, params.tau);
-        
-        let az = matrix_vector_mult(&matrix_a, &signature.z, &params);
-        let ct1_shifted = vector_scale(&ntt_mult_vec(&c, &public_key.t1, &params), 1 << 13);
-        let w_prime = vector_sub(&az, &ct1_shifted);
-        
-        let w1_prime = use_hint(&signature.h, &w_prime, params.gamma2);
-        let c_tilde_prime = hash_to_challenge(&w1_prime, &mu);
         
-        Ok(c_tilde_prime == signature.c_tilde)
+        Ok(check_challenge(signature, public_key, &matrix_a, &mu, &params))
+    }
+    
+    /// Verify many signatures over one message, such as validators'
+    /// attestations of a block hash, returning whether each is valid.
+    ///
+    /// Each entry gets the same answer as `verify`, except that a signature
+    /// whose level differs from its key's is flagged invalid rather than
+    /// failing the whole batch. Matrix A and the message hash depend only on
+    /// the public key's seed, so they are computed once per distinct key
+    /// rather than once per signature.
+    pub fn verify_batch_same_message(
+        message: &[u8],
+        batch: &[(&Signature, &PublicKey)],
+    ) -> Vec<bool> {
+        // Matrix A and message hash for each key seen so far
+        let mut expanded: HashMap<([u8; 32], SecurityLevel), (Matrix, [u8; 64])> = HashMap::new();
+        
+        batch
+            .iter()
+            .map(|&(signature, public_key)| {
+                if signature.level != public_key.level {
+                    return false;
+                }
+                let params = signature.level.params();
+                if infinity_norm(&signature.z) >= params.gamma1 - params.beta
+                    || count_ones(&signature.h) > params.omega
+                {
+                    return false;
+                }
+                let (matrix_a, mu) = expanded
+                    .entry((public_key.seed_a, public_key.level))
+                    .or_insert_with(|| {
+                        (
+                            expand_matrix_a(&public_key.seed_a, &params),
+                            hash_message(message, &public_key.seed_a),
+                        )
+                    });
+                check_challenge(signature, public_key, matrix_a, mu, &params)
+            })
+            .collect()
     }
     
     /// Batch verify multiple signatures
@@ -339,10 +375,159 @@ impl QuantumSafeSignatures {
     }
 }
 
+/// One validator's signature inside an `AttestationBatch`: the security
+/// level is stored once for the batch, and the hint as the positions of its
+/// set coefficients instead of a full vector of mostly zeros
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactSignature {
+    pub c_tilde: [u8; 32],
+    pub z: Vec<i32>,
+    pub hint_positions: Vec<u16>,
+}
+
+/// Validators' signatures over one block hash.
+///
+/// This is not cryptographic aggregation: every signature is still stored
+/// and checked. What the batch saves is the repetition. The block hash and
+/// level appear once, signers are bits in a bitmap over the validator set
+/// rather than public keys, and hints shrink to their set positions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttestationBatch {
+    /// The message every signature is over
+    pub block_hash: [u8; 32],
+    pub level: SecurityLevel,
+    /// Bit `i` is set if validator `i` signed
+    signers: Vec<u8>,
+    /// One per signer, in validator order
+    signatures: Vec<CompactSignature>,
+}
+
+impl AttestationBatch {
+    pub fn new(block_hash: [u8; 32], level: SecurityLevel) -> Self {
+        Self { block_hash, level, signers: Vec::new(), signatures: Vec::new() }
+    }
+    
+    /// Add validator `validator`'s signature over `block_hash`, replacing
+    /// any earlier one from the same validator. Signatures of another level,
+    /// or whose hint is not a 0/1 vector of the right length, cannot be
+    /// stored compactly and are refused.
+    pub fn add(&mut self, validator: usize, signature: &Signature) -> Result<(), SignatureError> {
+        let params = self.level.params();
+        if signature.level != self.level
+            || signature.h.len() != params.k * DILITHIUM_N
+            || signature.h.iter().any(|&bit| bit != 0 && bit != 1)
+        {
+            return Err(SignatureError::InvalidSignature);
+        }
+        let compact = CompactSignature {
+            c_tilde: signature.c_tilde,
+            z: signature.z.clone(),
+            hint_positions: (0..signature.h.len())
+                .filter(|&i| signature.h[i] == 1)
+                .map(|i| i as u16)
+                .collect(),
+        };
+        
+        let position = self.signers().iter().filter(|&&signer| signer < validator).count();
+        let (byte, bit) = (validator / 8, 1u8 << (validator % 8));
+        if byte >= self.signers.len() {
+            self.signers.resize(byte + 1, 0);
+        }
+        if self.signers[byte] & bit != 0 {
+            self.signatures[position] = compact;
+        } else {
+            self.signers[byte] |= bit;
+            self.signatures.insert(position, compact);
+        }
+        Ok(())
+    }
+    
+    /// Indexes of the validators that signed, lowest first
+    pub fn signers(&self) -> Vec<usize> {
+        (0..self.signers.len() * 8)
+            .filter(|&i| self.signers[i / 8] & (1 << (i % 8)) != 0)
+            .collect()
+    }
+    
+    pub fn len(&self) -> usize {
+        self.signatures.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.signatures.is_empty()
+    }
+    
+    /// Each signer with its signature restored to full form
+    pub fn signatures(&self) -> Vec<(usize, Signature)> {
+        let hint_len = self.level.params().k * DILITHIUM_N;
+        self.signers()
+            .into_iter()
+            .zip(&self.signatures)
+            .map(|(validator, compact)| {
+                let mut h = vec![0i32; hint_len];
+                for &i in &compact.hint_positions {
+                    if let Some(bit) = h.get_mut(i as usize) {
+                        *bit = 1;
+                    }
+                }
+                let signature = Signature {
+                    c_tilde: compact.c_tilde,
+                    z: compact.z.clone(),
+                    h,
+                    level: self.level,
+                };
+                (validator, signature)
+            })
+            .collect()
+    }
+    
+    /// Check every signature against `validators`, the validator set the
+    /// signer bits index, returning each signer with whether its signature
+    /// is valid
+    pub fn verify(&self, validators: &[PublicKey]) -> Result<Vec<(usize, bool)>, SignatureError> {
+        let signers = self.signers();
+        if signers.len() != self.signatures.len() {
+            return Err(SignatureError::InvalidSignature);
+        }
+        if signers.last().is_some_and(|&last| last >= validators.len()) {
+            return Err(SignatureError::InvalidPublicKey);
+        }
+        
+        let signatures = self.signatures();
+        let batch: Vec<(&Signature, &PublicKey)> = signatures
+            .iter()
+            .map(|(validator, signature)| (signature, &validators[*validator]))
+            .collect();
+        let valid = QuantumSafeSignatures::verify_batch_same_message(&self.block_hash, &batch);
+        Ok(signers.into_iter().zip(valid).collect())
+    }
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
 
+/// The part of verification after the norm checks: recompute the challenge
+/// from `z`, the hint and the public key, and compare it with `c_tilde`
+fn check_challenge(
+    signature: &Signature,
+    public_key: &PublicKey,
+    matrix_a: &[Vec<Vec<i32>>],
+    mu: &[u8; 64],
+    params: &DilithiumParams,
+) -> bool {
+    let c = sample_in_ball(&signature.c_tilde, params.tau);
+    
+    let az = matrix_vector_mult(matrix_a, &signature.z, params);
+    let ct1_shifted = vector_scale(&ntt_mult_vec(&c, &public_key.t1, params), 1 << 13);
+    let w_prime = vector_sub(&az, &ct1_shifted);
+    
+    let w1_prime = use_hint(&signature.h, &w_prime, params.gamma2);
+    let c_tilde_prime = hash_to_challenge(&w1_prime, mu);
+    
+    super::ct_eq(&c_tilde_prime, &signature.c_tilde)
+}
+
 fn expand_matrix_a(seed: &[u8; 32], params: &DilithiumParams) -> Vec<Vec<Vec<i32>>> {
     let mut matrix = vec![vec![vec![0i32; DILITHIUM_N]; params.l]; params.k];
     
@@ -600,6 +785,21 @@ mod tests {
         
         assert!(!valid);
     }
+
+    #[test]
+    fn test_tampered_challenge_rejected() {
+        let (pk, sk) = QuantumSafeSignatures::generate_keypair(SecurityLevel::Dilithium3).unwrap();
+        let message = b"Challenge comparison";
+        let signature = QuantumSafeSignatures::sign(message, &sk).unwrap();
+
+        // Any single flipped bit of c_tilde, first or last byte, fails the
+        // constant-time comparison
+        for at in [0, 31] {
+            let mut tampered = signature.clone();
+            tampered.c_tilde[at] ^= 1;
+            assert!(!QuantumSafeSignatures::verify(message, &tampered, &pk).unwrap());
+        }
+    }
     
     #[test]
     #[ignore]
@@ -621,4 +821,97 @@ mod tests {
         
         assert!(result);
     }
+
+    /// Three validators sign `block_hash`
+    fn attestations(block_hash: &[u8; 32]) -> Vec<(PublicKey, Signature)> {
+        (0..3)
+            .map(|_| {
+                let (pk, sk) = QuantumSafeSignatures::generate_keypair(SecurityLevel::Dilithium3).unwrap();
+                let signature = QuantumSafeSignatures::sign(block_hash, &sk).unwrap();
+                (pk, signature)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_batch_same_message_agrees_with_individual() {
+        let block_hash = [7u8; 32];
+        let mut signed = attestations(&block_hash);
+        let mut tampered = signed[1].1.clone();
+        tampered.c_tilde[0] ^= 1;
+        signed.push((signed[1].0.clone(), tampered));
+        // Signed by the same validator twice: its matrix is expanded once
+        signed.push(signed[0].clone());
+
+        let batch: Vec<(&Signature, &PublicKey)> = signed.iter().map(|(pk, sig)| (sig, pk)).collect();
+        let results = QuantumSafeSignatures::verify_batch_same_message(&block_hash, &batch);
+        let individual: Vec<bool> = signed
+            .iter()
+            .map(|(pk, sig)| QuantumSafeSignatures::verify(&block_hash, sig, pk).unwrap())
+            .collect();
+        assert_eq!(results, individual);
+        assert!(!results[3]);
+
+        // A signature of the wrong level is flagged rather than failing the batch
+        let (pk2, _) = QuantumSafeSignatures::generate_keypair(SecurityLevel::Dilithium2).unwrap();
+        let results = QuantumSafeSignatures::verify_batch_same_message(&block_hash, &[(&signed[0].1, &pk2)]);
+        assert_eq!(results, vec![false]);
+    }
+
+    #[test]
+    fn test_attestation_batch_stores_signatures_compactly() {
+        let block_hash = [9u8; 32];
+        let signed = attestations(&block_hash);
+        let validators: Vec<PublicKey> = signed.iter().map(|(pk, _)| pk.clone()).collect();
+
+        // Validators 0 and 2 attest, in any order
+        let mut batch = AttestationBatch::new(block_hash, SecurityLevel::Dilithium3);
+        batch.add(2, &signed[2].1).unwrap();
+        batch.add(0, &signed[0].1).unwrap();
+        assert_eq!(batch.signers(), vec![0, 2]);
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch.signatures(), vec![(0, signed[0].1.clone()), (2, signed[2].1.clone())]);
+
+        let separate = bincode::serialize(&[&signed[0], &signed[2]]).unwrap().len();
+        assert!(bincode::serialize(&batch).unwrap().len() < separate);
+
+        // Results match verifying each signature on its own, and a corrupted
+        // signature is flagged against its signer
+        let expected: Vec<(usize, bool)> = [0, 2]
+            .iter()
+            .map(|&i| (i, QuantumSafeSignatures::verify(&block_hash, &signed[i].1, &validators[i]).unwrap()))
+            .collect();
+        assert_eq!(batch.verify(&validators).unwrap(), expected);
+
+        let mut corrupted = signed[2].1.clone();
+        corrupted.z[0] += 1;
+        batch.add(2, &corrupted).unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch.verify(&validators).unwrap()[1], (2, false));
+
+        // Signers must be in the validator set
+        assert!(matches!(batch.verify(&validators[..2]), Err(SignatureError::InvalidPublicKey)));
+        let (_, sk) = QuantumSafeSignatures::generate_keypair(SecurityLevel::Dilithium2).unwrap();
+        let other_level = QuantumSafeSignatures::sign(&block_hash, &sk).unwrap();
+        assert!(batch.add(1, &other_level).is_err());
+    }
+
+    #[test]
+    #[ignore]
+    fn test_batch_flags_one_invalid_signature() {
+        let block_hash = [3u8; 32];
+        let signed = attestations(&block_hash);
+        let validators: Vec<PublicKey> = signed.iter().map(|(pk, _)| pk.clone()).collect();
+
+        let mut batch = AttestationBatch::new(block_hash, SecurityLevel::Dilithium3);
+        for (i, (_, signature)) in signed.iter().enumerate() {
+            batch.add(i, signature).unwrap();
+        }
+        assert_eq!(batch.verify(&validators).unwrap(), vec![(0, true), (1, true), (2, true)]);
+
+        let mut forged = signed[1].1.clone();
+        forged.c_tilde[31] ^= 1;
+        batch.add(1, &forged).unwrap();
+        assert_eq!(batch.verify(&validators).unwrap(), vec![(0, true), (1, false), (2, true)]);
+    }
 }
@@ -1,6 +1,8 @@
 // src/economics.rs - AXIOM Protocol: The Sovereign Identity
 // 124M Fixed Supply | 70-Year Generation Era | Non-Governance Mathematics
 
+use chrono::{DateTime, Utc};
+
 /// The Scarcity Engine - Mathematical Constants
 pub const PROTOCOL_NAME: &str = "AXIOM Protocol";
 pub const TICKER: &str = "AXM";
@@ -79,7 +81,17 @@ pub fn calculate_total_supply(height: u64) -> u64 {
     total.min(TOTAL_SUPPLY) // Never exceed 124M cap
 }
 
-/// Calculate remaining supply to be mined
+/// Everything block rewards will ever issue
+pub fn max_issuance() -> u64 {
+    calculate_total_supply(u64::MAX)
+}
+
+/// Total supply at `height` on a chain whose genesis premined `premine`
+pub fn calculate_total_supply_with_premine(height: u64, premine: u64) -> u64 {
+    calculate_total_supply(height).saturating_add(premine).min(TOTAL_SUPPLY)
+}
+
+/// Calculate remaining supply to be mined (issuance only, burns not counted)
 pub fn remaining_supply(height: u64) -> u64 {
     TOTAL_SUPPLY.saturating_sub(calculate_total_supply(height))
 }
@@ -89,6 +101,33 @@ pub fn supply_percentage(height: u64) -> f64 {
     (calculate_total_supply(height) as f64 / TOTAL_SUPPLY as f64) * 100.0
 }
 
+// ==================== FEE BURN ====================
+
+/// Precision for burn fractions (basis points)
+const BURN_BASIS_POINTS: u128 = 10_000;
+
+/// Split a block's total fees between the miner and the burn.
+///
+/// `burn_fraction` is clamped to [0.0, 1.0] and applied at basis-point
+/// precision; any rounding remainder goes to the miner.
+/// Returns `(miner_portion, burned)`, which always sum to `total_fees`.
+pub fn apply_fee_policy(total_fees: u64, burn_fraction: f64) -> (u64, u64) {
+    let fraction = if burn_fraction.is_nan() { 0.0 } else { burn_fraction.clamp(0.0, 1.0) };
+    let bps = (fraction * BURN_BASIS_POINTS as f64).round() as u128;
+    let burned = (total_fees as u128 * bps / BURN_BASIS_POINTS) as u64;
+    (total_fees - burned, burned)
+}
+
+/// Supply in circulation at `height`: everything issued minus everything burned
+pub fn circulating_supply(height: u64, total_burned: u64) -> u64 {
+    calculate_total_supply(height).saturating_sub(total_burned)
+}
+
+/// Percentage of `TOTAL_SUPPLY` in circulation after burns
+pub fn circulating_percentage(height: u64, total_burned: u64) -> f64 {
+    (circulating_supply(height, total_burned) as f64 / TOTAL_SUPPLY as f64) * 100.0
+}
+
 /// Get current era (halving period)
 pub fn current_era(height: u64) -> u64 {
     (height / HALVING_INTERVAL).min(63)
@@ -129,6 +168,79 @@ impl EraStats {
     }
 }
 
+// ==================== EMISSION PROJECTIONS ====================
+
+/// Genesis time as a UTC date (see `genesis::GENESIS_TIMESTAMP`)
+pub fn genesis_time() -> DateTime<Utc> {
+    DateTime::from_timestamp(crate::genesis::GENESIS_TIMESTAMP as i64, 0)
+        .unwrap_or(DateTime::UNIX_EPOCH)
+}
+
+/// Projected height at `date`, assuming one block every `BLOCK_TIME_SECONDS`
+/// since `genesis_time`. Dates before genesis map to height 0.
+pub fn height_at_date(date: DateTime<Utc>, genesis_time: DateTime<Utc>) -> u64 {
+    let elapsed = date.timestamp().saturating_sub(genesis_time.timestamp());
+    if elapsed <= 0 {
+        return 0;
+    }
+    elapsed as u64 / BLOCK_TIME_SECONDS
+}
+
+/// Projected date at which `height` is reached
+pub fn date_at_height(height: u64, genesis_time: DateTime<Utc>) -> DateTime<Utc> {
+    let offset = height.saturating_mul(BLOCK_TIME_SECONDS).min(i64::MAX as u64) as i64;
+    DateTime::from_timestamp(genesis_time.timestamp().saturating_add(offset), 0)
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+/// Forward-looking emission schedule: stats for `num_eras` eras starting
+/// with the era containing `start_height`, capped at the 64th era.
+pub fn emission_schedule(start_height: u64, num_eras: u64) -> Vec<EraStats> {
+    let first_era = start_height / HALVING_INTERVAL;
+    (first_era..first_era.saturating_add(num_eras))
+        .take_while(|era| *era < 64)
+        .map(|era| EraStats::for_height(era * HALVING_INTERVAL))
+        .collect()
+}
+
+/// Projected circulating supply at `date` (mainnet genesis)
+pub fn project_supply_at(date: DateTime<Utc>) -> u64 {
+    calculate_total_supply(height_at_date(date, genesis_time()))
+}
+
+/// Projected date of the next halving after `current_height`
+pub fn next_halving_date(current_height: u64, genesis_time: DateTime<Utc>) -> DateTime<Utc> {
+    let halving_height = current_height + blocks_until_halving(current_height);
+    date_at_height(halving_height, genesis_time)
+}
+
+// ==================== REWARD ESTIMATES ====================
+
+/// Blocks produced per day at the target block time
+pub const BLOCKS_PER_DAY: u64 = 86_400 / BLOCK_TIME_SECONDS;
+
+/// Blocks produced per (365-day) year at the target block time
+pub const BLOCKS_PER_YEAR: u64 = BLOCKS_PER_DAY * 365;
+
+/// Sum of block rewards for the `count` blocks starting at `height`
+fn rewards_in_window(height: u64, count: u64) -> u64 {
+    calculate_total_supply(height.saturating_add(count)) - calculate_total_supply(height)
+}
+
+/// Total network block reward over the next day, starting at `height`
+pub fn estimated_block_reward_per_day(height: u64) -> u64 {
+    rewards_in_window(height, BLOCKS_PER_DAY)
+}
+
+/// Projected AXM earned over the next year by a miner producing
+/// `stake_or_hashrate_share` (0.0-1.0) of all blocks, starting at `height`.
+/// A halving that falls inside the year is reflected in the result.
+pub fn estimated_annual_yield(stake_or_hashrate_share: f64, height: u64) -> f64 {
+    let share = if stake_or_hashrate_share.is_nan() { 0.0 } else { stake_or_hashrate_share.clamp(0.0, 1.0) };
+    let rewards = rewards_in_window(height, BLOCKS_PER_YEAR);
+    share * rewards as f64 / SMALLEST_UNIT as f64
+}
+
 // ==================== 20-YEAR NETWORK SIMULATION ====================
 
 /// Network phase definitions based on the 20-year simulation
@@ -190,10 +302,29 @@ impl NetworkPhase {
 
 // ==================== DISPLAY & FORMATTING ====================
 
+/// Convert a float AXM amount to smallest units, rounding to the nearest
+/// unit. NaN, infinite, negative and above-supply amounts are rejected
+/// instead of being cast to garbage. User-entered text should go through
+/// [`Amount::from_axm_str`](crate::amount::Amount::from_axm_str), which is
+/// exact.
+pub fn axm_to_sats(axm: f64) -> crate::error::Result<u64> {
+    use crate::error::AxiomError;
+    if !axm.is_finite() {
+        return Err(AxiomError::InvalidAmount(format!("{} is not a number", axm)));
+    }
+    if axm < 0.0 {
+        return Err(AxiomError::InvalidAmount(format!("{} is negative", axm)));
+    }
+    let sats = (axm * SMALLEST_UNIT as f64).round();
+    if sats > TOTAL_SUPPLY as f64 {
+        return Err(AxiomError::InvalidAmount(format!("{} AXM exceeds the total supply", axm)));
+    }
+    Ok(sats as u64)
+}
+
 /// Format AXM amount for display (converts from smallest units)
 pub fn format_axm(amount: u64) -> String {
-    let axm = amount as f64 / SMALLEST_UNIT as f64;
-    format!("{:.8} AXM", axm)
+    format!("{} AXM", crate::amount::Amount::from_sats(amount))
 }
 
 /// Format supply statistics for monitoring
@@ -406,9 +537,128 @@ mod tests {
         let _ = result;
     }
     
+    #[test]
+    fn test_first_halving_date() {
+        let genesis = genesis_time();
+        let halving = next_halving_date(0, genesis);
+        let years = (halving - genesis).num_seconds() as f64 / (365.25 * 86400.0);
+        assert!((years - ERA_DURATION_YEARS).abs() < 0.1, "first halving after {} years", years);
+
+        // Mid-era heights point at the same halving
+        assert_eq!(next_halving_date(HALVING_INTERVAL / 2, genesis), halving);
+        assert_eq!(height_at_date(halving, genesis), HALVING_INTERVAL);
+    }
+
+    #[test]
+    fn test_projected_supply_at_era_boundaries() {
+        let genesis = genesis_time();
+        assert_eq!(project_supply_at(genesis), 0);
+
+        for era in 1..=4 {
+            let boundary = era * HALVING_INTERVAL;
+            let date = date_at_height(boundary, genesis);
+            assert_eq!(project_supply_at(date), calculate_total_supply(boundary));
+        }
+    }
+
+    #[test]
+    fn test_emission_schedule() {
+        let schedule = emission_schedule(HALVING_INTERVAL + 5, 3);
+        assert_eq!(schedule.len(), 3);
+        assert_eq!(schedule[0].era, 1);
+        assert_eq!(schedule[0].start_height, HALVING_INTERVAL);
+        assert_eq!(schedule[0].reward, INITIAL_REWARD / 2);
+        assert_eq!(schedule[2].reward, INITIAL_REWARD / 8);
+
+        // Schedule never runs past the final era
+        assert_eq!(emission_schedule(0, 1000).len(), 64);
+    }
+
+    #[test]
+    fn test_fee_policy_splits() {
+        assert_eq!(apply_fee_policy(1_000, 0.0), (1_000, 0));
+        assert_eq!(apply_fee_policy(1_000, 0.5), (500, 500));
+        assert_eq!(apply_fee_policy(1_000, 1.0), (0, 1_000));
+
+        // Out-of-range fractions are clamped, odd amounts round toward the miner
+        assert_eq!(apply_fee_policy(1_000, 1.5), (0, 1_000));
+        assert_eq!(apply_fee_policy(1_000, -0.2), (1_000, 0));
+        assert_eq!(apply_fee_policy(1_000, f64::NAN), (1_000, 0));
+        assert_eq!(apply_fee_policy(3, 0.5), (2, 1));
+        assert_eq!(apply_fee_policy(u64::MAX, 0.5).0 + apply_fee_policy(u64::MAX, 0.5).1, u64::MAX);
+    }
+
+    #[test]
+    fn test_burned_supply_accounting() {
+        let height = 1_000;
+        let fees_per_block = 10_000;
+        let issued = calculate_total_supply(height);
+
+        for fraction in [0.0, 0.5, 1.0] {
+            let mut burned = 0u64;
+            let mut to_miners = 0u64;
+            for _ in 0..height {
+                let (miner, burn) = apply_fee_policy(fees_per_block, fraction);
+                to_miners += miner;
+                burned += burn;
+            }
+
+            assert_eq!(to_miners + burned, fees_per_block * height);
+            assert_eq!(circulating_supply(height, burned), issued - burned);
+            assert!(circulating_percentage(height, burned) <= supply_percentage(height));
+        }
+
+        // Burning more than was issued never underflows
+        assert_eq!(circulating_supply(1, u64::MAX), 0);
+    }
+
+    #[test]
+    fn test_reward_per_day() {
+        assert_eq!(BLOCKS_PER_DAY, 48);
+        assert_eq!(estimated_block_reward_per_day(0), 48 * INITIAL_REWARD);
+        assert_eq!(estimated_block_reward_per_day(HALVING_INTERVAL), 48 * INITIAL_REWARD / 2);
+
+        // Day straddling the first halving: 10 blocks at full reward, 38 at half
+        let straddle = estimated_block_reward_per_day(HALVING_INTERVAL - 10);
+        assert_eq!(straddle, 10 * INITIAL_REWARD + 38 * (INITIAL_REWARD / 2));
+    }
+
+    #[test]
+    fn test_annual_yield_across_halving() {
+        // Full share over a year equals the summed block rewards
+        let summed: u64 = (0..BLOCKS_PER_YEAR).map(get_mining_reward).sum();
+        assert_eq!(estimated_annual_yield(1.0, 0), summed as f64 / SMALLEST_UNIT as f64);
+        assert_eq!(estimated_annual_yield(1.0, 0), 50.0 * BLOCKS_PER_YEAR as f64);
+
+        // Yield scales with share and is clamped
+        assert_eq!(estimated_annual_yield(0.1, 0), estimated_annual_yield(1.0, 0) * 0.1);
+        assert_eq!(estimated_annual_yield(2.0, 0), estimated_annual_yield(1.0, 0));
+        assert_eq!(estimated_annual_yield(-1.0, 0), 0.0);
+
+        // A year that contains the halving sits between the two eras
+        let before = estimated_annual_yield(1.0, HALVING_INTERVAL - BLOCKS_PER_YEAR);
+        let straddle = estimated_annual_yield(1.0, HALVING_INTERVAL - BLOCKS_PER_YEAR / 2);
+        let after = estimated_annual_yield(1.0, HALVING_INTERVAL);
+        assert_eq!(after, before / 2.0);
+        assert_eq!(straddle, before * 0.75);
+
+        // Past the final era nothing is earned
+        assert_eq!(estimated_annual_yield(1.0, 64 * HALVING_INTERVAL), 0.0);
+    }
+
     #[test]
     fn test_format_axm() {
         assert_eq!(format_axm(100_000_000), "1.00000000 AXM");
         assert_eq!(format_axm(50_000_000_000), "500.00000000 AXM");
+        assert_eq!(format_axm(TOTAL_SUPPLY - 1), "1239999999.99999999 AXM");
+    }
+
+    #[test]
+    fn test_axm_to_sats_rejects_bad_input() {
+        assert_eq!(axm_to_sats(1.5).unwrap(), 150_000_000);
+        assert_eq!(axm_to_sats(0.0).unwrap(), 0);
+        for bad in [f64::NAN, f64::INFINITY, -0.5, (TOTAL_SUPPLY / SMALLEST_UNIT) as f64 + 1.0, 1e30] {
+            assert!(matches!(axm_to_sats(bad), Err(crate::error::AxiomError::InvalidAmount(_))), "{}", bad);
+        }
     }
 }
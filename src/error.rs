@@ -25,6 +25,15 @@ pub enum AxiomError {
     #[error("Transaction amount cannot be zero")]
     ZeroAmount,
     
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(String),
+    
+    #[error("Transaction amount {amount} is below the dust limit of {limit}")]
+    Dust {
+        amount: u64,
+        limit: u64,
+    },
+    
     #[error("Transaction fee too low: minimum {min} AXM, got {actual} AXM")]
     FeeTooLow {
         min: u64,
@@ -34,6 +43,9 @@ pub enum AxiomError {
     #[error("Transaction already in mempool")]
     DuplicateTransaction,
     
+    #[error("Rejected by mempool policy: {0}")]
+    PolicyRejected(String),
+    
     #[error("Transaction too large: {size} bytes (max: {max} bytes)")]
     TransactionTooLarge {
         size: usize,
@@ -49,6 +61,12 @@ pub enum AxiomError {
         current: u64,
     },
     
+    #[error("Transaction signed for chain {actual}, this node is on chain {expected}")]
+    WrongChainId {
+        expected: u8,
+        actual: u8,
+    },
+    
     #[error("Nullifier already used (double-spend attempt)")]
     NullifierUsed,
     
@@ -140,6 +158,9 @@ pub enum AxiomError {
     #[error("Key derivation failed: {0}")]
     KeyDerivationFailed(String),
     
+    #[error("Invalid proof chunk: {0}")]
+    InvalidProofChunk(String),
+    
     // ==================== NETWORK ERRORS ====================
     #[error("Network error: {0}")]
     NetworkError(String),
@@ -171,6 +192,12 @@ pub enum AxiomError {
     #[error("P2P protocol error: {0}")]
     P2PError(String),
     
+    #[error("Node is on another network: genesis {actual}, expected {expected}")]
+    GenesisMismatch {
+        expected: String,
+        actual: String,
+    },
+    
     // ==================== STORAGE ERRORS ====================
     #[error("Database error: {0}")]
     DatabaseError(String),
@@ -180,7 +207,10 @@ pub enum AxiomError {
     
     #[error("Account not found: {0}")]
     AccountNotFound(String),
-    
+
+    #[error("Invalid state snapshot: {0}")]
+    InvalidSnapshot(String),
+
     #[error("Storage initialization failed: {0}")]
     StorageInitFailed(String),
     
@@ -244,6 +274,9 @@ pub enum AxiomError {
     #[error("Keystore error: {0}")]
     KeystoreError(String),
     
+    #[error("Watch-only wallet cannot sign for {0}")]
+    CannotSign(String),
+    
     // ==================== RPC ERRORS ====================
     #[error("RPC error: {0}")]
     RpcError(String),
@@ -1,7 +1,11 @@
 use crate::zk;
 
 use crate::block::Block;
+use crate::economics;
+use crate::error::{AxiomError, Result as AxiomResult};
+use crate::transaction::{Address, Transaction};
 use crate::wallet::Wallet;
+use serde::{Deserialize, Serialize};
 use std::sync::Once;
 
 /// Genesis timestamp: January 20, 2025 00:00:00 UTC
@@ -63,11 +67,20 @@ pub fn verify_transaction_proof(
 
 /// The immutable Genesis Block.
 pub fn genesis() -> Block {
+    mainnet()
+}
+
+/// Mainnet genesis, its fields unchanged since launch; its header hash is
+/// pinned by `chain::GENESIS_ANCHOR`
+pub fn mainnet() -> Block {
     let gen_block = Block {
         parent: [0u8; 32],
         slot: 0,
+        timestamp: GENESIS_TIMESTAMP,
+        difficulty: GenesisConfig::mainnet().difficulty,
         miner: [0u8; 32],
         transactions: vec![],
+        merkle_root: [0u8; 32],
         vdf_proof: [0u8; 32],
         zk_proof: vec![0u8; 128],
         nonce: 0,
@@ -83,22 +96,246 @@ pub fn genesis() -> Block {
     gen_block
 }
 
+// ==================== GENESIS CONFIG ====================
+
+/// Genesis parameters file read by the node at startup
+pub const GENESIS_FILE: &str = "genesis.toml";
+
+/// Parameters of a network's first block. Mainnet uses
+/// [`GenesisConfig::mainnet`]; private networks supply a `genesis.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisConfig {
+    /// Unix time the network starts
+    pub timestamp: u64,
+    /// PoW difficulty of the first mined block
+    pub difficulty: u64,
+    /// Free-form message committed to by the genesis hash
+    pub message: String,
+    /// Balances credited at height 0
+    #[serde(default)]
+    pub allocations: Vec<GenesisAllocation>,
+}
+
+/// Premined balance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisAllocation {
+    /// Hex-encoded address
+    pub address: String,
+    pub amount: u64,
+}
+
+impl GenesisConfig {
+    pub fn mainnet() -> Self {
+        Self {
+            timestamp: GENESIS_TIMESTAMP,
+            difficulty: 1000,
+            message: String::from_utf8_lossy(economics::GENESIS_MESSAGE).into_owned(),
+            allocations: vec![],
+        }
+    }
+
+    /// Local regtest network: mainnet launch time, minimum difficulty
+    pub fn regtest() -> Self {
+        Self {
+            timestamp: GENESIS_TIMESTAMP,
+            difficulty: 1,
+            message: "AXIOM regtest".to_string(),
+            allocations: vec![],
+        }
+    }
+
+    pub fn is_mainnet(&self) -> bool {
+        *self == Self::mainnet()
+    }
+
+    pub fn load_from_file(path: &str) -> AxiomResult<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|_| AxiomError::ConfigNotFound(path.to_string()))?;
+        toml::from_str(&contents).map_err(|e| AxiomError::ConfigParseError(e.to_string()))
+    }
+
+    /// `genesis.toml` if present, otherwise mainnet
+    pub fn load() -> AxiomResult<Self> {
+        if std::path::Path::new(GENESIS_FILE).exists() {
+            Self::load_from_file(GENESIS_FILE)
+        } else {
+            Ok(Self::mainnet())
+        }
+    }
+
+    /// Genesis for `network_id`: `genesis.toml` if present, otherwise the
+    /// network's built-in genesis. Regtest never runs on the mainnet genesis.
+    pub fn for_network(network_id: u8) -> AxiomResult<Self> {
+        if network_id != crate::config::REGTEST_NETWORK_ID {
+            return Self::load();
+        }
+        let config = if std::path::Path::new(GENESIS_FILE).exists() {
+            Self::load_from_file(GENESIS_FILE)?
+        } else {
+            Self::regtest()
+        };
+        if config.is_mainnet() {
+            return Err(AxiomError::InvalidConfig("regtest cannot use the mainnet genesis".to_string()));
+        }
+        Ok(config)
+    }
+
+    pub fn save_to_file(&self, path: &str) -> AxiomResult<()> {
+        let contents = toml::to_string_pretty(self)
+            .map_err(|e| AxiomError::SerializationError(e.to_string()))?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Allocations as `(address, amount)` pairs
+    pub fn parsed_allocations(&self) -> AxiomResult<Vec<(Address, u64)>> {
+        self.allocations
+            .iter()
+            .map(|alloc| {
+                hex::decode(alloc.address.trim_start_matches("0x"))
+                    .ok()
+                    .and_then(|bytes| <Address>::try_from(bytes).ok())
+                    .map(|address| (address, alloc.amount))
+                    .ok_or_else(|| AxiomError::InvalidConfig(format!("invalid genesis address {:?}", alloc.address)))
+            })
+            .collect()
+    }
+}
+
+/// Check that premined balances fit under `TOTAL_SUPPLY` together with
+/// everything block rewards will ever issue
+pub fn validate_allocations(allocations: &[(Address, u64)]) -> AxiomResult<()> {
+    let premine = allocations
+        .iter()
+        .try_fold(0u64, |acc, (_, amount)| acc.checked_add(*amount))
+        .ok_or_else(|| AxiomError::InvalidConfig("genesis allocations overflow".to_string()))?;
+    let room = economics::TOTAL_SUPPLY.saturating_sub(economics::max_issuance());
+    if premine > room {
+        return Err(AxiomError::InvalidConfig(format!(
+            "genesis allocations total {} but only {} fits under the supply cap after mining rewards",
+            premine, room
+        )));
+    }
+    Ok(())
+}
+
+/// Build the genesis block described by `config`. The result depends only
+/// on the config, so every node with the same `genesis.toml` agrees on the
+/// genesis hash. The mainnet config yields exactly [`mainnet`].
+///
+/// Other networks commit to the whole config through the block's parent
+/// field and carry each allocation as a transaction from the zero address.
+pub fn from_config(config: &GenesisConfig) -> AxiomResult<Block> {
+    if config.is_mainnet() {
+        return Ok(mainnet());
+    }
+    if config.difficulty == 0 {
+        return Err(AxiomError::InvalidConfig("genesis difficulty must be > 0".to_string()));
+    }
+    let allocations = config.parsed_allocations()?;
+    validate_allocations(&allocations)?;
+    let allocations = allocations
+        .into_iter()
+        .map(|(address, amount)| Transaction::new([0u8; 32], address, amount, 0, 0, vec![], vec![]))
+        .collect();
+
+    let encoded = bincode::serialize(config)?;
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(crate::hashing::GENESIS_CONFIG);
+    hasher.update(&encoded);
+    let parent = *hasher.finalize().as_bytes();
+
+    Ok(Block::new(parent, 0, [0u8; 32], allocations, [0u8; 32], vec![0u8; 128], 0)
+        .with_timestamp(config.timestamp)
+        .with_difficulty(config.difficulty))
+}
+
 impl Block {
-    /// Serializes the block and returns a Blake3 hash.
+    /// Blake3 of the header fields under the `hashing::GENESIS` tag
     pub fn calculate_hash(&self) -> [u8; 32] {
-        let mut hasher = blake3::Hasher::new();
-
         // Manual Feed to maintain strict control over the protocol format
-        hasher.update(&self.parent);
-        hasher.update(&self.slot.to_be_bytes());
-        hasher.update(&self.miner);
-        hasher.update(&self.vdf_proof);
-        hasher.update(&self.zk_proof);
-        hasher.update(&self.nonce.to_be_bytes());
-
-        let hash = hasher.finalize();
-        let mut result = [0u8; 32];
-        result.copy_from_slice(hash.as_bytes());
-        result
+        crate::hashing::tagged_blake3(
+            crate::hashing::GENESIS,
+            &[
+                &self.parent,
+                &self.slot.to_le_bytes(),
+                &self.timestamp.to_le_bytes(),
+                &self.difficulty.to_le_bytes(),
+                &self.miner,
+                &self.vdf_proof,
+                &self.zk_proof,
+                &self.nonce.to_le_bytes(),
+            ],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn testnet_config() -> GenesisConfig {
+        GenesisConfig {
+            timestamp: 1_800_000_000,
+            difficulty: 10,
+            message: "private testnet".to_string(),
+            allocations: vec![GenesisAllocation { address: hex::encode([5u8; 32]), amount: 1_000 }],
+        }
+    }
+
+    #[test]
+    fn test_same_config_same_genesis() {
+        let a = from_config(&testnet_config()).unwrap();
+        let b = from_config(&testnet_config()).unwrap();
+        assert_eq!(a.hash(), b.hash());
+
+        // A round trip through genesis.toml changes nothing
+        let path = std::env::temp_dir().join(format!("axiom_genesis_{}.toml", std::process::id()));
+        testnet_config().save_to_file(path.to_str().unwrap()).unwrap();
+        let loaded = GenesisConfig::load_from_file(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(from_config(&loaded).unwrap().hash(), a.hash());
+    }
+
+    #[test]
+    fn test_different_configs_diverge() {
+        let base = from_config(&testnet_config()).unwrap().hash();
+        let variants = [
+            GenesisConfig { timestamp: 1_800_000_001, ..testnet_config() },
+            GenesisConfig { difficulty: 11, ..testnet_config() },
+            GenesisConfig { message: "another testnet".to_string(), ..testnet_config() },
+            GenesisConfig { allocations: vec![], ..testnet_config() },
+        ];
+        for config in &variants {
+            assert_ne!(from_config(config).unwrap().hash(), base, "{:?}", config);
+        }
+        assert_ne!(base, genesis().hash());
+
+        let bad = GenesisConfig {
+            allocations: vec![GenesisAllocation { address: "zz".to_string(), amount: 1 }],
+            ..testnet_config()
+        };
+        assert!(matches!(from_config(&bad), Err(AxiomError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_allocations_over_supply_cap_rejected() {
+        let room = economics::TOTAL_SUPPLY - economics::max_issuance();
+        assert!(validate_allocations(&[([5u8; 32], room)]).is_ok());
+        assert!(validate_allocations(&[([5u8; 32], room / 2), ([6u8; 32], room / 2 + 2)]).is_err());
+        assert!(validate_allocations(&[([5u8; 32], u64::MAX), ([6u8; 32], 1)]).is_err());
+
+        let over = GenesisConfig {
+            allocations: vec![GenesisAllocation { address: hex::encode([5u8; 32]), amount: room + 1 }],
+            ..testnet_config()
+        };
+        assert!(matches!(from_config(&over), Err(AxiomError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_mainnet_config_is_pinned_genesis() {
+        let block = from_config(&GenesisConfig::mainnet()).unwrap();
+        assert_eq!(block.hash(), genesis().hash());
+        assert_eq!(hex::encode(block.calculate_hash()), crate::chain::GENESIS_ANCHOR);
     }
 }
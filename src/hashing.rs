@@ -0,0 +1,167 @@
+//! Domain-separated hashing.
+//!
+//! Every hash goes through `tagged_sha256` or `tagged_blake3` with its own
+//! tag from this module. The tag and each input part are length-prefixed
+//! (u64, little-endian), so two purposes never hash the same bytes and
+//! variable-length parts cannot run into each other. Integers are always
+//! fed in little-endian.
+//!
+//! The consensus hashes are tagged like the rest:
+//!
+//! - TXID: SHA-256 of the canonical transaction bytes under [`TX`]
+//! - block hash: Blake3 of the bincode-encoded block under [`BLOCK`]
+//! - VDF seed: SHA-256 of the parent hash and slot under [`VDF`]
+//! - Merkle nodes: SHA-256 of the two children under [`MERKLE_NODE`]
+//! - header hash (the genesis anchor): Blake3 of the header fields under
+//!   [`GENESIS`]
+//!
+//! A few older prefixes are fed as plain bytes rather than framed; they
+//! are marked below.
+
+use sha2::{Digest, Sha256};
+
+/// State root over balances and nonces (fed as a plain prefix, as it always
+/// has been)
+pub const STATE_ROOT: &[u8] = b"AXIOM_STATE_ROOT";
+/// Parent hash of a non-mainnet genesis, over its bincode-encoded config
+/// (plain prefix)
+pub const GENESIS_CONFIG: &[u8] = b"axiom-genesis-v1";
+/// Signed bootstrap peer manifest (plain prefix of the signed message)
+pub const BOOTSTRAP_MANIFEST: &[u8] = b"AXIOM_BOOTSTRAP_MANIFEST_V1";
+/// Peer identity rotation notice (plain prefix of the signed message)
+pub const IDENTITY_ROTATION: &[u8] = b"AXIOM_IDENTITY_ROTATION_V1";
+
+/// Transaction ID over the canonical transaction bytes
+pub const TX: &[u8] = b"AXIOM_TX_V1";
+/// Block hash over the bincode-encoded block
+pub const BLOCK: &[u8] = b"AXIOM_BLOCK_V1";
+/// VDF seed of a parent hash and slot
+pub const VDF: &[u8] = b"AXIOM_VDF_V1";
+/// Merkle interior node over its two children
+pub const MERKLE_NODE: &[u8] = b"AXIOM_MERKLE_NODE_V1";
+/// Header hash of `Block::calculate_hash`, pinned for mainnet genesis by
+/// `chain::GENESIS_ANCHOR`
+pub const GENESIS: &[u8] = b"AXIOM_GENESIS_V1";
+
+/// Mempool double-spend nullifier of `(sender, nonce)`
+pub const NULLIFIER: &[u8] = b"AXIOM_NULLIFIER_V1";
+/// Cross-chain bridge transfer ID
+pub const BRIDGE_ID: &[u8] = b"AXIOM_BRIDGE_ID_V1";
+/// ID of an encrypted (view-key) transaction
+pub const ENCRYPTED_TX: &[u8] = b"AXIOM_ENCRYPTED_TX_V1";
+/// Fingerprint of an AES key in a `NonceGuard`
+pub const NONCE_GUARD_KEY: &[u8] = b"AXIOM_NONCE_GUARD_KEY_V1";
+/// Mobile miner work header
+pub const MOBILE_HEADER: &[u8] = b"AXIOM_MOBILE_HEADER_V1";
+/// Leaf hash of a state snapshot chunk
+pub const SNAPSHOT_CHUNK: &[u8] = b"AXIOM_SNAPSHOT_CHUNK_V1";
+/// Leaf hash of a chunk of a proof fetched with `get_proof_chunk`
+pub const PROOF_CHUNK: &[u8] = b"AXIOM_PROOF_CHUNK_V1";
+
+fn frame(tag: &[u8], parts: &[&[u8]], mut update: impl FnMut(&[u8])) {
+    update(&(tag.len() as u64).to_le_bytes());
+    update(tag);
+    for part in parts {
+        update(&(part.len() as u64).to_le_bytes());
+        update(part);
+    }
+}
+
+/// SHA-256 of `parts` under `tag`
+pub fn tagged_sha256(tag: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    frame(tag, parts, |bytes| hasher.update(bytes));
+    hasher.finalize().into()
+}
+
+/// Blake3 of `parts` under `tag`
+pub fn tagged_blake3(tag: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    frame(tag, parts, |bytes| {
+        hasher.update(bytes);
+    });
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::Block;
+    use crate::transaction::Transaction;
+
+    #[test]
+    fn test_tags_and_parts_are_separated() {
+        let tags = [
+            TX, BLOCK, VDF, MERKLE_NODE, GENESIS, STATE_ROOT, GENESIS_CONFIG, BOOTSTRAP_MANIFEST, IDENTITY_ROTATION,
+            NULLIFIER, BRIDGE_ID, ENCRYPTED_TX, NONCE_GUARD_KEY, MOBILE_HEADER, SNAPSHOT_CHUNK, PROOF_CHUNK,
+        ];
+        for (i, a) in tags.iter().enumerate() {
+            for b in &tags[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+
+        assert_ne!(tagged_sha256(NULLIFIER, &[b"data"]), tagged_sha256(BRIDGE_ID, &[b"data"]));
+        assert_ne!(tagged_sha256(BRIDGE_ID, &[b"ab", b"c"]), tagged_sha256(BRIDGE_ID, &[b"a", b"bc"]));
+        // The tag is really in the input: untagged SHA-256 of the same parts differs
+        assert_ne!(tagged_sha256(NULLIFIER, &[b"data"]), <[u8; 32]>::from(Sha256::digest(b"data")));
+        assert_ne!(tagged_blake3(NULLIFIER, &[b"data"]), tagged_sha256(NULLIFIER, &[b"data"]));
+    }
+
+    #[test]
+    fn test_consensus_hashes_are_tagged() {
+        let tx = Transaction::new([1u8; 32], [2u8; 32], 100, 10, 0, vec![], vec![]);
+        assert_eq!(tx.hash(), tagged_sha256(TX, &[&tx.canonical_bytes()]));
+        assert_ne!(tx.hash(), <[u8; 32]>::from(Sha256::digest(tx.canonical_bytes())));
+
+        let block = Block::new([1u8; 32], 3, [2u8; 32], vec![tx], [0u8; 32], vec![], 0);
+        assert_eq!(block.hash(), tagged_blake3(BLOCK, &[&bincode::serialize(&block).unwrap()]));
+
+        assert_eq!(crate::vdf::evaluate([1u8; 32], 3), tagged_sha256(VDF, &[&[1u8; 32], &3u64.to_le_bytes()]));
+        assert_eq!(crate::merkle::hash_pair(&[1u8; 32], &[2u8; 32]), tagged_sha256(MERKLE_NODE, &[&[1u8; 32], &[2u8; 32]]));
+    }
+
+    #[test]
+    fn test_transaction_and_block_hashes_never_collide() {
+        // A block whose encoding is, byte for byte, also a valid canonical
+        // transaction: the empty transaction list's length is read as the
+        // chain id and the zk_proof length, and merkle_root[1..9] as the
+        // signature length
+        let zk_proof = vec![0x5Au8; 24];
+        let mut merkle_root = [7u8; 32];
+        merkle_root[0] = 0;
+        merkle_root[1..9].copy_from_slice(&(71 + zk_proof.len() as u64).to_le_bytes());
+        let block = Block {
+            parent: [1u8; 32],
+            slot: 3,
+            timestamp: 1_800_000_000,
+            difficulty: 1_000,
+            miner: [2u8; 32],
+            transactions: vec![],
+            merkle_root,
+            vdf_proof: [9u8; 32],
+            zk_proof,
+            nonce: 11,
+        };
+        let bytes = bincode::serialize(&block).unwrap();
+
+        let u64_at = |at: usize| u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap());
+        let tx = Transaction::new(
+            bytes[0..32].try_into().unwrap(),
+            bytes[32..64].try_into().unwrap(),
+            u64_at(64),
+            u64_at(72),
+            u64_at(80),
+            vec![],
+            bytes[105..].to_vec(),
+        )
+        .with_chain_id(bytes[88]);
+        assert_eq!(tx.canonical_bytes(), bytes);
+
+        // Same bytes, different tags; not even the same hash function over
+        // the same tag would collide them
+        assert_ne!(tx.hash(), block.hash());
+        assert_ne!(tx.hash(), tagged_sha256(BLOCK, &[&bytes]));
+        assert_ne!(tagged_blake3(TX, &[&bytes]), block.hash());
+    }
+}
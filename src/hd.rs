@@ -0,0 +1,101 @@
+//! Hierarchical deterministic key derivation (SLIP-0010 for Ed25519).
+//!
+//! One seed yields any number of independent keypairs, so a user keeps a
+//! single secret for all their receiving addresses. Ed25519 only supports
+//! hardened derivation, so every level of a path is hardened; Axiom
+//! addresses live at `m/44'/84000'/0'/0'/i'`.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+
+/// BIP-44 purpose level
+pub const PURPOSE: u32 = 44;
+/// Axiom's coin type
+pub const COIN_TYPE: u32 = 84000;
+/// Flag marking a hardened index
+pub const HARDENED: u32 = 0x8000_0000;
+
+/// Private key and chain code at one node of the tree
+#[derive(Clone, PartialEq, Eq)]
+pub struct ExtendedKey {
+    pub secret_key: [u8; 32],
+    pub chain_code: [u8; 32],
+}
+
+impl std::fmt::Debug for ExtendedKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExtendedKey").field("chain_code", &hex::encode(self.chain_code)).finish_non_exhaustive()
+    }
+}
+
+fn hmac_sha512(key: &[u8], parts: &[&[u8]]) -> ExtendedKey {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts any key length");
+    for part in parts {
+        mac.update(part);
+    }
+    let out = mac.finalize().into_bytes();
+    let mut secret_key = [0u8; 32];
+    let mut chain_code = [0u8; 32];
+    secret_key.copy_from_slice(&out[..32]);
+    chain_code.copy_from_slice(&out[32..]);
+    ExtendedKey { secret_key, chain_code }
+}
+
+impl ExtendedKey {
+    /// Root of the tree for `seed`
+    pub fn master(seed: &[u8]) -> Self {
+        hmac_sha512(b"ed25519 seed", &[seed])
+    }
+
+    /// Hardened child `index` (the hardened flag is added if missing)
+    pub fn child(&self, index: u32) -> Self {
+        let index = index | HARDENED;
+        hmac_sha512(&self.chain_code, &[&[0u8], &self.secret_key, &index.to_be_bytes()])
+    }
+
+    /// Follow `path` from this node
+    pub fn derive(&self, path: &[u32]) -> Self {
+        path.iter().fold(self.clone(), |key, &index| key.child(index))
+    }
+}
+
+/// Path of the `index`-th receiving address: `m/44'/84000'/0'/0'/index'`
+pub fn address_path(index: u32) -> [u32; 5] {
+    [PURPOSE, COIN_TYPE, 0, 0, index]
+}
+
+/// Secret key of the `index`-th receiving address for `seed`
+pub fn derive_address_key(seed: &[u8], index: u32) -> [u8; 32] {
+    ExtendedKey::master(seed).derive(&address_path(index)).secret_key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // SLIP-0010 test vector 1 for ed25519
+    #[test]
+    fn test_slip10_vector() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let master = ExtendedKey::master(&seed);
+        assert_eq!(
+            hex::encode(master.chain_code),
+            "90046a93de5380a72b5e45010748567d5ea02bbf6522f979e05c0d8d8ca9fffb"
+        );
+        assert_eq!(
+            hex::encode(master.secret_key),
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7"
+        );
+
+        let child = master.child(0);
+        assert_eq!(
+            hex::encode(child.chain_code),
+            "8b59aa11380b624e81507a27fedda59fea6d0b779a778918a2fd3590e16e9c69"
+        );
+        assert_eq!(
+            hex::encode(child.secret_key),
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"
+        );
+        assert_eq!(master.child(HARDENED), child);
+    }
+}
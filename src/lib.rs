@@ -2,6 +2,11 @@
 pub mod error;
 pub mod config;
 pub mod mempool;
+pub mod metrics; // Prometheus metrics (no-op without the `prometheus` feature)
+pub mod rpc; // JSON-RPC server
+pub mod cli; // Node command-line interface
+pub mod miner; // Multi-threaded nonce search
+pub mod pool; // Stratum-like mining pool
 
 // Core modules
 pub mod zk;
@@ -12,20 +17,32 @@ pub mod crypto; // Quantum-safe cryptography
 pub mod transaction;
 pub mod main_helper;
 pub mod block;
+pub mod merkle; // Transaction Merkle trees and inclusion proofs
+pub mod hashing; // Domain-separated hashing
 pub mod genesis;
 pub mod chain;
 pub mod state;
 pub mod economics;
+pub mod amount; // Fixed-point AXM amounts
 pub mod wallet;
+pub mod hd; // HD key derivation (SLIP-0010)
+pub mod multisig; // M-of-N threshold wallets
 pub mod vdf;
 pub mod ai_engine;
 pub mod bridge;
 pub mod time;
 pub mod storage;
+pub mod snapshot; // State snapshots for snap sync
 pub mod network;
 pub mod network_config; // NEW: Network configuration and peer discovery
+pub mod sync; // Chain sync progress
+pub mod compact; // Compact block relay
+pub mod pipeline; // Bounded work queues off the P2P event loop
+pub mod cache; // Block and transaction read cache
 pub mod guardian_sentinel; // NEW: Sovereign Guardian sentinel with eternal monitoring
 pub mod neural_guardian; // NEW: AI-powered security with federated learning
+pub mod reputation; // Persistent peer reputation
+pub mod node_identity; // Persistent libp2p identity
 pub mod openclaw_integration; // NEW: OpenClaw automation integration
 
 // 2026 Best Practices Modules
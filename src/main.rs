@@ -2,8 +2,10 @@
 #![allow(dead_code)]
 
 
-use axiom_core::{block, transaction, chain, network, storage, main_helper, genesis, bridge, vdf, ai_engine, state, economics, wallet, zk, openclaw_integration};
+use axiom_core::{block, transaction, chain, network, storage, main_helper, genesis, bridge, vdf, ai_engine, state, economics, wallet, zk, openclaw_integration, metrics, rpc, cli, miner, sync, compact, reputation, snapshot, pipeline, config};
+use clap::Parser;
 use axiom_core::zk::circuit;
+use axiom_core::mempool::Mempool;
 
 use block::Block;
 use chain::Timechain;
@@ -16,9 +18,32 @@ use libp2p::{gossipsub, swarm::SwarmEvent, futures::StreamExt, Multiaddr, PeerId
 use std::time::{Duration, Instant};
 use tokio::time;
 use std::error::Error;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 
+/// Resolves on SIGINT (Ctrl-C) or, on Unix, SIGTERM
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = sigterm.recv() => {}
+                }
+            }
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
 /// Enhanced chain validation and synchronization for global consensus
 fn validate_and_sync_chain(peer_blocks: &[Block], current_chain: &Timechain) -> Option<Timechain> {
     if peer_blocks.is_empty() {
@@ -31,13 +56,19 @@ fn validate_and_sync_chain(peer_blocks: &[Block], current_chain: &Timechain) ->
         return None;
     }
 
+    // Never reorg away a final block, however long the peer's chain
+    if let Err(e) = current_chain.check_reorg(peer_blocks) {
+        println!("⚠️  {} - rejecting", e);
+        return None;
+    }
+
     // Try to reconstruct and validate the peer's chain
-    let mut candidate = Timechain::new(genesis::genesis());
+    let mut candidate = current_chain.genesis_only();
     let mut valid = true;
 
     for (i, block) in peer_blocks.iter().enumerate().skip(1) {
         // Validate block structure and consensus rules
-        if candidate.add_block(block.clone(), 1800).is_err() {
+        if candidate.add_block(block.clone(), candidate.block_time).is_err() {
             println!("⚠️  Invalid block at height {} from peer - rejecting chain", i);
             valid = false;
             break;
@@ -53,6 +84,8 @@ fn validate_and_sync_chain(peer_blocks: &[Block], current_chain: &Timechain) ->
     let current_work = calculate_chain_work(current_chain);
 
     if candidate.blocks.len() > current_chain.blocks.len() || peer_work > current_work {
+        // Blocks we finalized by checkpoint stay final on the adopted chain
+        candidate.finalized_height = candidate.finalized_height.max(current_chain.finalized_height);
         println!("✅ Peer chain validated - Work: {} vs {}", peer_work, current_work);
         Some(candidate)
     } else {
@@ -60,6 +93,73 @@ fn validate_and_sync_chain(peer_blocks: &[Block], current_chain: &Timechain) ->
     }
 }
 
+/// Queue a write of the account index for readers like the explorer
+fn persist_state(storage: &pipeline::StorageWriter, chain: &Timechain) {
+    let tip = chain.blocks.last().unwrap();
+    storage.submit(pipeline::StorageJob::SaveState { height: tip.slot, block_hash: tip.hash(), state: chain.state.clone() });
+}
+
+/// Prune block bodies below the served snapshot's checkpoint once that is
+/// final, as `storage.pruning` allows; a restart rebuilds from the snapshot
+fn prune_log(
+    storage: &pipeline::StorageWriter,
+    chain: &Timechain,
+    pruning: config::PruningMode,
+    served_snapshot: &Mutex<Option<snapshot::Snapshot>>,
+) {
+    let served = served_snapshot.lock().unwrap();
+    let Some(checkpoint) = served.as_ref().map(|s| &s.manifest.checkpoint).filter(|c| {
+        c.height <= chain.finalized_height
+            && chain.blocks.get(c.height as usize).is_some_and(|b| b.hash() == c.block_hash)
+    }) else {
+        return;
+    };
+    let tip = chain.blocks.len() as u64 - 1;
+    let keep_depth = storage::prune_depth(tip, chain.confirmation_depth, Some(checkpoint.height));
+    storage.submit(pipeline::StorageJob::Prune { mode: pruning, keep_depth });
+}
+
+/// Drop `block`'s transactions, and any they conflict with, from the
+/// mempool, which announces them
+fn prune_mined(mempool: &mut Mempool, block: &Block) {
+    mempool.remove_mined(block);
+    metrics::set_mempool_size(mempool.len());
+}
+
+/// Add a block received from a peer; on success announce, store and index it
+fn accept_peer_block(
+    tc: &mut Timechain,
+    mempool: &mut Mempool,
+    block: Block,
+    elapsed: u64,
+    node_events: &tokio::sync::broadcast::Sender<rpc::NodeEvent>,
+    tx_traces: &rpc::TxTracer,
+    storage: &pipeline::StorageWriter,
+) -> bool {
+    if tc.add_block(block.clone(), elapsed).is_err() {
+        return false;
+    }
+    metrics::record_block(tc.blocks.len() as u64 - 1);
+    tx_traces.record_block(&block);
+    prune_mined(mempool, &block);
+    let _ = node_events.send(rpc::NodeEvent::NewBlock(block.clone()));
+    storage.submit(pipeline::StorageJob::AppendBlock(block));
+    persist_state(storage, tc);
+    true
+}
+
+/// Announce `block` as a compact block; peers rebuild it from their mempools
+fn compact_announcement(block: &Block) -> Vec<u8> {
+    network::encode_gossip(&compact::CompactBlock::from_block(block))
+}
+
+/// Decode a gossip message, logging why it was dropped if it can't be
+fn gossip_payload<T: serde::de::DeserializeOwned>(data: &[u8], source: &PeerId) -> Option<T> {
+    network::decode_gossip(data)
+        .map_err(|e| println!("⚠️  Dropping gossip from {}: {}", source, e))
+        .ok()
+}
+
 /// Calculate total work (cumulative difficulty) of a chain
 fn calculate_chain_work(chain: &Timechain) -> u64 {
     chain.blocks.iter().map(|block| block.nonce.max(1)).sum()
@@ -67,9 +167,38 @@ fn calculate_chain_work(chain: &Timechain) -> u64 {
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let args = cli::Cli::parse();
+    match cli::dispatch(args.command.unwrap_or(cli::Command::Run), &mut std::io::stdout()) {
+        Ok(cli::Outcome::RunNode) => {}
+        Ok(cli::Outcome::Done) => return Ok(()),
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    // `tracing` output, e.g. transaction traces, is selected with RUST_LOG
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+
+    let node_config = axiom_core::AxiomConfig::load().unwrap_or_default();
+    let vdf_steps = match node_config
+        .validate_network()
+        .and_then(|_| node_config.validate_mining())
+        .and_then(|_| node_config.validate_mempool())
+        .and_then(|_| node_config.consensus.block_vdf_steps())
+    {
+        Ok(steps) => steps,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
+
     println!("--------------------------------------------------");
     println!("🏛️  AXIOM CORE | PRIVACY-FIRST BLOCKCHAIN");
-    println!("🛡️  VDF: 1800sec (30min) | PoW Hybrid | 124M Fixed Supply");
+    println!("🛡️  VDF: {}sec | PoW Hybrid | 124M Fixed Supply", node_config.consensus.block_time_seconds);
     println!("🤖 AI NEURAL GUARDIAN: ATTACK DETECTION ACTIVE");
     println!("🔐 MANDATORY ZK-SNARK PRIVACY | ED25519 SIGNATURES");
     println!("--------------------------------------------------");
@@ -82,33 +211,164 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
     let wallet = Wallet::load_or_create();
     println!("💳 Wallet Address: {:?}", hex::encode(wallet.address));
     println!("📁 Wallet file: ./wallet.dat (keep safe!)");
+    // Validated above; rewards go to the wallet unless mining.miner_address is set
+    let reward_address = node_config.mining.reward_address().ok().flatten().unwrap_or(wallet.address);
+    println!("⛏️  Reward Address: {}", hex::encode(reward_address));
     let ai_guardian = Arc::new(Mutex::new(NeuralGuardian::new()));
     let mut peer_message_counts: HashMap<PeerId, (u32, Instant)> = HashMap::new();
+    // Reputation outlives the session; peers are matched to it by public key once identified
+    let mut reputations = reputation::ReputationStore::load(reputation::REPUTATION_PATH);
+    let mut peer_keys: HashMap<PeerId, reputation::PeerKey> = HashMap::new();
 
-    // Transaction mempool
-    let mut mempool: VecDeque<Transaction> = VecDeque::new();
+    // Accepted blocks and transactions, fanned out to WebSocket subscribers
+    let (node_events, _) = tokio::sync::broadcast::channel::<rpc::NodeEvent>(256);
+    // Transaction mempool, shared with the RPC server; it announces its own
+    // additions, replacements and evictions to subscribers
+    let mut mempool = Mempool::new()
+        .with_consensus_policy(&node_config.consensus)
+        // Validated above; no [mempool] rules admits whatever the consensus checks pass
+        .with_policy(node_config.mempool.policy().unwrap_or_default())
+        .with_events(node_events.clone());
 
-    let mut tc = if let Some(saved_blocks) = storage::load_chain() {
-        let mut chain = Timechain::new(genesis::genesis());
-        for b in saved_blocks { let _ = chain.add_block(b, 1800); }
-        chain
-    } else {
-        Timechain::new(genesis::genesis())
+    if node_config.is_regtest() {
+        println!("🧪 REGTEST: {}s blocks, generate_blocks RPC enabled", node_config.consensus.block_time_seconds);
+    }
+
+    // Genesis from ./genesis.toml, or the network's built-in genesis when absent
+    let genesis_config = match genesis::GenesisConfig::for_network(node_config.network.network_id) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("❌ Invalid {}: {}", genesis::GENESIS_FILE, e);
+            std::process::exit(1);
+        }
+    };
+    // Every chain this node builds retargets against the configured block time,
+    // checks block VDFs at the configured length, applies the configured dust,
+    // fee-rate, block-size, finality and fee-burn policy and only accepts
+    // transactions signed for this network
+    let new_chain = || match Timechain::with_genesis(&genesis_config) {
+        Ok(chain) => chain
+            .with_block_time(node_config.consensus.block_time_seconds)
+            .with_vdf_steps(vdf_steps)
+            .with_dust_limit(node_config.consensus.dust_limit, node_config.consensus.allow_zero_amount)
+            .with_min_fee_per_byte(node_config.consensus.min_fee_per_byte)
+            .with_block_limits(
+                node_config.consensus.max_block_size,
+                node_config.consensus.max_transactions_per_block,
+            )
+            .with_confirmation_depth(node_config.consensus.confirmation_depth)
+            .with_fee_burn_fraction(node_config.consensus.fee_burn_fraction)
+            .with_network_id(node_config.network.network_id),
+        Err(e) => {
+            eprintln!("❌ Invalid {}: {}", genesis::GENESIS_FILE, e);
+            std::process::exit(1);
+        }
+    };
+    if !genesis_config.is_mainnet() {
+        println!("🧪 Custom genesis {}", hex::encode(new_chain().blocks[0].hash()));
+    }
+
+    let checkpoint_key = ed25519_dalek::SigningKey::from_bytes(&wallet.secret_key);
+    let saved = storage::load_records();
+    let saved_len = saved.as_ref().map_or(0, Vec::len);
+    let tc = match saved {
+        Some(records) if records.first().map(|r| r.hash) != Some(new_chain().blocks[0].hash()) => {
+            eprintln!("⚠️  Saved chain was built on a different genesis; not restoring it");
+            new_chain()
+        }
+        Some(records) => {
+            let mut chain = new_chain();
+            // The account index saved at our tip spares a replay
+            let index = storage::load_state().filter(|index| {
+                records.last().is_some_and(|r| r.block.slot == index.height && r.hash == index.block_hash)
+                    && !records.iter().any(|r| r.pruned)
+            });
+            let resumed = index.is_some_and(|index| match chain.resume(records.clone(), index) {
+                Ok(()) => {
+                    println!("⚡ Resumed {} blocks from the saved account index", chain.blocks.len());
+                    true
+                }
+                Err(e) => {
+                    eprintln!("⚠️  Saved account index unusable ({}); replaying", e);
+                    false
+                }
+            });
+            if resumed {
+                chain
+            } else {
+                // Our own checkpoints, and configured ones from trusted signers, let
+                // us skip re-validating the saved chain; our snapshot stands in for
+                // pruned bodies
+                let mut trusted = node_config.network.checkpoint_signers().unwrap_or_default();
+                trusted.push(checkpoint_key.verifying_key().to_bytes());
+                let mut checkpoints = storage::load_checkpoints();
+                checkpoints.extend(node_config.network.checkpoints().unwrap_or_default());
+                checkpoints.sort_by_key(|c| c.height);
+                match chain.restore(records, &checkpoints, &trusted, storage::load_snapshot().as_ref()) {
+                    Ok(Some(height)) => {
+                        println!("⚡ Fast-synced {} blocks from checkpoint H-{}", chain.blocks.len(), height);
+                        chain
+                    }
+                    Ok(None) => chain,
+                    Err(e) => {
+                        eprintln!("⚠️  Could not restore the saved chain ({}); resyncing", e);
+                        new_chain()
+                    }
+                }
+            }
+        }
+        None => new_chain(),
     };
+    // Disk writes from here on go through a worker, off the event loop
+    let storage_writer = pipeline::StorageWriter::spawn(pipeline::STORAGE_QUEUE_CAPACITY)?;
+    // The block log is only written when it does not hold exactly the
+    // restored chain (or when there is none yet, as with the legacy format);
+    // a saved chain we could not restore in full is moved aside first
+    // rather than overwritten
+    let restored_all = tc.blocks.len() == saved_len;
+    if !restored_all || storage::tip_height().is_err() {
+        if !restored_all && saved_len > 0 {
+            match storage::set_aside_chain() {
+                Ok(Some(path)) => println!("📦 Kept the unrestored chain at {}", path.display()),
+                Ok(None) => {}
+                Err(e) => {
+                    eprintln!("❌ Could not move the saved chain aside ({}); refusing to overwrite it", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        storage::save_chain(&tc.blocks);
+    }
+    persist_state(&storage_writer, &tc);
+
+    // Restore transactions that were pending at the last shutdown
+    for tx in storage::load_mempool() {
+        if tc.validate_transaction(&tx).is_ok() && !mempool.contains(&tx.hash()) {
+            let _ = mempool.add(tx, &tc.state);
+        }
+    }
+
+    // Prometheus /metrics endpoint (only with the `prometheus` feature and
+    // `node.metrics_enabled`)
+    metrics::init();
+    metrics::set_chain_height(tc.blocks.len() as u64 - 1);
+    #[cfg(feature = "prometheus")]
+    if node_config.node.metrics_enabled {
+        let metrics_addr = &node_config.node.metrics_address;
+        match metrics::serve(metrics_addr) {
+            Ok(server) => {
+                println!("📊 Metrics: http://{}/metrics", metrics_addr);
+                tokio::spawn(server);
+            }
+            Err(e) => eprintln!("⚠️  Metrics server failed to bind {}: {}", metrics_addr, e),
+        }
+    }
 
     // 2. NETWORK SETUP
     // --- Network Setup with Dynamic Port Hunting and Bootstrap Peers ---
-    let bootstrap_peers: Vec<String> = std::env::var("AXIOM_BOOTSTRAP_PEERS")
-        .unwrap_or_default()
-        .split(',')
-        .filter(|s| !s.trim().is_empty())
-        .map(|s| s.trim().to_string())
-        .collect();
-    let mut swarm = if !bootstrap_peers.is_empty() {
-        network::init_network_with_bootstrap(bootstrap_peers).await?
-    } else {
-        network::init_network().await?
-    };
+    // Env override, else the signed manifest, else the compiled-in defaults
+    let bootstrap_peers = network::bootstrap_peers();
+    let mut swarm = network::init_network_with_bootstrap(bootstrap_peers.clone()).await?;
 
     let mut current_port: u16 = 6000;
     let max_port: u16 = 6003; // Restricted to 4 genesis mining nodes only
@@ -145,80 +405,36 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
     let req_topic = gossipsub::IdentTopic::new("timechain-requests");
     let chain_topic = gossipsub::IdentTopic::new("timechain-chain");
     let tx_topic = gossipsub::IdentTopic::new("timechain-transactions");
+    let compact_topic = gossipsub::IdentTopic::new("timechain-compact-blocks");
     let _ = swarm.behaviour_mut().gossipsub.subscribe(&blocks_topic);
+    let _ = swarm.behaviour_mut().gossipsub.subscribe(&compact_topic);
     let _ = swarm.behaviour_mut().gossipsub.subscribe(&req_topic);
     let _ = swarm.behaviour_mut().gossipsub.subscribe(&chain_topic);
     let _ = swarm.behaviour_mut().gossipsub.subscribe(&tx_topic);
 
     // 3. BOOTSTRAP CONNECTIONS - Connect to mainnet bootnodes for global sync
     println!("🌍 Bootstrap Configuration:");
-    let mut bootstrap_addrs = Vec::new();
     let mut bootstrap_connected = 0;
-    
-    // First, try environment variable for dynamic bootstrap peers
-    let env_bootstrap_peers: Vec<String> = std::env::var("AXIOM_BOOTSTRAP_PEERS")
-        .unwrap_or_default()
-        .split(',')
-        .filter(|s| !s.trim().is_empty())
-        .map(|s| s.trim().to_string())
-        .collect();
-    
-    if !env_bootstrap_peers.is_empty() {
-        println!("   📌 Using AXIOM_BOOTSTRAP_PEERS environment variable");
-        for addr_str in &env_bootstrap_peers {
-            if let Ok(addr) = addr_str.parse::<Multiaddr>() {
-                bootstrap_addrs.push((addr_str.clone(), addr.clone()));
-                match swarm.dial(addr.clone()) {
-                    Ok(_) => {
-                        println!("   ✅ Dialing bootstrap node: {}", addr_str);
-                        bootstrap_connected += 1;
-                        if let Some(peer_str) = addr_str.split("/p2p/").nth(1) {
-                            if let Ok(peer_id) = peer_str.parse::<libp2p::PeerId>() {
-                                swarm.behaviour_mut().kademlia.add_address(&peer_id, addr);
-                            }
-                        }
-                    },
-                    Err(e) => println!("   ⚠️  Failed to dial bootstrap node {}: {:?}", addr_str, e),
-                }
-            }
-        }
-    } else if let Ok(bootstrap_content) = std::fs::read_to_string("config/bootstrap.toml") {
-        // Fall back to config file
-        if let Ok(bootstrap_config) = toml::from_str::<toml::Value>(&bootstrap_content) {
-            if let Some(bootnodes) = bootstrap_config.get("bootnodes").and_then(|v| v.as_array()) {
-                if !bootnodes.is_empty() {
-                    println!("   📌 Using config/bootstrap.toml addresses");
-                    for bootnode in bootnodes {
-                        if let Some(addr_str) = bootnode.as_str() {
-                            if let Ok(addr) = addr_str.parse::<Multiaddr>() {
-                                bootstrap_addrs.push((addr_str.to_string(), addr.clone()));
-                                match swarm.dial(addr.clone()) {
-                                    Ok(_) => {
-                                        println!("   ✅ Dialing bootstrap node: {}", addr_str);
-                                        bootstrap_connected += 1;
-                                        if let Some(peer_str) = addr_str.split("/p2p/").nth(1) {
-                                            if let Ok(peer_id) = peer_str.parse::<libp2p::PeerId>() {
-                                                swarm.behaviour_mut().kademlia.add_address(&peer_id, addr);
-                                            }
-                                        }
-                                    },
-                                    Err(e) => println!("   ⚠️  Failed to dial bootstrap node {}: {:?}", addr_str, e),
-                                }
-                            }
+    for addr_str in &bootstrap_peers {
+        if let Ok(addr) = addr_str.parse::<Multiaddr>() {
+            match swarm.dial(addr.clone()) {
+                Ok(_) => {
+                    println!("   ✅ Dialing bootstrap node: {}", addr_str);
+                    bootstrap_connected += 1;
+                    if let Some(peer_str) = addr_str.split("/p2p/").nth(1) {
+                        if let Ok(peer_id) = peer_str.parse::<libp2p::PeerId>() {
+                            swarm.behaviour_mut().kademlia.add_address(&peer_id, addr);
                         }
                     }
-                } else {
-                    println!("   📌 No bootstrap nodes configured (config/bootstrap.toml is empty)");
-                }
+                },
+                Err(e) => println!("   ⚠️  Failed to dial bootstrap node {}: {:?}", addr_str, e),
             }
         }
-    } else {
-        println!("   📌 No bootstrap config found");
     }
     
     if bootstrap_connected == 0 {
         println!("   🌐 Using mDNS for local peer discovery only");
-        println!("   💡 For mainnet: Set AXIOM_BOOTSTRAP_PEERS env var or update config/bootstrap.toml");
+        println!("   💡 For mainnet: Set AXIOM_BOOTSTRAP_PEERS env var or install a signed config/bootstrap.toml");
     } else {
         println!("   ✅ {} bootstrap nodes queued for connection", bootstrap_connected);
     }
@@ -252,6 +468,23 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
     
     // Track connected peers for network monitoring
     let mut connected_peers: std::collections::HashSet<libp2p::PeerId> = std::collections::HashSet::new();
+    // Per-subnet connection caps against Sybil/eclipse peer sets
+    let mut peer_diversity = network::PeerDiversity::from_config(&node_config.network);
+    let mut mining_status = miner::MiningStatus::Active;
+    // Compact blocks waiting on transactions from their announcer, by merkle root
+    let mut pending_compact: HashMap<[u8; 32], compact::PartialBlock> = HashMap::new();
+    // Snap sync: peers' snapshots are only taken at checkpoints from these signers
+    let snapshot_signers = node_config.network.checkpoint_signers().unwrap_or_default();
+    let mut snap_sync: Option<snapshot::SnapshotAssembler> = None;
+    let mut snapshot_declined: HashSet<PeerId> = HashSet::new();
+    // Snapshot served to syncing peers, refreshed at every checkpoint
+    let served_snapshot = Arc::new(Mutex::new(storage::load_snapshot()));
+    let pruning = node_config.storage.pruning;
+    // Blocks past the snapshot's checkpoint, collected batch by batch
+    let mut snap_blocks: Vec<Block> = Vec::new();
+    // Batched catch-up from the peer furthest ahead
+    let max_blocks_per_response = node_config.network.max_blocks_per_response;
+    let mut download: Option<sync::BlockDownload> = None;
     
     // Known peer addresses for cross-network discovery (can be set via env)
     let known_peers: Vec<String> = std::env::var("AXIOM_KNOWN_PEERS")
@@ -261,197 +494,582 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
         .map(|s| s.trim().to_string())
         .collect();
 
+    // Live chain and mempool, shared with the RPC server
+    let shared_chain = Arc::new(Mutex::new(tc));
+    let shared_mempool = Arc::new(Mutex::new(mempool));
+    let (rpc_tx_relay, mut rpc_relayed_txs) = tokio::sync::mpsc::unbounded_channel::<Transaction>();
+    // Stages of transactions submitted over RPC, logged under their trace IDs
+    let tx_traces = Arc::new(rpc::TxTracer::default());
+
+    // Blocks mined or submitted over RPC, persisted and gossiped by the main loop
+    let (rpc_block_relay, mut rpc_relayed_blocks) = tokio::sync::mpsc::unbounded_channel::<Block>();
+    let block_time = node_config.consensus.block_time_seconds;
+    let regtest = node_config.is_regtest();
+    let mining_threads = node_config.mining.threads;
+    let mining_intensity = node_config.mining.intensity;
+    let min_peers_to_mine = node_config.mining.min_peers_to_mine;
+    let solo_mining = regtest || node_config.mining.solo;
+    // Peer heights, compared against our tip before mining and over RPC
+    let sync_tracker = Arc::new(Mutex::new(sync::SyncTracker::new()));
+    let peer_count = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+    // Peer blocks and transactions are validated by workers, so a burst of
+    // gossip queues up (or is shed) instead of holding up the swarm
+    let block_queue = {
+        let (chain, mempool, events, traces, storage, snapshot) = (
+            shared_chain.clone(), shared_mempool.clone(), node_events.clone(), tx_traces.clone(), storage_writer.clone(),
+            served_snapshot.clone(),
+        );
+        pipeline::WorkQueue::spawn("blocks", pipeline::BLOCK_QUEUE_CAPACITY, move |(block, elapsed): (Block, u64)| {
+            let mut tc = chain.lock().unwrap();
+            let mut mempool = mempool.lock().unwrap();
+            let slot = block.slot;
+            if accept_peer_block(&mut tc, &mut mempool, block, elapsed, &events, &traces, &storage) {
+                println!("✅ Peer block H-{} accepted and added to chain", slot);
+                prune_log(&storage, &tc, pruning, &snapshot);
+            }
+        })?
+    };
+    let tx_queue = {
+        let (chain, mempool) = (shared_chain.clone(), shared_mempool.clone());
+        pipeline::WorkQueue::spawn("transactions", pipeline::TX_QUEUE_CAPACITY, move |tx: Transaction| {
+            let tc = chain.lock().unwrap();
+            let mut mempool = mempool.lock().unwrap();
+            if tc.validate_transaction(&tx).is_ok() && !mempool.contains(&tx.hash()) {
+                // A fee bump replaces the pending transaction with its nonce
+                match mempool.add(tx, &tc.state) {
+                    Ok(()) => {
+                        metrics::set_mempool_size(mempool.len());
+                        println!("✅ Transaction added to mempool");
+                    }
+                    Err(e) => log::debug!("Mempool refused gossiped transaction: {}", e),
+                }
+            }
+        })?
+    };
+
+    // 5. JSON-RPC SERVER
+
+    // Reuse persisted Groth16 keys; only the first start runs setup
+    let keys_dir = node_config.storage.zk_keys_dir.to_string_lossy().into_owned();
+    let zk_system = match axiom_core::zk::circuit::ZkProofSystem::load_or_setup(&keys_dir) {
+        Ok(zk) => {
+            println!("🔑 ZK keys loaded from {}", keys_dir);
+            Some(Arc::new(zk))
+        }
+        Err(e) => {
+            eprintln!("⚠️  ZK keys unavailable ({}): {}", keys_dir, e);
+            None
+        }
+    };
+
+    let rpc_config = node_config.rpc;
+    if rpc_config.enabled {
+        let mut rpc_state = rpc::RpcState::new(shared_chain.clone(), shared_mempool.clone())
+            .with_tx_relay(rpc_tx_relay)
+            .with_events(node_events.clone())
+            .with_block_relay(rpc_block_relay)
+            .with_sync(sync_tracker.clone())
+            .with_peers(peer_count.clone(), if solo_mining { 0 } else { min_peers_to_mine })
+            .with_proof_limits(rpc_config.max_concurrent_proofs, rpc_config.max_queued_proofs)
+            .with_proof_store(rpc::proof_store::DEFAULT_STORED_PROOFS, rpc_config.proof_chunk_size)
+            .with_block_cache(node_config.storage.block_cache_capacity)
+            .with_tracer(tx_traces.clone());
+        if let Some(zk) = &zk_system {
+            rpc_state = rpc_state.with_zk_system(zk.clone());
+        }
+        if regtest {
+            rpc_state = rpc_state.with_regtest_miner(Arc::new(Wallet::from_secret_key(wallet.secret_key)));
+        }
+        match rpc::serve(&rpc_config, rpc_state) {
+            Ok((server, addr)) => {
+                println!("🛰️  JSON-RPC listening on http://{}/rpc", addr);
+                tokio::spawn(server);
+            }
+            Err(e) => eprintln!("⚠️  RPC server failed to bind {}: {}", rpc_config.listen_address, e),
+        }
+    }
+
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+
     loop {
         tokio::select! {
-            // --- P2P EVENT LOOP: AI-ASSISTED SPAM & DOS PROTECTION ---
-            event = swarm.select_next_some() => match event {
-                SwarmEvent::Behaviour(network::TimechainBehaviourEvent::Gossipsub(gossipsub::Event::Message {
-                    propagation_source, message, ..
-                })) => {
-                    // Rate limiting: allow max 100 messages per peer per minute
-                    let now = Instant::now();
-                    let entry = peer_message_counts.entry(propagation_source).or_insert((0, now));
-                    if now.duration_since(entry.1) > Duration::from_secs(60) {
-                        entry.0 = 0;
-                        entry.1 = now;
-                    }
-                    entry.0 += 1;
-                    if entry.0 > 100 {
-                        println!("🚨 DoS protection: Peer {} exceeded message rate limit, ignoring", propagation_source);
-                        continue;
+            // --- GRACEFUL SHUTDOWN ---
+            // Branches run to completion, so any save_chain/append_block in
+            // progress has finished before this one is polled.
+            _ = &mut shutdown => {
+                let tc = shared_chain.lock().unwrap();
+                let mempool = shared_mempool.lock().unwrap();
+                println!("\n🛑 Shutdown signal received. Stopping mining and flushing state...");
+                // Queued writes land first so the final save has the last word
+                storage_writer.flush();
+                storage::save_chain(&tc.blocks);
+                if let Err(e) = storage::save_state(tc.blocks.len() as u64 - 1, tc.blocks.last().unwrap().hash(), &tc.state) {
+                    eprintln!("❌ STORAGE ERROR: Could not save state index: {}", e);
+                }
+                let pending: Vec<Transaction> = mempool.iter().cloned().collect();
+                if let Err(e) = storage::save_mempool(&pending) {
+                    eprintln!("❌ STORAGE ERROR: Could not save mempool: {}", e);
+                }
+                if let Ok(ai) = ai_guardian.lock() {
+                    if let Err(e) = ai.save_stats("ai_stats.json") {
+                        eprintln!("⚠️  Could not save AI stats: {}", e);
                     }
+                }
+                if let Err(e) = reputations.save_to_file(reputation::REPUTATION_PATH) {
+                    eprintln!("⚠️  Could not save peer reputations: {}", e);
+                }
+                for peer in connected_peers.drain() {
+                    let _ = swarm.disconnect_peer_id(peer);
+                }
+                println!("✅ Chain saved at height {} with {} pending txs. Goodbye.", tc.blocks.len() - 1, pending.len());
+                break;
+            }
 
-                    let mut ai = ai_guardian.lock().unwrap();
-                    let is_trustworthy = ai.predict_trust(1.0 / (entry.0 as f32), 1.0, 1.0);
-
-                    if is_trustworthy && entry.0 <= 15 {
-                        // 1) If this is a chain request, respond with our entire chain
-                        if message.data == b"REQ_CHAIN" {
-                            if let Ok(encoded) = bincode::serialize(&tc.blocks) {
-                                let _ = swarm.behaviour_mut().gossipsub.publish(chain_topic.clone(), encoded);
-                            }
+            // --- P2P EVENT LOOP: AI-ASSISTED SPAM & DOS PROTECTION ---
+            event = swarm.select_next_some() => {
+                let mut tc = shared_chain.lock().unwrap();
+                let mut mempool = shared_mempool.lock().unwrap();
+                match event {
+                    SwarmEvent::Behaviour(network::TimechainBehaviourEvent::Gossipsub(gossipsub::Event::Message {
+                        propagation_source, message, ..
+                    })) => {
+                        // Rate limiting: allow max 100 messages per peer per minute
+                        let now = Instant::now();
+                        let entry = peer_message_counts.entry(propagation_source).or_insert((0, now));
+                        if now.duration_since(entry.1) > Duration::from_secs(60) {
+                            entry.0 = 0;
+                            entry.1 = now;
                         }
-                        // 2) If this is a block, validate and add it
-                        else if message.topic == blocks_topic.hash() {
-                            if let Ok(block) = bincode::deserialize::<Block>(&message.data) {
-                                let elapsed = last_vdf.elapsed().as_secs();
-                                if tc.add_block(block, elapsed).is_ok() {
-                                    println!("✅ Block accepted and added to chain");
-                                    storage::save_chain(&tc.blocks);
+                        entry.0 += 1;
+                        let unix_now = chrono::Utc::now().timestamp() as u64;
+                        let peer_key = peer_keys.get(&propagation_source).copied();
+                        if entry.0 > 100 {
+                            println!("🚨 DoS protection: Peer {} exceeded message rate limit, ignoring", propagation_source);
+                            if let Some(key) = peer_key {
+                                reputations.record_violation(key, unix_now);
+                                if reputations.is_flagged(&key, unix_now) {
+                                    println!("🚫 Banning {}: reputation exhausted by flooding", propagation_source);
+                                    reputations.record_ban(key, "message rate limit", unix_now);
+                                    let _ = swarm.disconnect_peer_id(propagation_source);
                                 }
                             }
+                            continue;
                         }
-                        // 3) If this is a transaction, validate and add to mempool
-                        else if message.topic == tx_topic.hash() {
-                            if let Ok(tx) = bincode::deserialize::<Transaction>(&message.data) {
-                                if tc.validate_transaction(&tx).is_ok() && !mempool.contains(&tx) {
-                                    mempool.push_back(tx);
-                                    println!("✅ Transaction added to mempool");
+
+                        let mut ai = ai_guardian.lock().unwrap();
+                        let is_trustworthy = ai.predict_trust(1.0 / (entry.0 as f32), 1.0, 1.0);
+                        if let Some(key) = peer_key {
+                            reputations.record_trust(key, if is_trustworthy { 1.0 } else { 0.0 }, unix_now);
+                        }
+
+                        if is_trustworthy && entry.0 <= 15 {
+                            // 1) If this is a chain request, respond with our entire chain
+                            if message.data == b"REQ_CHAIN" {
+                                let _ = swarm.behaviour_mut().gossipsub.publish(chain_topic.clone(), network::encode_gossip(&tc.blocks));
+                            }
+                            // 2) If this is a block, validate and add it
+                            else if message.topic == blocks_topic.hash() {
+                                if let Some(block) = gossip_payload::<Block>(&message.data, &propagation_source) {
+                                    sync_tracker.lock().unwrap().observe(propagation_source, block.slot);
+                                    let elapsed = last_vdf.elapsed().as_secs();
+                                    if !block_queue.submit((block, elapsed)) {
+                                        log::debug!("Block queue full; dropped block from {}", propagation_source);
+                                    }
+                                }
+                            }
+                            // Compact block: rebuild from the mempool, fetching only what is missing
+                            else if message.topic == compact_topic.hash() {
+                                if let Some(announced) = gossip_payload::<compact::CompactBlock>(&message.data, &propagation_source) {
+                                    sync_tracker.lock().unwrap().observe(propagation_source, announced.slot);
+                                    let merkle_root = announced.merkle_root;
+                                    let partial = compact::PartialBlock::new(announced, mempool.iter());
+                                    if partial.is_complete() {
+                                        let elapsed = last_vdf.elapsed().as_secs();
+                                        if let Ok(block) = partial.into_block() {
+                                            if !block_queue.submit((block, elapsed)) {
+                                                log::debug!("Block queue full; dropped compact block from {}", propagation_source);
+                                            }
+                                        }
+                                    } else {
+                                        let missing = partial.missing();
+                                        println!("📦 Compact block needs {} transaction(s) from {}", missing.len(), propagation_source);
+                                        let _ = swarm.behaviour_mut().request_response.send_request(
+                                            &propagation_source,
+                                            network::ChainRequest::transactions(merkle_root, missing),
+                                        );
+                                        // Announcers that never answer must not grow this without bound
+                                        if pending_compact.len() >= 16 {
+                                            pending_compact.clear();
+                                        }
+                                        pending_compact.insert(merkle_root, partial);
+                                    }
+                                }
+                            }
+                            // 3) If this is a transaction, validate and add to mempool
+                            else if message.topic == tx_topic.hash() {
+                                if let Some(tx) = gossip_payload::<Transaction>(&message.data, &propagation_source) {
+                                    if !tx_queue.submit(tx) {
+                                        log::debug!("Transaction queue full; dropped transaction from {}", propagation_source);
+                                    }
                                 }
                             }
-                        }
 
-                        // 2) If this is a full chain broadcast, attempt to adopt it if it's longer and valid
-                        else if message.topic == chain_topic.hash() {
-                            if let Ok(peer_blocks) = bincode::deserialize::<Vec<Block>>(&message.data) {
-                                // Enhanced chain validation for global consensus
-                                if let Some(valid_chain) = validate_and_sync_chain(&peer_blocks, &tc) {
-                                    tc = valid_chain;
-                                    println!("🔁 Synced complete chain from peer. New height: {}", tc.blocks.len());
-                                    storage::save_chain(&tc.blocks);
-                                    last_vdf = Instant::now();
+                            // 2) If this is a full chain broadcast, attempt to adopt it if it's longer and valid
+                            else if message.topic == chain_topic.hash() {
+                                if let Some(peer_blocks) = gossip_payload::<Vec<Block>>(&message.data, &propagation_source) {
+                                    if let Some(tip) = peer_blocks.last() {
+                                        sync_tracker.lock().unwrap().observe(propagation_source, tip.slot);
+                                    }
+                                    // Enhanced chain validation for global consensus
+                                    if let Some(valid_chain) = validate_and_sync_chain(&peer_blocks, &tc) {
+                                        *tc = valid_chain;
+                                        println!("🔁 Synced complete chain from peer. New height: {}", tc.blocks.len());
+                                        storage_writer.submit(pipeline::StorageJob::SaveChain(tc.blocks.clone()));
+                                        persist_state(&storage_writer, &tc);
+                                        metrics::set_chain_height(tc.blocks.len() as u64 - 1);
+                                        last_vdf = Instant::now();
 
-                                    // Broadcast our updated chain state to help other peers sync
-                                    if let Ok(encoded) = bincode::serialize(&tc.blocks) {
-                                        let _ = swarm.behaviour_mut().gossipsub.publish(chain_topic.clone(), encoded);
+                                        // Broadcast our updated chain state to help other peers sync
+                                        let _ = swarm.behaviour_mut().gossipsub.publish(chain_topic.clone(), network::encode_gossip(&tc.blocks));
                                     }
                                 }
                             }
-                        }
 
-                        // 3) Otherwise try to decode as a single Block (existing behaviour)
-                        else if let Ok(incoming_block) = bincode::deserialize::<Block>(&message.data) {
-                            let elapsed = last_vdf.elapsed().as_secs();
+                            // 3) Otherwise try to decode as a single Block (existing behaviour)
+                            else if let Some(incoming_block) = gossip_payload::<Block>(&message.data, &propagation_source) {
+                                sync_tracker.lock().unwrap().observe(propagation_source, incoming_block.slot);
+                                let elapsed = last_vdf.elapsed().as_secs();
 
-                            // RESOLVED: last_diff is now updated before being used in dashboard
-                            last_diff = tc.difficulty;
+                                // RESOLVED: last_diff is now updated before being used in dashboard
+                                last_diff = tc.difficulty;
 
-                            if tc.add_block(incoming_block.clone(), elapsed).is_ok() {
-                                println!("📥 AI Verified Block: H-{}", tc.blocks.len());
-                                storage::save_chain(&tc.blocks);
-                                last_vdf = Instant::now();
-                                ai.train([1.0, 1.0, 1.0], 1.0);
+                                if tc.add_block(incoming_block.clone(), elapsed).is_ok() {
+                                    println!("📥 AI Verified Block: H-{}", tc.blocks.len());
+                                    metrics::record_block(tc.blocks.len() as u64 - 1);
+                                    prune_mined(&mut mempool, &incoming_block);
+                                    let _ = node_events.send(rpc::NodeEvent::NewBlock(incoming_block.clone()));
+                                    storage_writer.submit(pipeline::StorageJob::AppendBlock(incoming_block));
+                                    persist_state(&storage_writer, &tc);
+                                    prune_log(&storage_writer, &tc, pruning, &served_snapshot);
+                                    last_vdf = Instant::now();
+                                    ai.train([1.0, 1.0, 1.0], 1.0);
+                                }
                             }
+                        } else if entry.0 > 20 {
+                            ai.train([0.1, 0.0, 0.0], 0.0);
                         }
-                    } else if entry.0 > 20 {
-                        ai.train([0.1, 0.0, 0.0], 0.0);
-                    }
-                },
-                SwarmEvent::NewListenAddr { address, .. } => {
-                    println!("🌐 Node active on: {:?}", address);
-                    // Check if this is an external address
-                    if address.to_string().contains("/ip4/") && !address.to_string().contains("/ip4/127.0.0.1") && !address.to_string().contains("/ip4/0.0.0.0") {
-                        println!("🌍 External address detected! Other nodes can connect to: {}/p2p/{}", address, swarm.local_peer_id());
-                    }
-                    // Announce our current chain to the local network to help new peers sync
-                    if let Ok(encoded) = bincode::serialize(&tc.blocks) {
-                        let _ = swarm.behaviour_mut().gossipsub.publish(chain_topic.clone(), encoded);
-                    }
-                },
-                SwarmEvent::ConnectionEstablished { peer_id, endpoint, .. } => {
-                    connected_peers.insert(peer_id);
-                    println!("🔗 Peer connected: {} | Total peers: {}", peer_id, connected_peers.len());
-                    println!("   └─ Direction: {:?} | Address: {:?}", endpoint.is_dialer(), endpoint.get_remote_address());
-                },
-                SwarmEvent::ConnectionClosed { peer_id, cause, .. } => {
-                    connected_peers.remove(&peer_id);
-                    println!("🔌 Peer disconnected: {} | Total peers: {}", peer_id, connected_peers.len());
-                    if let Some(err) = cause {
-                        println!("   └─ Cause: {:?}", err);
-                    }
-                },
+                    },
+                    SwarmEvent::NewListenAddr { address, .. } => {
+                        println!("🌐 Node active on: {:?}", address);
+                        // Check if this is an external address
+                        if address.to_string().contains("/ip4/") && !address.to_string().contains("/ip4/127.0.0.1") && !address.to_string().contains("/ip4/0.0.0.0") {
+                            println!("🌍 External address detected! Other nodes can connect to: {}/p2p/{}", address, swarm.local_peer_id());
+                        }
+                        // Announce our current chain to the local network to help new peers sync
+                        let _ = swarm.behaviour_mut().gossipsub.publish(chain_topic.clone(), network::encode_gossip(&tc.blocks));
+                    },
+                    SwarmEvent::ConnectionEstablished { peer_id, endpoint, .. } => {
+                        match peer_diversity.admit(peer_id, endpoint.get_remote_address()) {
+                            network::Admission::Accept => {}
+                            network::Admission::Evict(victim) => {
+                                println!("🔀 Dropping {} to make room for a peer from a less crowded subnet", victim);
+                                let _ = swarm.disconnect_peer_id(victim);
+                            }
+                            network::Admission::Reject(reason) => {
+                                println!("🚫 Refusing peer {} ({}): {}", peer_id, endpoint.get_remote_address(), reason);
+                                let _ = swarm.disconnect_peer_id(peer_id);
+                                continue;
+                            }
+                        }
+                        connected_peers.insert(peer_id);
+                        peer_count.store(connected_peers.len(), std::sync::atomic::Ordering::Relaxed);
+                        metrics::set_connected_peers(connected_peers.len());
+                        println!("🔗 Peer connected: {} | Total peers: {}", peer_id, connected_peers.len());
+                        println!("   └─ Direction: {:?} | Address: {:?}", endpoint.is_dialer(), endpoint.get_remote_address());
+                    },
+                    SwarmEvent::ConnectionClosed { peer_id, cause, .. } => {
+                        connected_peers.remove(&peer_id);
+                        peer_diversity.remove(&peer_id);
+                        peer_keys.remove(&peer_id);
+                        sync_tracker.lock().unwrap().remove_peer(&peer_id);
+                        if download.as_ref().is_some_and(|d| d.peer() == peer_id) {
+                            download = None;
+                            sync_tracker.lock().unwrap().set_download(None);
+                        }
+                        peer_count.store(connected_peers.len(), std::sync::atomic::Ordering::Relaxed);
+                        metrics::set_connected_peers(connected_peers.len());
+                        println!("🔌 Peer disconnected: {} | Total peers: {}", peer_id, connected_peers.len());
+                        if let Some(err) = cause {
+                            println!("   └─ Cause: {:?}", err);
+                        }
+                    },
 
-                // When mDNS discovers peers on the LAN, proactively request their chain
-                SwarmEvent::Behaviour(network::TimechainBehaviourEvent::Mdns(ev)) => {
-                    match ev {
-                        libp2p::mdns::Event::Discovered(list) => {
-                            for (peer_id, addr) in list {
-                                println!("🔎 mDNS discovered peer: {} at {}", peer_id, addr);
-                                // Actually dial the discovered peer to establish connection
-                                if let Err(e) = swarm.dial(addr.clone()) {
-                                    println!("   └─ ⚠️  Failed to dial: {:?}", e);
-                                } else {
-                                    println!("   └─ 📞 Dialing...");
+                    // When mDNS discovers peers on the LAN, proactively request their chain
+                    SwarmEvent::Behaviour(network::TimechainBehaviourEvent::Mdns(ev)) => {
+                        match ev {
+                            libp2p::mdns::Event::Discovered(list) => {
+                                for (peer_id, addr) in list {
+                                    println!("🔎 mDNS discovered peer: {} at {}", peer_id, addr);
+                                    // Actually dial the discovered peer to establish connection
+                                    if let Err(e) = swarm.dial(addr.clone()) {
+                                        println!("   └─ ⚠️  Failed to dial: {:?}", e);
+                                    } else {
+                                        println!("   └─ 📞 Dialing...");
+                                    }
+                                    let _ = swarm.behaviour_mut().gossipsub.publish(req_topic.clone(), b"REQ_CHAIN".to_vec());
                                 }
-                                let _ = swarm.behaviour_mut().gossipsub.publish(req_topic.clone(), b"REQ_CHAIN".to_vec());
                             }
-                        }
-                        libp2p::mdns::Event::Expired(list) => {
-                            for (peer_id, _addr) in list {
-                                println!("🔻 mDNS expired peer: {}", peer_id);
+                            libp2p::mdns::Event::Expired(list) => {
+                                for (peer_id, _addr) in list {
+                                    println!("🔻 mDNS expired peer: {}", peer_id);
+                                }
                             }
                         }
-                    }
-                },
+                    },
 
-                // When identify events occur (new peers), ask them for their chain
-                SwarmEvent::Behaviour(network::TimechainBehaviourEvent::Identify(libp2p::identify::Event::Received { peer_id, info, .. })) => {
-                    println!("👋 Identified peer: {} ({:?})", peer_id, info.agent_version);
-                    let _ = swarm.behaviour_mut().gossipsub.publish(req_topic.clone(), b"REQ_CHAIN".to_vec());
-                    // Also send a direct request-response asking for missing blocks
-                    let _ = swarm.behaviour_mut().request_response.send_request(
-                        &peer_id,
-                        network::ChainRequest { start_height: tc.blocks.len() as u64 },
-                    );
-                },
-                SwarmEvent::IncomingConnection { connection_id, local_addr, send_back_addr } => {
-                    println!("📞 Incoming connection attempt from {}", send_back_addr);
-                    println!("   └─ Local addr: {} | Connection ID: {:?}", local_addr, connection_id);
-                },
-                SwarmEvent::OutgoingConnectionError { peer_id, error, .. } => {
-                    if let Some(peer) = peer_id {
-                        println!("⚠️  Outgoing connection to {} failed: {:?}", peer, error);
-                    } else {
-                        println!("⚠️  Outgoing connection failed: {:?}", error);
-                    }
-                },
-                SwarmEvent::IncomingConnectionError { send_back_addr, error, .. } => {
-                    println!("⚠️  Incoming connection from {} failed: {:?}", send_back_addr, error);
-                },
-                SwarmEvent::Behaviour(network::TimechainBehaviourEvent::RequestResponse(ev)) => {
-                    match ev {
-                        libp2p::request_response::Event::Message { peer, message } => {
-                            match message {
-                                libp2p::request_response::Message::Request { request, channel, .. } => {
-                                    // Peer asked for our chain starting at `start_height`
-                                    let start = request.start_height as usize;
-                                    let to_send = if start < tc.blocks.len() { tc.blocks[start..].to_vec() } else { Vec::new() };
-                                    let resp = network::ChainResponse { blocks: to_send };
-                                    let _ = swarm.behaviour_mut().request_response.send_response(channel, resp);
-                                }
-                                libp2p::request_response::Message::Response { response, .. } => {
-                                    if !response.blocks.is_empty() {
-                                        println!("📥 Received {} blocks via request-response from {}", response.blocks.len(), peer);
-                                        for b in response.blocks {
-                                            let _ = tc.add_block(b, 1800);
+                    // When identify events occur (new peers), ask them for their chain
+                    SwarmEvent::Behaviour(network::TimechainBehaviourEvent::Identify(libp2p::identify::Event::Received { peer_id, info, .. })) => {
+                        println!("👋 Identified peer: {} ({:?})", peer_id, info.agent_version);
+                        if !network::is_gossip_compatible(&info.protocol_version) {
+                            println!(
+                                "🚫 Disconnecting {}: it speaks {:?}, this node needs gossip version {}",
+                                peer_id, info.protocol_version, network::GOSSIP_VERSION
+                            );
+                            let _ = swarm.disconnect_peer_id(peer_id);
+                            continue;
+                        }
+                        if let Ok(key) = info.public_key.clone().try_into_ed25519() {
+                            let key = key.to_bytes();
+                            let unix_now = chrono::Utc::now().timestamp() as u64;
+                            if reputations.is_flagged(&key, unix_now) {
+                                println!(
+                                    "🚫 Refusing {}: poor reputation (score {:.2})",
+                                    peer_id, reputations.score(&key, unix_now)
+                                );
+                                let _ = swarm.disconnect_peer_id(peer_id);
+                                continue;
+                            }
+                            peer_keys.insert(peer_id, key);
+                        }
+                        let _ = swarm.behaviour_mut().gossipsub.publish(req_topic.clone(), b"REQ_CHAIN".to_vec());
+                        // Also send a direct request-response asking for missing blocks,
+                        // or for a snapshot first when starting from genesis
+                        let request = if tc.blocks.len() == 1 && snap_sync.is_none() && !snapshot_signers.is_empty() {
+                            network::ChainRequest::snapshot(network::SnapshotRequest::Manifest)
+                        } else {
+                            network::ChainRequest::blocks_from(tc.blocks.len() as u64)
+                        };
+                        let _ = swarm.behaviour_mut().request_response.send_request(&peer_id, request);
+                    },
+                    SwarmEvent::IncomingConnection { connection_id, local_addr, send_back_addr } => {
+                        println!("📞 Incoming connection attempt from {}", send_back_addr);
+                        println!("   └─ Local addr: {} | Connection ID: {:?}", local_addr, connection_id);
+                    },
+                    SwarmEvent::OutgoingConnectionError { peer_id, error, .. } => {
+                        if let Some(peer) = peer_id {
+                            println!("⚠️  Outgoing connection to {} failed: {:?}", peer, error);
+                        } else {
+                            println!("⚠️  Outgoing connection failed: {:?}", error);
+                        }
+                    },
+                    SwarmEvent::IncomingConnectionError { send_back_addr, error, .. } => {
+                        println!("⚠️  Incoming connection from {} failed: {:?}", send_back_addr, error);
+                    },
+                    SwarmEvent::Behaviour(network::TimechainBehaviourEvent::RequestResponse(ev)) => {
+                        match ev {
+                            libp2p::request_response::Event::Message { peer, message } => {
+                                match message {
+                                    libp2p::request_response::Message::Request { request, channel, .. } => {
+                                        let resp = if let Some(wanted) = request.block_transactions {
+                                            // Peer is rebuilding one of our compact blocks
+                                            let transactions = tc.blocks.iter().rev()
+                                                .find(|b| b.merkle_root == wanted.merkle_root)
+                                                .and_then(|b| compact::block_transactions(b, &wanted.indexes))
+                                                .unwrap_or_default();
+                                            network::ChainResponse {
+                                                blocks: Vec::new(),
+                                                block_transactions: Some(network::BlockTransactions { merkle_root: wanted.merkle_root, transactions }),
+                                                snapshot: None,
+                                                tip_height: None,
+                                            }
+                                        } else if let Some(wanted) = request.snapshot {
+                                            // Peer is snap-syncing from our latest snapshot
+                                            let part = served_snapshot.lock().unwrap().as_ref().and_then(|s| match wanted {
+                                                network::SnapshotRequest::Manifest => Some(network::SnapshotResponse::Manifest(s.manifest.clone())),
+                                                network::SnapshotRequest::Chunk { index } => s
+                                                    .chunk(index)
+                                                    .map(|(chunk, proof)| network::SnapshotResponse::Chunk { chunk, proof }),
+                                            });
+                                            network::ChainResponse { snapshot: part, ..network::ChainResponse::blocks(Vec::new()) }
+                                        } else {
+                                            // Peer asked for our chain starting at `start_height`; it
+                                            // asks again for whatever does not fit in one batch
+                                            network::ChainResponse::batch(&tc.blocks, request.start_height, max_blocks_per_response)
+                                        };
+                                        let _ = swarm.behaviour_mut().request_response.send_response(channel, resp);
+                                    }
+                                    libp2p::request_response::Message::Response { response, .. } => {
+                                        let answered_compact = response.block_transactions.is_some();
+                                        if let Some(fetched) = response.block_transactions {
+                                            if let Some(mut partial) = pending_compact.remove(&fetched.merkle_root) {
+                                                let elapsed = last_vdf.elapsed().as_secs();
+                                                match partial.fill(fetched.transactions).and_then(|_| partial.into_block()) {
+                                                    Ok(block) => {
+                                                        if !block_queue.submit((block, elapsed)) {
+                                                            log::debug!("Block queue full; dropped compact block from {}", peer);
+                                                        }
+                                                    }
+                                                    Err(e) => log::debug!("Could not rebuild compact block from {}: {}", peer, e),
+                                                }
+                                            }
+                                        }
+                                        match response.snapshot {
+                                            Some(network::SnapshotResponse::Manifest(manifest)) if tc.blocks.len() == 1 && snap_sync.is_none() => {
+                                                match snapshot::SnapshotAssembler::new(manifest, &snapshot_signers) {
+                                                    Ok(assembler) => {
+                                                        println!("📸 Snap sync from {}: {} chunks at height {}", peer, assembler.manifest().chunk_count, assembler.manifest().checkpoint.height);
+                                                        for index in assembler.missing() {
+                                                            let _ = swarm.behaviour_mut().request_response.send_request(
+                                                                &peer,
+                                                                network::ChainRequest::snapshot(network::SnapshotRequest::Chunk { index }),
+                                                            );
+                                                        }
+                                                        if assembler.is_complete() {
+                                                            let _ = swarm.behaviour_mut().request_response.send_request(&peer, network::ChainRequest::blocks_from(1));
+                                                        }
+                                                        snap_sync = Some(assembler);
+                                                    }
+                                                    Err(e) => {
+                                                        log::debug!("Ignoring snapshot from {}: {}", peer, e);
+                                                        let _ = swarm.behaviour_mut().request_response.send_request(&peer, network::ChainRequest::blocks_from(tc.blocks.len() as u64));
+                                                    }
+                                                }
+                                            }
+                                            Some(network::SnapshotResponse::Chunk { chunk, proof }) => {
+                                                if let Some(assembler) = snap_sync.as_mut() {
+                                                    match assembler.add_chunk(chunk, &proof) {
+                                                        // Every chunk is in: fetch the blocks to link up to the checkpoint
+                                                        Ok(()) if assembler.is_complete() => {
+                                                            let _ = swarm.behaviour_mut().request_response.send_request(&peer, network::ChainRequest::blocks_from(1));
+                                                        }
+                                                        Ok(()) => {}
+                                                        Err(e) => log::debug!("Bad snapshot chunk from {}: {}", peer, e),
+                                                    }
+                                                }
+                                            }
+                                            Some(_) => {}
+                                            // Empty answer at genesis: the peer serves no snapshot, so sync
+                                            // block by block (once per peer, as it may have no blocks either)
+                                            None if tc.blocks.len() == 1
+                                                && snap_sync.is_none()
+                                                && !snapshot_signers.is_empty()
+                                                && response.blocks.is_empty()
+                                                && !answered_compact
+                                                && snapshot_declined.insert(peer) =>
+                                            {
+                                                let _ = swarm.behaviour_mut().request_response.send_request(&peer, network::ChainRequest::blocks_from(1));
+                                            }
+                                            None => {}
+                                        }
+                                        if let Some(last_slot) = response.blocks.last().map(|b| b.slot) {
+                                            // Peers that predate batching send everything and no tip
+                                            let peer_tip = response.tip_height.unwrap_or(last_slot);
+                                            sync_tracker.lock().unwrap().observe(peer, peer_tip);
+                                            println!("📥 Received {} blocks via request-response from {}", response.blocks.len(), peer);
+                                            if tc.blocks.len() == 1 && snap_sync.as_ref().is_some_and(|a| a.is_complete()) {
+                                                // Collect batches up to the peer's tip, then replay them all at
+                                                // once; batches that do not continue the collection are ignored
+                                                if response.blocks[0].slot == snap_blocks.len() as u64 + 1 {
+                                                    snap_blocks.extend(response.blocks);
+                                                    if last_slot < peer_tip {
+                                                        let _ = swarm.behaviour_mut().request_response.send_request(&peer, network::ChainRequest::blocks_from(last_slot + 1));
+                                                    } else {
+                                                        let assembler = snap_sync.take().unwrap();
+                                                        let checkpoint = assembler.manifest().checkpoint.clone();
+                                                        let blocks = std::mem::take(&mut snap_blocks);
+                                                        let synced = assembler.into_state().map_err(|e| e.to_string()).and_then(|state| {
+                                                            tc.sync_from_snapshot(&checkpoint, &snapshot_signers, state, blocks)
+                                                                .map_err(str::to_string)
+                                                        });
+                                                        match synced {
+                                                            Ok(()) => println!("⚡ Snap-synced {} blocks from {}, replaying only past height {}", tc.blocks.len(), peer, checkpoint.height),
+                                                            Err(e) => println!("⚠️  Snap sync from {} failed: {}", peer, e),
+                                                        }
+                                                    }
+                                                }
+                                            } else {
+                                                let height_before = tc.blocks.len() as u64 - 1;
+                                                for b in response.blocks {
+                                                    let block_time = tc.block_time;
+                                                    let _ = tc.add_block(b, block_time);
+                                                }
+                                                let height = tc.blocks.len() as u64 - 1;
+                                                // A peer further ahead than the one being downloaded from takes over
+                                                if let Some(current) = download.as_mut().filter(|d| d.is_beaten_by(peer, peer_tip)) {
+                                                    println!("↪️  Dropping download from {}: {} is at height {}", current.peer(), peer, peer_tip);
+                                                    current.cancel();
+                                                }
+                                                if download.as_ref().is_none_or(|d| d.is_cancelled()) && peer_tip > height_before {
+                                                    download = Some(sync::BlockDownload::new(peer, height_before, peer_tip));
+                                                }
+                                                if let Some(current) = download.as_mut().filter(|d| d.peer() == peer) {
+                                                    let next = current.on_batch(height, peer_tip);
+                                                    let progress = current.progress();
+                                                    println!("⏬ Downloaded {}/{} blocks from {}", progress.fetched, progress.total, peer);
+                                                    match next {
+                                                        Some(request) => {
+                                                            sync_tracker.lock().unwrap().set_download(Some(progress));
+                                                            let _ = swarm.behaviour_mut().request_response.send_request(&peer, request);
+                                                        }
+                                                        None => {
+                                                            download = None;
+                                                            sync_tracker.lock().unwrap().set_download(None);
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                            storage_writer.submit(pipeline::StorageJob::SaveChain(tc.blocks.clone()));
+                                            persist_state(&storage_writer, &tc);
+                                            metrics::set_chain_height(tc.blocks.len() as u64 - 1);
                                         }
-                                        storage::save_chain(&tc.blocks);
                                     }
                                 }
                             }
+                            libp2p::request_response::Event::OutboundFailure { peer, error, .. } => {
+                                // Fallback to gossipsub for any request-response failure
+                                log::debug!("RequestResponse failure with peer {}: {:?} - using gossipsub fallback", peer, error);
+                                let _ = swarm.behaviour_mut().gossipsub.publish(chain_topic.clone(), network::encode_gossip(&tc.blocks));
+                            }
+                            libp2p::request_response::Event::InboundFailure { peer, error, .. } => {
+                                log::debug!("RequestResponse inbound failure from {}: {:?}", peer, error);
+                            }
+                            _ => {}
                         }
-                        libp2p::request_response::Event::OutboundFailure { peer, error, .. } => {
-                            // Fallback to gossipsub for any request-response failure
-                            log::debug!("RequestResponse failure with peer {}: {:?} - using gossipsub fallback", peer, error);
-                            let _ = swarm.behaviour_mut().gossipsub.publish(chain_topic.clone(), bincode::serialize(&tc.blocks).unwrap_or_default());
-                        }
-                        libp2p::request_response::Event::InboundFailure { peer, error, .. } => {
-                            log::debug!("RequestResponse inbound failure from {}: {:?}", peer, error);
-                        }
-                        _ => {}
-                    }
-                },
-                _ => {}
+                    },
+                    _ => {}
+                }
+            },
+
+            // --- RELAY TRANSACTIONS SUBMITTED OVER RPC ---
+            Some(tx) = rpc_relayed_txs.recv() => {
+                if swarm.behaviour_mut().gossipsub.publish(tx_topic.clone(), network::encode_gossip(&tx)).is_ok() {
+                    tx_traces.record(&tx.hash(), rpc::trace::Stage::Gossiped);
+                }
+            },
+
+            // --- PERSIST AND RELAY BLOCKS MINED OVER RPC ---
+            Some(block) = rpc_relayed_blocks.recv() => {
+                storage_writer.submit(pipeline::StorageJob::AppendBlock(block.clone()));
+                {
+                    let tc = shared_chain.lock().unwrap();
+                    persist_state(&storage_writer, &tc);
+                    prune_log(&storage_writer, &tc, pruning, &served_snapshot);
+                }
+                metrics::record_block(block.slot);
+                let _ = swarm.behaviour_mut().gossipsub.publish(compact_topic.clone(), compact_announcement(&block));
+                last_vdf = Instant::now();
             },
 
             _ = throttle_reset.tick() => {
@@ -460,10 +1078,11 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
 
             // --- BROADCAST PENDING TRANSACTIONS ---
             _ = tx_broadcast_timer.tick() => {
+                let tc = shared_chain.lock().unwrap();
                 if let Ok(tx_data) = std::fs::read("pending_tx.dat") {
                     if let Ok(tx) = bincode::deserialize::<Transaction>(&tx_data) {
                         if tc.validate_transaction(&tx).is_ok() {
-                            let encoded = bincode::serialize(&tx).unwrap();
+                            let encoded = network::encode_gossip(&tx);
                             let _ = swarm.behaviour_mut().gossipsub.publish(
                                 gossipsub::IdentTopic::new("timechain-transactions"), encoded
                             );
@@ -477,7 +1096,12 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
 
             // --- PERIODIC CHAIN SYNC: Ensure global consensus ---
             _ = chain_sync_timer.tick() => {
+                let tc = shared_chain.lock().unwrap();
                 println!("🔄 Performing periodic chain synchronization...");
+                // A block log write was shed under load: rewrite the log whole
+                if storage_writer.take_stale() {
+                    storage_writer.submit(pipeline::StorageJob::SaveChain(tc.blocks.clone()));
+                }
                 // Request chains from connected peers to ensure we're in sync
                 let _ = swarm.behaviour_mut().gossipsub.publish(req_topic.clone(), b"REQ_CHAIN".to_vec());
 
@@ -485,23 +1109,26 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
                 if connected_peers.len() > 0 {
                     let peer_ids: Vec<_> = connected_peers.iter().cloned().collect();
                     for peer_id in peer_ids {
+                        // The peer being downloaded from already has our next request
+                        if download.as_ref().is_some_and(|d| d.peer() == peer_id) {
+                            continue;
+                        }
                         let _ = swarm.behaviour_mut().request_response.send_request(
                             &peer_id,
-                            network::ChainRequest { start_height: tc.blocks.len() as u64 },
+                            network::ChainRequest::blocks_from(tc.blocks.len() as u64),
                         );
                     }
                 }
 
                 // Broadcast our current chain state to help peers sync
-                if let Ok(encoded) = bincode::serialize(&tc.blocks) {
-                    let _ = swarm.behaviour_mut().gossipsub.publish(chain_topic.clone(), encoded);
-                }
+                let _ = swarm.behaviour_mut().gossipsub.publish(chain_topic.clone(), network::encode_gossip(&tc.blocks));
             },
 
             // --- DASHBOARD: RESOLVING UNUSED WARNINGS ---
             _ = dashboard_timer.tick() => {
+                let tc = shared_chain.lock().unwrap();
                 let elapsed = last_vdf.elapsed().as_secs();
-                let remaining = 1800u64.saturating_sub(elapsed);
+                let remaining = block_time.saturating_sub(elapsed);
                 // Using last_diff to calculate and show the difficulty trend
                 let trend = if tc.difficulty > last_diff { "UP ⬆️" } else if tc.difficulty < last_diff { "DOWN ⬇️" } else { "STABLE ↔️" };
                 // Supply info
@@ -517,6 +1144,15 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
                 println!("🌐 Network Status:");
                 println!("   ├─ PeerId: {}", swarm.local_peer_id());
                 println!("   ├─ Connected Peers: {}", connected_peers.len());
+                let sync_status = sync_tracker.lock().unwrap().status(tc.blocks.len() as u64 - 1);
+                match sync_status {
+                    sync::SyncStatus::Syncing { current, target } => {
+                        println!("   ├─ Sync: {}/{} ({:.1}%)", current, target, sync_status.progress() * 100.0)
+                    }
+                    sync::SyncStatus::Synced => println!("   ├─ Sync: synced"),
+                }
+                println!("   ├─ Mining: {}", miner::MiningStatus::new(sync_status, connected_peers.len(), min_peers_to_mine, solo_mining));
+                println!("   ├─ Reward Address: {}", hex::encode(reward_address));
                 if connected_peers.is_empty() {
                     println!("   │  └─ No peers connected (check firewall/NAT)");
                 } else {
@@ -538,7 +1174,14 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
                     let _ = serde_json::to_writer_pretty(&mut f, &ai.stats);
                 }
                 println!("[Dashboard] AI stats written to ai_stats.json");
+                if let Err(e) = reputations.save_to_file(reputation::REPUTATION_PATH) {
+                    eprintln!("⚠️  Could not save peer reputations: {}", e);
+                }
                 println!("------------------------\n");
+                // Balances must add up to the issued supply
+                if let Err(mismatch) = tc.audit_supply() {
+                    eprintln!("🚨 SUPPLY AUDIT FAILED: {}", mismatch);
+                }
                 // Sync last_diff for the next interval
                 last_diff = tc.difficulty;
             },
@@ -548,17 +1191,9 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
                 // Periodically retry connecting to bootstrap nodes if not enough peers
                 if connected_peers.len() < 2 && last_bootstrap_retry.elapsed().as_secs() > 120 {
                     log::info!("Retrying bootstrap connections (current peers: {})", connected_peers.len());
-                    if let Ok(bootstrap_content) = std::fs::read_to_string("config/bootstrap.toml") {
-                        if let Ok(bootstrap_config) = toml::from_str::<toml::Value>(&bootstrap_content) {
-                            if let Some(bootnodes) = bootstrap_config.get("bootnodes").and_then(|v| v.as_array()) {
-                                for bootnode in bootnodes {
-                                    if let Some(addr_str) = bootnode.as_str() {
-                                        if let Ok(addr) = addr_str.parse::<Multiaddr>() {
-                                            let _ = swarm.dial(addr.clone());
-                                        }
-                                    }
-                                }
-                            }
+                    for addr_str in &bootstrap_peers {
+                        if let Ok(addr) = addr_str.parse::<Multiaddr>() {
+                            let _ = swarm.dial(addr);
                         }
                     }
                     last_bootstrap_retry = Instant::now();
@@ -584,60 +1219,65 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
             _ = vdf_loop.tick() => {
                 let elapsed = last_vdf.elapsed().as_secs();
 
-                if elapsed >= 3600 {
-                    let parent_hash = tc.blocks.last().unwrap().hash();
-                    let current_slot = tc.blocks.len() as u64;
-                    let vdf_seed = vdf::evaluate(parent_hash, current_slot);
-                    let vdf_proof = compute_vdf(vdf_seed, tc.difficulty as u32);
-                    let zk_pass = genesis::generate_zk_pass(&wallet, parent_hash);
-
-                    // Select transactions from mempool (up to some limit)
-                    let mut selected_txs = Vec::new();
-                    let max_txs_per_block = 100;
-                    while let Some(tx) = mempool.front() {
-                        if selected_txs.len() >= max_txs_per_block {
-                            break;
-                        }
-                        // Double-check transaction is still valid
-                        if tc.validate_transaction(tx).is_ok() {
-                            selected_txs.push(mempool.pop_front().unwrap());
-                        } else {
-                            // Remove invalid transaction
-                            mempool.pop_front();
-                        }
+                // A node that is behind, or isolated, would only build a fork that gets orphaned
+                let height = shared_chain.lock().unwrap().blocks.len() as u64 - 1;
+                let sync_status = sync_tracker.lock().unwrap().status(height);
+                let status = miner::MiningStatus::new(sync_status, connected_peers.len(), min_peers_to_mine, solo_mining);
+                if status != mining_status {
+                    println!("⛏️  Mining {}", status);
+                    mining_status = status;
+                }
+
+                if elapsed >= block_time && status.is_active() {
+                    let mut tc = shared_chain.lock().unwrap();
+                    let mut mempool = shared_mempool.lock().unwrap();
+                    // Best fee rate first, as far as the block limits allow; they
+                    // leave the mempool once the block is accepted, the invalid
+                    // ones right away
+                    let candidates = mempool.get_for_mining(usize::MAX);
+                    let (selected_txs, invalid) = tc.select_transactions(reward_address, candidates);
+                    for tx in invalid {
+                        mempool.evict(&tx.hash());
                     }
 
-                    let mut nonce = 0u64;
-                    let mut found = false;
+                    // Coinbase, selected transactions in canonical order and ZK
+                    // pass; the VDF and nonce are filled in below
+                    let mut candidate = tc.next_block_rewarding(&wallet, reward_address, selected_txs);
+                    let vdf_start = Instant::now();
+                    candidate.vdf_proof = compute_vdf(vdf::evaluate(candidate.parent, candidate.slot), tc.vdf_steps);
+                    metrics::observe_vdf_seconds(vdf_start.elapsed().as_secs_f64());
+
                     let max_attempts = if tc.blocks.len() <= 2 {
                         1000000 // More attempts for early blocks
                     } else {
                         100000
                     };
 
-                    while !found && nonce < max_attempts {
-                        let candidate = Block {
-                            parent: parent_hash,
-                            slot: current_slot,
-                            miner: wallet.address,
-                            transactions: selected_txs.clone(),
-                            vdf_proof,
-                            zk_proof: zk_pass.clone(),
-                            nonce,
-                        };
-
-                        if candidate.meets_difficulty(tc.difficulty)
-                            && tc.add_block(candidate.clone(), elapsed).is_ok() {
-                            println!("✨ MINED: H-{} | Nonce: {} | Txs: {}", tc.blocks.len(), nonce, selected_txs.len());
-                            let encoded = bincode::serialize(&candidate).unwrap();
-                            let _ = swarm.behaviour_mut().gossipsub.publish(
-                                gossipsub::IdentTopic::new("timechain-blocks"), encoded
-                            );
-                            storage::save_chain(&tc.blocks);
+                    // All mining threads race on the same candidate; at most one block comes back
+                    let mut found = false;
+                    let solved = miner::mine_parallel(&candidate, tc.difficulty, mining_threads, mining_intensity, max_attempts);
+                    if let Some(candidate) = solved {
+                        if tc.add_block(candidate.clone(), elapsed).is_ok() {
+                            tx_traces.record_block(&candidate);
+                            prune_mined(&mut mempool, &candidate);
+                            println!("✨ MINED: H-{} | Nonce: {} | Txs: {}", tc.blocks.len(), candidate.nonce, candidate.user_transactions().len());
+                            metrics::record_block(tc.blocks.len() as u64 - 1);
+                            let _ = node_events.send(rpc::NodeEvent::NewBlock(candidate.clone()));
+                            let _ = swarm.behaviour_mut().gossipsub.publish(compact_topic.clone(), compact_announcement(&candidate));
+                            storage_writer.submit(pipeline::StorageJob::AppendBlock(candidate));
+                            persist_state(&storage_writer, &tc);
+                            if (tc.blocks.len() as u64 - 1).is_multiple_of(storage::CHECKPOINT_INTERVAL) {
+                                let checkpoint = tc.checkpoint(&checkpoint_key);
+                                storage_writer.submit(pipeline::StorageJob::SaveCheckpoint(checkpoint.clone()));
+                                // Serve the state at this checkpoint to snap-syncing peers
+                                let snapshot = snapshot::Snapshot::new(checkpoint, &tc.state);
+                                storage_writer.submit(pipeline::StorageJob::SaveSnapshot(snapshot.clone()));
+                                *served_snapshot.lock().unwrap() = Some(snapshot);
+                            }
+                            prune_log(&storage_writer, &tc, pruning, &served_snapshot);
                             last_vdf = Instant::now();
                             found = true;
                         }
-                        nonce += 1;
                     }
 
                     // If mining failed, adjust difficulty for next attempt
@@ -653,4 +1293,6 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
             },
         }
     }
+
+    Ok(())
 }
@@ -49,3 +49,44 @@ pub fn compute_vdf(seed: [u8; 32], iterations: u32) -> [u8; 32] {
     }
     result
 }
+
+/// Throughput of `compute_vdf` on this machine (see `benchmark_vdf`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VdfBenchmark {
+    /// Sequential iterations per evaluation (the chain's `vdf_steps`)
+    pub difficulty: u32,
+    /// Evaluations timed
+    pub evaluations: u32,
+    pub evaluations_per_second: f64,
+}
+
+impl VdfBenchmark {
+    /// Seconds this machine spends on the VDF of one block
+    pub fn seconds_per_block(&self) -> f64 {
+        1.0 / self.evaluations_per_second
+    }
+
+    /// How many blocks' worth of VDF fit in `block_time` seconds; below 1.0
+    /// the machine cannot keep up with the target
+    pub fn blocks_per_block_time(&self, block_time: u64) -> f64 {
+        block_time as f64 * self.evaluations_per_second
+    }
+}
+
+/// Time `evaluations` VDFs at `difficulty`, each seeded the way the mining
+/// loop seeds one (`vdf::evaluate` over a parent hash and slot)
+pub fn benchmark_vdf(difficulty: u32, evaluations: u32) -> VdfBenchmark {
+    let evaluations = evaluations.max(1);
+    let start = std::time::Instant::now();
+    let mut parent = [0u8; 32];
+    for slot in 0..evaluations {
+        parent = compute_vdf(crate::vdf::evaluate(parent, slot as u64), difficulty);
+    }
+    // Guard against a zero reading on very fast runs
+    let elapsed = start.elapsed().as_secs_f64().max(f64::EPSILON);
+    VdfBenchmark {
+        difficulty,
+        evaluations,
+        evaluations_per_second: evaluations as f64 / elapsed,
+    }
+}
@@ -1,61 +1,232 @@
 // src/mempool.rs - AXIOM Protocol Production Transaction Pool
 
 use std::collections::{HashMap, HashSet, BTreeMap};
-use sha2::Digest;
-use crate::{transaction::Transaction, error::{AxiomError, Result}};
+use tokio::sync::broadcast;
+use crate::{block::Block, config::ConsensusConfig, hashing, state::State, transaction::Transaction, error::{AxiomError, Result}};
 
 pub type Address = [u8; 32];
 
 const DEFAULT_MAX_SIZE: usize = 100_000;
 const DEFAULT_MAX_TX_SIZE: usize = 100_000;
+/// Default `aging_rate`: priority grows 1% per minute in the pool, so a
+/// transaction waiting ~100 minutes competes with twice its fee rate
+pub const DEFAULT_AGING_RATE: f64 = 0.01;
+
+/// A change to the pending set, published on the `with_events` channel
+#[derive(Debug, Clone, PartialEq)]
+pub enum MempoolEvent {
+    Added(Transaction),
+    /// `new` took over `old`'s (sender, nonce) by paying more (RBF)
+    Replaced { old: Transaction, new: Transaction },
+    /// Dropped for a better-paying transaction, or made invalid by a block
+    Evicted(Transaction),
+    /// Included in the block at `height`
+    Mined { hash: [u8; 32], height: u64 },
+    /// Waited longer than the pool keeps transactions
+    Expired(Transaction),
+}
+
+/// Deployment-specific admission rule, consulted by `Mempool::add` after
+/// the built-in size, dust and fee checks and before the transaction is
+/// inserted. `state` is the chain state the transaction will apply to.
+pub trait MempoolPolicy: Send + Sync {
+    fn accept(&self, tx: &Transaction, state: &State) -> Result<()>;
+}
+
+/// Accepts everything the built-in checks accept (the default)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PermissivePolicy;
+
+impl MempoolPolicy for PermissivePolicy {
+    fn accept(&self, _tx: &Transaction, _state: &State) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Rejects transactions from or to any listed address
+#[derive(Debug, Clone, Default)]
+pub struct BlacklistPolicy {
+    addresses: HashSet<Address>,
+}
+
+impl BlacklistPolicy {
+    pub fn new(addresses: impl IntoIterator<Item = Address>) -> Self {
+        Self { addresses: addresses.into_iter().collect() }
+    }
+}
+
+impl MempoolPolicy for BlacklistPolicy {
+    fn accept(&self, tx: &Transaction, _state: &State) -> Result<()> {
+        if let Some(addr) = [tx.from, tx.to].iter().find(|addr| self.addresses.contains(*addr)) {
+            return Err(AxiomError::PolicyRejected(format!("address {} is blacklisted", hex::encode(addr))));
+        }
+        Ok(())
+    }
+}
+
+/// Rejects transactions without a valid ZK proof
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequireZkPolicy;
+
+impl MempoolPolicy for RequireZkPolicy {
+    fn accept(&self, tx: &Transaction, _state: &State) -> Result<()> {
+        if tx.zk_proof.is_empty() {
+            return Err(AxiomError::PolicyRejected("missing ZK proof".to_string()));
+        }
+        match crate::genesis::verify_transaction_proof(&tx.zk_proof, &tx.from, tx.amount, tx.fee) {
+            Ok(true) => Ok(()),
+            _ => Err(AxiomError::PolicyRejected("invalid ZK proof".to_string())),
+        }
+    }
+}
+
+/// Limits each sender to `max_pending` transactions ahead of its confirmed
+/// nonce. The pool holds one transaction per (sender, nonce), so this caps
+/// how many a sender can have pending at once.
+#[derive(Debug, Clone, Copy)]
+pub struct PerSenderCapPolicy {
+    pub max_pending: u64,
+}
+
+impl MempoolPolicy for PerSenderCapPolicy {
+    fn accept(&self, tx: &Transaction, state: &State) -> Result<()> {
+        let ahead = tx.nonce.saturating_sub(state.nonce(&tx.from));
+        if ahead >= self.max_pending {
+            return Err(AxiomError::PolicyRejected(format!(
+                "sender already has {} pending transactions",
+                self.max_pending
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Every policy must accept; the first rejection is returned
+impl MempoolPolicy for Vec<Box<dyn MempoolPolicy>> {
+    fn accept(&self, tx: &Transaction, state: &State) -> Result<()> {
+        self.iter().try_for_each(|policy| policy.accept(tx, state))
+    }
+}
 
 /// Production-grade transaction mempool
 pub struct Mempool {
     /// All transactions indexed by hash
     transactions: HashMap<[u8; 32], Transaction>,
-    /// Transactions sorted by fee (high to low)
-    by_fee: BTreeMap<u64, HashSet<[u8; 32]>>,
+    /// Transactions sorted by fee rate (`Transaction::fee_rate`)
+    by_fee_rate: BTreeMap<u64, HashSet<[u8; 32]>>,
     /// Transactions grouped by sender
     by_sender: HashMap<Address, Vec<[u8; 32]>>,
+    /// Arrival time of each transaction (unix seconds)
+    added_at: HashMap<[u8; 32], u64>,
     /// Nullifiers to prevent double-spend
     nullifiers: HashSet<[u8; 32]>,
     /// Maximum mempool size
     max_size: usize,
     /// Maximum transaction size
     max_tx_size: usize,
+    /// Smallest accepted amount; 0 disables the check
+    dust_limit: u64,
+    /// Accept zero-amount transactions despite `dust_limit`
+    allow_zero_amount: bool,
+    /// Smallest fee per serialized byte
+    min_fee_per_byte: u64,
+    /// Fractional priority gain per minute spent in the pool
+    aging_rate: f64,
+    /// Where changes to the pool are announced
+    events: Option<Box<dyn Fn(MempoolEvent) + Send + Sync>>,
+    /// Operator admission rules
+    policy: Box<dyn MempoolPolicy>,
 }
 
 impl Mempool {
     pub fn new() -> Self {
         Self {
             transactions: HashMap::new(),
-            by_fee: BTreeMap::new(),
+            by_fee_rate: BTreeMap::new(),
             by_sender: HashMap::new(),
+            added_at: HashMap::new(),
             nullifiers: HashSet::new(),
             max_size: DEFAULT_MAX_SIZE,
             max_tx_size: DEFAULT_MAX_TX_SIZE,
+            dust_limit: 0,
+            allow_zero_amount: false,
+            min_fee_per_byte: 0,
+            aging_rate: DEFAULT_AGING_RATE,
+            events: None,
+            policy: Box::new(PermissivePolicy),
         }
     }
     
     pub fn with_capacity(max_size: usize, max_tx_size: usize) -> Self {
         Self {
             transactions: HashMap::with_capacity(max_size),
-            by_fee: BTreeMap::new(),
+            by_fee_rate: BTreeMap::new(),
             by_sender: HashMap::new(),
+            added_at: HashMap::new(),
             nullifiers: HashSet::new(),
             max_size,
             max_tx_size,
+            dust_limit: 0,
+            allow_zero_amount: false,
+            min_fee_per_byte: 0,
+            aging_rate: DEFAULT_AGING_RATE,
+            events: None,
+            policy: Box::new(PermissivePolicy),
         }
     }
     
-    /// Add transaction to mempool
-    pub fn add(&mut self, tx: Transaction) -> Result<()> {
+    /// Apply the dust, fee-rate and aging policy from `consensus`
+    pub fn with_consensus_policy(mut self, consensus: &ConsensusConfig) -> Self {
+        self.dust_limit = consensus.dust_limit;
+        self.allow_zero_amount = consensus.allow_zero_amount;
+        self.min_fee_per_byte = consensus.min_fee_per_byte;
+        self.with_aging_rate(consensus.mempool_aging_rate)
+    }
+    
+    /// Set how fast waiting transactions gain priority; 0 orders by fee rate only
+    pub fn with_aging_rate(mut self, aging_rate: f64) -> Self {
+        self.aging_rate = aging_rate.max(0.0);
+        self
+    }
+    
+    /// Announce additions, replacements and removals on `events`, e.g. the
+    /// node's event channel that WebSocket subscribers listen on
+    pub fn with_events<E>(mut self, events: broadcast::Sender<E>) -> Self
+    where
+        E: From<MempoolEvent> + Send + 'static,
+    {
+        self.events = Some(Box::new(move |event| {
+            let _ = events.send(E::from(event));
+        }));
+        self
+    }
+    
+    /// Admit only transactions `policy` accepts
+    pub fn with_policy(mut self, policy: impl MempoolPolicy + 'static) -> Self {
+        self.policy = Box::new(policy);
+        self
+    }
+    
+    fn emit(&self, event: MempoolEvent) {
+        if let Some(events) = &self.events {
+            events(event);
+        }
+    }
+    
+    /// Add transaction to mempool; `state` is the chain state the policy
+    /// judges it against
+    pub fn add(&mut self, tx: Transaction, state: &State) -> Result<()> {
+        self.add_at(tx, state, now_secs())
+    }
+    
+    /// Add transaction to mempool, arriving at `now` (unix seconds)
+    pub fn add_at(&mut self, tx: Transaction, state: &State, now: u64) -> Result<()> {
         let hash = tx.hash();
         
+        tx.check_dust(self.dust_limit, self.allow_zero_amount)?;
+        
         // Calculate size
-        let tx_size = bincode::serialize(&tx)
-            .map_err(|e| AxiomError::SerializationError(e.to_string()))?
-            .len();
+        let tx_size = tx.size();
         
         // Check size limit
         if tx_size > self.max_tx_size {
@@ -65,44 +236,61 @@ impl Mempool {
             });
         }
         
+        // Check fee rate
+        let min_fee = tx.min_fee(self.min_fee_per_byte);
+        if tx.fee < min_fee {
+            return Err(AxiomError::FeeTooLow { min: min_fee, actual: tx.fee });
+        }
+        
+        self.policy.accept(&tx, state)?;
+        
         // Check for duplicates
         if self.transactions.contains_key(&hash) {
             return Err(AxiomError::DuplicateTransaction);
         }
         
-        // Generate nullifier (hash of from + nonce)
-        let nullifier = {
-            let mut hasher = sha2::Sha256::new();
-            hasher.update(tx.from);
-            hasher.update(tx.nonce.to_le_bytes());
-            let result = hasher.finalize();
-            let mut n = [0u8; 32];
-            n.copy_from_slice(&result);
-            n
-        };
-        
-        // Check nullifier (double-spend protection)
+        // Nullifier of (from, nonce)
+        let nullifier = nullifier(&tx);
+        
+        // Check nullifier (double-spend protection); a pending transaction
+        // with the same (sender, nonce) is replaced if this one pays more
+        let fee_rate = tx.fee_rate();
+        let mut replaced = None;
         if self.nullifiers.contains(&nullifier) {
-            return Err(AxiomError::NullifierUsed);
+            let Some(old) = self.get_by_sender(&tx.from).into_iter().find(|old| old.nonce == tx.nonce) else {
+                return Err(AxiomError::NullifierUsed);
+            };
+            if tx.fee <= old.fee || fee_rate <= old.fee_rate() {
+                // Smallest fee beating both the old fee and its rate
+                let min = ((old.fee_rate() as u128 + 1) * tx_size as u128).div_ceil(1000);
+                return Err(AxiomError::FeeTooLow {
+                    min: u64::try_from(min).unwrap_or(u64::MAX).max(old.fee.saturating_add(1)),
+                    actual: tx.fee,
+                });
+            }
+            self.remove(&old.hash());
+            replaced = Some(old);
         }
         
         // Check mempool capacity
         if self.transactions.len() >= self.max_size {
-            // Try to evict lowest fee transaction
-            if let Some((&lowest_fee, _)) = self.by_fee.iter().next() {
-                if tx.fee <= lowest_fee {
+            // Try to evict lowest fee-rate transaction
+            if let Some((&lowest_rate, _)) = self.by_fee_rate.iter().next() {
+                if fee_rate <= lowest_rate {
+                    // Smallest fee whose rate beats the lowest in the pool
+                    let min = ((lowest_rate as u128 + 1) * tx_size as u128).div_ceil(1000);
                     return Err(AxiomError::FeeTooLow {
-                        min: lowest_fee + 1,
+                        min: u64::try_from(min).unwrap_or(u64::MAX),
                         actual: tx.fee,
                     });
                 }
-                self.evict_lowest_fee();
+                self.evict_lowest_fee_rate();
             }
         }
         
         // Add to indexes
-        self.by_fee
-            .entry(tx.fee)
+        self.by_fee_rate
+            .entry(fee_rate)
             .or_default()
             .insert(hash);
         
@@ -112,28 +300,38 @@ impl Mempool {
             .push(hash);
         
         self.nullifiers.insert(nullifier);
-        self.transactions.insert(hash, tx);
+        self.added_at.insert(hash, now);
+        self.transactions.insert(hash, tx.clone());
         
+        self.emit(match replaced {
+            Some(old) => MempoolEvent::Replaced { old, new: tx },
+            None => MempoolEvent::Added(tx),
+        });
         Ok(())
     }
     
-    /// Get transactions for mining (highest fee first)
+    /// Get transactions for mining (highest priority first, see `priority`)
     pub fn get_for_mining(&self, max_count: usize) -> Vec<Transaction> {
-        let mut result: Vec<Transaction> = Vec::with_capacity(max_count);
-        
-        // Iterate from highest fee to lowest
-        for (_, hashes) in self.by_fee.iter().rev() {
-            for hash in hashes {
-                if let Some(tx) = self.transactions.get(hash) {
-                    result.push(tx.clone());
-                    if result.len() >= max_count {
-                        return result;
-                    }
-                }
-            }
-        }
-        
-        result
+        self.get_for_mining_at(max_count, now_secs())
+    }
+    
+    /// `get_for_mining` with the pool ages measured at `now` (unix seconds)
+    pub fn get_for_mining_at(&self, max_count: usize, now: u64) -> Vec<Transaction> {
+        let mut ranked: Vec<(f64, &Transaction)> = self
+            .transactions
+            .iter()
+            .map(|(hash, tx)| (self.priority(tx, now.saturating_sub(self.added_at[hash])), tx))
+            .collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| b.1.fee_rate().cmp(&a.1.fee_rate())));
+        ranked.into_iter().take(max_count).map(|(_, tx)| tx.clone()).collect()
+    }
+    
+    /// Fee rate scaled up by time in the pool:
+    /// `fee_rate * (1 + aging_rate * minutes)`. Fee dominates among recent
+    /// transactions, but a waiting one eventually outranks any newer fee
+    /// rate and cannot starve.
+    pub fn priority(&self, tx: &Transaction, age_secs: u64) -> f64 {
+        tx.fee_rate() as f64 * (1.0 + self.aging_rate * age_secs as f64 / 60.0)
     }
     
     /// Get transaction by hash
@@ -146,14 +344,21 @@ impl Mempool {
         self.transactions.contains_key(hash)
     }
     
+    /// Every pending transaction, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &Transaction> {
+        self.transactions.values()
+    }
+    
     /// Remove transaction (after mining or expiry)
     pub fn remove(&mut self, hash: &[u8; 32]) -> Option<Transaction> {
         if let Some(tx) = self.transactions.remove(hash) {
-            // Remove from fee index
-            if let Some(hashes) = self.by_fee.get_mut(&tx.fee) {
+            self.added_at.remove(hash);
+            // Remove from fee-rate index
+            let fee_rate = tx.fee_rate();
+            if let Some(hashes) = self.by_fee_rate.get_mut(&fee_rate) {
                 hashes.remove(hash);
                 if hashes.is_empty() {
-                    self.by_fee.remove(&tx.fee);
+                    self.by_fee_rate.remove(&fee_rate);
                 }
             }
             
@@ -166,16 +371,7 @@ impl Mempool {
             }
             
             // Remove nullifier
-            let nullifier = {
-                let mut hasher = sha2::Sha256::new();
-                hasher.update(tx.from);
-                hasher.update(tx.nonce.to_le_bytes());
-                let result = hasher.finalize();
-                let mut n = [0u8; 32];
-                n.copy_from_slice(&result);
-                n
-            };
-            self.nullifiers.remove(&nullifier);
+            self.nullifiers.remove(&nullifier(&tx));
             
             Some(tx)
         } else {
@@ -190,6 +386,45 @@ impl Mempool {
         }
     }
     
+    /// Drop a transaction that is no longer valid, announcing it as evicted
+    pub fn evict(&mut self, hash: &[u8; 32]) -> Option<Transaction> {
+        let tx = self.remove(hash)?;
+        self.emit(MempoolEvent::Evicted(tx.clone()));
+        Some(tx)
+    }
+    
+    /// Drop what `block` confirmed (`Mined`) and what it made unspendable
+    /// by using the same (sender, nonce) (`Evicted`)
+    pub fn remove_mined(&mut self, block: &Block) {
+        for tx in &block.transactions {
+            let hash = tx.hash();
+            if self.remove(&hash).is_some() {
+                self.emit(MempoolEvent::Mined { hash, height: block.slot });
+            } else if self.nullifiers.contains(&nullifier(tx)) {
+                if let Some(conflict) = self.get_by_sender(&tx.from).into_iter().find(|c| c.nonce == tx.nonce) {
+                    self.remove(&conflict.hash());
+                    self.emit(MempoolEvent::Evicted(conflict));
+                }
+            }
+        }
+    }
+    
+    /// Drop transactions that arrived more than `max_age_secs` before `now`
+    pub fn expire(&mut self, max_age_secs: u64, now: u64) -> usize {
+        let stale: Vec<[u8; 32]> = self
+            .added_at
+            .iter()
+            .filter(|(_, added)| now.saturating_sub(**added) > max_age_secs)
+            .map(|(hash, _)| *hash)
+            .collect();
+        for hash in &stale {
+            if let Some(tx) = self.remove(hash) {
+                self.emit(MempoolEvent::Expired(tx));
+            }
+        }
+        stale.len()
+    }
+    
     /// Get all transactions from a sender
     pub fn get_by_sender(&self, sender: &Address) -> Vec<Transaction> {
         self.by_sender
@@ -202,11 +437,13 @@ impl Mempool {
             .unwrap_or_default()
     }
     
-    /// Evict lowest fee transaction
-    fn evict_lowest_fee(&mut self) {
-        if let Some((_, hashes)) = self.by_fee.iter().next() {
+    /// Evict lowest fee-rate transaction
+    fn evict_lowest_fee_rate(&mut self) {
+        if let Some((_, hashes)) = self.by_fee_rate.iter().next() {
             if let Some(&hash) = hashes.iter().next() {
-                self.remove(&hash);
+                if let Some(tx) = self.remove(&hash) {
+                    self.emit(MempoolEvent::Evicted(tx));
+                }
             }
         }
     }
@@ -229,8 +466,9 @@ impl Mempool {
     /// Clear all transactions
     pub fn clear(&mut self) {
         self.transactions.clear();
-        self.by_fee.clear();
+        self.by_fee_rate.clear();
         self.by_sender.clear();
+        self.added_at.clear();
         self.nullifiers.clear();
     }
     
@@ -240,8 +478,8 @@ impl Mempool {
             size: self.len(),
             total_fees: self.total_fees(),
             unique_senders: self.by_sender.len(),
-            highest_fee: self.by_fee.keys().next_back().copied().unwrap_or(0),
-            lowest_fee: self.by_fee.keys().next().copied().unwrap_or(0),
+            highest_fee: self.transactions.values().map(|tx| tx.fee).max().unwrap_or(0),
+            lowest_fee: self.transactions.values().map(|tx| tx.fee).min().unwrap_or(0),
         }
     }
 }
@@ -252,6 +490,18 @@ impl Default for Mempool {
     }
 }
 
+/// Double-spend nullifier: one per (sender, nonce)
+fn nullifier(tx: &Transaction) -> [u8; 32] {
+    hashing::tagged_sha256(hashing::NULLIFIER, &[&tx.from, &tx.nonce.to_le_bytes()])
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
 /// Mempool statistics
 #[derive(Debug, Clone)]
 pub struct MempoolStats {
@@ -273,6 +523,7 @@ mod tests {
             amount,
             fee,
             nonce,
+            chain_id: crate::config::MAINNET_NETWORK_ID,
             zk_proof: vec![],
             signature: vec![],
         }
@@ -283,7 +534,7 @@ mod tests {
         let mut mempool = Mempool::new();
         let tx = create_test_transaction(100, 10, 0);
         
-        assert!(mempool.add(tx).is_ok());
+        assert!(mempool.add(tx, &State::new()).is_ok());
         assert_eq!(mempool.len(), 1);
     }
     
@@ -292,17 +543,17 @@ mod tests {
         let mut mempool = Mempool::new();
         let tx = create_test_transaction(100, 10, 0);
         
-        assert!(mempool.add(tx.clone()).is_ok());
-        assert!(mempool.add(tx).is_err());
+        assert!(mempool.add(tx.clone(), &State::new()).is_ok());
+        assert!(mempool.add(tx, &State::new()).is_err());
     }
     
     #[test]
     fn test_mempool_fee_ordering() {
         let mut mempool = Mempool::new();
         
-        assert!(mempool.add(create_test_transaction(100, 5, 0)).is_ok(), "Failed to add tx with fee 5");
-        assert!(mempool.add(create_test_transaction(100, 10, 1)).is_ok(), "Failed to add tx with fee 10");
-        assert!(mempool.add(create_test_transaction(100, 1, 2)).is_ok(), "Failed to add tx with fee 1");
+        assert!(mempool.add(create_test_transaction(100, 5, 0), &State::new()).is_ok(), "Failed to add tx with fee 5");
+        assert!(mempool.add(create_test_transaction(100, 10, 1), &State::new()).is_ok(), "Failed to add tx with fee 10");
+        assert!(mempool.add(create_test_transaction(100, 1, 2), &State::new()).is_ok(), "Failed to add tx with fee 1");
         
         let txs = mempool.get_for_mining(3);
         assert_eq!(txs[0].fee, 10);
@@ -314,14 +565,251 @@ mod tests {
     fn test_mempool_eviction() {
         let mut mempool = Mempool::with_capacity(2, DEFAULT_MAX_TX_SIZE);
         
-        assert!(mempool.add(create_test_transaction(100, 5, 0)).is_ok(), "Failed to add tx with fee 5");
-        assert!(mempool.add(create_test_transaction(100, 10, 1)).is_ok(), "Failed to add tx with fee 10");
+        assert!(mempool.add(create_test_transaction(100, 5, 0), &State::new()).is_ok(), "Failed to add tx with fee 5");
+        assert!(mempool.add(create_test_transaction(100, 10, 1), &State::new()).is_ok(), "Failed to add tx with fee 10");
         
         // This should evict the lowest fee (5)
-        assert!(mempool.add(create_test_transaction(100, 15, 2)).is_ok(), "Failed to add tx with fee 15");
+        assert!(mempool.add(create_test_transaction(100, 15, 2), &State::new()).is_ok(), "Failed to add tx with fee 15");
         
         assert_eq!(mempool.len(), 2);
         let stats = mempool.stats();
         assert_eq!(stats.lowest_fee, 10);
     }
+    
+    #[test]
+    fn test_mempool_dust_limit() {
+        let consensus = ConsensusConfig { dust_limit: 500, min_fee_per_byte: 0, ..ConsensusConfig::default() };
+        let mut mempool = Mempool::new().with_consensus_policy(&consensus);
+        
+        assert!(matches!(
+            mempool.add(create_test_transaction(499, 10, 0), &State::new()),
+            Err(AxiomError::Dust { amount: 499, limit: 500 })
+        ));
+        assert!(mempool.add(create_test_transaction(500, 10, 1), &State::new()).is_ok());
+        // Zero is dust too unless data carriers are allowed
+        assert!(mempool.add(create_test_transaction(0, 10, 2), &State::new()).is_err());
+        
+        let carriers = ConsensusConfig { allow_zero_amount: true, ..consensus };
+        let mut mempool = Mempool::new().with_consensus_policy(&carriers);
+        assert!(mempool.add(create_test_transaction(0, 10, 2), &State::new()).is_ok());
+        assert!(mempool.add(create_test_transaction(1, 10, 3), &State::new()).is_err());
+    }
+    
+    fn with_proof(fee: u64, nonce: u64, proof_len: usize) -> Transaction {
+        Transaction { zk_proof: vec![0u8; proof_len], ..create_test_transaction(100, fee, nonce) }
+    }
+    
+    #[test]
+    fn test_mempool_min_fee_per_byte() {
+        let consensus = ConsensusConfig { min_fee_per_byte: 10, dust_limit: 0, ..ConsensusConfig::default() };
+        let mut mempool = Mempool::new().with_consensus_policy(&consensus);
+        
+        // The same absolute fee covers a small transaction but not a large one
+        let small = with_proof(2_000, 0, 0);
+        let large = with_proof(2_000, 1, 1_000);
+        assert!(small.fee >= small.min_fee(10));
+        assert!(mempool.add(small, &State::new()).is_ok());
+        assert!(matches!(
+            mempool.add(large.clone(), &State::new()),
+            Err(AxiomError::FeeTooLow { min, actual: 2_000 }) if min == 10 * large.size() as u64
+        ));
+    }
+    
+    #[test]
+    fn test_mempool_orders_by_fee_rate() {
+        let mut mempool = Mempool::new();
+        let bulky = with_proof(1_000, 0, 2_000);
+        let compact = with_proof(500, 1, 0);
+        assert!(bulky.fee > compact.fee && bulky.fee_rate() < compact.fee_rate());
+        mempool.add(bulky, &State::new()).unwrap();
+        mempool.add(compact, &State::new()).unwrap();
+        
+        let txs = mempool.get_for_mining(2);
+        assert_eq!(txs[0].fee, 500);
+        assert_eq!(txs[1].fee, 1_000);
+    }
+    
+    #[test]
+    fn test_mempool_evicts_lowest_fee_rate() {
+        let mut mempool = Mempool::with_capacity(2, DEFAULT_MAX_TX_SIZE);
+        let bulky = with_proof(1_000, 0, 2_000);
+        mempool.add(bulky.clone(), &State::new()).unwrap();
+        mempool.add(with_proof(500, 1, 0), &State::new()).unwrap();
+        
+        // Beats the bulky transaction's rate despite a lower absolute fee
+        mempool.add(with_proof(600, 2, 0), &State::new()).unwrap();
+        assert_eq!(mempool.len(), 2);
+        assert!(!mempool.contains(&bulky.hash()));
+        
+        // Cannot beat the lowest remaining rate
+        assert!(matches!(mempool.add(with_proof(400, 3, 0), &State::new()), Err(AxiomError::FeeTooLow { .. })));
+    }
+    
+    fn subscribed(mempool: Mempool) -> (Mempool, broadcast::Receiver<MempoolEvent>) {
+        let (events, receiver) = broadcast::channel(16);
+        (mempool.with_events(events), receiver)
+    }
+    
+    #[test]
+    fn test_rbf_emits_replaced() {
+        let (mut mempool, mut events) = subscribed(Mempool::new());
+        let old = create_test_transaction(100, 10, 0);
+        mempool.add(old.clone(), &State::new()).unwrap();
+        assert_eq!(events.try_recv().unwrap(), MempoolEvent::Added(old.clone()));
+        
+        // Same (sender, nonce) without a higher fee is refused
+        let cheap = create_test_transaction(90, 10, 0);
+        assert!(matches!(mempool.add(cheap, &State::new()), Err(AxiomError::FeeTooLow { min: 11, actual: 10 })));
+        assert!(events.try_recv().is_err());
+        
+        let bump = create_test_transaction(100, 20, 0);
+        mempool.add(bump.clone(), &State::new()).unwrap();
+        assert_eq!(events.try_recv().unwrap(), MempoolEvent::Replaced { old: old.clone(), new: bump.clone() });
+        assert_eq!(mempool.len(), 1);
+        assert!(mempool.contains(&bump.hash()) && !mempool.contains(&old.hash()));
+    }
+    
+    #[test]
+    fn test_eviction_mining_and_expiry_events() {
+        let (mut mempool, mut events) = subscribed(Mempool::with_capacity(2, DEFAULT_MAX_TX_SIZE));
+        let low = create_test_transaction(100, 5, 0);
+        let mined = create_test_transaction(100, 10, 1);
+        let high = create_test_transaction(100, 15, 2);
+        mempool.add_at(low.clone(), &State::new(), 0).unwrap();
+        mempool.add_at(mined.clone(), &State::new(), 0).unwrap();
+        mempool.add_at(high.clone(), &State::new(), 100).unwrap();
+        let received: Vec<MempoolEvent> = std::iter::from_fn(|| events.try_recv().ok()).collect();
+        assert_eq!(received, vec![
+            MempoolEvent::Added(low),
+            MempoolEvent::Added(mined.clone()),
+            MempoolEvent::Evicted(create_test_transaction(100, 5, 0)),
+            MempoolEvent::Added(high.clone()),
+        ]);
+        
+        // A block confirms one and spends the other's nonce with a different transaction
+        let rival = create_test_transaction(1, 15, 2);
+        let block = Block::new([0u8; 32], 9, [4u8; 32], vec![mined.clone(), rival], [0u8; 32], vec![], 0);
+        mempool.remove_mined(&block);
+        assert_eq!(events.try_recv().unwrap(), MempoolEvent::Mined { hash: mined.hash(), height: 9 });
+        assert_eq!(events.try_recv().unwrap(), MempoolEvent::Evicted(high));
+        assert!(mempool.is_empty());
+        
+        let stale = create_test_transaction(100, 10, 3);
+        mempool.add_at(stale.clone(), &State::new(), 0).unwrap();
+        mempool.add_at(create_test_transaction(100, 10, 4), &State::new(), 500).unwrap();
+        assert_eq!(mempool.expire(600, 700), 1);
+        let received: Vec<MempoolEvent> = std::iter::from_fn(|| events.try_recv().ok()).collect();
+        assert_eq!(received.last(), Some(&MempoolEvent::Expired(stale)));
+        assert_eq!(mempool.len(), 1);
+    }
+    
+    #[test]
+    fn test_mempool_aging_prevents_starvation() {
+        let mut mempool = Mempool::new();
+        let old = create_test_transaction(100, 100, 0);
+        mempool.add_at(old.clone(), &State::new(), 0).unwrap();
+        
+        // A double-fee transaction arrives every minute and one block is mined
+        let mut selected_at = None;
+        for minute in 1..=200u64 {
+            let now = minute * 60;
+            mempool.add_at(create_test_transaction(100, 200, minute), &State::new(), now).unwrap();
+            let best = mempool.get_for_mining_at(1, now).remove(0);
+            mempool.remove(&best.hash());
+            if best.hash() == old.hash() {
+                selected_at = Some(minute);
+                break;
+            }
+        }
+        
+        // Outbid early on, but picked once it has waited ~100 minutes
+        let minute = selected_at.expect("old transaction was starved");
+        assert!((95..=105).contains(&minute), "selected after {} minutes", minute);
+    }
+    
+    #[test]
+    fn test_mempool_equal_ages_order_by_fee() {
+        let mut mempool = Mempool::new();
+        for (nonce, fee) in [5u64, 20, 1, 10].into_iter().enumerate() {
+            mempool.add_at(create_test_transaction(100, fee, nonce as u64), &State::new(), 1_000).unwrap();
+        }
+        // However long they have all waited
+        for now in [1_000, 1_000 + 86_400] {
+            let fees: Vec<u64> = mempool.get_for_mining_at(4, now).iter().map(|tx| tx.fee).collect();
+            assert_eq!(fees, vec![20, 10, 5, 1]);
+        }
+        
+        // Without aging, age never matters
+        let mut mempool = Mempool::new().with_aging_rate(0.0);
+        mempool.add_at(create_test_transaction(100, 5, 0), &State::new(), 0).unwrap();
+        mempool.add_at(create_test_transaction(100, 10, 1), &State::new(), 1_000_000).unwrap();
+        assert_eq!(mempool.get_for_mining_at(1, 1_000_000)[0].fee, 10);
+    }
+    
+    #[test]
+    fn test_mempool_aging_rate_from_config() {
+        let consensus = ConsensusConfig { dust_limit: 0, min_fee_per_byte: 0, ..ConsensusConfig::default() };
+        assert_eq!(Mempool::new().with_consensus_policy(&consensus).aging_rate, DEFAULT_AGING_RATE);
+        
+        // A node configured to rank by fee rate alone leaves the old transaction waiting
+        let unaged = ConsensusConfig { mempool_aging_rate: 0.0, ..consensus };
+        let mut mempool = Mempool::new().with_consensus_policy(&unaged);
+        mempool.add_at(create_test_transaction(100, 100, 0), &State::new(), 0).unwrap();
+        mempool.add_at(create_test_transaction(100, 150, 1), &State::new(), 9_000).unwrap();
+        assert_eq!(mempool.get_for_mining_at(1, 12_000)[0].fee, 150);
+        assert_eq!(mempool.with_aging_rate(DEFAULT_AGING_RATE).get_for_mining_at(1, 12_000)[0].fee, 100);
+    }
+
+    #[test]
+    fn test_blacklist_policy() {
+        let banned = [9u8; 32];
+        let mut mempool = Mempool::new().with_policy(BlacklistPolicy::new([banned]));
+        let state = State::new();
+
+        let mut to_banned = create_test_transaction(100, 10, 0);
+        to_banned.to = banned;
+        assert!(matches!(mempool.add(to_banned, &state), Err(AxiomError::PolicyRejected(_))));
+        let mut from_banned = create_test_transaction(100, 10, 0);
+        from_banned.from = banned;
+        assert!(matches!(mempool.add(from_banned, &state), Err(AxiomError::PolicyRejected(_))));
+        assert!(mempool.is_empty());
+
+        assert!(mempool.add(create_test_transaction(100, 10, 0), &state).is_ok());
+        assert_eq!(mempool.len(), 1);
+    }
+
+    #[test]
+    fn test_per_sender_cap_policy() {
+        let mut mempool = Mempool::new().with_policy(PerSenderCapPolicy { max_pending: 2 });
+        let mut state = State::new();
+        let sender = [1u8; 32];
+
+        mempool.add(create_test_transaction(100, 10, 0), &state).unwrap();
+        mempool.add(create_test_transaction(100, 10, 1), &state).unwrap();
+        assert!(matches!(
+            mempool.add(create_test_transaction(100, 10, 2), &state),
+            Err(AxiomError::PolicyRejected(_))
+        ));
+
+        // Other senders have their own allowance
+        let mut other = create_test_transaction(100, 10, 0);
+        other.from = [3u8; 32];
+        assert!(mempool.add(other, &state).is_ok());
+
+        // Confirming the first transaction frees a slot
+        mempool.remove(&create_test_transaction(100, 10, 0).hash());
+        state.nonces.insert(sender, 1);
+        assert!(mempool.add(create_test_transaction(100, 10, 2), &state).is_ok());
+        assert_eq!(mempool.get_by_sender(&sender).len(), 2);
+    }
+
+    #[test]
+    fn test_composed_policies() {
+        let policies: Vec<Box<dyn MempoolPolicy>> =
+            vec![Box::new(PerSenderCapPolicy { max_pending: 5 }), Box::new(RequireZkPolicy)];
+        let mut mempool = Mempool::new().with_policy(policies);
+        let err = mempool.add(create_test_transaction(100, 10, 0), &State::new()).unwrap_err();
+        assert!(err.to_string().contains("missing ZK proof"));
+        assert!(Mempool::new().add(create_test_transaction(100, 10, 0), &State::new()).is_ok());
+    }
 }
@@ -0,0 +1,272 @@
+//! Merkle trees over block transactions.
+//!
+//! Leaves are transaction hashes. Interior nodes are SHA-256 of the two
+//! children under `hashing::MERKLE_NODE`; a level with an odd number of nodes
+//! pairs its last node with itself. An empty tree has the all-zero root and a
+//! single-leaf tree's root is the leaf itself.
+//!
+//! [`MerkleAccumulator`] builds the same root leaf by leaf, for block
+//! assembly.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Double SHA-256 of `data`
+pub fn double_sha256(data: &[u8]) -> [u8; 32] {
+    let first = Sha256::digest(data);
+    Sha256::digest(first).into()
+}
+
+/// Parent node of two children
+pub fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    crate::hashing::tagged_sha256(crate::hashing::MERKLE_NODE, &[left, right])
+}
+
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+        .collect()
+}
+
+/// Merkle root of `leaves`
+pub fn root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    if leaves.is_empty() {
+        return [0u8; 32];
+    }
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level[0]
+}
+
+/// Merkle tree built by appending leaves one at a time, giving the same
+/// root as [`root`] over the leaves so far.
+///
+/// Only the roots of the complete subtrees along the right edge are kept
+/// (one per set bit of the leaf count), so appending costs O(1) hashes
+/// amortized and `root` O(log n). The siblings on the path of the first leaf
+/// are kept too, so that leaf can be replaced in O(log n) hashes: a block's
+/// coinbase comes first but pays the fees of the transactions after it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MerkleAccumulator {
+    /// `subtrees[level]`: root of a complete subtree of `2^level` leaves
+    /// still waiting for its right sibling
+    subtrees: Vec<Option<[u8; 32]>>,
+    /// `first_path[level]`: sibling of the first leaf's ancestor at `level`
+    first_path: Vec<[u8; 32]>,
+    len: u64,
+}
+
+impl MerkleAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of leaves appended
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Append `leaf`
+    pub fn push(&mut self, leaf: [u8; 32]) {
+        self.len += 1;
+        let mut node = leaf;
+        for (level, slot) in self.subtrees.iter_mut().enumerate() {
+            match slot.take() {
+                None => {
+                    *slot = Some(node);
+                    return;
+                }
+                Some(left) => {
+                    // Completing the subtree that holds the first leaf
+                    if self.len == 1 << (level + 1) {
+                        self.first_path.push(node);
+                    }
+                    node = hash_pair(&left, &node);
+                }
+            }
+        }
+        self.subtrees.push(Some(node));
+    }
+
+    /// Replace the first leaf with `leaf`. Does nothing while empty.
+    pub fn set_first(&mut self, leaf: [u8; 32]) {
+        let Some(Some(top)) = self.subtrees.last_mut() else {
+            return;
+        };
+        // The highest pending subtree is the one starting at the first leaf
+        *top = self.first_path.iter().fold(leaf, |node, sibling| hash_pair(&node, sibling));
+    }
+
+    /// Root over every leaf appended so far
+    pub fn root(&self) -> [u8; 32] {
+        let top = self.subtrees.len().saturating_sub(1);
+        // Right-edge node of each level, built from the leaves not yet in
+        // a complete subtree
+        let mut edge: Option<[u8; 32]> = None;
+        for (level, subtree) in self.subtrees.iter().enumerate() {
+            edge = match (*subtree, edge) {
+                (Some(left), Some(right)) => Some(hash_pair(&left, &right)),
+                (Some(node), None) | (None, Some(node)) => {
+                    if level == top {
+                        return node;
+                    }
+                    // Last node of a level with an odd count pairs with itself
+                    Some(hash_pair(&node, &node))
+                }
+                (None, None) => None,
+            };
+        }
+        edge.unwrap_or([0u8; 32])
+    }
+}
+
+impl Extend<[u8; 32]> for MerkleAccumulator {
+    fn extend<I: IntoIterator<Item = [u8; 32]>>(&mut self, leaves: I) {
+        for leaf in leaves {
+            self.push(leaf);
+        }
+    }
+}
+
+/// Sibling path from a leaf up to the root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    /// Position of the leaf among the block's transactions
+    pub index: u64,
+    /// Sibling hashes, leaf level first
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl MerkleProof {
+    /// Build the proof for `leaves[index]`, or `None` if out of range
+    pub fn generate(leaves: &[[u8; 32]], index: usize) -> Option<Self> {
+        if index >= leaves.len() {
+            return None;
+        }
+        let mut siblings = Vec::new();
+        let mut level = leaves.to_vec();
+        let mut position = index;
+        while level.len() > 1 {
+            let sibling = level.get(position ^ 1).unwrap_or(&level[position]);
+            siblings.push(*sibling);
+            level = next_level(&level);
+            position /= 2;
+        }
+        Some(Self { index: index as u64, siblings })
+    }
+
+    /// Root implied by `leaf` and this path
+    pub fn compute_root(&self, leaf: [u8; 32]) -> [u8; 32] {
+        let mut node = leaf;
+        let mut position = self.index;
+        for sibling in &self.siblings {
+            node = if position.is_multiple_of(2) {
+                hash_pair(&node, sibling)
+            } else {
+                hash_pair(sibling, &node)
+            };
+            position /= 2;
+        }
+        node
+    }
+
+    /// Whether `leaf` is committed to by `merkle_root` through this path
+    pub fn verify(&self, leaf: [u8; 32], merkle_root: [u8; 32]) -> bool {
+        // Reject paths longer than any tree with `index` in range could need
+        if self.siblings.len() < 64 && self.index >> self.siblings.len() != 0 {
+            return false;
+        }
+        self.compute_root(leaf) == merkle_root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(n: u8) -> Vec<[u8; 32]> {
+        (0..n).map(|i| [i; 32]).collect()
+    }
+
+    #[test]
+    fn test_root_small_trees() {
+        assert_eq!(root(&[]), [0u8; 32]);
+        assert_eq!(root(&[[7u8; 32]]), [7u8; 32]);
+
+        let l = leaves(3);
+        let expected = hash_pair(&hash_pair(&l[0], &l[1]), &hash_pair(&l[2], &l[2]));
+        assert_eq!(root(&l), expected);
+    }
+
+    #[test]
+    fn test_accumulator_matches_full_recompute() {
+        let all: Vec<[u8; 32]> = (0..70u64).map(|i| double_sha256(&i.to_le_bytes())).collect();
+        let mut acc = MerkleAccumulator::new();
+        assert_eq!(acc.root(), root(&[]));
+        for n in 1..=all.len() {
+            acc.push(all[n - 1]);
+            assert_eq!(acc.len(), n as u64);
+            assert_eq!(acc.root(), root(&all[..n]), "{} leaves", n);
+        }
+    }
+
+    #[test]
+    fn test_accumulator_replaces_first_leaf() {
+        let coinbase = [0xCBu8; 32];
+        for n in [1usize, 2, 3, 4, 5, 7, 8, 9, 16, 17, 31] {
+            let mut l = leaves(n as u8);
+            let mut acc = MerkleAccumulator::new();
+            acc.extend(l.iter().copied());
+            acc.set_first(coinbase);
+            l[0] = coinbase;
+            assert_eq!(acc.root(), root(&l), "{} leaves", n);
+
+            // Later appends build on the replaced leaf
+            acc.push([0xEEu8; 32]);
+            l.push([0xEEu8; 32]);
+            assert_eq!(acc.root(), root(&l), "{} leaves", n + 1);
+        }
+
+        let mut empty = MerkleAccumulator::new();
+        empty.set_first(coinbase);
+        assert!(empty.is_empty());
+        assert_eq!(empty.root(), [0u8; 32]);
+    }
+
+    #[test]
+    fn test_proofs_verify_for_every_leaf() {
+        for n in 1..=9u8 {
+            let l = leaves(n);
+            let r = root(&l);
+            for (i, leaf) in l.iter().enumerate() {
+                let proof = MerkleProof::generate(&l, i).unwrap();
+                assert!(proof.verify(*leaf, r), "leaf {} of {}", i, n);
+            }
+            assert!(MerkleProof::generate(&l, n as usize).is_none());
+        }
+    }
+
+    #[test]
+    fn test_tampered_proof_fails() {
+        let l = leaves(6);
+        let r = root(&l);
+        let proof = MerkleProof::generate(&l, 4).unwrap();
+
+        let mut bad_sibling = proof.clone();
+        bad_sibling.siblings[1][0] ^= 1;
+        assert!(!bad_sibling.verify(l[4], r));
+
+        let mut bad_index = proof.clone();
+        bad_index.index = 5;
+        assert!(!bad_index.verify(l[4], r));
+
+        assert!(!proof.verify(l[3], r));
+    }
+}
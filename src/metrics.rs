@@ -0,0 +1,194 @@
+//! Prometheus metrics for the node.
+//!
+//! With the `prometheus` feature enabled, metrics are registered in a
+//! dedicated registry and served in the text exposition format on `/metrics`.
+//! Without it, the recording functions are no-ops so call sites stay
+//! unconditional.
+
+#[cfg(feature = "prometheus")]
+mod registry {
+    use actix_web::{web, App, HttpResponse, HttpServer, Responder};
+    use lazy_static::lazy_static;
+    use prometheus::{
+        Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder,
+    };
+
+    lazy_static! {
+        pub static ref REGISTRY: Registry = Registry::new();
+        pub static ref BLOCKS_TOTAL: IntCounter =
+            IntCounter::new("blocks_total", "Blocks accepted into the local chain").unwrap();
+        pub static ref MEMPOOL_SIZE: IntGauge =
+            IntGauge::new("mempool_size", "Transactions waiting in the mempool").unwrap();
+        pub static ref CONNECTED_PEERS: IntGauge =
+            IntGauge::new("connected_peers", "Currently connected libp2p peers").unwrap();
+        pub static ref CHAIN_HEIGHT: IntGauge =
+            IntGauge::new("chain_height", "Height of the local chain tip").unwrap();
+        pub static ref PROOF_QUEUE_DEPTH: IntGauge =
+            IntGauge::new("proof_queue_depth", "RPC proof requests waiting for a proving slot").unwrap();
+        pub static ref PROOFS_REJECTED_TOTAL: IntCounter =
+            IntCounter::new("proofs_rejected_total", "RPC proof requests turned away with the queue full").unwrap();
+        pub static ref BLOCK_CACHE_HITS_TOTAL: IntCounter =
+            IntCounter::new("block_cache_hits_total", "Block and transaction lookups served from the cache").unwrap();
+        pub static ref BLOCK_CACHE_MISSES_TOTAL: IntCounter =
+            IntCounter::new("block_cache_misses_total", "Block and transaction lookups that missed the cache").unwrap();
+        pub static ref WORK_QUEUE_DEPTH: IntGaugeVec = IntGaugeVec::new(
+            Opts::new("work_queue_depth", "Items waiting in an event-loop work queue"),
+            &["queue"]
+        ).unwrap();
+        pub static ref WORK_QUEUE_DROPPED_TOTAL: IntCounterVec = IntCounterVec::new(
+            Opts::new("work_queue_dropped_total", "Items shed because their work queue was full"),
+            &["queue"]
+        ).unwrap();
+        pub static ref VDF_EVAL_SECONDS: Histogram = Histogram::with_opts(
+            HistogramOpts::new("vdf_eval_seconds", "Time spent evaluating the block VDF")
+                .buckets(vec![0.01, 0.1, 0.5, 1.0, 5.0, 30.0, 120.0, 600.0, 1800.0])
+        ).unwrap();
+    }
+
+    /// Register all collectors. Safe to call more than once.
+    pub fn init() {
+        let _ = REGISTRY.register(Box::new(BLOCKS_TOTAL.clone()));
+        let _ = REGISTRY.register(Box::new(MEMPOOL_SIZE.clone()));
+        let _ = REGISTRY.register(Box::new(CONNECTED_PEERS.clone()));
+        let _ = REGISTRY.register(Box::new(CHAIN_HEIGHT.clone()));
+        let _ = REGISTRY.register(Box::new(PROOF_QUEUE_DEPTH.clone()));
+        let _ = REGISTRY.register(Box::new(PROOFS_REJECTED_TOTAL.clone()));
+        let _ = REGISTRY.register(Box::new(BLOCK_CACHE_HITS_TOTAL.clone()));
+        let _ = REGISTRY.register(Box::new(BLOCK_CACHE_MISSES_TOTAL.clone()));
+        let _ = REGISTRY.register(Box::new(WORK_QUEUE_DEPTH.clone()));
+        let _ = REGISTRY.register(Box::new(WORK_QUEUE_DROPPED_TOTAL.clone()));
+        let _ = REGISTRY.register(Box::new(VDF_EVAL_SECONDS.clone()));
+    }
+
+    pub fn record_block(height: u64) {
+        BLOCKS_TOTAL.inc();
+        CHAIN_HEIGHT.set(height as i64);
+    }
+
+    pub fn set_chain_height(height: u64) {
+        CHAIN_HEIGHT.set(height as i64);
+    }
+
+    pub fn set_mempool_size(size: usize) {
+        MEMPOOL_SIZE.set(size as i64);
+    }
+
+    pub fn set_connected_peers(count: usize) {
+        CONNECTED_PEERS.set(count as i64);
+    }
+
+    pub fn observe_vdf_seconds(seconds: f64) {
+        VDF_EVAL_SECONDS.observe(seconds);
+    }
+
+    pub fn set_proof_queue_depth(depth: usize) {
+        PROOF_QUEUE_DEPTH.set(depth as i64);
+    }
+
+    pub fn record_proof_rejected() {
+        PROOFS_REJECTED_TOTAL.inc();
+    }
+
+    pub fn set_queue_depth(queue: &str, depth: usize) {
+        WORK_QUEUE_DEPTH.with_label_values(&[queue]).set(depth as i64);
+    }
+
+    pub fn record_queue_dropped(queue: &str) {
+        WORK_QUEUE_DROPPED_TOTAL.with_label_values(&[queue]).inc();
+    }
+
+    pub fn record_cache_lookup(hit: bool) {
+        if hit {
+            BLOCK_CACHE_HITS_TOTAL.inc();
+        } else {
+            BLOCK_CACHE_MISSES_TOTAL.inc();
+        }
+    }
+
+    /// Current metrics in the Prometheus text exposition format
+    pub fn gather() -> String {
+        init();
+        let mut buffer = Vec::new();
+        let _ = TextEncoder::new().encode(&REGISTRY.gather(), &mut buffer);
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+
+    async fn metrics_handler() -> impl Responder {
+        HttpResponse::Ok()
+            .content_type(prometheus::TEXT_FORMAT)
+            .body(gather())
+    }
+
+    /// Mount the `/metrics` route on an actix app
+    pub fn configure(cfg: &mut web::ServiceConfig) {
+        cfg.route("/metrics", web::get().to(metrics_handler));
+    }
+
+    /// Bind a `/metrics` server on `addr`. The returned server runs once
+    /// spawned (e.g. `tokio::spawn(server)`).
+    pub fn serve(addr: &str) -> std::io::Result<actix_web::dev::Server> {
+        init();
+        Ok(HttpServer::new(|| App::new().configure(configure))
+            .workers(1)
+            .bind(addr)?
+            .run())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use actix_web::test;
+
+        #[actix_rt::test]
+        async fn test_metrics_endpoint_exposition() {
+            record_block(42);
+            set_mempool_size(3);
+            set_connected_peers(2);
+            observe_vdf_seconds(0.25);
+            set_queue_depth("storage", 7);
+            record_queue_dropped("storage");
+
+            let app = test::init_service(App::new().configure(configure)).await;
+            let req = test::TestRequest::get().uri("/metrics").to_request();
+            let body = test::call_and_read_body(&app, req).await;
+            let text = String::from_utf8(body.to_vec()).unwrap();
+
+            // Every sample line is `name[{labels}] value`
+            let mut samples = std::collections::HashMap::new();
+            for line in text.lines().filter(|l| !l.starts_with('#') && !l.is_empty()) {
+                let (name, value) = line.rsplit_once(' ').expect("sample line");
+                let value: f64 = value.parse().expect("numeric sample");
+                samples.insert(name.to_string(), value);
+            }
+
+            assert!(samples["blocks_total"] >= 1.0);
+            assert_eq!(samples["chain_height"], 42.0);
+            assert_eq!(samples["mempool_size"], 3.0);
+            assert_eq!(samples["connected_peers"], 2.0);
+            assert!(samples["vdf_eval_seconds_count"] >= 1.0);
+            assert_eq!(samples["work_queue_depth{queue=\"storage\"}"], 7.0);
+            assert!(samples["work_queue_dropped_total{queue=\"storage\"}"] >= 1.0);
+        }
+    }
+}
+
+#[cfg(feature = "prometheus")]
+pub use registry::*;
+
+#[cfg(not(feature = "prometheus"))]
+mod noop {
+    pub fn init() {}
+    pub fn record_block(_height: u64) {}
+    pub fn set_chain_height(_height: u64) {}
+    pub fn set_mempool_size(_size: usize) {}
+    pub fn set_connected_peers(_count: usize) {}
+    pub fn observe_vdf_seconds(_seconds: f64) {}
+    pub fn set_proof_queue_depth(_depth: usize) {}
+    pub fn record_proof_rejected() {}
+    pub fn set_queue_depth(_queue: &str, _depth: usize) {}
+    pub fn record_queue_dropped(_queue: &str) {}
+    pub fn record_cache_lookup(_hit: bool) {}
+}
+
+#[cfg(not(feature = "prometheus"))]
+pub use noop::*;
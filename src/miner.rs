@@ -0,0 +1,179 @@
+//! Multi-threaded nonce search for the node's mining loop.
+//!
+//! `mine_parallel` splits the nonce space of one sealed-but-unsolved block
+//! across `MiningConfig.threads` worker threads. Thread `i` tries nonces
+//! `i, i + threads, i + 2 * threads, ...`; the first to meet the difficulty
+//! claims the win and the others stop at their next check. Every thread
+//! works on its own clone of the same candidate, so whichever wins, the
+//! block differs from the candidate only in its nonce.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::block::Block;
+use crate::sync::SyncStatus;
+
+/// Nonces tried between checks for a winner and intensity pauses
+const BATCH_SIZE: u64 = 1024;
+
+/// Search up to `max_attempts` nonces of `candidate` for one meeting
+/// `difficulty`, on `threads` threads. At `intensity` below 100 each thread
+/// rests after every batch (see `throttle_pause`). Returns the solved
+/// block, or `None` if every nonce was tried.
+pub fn mine_parallel(candidate: &Block, difficulty: u64, threads: usize, intensity: u8, max_attempts: u64) -> Option<Block> {
+    let threads = threads.max(1) as u64;
+    let done = AtomicBool::new(false);
+    let winner: Mutex<Option<Block>> = Mutex::new(None);
+
+    std::thread::scope(|scope| {
+        for start in 0..threads {
+            let (done, winner) = (&done, &winner);
+            let mut block = candidate.clone();
+            scope.spawn(move || {
+                let mut nonce = start;
+                while !done.load(Ordering::Relaxed) && nonce < max_attempts {
+                    let batch_start = Instant::now();
+                    for _ in 0..BATCH_SIZE {
+                        if nonce >= max_attempts {
+                            break;
+                        }
+                        block.nonce = nonce;
+                        if block.meets_target_for(difficulty) {
+                            // Only the first thread to get here publishes its block
+                            if done.compare_exchange(false, true, Ordering::AcqRel, Ordering::Relaxed).is_ok() {
+                                *winner.lock().unwrap() = Some(block);
+                            }
+                            return;
+                        }
+                        nonce = nonce.saturating_add(threads);
+                    }
+                    let pause = throttle_pause(batch_start.elapsed(), intensity);
+                    if !pause.is_zero() {
+                        std::thread::sleep(pause);
+                    }
+                }
+            });
+        }
+    });
+
+    winner.into_inner().unwrap()
+}
+
+/// Rest after a batch that took `work`, so a thread runs about `intensity`
+/// percent (1-100) of the time
+pub fn throttle_pause(work: Duration, intensity: u8) -> Duration {
+    let intensity = intensity.clamp(1, 100) as u32;
+    work * (100 - intensity) / intensity
+}
+
+/// Whether the mining loop may produce blocks right now
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MiningStatus {
+    Active,
+    /// Too few peers: a block mined now would start a private fork
+    Paused { peers: usize, required: usize },
+    /// Still downloading the chain; a block mined now would be stale
+    Syncing { current: u64, target: u64 },
+}
+
+impl MiningStatus {
+    /// Peer check (see `for_peers`), after first waiting for the chain to
+    /// be `Synced`
+    pub fn new(sync: SyncStatus, peers: usize, min_peers_to_mine: usize, solo: bool) -> Self {
+        match sync {
+            SyncStatus::Syncing { current, target } => MiningStatus::Syncing { current, target },
+            SyncStatus::Synced => Self::for_peers(peers, min_peers_to_mine, solo),
+        }
+    }
+
+    /// Status with `peers` connected against `mining.min_peers_to_mine`.
+    /// `solo` (regtest, or `mining.solo`) mines regardless.
+    pub fn for_peers(peers: usize, min_peers_to_mine: usize, solo: bool) -> Self {
+        if solo || peers >= min_peers_to_mine {
+            MiningStatus::Active
+        } else {
+            MiningStatus::Paused { peers, required: min_peers_to_mine }
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        *self == MiningStatus::Active
+    }
+}
+
+impl fmt::Display for MiningStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MiningStatus::Active => write!(f, "active"),
+            MiningStatus::Paused { peers, required } => {
+                write!(f, "paused: insufficient peers ({}/{})", peers, required)
+            }
+            MiningStatus::Syncing { current, target } => {
+                write!(f, "paused: syncing ({}/{})", current, target)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::Timechain;
+    use crate::genesis;
+    use crate::wallet::Wallet;
+
+    #[test]
+    fn test_parallel_mining_finds_valid_block() {
+        let candidate = Block::new([1u8; 32], 1, [2u8; 32], vec![], [3u8; 32], vec![1u8; 128], 0);
+        let block = mine_parallel(&candidate, 64, 4, 100, u64::MAX).unwrap();
+        assert!(block.meets_target_for(64));
+
+        // Only the nonce differs from the shared candidate
+        let mut unsolved = block.clone();
+        unsolved.nonce = 0;
+        assert_eq!(unsolved, candidate);
+
+        // An impossible target within the attempt budget yields nothing
+        assert!(mine_parallel(&candidate, u64::MAX, 4, 100, 64).is_none());
+
+        assert_eq!(throttle_pause(Duration::from_millis(10), 50), Duration::from_millis(10));
+        assert_eq!(throttle_pause(Duration::from_millis(10), 100), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_one_block_per_round() {
+        let mut chain = Timechain::new(genesis::genesis());
+        let wallet = Wallet::from_secret_key([6u8; 32]);
+        for round in 1..=3u64 {
+            let mut candidate = chain.next_block(&wallet, vec![]);
+            candidate.vdf_proof = crate::main_helper::compute_vdf(
+                crate::vdf::evaluate(candidate.parent, candidate.slot),
+                chain.vdf_steps,
+            );
+            // Eight threads race; exactly one solution comes back and it extends the chain
+            let block = mine_parallel(&candidate, chain.difficulty, 8, 100, u64::MAX).unwrap();
+            chain.add_block(block.clone(), chain.block_time).unwrap();
+            assert_eq!(chain.blocks.len() as u64, round + 1);
+            assert!(chain.add_block(block, chain.block_time).is_err());
+        }
+    }
+
+    #[test]
+    fn test_min_peers_to_mine() {
+        let one_peer = MiningStatus::for_peers(1, 2, false);
+        assert_eq!(one_peer, MiningStatus::Paused { peers: 1, required: 2 });
+        assert!(!one_peer.is_active());
+        assert_eq!(one_peer.to_string(), "paused: insufficient peers (1/2)");
+
+        assert!(MiningStatus::for_peers(2, 2, false).is_active());
+        assert!(MiningStatus::for_peers(0, 2, true).is_active());
+        assert!(MiningStatus::for_peers(0, 0, false).is_active());
+
+        // Nothing is mined while behind a peer, however many peers there are
+        let syncing = MiningStatus::new(SyncStatus::Syncing { current: 3, target: 9 }, 5, 2, false);
+        assert_eq!(syncing.to_string(), "paused: syncing (3/9)");
+        assert!(MiningStatus::new(SyncStatus::Synced, 5, 2, false).is_active());
+    }
+}
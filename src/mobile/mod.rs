@@ -163,16 +163,10 @@ impl MobileBlock {
     }
 
     fn calculate_hash(height: u64, prev: [u8; 32], miner: [u8; 32], time: u64) -> [u8; 32] {
-        use sha2::{Digest, Sha256};
-        let mut hasher = Sha256::new();
-        hasher.update(height.to_le_bytes());
-        hasher.update(prev);
-        hasher.update(miner);
-        hasher.update(time.to_le_bytes());
-        let result = hasher.finalize();
-        let mut hash = [0u8; 32];
-        hash.copy_from_slice(&result[..32]);
-        hash
+        crate::hashing::tagged_sha256(
+            crate::hashing::MOBILE_HEADER,
+            &[&height.to_le_bytes(), &prev, &miner, &time.to_le_bytes()],
+        )
     }
 
     /// Verify block validity
@@ -0,0 +1,286 @@
+//! M-of-N threshold wallets.
+//!
+//! A multisig address is the hash of its threshold and sorted member keys,
+//! so it is not itself an Ed25519 key. Transactions from it carry a
+//! [`MultisigSignature`] in `signature`: the member set plus at least
+//! `threshold` member signatures over the usual signing message. The node
+//! recognises the encoding in `Transaction::verify_signature`, so mempool
+//! and block validation accept multisig spends like any other.
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::{AxiomError, Result};
+use crate::transaction::{Address, Transaction};
+use crate::wallet::Wallet;
+
+/// Largest supported member set
+pub const MAX_MULTISIG_KEYS: usize = 16;
+
+/// Prefix marking a multisig signature set in `Transaction::signature`
+const MULTISIG_MAGIC: &[u8; 4] = b"AXMS";
+
+/// M-of-N spending policy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultisigWallet {
+    pub threshold: u8,
+    /// Member public keys, sorted
+    pub public_keys: Vec<[u8; 32]>,
+}
+
+/// One member's signature over a transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartialSignature {
+    pub signer: [u8; 32],
+    pub signature: Vec<u8>,
+}
+
+/// Signature set carried by a multisig transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultisigSignature {
+    pub wallet: MultisigWallet,
+    /// `(member index, signature)`, ascending by index
+    pub signatures: Vec<(u8, Vec<u8>)>,
+}
+
+impl MultisigWallet {
+    /// Policy requiring `threshold` of `public_keys`. Keys must be distinct
+    /// valid Ed25519 keys; their order does not affect the address.
+    pub fn new(threshold: u8, mut public_keys: Vec<[u8; 32]>) -> Result<Self> {
+        public_keys.sort_unstable();
+        public_keys.dedup();
+        if public_keys.is_empty() || public_keys.len() > MAX_MULTISIG_KEYS {
+            return Err(AxiomError::InvalidTransaction(format!(
+                "multisig needs 1 to {} distinct keys",
+                MAX_MULTISIG_KEYS
+            )));
+        }
+        if threshold == 0 || threshold as usize > public_keys.len() {
+            return Err(AxiomError::InvalidTransaction(format!(
+                "threshold {} out of range for {} keys",
+                threshold,
+                public_keys.len()
+            )));
+        }
+        if let Some(bad) = public_keys.iter().find(|k| VerifyingKey::from_bytes(k).is_err()) {
+            return Err(AxiomError::InvalidPublicKey(hex::encode(bad)));
+        }
+        Ok(Self { threshold, public_keys })
+    }
+
+    /// Address funds are sent to
+    pub fn address(&self) -> Address {
+        let mut hasher = Sha256::new();
+        hasher.update(b"axiom-multisig-v1");
+        hasher.update([self.threshold, self.public_keys.len() as u8]);
+        for key in &self.public_keys {
+            hasher.update(key);
+        }
+        hasher.finalize().into()
+    }
+
+    fn member_index(&self, key: &[u8; 32]) -> Option<u8> {
+        self.public_keys.binary_search(key).ok().map(|i| i as u8)
+    }
+
+    /// Sign `tx` as one member
+    pub fn partial_sign(&self, tx: &Transaction, wallet: &Wallet) -> Result<PartialSignature> {
+        if tx.from != self.address() {
+            return Err(AxiomError::InvalidTransaction("transaction is not from this multisig".to_string()));
+        }
+        if self.member_index(&wallet.address).is_none() {
+            return Err(AxiomError::InvalidSignature("signer is not a multisig member".to_string()));
+        }
+        let key = SigningKey::from_bytes(&wallet.secret_key);
+        Ok(PartialSignature {
+            signer: wallet.address,
+            signature: key.sign(&tx.signing_message()).to_bytes().to_vec(),
+        })
+    }
+
+    /// Attach `threshold` valid partials to `tx`, producing a transaction
+    /// ready to broadcast. Any partial from a non-member or with a bad
+    /// signature is rejected; duplicates from one signer count once.
+    pub fn combine(&self, tx: &Transaction, partials: &[PartialSignature]) -> Result<Transaction> {
+        if tx.from != self.address() {
+            return Err(AxiomError::InvalidTransaction("transaction is not from this multisig".to_string()));
+        }
+        let message = tx.signing_message();
+        let mut signatures: Vec<(u8, Vec<u8>)> = Vec::new();
+        for partial in partials {
+            let index = self.member_index(&partial.signer).ok_or_else(|| {
+                AxiomError::InvalidSignature(format!("{} is not a multisig member", hex::encode(partial.signer)))
+            })?;
+            verify_member(&partial.signer, &message, &partial.signature)?;
+            if !signatures.iter().any(|(i, _)| *i == index) {
+                signatures.push((index, partial.signature.clone()));
+            }
+        }
+        if signatures.len() < self.threshold as usize {
+            return Err(AxiomError::InvalidSignature(format!(
+                "{} of {} required signatures",
+                signatures.len(),
+                self.threshold
+            )));
+        }
+        signatures.sort_by_key(|(i, _)| *i);
+        signatures.truncate(self.threshold as usize);
+
+        let mut signed = tx.clone();
+        signed.signature = MultisigSignature { wallet: self.clone(), signatures }.encode();
+        Ok(signed)
+    }
+}
+
+fn verify_member(key: &[u8; 32], message: &[u8], signature: &[u8]) -> Result<()> {
+    let verifying_key = VerifyingKey::from_bytes(key)
+        .map_err(|_| AxiomError::InvalidPublicKey(hex::encode(key)))?;
+    let bytes: [u8; 64] = signature
+        .try_into()
+        .map_err(|_| AxiomError::InvalidSignature(format!("expected 64 bytes, got {}", signature.len())))?;
+    verifying_key
+        .verify(message, &Signature::from_bytes(&bytes))
+        .map_err(|_| AxiomError::InvalidSignature(format!("bad signature from {}", hex::encode(key))))
+}
+
+impl MultisigSignature {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = MULTISIG_MAGIC.to_vec();
+        bytes.extend(bincode::serialize(self).expect("CRITICAL: multisig serialization failed"));
+        bytes
+    }
+
+    /// Parse a transaction signature field, if it holds a multisig set.
+    /// Single-key signatures are exactly 64 bytes and never match.
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() == 64 || !bytes.starts_with(MULTISIG_MAGIC) {
+            return None;
+        }
+        bincode::deserialize(&bytes[MULTISIG_MAGIC.len()..]).ok()
+    }
+
+    /// Check that this set authorizes spending from `address`: the policy
+    /// hashes to `address` and `threshold` distinct members signed `message`
+    pub fn verify(&self, address: &Address, message: &[u8]) -> Result<()> {
+        let wallet = MultisigWallet::new(self.wallet.threshold, self.wallet.public_keys.clone())?;
+        if wallet != self.wallet || &wallet.address() != address {
+            return Err(AxiomError::InvalidSignature("multisig policy does not match sender".to_string()));
+        }
+        let mut seen = Vec::with_capacity(self.signatures.len());
+        for (index, signature) in &self.signatures {
+            let key = wallet
+                .public_keys
+                .get(*index as usize)
+                .ok_or_else(|| AxiomError::InvalidSignature(format!("no member {}", index)))?;
+            if seen.contains(index) {
+                return Err(AxiomError::InvalidSignature(format!("member {} signed twice", index)));
+            }
+            verify_member(key, message, signature)?;
+            seen.push(*index);
+        }
+        if seen.len() < wallet.threshold as usize {
+            return Err(AxiomError::InvalidSignature(format!(
+                "{} of {} required signatures",
+                seen.len(),
+                wallet.threshold
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(seed: u8) -> Wallet {
+        let key = SigningKey::from_bytes(&[seed; 32]);
+        Wallet { secret_key: key.to_bytes(), address: key.verifying_key().to_bytes() }
+    }
+
+    fn two_of_three() -> (MultisigWallet, Vec<Wallet>) {
+        let members: Vec<Wallet> = (1..=3).map(member).collect();
+        let multisig = MultisigWallet::new(2, members.iter().map(|m| m.address).collect()).unwrap();
+        (multisig, members)
+    }
+
+    fn spend(multisig: &MultisigWallet) -> Transaction {
+        Transaction::new(multisig.address(), [9u8; 32], 500, 10, 0, vec![], vec![])
+    }
+
+    #[test]
+    fn test_two_of_three_spend() {
+        let (multisig, members) = two_of_three();
+        let tx = spend(&multisig);
+        let partials = vec![
+            multisig.partial_sign(&tx, &members[2]).unwrap(),
+            multisig.partial_sign(&tx, &members[0]).unwrap(),
+        ];
+        let signed = multisig.combine(&tx, &partials).unwrap();
+        assert!(signed.verify_signature().is_ok());
+
+        // The node authorizes it like any funded transaction
+        let mut chain = crate::chain::Timechain::new(crate::genesis::genesis());
+        chain.state.credit(multisig.address(), 1_000);
+        assert!(chain.check_transaction_authorization(&signed).is_ok());
+        assert!(chain.check_transaction_authorization(&tx).is_err());
+
+        // Signatures cover the whole transaction
+        let mut tampered = signed.clone();
+        tampered.amount = 501;
+        assert!(tampered.verify_signature().is_err());
+    }
+
+    #[test]
+    fn test_one_signer_is_not_enough() {
+        let (multisig, members) = two_of_three();
+        let tx = spend(&multisig);
+        let partial = multisig.partial_sign(&tx, &members[1]).unwrap();
+        assert!(matches!(multisig.combine(&tx, std::slice::from_ref(&partial)), Err(AxiomError::InvalidSignature(_))));
+        // The same signer twice still counts once
+        assert!(multisig.combine(&tx, &[partial.clone(), partial.clone()]).is_err());
+
+        // A hand-built set below threshold is rejected by validation too
+        let mut forged = tx.clone();
+        forged.signature = MultisigSignature {
+            wallet: multisig.clone(),
+            signatures: vec![(1, partial.signature.clone()), (1, partial.signature)],
+        }
+        .encode();
+        assert!(forged.verify_signature().is_err());
+    }
+
+    #[test]
+    fn test_non_member_partial_rejected() {
+        let (multisig, members) = two_of_three();
+        let outsider = member(42);
+        let tx = spend(&multisig);
+        assert!(multisig.partial_sign(&tx, &outsider).is_err());
+
+        let outsider_partial = PartialSignature {
+            signer: outsider.address,
+            signature: SigningKey::from_bytes(&outsider.secret_key).sign(&tx.signing_message()).to_bytes().to_vec(),
+        };
+        let member_partial = multisig.partial_sign(&tx, &members[0]).unwrap();
+        assert!(multisig.combine(&tx, &[member_partial, outsider_partial]).is_err());
+    }
+
+    #[test]
+    fn test_policy_must_match_sender() {
+        let (multisig, members) = two_of_three();
+        // Same keys in another order give the same address
+        let shuffled = MultisigWallet::new(2, members.iter().rev().map(|m| m.address).collect()).unwrap();
+        assert_eq!(shuffled.address(), multisig.address());
+
+        // A 1-of-3 policy over the same keys is a different address
+        let weaker = MultisigWallet::new(1, multisig.public_keys.clone()).unwrap();
+        let mut tx = spend(&weaker);
+        tx = weaker.combine(&tx, &[weaker.partial_sign(&tx, &members[0]).unwrap()]).unwrap();
+        tx.from = multisig.address();
+        assert!(tx.verify_signature().is_err());
+
+        assert!(MultisigWallet::new(4, multisig.public_keys.clone()).is_err());
+        assert!(MultisigWallet::new(0, multisig.public_keys.clone()).is_err());
+    }
+}
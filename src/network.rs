@@ -1,13 +1,20 @@
-use std::collections::HashSet;
-use libp2p::{gossipsub, mdns, kad, identify, swarm::{NetworkBehaviour, Swarm}, Multiaddr};
+use std::collections::{HashMap, HashSet};
+use libp2p::{gossipsub, mdns, kad, identify, swarm::{NetworkBehaviour, Swarm}, multiaddr::Protocol, Multiaddr, PeerId};
 use log;
 use std::error::Error;
-use libp2p::identity;
 use libp2p::request_response::{self, ProtocolSupport};
 use futures::io::{AsyncRead, AsyncWrite, AsyncReadExt, AsyncWriteExt};
 use std::io;
-use serde::{Serialize, Deserialize};
+use serde::{de::DeserializeOwned, Serialize, Deserialize};
 use crate::block::Block;
+use crate::config::NetworkConfig;
+use crate::error::AxiomError;
+use crate::merkle::MerkleProof;
+use crate::node_identity::NodeIdentity;
+use crate::snapshot::{SnapshotChunk, SnapshotManifest};
+use crate::transaction::Transaction;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use std::path::Path;
 
 /// External validator registry
 #[derive(Default)]
@@ -25,6 +32,101 @@ impl ValidatorRegistry {
     }
 }
 
+/// Network a peer connects from: its /24 for IPv4, its /48 for IPv6.
+/// Addresses an attacker can cheaply obtain in bulk share a subnet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Subnet {
+    V4([u8; 3]),
+    V6([u8; 6]),
+}
+
+impl Subnet {
+    /// Subnet of the first IP component of `addr`
+    pub fn of(addr: &Multiaddr) -> Option<Self> {
+        addr.iter().find_map(|protocol| match protocol {
+            Protocol::Ip4(ip) => Some(Subnet::V4(ip.octets()[..3].try_into().unwrap())),
+            Protocol::Ip6(ip) => Some(Subnet::V6(ip.octets()[..6].try_into().unwrap())),
+            _ => None,
+        })
+    }
+}
+
+/// Outcome of `PeerDiversity::admit`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Admission {
+    Accept,
+    /// Accepted in place of this peer from a more crowded subnet
+    Evict(PeerId),
+    Reject(&'static str),
+}
+
+/// Connection slots spread across subnets, so one network cannot fill
+/// them with Sybil peers and eclipse the node
+#[derive(Debug)]
+pub struct PeerDiversity {
+    max_peers: usize,
+    max_per_subnet: usize,
+    peers: HashMap<PeerId, Option<Subnet>>,
+}
+
+impl PeerDiversity {
+    pub fn new(max_peers: usize, max_per_subnet: usize) -> Self {
+        Self { max_peers, max_per_subnet, peers: HashMap::new() }
+    }
+
+    pub fn from_config(config: &NetworkConfig) -> Self {
+        Self::new(config.max_peers, config.max_peers_per_subnet)
+    }
+
+    /// Decide whether `peer`, connecting from `addr`, gets a slot. Peers
+    /// over the per-subnet cap are rejected. When all slots are taken, a
+    /// peer from a subnet with fewer peers than the most crowded one
+    /// replaces a peer from it.
+    pub fn admit(&mut self, peer: PeerId, addr: &Multiaddr) -> Admission {
+        if self.peers.contains_key(&peer) {
+            return Admission::Accept;
+        }
+        let subnet = Subnet::of(addr);
+        let same_subnet = subnet.map_or(0, |s| self.count(s));
+        if same_subnet >= self.max_per_subnet {
+            return Admission::Reject("subnet peer limit reached");
+        }
+        if self.peers.len() < self.max_peers {
+            self.peers.insert(peer, subnet);
+            return Admission::Accept;
+        }
+        let crowded = self.peers.values().flatten().copied().max_by_key(|s| self.count(*s));
+        match crowded {
+            Some(crowded) if self.count(crowded) > same_subnet + 1 => {
+                let victim = *self.peers.iter().find(|(_, s)| **s == Some(crowded)).unwrap().0;
+                self.peers.remove(&victim);
+                self.peers.insert(peer, subnet);
+                Admission::Evict(victim)
+            }
+            _ => Admission::Reject("peer limit reached"),
+        }
+    }
+
+    pub fn remove(&mut self, peer: &PeerId) {
+        self.peers.remove(peer);
+    }
+
+    pub fn count(&self, subnet: Subnet) -> usize {
+        self.peers.values().filter(|s| **s == Some(subnet)).count()
+    }
+
+    /// Share of peers in the most crowded subnet (0.0 with no peers); the
+    /// Neural Guardian's eclipse signal
+    pub fn subnet_concentration(&self) -> f32 {
+        let largest = self.peers.values().flatten().map(|s| self.count(*s)).max().unwrap_or(0);
+        if self.peers.is_empty() {
+            0.0
+        } else {
+            largest as f32 / self.peers.len() as f32
+        }
+    }
+}
+
 /// Add external peer to the network
 pub fn add_external_peer(swarm: &mut Swarm<TimechainBehaviour>, peer_addr: &str, peer_id: &str) {
     if let Ok(addr) = peer_addr.parse() {
@@ -40,11 +142,139 @@ pub fn add_external_peer(swarm: &mut Swarm<TimechainBehaviour>, peer_addr: &str,
     }
 }
 
+/// Version of the gossip encoding. Every gossiped block, compact block,
+/// transaction or chain goes out as this byte followed by its bincode
+/// encoding; bump it whenever one of those layouts changes.
+pub const GOSSIP_VERSION: u8 = 2;
+
+/// `protocol_version` this node advertises over identify, carrying
+/// `GOSSIP_VERSION` so peers learn it before exchanging gossip
+pub fn identify_protocol_version() -> String {
+    format!("axiom/1.0.0/gossip-{}", GOSSIP_VERSION)
+}
+
+/// Gossip version a peer advertised over identify; `None` for peers from
+/// before gossip was versioned
+pub fn peer_gossip_version(protocol_version: &str) -> Option<u8> {
+    protocol_version.rsplit_once("/gossip-")?.1.parse().ok()
+}
+
+/// Whether a peer advertising `protocol_version` can exchange gossip with us
+pub fn is_gossip_compatible(protocol_version: &str) -> bool {
+    peer_gossip_version(protocol_version) == Some(GOSSIP_VERSION)
+}
+
+/// `message` framed for gossip: `GOSSIP_VERSION`, then bincode
+pub fn encode_gossip<T: Serialize>(message: &T) -> Vec<u8> {
+    let mut data = vec![GOSSIP_VERSION];
+    data.extend(bincode::serialize(message).unwrap_or_default());
+    data
+}
+
+/// Decode a gossip payload, refusing versions other than ours
+pub fn decode_gossip<T: DeserializeOwned>(data: &[u8]) -> Result<T, AxiomError> {
+    let (&version, payload) = data
+        .split_first()
+        .ok_or_else(|| AxiomError::GossipError("empty message".to_string()))?;
+    if version != GOSSIP_VERSION {
+        return Err(AxiomError::GossipError(format!(
+            "unsupported gossip version {} (this node speaks {})",
+            version, GOSSIP_VERSION
+        )));
+    }
+    bincode::deserialize(payload).map_err(|e| AxiomError::GossipError(format!("malformed message: {}", e)))
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChainRequest {
+    pub start_height: u64,
+    /// Set to ask instead for transactions of a compact block being rebuilt.
+    /// Always encoded (as `null` when unset) so the message also survives
+    /// bincode; `default` still accepts peers that omit it.
+    #[serde(default)]
+    pub block_transactions: Option<BlockTransactionsRequest>,
+    /// Set to ask instead for the peer's state snapshot (snap sync)
+    #[serde(default)]
+    pub snapshot: Option<SnapshotRequest>,
+}
+
+impl ChainRequest {
+    /// Blocks from `start_height` on
+    pub fn blocks_from(start_height: u64) -> Self {
+        Self { start_height, block_transactions: None, snapshot: None }
+    }
+
+    /// Non-coinbase transactions at `indexes` of the block with
+    /// `merkle_root`; a compact block's hash is unknown until it is rebuilt
+    pub fn transactions(merkle_root: [u8; 32], indexes: Vec<u32>) -> Self {
+        Self {
+            start_height: 0,
+            block_transactions: Some(BlockTransactionsRequest { merkle_root, indexes }),
+            snapshot: None,
+        }
+    }
+
+    /// Part of the peer's latest state snapshot
+    pub fn snapshot(wanted: SnapshotRequest) -> Self {
+        Self { start_height: 0, block_transactions: None, snapshot: Some(wanted) }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct ChainRequest { pub start_height: u64 }
+pub struct BlockTransactionsRequest {
+    pub merkle_root: [u8; 32],
+    pub indexes: Vec<u32>,
+}
+
+/// What a snap-syncing node asks for: the manifest first, then each chunk
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SnapshotRequest {
+    Manifest,
+    Chunk { index: u32 },
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct ChainResponse { pub blocks: Vec<Block> }
+pub struct ChainResponse {
+    pub blocks: Vec<Block>,
+    /// Answer to `ChainRequest::block_transactions`
+    #[serde(default)]
+    pub block_transactions: Option<BlockTransactions>,
+    /// Answer to `ChainRequest::snapshot`; `None` if the peer has no snapshot
+    #[serde(default)]
+    pub snapshot: Option<SnapshotResponse>,
+    /// Height of the sender's tip, set on block batches so the requester
+    /// knows whether to ask for the next range
+    #[serde(default)]
+    pub tip_height: Option<u64>,
+}
+
+impl ChainResponse {
+    pub fn blocks(blocks: Vec<Block>) -> Self {
+        Self { blocks, block_transactions: None, snapshot: None, tip_height: None }
+    }
+
+    /// At most `max_blocks` of `chain` from `start_height` on, with the
+    /// height of its tip
+    pub fn batch(chain: &[Block], start_height: u64, max_blocks: usize) -> Self {
+        let start = (start_height as usize).min(chain.len());
+        let end = start.saturating_add(max_blocks).min(chain.len());
+        Self { tip_height: chain.last().map(|tip| tip.slot), ..Self::blocks(chain[start..end].to_vec()) }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SnapshotResponse {
+    Manifest(SnapshotManifest),
+    /// A chunk with its proof against the manifest's chunk root
+    Chunk { chunk: SnapshotChunk, proof: MerkleProof },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BlockTransactions {
+    pub merkle_root: [u8; 32],
+    /// In the order requested
+    pub transactions: Vec<Transaction>,
+}
 
 #[derive(Clone, Default)]
 pub struct ChainCodec;
@@ -133,16 +363,89 @@ const DEFAULT_BOOTSTRAP_PEERS: &[&str] = &[
     "/ip4/3.8.120.113/tcp/6000",    // Example: AWS EC2
 ];
 
+/// Bootstrap manifest read at startup, signed by the network key
+pub const BOOTSTRAP_MANIFEST_PATH: &str = "config/bootstrap.toml";
+
+/// Ed25519 key that signs the mainnet bootstrap manifest. Until the
+/// network key is published no manifest is trusted, and nodes dial
+/// `AXIOM_BOOTSTRAP_PEERS` or the compiled-in defaults.
+pub const BOOTSTRAP_MANIFEST_KEY: Option<[u8; 32]> = None;
+
+/// Peer list signed by the network key, so a tampered config cannot point
+/// a node at attacker-controlled peers
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BootstrapManifest {
+    pub bootnodes: Vec<String>,
+    /// Hex Ed25519 signature over the domain separator and `bootnodes`
+    #[serde(default)]
+    pub signature: String,
+}
+
+impl BootstrapManifest {
+    pub fn sign(bootnodes: Vec<String>, key: &SigningKey) -> Self {
+        let signature = key.sign(&Self::signing_message(&bootnodes));
+        Self { bootnodes, signature: hex::encode(signature.to_bytes()) }
+    }
+
+    pub fn verify(&self, pubkey: &[u8; 32]) -> Result<(), AxiomError> {
+        let key = VerifyingKey::from_bytes(pubkey).map_err(|e| AxiomError::InvalidPublicKey(e.to_string()))?;
+        if self.signature.is_empty() {
+            return Err(AxiomError::InvalidSignature("bootstrap manifest is not signed".to_string()));
+        }
+        let signature = hex::decode(&self.signature)
+            .ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok())
+            .ok_or_else(|| AxiomError::InvalidSignature("malformed bootstrap manifest signature".to_string()))?;
+        key.verify(&Self::signing_message(&self.bootnodes), &signature)
+            .map_err(|_| AxiomError::InvalidSignature("bootstrap manifest signature does not match".to_string()))
+    }
+
+    fn signing_message(bootnodes: &[String]) -> Vec<u8> {
+        let mut message = crate::hashing::BOOTSTRAP_MANIFEST.to_vec();
+        message.extend(bincode::serialize(bootnodes).expect("peer list serializes"));
+        message
+    }
+}
+
+/// Peers from the manifest at `path`, only if it is signed by `pubkey`
+pub fn load_signed_bootstrap(path: &Path, pubkey: &[u8; 32]) -> Result<Vec<String>, AxiomError> {
+    let manifest: BootstrapManifest = toml::from_str(&std::fs::read_to_string(path)?)?;
+    manifest.verify(pubkey)?;
+    Ok(manifest.bootnodes)
+}
+
+/// Peers to dial at startup: `AXIOM_BOOTSTRAP_PEERS` when set by the
+/// operator, else the signed manifest, else `DEFAULT_BOOTSTRAP_PEERS`. An
+/// unsigned or badly signed manifest is ignored.
+pub fn bootstrap_peers() -> Vec<String> {
+    let from_env: Vec<String> = std::env::var("AXIOM_BOOTSTRAP_PEERS")
+        .unwrap_or_default()
+        .split(',')
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| s.trim().to_string())
+        .collect();
+    if !from_env.is_empty() {
+        return from_env;
+    }
+    if let Some(pubkey) = BOOTSTRAP_MANIFEST_KEY {
+        match load_signed_bootstrap(Path::new(BOOTSTRAP_MANIFEST_PATH), &pubkey) {
+            Ok(peers) => return peers,
+            Err(e) => log::warn!("Ignoring {}: {}", BOOTSTRAP_MANIFEST_PATH, e),
+        }
+    }
+    DEFAULT_BOOTSTRAP_PEERS.iter().map(|s| s.to_string()).collect()
+}
+
 pub async fn init_network() -> Result<Swarm<TimechainBehaviour>, Box<dyn Error + Send + Sync>> {
-    let peers = DEFAULT_BOOTSTRAP_PEERS.iter().map(|s| s.to_string()).collect();
-    init_network_with_bootstrap(peers).await
+    init_network_with_bootstrap(bootstrap_peers()).await
 }
 
 /// Initialize network with optional bootstrap peers
 /// Initialize network with advanced security: peer authentication, encrypted channels, rate limiting, and robust bootstrap logic.
 pub async fn init_network_with_bootstrap(bootstrap_peers: Vec<String>) -> Result<Swarm<TimechainBehaviour>, Box<dyn Error + Send + Sync>> {
-    // Use Ed25519 for strong peer identity
-    let local_key = identity::Keypair::generate_ed25519();
+    // Use Ed25519 for strong peer identity, persisted so the PeerId survives restarts
+    let node_identity = NodeIdentity::default();
+    let local_key = node_identity.load_or_create()?;
     let peer_id = local_key.public().to_peer_id();
     
     // Configure Yamux with longer idle timeout to prevent disconnects
@@ -164,7 +467,7 @@ pub async fn init_network_with_bootstrap(bootstrap_peers: Vec<String>) -> Result
                 )?,
                 mdns: mdns::tokio::Behaviour::new(mdns::Config::default(), key.public().to_peer_id())?,
                 kademlia: kad::Behaviour::new(key.public().to_peer_id(), kad::store::MemoryStore::new(key.public().to_peer_id())),
-                identify: identify::Behaviour::new(identify::Config::new("axiom/1.0.0".into(), key.public())),
+                identify: identify::Behaviour::new(identify::Config::new(identify_protocol_version(), key.public())),
                 request_response: {
                     // Support multiple protocol versions for compatibility
                     request_response::Behaviour::new(
@@ -196,56 +499,39 @@ pub async fn init_network_with_bootstrap(bootstrap_peers: Vec<String>) -> Result
     if added == 0 {
         log::warn!("No valid bootstrap peers added. Node will rely on mDNS/local discovery.");
     }
+
+    // Point peers still looking up a retired identity at its successor
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    for notice in node_identity.active_rotations(now) {
+        let (Ok(old_peer), Ok(value)) = (notice.old_peer_id(), bincode::serialize(&notice)) else {
+            continue;
+        };
+        let record = kad::Record::new(old_peer.to_bytes(), value);
+        if let Err(e) = swarm.behaviour_mut().kademlia.put_record(record, kad::Quorum::One) {
+            log::warn!("Failed to publish rotation notice for {}: {:?}", old_peer, e);
+        }
+    }
     Ok(swarm)
 }
 
-/// Utility: Check connectivity to bootstrap nodes from config or environment (non-blocking)
+/// Utility: Check connectivity to the `bootstrap_peers()` (non-blocking)
 pub fn check_bootstrap_connectivity() {
     println!("🔍 Checking bootstrap connectivity...");
     // Spawn async checks to avoid blocking main thread
     tokio::spawn(async {
         let mut nodes_to_check = Vec::new();
         
-        // First, try environment variable for dynamic bootstrap peers
-        let env_bootstrap_peers: Vec<String> = std::env::var("AXIOM_BOOTSTRAP_PEERS")
-            .unwrap_or_default()
-            .split(',')
-            .filter(|s| !s.trim().is_empty())
-            .map(|s| s.trim().to_string())
-            .collect();
-        
-        if !env_bootstrap_peers.is_empty() {
-            for addr_str in &env_bootstrap_peers {
-                // Parse multiaddr to extract IP and port
-                if let Some(ip_part) = addr_str.split("/ip4/").nth(1) {
-                    if let Some(ip) = ip_part.split('/').next() {
-                        if let Some(port_part) = addr_str.split("/tcp/").nth(1) {
-                            if let Some(port_str) = port_part.split('/').next() {
-                                if let Ok(port) = port_str.parse::<u16>() {
-                                    nodes_to_check.push((ip.to_string(), port));
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        } else if let Ok(bootstrap_content) = std::fs::read_to_string("config/bootstrap.toml") {
-            // Fall back to config file
-            if let Ok(bootstrap_config) = toml::from_str::<toml::Value>(&bootstrap_content) {
-                if let Some(bootnodes) = bootstrap_config.get("bootnodes").and_then(|v| v.as_array()) {
-                    for bootnode in bootnodes {
-                        if let Some(addr_str) = bootnode.as_str() {
-                            // Parse multiaddr to extract IP and port
-                            if let Some(ip_part) = addr_str.split("/ip4/").nth(1) {
-                                if let Some(ip) = ip_part.split('/').next() {
-                                    if let Some(port_part) = addr_str.split("/tcp/").nth(1) {
-                                        if let Some(port_str) = port_part.split('/').next() {
-                                            if let Ok(port) = port_str.parse::<u16>() {
-                                                nodes_to_check.push((ip.to_string(), port));
-                                            }
-                                        }
-                                    }
-                                }
+        for addr_str in bootstrap_peers() {
+            // Parse multiaddr to extract IP and port
+            if let Some(ip_part) = addr_str.split("/ip4/").nth(1) {
+                if let Some(ip) = ip_part.split('/').next() {
+                    if let Some(port_part) = addr_str.split("/tcp/").nth(1) {
+                        if let Some(port_str) = port_part.split('/').next() {
+                            if let Ok(port) = port_str.parse::<u16>() {
+                                nodes_to_check.push((ip.to_string(), port));
                             }
                         }
                     }
@@ -271,3 +557,178 @@ pub fn check_bootstrap_connectivity() {
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("axiom_bootstrap_{}_{}.toml", name, std::process::id()))
+    }
+
+    fn peers() -> Vec<String> {
+        vec![
+            "/ip4/10.0.0.1/tcp/6000/p2p/12D3KooWAzD3QjhHMamey1XuysPovzwXyAZy9VzpZmQN7GkrURWU".to_string(),
+            "/ip4/10.0.0.2/tcp/6000".to_string(),
+        ]
+    }
+
+    fn addr(ip: &str) -> Multiaddr {
+        format!("/ip4/{}/tcp/6000", ip).parse().unwrap()
+    }
+
+    #[test]
+    fn test_gossip_versioning() {
+        let tx = Transaction::new([1u8; 32], [2u8; 32], 100, 1, 0, vec![], vec![]);
+
+        // Same version: the payload is the old bincode encoding behind one byte
+        let data = encode_gossip(&tx);
+        assert_eq!(data[0], GOSSIP_VERSION);
+        assert_eq!(data[1..], bincode::serialize(&tx).unwrap()[..]);
+        assert_eq!(decode_gossip::<Transaction>(&data).unwrap(), tx);
+
+        // A future version is refused with a reason, not misread
+        let mut future = data.clone();
+        future[0] = GOSSIP_VERSION + 1;
+        match decode_gossip::<Transaction>(&future) {
+            Err(AxiomError::GossipError(reason)) => assert!(reason.contains(&format!("unsupported gossip version {}", GOSSIP_VERSION + 1))),
+            other => panic!("expected a version error, got {:?}", other),
+        }
+        assert!(decode_gossip::<Transaction>(&[]).is_err());
+        assert!(decode_gossip::<Transaction>(&data[..20]).is_err());
+
+        // Capabilities from identify
+        assert!(is_gossip_compatible(&identify_protocol_version()));
+        assert_eq!(peer_gossip_version("axiom/1.0.0/gossip-7"), Some(7));
+        assert!(!is_gossip_compatible("axiom/1.0.0/gossip-7"));
+        assert_eq!(peer_gossip_version("axiom/1.0.0"), None);
+        assert!(!is_gossip_compatible("axiom/1.0.0"));
+    }
+
+    #[test]
+    fn test_chain_messages_survive_both_encodings() {
+        let tx = Transaction::new([1u8; 32], [2u8; 32], 100, 1, 0, vec![7; 16], vec![8; 64]);
+        let block = Block::new([0u8; 32], 3, [4u8; 32], vec![tx.clone()], [5u8; 32], vec![6; 8], 9);
+        let requests = [
+            ChainRequest::blocks_from(12),
+            ChainRequest::transactions([3u8; 32], vec![0, 2]),
+            ChainRequest::snapshot(SnapshotRequest::Manifest),
+            ChainRequest::snapshot(SnapshotRequest::Chunk { index: 4 }),
+        ];
+        let state = {
+            let mut state = crate::state::State::new();
+            state.credit([1u8; 32], 500);
+            state
+        };
+        let key = SigningKey::from_bytes(&[5u8; 32]);
+        let checkpoint = crate::storage::Checkpoint::sign(9, [1u8; 32], state.state_root(), 10, &key);
+        let snapshot = crate::snapshot::Snapshot::new(checkpoint, &state);
+        let (chunk, proof) = snapshot.chunk(0).unwrap();
+        let responses = [
+            ChainResponse::blocks(vec![block.clone()]),
+            ChainResponse {
+                blocks: vec![],
+                block_transactions: Some(BlockTransactions { merkle_root: [3u8; 32], transactions: vec![tx] }),
+                snapshot: None,
+                tip_height: None,
+            },
+            ChainResponse::batch(std::slice::from_ref(&block), 0, 10),
+            ChainResponse { snapshot: Some(SnapshotResponse::Manifest(snapshot.manifest.clone())), ..ChainResponse::blocks(vec![]) },
+            ChainResponse { snapshot: Some(SnapshotResponse::Chunk { chunk, proof }), ..ChainResponse::blocks(vec![]) },
+        ];
+
+        // Compare through JSON, the form the sync protocol sends
+        for request in &requests {
+            let json = serde_json::to_string(request).unwrap();
+            let via_bincode: ChainRequest = bincode::deserialize(&bincode::serialize(request).unwrap()).unwrap();
+            assert_eq!(serde_json::to_string(&via_bincode).unwrap(), json);
+            let via_json: ChainRequest = serde_json::from_str(&json).unwrap();
+            assert_eq!(serde_json::to_string(&via_json).unwrap(), json);
+        }
+        for response in &responses {
+            let json = serde_json::to_string(response).unwrap();
+            let via_bincode: ChainResponse = bincode::deserialize(&bincode::serialize(response).unwrap()).unwrap();
+            assert_eq!(serde_json::to_string(&via_bincode).unwrap(), json);
+        }
+
+        // Peers that predate compact blocks omit the fields entirely
+        let old: ChainRequest = serde_json::from_str(r#"{"start_height":5}"#).unwrap();
+        assert!(old.block_transactions.is_none() && old.snapshot.is_none());
+        let old: ChainResponse = serde_json::from_str(r#"{"blocks":[]}"#).unwrap();
+        assert!(old.block_transactions.is_none() && old.snapshot.is_none() && old.tip_height.is_none());
+    }
+
+    #[test]
+    fn test_subnet_cap_rejects_excess_peers() {
+        let mut diversity = PeerDiversity::new(50, 2);
+        assert_eq!(diversity.admit(PeerId::random(), &addr("203.0.113.1")), Admission::Accept);
+        assert_eq!(diversity.admit(PeerId::random(), &addr("203.0.113.2")), Admission::Accept);
+        // A third peer from the same /24 is turned away
+        assert!(matches!(diversity.admit(PeerId::random(), &addr("203.0.113.3")), Admission::Reject(_)));
+        assert_eq!(diversity.count(Subnet::of(&addr("203.0.113.9")).unwrap()), 2);
+
+        // Peers from other subnets still get in
+        assert_eq!(diversity.admit(PeerId::random(), &addr("198.51.100.1")), Admission::Accept);
+        assert_eq!(diversity.admit(PeerId::random(), &addr("192.0.2.1")), Admission::Accept);
+        let v6: Multiaddr = "/ip6/2001:db8:1::1/tcp/6000".parse().unwrap();
+        assert_eq!(diversity.admit(PeerId::random(), &v6), Admission::Accept);
+        assert_eq!(Subnet::of(&"/ip6/2001:db8:1:ffff::2/tcp/1".parse().unwrap()), Subnet::of(&v6));
+        assert_eq!(diversity.subnet_concentration(), 2.0 / 5.0);
+    }
+
+    #[test]
+    fn test_diverse_peer_replaces_crowded_subnet_at_capacity() {
+        let mut diversity = PeerDiversity::new(3, 3);
+        let crowded: Vec<PeerId> = (0..3).map(|_| PeerId::random()).collect();
+        for (i, peer) in crowded.iter().enumerate() {
+            assert_eq!(diversity.admit(*peer, &addr(&format!("203.0.113.{}", i + 1))), Admission::Accept);
+        }
+        assert_eq!(diversity.subnet_concentration(), 1.0);
+
+        // Full: a peer from a new subnet takes a slot from the crowded one
+        match diversity.admit(PeerId::random(), &addr("198.51.100.1")) {
+            Admission::Evict(victim) => assert!(crowded.contains(&victim)),
+            other => panic!("expected eviction, got {:?}", other),
+        }
+        match diversity.admit(PeerId::random(), &addr("192.0.2.1")) {
+            Admission::Evict(victim) => assert!(crowded.contains(&victim)),
+            other => panic!("expected eviction, got {:?}", other),
+        }
+        // Now every subnet has one peer; nothing more to gain
+        assert!(matches!(diversity.admit(PeerId::random(), &addr("100.64.0.1")), Admission::Reject(_)));
+    }
+
+    #[test]
+    fn test_signed_bootstrap_accepted() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let path = manifest_path("valid");
+        let manifest = BootstrapManifest::sign(peers(), &key);
+        std::fs::write(&path, toml::to_string(&manifest).unwrap()).unwrap();
+
+        let loaded = load_signed_bootstrap(&path, &key.verifying_key().to_bytes()).unwrap();
+        assert_eq!(loaded, peers());
+
+        // Signed by someone else
+        let other = SigningKey::from_bytes(&[8u8; 32]).verifying_key().to_bytes();
+        assert!(matches!(load_signed_bootstrap(&path, &other), Err(AxiomError::InvalidSignature(_))));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_tampered_bootstrap_rejected() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let pubkey = key.verifying_key().to_bytes();
+        let path = manifest_path("tampered");
+
+        // An attacker swaps in their own peer but keeps the signature
+        let mut manifest = BootstrapManifest::sign(peers(), &key);
+        manifest.bootnodes[1] = "/ip4/6.6.6.6/tcp/6000".to_string();
+        std::fs::write(&path, toml::to_string(&manifest).unwrap()).unwrap();
+        assert!(matches!(load_signed_bootstrap(&path, &pubkey), Err(AxiomError::InvalidSignature(_))));
+
+        // An unsigned list, as in an unmodified config/bootstrap.toml
+        std::fs::write(&path, "bootnodes = [\"/ip4/6.6.6.6/tcp/6000\"]\n").unwrap();
+        assert!(matches!(load_signed_bootstrap(&path, &pubkey), Err(AxiomError::InvalidSignature(_))));
+        let _ = std::fs::remove_file(&path);
+    }
+}
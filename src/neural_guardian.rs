@@ -21,6 +21,8 @@ pub struct NetworkEvent {
     pub reorg_depth: f32,         // Reorganization depth
     pub bandwidth_usage: f32,     // Network bandwidth (KB/s)
     pub connection_churn: f32,    // Peer connect/disconnect rate
+    #[serde(default)]
+    pub subnet_concentration: f32, // Share of peers in the most crowded subnet
     pub timestamp: u64,
 }
 
@@ -60,8 +62,8 @@ pub enum Action {
 /// Simple neural network for threat detection
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NeuralNetwork {
-    // Input layer (10 features) -> Hidden layer (64) -> Output layer (6 threat types)
-    weights_input_hidden: Vec<Vec<f32>>,  // 10x64
+    // Input layer (11 features) -> Hidden layer (64) -> Output layer (6 threat types)
+    weights_input_hidden: Vec<Vec<f32>>,  // 11x64
     bias_hidden: Vec<f32>,                // 64
     weights_hidden_output: Vec<Vec<f32>>, // 64x6
     bias_output: Vec<f32>,                // 6
@@ -73,7 +75,7 @@ impl NeuralNetwork {
         use rand::Rng;
         let mut rng = rand::thread_rng();
         
-        let input_size = 10;
+        let input_size = 11;
         let hidden_size = 64;
         let output_size = 6; // 6 threat types (including Benign)
         
@@ -167,6 +169,9 @@ pub struct ModelUpdate {
     pub timestamp: u64,
 }
 
+/// `subnet_concentration` at which a peer set is flagged as an eclipse
+pub const ECLIPSE_SUBNET_CONCENTRATION: f32 = 0.5;
+
 /// Neural Guardian with federated learning
 pub struct NeuralGuardian {
     model: NeuralNetwork,
@@ -204,6 +209,7 @@ impl NeuralGuardian {
             normalize_depth(event.reorg_depth),
             normalize_size(event.bandwidth_usage),
             normalize_rate(event.connection_churn),
+            event.subnet_concentration.clamp(0.0, 1.0),
         ]
     }
     
@@ -242,7 +248,8 @@ impl NeuralGuardian {
         if sybil_prob > 0.8 {
             threats.push(ThreatType::SybilAttack);
         }
-        if eclipse_prob > 0.6 {
+        // Most peers on one subnet is an eclipse whatever the model says
+        if eclipse_prob > 0.6 || recent_event.subnet_concentration >= ECLIPSE_SUBNET_CONCENTRATION {
             threats.push(ThreatType::EclipseAttack);
         }
         if dos_prob > 0.7 {
@@ -445,7 +452,7 @@ mod tests {
     #[test]
     fn test_neural_network_forward() {
         let nn = NeuralNetwork::new();
-        let input = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0];
+        let input = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0, 0.5];
         let output = nn.forward(&input);
         
         assert_eq!(output.len(), 6);
@@ -475,6 +482,7 @@ mod tests {
             reorg_depth: 0.0,
             bandwidth_usage: 100.0,
             connection_churn: 0.5,
+            subnet_concentration: 0.1,
             timestamp: current_timestamp(),
         };
         
@@ -504,11 +512,12 @@ mod tests {
             reorg_depth: 2.0,
             bandwidth_usage: 256.0,
             connection_churn: 1.0,
+            subnet_concentration: 0.2,
             timestamp: current_timestamp(),
         };
         
         let features = guardian.extract_features(&event);
-        assert_eq!(features.len(), 10);
+        assert_eq!(features.len(), 11);
         
         // All features should be normalized between 0 and 1
         for &f in &features {
@@ -533,6 +542,7 @@ mod tests {
             reorg_depth: 0.0,
             bandwidth_usage: 100.0,
             connection_churn: 0.5,
+            subnet_concentration: 0.1,
             timestamp: current_timestamp(),
         };
         
@@ -544,6 +554,31 @@ mod tests {
         assert_eq!(update.num_samples, 1);
     }
     
+    #[test]
+    fn test_subnet_concentration_flags_eclipse() {
+        let mut guardian = NeuralGuardian::new();
+        let event = NetworkEvent {
+            peer_id: "peer1".to_string(),
+            block_interval: 3600.0,
+            block_size: 500.0,
+            tx_count: 50.0,
+            propagation_time: 100.0,
+            peer_count: 10.0,
+            fork_count: 0.0,
+            orphan_rate: 0.0,
+            reorg_depth: 0.0,
+            bandwidth_usage: 100.0,
+            connection_churn: 0.5,
+            subnet_concentration: 0.8,
+            timestamp: current_timestamp(),
+        };
+        assert_eq!(guardian.extract_features(&event)[10], 0.8);
+
+        guardian.record_event("peer1".to_string(), event);
+        let assessment = guardian.analyze_peer("peer1").unwrap();
+        assert!(assessment.detected_threats.contains(&ThreatType::EclipseAttack));
+    }
+
     #[test]
     fn test_action_determination() {
         assert_eq!(determine_action(&[]), Action::None);
@@ -0,0 +1,233 @@
+// src/node_identity.rs - Persistent libp2p identity
+//
+// The node's Ed25519 peer key is kept in NODE_KEY_FILE, stored exactly like
+// a wallet (encrypted under AXIOM_WALLET_PASSWORD when it is set), so the
+// PeerId survives restarts and peers' reputation records and bootstrap
+// whitelists keep recognizing the node.
+//
+// `rotate` retires the key on request: the old key file is archived as
+// `node_key.<old peer id>.dat` and a RotationNotice, signed by the old key,
+// names its successor. For ROTATION_GRACE_SECS the node publishes each
+// notice on the DHT under the old PeerId, so peers still looking for the
+// old identity find the new one.
+
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use libp2p::{identity, PeerId};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{AxiomError, Result as AxiomResult};
+use crate::hashing;
+use crate::wallet::Wallet;
+
+/// Current peer key, relative to the identity directory
+pub const NODE_KEY_FILE: &str = "node_key.dat";
+
+/// Signed notices of past rotations
+pub const ROTATIONS_FILE: &str = "node_key_rotations.dat";
+
+/// How long a retired identity keeps pointing at its successor: one week
+pub const ROTATION_GRACE_SECS: u64 = 7 * 24 * 3600;
+
+/// Statement by a retired peer key that `new_key` replaced it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RotationNotice {
+    pub old_key: [u8; 32],
+    pub new_key: [u8; 32],
+    /// Unix seconds
+    pub rotated_at: u64,
+    /// Ed25519 signature by `old_key`
+    pub signature: Vec<u8>,
+}
+
+impl RotationNotice {
+    fn sign(old: &Wallet, new_key: [u8; 32], rotated_at: u64) -> Self {
+        let signature = old.sign_message(&Self::signing_message(&new_key, rotated_at));
+        Self { old_key: old.address, new_key, rotated_at, signature }
+    }
+
+    fn signing_message(new_key: &[u8; 32], rotated_at: u64) -> Vec<u8> {
+        let mut message = hashing::IDENTITY_ROTATION.to_vec();
+        message.extend_from_slice(new_key);
+        message.extend_from_slice(&rotated_at.to_le_bytes());
+        message
+    }
+
+    /// Whether the notice is signed by the key it retires
+    pub fn verify(&self) -> bool {
+        let Ok(key) = VerifyingKey::from_bytes(&self.old_key) else {
+            return false;
+        };
+        let Ok(signature) = Signature::from_slice(&self.signature) else {
+            return false;
+        };
+        key.verify(&Self::signing_message(&self.new_key, self.rotated_at), &signature).is_ok()
+    }
+
+    pub fn old_peer_id(&self) -> AxiomResult<PeerId> {
+        peer_id_of(&self.old_key)
+    }
+
+    pub fn new_peer_id(&self) -> AxiomResult<PeerId> {
+        peer_id_of(&self.new_key)
+    }
+
+    /// Whether the old identity should still be published at `now`
+    pub fn in_grace_period(&self, now: u64) -> bool {
+        now < self.rotated_at.saturating_add(ROTATION_GRACE_SECS)
+    }
+}
+
+/// PeerId of the Ed25519 public key `key`
+pub fn peer_id_of(key: &[u8; 32]) -> AxiomResult<PeerId> {
+    let public = identity::ed25519::PublicKey::try_from_bytes(key)
+        .map_err(|e| AxiomError::InvalidPublicKey(e.to_string()))?;
+    Ok(identity::PublicKey::from(public).to_peer_id())
+}
+
+fn keypair_of(wallet: &Wallet) -> AxiomResult<identity::Keypair> {
+    identity::Keypair::ed25519_from_bytes(wallet.secret_key).map_err(|e| AxiomError::KeystoreError(e.to_string()))
+}
+
+/// The peer key and rotation history kept in one directory
+pub struct NodeIdentity {
+    dir: PathBuf,
+}
+
+impl NodeIdentity {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn key_path(&self) -> PathBuf {
+        self.dir.join(NODE_KEY_FILE)
+    }
+
+    /// Where `rotate` archives the key of `peer_id`
+    pub fn archive_path(&self, peer_id: &PeerId) -> PathBuf {
+        self.dir.join(format!("node_key.{}.dat", peer_id))
+    }
+
+    /// The persisted peer key, generating and storing one on first start
+    pub fn load_or_create(&self) -> AxiomResult<identity::Keypair> {
+        let path = self.key_path();
+        let key = if path.exists() {
+            Wallet::open(&path)?
+        } else {
+            let key = Wallet::generate();
+            key.store(&path)?;
+            key
+        };
+        keypair_of(&key)
+    }
+
+    /// Replace the peer key with a fresh one, archiving the old key and
+    /// recording a notice it signs. The node must restart to use the new
+    /// identity.
+    pub fn rotate(&self, now: u64) -> AxiomResult<RotationNotice> {
+        let path = self.key_path();
+        let old = Wallet::open(&path)?;
+        let new = Wallet::generate();
+        let notice = RotationNotice::sign(&old, new.address, now);
+
+        // The archived file keeps the old key's encryption
+        fs::rename(&path, self.archive_path(&notice.old_peer_id()?))?;
+        new.store(&path)?;
+
+        let mut notices = self.rotations();
+        notices.push(notice.clone());
+        let encoded = bincode::serialize(&notices)?;
+        let rotations_path = self.dir.join(ROTATIONS_FILE);
+        let temp_path = rotations_path.with_extension("tmp");
+        fs::write(&temp_path, encoded)?;
+        fs::rename(temp_path, rotations_path)?;
+        Ok(notice)
+    }
+
+    /// Every recorded rotation, oldest first; none if the file is missing
+    /// or corrupt
+    pub fn rotations(&self) -> Vec<RotationNotice> {
+        load_rotations(&self.dir.join(ROTATIONS_FILE))
+    }
+
+    /// Validly signed rotations still within their grace period at `now`
+    pub fn active_rotations(&self, now: u64) -> Vec<RotationNotice> {
+        self.rotations().into_iter().filter(|n| n.in_grace_period(now) && n.verify()).collect()
+    }
+}
+
+impl Default for NodeIdentity {
+    /// Identity kept in the working directory, next to the wallet and chain
+    fn default() -> Self {
+        Self::new(".")
+    }
+}
+
+fn load_rotations(path: &Path) -> Vec<RotationNotice> {
+    let Ok(data) = fs::read(path) else { return Vec::new() };
+    bincode::deserialize(&data).unwrap_or_else(|e| {
+        eprintln!("⚠️  Failed to decode {} ({}). Ignoring.", path.display(), e);
+        Vec::new()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("axiom_identity_{}_{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_restarts_reuse_peer_id() {
+        let dir = temp_dir("restart");
+        let first = NodeIdentity::new(&dir).load_or_create().unwrap();
+        let second = NodeIdentity::new(&dir).load_or_create().unwrap();
+        assert_eq!(first.public().to_peer_id(), second.public().to_peer_id());
+
+        // A different directory is a different node
+        let other = NodeIdentity::new(temp_dir("other")).load_or_create().unwrap();
+        assert_ne!(other.public().to_peer_id(), first.public().to_peer_id());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_rotation_archives_old_key() {
+        let dir = temp_dir("rotate");
+        let identity = NodeIdentity::new(&dir);
+        let old_peer = identity.load_or_create().unwrap().public().to_peer_id();
+        assert!(identity.rotate(1_000).is_ok());
+
+        let new_peer = identity.load_or_create().unwrap().public().to_peer_id();
+        assert_ne!(new_peer, old_peer);
+        let archived = Wallet::open(identity.archive_path(&old_peer)).unwrap();
+        assert_eq!(peer_id_of(&archived.address).unwrap(), old_peer);
+
+        // The notice links the identities and is signed by the old key
+        let notices = identity.rotations();
+        assert_eq!(notices.len(), 1);
+        let notice = &notices[0];
+        assert!(notice.verify());
+        assert_eq!(notice.old_peer_id().unwrap(), old_peer);
+        assert_eq!(notice.new_peer_id().unwrap(), new_peer);
+
+        let mut forged = notice.clone();
+        forged.new_key = [9u8; 32];
+        assert!(!forged.verify());
+
+        // Published for the grace period only
+        assert_eq!(identity.active_rotations(1_000 + ROTATION_GRACE_SECS - 1).len(), 1);
+        assert!(identity.active_rotations(1_000 + ROTATION_GRACE_SECS).is_empty());
+
+        // Rotating again chains on from the current key
+        let second = identity.rotate(2_000).unwrap();
+        assert_eq!(second.old_peer_id().unwrap(), new_peer);
+        assert_eq!(identity.rotations().len(), 2);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,283 @@
+//! Bounded work queues off the P2P event loop.
+//!
+//! The swarm loop has to keep polling: a burst of gossip or a slow disk write
+//! handled inline stalls mining and every peer at once. Peer blocks, peer
+//! transactions and storage writes therefore go onto `WorkQueue`s, each
+//! drained by its own worker thread. Submitting never waits. When a queue is
+//! full the item is dropped and counted (`work_queue_dropped_total`); peers
+//! re-announce blocks and transactions we missed.
+//!
+//! Storage writes cannot simply be lost, so `StorageWriter` remembers when it
+//! dropped one to the block log and the loop rewrites the whole chain once
+//! there is room again (`StorageWriter::take_stale`).
+
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, SyncSender};
+use std::sync::Arc;
+
+use crate::block::Block;
+use crate::config::PruningMode;
+use crate::error::Result as AxiomResult;
+use crate::metrics;
+use crate::snapshot::Snapshot;
+use crate::state::State;
+use crate::storage::{self, Checkpoint};
+
+/// Peer blocks waiting for validation
+pub const BLOCK_QUEUE_CAPACITY: usize = 64;
+/// Peer transactions waiting for validation
+pub const TX_QUEUE_CAPACITY: usize = 1_024;
+/// Writes waiting for the disk
+pub const STORAGE_QUEUE_CAPACITY: usize = 256;
+
+/// A bounded queue drained by a dedicated worker thread
+pub struct WorkQueue<T> {
+    name: &'static str,
+    sender: SyncSender<T>,
+    queued: Arc<AtomicUsize>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl<T: Send + 'static> WorkQueue<T> {
+    /// Start a worker running `handler` on every item, with room for
+    /// `capacity` items waiting behind the one in progress. The worker stops
+    /// once every handle to the queue is dropped.
+    pub fn spawn(name: &'static str, capacity: usize, mut handler: impl FnMut(T) + Send + 'static) -> AxiomResult<Self> {
+        let (sender, receiver) = mpsc::sync_channel::<T>(capacity.max(1));
+        let queued = Arc::new(AtomicUsize::new(0));
+        let worker_queued = queued.clone();
+        std::thread::Builder::new().name(format!("axiom-{}", name)).spawn(move || {
+            for item in receiver {
+                let depth = worker_queued.fetch_sub(1, Ordering::SeqCst) - 1;
+                metrics::set_queue_depth(name, depth);
+                handler(item);
+            }
+        })?;
+        Ok(Self { name, sender, queued, dropped: Arc::new(AtomicU64::new(0)) })
+    }
+
+    /// Queue `item` without waiting; `false` if it was dropped because the
+    /// queue is full or the worker has stopped
+    pub fn submit(&self, item: T) -> bool {
+        // Counted before sending so the worker never sees it go negative
+        let depth = self.queued.fetch_add(1, Ordering::SeqCst) + 1;
+        if self.sender.try_send(item).is_ok() {
+            metrics::set_queue_depth(self.name, depth);
+            return true;
+        }
+        self.queued.fetch_sub(1, Ordering::SeqCst);
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+        metrics::record_queue_dropped(self.name);
+        false
+    }
+
+    /// Items waiting, not counting the one being handled
+    pub fn depth(&self) -> usize {
+        self.queued.load(Ordering::SeqCst)
+    }
+
+    /// Items turned away so far
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl<T> Clone for WorkQueue<T> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name,
+            sender: self.sender.clone(),
+            queued: self.queued.clone(),
+            dropped: self.dropped.clone(),
+        }
+    }
+}
+
+/// One write for the storage worker
+pub enum StorageJob {
+    AppendBlock(Block),
+    /// Rewrite the block log with the whole chain
+    SaveChain(Vec<Block>),
+    SaveState { height: u64, block_hash: [u8; 32], state: State },
+    SaveCheckpoint(Checkpoint),
+    SaveSnapshot(Snapshot),
+    /// Drop transaction bodies deeper than `keep_depth` (see `storage::prune`)
+    Prune { mode: PruningMode, keep_depth: u64 },
+    /// Acknowledged once every earlier job is done
+    Flush(mpsc::Sender<()>),
+}
+
+impl StorageJob {
+    /// Perform the write, logging failures like the inline writes did
+    pub fn run(self) {
+        let result = match self {
+            StorageJob::AppendBlock(block) => storage::append_block(&block).map_err(|e| ("append block", e)),
+            StorageJob::SaveChain(blocks) => {
+                storage::save_chain(&blocks);
+                Ok(())
+            }
+            StorageJob::SaveState { height, block_hash, state } => {
+                storage::save_state(height, block_hash, &state).map_err(|e| ("save state index", e))
+            }
+            StorageJob::SaveCheckpoint(checkpoint) => {
+                storage::save_checkpoint(&checkpoint).map_err(|e| ("save checkpoint", e))
+            }
+            StorageJob::SaveSnapshot(snapshot) => {
+                storage::save_snapshot(&snapshot).map_err(|e| ("save state snapshot", e))
+            }
+            StorageJob::Prune { mode, keep_depth } => {
+                storage::prune(mode, keep_depth).map(|_| ()).map_err(|e| ("prune block log", e))
+            }
+            StorageJob::Flush(done) => {
+                let _ = done.send(());
+                Ok(())
+            }
+        };
+        if let Err((what, e)) = result {
+            eprintln!("❌ STORAGE ERROR: Could not {}: {}", what, e);
+        }
+    }
+
+    /// Whether losing this job leaves the block log behind the chain
+    fn writes_block_log(&self) -> bool {
+        matches!(self, StorageJob::AppendBlock(_) | StorageJob::SaveChain(_))
+    }
+}
+
+/// Storage writes queued for a worker thread
+#[derive(Clone)]
+pub struct StorageWriter {
+    queue: WorkQueue<StorageJob>,
+    stale: Arc<AtomicBool>,
+}
+
+impl StorageWriter {
+    /// Writer running each job against the node's storage files
+    pub fn spawn(capacity: usize) -> AxiomResult<Self> {
+        Self::spawn_with(capacity, StorageJob::run)
+    }
+
+    /// Writer handing each job to `handler` instead
+    pub fn spawn_with(capacity: usize, handler: impl FnMut(StorageJob) + Send + 'static) -> AxiomResult<Self> {
+        Ok(Self { queue: WorkQueue::spawn("storage", capacity, handler)?, stale: Arc::new(AtomicBool::new(false)) })
+    }
+
+    /// Queue `job`; see `WorkQueue::submit`
+    pub fn submit(&self, job: StorageJob) -> bool {
+        let writes_block_log = job.writes_block_log();
+        let queued = self.queue.submit(job);
+        if !queued && writes_block_log {
+            self.stale.store(true, Ordering::SeqCst);
+        }
+        queued
+    }
+
+    /// Whether a block log write was dropped since the last call; the
+    /// caller should queue a `SaveChain` to bring the log up to date
+    pub fn take_stale(&self) -> bool {
+        self.stale.swap(false, Ordering::SeqCst)
+    }
+
+    /// Block until every job queued so far has been written
+    pub fn flush(&self) {
+        let (done, finished) = mpsc::channel();
+        // Unlike `submit`, waits for room in the queue
+        self.queue.queued.fetch_add(1, Ordering::SeqCst);
+        if self.queue.sender.send(StorageJob::Flush(done)).is_ok() {
+            let _ = finished.recv();
+        } else {
+            self.queue.queued.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    pub fn queue(&self) -> &WorkQueue<StorageJob> {
+        &self.queue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn block(slot: u64) -> Block {
+        Block::new([0u8; 32], slot, [1u8; 32], vec![], [0u8; 32], vec![], 0)
+    }
+
+    #[test]
+    fn test_full_queue_drops_instead_of_waiting() {
+        let (release, gate) = mpsc::channel::<()>();
+        let (done, finished) = mpsc::channel::<u32>();
+        let queue = WorkQueue::spawn("test", 2, move |n: u32| {
+            gate.recv().unwrap();
+            done.send(n).unwrap();
+        })
+        .unwrap();
+
+        // The worker takes the first item and blocks on it
+        assert!(queue.submit(0));
+        while queue.depth() != 0 {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        // Two more fit behind it; the fourth is shed
+        assert!(queue.submit(1));
+        assert!(queue.submit(2));
+        assert!(!queue.submit(3));
+        assert_eq!(queue.depth(), 2);
+        assert_eq!(queue.dropped(), 1);
+
+        for _ in 0..3 {
+            release.send(()).unwrap();
+        }
+        let handled: Vec<u32> = (0..3).map(|_| finished.recv_timeout(Duration::from_secs(5)).unwrap()).collect();
+        assert_eq!(handled, vec![0, 1, 2]);
+        assert_eq!(queue.depth(), 0);
+    }
+
+    #[test]
+    fn test_slow_storage_does_not_stall_event_loop() {
+        // A disk that takes far longer per write than the whole burst below
+        let writes = Arc::new(AtomicUsize::new(0));
+        let writer = {
+            let writes = writes.clone();
+            StorageWriter::spawn_with(4, move |job| match job {
+                StorageJob::Flush(done) => done.send(()).unwrap(),
+                _ => {
+                    std::thread::sleep(Duration::from_millis(200));
+                    writes.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+            .unwrap()
+        };
+
+        // The event loop: every swarm event leads to a block append
+        let (events, inbox) = mpsc::channel::<u64>();
+        let loop_writer = writer.clone();
+        let event_loop = std::thread::spawn(move || {
+            let mut handled = 0;
+            for slot in inbox {
+                loop_writer.submit(StorageJob::AppendBlock(block(slot)));
+                handled += 1;
+            }
+            handled
+        });
+
+        let start = Instant::now();
+        for slot in 0..100 {
+            events.send(slot).unwrap();
+        }
+        drop(events);
+        assert_eq!(event_loop.join().unwrap(), 100);
+        assert!(start.elapsed() < Duration::from_millis(200), "loop waited on storage");
+
+        // Excess writes were shed, and the log is flagged for a full rewrite
+        assert!(writer.queue().dropped() >= 90);
+        assert!(writer.take_stale());
+        assert!(!writer.take_stale());
+
+        // Everything that was queued still reaches the disk
+        writer.flush();
+        assert_eq!(writes.load(Ordering::SeqCst) as u64, 100 - writer.queue().dropped());
+        assert_eq!(writer.queue().depth(), 0);
+    }
+}
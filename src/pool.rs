@@ -0,0 +1,397 @@
+//! Stratum-like mining pool.
+//!
+//! The pool operator builds the next block with `Timechain::next_block`,
+//! exactly as the solo mining loop does, and hands it to workers as a
+//! `WorkTemplate`. Workers fill in the VDF proof and search nonces until the
+//! block hash meets the pool's `share_difficulty`, which is lower than the
+//! chain's. Every such share is credited to the worker; a share that also
+//! meets the chain difficulty is a full block for the operator to submit.
+//! When the block reward arrives, `close_round` splits it in proportion to
+//! shares.
+//!
+//! On the wire, requests and responses are newline-delimited JSON over TCP
+//! (`serve` and `PoolWorker`).
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::mpsc;
+
+use crate::block::Block;
+use crate::chain::Timechain;
+use crate::error::{AxiomError, Result};
+use crate::main_helper::compute_vdf;
+use crate::transaction::{Address, Transaction};
+use crate::vdf;
+use crate::wallet::Wallet;
+
+/// Work handed to pool workers
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WorkTemplate {
+    pub job_id: u64,
+    /// Unsealed block: coinbase to the pool, transactions and ZK pass
+    pub block: Block,
+    /// Chain difficulty; sets the block target
+    pub difficulty: u64,
+    /// Iterations of the block VDF (`Timechain::vdf_steps`)
+    pub vdf_steps: u32,
+    /// Easier target a hash must meet to count as a share
+    pub share_difficulty: u64,
+}
+
+/// A worker's solution to a `WorkTemplate`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Share {
+    pub job_id: u64,
+    pub worker: Address,
+    pub vdf_proof: [u8; 32],
+    pub nonce: u64,
+}
+
+/// An accepted share
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShareOutcome {
+    /// Credited, but below the chain difficulty
+    Share,
+    /// Credited and a valid block for the chain
+    Block(Box<Block>),
+}
+
+pub struct Pool {
+    wallet: Wallet,
+    share_difficulty: u64,
+    next_job_id: u64,
+    /// Templates for the current tip, by job id
+    jobs: HashMap<u64, WorkTemplate>,
+    /// (job, nonce) pairs already credited
+    seen: HashSet<(u64, u64)>,
+    /// Shares per worker in the current round
+    shares: BTreeMap<Address, u64>,
+}
+
+impl Pool {
+    /// Pool mining to `wallet`, crediting hashes that meet `share_difficulty`
+    pub fn new(wallet: Wallet, share_difficulty: u64) -> Self {
+        Self {
+            wallet,
+            share_difficulty: share_difficulty.max(1),
+            next_job_id: 0,
+            jobs: HashMap::new(),
+            seen: HashSet::new(),
+            shares: BTreeMap::new(),
+        }
+    }
+
+    /// New template on top of `chain`'s tip. Templates for an older tip
+    /// are dropped, so shares against them are rejected as stale.
+    pub fn work_template(&mut self, chain: &Timechain, transactions: Vec<Transaction>) -> WorkTemplate {
+        let block = chain.next_block(&self.wallet, transactions);
+        self.jobs.retain(|_, job| job.block.parent == block.parent);
+        self.seen.retain(|(job_id, _)| self.jobs.contains_key(job_id));
+
+        self.next_job_id += 1;
+        let template = WorkTemplate {
+            job_id: self.next_job_id,
+            block,
+            difficulty: chain.difficulty,
+            vdf_steps: chain.vdf_steps,
+            share_difficulty: self.share_difficulty.min(chain.difficulty),
+        };
+        self.jobs.insert(template.job_id, template.clone());
+        template
+    }
+
+    /// Most recently issued template
+    pub fn current_work(&self) -> Option<&WorkTemplate> {
+        self.jobs.get(&self.next_job_id)
+    }
+
+    /// Check `share` against its template and credit it to the worker
+    pub fn submit(&mut self, share: &Share) -> Result<ShareOutcome> {
+        let template = self
+            .jobs
+            .get(&share.job_id)
+            .ok_or_else(|| AxiomError::InvalidBlock(format!("unknown or stale job {}", share.job_id)))?;
+        if self.seen.contains(&(share.job_id, share.nonce)) {
+            return Err(AxiomError::InvalidBlock("duplicate share".to_string()));
+        }
+
+        let mut block = template.block.clone();
+        let seed = vdf::evaluate(block.parent, block.slot);
+        if !vdf::verify_vdf(seed, template.vdf_steps, share.vdf_proof) {
+            return Err(AxiomError::VDFVerificationFailed("share VDF proof does not match the template".to_string()));
+        }
+        block.vdf_proof = share.vdf_proof;
+        block.nonce = share.nonce;
+        if !block.meets_target_for(template.share_difficulty) {
+            return Err(AxiomError::PoWVerificationFailed {
+                hash: hex::encode(block.hash()),
+                difficulty: template.share_difficulty,
+            });
+        }
+
+        let found = block.meets_difficulty();
+        self.seen.insert((share.job_id, share.nonce));
+        *self.shares.entry(share.worker).or_insert(0) += 1;
+        Ok(if found { ShareOutcome::Block(Box::new(block)) } else { ShareOutcome::Share })
+    }
+
+    /// Shares `worker` has in the current round
+    pub fn shares(&self, worker: &Address) -> u64 {
+        self.shares.get(worker).copied().unwrap_or(0)
+    }
+
+    /// Split `reward` across workers in proportion to their shares and start
+    /// a new round. Rounding leftovers stay with the pool.
+    pub fn close_round(&mut self, reward: u64) -> Vec<(Address, u64)> {
+        let total: u128 = self.shares.values().map(|&n| n as u128).sum();
+        let shares = std::mem::take(&mut self.shares);
+        if total == 0 {
+            return Vec::new();
+        }
+        shares
+            .into_iter()
+            .map(|(worker, n)| (worker, (reward as u128 * n as u128 / total) as u64))
+            .collect()
+    }
+}
+
+/// Fill in the VDF proof and search up to `max_attempts` nonces for a hash
+/// meeting the template's share difficulty
+pub fn solve(template: &WorkTemplate, worker: Address, max_attempts: u64) -> Option<Share> {
+    let mut block = template.block.clone();
+    block.vdf_proof = compute_vdf(vdf::evaluate(block.parent, block.slot), template.vdf_steps);
+    (0..max_attempts).find_map(|nonce| {
+        block.nonce = nonce;
+        block.meets_target_for(template.share_difficulty).then_some(Share {
+            job_id: template.job_id,
+            worker,
+            vdf_proof: block.vdf_proof,
+            nonce,
+        })
+    })
+}
+
+/// Worker → pool messages, one JSON object per line
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum PoolRequest {
+    GetWork,
+    Submit(Share),
+}
+
+/// Pool → worker replies
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolResponse {
+    Work(Box<WorkTemplate>),
+    Accepted { block_found: bool },
+    Rejected(String),
+}
+
+/// Answer one request. Found blocks go to `blocks` for the node to add.
+pub fn handle_request(pool: &Mutex<Pool>, request: PoolRequest, blocks: &mpsc::UnboundedSender<Block>) -> PoolResponse {
+    let mut pool = pool.lock().unwrap();
+    match request {
+        PoolRequest::GetWork => match pool.current_work() {
+            Some(work) => PoolResponse::Work(Box::new(work.clone())),
+            None => PoolResponse::Rejected("no work available yet".to_string()),
+        },
+        PoolRequest::Submit(share) => match pool.submit(&share) {
+            Ok(ShareOutcome::Share) => PoolResponse::Accepted { block_found: false },
+            Ok(ShareOutcome::Block(block)) => {
+                let _ = blocks.send(*block);
+                PoolResponse::Accepted { block_found: true }
+            }
+            Err(e) => PoolResponse::Rejected(e.to_string()),
+        },
+    }
+}
+
+/// Accept workers on `listener` until the task is dropped
+pub async fn serve(listener: TcpListener, pool: Arc<Mutex<Pool>>, blocks: mpsc::UnboundedSender<Block>) {
+    while let Ok((stream, _)) = listener.accept().await {
+        let pool = pool.clone();
+        let blocks = blocks.clone();
+        tokio::spawn(async move {
+            let (read, mut write) = stream.into_split();
+            let mut lines = BufReader::new(read).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let response = match serde_json::from_str::<PoolRequest>(&line) {
+                    Ok(request) => handle_request(&pool, request, &blocks),
+                    Err(e) => PoolResponse::Rejected(format!("malformed request: {}", e)),
+                };
+                let Ok(mut encoded) = serde_json::to_vec(&response) else { break };
+                encoded.push(b'\n');
+                if write.write_all(&encoded).await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+/// Connection from a worker to a pool
+pub struct PoolWorker {
+    address: Address,
+    reader: BufReader<OwnedReadHalf>,
+    writer: OwnedWriteHalf,
+}
+
+impl PoolWorker {
+    /// Connect to the pool at `addr`, crediting shares to `address`
+    pub async fn connect(addr: impl ToSocketAddrs, address: Address) -> Result<Self> {
+        let (read, writer) = TcpStream::connect(addr).await?.into_split();
+        Ok(Self { address, reader: BufReader::new(read), writer })
+    }
+
+    async fn call(&mut self, request: &PoolRequest) -> Result<PoolResponse> {
+        let mut encoded = serde_json::to_vec(request).map_err(|e| AxiomError::SerializationError(e.to_string()))?;
+        encoded.push(b'\n');
+        self.writer.write_all(&encoded).await?;
+        let mut line = String::new();
+        if self.reader.read_line(&mut line).await? == 0 {
+            return Err(AxiomError::NetworkError("pool closed the connection".to_string()));
+        }
+        serde_json::from_str(&line).map_err(|e| AxiomError::DeserializationError(e.to_string()))
+    }
+
+    pub async fn get_work(&mut self) -> Result<WorkTemplate> {
+        match self.call(&PoolRequest::GetWork).await? {
+            PoolResponse::Work(work) => Ok(*work),
+            PoolResponse::Rejected(reason) => Err(AxiomError::NetworkError(reason)),
+            other => Err(AxiomError::P2PError(format!("unexpected pool reply: {:?}", other))),
+        }
+    }
+
+    pub async fn submit(&mut self, share: Share) -> Result<PoolResponse> {
+        self.call(&PoolRequest::Submit(share)).await
+    }
+
+    /// Fetch work, solve it and submit the share. `None` if no share was
+    /// found within `max_attempts` nonces.
+    pub async fn mine_once(&mut self, max_attempts: u64) -> Result<Option<PoolResponse>> {
+        let work = self.get_work().await?;
+        let address = self.address;
+        let share = tokio::task::spawn_blocking(move || solve(&work, address, max_attempts))
+            .await
+            .map_err(|e| AxiomError::ThreadError(e.to_string()))?;
+        match share {
+            Some(share) => Ok(Some(self.submit(share).await?)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genesis;
+
+    fn pool_and_chain(share_difficulty: u64) -> (Pool, Timechain) {
+        let chain = Timechain::new(genesis::genesis());
+        (Pool::new(Wallet::from_secret_key([3u8; 32]), share_difficulty), chain)
+    }
+
+    #[test]
+    fn test_work_template_matches_solo_block() {
+        let (mut pool, chain) = pool_and_chain(1);
+        let work = pool.work_template(&chain, vec![]);
+        assert_eq!(work.block, chain.next_block(&pool.wallet, vec![]));
+        assert_eq!(work.block.slot, 1);
+        assert_eq!(work.block.coinbase().unwrap().to, pool.wallet.address);
+        assert_eq!(work.difficulty, chain.difficulty);
+        assert_eq!(pool.current_work(), Some(&work));
+
+        // A second template for the same tip keeps the first one valid
+        let again = pool.work_template(&chain, vec![]);
+        assert_ne!(again.job_id, work.job_id);
+        assert!(pool.jobs.contains_key(&work.job_id));
+    }
+
+    #[test]
+    fn test_share_validation_against_pool_difficulty() {
+        let (mut pool, mut chain) = pool_and_chain(1);
+        let work = pool.work_template(&chain, vec![]);
+        let worker = [7u8; 32];
+
+        // Share difficulty 1 accepts any hash
+        let share = solve(&work, worker, 1).unwrap();
+        assert!(pool.submit(&share).is_ok());
+        assert_eq!(pool.shares(&worker), 1);
+        assert!(matches!(pool.submit(&share), Err(AxiomError::InvalidBlock(_))));
+
+        let mut bad_vdf = share.clone();
+        bad_vdf.nonce += 1;
+        bad_vdf.vdf_proof = [0u8; 32];
+        assert!(matches!(pool.submit(&bad_vdf), Err(AxiomError::VDFVerificationFailed(_))));
+        let mut unknown = share.clone();
+        unknown.job_id = 99;
+        assert!(pool.submit(&unknown).is_err());
+
+        // A nonce that misses the share target is rejected; one that meets the
+        // chain target is returned as a block the chain accepts
+        pool.share_difficulty = chain.difficulty;
+        let work = pool.work_template(&chain, vec![]);
+        let mut block = work.block.clone();
+        block.vdf_proof = share.vdf_proof;
+        let miss = (0..).find(|&n| { block.nonce = n; !block.meets_target_for(work.share_difficulty) }).unwrap();
+        let share = Share { job_id: work.job_id, worker, vdf_proof: block.vdf_proof, nonce: miss };
+        assert!(matches!(pool.submit(&share), Err(AxiomError::PoWVerificationFailed { .. })));
+
+        let share = solve(&work, worker, u64::MAX).unwrap();
+        match pool.submit(&share).unwrap() {
+            ShareOutcome::Block(block) => chain.add_block(*block, chain.block_time).unwrap(),
+            ShareOutcome::Share => panic!("a share at chain difficulty is a block"),
+        }
+        assert_eq!(pool.shares(&worker), 2);
+
+        // Work for the old tip is now stale
+        pool.work_template(&chain, vec![]);
+        assert!(matches!(pool.submit(&share), Err(AxiomError::InvalidBlock(_))));
+    }
+
+    #[test]
+    fn test_payouts_proportional_to_shares() {
+        let (mut pool, chain) = pool_and_chain(1);
+        let work = pool.work_template(&chain, vec![]);
+        let (alice, bob) = ([1u8; 32], [2u8; 32]);
+
+        let vdf_proof = solve(&work, alice, 1).unwrap().vdf_proof;
+        for nonce in 0..4 {
+            let worker = if nonce == 0 { bob } else { alice };
+            pool.submit(&Share { job_id: work.job_id, worker, vdf_proof, nonce }).unwrap();
+        }
+        assert_eq!((pool.shares(&alice), pool.shares(&bob)), (3, 1));
+
+        assert_eq!(pool.close_round(1_001), vec![(alice, 750), (bob, 250)]);
+        assert_eq!(pool.shares(&alice), 0);
+        assert!(pool.close_round(1_000).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_worker_mines_over_tcp() {
+        let (mut pool, chain) = pool_and_chain(1);
+        pool.work_template(&chain, vec![]);
+        let pool = Arc::new(Mutex::new(pool));
+        let (found_tx, _found_rx) = mpsc::unbounded_channel();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, pool.clone(), found_tx));
+
+        let worker = [4u8; 32];
+        let mut client = PoolWorker::connect(addr, worker).await.unwrap();
+        let reply = client.mine_once(1).await.unwrap();
+        assert!(matches!(reply, Some(PoolResponse::Accepted { .. })));
+        assert_eq!(pool.lock().unwrap().shares(&worker), 1);
+
+        let mut bogus = client.get_work().await.map(|work| solve(&work, worker, 1).unwrap()).unwrap();
+        bogus.vdf_proof = [1u8; 32];
+        assert!(matches!(client.submit(bogus).await.unwrap(), PoolResponse::Rejected(_)));
+    }
+}
@@ -8,5 +8,6 @@ pub use view_keys::{
     SelectiveDisclosure,
     TransactionDetails,
     EncryptedTransaction,
+    NonceGuard,
     ComplianceReport,
 };
@@ -7,6 +7,8 @@ use aes_gcm::{Aes256Gcm, KeyInit};
 use aes_gcm::aead::Aead;
 use sha2::{Sha256, Digest};
 use rand::Rng;
+use std::collections::{HashMap, HashSet};
+use crate::hashing;
 
 /// View Key - Allows third parties to VIEW transactions without spending
 /// Use cases: Tax compliance, audits, regulatory reporting
@@ -322,16 +324,109 @@ pub struct EncryptedTransaction {
 }
 
 impl EncryptedTransaction {
+    /// Encrypt `details` under `key` with a fresh nonce from `guard`
+    pub fn encrypt(
+        details: &TransactionDetails,
+        key: &[u8; 32],
+        ephemeral_public_key: [u8; 32],
+        guard: &mut NonceGuard,
+    ) -> Result<Self, String> {
+        let nonce = guard.next(key);
+        Self::seal(details, key, ephemeral_public_key, nonce)
+    }
+
+    /// Encrypt with a caller-chosen nonce. Refused if `guard` has already
+    /// seen `nonce` under `key`.
+    pub fn encrypt_with_nonce(
+        details: &TransactionDetails,
+        key: &[u8; 32],
+        ephemeral_public_key: [u8; 32],
+        nonce: [u8; 12],
+        guard: &mut NonceGuard,
+    ) -> Result<Self, String> {
+        guard.reserve(key, nonce)?;
+        Self::seal(details, key, ephemeral_public_key, nonce)
+    }
+
+    fn seal(
+        details: &TransactionDetails,
+        key: &[u8; 32],
+        ephemeral_public_key: [u8; 32],
+        nonce: [u8; 12],
+    ) -> Result<Self, String> {
+        use aes_gcm::aead::generic_array::GenericArray;
+
+        let cipher = Aes256Gcm::new(GenericArray::from_slice(key));
+        let mut plaintext = Vec::with_capacity(40);
+        plaintext.extend_from_slice(&details.to);
+        plaintext.extend_from_slice(&details.amount.to_le_bytes());
+        let encrypted_data = cipher
+            .encrypt(GenericArray::from_slice(&nonce), plaintext.as_ref())
+            .map_err(|_| "Encryption failed")?;
+
+        Ok(Self {
+            from: details.from,
+            encrypted_data,
+            ephemeral_public_key,
+            nonce,
+            timestamp: details.timestamp,
+        })
+    }
+
     pub fn hash(&self) -> [u8; 32] {
-        let mut hasher = Sha256::new();
-        hasher.update(self.from);
-        hasher.update(&self.encrypted_data);
-        hasher.update(self.ephemeral_public_key);
-        let hash = hasher.finalize();
-        
-        let mut result = [0u8; 32];
-        result.copy_from_slice(&hash);
-        result
+        hashing::tagged_sha256(
+            hashing::ENCRYPTED_TX,
+            &[&self.from, &self.encrypted_data, &self.ephemeral_public_key],
+        )
+    }
+}
+
+/// Nonces already used under each AES-GCM key.
+///
+/// Invariant: a (key, nonce) pair is never used for two encryptions. A
+/// repeat under GCM leaks the XOR of both plaintexts and lets anyone forge
+/// tags for that key, so every encryption takes its nonce through a guard.
+/// Nonces are random 96-bit values, redrawn on the (negligible) chance of
+/// a collision; keys are tracked by fingerprint rather than kept in memory.
+#[derive(Debug, Default)]
+pub struct NonceGuard {
+    used: HashMap<[u8; 32], HashSet<[u8; 12]>>,
+}
+
+impl NonceGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A random nonce not yet used under `key`, recorded as used
+    pub fn next(&mut self, key: &[u8; 32]) -> [u8; 12] {
+        let used = self.used.entry(Self::fingerprint(key)).or_default();
+        loop {
+            let nonce: [u8; 12] = rand::thread_rng().gen();
+            if used.insert(nonce) {
+                return nonce;
+            }
+        }
+    }
+
+    /// Record `nonce` as used under `key`, or refuse if it already was
+    pub fn reserve(&mut self, key: &[u8; 32], nonce: [u8; 12]) -> Result<(), String> {
+        if self.used.entry(Self::fingerprint(key)).or_default().insert(nonce) {
+            Ok(())
+        } else {
+            Err("Nonce already used with this key".to_string())
+        }
+    }
+
+    /// Whether `nonce` has been used under `key`
+    pub fn is_used(&self, key: &[u8; 32], nonce: &[u8; 12]) -> bool {
+        self.used
+            .get(&Self::fingerprint(key))
+            .is_some_and(|used| used.contains(nonce))
+    }
+
+    fn fingerprint(key: &[u8; 32]) -> [u8; 32] {
+        hashing::tagged_sha256(hashing::NONCE_GUARD_KEY, &[key])
     }
 }
 
@@ -384,4 +479,38 @@ mod tests {
         assert_eq!(disclosure.transaction_hash, tx_hash);
         assert!(disclosure.expires_at > chrono::Utc::now().timestamp() as u64);
     }
+
+    fn details(to: [u8; 32], amount: u64) -> TransactionDetails {
+        TransactionDetails { from: [1u8; 32], to, amount, timestamp: 1 }
+    }
+
+    #[test]
+    fn test_distinct_nonces_per_recipient() {
+        let key = [7u8; 32];
+        let payment = details([2u8; 32], 100);
+        let mut guard = NonceGuard::new();
+
+        let first = EncryptedTransaction::encrypt(&payment, &key, [3u8; 32], &mut guard).unwrap();
+        let second = EncryptedTransaction::encrypt(&payment, &key, [3u8; 32], &mut guard).unwrap();
+        assert_ne!(first.nonce, second.nonce);
+        assert_ne!(first.encrypted_data, second.encrypted_data);
+
+        let decrypted = AxiomWallet::decrypt_with_key(&second, &key).unwrap();
+        assert_eq!(decrypted.to, payment.to);
+        assert_eq!(decrypted.amount, 100);
+    }
+
+    #[test]
+    fn test_nonce_reuse_refused() {
+        let key = [7u8; 32];
+        let mut guard = NonceGuard::new();
+        let tx = EncryptedTransaction::encrypt(&details([2u8; 32], 5), &key, [3u8; 32], &mut guard).unwrap();
+        assert!(guard.is_used(&key, &tx.nonce));
+
+        let reused = EncryptedTransaction::encrypt_with_nonce(&details([4u8; 32], 9), &key, [3u8; 32], tx.nonce, &mut guard);
+        assert_eq!(reused.unwrap_err(), "Nonce already used with this key");
+
+        // The same nonce under a different key is fine
+        assert!(EncryptedTransaction::encrypt_with_nonce(&details([4u8; 32], 9), &[8u8; 32], [3u8; 32], tx.nonce, &mut guard).is_ok());
+    }
 }
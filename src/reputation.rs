@@ -0,0 +1,212 @@
+// src/reputation.rs - Persistent peer reputation
+//
+// The Neural Guardian judges peers message by message and forgets them on
+// restart. This store keeps what it learned: a trust score per peer, the
+// violations it committed and the times it was banned, keyed by the peer's
+// ed25519 public key as reported over identify. It is saved next to the
+// chain and consulted whenever a peer is identified.
+//
+// Scores run from 0 (hostile) to 1 (trusted), starting at NEUTRAL_SCORE.
+// Between observations a score decays toward neutral with a half-life of
+// DECAY_HALF_LIFE_SECS, so a peer banned long ago can earn its way back.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::error::{AxiomError, Result as AxiomResult};
+
+/// Default reputation file
+pub const REPUTATION_PATH: &str = "axiom_reputation.dat";
+
+/// Score of a peer we know nothing about
+pub const NEUTRAL_SCORE: f32 = 0.5;
+
+/// Peers scoring below this are refused on connect
+pub const FLAG_THRESHOLD: f32 = 0.2;
+
+/// Time for a score's distance from neutral to halve: one week
+pub const DECAY_HALF_LIFE_SECS: u64 = 7 * 24 * 3600;
+
+/// Weight of a single trust observation against the peer's history
+const OBSERVATION_WEIGHT: f32 = 0.1;
+
+/// Score lost per violation
+const VIOLATION_PENALTY: f32 = 0.1;
+
+/// ed25519 public key of a peer
+pub type PeerKey = [u8; 32];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BanRecord {
+    /// Unix seconds
+    pub at: u64,
+    pub reason: String,
+}
+
+/// Everything remembered about one peer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeerReputation {
+    /// Score as of `updated_at`, before decay
+    pub score: f32,
+    pub violations: u64,
+    pub bans: Vec<BanRecord>,
+    /// Unix seconds of the last observation
+    pub updated_at: u64,
+}
+
+impl PeerReputation {
+    fn new(now: u64) -> Self {
+        Self { score: NEUTRAL_SCORE, violations: 0, bans: Vec::new(), updated_at: now }
+    }
+
+    /// Score at `now`, decayed toward neutral since the last observation
+    pub fn score_at(&self, now: u64) -> f32 {
+        let half_lives = now.saturating_sub(self.updated_at) as f64 / DECAY_HALF_LIFE_SECS as f64;
+        NEUTRAL_SCORE + (self.score - NEUTRAL_SCORE) * 0.5f64.powf(half_lives) as f32
+    }
+
+    /// Bring the stored score up to `now` before applying an observation
+    fn settle(&mut self, now: u64) {
+        self.score = self.score_at(now);
+        self.updated_at = self.updated_at.max(now);
+    }
+}
+
+/// Reputations of every peer this node has identified
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReputationStore {
+    peers: HashMap<PeerKey, PeerReputation>,
+}
+
+impl ReputationStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &PeerKey) -> Option<&PeerReputation> {
+        self.peers.get(key)
+    }
+
+    /// Current score of `key`; unknown peers are neutral
+    pub fn score(&self, key: &PeerKey, now: u64) -> f32 {
+        self.peers.get(key).map_or(NEUTRAL_SCORE, |rep| rep.score_at(now))
+    }
+
+    /// Whether `key` has too poor a record to be let in
+    pub fn is_flagged(&self, key: &PeerKey, now: u64) -> bool {
+        self.score(key, now) < FLAG_THRESHOLD
+    }
+
+    fn entry(&mut self, key: PeerKey, now: u64) -> &mut PeerReputation {
+        let rep = self.peers.entry(key).or_insert_with(|| PeerReputation::new(now));
+        rep.settle(now);
+        rep
+    }
+
+    /// Blend a Neural Guardian trust assessment (0 to 1) into the score
+    pub fn record_trust(&mut self, key: PeerKey, trust: f32, now: u64) {
+        let rep = self.entry(key, now);
+        rep.score += (trust.clamp(0.0, 1.0) - rep.score) * OBSERVATION_WEIGHT;
+    }
+
+    /// Count a protocol violation (flooding, invalid data) against `key`
+    pub fn record_violation(&mut self, key: PeerKey, now: u64) {
+        let rep = self.entry(key, now);
+        rep.violations += 1;
+        rep.score = (rep.score - VIOLATION_PENALTY).max(0.0);
+    }
+
+    /// Record a ban; the score drops to zero and recovers only by decay
+    pub fn record_ban(&mut self, key: PeerKey, reason: &str, now: u64) {
+        let rep = self.entry(key, now);
+        rep.bans.push(BanRecord { at: now, reason: reason.to_string() });
+        rep.score = 0.0;
+    }
+
+    /// Persist the store (atomic write via temp file + rename)
+    pub fn save_to_file(&self, path: &str) -> AxiomResult<()> {
+        let encoded = bincode::serialize(self)?;
+        let temp_path = format!("{}.tmp", path);
+        std::fs::write(&temp_path, encoded)?;
+        std::fs::rename(temp_path, path)?;
+        Ok(())
+    }
+
+    /// Load a previously persisted store
+    pub fn load_from_file(path: &str) -> AxiomResult<Self> {
+        let data = std::fs::read(path)?;
+        bincode::deserialize(&data).map_err(|e| AxiomError::DeserializationError(e.to_string()))
+    }
+
+    /// Load the store at `path`; a missing or corrupt file yields an empty one
+    pub fn load(path: &str) -> Self {
+        if !std::path::Path::new(path).exists() {
+            return Self::new();
+        }
+        Self::load_from_file(path).unwrap_or_else(|e| {
+            eprintln!("⚠️  Failed to load peer reputations ({}). Starting fresh.", e);
+            Self::new()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 24 * 3600;
+
+    #[test]
+    fn test_bad_peer_flagged_after_restart() {
+        let path = std::env::temp_dir().join(format!("axiom_reputation_{}.dat", std::process::id()));
+        let path = path.to_str().unwrap();
+        let (bad, good) = ([1u8; 32], [2u8; 32]);
+
+        let mut store = ReputationStore::new();
+        for _ in 0..5 {
+            store.record_violation(bad, 1_000);
+        }
+        store.record_ban(bad, "message rate limit", 1_000);
+        store.record_trust(good, 1.0, 1_000);
+        store.save_to_file(path).unwrap();
+
+        // A fresh node loads the history and refuses the peer an hour later
+        let restarted = ReputationStore::load(path);
+        let _ = std::fs::remove_file(path);
+        assert!(restarted.is_flagged(&bad, 1_000 + 3600));
+        assert!(!restarted.is_flagged(&good, 1_000 + 3600));
+        assert!(!restarted.is_flagged(&[3u8; 32], 1_000 + 3600));
+
+        let rep = restarted.get(&bad).unwrap();
+        assert_eq!(rep.violations, 5);
+        assert_eq!(rep.bans, vec![BanRecord { at: 1_000, reason: "message rate limit".to_string() }]);
+    }
+
+    #[test]
+    fn test_reputation_decays_toward_neutral() {
+        let (bad, good) = ([1u8; 32], [2u8; 32]);
+        let mut store = ReputationStore::new();
+        store.record_ban(bad, "invalid blocks", 0);
+        for _ in 0..50 {
+            store.record_trust(good, 1.0, 0);
+        }
+        assert_eq!(store.score(&bad, 0), 0.0);
+        assert!(store.score(&good, 0) > 0.95);
+
+        // One half-life closes half the gap to neutral, in both directions
+        let week = DECAY_HALF_LIFE_SECS;
+        assert!((store.score(&bad, week) - NEUTRAL_SCORE / 2.0).abs() < 1e-4);
+        assert!(store.score(&good, week) < store.score(&good, 0));
+
+        // Banned peers are refused for a few days, then readmitted
+        assert!(store.is_flagged(&bad, 3 * DAY));
+        assert!(!store.is_flagged(&bad, week));
+        assert!((store.score(&bad, 365 * DAY) - NEUTRAL_SCORE).abs() < 1e-4);
+        assert!((store.score(&good, 365 * DAY) - NEUTRAL_SCORE).abs() < 1e-4);
+
+        // History survives recovery; new observations start from the decayed score
+        store.record_trust(bad, 1.0, 365 * DAY);
+        assert!(store.score(&bad, 365 * DAY) > NEUTRAL_SCORE);
+        assert_eq!(store.get(&bad).unwrap().bans.len(), 1);
+    }
+}
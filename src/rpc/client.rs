@@ -0,0 +1,472 @@
+//! JSON-RPC client for wallets and tools talking to a node.
+//!
+//! A client made with [`RpcClient::new_pinned`] expects a particular genesis
+//! block. Before its first request it asks `get_chain_info` for the node's
+//! genesis hash, and if that is a different network it refuses every call
+//! with `AxiomError::GenesisMismatch`, so nothing (least of all a signed
+//! transaction) reaches the wrong network.
+//!
+//! [`RpcClient::wait_for_confirmation`] polls `get_transaction` every
+//! `poll_interval` until a transaction is buried deep enough, drops out
+//! of the node, or the timeout passes.
+//!
+//! [`RpcClient::reserve_nonce`] hands out nonces per sender without asking
+//! the node each time, so several transactions can be signed before any of
+//! them is mined. The first reservation starts from `get_nonce`; after that
+//! the client counts up on its own. If a transaction it broadcast is
+//! rejected or later dropped, the count is stale and the sender's cache is
+//! cleared, so the next reservation asks the node again.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tokio::sync::OnceCell;
+
+use super::proof_store::{ProofAssembler, ProofChunk};
+use super::{proof_chunk_from_json, RpcError, RpcRequest, RpcResponse, NOT_FOUND};
+use crate::error::{AxiomError, Result as AxiomResult};
+use crate::transaction::{Address, Transaction};
+
+/// Default time between polls in `wait_for_confirmation`
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Where a transaction stands, as `wait_for_confirmation` last saw it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxStatus {
+    /// In the node's mempool
+    Pending,
+    /// In the block at height `block`, with `depth` blocks from it to the
+    /// tip (1 while it is the tip)
+    Confirmed { block: u64, depth: u64 },
+    /// Neither in the mempool nor in a block
+    Dropped,
+}
+
+/// Client for one node's `/rpc` endpoint
+pub struct RpcClient {
+    url: String,
+    http: reqwest::Client,
+    expected_genesis: Option<[u8; 32]>,
+    /// Set once the node's genesis has matched `expected_genesis`
+    pinned: OnceCell<()>,
+    poll_interval: Duration,
+    /// Next nonce to hand out, per sender
+    nonces: Mutex<HashMap<Address, u64>>,
+    /// Sender of each transaction broadcast and not yet settled
+    sent: Mutex<HashMap<[u8; 32], Address>>,
+}
+
+impl RpcClient {
+    /// Client for the node at `url` (e.g. `http://127.0.0.1:8332/rpc`),
+    /// whatever network it is on
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            http: reqwest::Client::new(),
+            expected_genesis: None,
+            pinned: OnceCell::new(),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            nonces: Mutex::new(HashMap::new()),
+            sent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Client that only talks to a node whose genesis hash is
+    /// `expected_genesis`
+    pub fn new_pinned(url: impl Into<String>, expected_genesis: [u8; 32]) -> Self {
+        Self { expected_genesis: Some(expected_genesis), ..Self::new(url) }
+    }
+
+    /// Poll every `interval` in `wait_for_confirmation`
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Call `method` with `params`, returning the `result` of the response
+    pub async fn call(&self, method: &str, params: Value) -> AxiomResult<Value> {
+        self.check_genesis().await?;
+        self.request(method, params).await
+    }
+
+    /// `get_chain_info`: height, tip, genesis hash, network id and supply
+    pub async fn chain_info(&self) -> AxiomResult<Value> {
+        self.call("get_chain_info", json!([])).await
+    }
+
+    /// `get_balance` of `address`
+    pub async fn balance(&self, address: Address) -> AxiomResult<u64> {
+        let response = self.call("get_balance", json!([hex::encode(address)])).await?;
+        response["balance"]
+            .as_u64()
+            .ok_or_else(|| AxiomError::RpcError("invalid balance response".to_string()))
+    }
+
+    /// `get_nonce`: the next nonce the node expects from `address`
+    pub async fn nonce(&self, address: Address) -> AxiomResult<u64> {
+        let response = self.call("get_nonce", json!([hex::encode(address)])).await?;
+        response["nonce"]
+            .as_u64()
+            .ok_or_else(|| AxiomError::RpcError("invalid nonce response".to_string()))
+    }
+
+    /// Nonce for the next transaction from `address`. Each call returns a
+    /// new one, counting up from the node's nonce on the first call.
+    pub async fn reserve_nonce(&self, address: Address) -> AxiomResult<u64> {
+        if let Some(next) = self.nonces.lock().unwrap().get_mut(&address) {
+            *next += 1;
+            return Ok(*next - 1);
+        }
+        let from_node = self.nonce(address).await?;
+        // Another reservation may have fetched it meanwhile; count on from
+        // whichever got in first
+        let mut nonces = self.nonces.lock().unwrap();
+        let next = nonces.entry(address).or_insert(from_node);
+        *next += 1;
+        Ok(*next - 1)
+    }
+
+    /// Forget the nonces reserved for `address`; the next reservation asks
+    /// the node again
+    pub fn reset_nonce_cache(&self, address: Address) {
+        self.nonces.lock().unwrap().remove(&address);
+    }
+
+    /// `broadcast_transaction`: hand `tx` to the node's mempool, returning
+    /// its hash. If the node rejects it, the sender's nonce cache is reset.
+    pub async fn broadcast_transaction(&self, tx: &Transaction) -> AxiomResult<[u8; 32]> {
+        let response = match self.call("broadcast_transaction", json!([tx])).await {
+            Ok(response) => response,
+            Err(e) => {
+                self.reset_nonce_cache(tx.from);
+                return Err(e);
+            }
+        };
+        let hash = response["tx_hash"]
+            .as_str()
+            .and_then(|hash| hex::decode(hash).ok())
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| AxiomError::RpcError("invalid broadcast response".to_string()))?;
+        self.sent.lock().unwrap().insert(hash, tx.from);
+        Ok(hash)
+    }
+
+    /// `get_transaction`: the transaction `hash` with its status, or None
+    /// if the node knows no such transaction
+    pub async fn transaction(&self, hash: [u8; 32]) -> AxiomResult<Option<Value>> {
+        self.call_optional("get_transaction", json!([hex::encode(hash)])).await
+    }
+
+    /// `get_block` at `height`, or None above the node's tip
+    pub async fn block(&self, height: u64) -> AxiomResult<Option<Value>> {
+        self.call_optional("get_block", json!([height])).await
+    }
+
+    /// Where the transaction `hash` stands now
+    pub async fn transaction_status(&self, hash: [u8; 32]) -> AxiomResult<TxStatus> {
+        let Some(found) = self.transaction(hash).await? else {
+            return Ok(TxStatus::Dropped);
+        };
+        let Some(block) = found["block_height"].as_u64() else {
+            return Ok(TxStatus::Pending);
+        };
+        let tip = self.chain_info().await?["height"]
+            .as_u64()
+            .ok_or_else(|| AxiomError::RpcError("invalid chain info response".to_string()))?;
+        Ok(TxStatus::Confirmed { block, depth: tip.saturating_sub(block) + 1 })
+    }
+
+    /// Poll until the transaction `hash` is `confirmations` blocks deep or
+    /// is dropped. After `timeout`, returns whatever was seen last, which
+    /// may be `Pending` or a shallower `Confirmed`. A dropped transaction
+    /// broadcast through this client resets its sender's nonce cache.
+    pub async fn wait_for_confirmation(
+        &self,
+        hash: [u8; 32],
+        confirmations: u64,
+        timeout: Duration,
+    ) -> AxiomResult<TxStatus> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let status = self.transaction_status(hash).await?;
+            let settled = match status {
+                TxStatus::Confirmed { depth, .. } => depth >= confirmations,
+                TxStatus::Dropped => true,
+                TxStatus::Pending => false,
+            };
+            if settled {
+                self.settle(hash, status);
+                return Ok(status);
+            }
+            if tokio::time::Instant::now() + self.poll_interval > deadline {
+                return Ok(status);
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    /// `get_address_history`: up to `limit` transactions of `address`,
+    /// newest first, skipping `offset`
+    pub async fn address_history(&self, address: Address, offset: u64, limit: u64) -> AxiomResult<Value> {
+        self.call("get_address_history", json!([hex::encode(address), offset, limit])).await
+    }
+
+    /// Fetch the stored proof `id` chunk by chunk with `get_proof_chunk`,
+    /// checking every chunk against the id
+    pub async fn fetch_proof(&self, id: [u8; 32]) -> AxiomResult<Vec<u8>> {
+        let first = self.proof_chunk(id, 0).await?;
+        if first.ticket.id != id {
+            return Err(AxiomError::InvalidProofChunk(format!("node answered for proof {}", hex::encode(first.ticket.id))));
+        }
+        let mut assembler = ProofAssembler::new(first.ticket);
+        assembler.add(first)?;
+        while let Some(offset) = assembler.next_offset() {
+            assembler.add(self.proof_chunk(id, offset).await?)?;
+        }
+        assembler.into_bytes()
+    }
+
+    /// Stop tracking the broadcast `hash`, releasing its sender's nonces if
+    /// it was dropped
+    fn settle(&self, hash: [u8; 32], status: TxStatus) {
+        let Some(sender) = self.sent.lock().unwrap().remove(&hash) else {
+            return;
+        };
+        if status == TxStatus::Dropped {
+            self.reset_nonce_cache(sender);
+        }
+    }
+
+    async fn proof_chunk(&self, id: [u8; 32], offset: u64) -> AxiomResult<ProofChunk> {
+        let response = self.call("get_proof_chunk", json!([hex::encode(id), offset])).await?;
+        proof_chunk_from_json(&response).ok_or_else(|| AxiomError::RpcError("invalid proof chunk response".to_string()))
+    }
+
+    async fn check_genesis(&self) -> AxiomResult<()> {
+        let Some(expected) = self.expected_genesis else {
+            return Ok(());
+        };
+        // A mismatch is not cached: every call asks again, and fails again
+        self.pinned
+            .get_or_try_init(|| async {
+                let info = self.request("get_chain_info", json!([])).await?;
+                let actual = info["genesis_hash"].as_str().unwrap_or_default();
+                if actual != hex::encode(expected) {
+                    return Err(AxiomError::GenesisMismatch {
+                        expected: hex::encode(expected),
+                        actual: actual.to_string(),
+                    });
+                }
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Like `call`, with the node's `NOT_FOUND` answer as None
+    async fn call_optional(&self, method: &str, params: Value) -> AxiomResult<Option<Value>> {
+        self.check_genesis().await?;
+        match self.exchange(method, params).await? {
+            Ok(result) => Ok(Some(result)),
+            Err(error) if error.code == NOT_FOUND => Ok(None),
+            Err(error) => Err(rpc_error(error)),
+        }
+    }
+
+    async fn request(&self, method: &str, params: Value) -> AxiomResult<Value> {
+        self.exchange(method, params).await?.map_err(rpc_error)
+    }
+
+    /// Send one request; the outer error is a transport or decoding
+    /// failure, the inner one the node's error object
+    async fn exchange(&self, method: &str, params: Value) -> AxiomResult<Result<Value, RpcError>> {
+        let request = RpcRequest { jsonrpc: "2.0".to_string(), method: method.to_string(), params, id: json!(1) };
+        let response: RpcResponse = self
+            .http
+            .post(&self.url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| AxiomError::NetworkError(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| AxiomError::RpcError(format!("invalid response: {}", e)))?;
+        Ok(match (response.result, response.error) {
+            (_, Some(error)) => Err(error),
+            (Some(result), None) => Ok(result),
+            (None, None) => Ok(Value::Null),
+        })
+    }
+}
+
+fn rpc_error(error: RpcError) -> AxiomError {
+    AxiomError::RpcError(format!("{} ({})", error.message, error.code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::testutil::MockNode;
+    use crate::rpc::TX_REJECTED;
+
+    #[tokio::test]
+    async fn test_balance_from_node() {
+        let node = MockNode::start();
+        node.reply("get_balance", json!({ "address": hex::encode([1u8; 32]), "balance": 750 }));
+
+        let client = RpcClient::new(node.url());
+        assert_eq!(client.balance([1u8; 32]).await.unwrap(), 750);
+        let requests = node.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].params, json!([hex::encode([1u8; 32])]));
+
+        // Queued after the first reply, which is used once more: a result
+        // missing the field is an error, not a zero balance
+        node.reply("get_balance", json!({}));
+        assert_eq!(client.balance([1u8; 32]).await.unwrap(), 750);
+        assert!(matches!(client.balance([1u8; 32]).await, Err(AxiomError::RpcError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_sends_transaction_and_returns_hash() {
+        let node = MockNode::start();
+        let tx = Transaction::new([1u8; 32], [2u8; 32], 100, 10, 0, vec![], vec![]);
+        node.reply("broadcast_transaction", json!({ "tx_hash": hex::encode(tx.hash()), "trace_id": "t" }));
+
+        let client = RpcClient::new(node.url());
+        assert_eq!(client.broadcast_transaction(&tx).await.unwrap(), tx.hash());
+        let sent: Transaction = serde_json::from_value(node.requests()[0].params[0].clone()).unwrap();
+        assert_eq!(sent, tx);
+    }
+
+    #[tokio::test]
+    async fn test_node_errors_surface_as_rpc_errors() {
+        let node = MockNode::start();
+        node.reply_error("broadcast_transaction", TX_REJECTED, "Insufficient balance");
+        node.reply_raw("get_chain_info", "{ not json");
+
+        let client = RpcClient::new(node.url());
+        let tx = Transaction::new([1u8; 32], [2u8; 32], 100, 10, 0, vec![], vec![]);
+        match client.broadcast_transaction(&tx).await {
+            Err(AxiomError::RpcError(message)) => assert_eq!(message, format!("Insufficient balance ({})", TX_REJECTED)),
+            other => panic!("expected an rpc error, got {:?}", other),
+        }
+        assert!(matches!(client.chain_info().await, Err(AxiomError::RpcError(_))));
+        // Unregistered methods are not found on the mock either
+        assert!(matches!(client.call("get_peers", json!([])).await, Err(AxiomError::RpcError(_))));
+
+        // Nothing listening at all is a network failure
+        let closed = RpcClient::new("http://127.0.0.1:1/rpc");
+        assert!(matches!(closed.chain_info().await, Err(AxiomError::NetworkError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_confirmation_across_polls() {
+        let node = MockNode::start();
+        let hash = [4u8; 32];
+        // Pending for two polls, then mined at height 5 and buried by a block
+        node.reply("get_transaction", json!({ "status": "pending", "block_height": null }))
+            .reply("get_transaction", json!({ "status": "pending", "block_height": null }))
+            .reply("get_transaction", json!({ "status": "confirmed", "block_height": 5 }));
+        node.reply("get_chain_info", json!({ "height": 5 })).reply("get_chain_info", json!({ "height": 6 }));
+
+        let client = RpcClient::new(node.url()).with_poll_interval(Duration::from_millis(10));
+        let status = client.wait_for_confirmation(hash, 2, Duration::from_secs(5)).await.unwrap();
+        assert_eq!(status, TxStatus::Confirmed { block: 5, depth: 2 });
+        assert_eq!(node.calls("get_transaction"), 4);
+        assert_eq!(node.requests()[0].params, json!([hex::encode(hash)]));
+
+        // Asking for more depth than arrives in time gives what was seen last
+        let status = client.wait_for_confirmation(hash, 10, Duration::from_millis(50)).await.unwrap();
+        assert_eq!(status, TxStatus::Confirmed { block: 5, depth: 2 });
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_confirmation_sees_drop() {
+        let node = MockNode::start();
+        node.reply("get_transaction", json!({ "status": "pending", "block_height": null }))
+            .reply_error("get_transaction", NOT_FOUND, "transaction not found");
+
+        let client = RpcClient::new(node.url()).with_poll_interval(Duration::from_millis(10));
+        let status = client.wait_for_confirmation([4u8; 32], 1, Duration::from_secs(5)).await.unwrap();
+        assert_eq!(status, TxStatus::Dropped);
+        assert_eq!(node.calls("get_transaction"), 2);
+
+        // Still pending at the deadline
+        let pending = MockNode::start();
+        pending.reply("get_transaction", json!({ "status": "pending", "block_height": null }));
+        let client = RpcClient::new(pending.url()).with_poll_interval(Duration::from_millis(10));
+        let status = client.wait_for_confirmation([4u8; 32], 1, Duration::from_millis(30)).await.unwrap();
+        assert_eq!(status, TxStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_reserve_nonce_counts_up_from_node() {
+        let node = MockNode::start();
+        node.reply("get_nonce", json!({ "address": hex::encode([1u8; 32]), "nonce": 7 }));
+        let client = RpcClient::new(node.url());
+
+        // Reserved together, before any transaction reaches the node
+        let (a, b, c) = tokio::join!(
+            client.reserve_nonce([1u8; 32]),
+            client.reserve_nonce([1u8; 32]),
+            client.reserve_nonce([1u8; 32]),
+        );
+        let mut reserved = vec![a.unwrap(), b.unwrap(), c.unwrap()];
+        reserved.sort();
+        assert_eq!(reserved, vec![7, 8, 9]);
+        assert_eq!(client.reserve_nonce([1u8; 32]).await.unwrap(), 10);
+        let fetched = node.calls("get_nonce");
+        assert!((1..=3).contains(&fetched));
+
+        // After a reset the node is asked again, and none of 7..=10 were
+        // mined
+        client.reset_nonce_cache([1u8; 32]);
+        assert_eq!(client.reserve_nonce([1u8; 32]).await.unwrap(), 7);
+        assert_eq!(node.calls("get_nonce"), fetched + 1);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_or_dropped_transaction_resets_nonces() {
+        let node = MockNode::start();
+        let tx = Transaction::new([1u8; 32], [2u8; 32], 100, 10, 3, vec![], vec![]);
+        node.reply("get_nonce", json!({ "nonce": 3 }));
+        node.reply_error("broadcast_transaction", TX_REJECTED, "Invalid nonce")
+            .reply("broadcast_transaction", json!({ "tx_hash": hex::encode(tx.hash()) }));
+        node.reply("get_transaction", json!({ "status": "pending", "block_height": null }))
+            .reply_error("get_transaction", NOT_FOUND, "transaction not found");
+        let client = RpcClient::new(node.url()).with_poll_interval(Duration::from_millis(10));
+
+        assert_eq!(client.reserve_nonce([1u8; 32]).await.unwrap(), 3);
+        assert!(client.broadcast_transaction(&tx).await.is_err());
+        assert_eq!(client.reserve_nonce([1u8; 32]).await.unwrap(), 3);
+        assert_eq!(node.calls("get_nonce"), 2);
+
+        let hash = client.broadcast_transaction(&tx).await.unwrap();
+        assert_eq!(client.reserve_nonce([1u8; 32]).await.unwrap(), 4);
+        let status = client.wait_for_confirmation(hash, 1, Duration::from_secs(5)).await.unwrap();
+        assert_eq!(status, TxStatus::Dropped);
+        assert_eq!(client.reserve_nonce([1u8; 32]).await.unwrap(), 3);
+        assert_eq!(node.calls("get_nonce"), 3);
+    }
+
+    #[tokio::test]
+    async fn test_not_found_is_none() {
+        let node = MockNode::start();
+        node.reply_error("get_block", NOT_FOUND, "no block at height 9");
+        node.reply_error("get_transaction", NOT_FOUND, "transaction not found");
+
+        let client = RpcClient::new(node.url());
+        assert_eq!(client.block(9).await.unwrap(), None);
+        assert_eq!(client.transaction([3u8; 32]).await.unwrap(), None);
+        assert_eq!(node.calls("get_block"), 1);
+        assert_eq!(node.calls("get_transaction"), 1);
+
+        // A found block comes back as the node sent it
+        let found = MockNode::start();
+        found.reply("get_block", json!({ "height": 2 }));
+        assert_eq!(RpcClient::new(found.url()).block(2).await.unwrap(), Some(json!({ "height": 2 })));
+    }
+}
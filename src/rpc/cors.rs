@@ -0,0 +1,91 @@
+//! CORS policy shared by the RPC server and the explorer.
+//!
+//! Built from `RpcConfig::cors_allowed_origins`: `"*"` admits any origin,
+//! otherwise only the listed origins (e.g. `https://explorer.axiom.network`)
+//! are allowed. An empty list denies every cross-origin request.
+
+use actix_cors::Cors;
+use actix_web::http::header;
+
+/// Whether `origin` is usable in `cors_allowed_origins`
+pub fn is_valid_origin(origin: &str) -> bool {
+    if origin == "*" {
+        return true;
+    }
+    let rest = match origin.strip_prefix("https://").or_else(|| origin.strip_prefix("http://")) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    !rest.is_empty() && !rest.contains('/') && !rest.chars().any(char::is_whitespace)
+}
+
+/// Build the CORS middleware for `origins`. Invalid entries are skipped
+/// with a warning rather than failing server startup.
+pub fn layer(origins: &[String]) -> Cors {
+    let cors = Cors::default()
+        .allowed_methods(vec!["GET", "POST", "OPTIONS"])
+        .allowed_headers(vec![header::CONTENT_TYPE, header::ACCEPT])
+        .max_age(3600);
+
+    if origins.iter().any(|o| o == "*") {
+        return cors.allow_any_origin().send_wildcard();
+    }
+    origins.iter().fold(cors, |cors, origin| {
+        if is_valid_origin(origin) {
+            cors.allowed_origin(origin)
+        } else {
+            eprintln!("⚠️  Ignoring invalid CORS origin: {:?}", origin);
+            cors
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, TestRequest};
+    use actix_web::{web, App, HttpResponse};
+
+    async fn allow_origin_header(origins: &[&str], origin: &str) -> Option<String> {
+        let origins: Vec<String> = origins.iter().map(|o| o.to_string()).collect();
+        let app = init_service(
+            App::new()
+                .wrap(layer(&origins))
+                .route("/", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+        let req = TestRequest::get().uri("/").insert_header(("Origin", origin)).to_request();
+        let resp = call_service(&app, req).await;
+        resp.headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .map(|v| v.to_str().unwrap().to_string())
+    }
+
+    #[actix_rt::test]
+    async fn test_exact_origins() {
+        let allowed = ["https://explorer.axiom.network", "http://localhost:3000"];
+        assert_eq!(
+            allow_origin_header(&allowed, "http://localhost:3000").await.as_deref(),
+            Some("http://localhost:3000")
+        );
+        assert_eq!(allow_origin_header(&allowed, "https://evil.example").await, None);
+    }
+
+    #[actix_rt::test]
+    async fn test_wildcard_and_empty() {
+        assert_eq!(allow_origin_header(&["*"], "https://anything.example").await.as_deref(), Some("*"));
+        assert_eq!(allow_origin_header(&[], "http://localhost:3000").await, None);
+        // Bad entries are dropped, not fatal
+        assert_eq!(allow_origin_header(&["not an origin"], "http://localhost:3000").await, None);
+    }
+
+    #[test]
+    fn test_origin_validation() {
+        assert!(is_valid_origin("*"));
+        assert!(is_valid_origin("https://explorer.axiom.network"));
+        assert!(is_valid_origin("http://127.0.0.1:8080"));
+        assert!(!is_valid_origin("explorer.axiom.network"));
+        assert!(!is_valid_origin("https://explorer.axiom.network/path"));
+        assert!(!is_valid_origin("https://"));
+    }
+}
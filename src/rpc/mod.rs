@@ -0,0 +1,1022 @@
+//! JSON-RPC 2.0 server for wallets, explorers and the SDK.
+//!
+//! Requests are POSTed to `/rpc`, either singly or as a JSON-RPC batch
+//! (an array of requests answered by an array of responses). Parameters may
+//! be passed either by position (`["<address>"]`) or by name
+//! (`{"address": "<address>"}`). Hashes and addresses are hex encoded.
+//! Subscriptions are served over WebSocket on `/ws` (see [`ws`]). Each
+//! client IP is limited to `rate_limit` requests per minute (see
+//! [`rate_limit`]). Cross-origin access follows `cors_allowed_origins`
+//! (see [`cors`]). Orchestrators probe `/health/live` (the process is up)
+//! and `/health/ready` (synced and peered; 503 otherwise). Proof generation
+//! is admission-controlled (see [`proof_queue`]). Submitted transactions
+//! are traced through the node (see [`trace`]). `get_chain_info` reports
+//! the genesis hash and network id, which [`client`] checks before talking
+//! to a node it was pinned to. Methods are typed handlers looked up by name
+//! (see [`registry`]). Proofs too large for one response are fetched in
+//! chunks with `get_proof_chunk` (see [`proof_store`]).
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use actix_web::{web, App, HttpResponse, HttpServer, Responder};
+use ark_bls12_381::Fr;
+use ark_serialize::CanonicalSerialize;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::{broadcast, mpsc};
+
+use crate::block::Block;
+use crate::cache::BlockCache;
+use crate::chain::{Timechain, TransactionSimulation};
+use crate::config::RpcConfig;
+use crate::crypto::quantum_safe_stark::{QuantumSafeStarkProver, DEFAULT_TRACE_LENGTH};
+use crate::main_helper::compute_vdf;
+use crate::mempool::Mempool;
+use crate::merkle::MerkleProof;
+use crate::sync::{SyncStatus, SyncTracker};
+use crate::transaction::{Address, Transaction};
+use crate::wallet::Wallet;
+use crate::zk::backend::{PrivateTransactionProof, PrivateTransferWitness, ProofSystem};
+use crate::{genesis, vdf};
+use crate::zk::circuit::{bytes_to_fr, ZkProofSystem};
+use proof_store::{ProofChunk, ProofTicket};
+use registry::{Encoded, Hex32, Hex64, NoParams};
+
+pub mod client;
+pub mod cors;
+pub mod proof_queue;
+pub mod proof_store;
+pub mod rate_limit;
+pub mod registry;
+#[cfg(test)]
+pub mod testutil;
+pub mod trace;
+pub mod ws;
+pub use client::RpcClient;
+pub use proof_queue::ProofQueue;
+pub use proof_store::{ProofAssembler, ProofStore};
+pub use rate_limit::RateLimiter;
+pub use registry::MethodRegistry;
+pub use trace::TxTracer;
+pub use ws::NodeEvent;
+
+// ==================== ERROR CODES ====================
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+/// Requested block or transaction does not exist
+pub const NOT_FOUND: i64 = -32001;
+/// Transaction failed validation
+pub const TX_REJECTED: i64 = -32002;
+/// Submitted block failed validation
+pub const BLOCK_REJECTED: i64 = -32003;
+/// Too many proofs running and queued; retry later
+pub const SERVER_BUSY: i64 = -32004;
+/// Client exceeded its request rate; retry after the advertised delay
+pub const RATE_LIMITED: i64 = -32029;
+
+/// Maximum number of requests accepted in one batch
+pub const MAX_BATCH_SIZE: usize = 100;
+
+/// Maximum number of blocks one `generate_blocks` call may mine
+pub const MAX_GENERATE_BLOCKS: u64 = 1000;
+
+/// Page size of `get_address_history` when no `limit` is given
+pub const DEFAULT_HISTORY_LIMIT: u64 = 50;
+/// Largest page `get_address_history` returns
+pub const MAX_HISTORY_LIMIT: u64 = 500;
+
+// ==================== WIRE TYPES ====================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcRequest {
+    #[serde(default = "jsonrpc_version")]
+    pub jsonrpc: String,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+    #[serde(default)]
+    pub id: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcResponse {
+    pub jsonrpc: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+    pub id: Value,
+}
+
+impl RpcResponse {
+    pub fn from_result(id: Value, result: Result<Value, RpcError>) -> Self {
+        let (result, error) = match result {
+            Ok(value) => (Some(value), None),
+            Err(e) => (None, Some(e)),
+        };
+        Self { jsonrpc: jsonrpc_version(), result, error, id }
+    }
+}
+
+fn jsonrpc_version() -> String {
+    "2.0".to_string()
+}
+
+// ==================== NODE STATE ====================
+
+/// Live node state shared between the main loop and the RPC server
+#[derive(Clone)]
+pub struct RpcState {
+    pub chain: Arc<Mutex<Timechain>>,
+    /// The node's pool; its events should go to the same channel as
+    /// `events` so subscribers see replacements and evictions
+    pub mempool: Arc<Mutex<Mempool>>,
+    /// Accepted transactions are forwarded here for P2P gossip
+    pub tx_relay: Option<mpsc::UnboundedSender<Transaction>>,
+    /// Node event feed for WebSocket subscribers
+    pub events: Option<broadcast::Sender<NodeEvent>>,
+    /// Groth16 keys loaded at startup, used by `generate_zk_proof`
+    pub zk: Option<Arc<ZkProofSystem>>,
+    /// Regtest only: wallet credited by `generate_blocks`. `None` disables
+    /// the method.
+    pub regtest_miner: Option<Arc<Wallet>>,
+    /// Blocks produced over RPC are forwarded here for storage and gossip
+    pub block_relay: Option<mpsc::UnboundedSender<Block>>,
+    /// Peer heights seen by the P2P loop, for `get_sync_status`
+    pub sync: Option<Arc<Mutex<SyncTracker>>>,
+    /// Connected peer count kept by the P2P loop, for `/health/ready`
+    pub peers: Option<Arc<AtomicUsize>>,
+    /// Peers required before `/health/ready` reports ready
+    pub min_peers: usize,
+    /// Admission control for `generate_zk_proof` and `generate_stark_proof`
+    pub proofs: ProofQueue,
+    /// Proofs too large to return inline, for `get_proof_chunk`
+    pub proof_store: Arc<ProofStore>,
+    /// Where confirmed transactions were last found
+    pub cache: Arc<Mutex<BlockCache>>,
+    /// Stages of transactions submitted here, shared with the main loop
+    pub traces: Arc<TxTracer>,
+    /// Methods served, `node_methods` unless replaced
+    pub methods: Arc<MethodRegistry>,
+}
+
+impl RpcState {
+    pub fn new(chain: Arc<Mutex<Timechain>>, mempool: Arc<Mutex<Mempool>>) -> Self {
+        Self {
+            chain,
+            mempool,
+            tx_relay: None,
+            events: None,
+            zk: None,
+            regtest_miner: None,
+            block_relay: None,
+            sync: None,
+            peers: None,
+            min_peers: 0,
+            proofs: ProofQueue::default(),
+            proof_store: Arc::new(ProofStore::default()),
+            cache: Arc::new(Mutex::new(BlockCache::default())),
+            traces: Arc::new(TxTracer::default()),
+            methods: Arc::new(node_methods()),
+        }
+    }
+
+    pub fn with_tx_relay(mut self, relay: mpsc::UnboundedSender<Transaction>) -> Self {
+        self.tx_relay = Some(relay);
+        self
+    }
+
+    pub fn with_events(mut self, events: broadcast::Sender<NodeEvent>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    pub fn with_zk_system(mut self, zk: Arc<ZkProofSystem>) -> Self {
+        self.zk = Some(zk);
+        self
+    }
+
+    /// Enable `generate_blocks`, crediting rewards to `miner`. Only for
+    /// regtest nodes; the method still refuses to extend the mainnet genesis.
+    pub fn with_regtest_miner(mut self, miner: Arc<Wallet>) -> Self {
+        self.regtest_miner = Some(miner);
+        self
+    }
+
+    pub fn with_block_relay(mut self, relay: mpsc::UnboundedSender<Block>) -> Self {
+        self.block_relay = Some(relay);
+        self
+    }
+
+    pub fn with_sync(mut self, sync: Arc<Mutex<SyncTracker>>) -> Self {
+        self.sync = Some(sync);
+        self
+    }
+
+    /// Report `peers` in `/health/ready`, which stays unready below
+    /// `min_peers`
+    pub fn with_peers(mut self, peers: Arc<AtomicUsize>, min_peers: usize) -> Self {
+        self.peers = Some(peers);
+        self.min_peers = min_peers;
+        self
+    }
+
+    /// Run at most `max_concurrent` proofs at once with up to `max_queued`
+    /// waiting
+    pub fn with_proof_limits(mut self, max_concurrent: usize, max_queued: usize) -> Self {
+        self.proofs = ProofQueue::new(max_concurrent, max_queued);
+        self
+    }
+
+    /// Keep up to `capacity` large proofs for `get_proof_chunk`, served
+    /// `chunk_size` bytes at a time; smaller proofs are returned inline
+    pub fn with_proof_store(mut self, capacity: usize, chunk_size: usize) -> Self {
+        self.proof_store = Arc::new(ProofStore::new(capacity, chunk_size));
+        self
+    }
+
+    /// Cache up to `capacity` blocks for transaction lookups
+    pub fn with_block_cache(mut self, capacity: usize) -> Self {
+        self.cache = Arc::new(Mutex::new(BlockCache::new(capacity)));
+        self
+    }
+
+    /// Record transaction stages in `traces`, which the main loop also
+    /// updates as it gossips and mines
+    pub fn with_tracer(mut self, traces: Arc<TxTracer>) -> Self {
+        self.traces = traces;
+        self
+    }
+
+    /// Serve `methods` instead of `node_methods`, e.g. the node's methods
+    /// plus extra ones registered on top
+    pub fn with_methods(mut self, methods: MethodRegistry) -> Self {
+        self.methods = Arc::new(methods);
+        self
+    }
+
+    /// Local tip height and its sync status against known peers
+    fn sync_status(&self) -> (u64, SyncStatus) {
+        let height = self.chain.lock().unwrap().blocks.len() as u64 - 1;
+        let status = match &self.sync {
+            Some(sync) => sync.lock().unwrap().status(height),
+            None => SyncStatus::Synced,
+        };
+        (height, status)
+    }
+
+    /// Publish an event to subscribers, if any are listening
+    pub fn notify(&self, event: NodeEvent) {
+        if let Some(events) = &self.events {
+            let _ = events.send(event);
+        }
+    }
+}
+
+// ==================== DISPATCH ====================
+
+/// Execute a single request against the node state
+pub fn dispatch(state: &RpcState, request: &RpcRequest) -> RpcResponse {
+    let result = if request.jsonrpc != "2.0" {
+        Err(RpcError::new(INVALID_REQUEST, "jsonrpc must be \"2.0\""))
+    } else {
+        state.methods.call(state, &request.method, &request.params)
+    };
+    RpcResponse::from_result(request.id.clone(), result)
+}
+
+// ==================== METHODS ====================
+
+#[derive(Deserialize)]
+struct AddressParams {
+    address: Hex32,
+}
+
+#[derive(Deserialize)]
+struct HashParams {
+    hash: Hex32,
+}
+
+#[derive(Deserialize)]
+struct TransactionParams {
+    transaction: Encoded<Transaction>,
+}
+
+#[derive(Deserialize)]
+struct HistoryParams {
+    address: Hex32,
+    #[serde(default)]
+    offset: Option<u64>,
+    #[serde(default)]
+    limit: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct HeightParams {
+    height: u64,
+}
+
+#[derive(Deserialize)]
+struct ZkProofParams {
+    secret_key: Hex32,
+    balance: u64,
+    nonce: u64,
+    amount: u64,
+    fee: u64,
+}
+
+#[derive(Deserialize)]
+struct StarkProofParams {
+    secret_key: Hex32,
+    sender: Hex32,
+    receiver: Hex32,
+    balance: u64,
+    amount: u64,
+    fee: u64,
+    nonce: u64,
+    signature: Hex64,
+}
+
+#[derive(Deserialize)]
+struct ProofChunkParams {
+    id: Hex32,
+    #[serde(default)]
+    offset: u64,
+}
+
+#[derive(Deserialize)]
+struct CountParams {
+    count: u64,
+}
+
+#[derive(Deserialize)]
+struct MinerParams {
+    miner: Hex32,
+}
+
+#[derive(Deserialize)]
+struct BlockParams {
+    block: Encoded<Block>,
+}
+
+/// The methods every node serves
+pub fn node_methods() -> MethodRegistry {
+    let mut methods = MethodRegistry::new();
+    methods
+        .register("get_balance", |state: &RpcState, AddressParams { address: Hex32(address) }| {
+            let balance = state.chain.lock().unwrap().balance(&address);
+            Ok(json!({ "address": hex::encode(address), "balance": balance }))
+        })
+        .register("get_nonce", |state: &RpcState, AddressParams { address: Hex32(address) }| {
+            let nonce = state.chain.lock().unwrap().state.nonce(&address);
+            Ok(json!({ "address": hex::encode(address), "nonce": nonce }))
+        })
+        .register("broadcast_transaction", |state: &RpcState, params: TransactionParams| {
+            broadcast_transaction(state, params.transaction.0)
+        })
+        .register("simulate_transaction", |state: &RpcState, params: TransactionParams| {
+            let tx = params.transaction.0;
+            let simulation = state.chain.lock().unwrap().simulate_transaction(&tx);
+            Ok(simulation_json(&tx, &simulation))
+        })
+        .register("get_transaction", |state: &RpcState, HashParams { hash: Hex32(hash) }| {
+            find_transaction(state, &hash)
+        })
+        .register("get_address_history", |state: &RpcState, params: HistoryParams| {
+            let offset = params.offset.unwrap_or(0);
+            let limit = params.limit.unwrap_or(DEFAULT_HISTORY_LIMIT);
+            address_history(state, params.address.0, offset, limit)
+        })
+        .register("get_merkle_proof", |state: &RpcState, HashParams { hash: Hex32(hash) }| {
+            merkle_proof(state, &hash)
+        })
+        .register("get_block", |state: &RpcState, HeightParams { height }| {
+            let chain = state.chain.lock().unwrap();
+            chain
+                .blocks
+                .get(height as usize)
+                .map(block_json)
+                .ok_or_else(|| RpcError::new(NOT_FOUND, format!("no block at height {}", height)))
+        })
+        .register("get_latest_block", |state: &RpcState, _: NoParams| {
+            let chain = state.chain.lock().unwrap();
+            Ok(block_json(chain.blocks.last().unwrap()))
+        })
+        .register("get_chain_info", |state: &RpcState, _: NoParams| {
+            let chain = state.chain.lock().unwrap();
+            let mempool_size = state.mempool.lock().unwrap().len();
+            let tip = chain.blocks.last().unwrap();
+            Ok(json!({
+                "height": tip.slot,
+                "tip_hash": hex::encode(tip.hash()),
+                "genesis_hash": hex::encode(chain.blocks[0].hash()),
+                "network_id": chain.network_id,
+                "difficulty": chain.difficulty,
+                "total_issued": chain.total_issued,
+                "total_burned": chain.total_burned,
+                "mempool_size": mempool_size,
+            }))
+        })
+        .register("get_sync_status", |state: &RpcState, _: NoParams| {
+            let (height, status) = state.sync_status();
+            let target = match status {
+                SyncStatus::Syncing { target, .. } => target,
+                SyncStatus::Synced => height,
+            };
+            // Blocks fetched / needed by the batched download under way
+            let download = state.sync.as_ref().and_then(|sync| sync.lock().unwrap().download());
+            Ok(json!({
+                "synced": status.is_synced(),
+                "current": height,
+                "target": target,
+                "progress": status.progress(),
+                "download": download,
+            }))
+        })
+        .register("audit_supply", |state: &RpcState, _: NoParams| {
+            let chain = state.chain.lock().unwrap();
+            let height = chain.blocks.len() as u64 - 1;
+            let total = chain.state.total_balance();
+            Ok(match chain.audit_supply() {
+                Ok(()) => json!({ "height": height, "ok": true, "expected": total, "actual": total, "delta": 0 }),
+                Err(mismatch) => json!({
+                    "height": mismatch.height,
+                    "ok": false,
+                    "expected": mismatch.expected,
+                    "actual": mismatch.actual,
+                    "delta": mismatch.delta,
+                }),
+            })
+        })
+        .register("generate_zk_proof", generate_zk_proof)
+        .register("generate_stark_proof", generate_stark_proof)
+        .register("get_proof_chunk", |state: &RpcState, ProofChunkParams { id: Hex32(id), offset }| {
+            let ticket = state
+                .proof_store
+                .ticket(&id)
+                .ok_or_else(|| RpcError::new(NOT_FOUND, format!("proof {} not stored", hex::encode(id))))?;
+            let chunk = state.proof_store.chunk(&id, offset).ok_or_else(|| {
+                RpcError::new(
+                    INVALID_PARAMS,
+                    format!("'offset' must be a multiple of {} below {}", ticket.chunk_size, ticket.size.max(1)),
+                )
+            })?;
+            Ok(proof_chunk_json(&chunk))
+        })
+        .register("generate_blocks", |state: &RpcState, CountParams { count }| generate_blocks(state, count))
+        .register("get_block_template", |state: &RpcState, MinerParams { miner: Hex32(miner) }| {
+            block_template(state, miner)
+        })
+        .register("submit_block", |state: &RpcState, params: BlockParams| submit_block(state, params.block.0));
+    methods
+}
+
+/// Validate `tx` and admit it through the mempool, which may replace a
+/// pending transaction with the same nonce (see `Mempool::add`). Sending
+/// one that is already pending again is not an error.
+fn broadcast_transaction(state: &RpcState, tx: Transaction) -> Result<Value, RpcError> {
+    let chain = state.chain.lock().unwrap();
+    chain
+        .validate_transaction(&tx)
+        .map_err(|e| RpcError::new(TX_REJECTED, e.to_string()))?;
+
+    let hash = tx.hash();
+    let mut mempool = state.mempool.lock().unwrap();
+    let added = !mempool.contains(&hash);
+    if added {
+        mempool
+            .add(tx.clone(), &chain.state)
+            .map_err(|e| RpcError::new(TX_REJECTED, e.to_string()))?;
+    }
+    let trace_id = state.traces.start(&hash);
+    if added {
+        state.traces.record(&hash, trace::Stage::MempoolAdded);
+        if let Some(relay) = &state.tx_relay {
+            let _ = relay.send(tx);
+        }
+    }
+    Ok(json!({ "tx_hash": hex::encode(hash), "trace_id": trace_id }))
+}
+
+/// Dry run of `broadcast_transaction`: each check's result and the balance
+/// changes, with nothing added to the mempool
+fn simulation_json(tx: &Transaction, simulation: &TransactionSimulation) -> Value {
+    let checks: Vec<Value> = simulation
+        .checks
+        .iter()
+        .map(|(name, result)| {
+            json!({ "check": name, "passed": result.is_ok(), "error": result.as_ref().err().map(ToString::to_string) })
+        })
+        .collect();
+    // Deltas past i64 exceed the supply cap, and such a transaction fails
+    // the balance check anyway
+    let balance_delta: serde_json::Map<String, Value> = simulation
+        .balance_changes
+        .iter()
+        .map(|(address, delta)| (hex::encode(address), json!((*delta).clamp(i64::MIN as i128, i64::MAX as i128) as i64)))
+        .collect();
+    json!({
+        "tx_hash": hex::encode(tx.hash()),
+        "would_succeed": simulation.would_succeed(),
+        "failure": simulation.failure().map(|(name, _)| name),
+        "checks": checks,
+        "balance_delta": balance_delta,
+    })
+}
+
+/// A transaction, its status and, if it was submitted here recently, its
+/// trace
+fn find_transaction(state: &RpcState, hash: &[u8; 32]) -> Result<Value, RpcError> {
+    let trace = state.traces.get(hash);
+    if let Some(tx) = state.mempool.lock().unwrap().get(hash) {
+        return Ok(json!({ "transaction": tx_json(tx), "status": "pending", "block_height": null, "trace": trace }));
+    }
+    let chain = state.chain.lock().unwrap();
+    if let Some(block) = confirming_block(state, &chain, hash) {
+        if let Some(tx) = block.transactions.iter().find(|tx| &tx.hash() == hash) {
+            return Ok(json!({
+                "transaction": tx_json(tx),
+                "status": "confirmed",
+                "block_height": block.slot,
+                "trace": trace,
+            }));
+        }
+    }
+    Err(RpcError::new(NOT_FOUND, format!("transaction {} not found", hex::encode(hash))))
+}
+
+/// A page of the confirmed transactions sent or received by `address`,
+/// newest first, from the chain's address index
+fn address_history(state: &RpcState, address: Address, offset: u64, limit: u64) -> Result<Value, RpcError> {
+    if limit == 0 || limit > MAX_HISTORY_LIMIT {
+        return Err(RpcError::new(
+            INVALID_PARAMS,
+            format!("'limit' must be between 1 and {}", MAX_HISTORY_LIMIT),
+        ));
+    }
+    let chain = state.chain.lock().unwrap();
+    let offset = usize::try_from(offset).unwrap_or(usize::MAX);
+    let transactions: Vec<Value> = chain
+        .address_history(&address, offset, limit as usize)
+        .into_iter()
+        .map(|(location, tx)| {
+            let mut entry = tx_json(tx);
+            entry["block_height"] = json!(location.height);
+            entry
+        })
+        .collect();
+    Ok(json!({
+        "address": hex::encode(address),
+        "total": chain.state.history_len(&address),
+        "offset": offset,
+        "transactions": transactions,
+    }))
+}
+
+/// Block of `chain` holding the transaction `hash`. The cache answers
+/// without a scan unless a reorg has since replaced the block it recorded,
+/// in which case everything from that height is invalidated. The cache is
+/// not held during a scan.
+fn confirming_block<'a>(state: &RpcState, chain: &'a Timechain, hash: &[u8; 32]) -> Option<&'a Block> {
+    {
+        let mut cache = state.cache.lock().unwrap();
+        if let Some(at) = cache.locate(hash) {
+            match chain.blocks.get(at.height as usize) {
+                Some(block) if block.hash() == at.block_hash => return Some(block),
+                _ => cache.invalidate_from(at.height),
+            }
+        }
+    }
+    let block = chain
+        .blocks
+        .iter()
+        .rev()
+        .find(|block| block.transactions.iter().any(|tx| &tx.hash() == hash))?;
+    state.cache.lock().unwrap().insert(block.slot, Arc::new(block.clone()));
+    Some(block)
+}
+
+/// Inclusion proof for a confirmed transaction against its block's Merkle root
+fn merkle_proof(state: &RpcState, hash: &[u8; 32]) -> Result<Value, RpcError> {
+    let chain = state.chain.lock().unwrap();
+    if let Some(block) = confirming_block(state, &chain, hash) {
+        if let Some(proof) = block.merkle_proof(*hash) {
+            return Ok(json!({
+                "tx_hash": hex::encode(hash),
+                "block_height": block.slot,
+                "block_hash": hex::encode(block.hash()),
+                "merkle_root": hex::encode(block.merkle_root),
+                "proof": merkle_proof_json(&proof),
+            }));
+        }
+    }
+    Err(RpcError::new(NOT_FOUND, format!("transaction {} not in any block", hex::encode(hash))))
+}
+
+/// Groth16 transfer proof made with the node's persisted keys
+fn generate_zk_proof(state: &RpcState, params: ZkProofParams) -> Result<Value, RpcError> {
+    let zk = state
+        .zk
+        .as_ref()
+        .ok_or_else(|| RpcError::new(INTERNAL_ERROR, "ZK proving keys not loaded"))?;
+    let ZkProofParams { secret_key: Hex32(secret_key), balance, nonce, amount, fee } = params;
+    if amount.checked_add(fee).is_none() {
+        return Err(RpcError::new(INVALID_PARAMS, "amount + fee overflows"));
+    }
+    let _permit = state
+        .proofs
+        .acquire()
+        .ok_or_else(|| RpcError::new(SERVER_BUSY, "proof queue is full, retry later"))?;
+    let (proof, public_inputs) = zk
+        .prove(
+            bytes_to_fr(&secret_key),
+            Fr::from(balance),
+            Fr::from(nonce),
+            Fr::from(amount),
+            Fr::from(fee),
+        )
+        .map_err(|e| RpcError::new(INVALID_PARAMS, e))?;
+
+    Ok(json!({
+        "proof": ark_hex(&proof),
+        "public_inputs": public_inputs.iter().map(ark_hex).collect::<Vec<_>>(),
+    }))
+}
+
+/// STARK transfer proof. Returned inline when it fits in one chunk of the
+/// proof store; otherwise stored, and the response carries the `proof_id`
+/// to fetch it by with `get_proof_chunk`.
+fn generate_stark_proof(state: &RpcState, params: StarkProofParams) -> Result<Value, RpcError> {
+    let witness = PrivateTransferWitness {
+        secret_key: params.secret_key.0,
+        sender: params.sender.0,
+        receiver: params.receiver.0,
+        balance: params.balance,
+        amount: params.amount,
+        fee: params.fee,
+        nonce: params.nonce,
+        signature: params.signature.0,
+    };
+    let _permit = state
+        .proofs
+        .acquire()
+        .ok_or_else(|| RpcError::new(SERVER_BUSY, "proof queue is full, retry later"))?;
+    let prover = QuantumSafeStarkProver::new(256, DEFAULT_TRACE_LENGTH, 4);
+    let proof = PrivateTransactionProof::create(&prover, &witness)
+        .map_err(|e| RpcError::new(INVALID_PARAMS, e))?
+        .proof;
+
+    if !state.proof_store.needs_chunking(proof.len()) {
+        return Ok(json!({ "system": ProofSystem::Stark, "size": proof.len(), "proof": hex::encode(proof) }));
+    }
+    let ticket = state.proof_store.insert(proof);
+    Ok(json!({
+        "system": ProofSystem::Stark,
+        "size": ticket.size,
+        "chunk_size": ticket.chunk_size,
+        "proof_id": hex::encode(ticket.id),
+    }))
+}
+
+/// Regtest: mine `count` blocks on top of the tip immediately, including
+/// whatever valid transactions are pending
+fn generate_blocks(state: &RpcState, count: u64) -> Result<Value, RpcError> {
+    let miner = state
+        .regtest_miner
+        .as_ref()
+        .ok_or_else(|| RpcError::new(METHOD_NOT_FOUND, "generate_blocks is only available in regtest"))?;
+    if count == 0 || count > MAX_GENERATE_BLOCKS {
+        return Err(RpcError::new(
+            INVALID_PARAMS,
+            format!("'count' must be between 1 and {}", MAX_GENERATE_BLOCKS),
+        ));
+    }
+
+    let mut chain = state.chain.lock().unwrap();
+    if chain.blocks[0].hash() == genesis::mainnet().hash() {
+        return Err(RpcError::new(INVALID_REQUEST, "generate_blocks cannot extend the mainnet chain"));
+    }
+    let mut hashes = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let candidates = state.mempool.lock().unwrap().get_for_mining(usize::MAX);
+        let (selected, _) = chain.select_transactions(miner.address, candidates);
+
+        let mut block = chain.next_block(miner, selected);
+        block.vdf_proof = compute_vdf(vdf::evaluate(block.parent, block.slot), chain.vdf_steps);
+        while !block.meets_difficulty() {
+            block.nonce += 1;
+        }
+        let block_time = chain.block_time;
+        chain
+            .add_block(block.clone(), block_time)
+            .map_err(|e| RpcError::new(INTERNAL_ERROR, e))?;
+
+        // The pool announces what the block mined
+        state.mempool.lock().unwrap().remove_mined(&block);
+        hashes.push(hex::encode(block.hash()));
+        state.traces.record_block(&block);
+        state.notify(NodeEvent::NewBlock(block.clone()));
+        if let Some(relay) = &state.block_relay {
+            let _ = relay.send(block);
+        }
+    }
+    Ok(json!({ "height": chain.blocks.len() - 1, "hashes": hashes }))
+}
+
+/// Everything an external miner needs to build the next block for `miner`.
+/// `transactions` (hex bincode, coinbase first) go into the block as given,
+/// with `timestamp` and `difficulty` in its header; the miner adds its ZK
+/// pass, the VDF output for `vdf_seed` after `vdf_iterations` rounds of
+/// `compute_vdf`, and a nonce meeting `difficulty`, then hands the block to
+/// `submit_block`.
+fn block_template(state: &RpcState, miner: Address) -> Result<Value, RpcError> {
+    let chain = state.chain.lock().unwrap();
+    // Best-paying first, as far as the senders cover them together with
+    // nonces in sequence and the block limits allow
+    let candidates = state.mempool.lock().unwrap().get_for_mining(usize::MAX);
+    let (selected, _) = chain.select_transactions(miner, candidates);
+    let template = chain.block_template(miner, selected);
+    let coinbase = template.coinbase().expect("templates start with a coinbase");
+    Ok(json!({
+        "height": template.slot,
+        "parent": hex::encode(template.parent),
+        "timestamp": template.timestamp,
+        "difficulty": template.difficulty,
+        "vdf_seed": hex::encode(vdf::evaluate(template.parent, template.slot)),
+        "vdf_iterations": chain.vdf_steps,
+        "miner": hex::encode(miner),
+        "coinbase": tx_json(coinbase),
+        "merkle_root": hex::encode(template.merkle_root),
+        "transactions": template
+            .transactions
+            .iter()
+            .map(|tx| bincode::serialize(tx).map(hex::encode))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| RpcError::new(INTERNAL_ERROR, e.to_string()))?,
+    }))
+}
+
+/// Validate a mined block, add it to the chain and pass it on for storage
+/// and gossip
+fn submit_block(state: &RpcState, block: Block) -> Result<Value, RpcError> {
+    let mut chain = state.chain.lock().unwrap();
+    let block_time = chain.block_time;
+    chain
+        .add_block(block.clone(), block_time)
+        .map_err(|e| RpcError::new(BLOCK_REJECTED, e))?;
+    // The pool announces what the block mined
+    state.mempool.lock().unwrap().remove_mined(&block);
+
+    let hash = hex::encode(block.hash());
+    state.traces.record_block(&block);
+    state.notify(NodeEvent::NewBlock(block.clone()));
+    if let Some(relay) = &state.block_relay {
+        let _ = relay.send(block);
+    }
+    Ok(json!({ "height": chain.blocks.len() - 1, "hash": hash }))
+}
+
+/// Execute a raw JSON-RPC payload: a single request object or a batch array.
+/// Each batch entry is handled independently, so one bad entry only
+/// produces an error response in its own slot.
+pub fn dispatch_payload(state: &RpcState, payload: Value) -> Value {
+    match payload {
+        Value::Array(entries) if entries.is_empty() => {
+            to_value(error_response(Value::Null, INVALID_REQUEST, "empty batch"))
+        }
+        Value::Array(entries) if entries.len() > MAX_BATCH_SIZE => to_value(error_response(
+            Value::Null,
+            INVALID_REQUEST,
+            format!("batch exceeds {} requests", MAX_BATCH_SIZE),
+        )),
+        Value::Array(entries) => Value::Array(
+            entries.into_iter().map(|entry| to_value(dispatch_value(state, entry))).collect(),
+        ),
+        single => to_value(dispatch_value(state, single)),
+    }
+}
+
+pub(super) fn dispatch_value(state: &RpcState, entry: Value) -> RpcResponse {
+    let id = entry.get("id").cloned().unwrap_or(Value::Null);
+    match serde_json::from_value::<RpcRequest>(entry) {
+        Ok(request) => dispatch(state, &request),
+        Err(e) => error_response(id, INVALID_REQUEST, e.to_string()),
+    }
+}
+
+pub(super) fn error_response(id: Value, code: i64, message: impl Into<String>) -> RpcResponse {
+    RpcResponse::from_result(id, Err(RpcError::new(code, message)))
+}
+
+pub(super) fn to_value(response: RpcResponse) -> Value {
+    serde_json::to_value(response).unwrap_or(Value::Null)
+}
+
+/// Client-side demultiplexing of a batch reply: returns one result per
+/// entry of `ids`, in that order, matched by `id` regardless of the order
+/// the server answered in. Missing responses become `INTERNAL_ERROR`.
+pub fn demux_batch(ids: &[Value], responses: Vec<RpcResponse>) -> Vec<Result<Value, RpcError>> {
+    let mut by_id: Vec<(Value, RpcResponse)> = responses.into_iter().map(|r| (r.id.clone(), r)).collect();
+    ids.iter()
+        .map(|id| match by_id.iter().position(|(rid, _)| rid == id) {
+            Some(pos) => {
+                let (_, response) = by_id.swap_remove(pos);
+                match (response.result, response.error) {
+                    (_, Some(error)) => Err(error),
+                    (Some(result), None) => Ok(result),
+                    (None, None) => Ok(Value::Null),
+                }
+            }
+            None => Err(RpcError::new(INTERNAL_ERROR, format!("no response for id {}", id))),
+        })
+        .collect()
+}
+
+// ==================== ENCODING ====================
+
+/// Hex of an arkworks value's compressed encoding
+fn ark_hex<T: CanonicalSerialize>(value: &T) -> String {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).expect("serializing to a Vec cannot fail");
+    hex::encode(bytes)
+}
+
+pub fn tx_json(tx: &Transaction) -> Value {
+    json!({
+        "hash": hex::encode(tx.hash()),
+        "from": hex::encode(tx.from),
+        "to": hex::encode(tx.to),
+        "amount": tx.amount,
+        "fee": tx.fee,
+        "nonce": tx.nonce,
+        "chain_id": tx.chain_id,
+    })
+}
+
+pub fn block_json(block: &Block) -> Value {
+    json!({
+        "height": block.slot,
+        "hash": hex::encode(block.hash()),
+        "parent": hex::encode(block.parent),
+        "miner": hex::encode(block.miner),
+        "nonce": block.nonce,
+        "vdf_proof": hex::encode(block.vdf_proof),
+        "merkle_root": hex::encode(block.merkle_root),
+        "transactions": block.transactions.iter().map(tx_json).collect::<Vec<_>>(),
+    })
+}
+
+pub fn merkle_proof_json(proof: &MerkleProof) -> Value {
+    json!({
+        "index": proof.index,
+        "siblings": proof.siblings.iter().map(hex::encode).collect::<Vec<_>>(),
+    })
+}
+
+/// `get_proof_chunk` response: the chunk, where it goes, and its proof
+/// against the proof id
+pub fn proof_chunk_json(chunk: &ProofChunk) -> Value {
+    let end = chunk.offset + chunk.data.len() as u64;
+    json!({
+        "proof_id": hex::encode(chunk.ticket.id),
+        "size": chunk.ticket.size,
+        "chunk_size": chunk.ticket.chunk_size,
+        "offset": chunk.offset,
+        "data": hex::encode(&chunk.data),
+        "proof": merkle_proof_json(&chunk.proof),
+        "next_offset": if end < chunk.ticket.size { json!(end) } else { Value::Null },
+    })
+}
+
+/// Parse a `get_proof_chunk` response
+pub fn proof_chunk_from_json(value: &Value) -> Option<ProofChunk> {
+    let id = hex::decode(value.get("proof_id")?.as_str()?).ok()?.try_into().ok()?;
+    let ticket = ProofTicket {
+        id,
+        size: value.get("size")?.as_u64()?,
+        chunk_size: value.get("chunk_size")?.as_u64()?,
+    };
+    Some(ProofChunk {
+        ticket,
+        offset: value.get("offset")?.as_u64()?,
+        data: hex::decode(value.get("data")?.as_str()?).ok()?,
+        proof: merkle_proof_from_json(value.get("proof")?)?,
+    })
+}
+
+/// Parse the `proof` object returned by `get_merkle_proof`
+pub fn merkle_proof_from_json(value: &Value) -> Option<MerkleProof> {
+    let index = value.get("index")?.as_u64()?;
+    let siblings = value
+        .get("siblings")?
+        .as_array()?
+        .iter()
+        .map(|s| hex::decode(s.as_str()?).ok()?.try_into().ok())
+        .collect::<Option<Vec<[u8; 32]>>>()?;
+    Some(MerkleProof { index, siblings })
+}
+
+// ==================== HTTP SERVER ====================
+
+/// Requests run on the blocking pool: a slow method (a proof, or waiting
+/// for a proving slot) must not stall the async workers serving everyone else
+async fn rpc_handler(state: web::Data<RpcState>, body: web::Bytes) -> impl Responder {
+    let response = match serde_json::from_slice::<Value>(&body) {
+        Ok(payload) => {
+            let state = state.into_inner();
+            web::block(move || dispatch_payload(&state, payload))
+                .await
+                .unwrap_or_else(|e| to_value(error_response(Value::Null, INTERNAL_ERROR, e.to_string())))
+        }
+        Err(e) => to_value(error_response(Value::Null, PARSE_ERROR, e.to_string())),
+    };
+    HttpResponse::Ok().json(response)
+}
+
+/// Liveness probe: answering at all means the process is up
+async fn health_live() -> impl Responder {
+    HttpResponse::Ok().json(json!({ "alive": true }))
+}
+
+/// Readiness probe: 200 once the node is synced and has at least
+/// `min_peers` peers, 503 until then
+async fn health_ready(state: web::Data<RpcState>) -> impl Responder {
+    let (ready, body) = readiness(&state);
+    if ready {
+        HttpResponse::Ok().json(body)
+    } else {
+        HttpResponse::ServiceUnavailable().json(body)
+    }
+}
+
+/// Whether the node should receive traffic, and the `/health/ready` body
+pub fn readiness(state: &RpcState) -> (bool, Value) {
+    let (height, status) = state.sync_status();
+    let peers = state.peers.as_ref().map(|p| p.load(Ordering::Relaxed)).unwrap_or(0);
+    let ready = status.is_synced() && peers >= state.min_peers;
+    let body = json!({
+        "ready": ready,
+        "height": height,
+        "peers": peers,
+        "min_peers": state.min_peers,
+        "sync": status,
+    });
+    (ready, body)
+}
+
+/// Mount `/rpc`, the health probes, and `/ws` when the state carries an
+/// event feed, on an actix app
+pub fn configure(cfg: &mut web::ServiceConfig, state: RpcState) {
+    let websocket = state.events.is_some();
+    cfg.app_data(web::Data::new(state))
+        .route("/rpc", web::post().to(rpc_handler))
+        .route("/health/live", web::get().to(health_live))
+        .route("/health/ready", web::get().to(health_ready));
+    if websocket {
+        cfg.route("/ws", web::get().to(ws::ws_handler));
+    }
+}
+
+/// Bind the RPC server according to `config`. Returns the server, which
+/// runs once spawned (e.g. `tokio::spawn(server)`), and the bound address.
+pub fn serve(config: &RpcConfig, mut state: RpcState) -> std::io::Result<(actix_web::dev::Server, SocketAddr)> {
+    if !config.websocket_enabled {
+        state.events = None;
+    }
+    let limiter = web::Data::new(RateLimiter::new(config.rate_limit));
+    let origins = config.cors_allowed_origins.clone();
+    let server = HttpServer::new(move || {
+        let state = state.clone();
+        App::new()
+            .app_data(limiter.clone())
+            .wrap(actix_web::middleware::from_fn(rate_limit::middleware))
+            .wrap(cors::layer(&origins))
+            .configure(move |cfg| configure(cfg, state))
+    })
+    .max_connections(config.max_connections)
+    .client_request_timeout(Duration::from_secs(config.request_timeout))
+    .workers(2)
+    .bind(&config.listen_address)?;
+
+    let addr = server.addrs()[0];
+    Ok((server.run(), addr))
+}
@@ -0,0 +1,124 @@
+//! Admission control for proof generation.
+//!
+//! A Groth16 proof takes seconds of CPU and a sizeable chunk of memory, so
+//! `generate_zk_proof` goes through a `ProofQueue`: at most
+//! `max_concurrent` proofs run at once, up to `max_queued` more wait for a
+//! slot, and anything beyond that is turned away with `SERVER_BUSY` instead
+//! of piling up. RPC requests run on the blocking pool (see `rpc_handler`),
+//! so waiting or proving never holds up the server's async workers.
+
+use std::sync::{Arc, Condvar, Mutex};
+
+use crate::metrics;
+
+/// Default `rpc.max_concurrent_proofs`
+pub const DEFAULT_MAX_CONCURRENT_PROOFS: usize = 2;
+/// Default `rpc.max_queued_proofs`
+pub const DEFAULT_MAX_QUEUED_PROOFS: usize = 8;
+
+#[derive(Debug, Default)]
+struct Slots {
+    running: usize,
+    queued: usize,
+}
+
+/// Bounded semaphore with a bounded wait queue
+#[derive(Clone)]
+pub struct ProofQueue {
+    max_concurrent: usize,
+    max_queued: usize,
+    slots: Arc<(Mutex<Slots>, Condvar)>,
+}
+
+/// A running proof's slot, released on drop
+pub struct ProofPermit {
+    slots: Arc<(Mutex<Slots>, Condvar)>,
+}
+
+impl ProofQueue {
+    pub fn new(max_concurrent: usize, max_queued: usize) -> Self {
+        Self {
+            max_concurrent: max_concurrent.max(1),
+            max_queued,
+            slots: Arc::new((Mutex::new(Slots::default()), Condvar::new())),
+        }
+    }
+
+    /// Take a slot, waiting in the queue if all are busy. `None` when the
+    /// queue is full too.
+    pub fn acquire(&self) -> Option<ProofPermit> {
+        let (lock, freed) = &*self.slots;
+        let mut slots = lock.lock().unwrap();
+        if slots.running >= self.max_concurrent {
+            if slots.queued >= self.max_queued {
+                metrics::record_proof_rejected();
+                return None;
+            }
+            slots.queued += 1;
+            metrics::set_proof_queue_depth(slots.queued);
+            while slots.running >= self.max_concurrent {
+                slots = freed.wait(slots).unwrap();
+            }
+            slots.queued -= 1;
+            metrics::set_proof_queue_depth(slots.queued);
+        }
+        slots.running += 1;
+        Some(ProofPermit { slots: self.slots.clone() })
+    }
+
+    /// Proofs running and waiting
+    pub fn depth(&self) -> (usize, usize) {
+        let slots = self.slots.0.lock().unwrap();
+        (slots.running, slots.queued)
+    }
+}
+
+impl Default for ProofQueue {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_CONCURRENT_PROOFS, DEFAULT_MAX_QUEUED_PROOFS)
+    }
+}
+
+impl Drop for ProofPermit {
+    fn drop(&mut self) {
+        let (lock, freed) = &*self.slots;
+        lock.lock().unwrap().running -= 1;
+        freed.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_excess_proofs_queue_then_reject() {
+        let queue = ProofQueue::new(1, 1);
+        let running = queue.acquire().unwrap();
+
+        // The second request waits for the running proof to finish
+        let (done_tx, done_rx) = mpsc::channel();
+        let waiter = {
+            let queue = queue.clone();
+            std::thread::spawn(move || {
+                let permit = queue.acquire();
+                done_tx.send(()).unwrap();
+                permit.is_some()
+            })
+        };
+        while queue.depth() != (1, 1) {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(done_rx.recv_timeout(Duration::from_millis(50)).is_err());
+
+        // A third finds both the slot and the queue taken
+        assert!(queue.acquire().is_none());
+
+        drop(running);
+        done_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(waiter.join().unwrap());
+        assert_eq!(queue.depth(), (0, 0));
+    }
+}
@@ -0,0 +1,279 @@
+//! Chunked transfer of large proofs.
+//!
+//! A proof bigger than one chunk is not returned inline: the node keeps it
+//! in a `ProofStore` and answers with its id, and the client fetches it with
+//! `get_proof_chunk(id, offset)`, one chunk per call. The id is the Merkle
+//! root of the chunk hashes, so a `ProofAssembler` checks every chunk
+//! against it on arrival, in any order, and the reassembled bytes are
+//! exactly the ones the node stored. The proof itself is verified once
+//! complete: its bincode envelope cannot be checked piece by piece.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Mutex;
+
+use crate::error::{AxiomError, Result as AxiomResult};
+use crate::hashing;
+use crate::merkle::{self, MerkleProof};
+
+/// Default `rpc.proof_chunk_size`; larger proofs are transferred in chunks
+pub const DEFAULT_PROOF_CHUNK_SIZE: usize = 64 * 1024;
+/// Proofs kept for clients to fetch; the oldest is dropped beyond this
+pub const DEFAULT_STORED_PROOFS: usize = 16;
+
+/// How a stored proof is fetched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofTicket {
+    pub id: [u8; 32],
+    /// Bytes in the whole proof
+    pub size: u64,
+    /// Bytes per chunk; every chunk but the last is full
+    pub chunk_size: u64,
+}
+
+impl ProofTicket {
+    pub fn chunk_count(&self) -> u64 {
+        self.size.div_ceil(self.chunk_size.max(1)).max(1)
+    }
+
+    /// Offset of chunk `index`
+    pub fn offset(&self, index: u64) -> u64 {
+        index * self.chunk_size
+    }
+}
+
+/// Bytes `offset..offset + data.len()` of a stored proof
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofChunk {
+    pub ticket: ProofTicket,
+    pub offset: u64,
+    pub data: Vec<u8>,
+    /// Places the chunk under `ticket.id`
+    pub proof: MerkleProof,
+}
+
+fn chunk_leaves(bytes: &[u8], chunk_size: usize) -> Vec<[u8; 32]> {
+    if bytes.is_empty() {
+        return vec![hashing::tagged_sha256(hashing::PROOF_CHUNK, &[bytes])];
+    }
+    bytes.chunks(chunk_size).map(|chunk| hashing::tagged_sha256(hashing::PROOF_CHUNK, &[chunk])).collect()
+}
+
+struct StoredProof {
+    bytes: Vec<u8>,
+    leaves: Vec<[u8; 32]>,
+}
+
+#[derive(Default)]
+struct Stored {
+    proofs: HashMap<[u8; 32], StoredProof>,
+    /// Oldest first
+    order: VecDeque<[u8; 32]>,
+}
+
+/// Proofs waiting to be fetched in chunks
+pub struct ProofStore {
+    capacity: usize,
+    chunk_size: usize,
+    stored: Mutex<Stored>,
+}
+
+impl ProofStore {
+    /// Keep up to `capacity` proofs, served `chunk_size` bytes at a time
+    pub fn new(capacity: usize, chunk_size: usize) -> Self {
+        Self { capacity: capacity.max(1), chunk_size: chunk_size.max(1), stored: Mutex::default() }
+    }
+
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Whether `size` bytes are too many to return in one response
+    pub fn needs_chunking(&self, size: usize) -> bool {
+        size > self.chunk_size
+    }
+
+    /// Keep `bytes` for fetching, dropping the oldest proof when full
+    pub fn insert(&self, bytes: Vec<u8>) -> ProofTicket {
+        let leaves = chunk_leaves(&bytes, self.chunk_size);
+        let ticket = ProofTicket { id: merkle::root(&leaves), size: bytes.len() as u64, chunk_size: self.chunk_size as u64 };
+
+        let mut stored = self.stored.lock().unwrap();
+        if stored.proofs.insert(ticket.id, StoredProof { bytes, leaves }).is_none() {
+            stored.order.push_back(ticket.id);
+        }
+        while stored.order.len() > self.capacity {
+            if let Some(oldest) = stored.order.pop_front() {
+                stored.proofs.remove(&oldest);
+            }
+        }
+        ticket
+    }
+
+    /// Ticket of proof `id`, while it is still stored
+    pub fn ticket(&self, id: &[u8; 32]) -> Option<ProofTicket> {
+        let stored = self.stored.lock().unwrap();
+        let proof = stored.proofs.get(id)?;
+        Some(ProofTicket { id: *id, size: proof.bytes.len() as u64, chunk_size: self.chunk_size as u64 })
+    }
+
+    /// The chunk of proof `id` starting at `offset`, which must be a
+    /// multiple of the chunk size within the proof
+    pub fn chunk(&self, id: &[u8; 32], offset: u64) -> Option<ProofChunk> {
+        let ticket = self.ticket(id)?;
+        if !offset.is_multiple_of(ticket.chunk_size) {
+            return None;
+        }
+        let index = offset / ticket.chunk_size;
+        let stored = self.stored.lock().unwrap();
+        let proof = stored.proofs.get(id)?;
+        let merkle_proof = MerkleProof::generate(&proof.leaves, usize::try_from(index).ok()?)?;
+        let start = offset as usize;
+        let end = (start + self.chunk_size).min(proof.bytes.len());
+        Some(ProofChunk { ticket, offset, data: proof.bytes[start..end].to_vec(), proof: merkle_proof })
+    }
+
+    /// Proofs currently stored
+    pub fn len(&self) -> usize {
+        self.stored.lock().unwrap().proofs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for ProofStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_STORED_PROOFS, DEFAULT_PROOF_CHUNK_SIZE)
+    }
+}
+
+/// Rebuilds a proof from its chunks, checking each against the ticket's id
+#[derive(Debug, Clone)]
+pub struct ProofAssembler {
+    ticket: ProofTicket,
+    /// By index; nothing is allocated for chunks not yet received, whatever
+    /// size the ticket claims
+    chunks: BTreeMap<u64, Vec<u8>>,
+}
+
+impl ProofAssembler {
+    pub fn new(ticket: ProofTicket) -> Self {
+        Self { ticket, chunks: BTreeMap::new() }
+    }
+
+    pub fn ticket(&self) -> &ProofTicket {
+        &self.ticket
+    }
+
+    /// Accept `chunk` if it is the right size for its place and its Merkle
+    /// proof puts it under the ticket's id
+    pub fn add(&mut self, chunk: ProofChunk) -> AxiomResult<()> {
+        let ticket = self.ticket;
+        let index = chunk.offset / ticket.chunk_size.max(1);
+        let expected_len = ticket.size.saturating_sub(chunk.offset).min(ticket.chunk_size);
+        if chunk.ticket != ticket
+            || !chunk.offset.is_multiple_of(ticket.chunk_size.max(1))
+            || index >= ticket.chunk_count()
+            || chunk.proof.index != index
+            || chunk.data.len() as u64 != expected_len
+        {
+            return Err(AxiomError::InvalidProofChunk(format!("chunk at offset {} out of place", chunk.offset)));
+        }
+        let leaf = hashing::tagged_sha256(hashing::PROOF_CHUNK, &[&chunk.data]);
+        if !chunk.proof.verify(leaf, ticket.id) {
+            return Err(AxiomError::InvalidProofChunk(format!("chunk at offset {} not in proof id", chunk.offset)));
+        }
+        self.chunks.insert(index, chunk.data);
+        Ok(())
+    }
+
+    /// Offset of the first chunk still missing
+    pub fn next_offset(&self) -> Option<u64> {
+        (0..self.ticket.chunk_count()).find(|index| !self.chunks.contains_key(index)).map(|index| self.ticket.offset(index))
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.chunks.len() as u64 == self.ticket.chunk_count()
+    }
+
+    /// The proof's bytes, once every chunk is in
+    pub fn into_bytes(self) -> AxiomResult<Vec<u8>> {
+        if !self.is_complete() {
+            return Err(AxiomError::InvalidProofChunk("missing chunks".to_string()));
+        }
+        Ok(self.chunks.into_values().flatten().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proof_bytes(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_chunks_reassemble_byte_identical() {
+        let store = ProofStore::new(4, 100);
+        let bytes = proof_bytes(1_050);
+        assert!(store.needs_chunking(bytes.len()));
+        let ticket = store.insert(bytes.clone());
+        assert_eq!(ticket.chunk_count(), 11);
+        assert_eq!(store.ticket(&ticket.id), Some(ticket));
+
+        // Chunks may arrive in any order
+        let mut assembler = ProofAssembler::new(ticket);
+        for index in (0..ticket.chunk_count()).rev().filter(|i| i % 2 == 0) {
+            assembler.add(store.chunk(&ticket.id, ticket.offset(index)).unwrap()).unwrap();
+        }
+        assert_eq!(assembler.next_offset(), Some(100));
+        assert!(assembler.clone().into_bytes().is_err());
+        while let Some(offset) = assembler.next_offset() {
+            assembler.add(store.chunk(&ticket.id, offset).unwrap()).unwrap();
+        }
+        assert!(assembler.is_complete());
+        assert_eq!(assembler.into_bytes().unwrap(), bytes);
+
+        // Offsets must fall on chunk boundaries inside the proof
+        assert!(store.chunk(&ticket.id, 50).is_none());
+        assert!(store.chunk(&ticket.id, 1_100).is_none());
+        assert!(store.chunk(&[0u8; 32], 0).is_none());
+    }
+
+    #[test]
+    fn test_tampered_chunk_rejected() {
+        let store = ProofStore::new(4, 64);
+        let ticket = store.insert(proof_bytes(200));
+        let mut assembler = ProofAssembler::new(ticket);
+
+        let mut altered = store.chunk(&ticket.id, 64).unwrap();
+        altered.data[0] ^= 1;
+        assert!(matches!(assembler.add(altered), Err(AxiomError::InvalidProofChunk(_))));
+
+        // A genuine chunk presented at another offset
+        let mut moved = store.chunk(&ticket.id, 64).unwrap();
+        moved.offset = 128;
+        assert!(assembler.add(moved).is_err());
+
+        // A truncated last chunk
+        let mut short = store.chunk(&ticket.id, 192).unwrap();
+        assert_eq!(short.data.len(), 8);
+        short.data.pop();
+        assert!(assembler.add(short).is_err());
+        assert_eq!(assembler.next_offset(), Some(0));
+    }
+
+    #[test]
+    fn test_oldest_proof_evicted() {
+        let store = ProofStore::new(2, 16);
+        let first = store.insert(proof_bytes(40));
+        let second = store.insert(proof_bytes(41));
+        let third = store.insert(proof_bytes(42));
+        assert_eq!(store.len(), 2);
+        assert!(store.ticket(&first.id).is_none());
+        assert!(store.ticket(&second.id).is_some());
+        assert!(store.chunk(&third.id, 32).is_some());
+    }
+}
@@ -0,0 +1,191 @@
+//! Per-client rate limiting for the RPC server.
+//!
+//! Each client IP gets a token bucket holding `rate_limit` tokens that
+//! refills continuously over one minute, so short bursts up to the limit are
+//! allowed while the sustained rate stays at `rate_limit` requests/minute.
+//! Buckets idle for a full window are back at capacity and are dropped to
+//! keep memory bounded.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::{web, HttpResponse};
+use serde_json::Value;
+
+use super::{error_response, RATE_LIMITED};
+
+/// Refill window for `rate_limit` tokens
+pub const WINDOW: Duration = Duration::from_secs(60);
+
+struct Bucket {
+    tokens: f64,
+    last: Instant,
+}
+
+struct Buckets {
+    clients: HashMap<IpAddr, Bucket>,
+    last_sweep: Instant,
+}
+
+/// Shared token-bucket limiter keyed by client IP
+#[derive(Clone)]
+pub struct RateLimiter {
+    per_minute: u64,
+    buckets: Arc<Mutex<Buckets>>,
+}
+
+impl RateLimiter {
+    /// Allow `per_minute` requests per client. Zero disables limiting.
+    pub fn new(per_minute: u64) -> Self {
+        Self {
+            per_minute,
+            buckets: Arc::new(Mutex::new(Buckets { clients: HashMap::new(), last_sweep: Instant::now() })),
+        }
+    }
+
+    /// Take one token for `ip`, or return how long until one is available
+    pub fn check(&self, ip: IpAddr) -> Result<(), Duration> {
+        self.check_at(ip, Instant::now())
+    }
+
+    /// [`check`](Self::check) at an explicit point in time
+    pub fn check_at(&self, ip: IpAddr, now: Instant) -> Result<(), Duration> {
+        if self.per_minute == 0 {
+            return Ok(());
+        }
+        let capacity = self.per_minute as f64;
+        let refill_per_sec = capacity / WINDOW.as_secs_f64();
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if now.saturating_duration_since(buckets.last_sweep) >= WINDOW {
+            buckets.clients.retain(|_, b| now.saturating_duration_since(b.last) < WINDOW);
+            buckets.last_sweep = now;
+        }
+
+        let bucket = buckets.clients.entry(ip).or_insert(Bucket { tokens: capacity, last: now });
+        let elapsed = now.saturating_duration_since(bucket.last).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * refill_per_sec).min(capacity);
+        bucket.last = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / refill_per_sec))
+        }
+    }
+
+    /// Number of clients currently tracked
+    pub fn tracked_clients(&self) -> usize {
+        self.buckets.lock().unwrap_or_else(|e| e.into_inner()).clients.len()
+    }
+}
+
+/// Whole seconds to advertise in `Retry-After`, never zero
+pub fn retry_after_secs(wait: Duration) -> u64 {
+    wait.as_secs_f64().ceil().max(1.0) as u64
+}
+
+/// JSON-RPC error body for a throttled request
+pub fn throttled_body(wait: Duration) -> Value {
+    let secs = retry_after_secs(wait);
+    super::to_value(error_response(
+        Value::Null,
+        RATE_LIMITED,
+        format!("rate limit exceeded, retry after {}s", secs),
+    ))
+}
+
+/// actix middleware enforcing the [`RateLimiter`] found in app data.
+/// Requests without a known peer address are let through.
+pub async fn middleware(
+    limiter: Option<web::Data<RateLimiter>>,
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, actix_web::Error> {
+    let verdict = match (limiter, req.peer_addr()) {
+        (Some(limiter), Some(peer)) => limiter.check(peer.ip()),
+        _ => Ok(()),
+    };
+    match verdict {
+        Ok(()) => Ok(next.call(req).await?.map_into_boxed_body()),
+        Err(wait) => {
+            let response = HttpResponse::TooManyRequests()
+                .insert_header(("Retry-After", retry_after_secs(wait).to_string()))
+                .json(throttled_body(wait));
+            Ok(req.into_response(response))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLIENT: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(10, 0, 0, 1));
+
+    #[test]
+    fn test_under_limit_succeeds_and_excess_is_throttled() {
+        let limiter = RateLimiter::new(5);
+        let now = Instant::now();
+        for _ in 0..5 {
+            assert!(limiter.check_at(CLIENT, now).is_ok());
+        }
+        let wait = limiter.check_at(CLIENT, now).unwrap_err();
+        // One token refills every 12s at 5/min
+        assert_eq!(retry_after_secs(wait), 12);
+
+        // Other clients have their own bucket
+        let other = IpAddr::V4(std::net::Ipv4Addr::new(10, 0, 0, 2));
+        assert!(limiter.check_at(other, now).is_ok());
+    }
+
+    #[test]
+    fn test_limit_resets_after_window() {
+        let limiter = RateLimiter::new(3);
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.check_at(CLIENT, start).unwrap();
+        }
+        assert!(limiter.check_at(CLIENT, start).is_err());
+
+        // A third of the window refills one token
+        assert!(limiter.check_at(CLIENT, start + WINDOW / 3).is_ok());
+        assert!(limiter.check_at(CLIENT, start + WINDOW / 3).is_err());
+
+        // A full window refills to capacity, not beyond
+        let later = start + WINDOW / 3 + WINDOW;
+        for _ in 0..3 {
+            assert!(limiter.check_at(CLIENT, later).is_ok());
+        }
+        assert!(limiter.check_at(CLIENT, later).is_err());
+    }
+
+    #[test]
+    fn test_idle_buckets_expire() {
+        let limiter = RateLimiter::new(10);
+        let start = Instant::now();
+        for i in 0..50u8 {
+            limiter.check_at(IpAddr::V4(std::net::Ipv4Addr::new(10, 1, 0, i)), start).unwrap();
+        }
+        assert_eq!(limiter.tracked_clients(), 50);
+
+        limiter.check_at(CLIENT, start + WINDOW * 2).unwrap();
+        assert_eq!(limiter.tracked_clients(), 1);
+    }
+
+    #[test]
+    fn test_zero_disables_limiting() {
+        let limiter = RateLimiter::new(0);
+        let now = Instant::now();
+        for _ in 0..1000 {
+            assert!(limiter.check_at(CLIENT, now).is_ok());
+        }
+        assert_eq!(limiter.tracked_clients(), 0);
+    }
+}
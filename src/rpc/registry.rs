@@ -0,0 +1,203 @@
+//! Method table for the JSON-RPC server.
+//!
+//! Each method is a handler taking its parameters as a typed struct and
+//! returning anything serializable. Adding a method means registering a
+//! handler (`MethodRegistry::register`), not extending a match. The registry
+//! decodes the request's `params` into the handler's type. Parameter structs
+//! derive `Deserialize`, so the same struct accepts positional (`[..]`) and
+//! named (`{..}`) parameters, and an absent `params` counts as `[]`.
+//! Parameters that do not decode are `INVALID_PARAMS`, and names with no
+//! handler are the standard `-32601 Method not found`.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::de::{DeserializeOwned, Error as _};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use super::{RpcError, RpcState, INTERNAL_ERROR, INVALID_PARAMS, METHOD_NOT_FOUND};
+
+type Handler = Box<dyn Fn(&RpcState, Value) -> Result<Value, RpcError> + Send + Sync>;
+
+/// JSON-RPC methods by name
+#[derive(Default)]
+pub struct MethodRegistry {
+    methods: HashMap<&'static str, Handler>,
+}
+
+impl MethodRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve `name` with `handler`, replacing any earlier handler
+    pub fn register<P, R, F>(&mut self, name: &'static str, handler: F) -> &mut Self
+    where
+        P: DeserializeOwned,
+        R: Serialize,
+        F: Fn(&RpcState, P) -> Result<R, RpcError> + Send + Sync + 'static,
+    {
+        let handler = move |state: &RpcState, params: Value| {
+            let params = if params.is_null() { Value::Array(Vec::new()) } else { params };
+            let params: P = serde_json::from_value(params)
+                .map_err(|e| RpcError::new(INVALID_PARAMS, format!("invalid params: {}", e)))?;
+            let result = handler(state, params)?;
+            serde_json::to_value(result).map_err(|e| RpcError::new(INTERNAL_ERROR, e.to_string()))
+        };
+        self.methods.insert(name, Box::new(handler));
+        self
+    }
+
+    /// Run `method` on `params`
+    pub fn call(&self, state: &RpcState, method: &str, params: &Value) -> Result<Value, RpcError> {
+        let handler = self
+            .methods
+            .get(method)
+            .ok_or_else(|| RpcError::new(METHOD_NOT_FOUND, "Method not found"))?;
+        handler(state, params.clone())
+    }
+
+    pub fn contains(&self, method: &str) -> bool {
+        self.methods.contains_key(method)
+    }
+
+    /// Registered method names, sorted
+    pub fn methods(&self) -> Vec<&'static str> {
+        let mut names: Vec<&'static str> = self.methods.keys().copied().collect();
+        names.sort_unstable();
+        names
+    }
+}
+
+impl fmt::Debug for MethodRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MethodRegistry").field("methods", &self.methods()).finish()
+    }
+}
+
+// ==================== PARAMETER TYPES ====================
+
+/// Parameters of a method that takes none
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct NoParams {}
+
+/// 32 hex-encoded bytes, such as a hash or an address, with or without `0x`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hex32(pub [u8; 32]);
+
+/// 64 hex-encoded bytes, such as a signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hex64(pub [u8; 64]);
+
+fn hex_array<'de, D: Deserializer<'de>, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error> {
+    let encoded = String::deserialize(deserializer)?;
+    hex::decode(encoded.trim_start_matches("0x"))
+        .ok()
+        .and_then(|bytes| <[u8; N]>::try_from(bytes).ok())
+        .ok_or_else(|| D::Error::custom(format!("expected {} hex-encoded bytes", N)))
+}
+
+impl<'de> Deserialize<'de> for Hex32 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        hex_array(deserializer).map(Hex32)
+    }
+}
+
+impl<'de> Deserialize<'de> for Hex64 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        hex_array(deserializer).map(Hex64)
+    }
+}
+
+/// A value given either as a JSON object or as hex-encoded bincode, as
+/// transactions and blocks are
+#[derive(Debug, Clone)]
+pub struct Encoded<T>(pub T);
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Encoded<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::String(encoded) => hex::decode(encoded.trim_start_matches("0x"))
+                .ok()
+                .and_then(|bytes| bincode::deserialize(&bytes).ok())
+                .map(Encoded)
+                .ok_or_else(|| D::Error::custom("invalid hex-encoded bincode")),
+            other => serde_json::from_value(other).map(Encoded).map_err(D::Error::custom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::Timechain;
+    use crate::genesis;
+    use crate::mempool::Mempool;
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Deserialize)]
+    struct TransferParams {
+        to: Hex32,
+        amount: u64,
+        #[serde(default)]
+        memo: Option<String>,
+    }
+
+    fn state() -> RpcState {
+        RpcState::new(
+            Arc::new(Mutex::new(Timechain::new(genesis::genesis()))),
+            Arc::new(Mutex::new(Mempool::new())),
+        )
+    }
+
+    fn registry() -> MethodRegistry {
+        let mut registry = MethodRegistry::new();
+        registry
+            .register("transfer", |_: &RpcState, params: TransferParams| {
+                Ok(json!({ "to": hex::encode(params.to.0), "amount": params.amount * 2, "memo": params.memo }))
+            })
+            .register("height", |state: &RpcState, _: NoParams| {
+                Ok(state.chain.lock().unwrap().blocks.len() as u64 - 1)
+            });
+        registry
+    }
+
+    #[test]
+    fn test_registered_method_gets_typed_params() {
+        let (state, registry) = (state(), registry());
+        assert_eq!(registry.methods(), vec!["height", "transfer"]);
+
+        let to = hex::encode([3u8; 32]);
+        let positional = registry.call(&state, "transfer", &json!([format!("0x{}", to), 21])).unwrap();
+        assert_eq!(positional, json!({ "to": to, "amount": 42, "memo": null }));
+        let named = registry.call(&state, "transfer", &json!({ "amount": 1, "to": to, "memo": "hi" })).unwrap();
+        assert_eq!(named, json!({ "to": to, "amount": 2, "memo": "hi" }));
+
+        // Absent params are the empty list
+        assert_eq!(registry.call(&state, "height", &Value::Null).unwrap(), json!(0));
+
+        for bad in [json!(["not-hex", 1]), json!([to, "many"]), json!([to]), json!([to, 1, "memo", 4])] {
+            let error = registry.call(&state, "transfer", &bad).unwrap_err();
+            assert_eq!(error.code, INVALID_PARAMS, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_unknown_method_not_found() {
+        let error = registry().call(&state(), "mine_me_a_block", &json!([])).unwrap_err();
+        assert_eq!(error, RpcError::new(-32601, "Method not found"));
+    }
+
+    #[test]
+    fn test_encoded_accepts_json_or_bincode() {
+        let value = (7u64, [1u8; 32]);
+        let from_hex: Encoded<(u64, [u8; 32])> =
+            serde_json::from_value(json!(hex::encode(bincode::serialize(&value).unwrap()))).unwrap();
+        let from_json: Encoded<(u64, [u8; 32])> = serde_json::from_value(json!(value)).unwrap();
+        assert_eq!(from_hex.0, value);
+        assert_eq!(from_json.0, value);
+        assert!(serde_json::from_value::<Encoded<(u64, [u8; 32])>>(json!("zz")).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+//! Mock JSON-RPC node for client tests.
+//!
+//! [`MockNode`] serves `/rpc` on a local port with canned answers per
+//! method and records every request it receives, so [`super::client`] can
+//! be tested without a chain. Each method answers from a queue: replies are
+//! used in the order they were registered and the last one repeats, so a
+//! test can walk a transaction from pending to confirmed across polls.
+//! Methods with no reply registered answer `METHOD_NOT_FOUND`.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use actix_web::{web, App, HttpResponse, HttpServer};
+use serde_json::{json, Value};
+
+use super::{RpcError, RpcRequest, METHOD_NOT_FOUND};
+
+/// One canned answer
+#[derive(Debug, Clone)]
+enum Reply {
+    Result(Value),
+    Error(RpcError),
+    /// Sent as the response body verbatim, e.g. malformed JSON
+    Raw(String),
+}
+
+#[derive(Default)]
+struct Script {
+    replies: HashMap<String, VecDeque<Reply>>,
+    requests: Vec<RpcRequest>,
+}
+
+impl Script {
+    fn answer(&mut self, request: RpcRequest) -> Reply {
+        let reply = match self.replies.get_mut(&request.method) {
+            Some(queue) if queue.len() > 1 => queue.pop_front(),
+            Some(queue) => queue.front().cloned(),
+            None => None,
+        };
+        let reply = reply.unwrap_or_else(|| {
+            Reply::Error(RpcError::new(METHOD_NOT_FOUND, format!("no reply for {}", request.method)))
+        });
+        self.requests.push(request);
+        reply
+    }
+}
+
+/// A node that answers from a script
+pub struct MockNode {
+    url: String,
+    script: Arc<Mutex<Script>>,
+}
+
+impl MockNode {
+    /// Serve on a free local port. Must be called inside a Tokio runtime.
+    pub fn start() -> Self {
+        let script = Arc::new(Mutex::new(Script::default()));
+        let data = web::Data::from(script.clone());
+        let server = HttpServer::new(move || App::new().app_data(data.clone()).route("/rpc", web::post().to(respond)))
+            .workers(1)
+            .bind("127.0.0.1:0")
+            .expect("bind mock rpc server");
+        let url = format!("http://{}/rpc", server.addrs()[0]);
+        tokio::spawn(server.run());
+        Self { url, script }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Answer `method` with `result`
+    pub fn reply(&self, method: &str, result: Value) -> &Self {
+        self.push(method, Reply::Result(result))
+    }
+
+    /// Answer `method` with an error object
+    pub fn reply_error(&self, method: &str, code: i64, message: &str) -> &Self {
+        self.push(method, Reply::Error(RpcError::new(code, message)))
+    }
+
+    /// Answer `method` with `body` as it is
+    pub fn reply_raw(&self, method: &str, body: &str) -> &Self {
+        self.push(method, Reply::Raw(body.to_string()))
+    }
+
+    /// Every request received so far, oldest first
+    pub fn requests(&self) -> Vec<RpcRequest> {
+        self.script.lock().unwrap().requests.clone()
+    }
+
+    /// How many times `method` was called
+    pub fn calls(&self, method: &str) -> usize {
+        self.script.lock().unwrap().requests.iter().filter(|r| r.method == method).count()
+    }
+
+    fn push(&self, method: &str, reply: Reply) -> &Self {
+        self.script.lock().unwrap().replies.entry(method.to_string()).or_default().push_back(reply);
+        self
+    }
+}
+
+async fn respond(script: web::Data<Mutex<Script>>, request: web::Json<RpcRequest>) -> HttpResponse {
+    let request = request.into_inner();
+    let id = request.id.clone();
+    match script.lock().unwrap().answer(request) {
+        Reply::Result(result) => HttpResponse::Ok().json(json!({ "jsonrpc": "2.0", "result": result, "id": id })),
+        Reply::Error(error) => HttpResponse::Ok().json(json!({ "jsonrpc": "2.0", "error": error, "id": id })),
+        Reply::Raw(body) => HttpResponse::Ok().content_type("application/json").body(body),
+    }
+}
@@ -0,0 +1,186 @@
+//! Transaction tracing across RPC, mempool, mining and gossip.
+//!
+//! `broadcast_transaction` gives each new transaction a random `trace_id`.
+//! Every later stage (mempool admission, gossip, inclusion in a block) is
+//! logged as a `tracing` event inside a `tx` span carrying that ID, and
+//! recorded so `get_transaction` can return the whole journey. Run the node
+//! with `RUST_LOG=axiom_core::rpc::trace=info` to see the events. Only the
+//! most recent `capacity` transactions are kept.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+use crate::block::Block;
+
+/// Default number of transactions whose traces are kept
+pub const DEFAULT_TRACE_CAPACITY: usize = 10_000;
+
+/// A state transition in a transaction's life
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum Stage {
+    /// Accepted by `broadcast_transaction`
+    Submitted,
+    /// Added to the node's mempool
+    MempoolAdded,
+    /// Published to peers
+    Gossiped,
+    /// Included in the block at `height`
+    Included { height: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StageEvent {
+    #[serde(flatten)]
+    pub stage: Stage,
+    /// Unix seconds
+    pub at: u64,
+}
+
+/// Everything recorded for one transaction, oldest stage first
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TxTrace {
+    pub trace_id: String,
+    pub stages: Vec<StageEvent>,
+}
+
+#[derive(Default)]
+struct Traces {
+    by_tx: HashMap<[u8; 32], TxTrace>,
+    /// Traced transactions, oldest first, for eviction
+    order: VecDeque<[u8; 32]>,
+}
+
+/// Traces of recently submitted transactions, keyed by transaction hash
+pub struct TxTracer {
+    capacity: usize,
+    traces: Mutex<Traces>,
+}
+
+impl TxTracer {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity: capacity.max(1), traces: Mutex::new(Traces::default()) }
+    }
+
+    /// Trace ID of `tx_hash`, starting a trace at `Submitted` if there is
+    /// none yet
+    pub fn start(&self, tx_hash: &[u8; 32]) -> String {
+        let mut traces = self.traces.lock().unwrap();
+        if let Some(trace) = traces.by_tx.get(tx_hash) {
+            return trace.trace_id.clone();
+        }
+        if traces.order.len() >= self.capacity {
+            if let Some(oldest) = traces.order.pop_front() {
+                traces.by_tx.remove(&oldest);
+            }
+        }
+        let trace_id = hex::encode(rand::random::<[u8; 8]>());
+        traces.order.push_back(*tx_hash);
+        traces.by_tx.insert(*tx_hash, TxTrace { trace_id: trace_id.clone(), stages: Vec::new() });
+        drop(traces);
+        self.record(tx_hash, Stage::Submitted);
+        trace_id
+    }
+
+    /// Log and record `stage` for `tx_hash` if it is being traced
+    pub fn record(&self, tx_hash: &[u8; 32], stage: Stage) {
+        let mut traces = self.traces.lock().unwrap();
+        let Some(trace) = traces.by_tx.get_mut(tx_hash) else {
+            return;
+        };
+        let span = tracing::info_span!("tx", trace_id = %trace.trace_id, hash = %hex::encode(tx_hash));
+        let _entered = span.enter();
+        tracing::info!(?stage, "transaction stage");
+        trace.stages.push(StageEvent { stage, at: now_secs() });
+    }
+
+    /// Record `Included` for every traced transaction in `block`
+    pub fn record_block(&self, block: &Block) {
+        for tx in &block.transactions {
+            self.record(&tx.hash(), Stage::Included { height: block.slot });
+        }
+    }
+
+    pub fn get(&self, tx_hash: &[u8; 32]) -> Option<TxTrace> {
+        self.traces.lock().unwrap().by_tx.get(tx_hash).cloned()
+    }
+}
+
+impl Default for TxTracer {
+    fn default() -> Self {
+        Self::new(DEFAULT_TRACE_CAPACITY)
+    }
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::Transaction;
+    use std::io::Write;
+    use std::sync::Arc;
+
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_trace_id_follows_transaction_to_block() {
+        let logs = Capture::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt().with_ansi(false).with_writer(move || writer.clone()).finish();
+
+        let tx = Transaction::new([1u8; 32], [2u8; 32], 100, 1, 0, vec![], vec![]);
+        let untraced = Transaction::new([3u8; 32], [2u8; 32], 100, 1, 0, vec![], vec![]);
+        let block = Block::new([0u8; 32], 7, [4u8; 32], vec![untraced.clone(), tx.clone()], [0u8; 32], vec![], 0);
+        let tracer = TxTracer::default();
+
+        let trace_id = tracing::subscriber::with_default(subscriber, || {
+            let trace_id = tracer.start(&tx.hash());
+            assert_eq!(tracer.start(&tx.hash()), trace_id);
+            tracer.record(&tx.hash(), Stage::MempoolAdded);
+            tracer.record_block(&block);
+            trace_id
+        });
+
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        let line = |stage: &str| logs.lines().find(|line| line.contains(stage)).unwrap_or_default().to_string();
+        assert!(line("MempoolAdded").contains(&trace_id));
+        assert!(line("Included { height: 7 }").contains(&trace_id));
+        assert!(!logs.contains(&hex::encode(untraced.hash())));
+
+        let stages: Vec<Stage> = tracer.get(&tx.hash()).unwrap().stages.into_iter().map(|e| e.stage).collect();
+        assert_eq!(stages, vec![Stage::Submitted, Stage::MempoolAdded, Stage::Included { height: 7 }]);
+        assert!(tracer.get(&untraced.hash()).is_none());
+    }
+
+    #[test]
+    fn test_oldest_traces_evicted() {
+        let tracer = TxTracer::new(2);
+        let hashes = [[1u8; 32], [2u8; 32], [3u8; 32]];
+        for hash in &hashes {
+            tracer.start(hash);
+        }
+        assert!(tracer.get(&hashes[0]).is_none());
+        assert!(tracer.get(&hashes[1]).is_some());
+        assert!(tracer.get(&hashes[2]).is_some());
+    }
+}
@@ -0,0 +1,233 @@
+//! WebSocket subscriptions on `/ws`.
+//!
+//! Clients send JSON-RPC requests over the socket. `subscribe` with
+//! `"newHeads"`, `"pendingTransactions"` or `"mempool"` returns a
+//! subscription id, after which notifications arrive as
+//! `{"jsonrpc":"2.0","method":"subscription","params":{"subscription":<id>,"result":<payload>}}`.
+//! `mempool` payloads carry an `event` (`added`, `replaced`, `evicted`,
+//! `mined` or `expired`) and the hashes a client needs to update its view.
+//! `unsubscribe` with that id stops them. Any other method is answered as
+//! over HTTP. Messages on an open socket count against the client's rate
+//! limit like HTTP requests do.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use actix_ws::{Message, MessageStream, Session};
+use futures::StreamExt;
+use serde_json::{json, Value};
+use tokio::sync::broadcast;
+
+use super::rate_limit::{throttled_body, RateLimiter};
+use super::{
+    block_json, dispatch_value, error_response, to_value, tx_json, RpcError, RpcResponse, RpcState,
+    INVALID_PARAMS, PARSE_ERROR,
+};
+use crate::block::Block;
+use crate::mempool::MempoolEvent;
+use crate::transaction::Transaction;
+
+/// Events published by the node's block-accept and mempool paths
+#[derive(Debug, Clone)]
+pub enum NodeEvent {
+    NewBlock(Block),
+    PendingTransaction(Transaction),
+    Mempool(MempoolEvent),
+}
+
+/// Lets the mempool publish straight onto the node's event channel
+impl From<MempoolEvent> for NodeEvent {
+    fn from(event: MempoolEvent) -> Self {
+        NodeEvent::Mempool(event)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Topic {
+    NewHeads,
+    PendingTransactions,
+    Mempool,
+}
+
+impl Topic {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "newHeads" => Some(Topic::NewHeads),
+            "pendingTransactions" => Some(Topic::PendingTransactions),
+            "mempool" => Some(Topic::Mempool),
+            _ => None,
+        }
+    }
+
+    fn payload(self, event: &NodeEvent) -> Option<Value> {
+        match (self, event) {
+            (Topic::NewHeads, NodeEvent::NewBlock(block)) => Some(block_json(block)),
+            (Topic::PendingTransactions, NodeEvent::PendingTransaction(tx))
+            | (Topic::PendingTransactions, NodeEvent::Mempool(MempoolEvent::Added(tx)))
+            | (Topic::PendingTransactions, NodeEvent::Mempool(MempoolEvent::Replaced { new: tx, .. })) => Some(tx_json(tx)),
+            (Topic::Mempool, NodeEvent::PendingTransaction(tx)) => Some(json!({ "event": "added", "tx": tx_json(tx) })),
+            (Topic::Mempool, NodeEvent::Mempool(event)) => Some(mempool_event_json(event)),
+            _ => None,
+        }
+    }
+}
+
+fn mempool_event_json(event: &MempoolEvent) -> Value {
+    match event {
+        MempoolEvent::Added(tx) => json!({ "event": "added", "tx": tx_json(tx) }),
+        MempoolEvent::Replaced { old, new } => json!({
+            "event": "replaced",
+            "old": hex::encode(old.hash()),
+            "new": tx_json(new),
+        }),
+        MempoolEvent::Evicted(tx) => json!({ "event": "evicted", "hash": hex::encode(tx.hash()) }),
+        MempoolEvent::Mined { hash, height } => json!({ "event": "mined", "hash": hex::encode(hash), "height": height }),
+        MempoolEvent::Expired(tx) => json!({ "event": "expired", "hash": hex::encode(tx.hash()) }),
+    }
+}
+
+pub(super) async fn ws_handler(
+    req: HttpRequest,
+    body: web::Payload,
+    state: web::Data<RpcState>,
+    limiter: Option<web::Data<RateLimiter>>,
+) -> actix_web::Result<HttpResponse> {
+    let events = match &state.events {
+        Some(events) => events.subscribe(),
+        None => return Ok(HttpResponse::NotFound().finish()),
+    };
+    let limit = limiter.zip(req.peer_addr()).map(|(limiter, peer)| (limiter.get_ref().clone(), peer.ip()));
+    let (response, session, messages) = actix_ws::handle(&req, body)?;
+    actix_web::rt::spawn(run_session(state.get_ref().clone(), limit, session, messages, events));
+    Ok(response)
+}
+
+async fn run_session(
+    state: RpcState,
+    limit: Option<(RateLimiter, IpAddr)>,
+    mut session: Session,
+    mut messages: MessageStream,
+    mut events: broadcast::Receiver<NodeEvent>,
+) {
+    let mut subscriptions: HashMap<String, Topic> = HashMap::new();
+    let mut next_id = 0u64;
+
+    loop {
+        tokio::select! {
+            message = messages.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let verdict = match &limit {
+                        Some((limiter, ip)) => limiter.check(*ip),
+                        None => Ok(()),
+                    };
+                    let reply = match verdict {
+                        Ok(()) => handle_request(&state, &text, &mut subscriptions, &mut next_id),
+                        Err(wait) => throttled_body(wait),
+                    };
+                    if session.text(reply.to_string()).await.is_err() {
+                        return;
+                    }
+                }
+                Some(Ok(Message::Ping(bytes))) => {
+                    // A failed pong means the peer is gone; the stream ends next
+                    let _ = session.pong(&bytes).await;
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                _ => {}
+            },
+            event = events.recv() => match event {
+                Ok(event) => {
+                    for (id, topic) in &subscriptions {
+                        if let Some(result) = topic.payload(&event) {
+                            let note = json!({
+                                "jsonrpc": "2.0",
+                                "method": "subscription",
+                                "params": { "subscription": id, "result": result },
+                            });
+                            if session.text(note.to_string()).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                }
+                // A slow client misses events rather than stalling the node
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+        }
+    }
+    let _ = session.close(None).await;
+}
+
+fn handle_request(
+    state: &RpcState,
+    text: &str,
+    subscriptions: &mut HashMap<String, Topic>,
+    next_id: &mut u64,
+) -> Value {
+    let request: Value = match serde_json::from_str(text) {
+        Ok(value) => value,
+        Err(e) => return to_value(error_response(Value::Null, PARSE_ERROR, e.to_string())),
+    };
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let first_param = request.get("params").and_then(|p| p.get(0)).and_then(Value::as_str);
+
+    let result = match request.get("method").and_then(Value::as_str) {
+        Some("subscribe") => first_param
+            .and_then(Topic::parse)
+            .map(|topic| {
+                *next_id += 1;
+                let sub_id = format!("0x{:x}", next_id);
+                subscriptions.insert(sub_id.clone(), topic);
+                json!(sub_id)
+            })
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "expected \"newHeads\", \"pendingTransactions\" or \"mempool\"")),
+        Some("unsubscribe") => first_param
+            .map(|sub_id| json!(subscriptions.remove(sub_id).is_some()))
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "missing subscription id")),
+        _ => return to_value(dispatch_value(state, request)),
+    };
+    to_value(RpcResponse::from_result(id, result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::Timechain;
+    use crate::genesis;
+    use crate::mempool::Mempool;
+    use crate::rpc::broadcast_transaction;
+    use ed25519_dalek::{Signer, SigningKey};
+    use std::sync::{Arc, Mutex};
+
+    fn signed_tx(key: &SigningKey, fee: u64, nonce: u64) -> Transaction {
+        let mut tx = Transaction::new(key.verifying_key().to_bytes(), [2u8; 32], 100, fee, nonce, vec![], vec![]);
+        tx.signature = key.sign(&tx.signing_message()).to_bytes().to_vec();
+        tx
+    }
+
+    #[test]
+    fn test_fee_bump_over_rpc_is_published_as_replaced() {
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        let mut chain = Timechain::new(genesis::genesis()).without_tx_proofs();
+        chain.state.credit(key.verifying_key().to_bytes(), 1_000);
+        let (events, mut subscriber) = broadcast::channel(16);
+        let mempool = Mempool::new().with_events(events.clone());
+        let state = RpcState::new(Arc::new(Mutex::new(chain)), Arc::new(Mutex::new(mempool))).with_events(events);
+
+        let (old, bump) = (signed_tx(&key, 10, 0), signed_tx(&key, 20, 0));
+        broadcast_transaction(&state, old.clone()).unwrap();
+        broadcast_transaction(&state, bump.clone()).unwrap();
+
+        let mut next = || Topic::Mempool.payload(&subscriber.try_recv().unwrap()).unwrap();
+        assert_eq!(next()["event"], "added");
+        let replaced = next();
+        assert_eq!(replaced["event"], "replaced");
+        assert_eq!(replaced["old"], hex::encode(old.hash()));
+        assert_eq!(replaced["new"]["hash"], hex::encode(bump.hash()));
+        assert!(subscriber.try_recv().is_err());
+        let mempool = state.mempool.lock().unwrap();
+        assert!(mempool.contains(&bump.hash()) && !mempool.contains(&old.hash()));
+    }
+}
@@ -0,0 +1,248 @@
+// src/snapshot.rs - State snapshots for snap sync
+//
+// A node joining a long chain need not replay every block from genesis: it
+// can fetch the account state at a trusted checkpoint from its peers and
+// replay only the blocks after it (`Timechain::sync_from_snapshot`).
+//
+// A snapshot splits the balances and nonces into chunks of CHUNK_ACCOUNTS
+// addresses, in address order. The manifest carries the signed checkpoint
+// and a Merkle root over the chunk hashes, so each chunk is checked against
+// its proof as it arrives, whichever peer sent it. Once every chunk is in,
+// the assembled state must hash to the checkpoint's state root.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::error::{AxiomError, Result as AxiomResult};
+use crate::hashing;
+use crate::merkle::{self, MerkleProof};
+use crate::state::State;
+use crate::storage::Checkpoint;
+use crate::transaction::Address;
+
+/// Addresses per chunk
+pub const CHUNK_ACCOUNTS: usize = 1024;
+
+/// Balances and nonces of a run of consecutive addresses
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotChunk {
+    pub index: u32,
+    /// In address order
+    pub balances: Vec<(Address, u64)>,
+    /// In address order
+    pub nonces: Vec<(Address, u64)>,
+}
+
+impl SnapshotChunk {
+    /// Leaf committing to this chunk in the manifest's `chunk_root`
+    pub fn hash(&self) -> [u8; 32] {
+        let encoded = bincode::serialize(self).unwrap_or_default();
+        hashing::tagged_sha256(hashing::SNAPSHOT_CHUNK, &[&encoded])
+    }
+}
+
+/// What a serving node announces about its snapshot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    /// Where the snapshot was taken; the assembled state must match its
+    /// state root
+    pub checkpoint: Checkpoint,
+    /// Merkle root of the chunk hashes, in index order
+    pub chunk_root: [u8; 32],
+    pub chunk_count: u32,
+}
+
+/// A complete snapshot, as kept by a serving node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub manifest: SnapshotManifest,
+    pub chunks: Vec<SnapshotChunk>,
+}
+
+impl Snapshot {
+    /// Snapshot of `state`, which must be the state at `checkpoint`
+    pub fn new(checkpoint: Checkpoint, state: &State) -> Self {
+        Self::with_chunk_accounts(checkpoint, state, CHUNK_ACCOUNTS)
+    }
+
+    /// Snapshot cut into chunks of `accounts` addresses
+    pub fn with_chunk_accounts(checkpoint: Checkpoint, state: &State, accounts: usize) -> Self {
+        let addresses: Vec<&Address> = state
+            .balances
+            .keys()
+            .chain(state.nonces.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let chunks: Vec<SnapshotChunk> = addresses
+            .chunks(accounts.max(1))
+            .enumerate()
+            .map(|(index, run)| SnapshotChunk {
+                index: index as u32,
+                balances: run.iter().filter_map(|&a| state.balances.get(a).map(|&b| (*a, b))).collect(),
+                nonces: run.iter().filter_map(|&a| state.nonces.get(a).map(|&n| (*a, n))).collect(),
+            })
+            .collect();
+        let chunk_root = merkle::root(&chunk_hashes(&chunks));
+        let manifest = SnapshotManifest { checkpoint, chunk_root, chunk_count: chunks.len() as u32 };
+        Self { manifest, chunks }
+    }
+
+    /// Chunk `index` with its proof against the manifest's `chunk_root`
+    pub fn chunk(&self, index: u32) -> Option<(SnapshotChunk, MerkleProof)> {
+        let chunk = self.chunks.get(index as usize)?;
+        let proof = MerkleProof::generate(&chunk_hashes(&self.chunks), index as usize)?;
+        Some((chunk.clone(), proof))
+    }
+
+    /// The state held in the chunks, once it hashes to the checkpoint's
+    /// state root
+    pub fn state(&self) -> AxiomResult<State> {
+        let chunks = self.chunks.iter().cloned().map(Some).collect();
+        SnapshotAssembler { manifest: self.manifest.clone(), chunks }.into_state()
+    }
+}
+
+fn chunk_hashes(chunks: &[SnapshotChunk]) -> Vec<[u8; 32]> {
+    chunks.iter().map(SnapshotChunk::hash).collect()
+}
+
+/// Collects a snapshot's chunks from peers, checking each on arrival
+#[derive(Debug, Clone)]
+pub struct SnapshotAssembler {
+    manifest: SnapshotManifest,
+    chunks: Vec<Option<SnapshotChunk>>,
+}
+
+impl SnapshotAssembler {
+    /// Start on the snapshot `manifest` describes, provided its checkpoint
+    /// is signed by one of `trusted_signers`
+    pub fn new(manifest: SnapshotManifest, trusted_signers: &[[u8; 32]]) -> AxiomResult<Self> {
+        if !manifest.checkpoint.is_trusted(trusted_signers) {
+            return Err(AxiomError::InvalidSnapshot("untrusted checkpoint".to_string()));
+        }
+        let chunks = vec![None; manifest.chunk_count as usize];
+        Ok(Self { manifest, chunks })
+    }
+
+    pub fn manifest(&self) -> &SnapshotManifest {
+        &self.manifest
+    }
+
+    /// Accept `chunk` if `proof` places it in the manifest's chunk root
+    pub fn add_chunk(&mut self, chunk: SnapshotChunk, proof: &MerkleProof) -> AxiomResult<()> {
+        let index = chunk.index as usize;
+        if index >= self.chunks.len() || proof.index != chunk.index as u64 {
+            return Err(AxiomError::InvalidSnapshot(format!("chunk {} out of place", chunk.index)));
+        }
+        if !proof.verify(chunk.hash(), self.manifest.chunk_root) {
+            return Err(AxiomError::InvalidSnapshot(format!("chunk {} not in chunk root", chunk.index)));
+        }
+        self.chunks[index] = Some(chunk);
+        Ok(())
+    }
+
+    /// Indexes of the chunks still to fetch, lowest first
+    pub fn missing(&self) -> Vec<u32> {
+        (0..self.chunks.len()).filter(|&i| self.chunks[i].is_none()).map(|i| i as u32).collect()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.chunks.iter().all(Option::is_some)
+    }
+
+    /// The assembled state, once it hashes to the checkpoint's state root.
+    /// Address history is not part of a snapshot and starts empty.
+    pub fn into_state(self) -> AxiomResult<State> {
+        let mut state = State::new();
+        for chunk in self.chunks {
+            let chunk = chunk.ok_or_else(|| AxiomError::InvalidSnapshot("missing chunks".to_string()))?;
+            state.balances.extend(chunk.balances);
+            state.nonces.extend(chunk.nonces);
+        }
+        if state.state_root() != self.manifest.checkpoint.state_root {
+            return Err(AxiomError::InvalidSnapshot("state root does not match checkpoint".to_string()));
+        }
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::SigningKey;
+
+    fn state_with(accounts: u8) -> State {
+        let mut state = State::new();
+        for i in 0..accounts {
+            state.credit([i; 32], 1_000 + i as u64);
+            if i % 2 == 0 {
+                state.nonces.insert([i; 32], i as u64);
+            }
+        }
+        state
+    }
+
+    fn checkpoint_of(state: &State, key: &SigningKey) -> Checkpoint {
+        Checkpoint::sign(10, [1u8; 32], state.state_root(), 1000, key)
+    }
+
+    #[test]
+    fn test_snapshot_reassembles_to_checkpoint_root() {
+        let key = SigningKey::from_bytes(&[5u8; 32]);
+        let signer = key.verifying_key().to_bytes();
+        let state = state_with(200);
+        let snapshot = Snapshot::with_chunk_accounts(checkpoint_of(&state, &key), &state, 64);
+        assert_eq!(snapshot.manifest.chunk_count, 4);
+        assert_eq!(Snapshot::new(snapshot.manifest.checkpoint.clone(), &state).manifest.chunk_count, 1);
+
+        // Chunks may arrive in any order
+        let mut assembler = SnapshotAssembler::new(snapshot.manifest.clone(), &[signer]).unwrap();
+        for index in [2, 0, 3] {
+            let (chunk, proof) = snapshot.chunk(index).unwrap();
+            assembler.add_chunk(chunk, &proof).unwrap();
+        }
+        assert_eq!(assembler.missing(), vec![1]);
+        assert!(!assembler.is_complete());
+        assert!(assembler.clone().into_state().is_err());
+
+        let (chunk, proof) = snapshot.chunk(1).unwrap();
+        assembler.add_chunk(chunk, &proof).unwrap();
+        assert!(assembler.is_complete());
+        let rebuilt = assembler.into_state().unwrap();
+        assert_eq!(rebuilt.state_root(), state.state_root());
+        assert_eq!(rebuilt.balance(&[7u8; 32]), 1_007);
+        assert_eq!(rebuilt.nonce(&[8u8; 32]), 8);
+        assert_eq!(snapshot.state().unwrap().state_root(), state.state_root());
+    }
+
+    #[test]
+    fn test_tampered_snapshot_rejected() {
+        let key = SigningKey::from_bytes(&[5u8; 32]);
+        let signer = key.verifying_key().to_bytes();
+        let state = state_with(5);
+        let snapshot = Snapshot::new(checkpoint_of(&state, &key), &state);
+        assert_eq!(snapshot.manifest.chunk_count, 1);
+
+        // Checkpoint from a signer we do not trust
+        assert!(SnapshotAssembler::new(snapshot.manifest.clone(), &[[1u8; 32]]).is_err());
+
+        // A chunk altered in transit no longer matches the chunk root
+        let mut assembler = SnapshotAssembler::new(snapshot.manifest.clone(), &[signer]).unwrap();
+        let (mut chunk, proof) = snapshot.chunk(0).unwrap();
+        chunk.balances[0].1 += 1;
+        assert!(matches!(assembler.add_chunk(chunk, &proof), Err(AxiomError::InvalidSnapshot(_))));
+        assert!(!assembler.is_complete());
+
+        // A server that commits to the wrong state passes chunk checks but
+        // fails the checkpoint's state root
+        let mut forged_state = state.clone();
+        forged_state.credit([9u8; 32], 1);
+        let forged = Snapshot::new(snapshot.manifest.checkpoint.clone(), &forged_state);
+        let mut assembler = SnapshotAssembler::new(forged.manifest.clone(), &[signer]).unwrap();
+        let (chunk, proof) = forged.chunk(0).unwrap();
+        assembler.add_chunk(chunk, &proof).unwrap();
+        assert!(matches!(assembler.into_state(), Err(AxiomError::InvalidSnapshot(_))));
+        assert!(matches!(forged.state(), Err(AxiomError::InvalidSnapshot(_))));
+    }
+}
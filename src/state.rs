@@ -4,6 +4,7 @@ pub struct StateSnapshot {
     pub balances: HashMap<Address, u64>,
     pub total_issued: u64,
     pub nonces: HashMap<Address, u64>,
+    pub address_txs: HashMap<Address, Vec<TxLocation>>,
 }
 
 impl State {
@@ -13,6 +14,7 @@ impl State {
             balances: self.balances.clone(),
             total_issued: self.total_issued,
             nonces: self.nonces.clone(),
+            address_txs: self.address_txs.clone(),
         }
     }
 
@@ -21,17 +23,55 @@ impl State {
         self.balances = snapshot.balances.clone();
         self.total_issued = snapshot.total_issued;
         self.nonces = snapshot.nonces.clone();
+        self.address_txs = snapshot.address_txs.clone();
+        self.journal = None;
     }
 }
 // Transaction nonce system is already implemented and functional.
 use std::collections::HashMap;
-use crate::transaction::{Transaction, Address};
+use serde::{Deserialize, Serialize};
+use crate::economics;
+use crate::transaction::{Transaction, Address, COINBASE_SENDER};
 
-#[derive(Clone)]
+/// Account index: balance and nonce per address, updated incrementally as
+/// blocks are applied so lookups never scan the chain
+#[derive(Clone, Serialize, Deserialize)]
 pub struct State {
     pub balances: HashMap<Address, u64>,
     pub total_issued: u64,
     pub nonces: HashMap<Address, u64>,
+    /// Where each address's transactions are, oldest first. Not persisted:
+    /// the node rebuilds it with the rest of the state from blocks.
+    #[serde(skip)]
+    address_txs: HashMap<Address, Vec<TxLocation>>,
+    /// Prior values of accounts changed since `begin_journal`
+    #[serde(skip)]
+    journal: Option<StateUndo>,
+}
+
+/// Balance and nonce of one address
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountInfo {
+    pub balance: u64,
+    pub nonce: u64,
+}
+
+/// Position of a transaction in the chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxLocation {
+    pub height: u64,
+    /// Index in the block's `transactions`, coinbase included
+    pub index: u32,
+}
+
+/// Prior values of the accounts one block touched, enough to undo it.
+/// `None` means the account did not exist.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateUndo {
+    balances: Vec<(Address, Option<u64>)>,
+    nonces: Vec<(Address, Option<u64>)>,
+    /// Addresses whose history gained an entry, one per entry
+    indexed: Vec<Address>,
 }
 
 impl State {
@@ -40,6 +80,56 @@ impl State {
             balances: HashMap::new(),
             total_issued: 0,
             nonces: HashMap::new(),
+            address_txs: HashMap::new(),
+            journal: None,
+        }
+    }
+
+    /// Start recording prior values of every account changed from now on
+    pub fn begin_journal(&mut self) {
+        self.journal = Some(StateUndo::default());
+    }
+
+    /// Stop recording and return what changed since `begin_journal`
+    pub fn take_journal(&mut self) -> StateUndo {
+        self.journal.take().unwrap_or_default()
+    }
+
+    /// Restore every account recorded in `undo` to its prior value
+    pub fn revert(&mut self, undo: &StateUndo) {
+        for (addr, prior) in undo.balances.iter().rev() {
+            match prior {
+                Some(balance) => self.balances.insert(*addr, *balance),
+                None => self.balances.remove(addr),
+            };
+        }
+        for (addr, prior) in undo.nonces.iter().rev() {
+            match prior {
+                Some(nonce) => self.nonces.insert(*addr, *nonce),
+                None => self.nonces.remove(addr),
+            };
+        }
+        for addr in undo.indexed.iter().rev() {
+            if let Some(history) = self.address_txs.get_mut(addr) {
+                history.pop();
+                if history.is_empty() {
+                    self.address_txs.remove(addr);
+                }
+            }
+        }
+    }
+
+    fn set_balance(&mut self, addr: Address, balance: u64) {
+        let prior = self.balances.insert(addr, balance);
+        if let Some(journal) = &mut self.journal {
+            journal.balances.push((addr, prior));
+        }
+    }
+
+    fn set_nonce(&mut self, addr: Address, nonce: u64) {
+        let prior = self.nonces.insert(addr, nonce);
+        if let Some(journal) = &mut self.journal {
+            journal.nonces.push((addr, prior));
         }
     }
 
@@ -51,9 +141,24 @@ impl State {
         *self.nonces.get(addr).unwrap_or(&0)
     }
 
+    pub fn account(&self, addr: &Address) -> AccountInfo {
+        AccountInfo { balance: self.balance(addr), nonce: self.nonce(addr) }
+    }
+
+    /// Every account in `addrs` loaded in one pass, for validating or
+    /// building a block without a lookup per transaction. Unknown addresses
+    /// get a zero balance and nonce.
+    pub fn get_accounts(&self, addrs: &[Address]) -> HashMap<Address, AccountInfo> {
+        let mut accounts = HashMap::with_capacity(addrs.len());
+        for addr in addrs {
+            accounts.entry(*addr).or_insert_with(|| self.account(addr));
+        }
+        accounts
+    }
+
     pub fn credit(&mut self, addr: Address, amount: u64) {
         let bal = self.balance(&addr);
-        self.balances.insert(addr, bal + amount);
+        self.set_balance(addr, bal + amount);
     }
 
     pub fn debit(&mut self, addr: Address, amount: u64) -> Result<(), &'static str> {
@@ -61,14 +166,14 @@ impl State {
         if bal < amount {
             return Err("Insufficient balance for debit");
         }
-        self.balances.insert(addr, bal - amount);
+        self.set_balance(addr, bal - amount);
         Ok(())
     }
 
     pub fn apply_tx(&mut self, tx: &Transaction) -> Result<(), &'static str> {
         let sender_bal = self.balance(&tx.from);
         let sender_nonce = self.nonce(&tx.from);
-        let cost = tx.amount + tx.fee;
+        let cost = tx.amount.checked_add(tx.fee).ok_or("Amount overflow")?;
 
         if sender_bal < cost {
             return Err("Insufficient balance");
@@ -79,17 +184,117 @@ impl State {
         }
 
         // Apply transaction
-        self.balances.insert(tx.from, sender_bal - cost);
+        self.set_balance(tx.from, sender_bal - cost);
         self.credit(tx.to, tx.amount);
-        self.nonces.insert(tx.from, sender_nonce + 1);
+        self.set_nonce(tx.from, sender_nonce + 1);
 
         Ok(())
     }
 
+    /// Record the transaction at `location` in the history of its sender
+    /// and recipient. `COINBASE_SENDER` (genesis allocations, coinbases)
+    /// gets no history.
+    pub fn index_tx(&mut self, tx: &Transaction, location: TxLocation) {
+        let mut addrs = vec![tx.from];
+        if tx.to != tx.from {
+            addrs.push(tx.to);
+        }
+        for addr in addrs.into_iter().filter(|addr| *addr != COINBASE_SENDER) {
+            self.address_txs.entry(addr).or_default().push(location);
+            if let Some(journal) = &mut self.journal {
+                journal.indexed.push(addr);
+            }
+        }
+    }
+
+    /// Number of transactions sent or received by `addr`
+    pub fn history_len(&self, addr: &Address) -> usize {
+        self.address_txs.get(addr).map_or(0, Vec::len)
+    }
+
+    /// Up to `limit` of `addr`'s transactions, newest first, skipping the
+    /// `offset` newest
+    pub fn address_history(&self, addr: &Address, offset: usize, limit: usize) -> Vec<TxLocation> {
+        self.address_txs
+            .get(addr)
+            .map(|history| history.iter().rev().skip(offset).take(limit).copied().collect())
+            .unwrap_or_default()
+    }
+
     /// Get next nonce for address
     pub fn next_nonce(&self, addr: &Address) -> u64 {
         self.nonce(addr) + 1
     }
+
+    /// Deterministic commitment to all balances and nonces.
+    /// Accounts are hashed in address order so the root is independent of map iteration.
+    pub fn state_root(&self) -> [u8; 32] {
+        let mut balances: Vec<_> = self.balances.iter().collect();
+        balances.sort();
+        let mut nonces: Vec<_> = self.nonces.iter().collect();
+        nonces.sort();
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(crate::hashing::STATE_ROOT);
+        for (addr, balance) in balances {
+            hasher.update(addr);
+            hasher.update(&balance.to_le_bytes());
+        }
+        hasher.update(b"nonces");
+        for (addr, nonce) in nonces {
+            hasher.update(addr);
+            hasher.update(&nonce.to_le_bytes());
+        }
+        *hasher.finalize().as_bytes()
+    }
+}
+
+/// Balances do not add up to the supply the chain has issued
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SupplyMismatch {
+    pub height: u64,
+    /// Issued rewards plus genesis allocations, minus burned fees
+    pub expected: u128,
+    /// Sum of all account balances
+    pub actual: u128,
+    /// `actual - expected`; positive means coins appeared from nowhere
+    pub delta: i128,
+}
+
+impl std::fmt::Display for SupplyMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "supply mismatch at height {}: balances sum to {}, expected {} (delta {:+})",
+            self.height, self.actual, self.expected, self.delta
+        )
+    }
+}
+
+impl std::error::Error for SupplyMismatch {}
+
+impl State {
+    /// Sum of every account balance
+    pub fn total_balance(&self) -> u128 {
+        self.balances.values().map(|&b| b as u128).sum()
+    }
+
+    /// Check that balances account for exactly the coins in existence at
+    /// `height`: every block reward so far plus `genesis_allocations`, less
+    /// `total_burned` fees. Fees paid to miners only move coins, so they
+    /// cancel out.
+    pub fn audit_supply(&self, height: u64, total_burned: u64, genesis_allocations: u64) -> Result<(), SupplyMismatch> {
+        // Genesis pays no reward, so blocks 1..=height have issued everything
+        // up to `height + 1` except the slot-0 reward
+        let issued = economics::calculate_total_supply(height.saturating_add(1))
+            .saturating_sub(economics::calculate_total_supply(1));
+        let expected = (issued as u128 + genesis_allocations as u128).saturating_sub(total_burned as u128);
+        let actual = self.total_balance();
+        if actual == expected {
+            return Ok(());
+        }
+        Err(SupplyMismatch { height, expected, actual, delta: actual as i128 - expected as i128 })
+    }
 }
 
 impl Default for State {
@@ -97,3 +302,130 @@ impl Default for State {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_and_revert_block_changes() {
+        let (sender, receiver, miner) = ([1u8; 32], [2u8; 32], [3u8; 32]);
+        let mut state = State::new();
+        state.credit(sender, 1_000);
+        let before = state.state_root();
+
+        state.begin_journal();
+        state.credit(miner, 50);
+        state.apply_tx(&Transaction::new(sender, receiver, 300, 20, 0, vec![], vec![])).unwrap();
+        state.apply_tx(&Transaction::new(sender, receiver, 100, 20, 1, vec![], vec![])).unwrap();
+        state.credit(miner, 40);
+        let undo = state.take_journal();
+
+        assert_eq!(state.balance(&sender), 1_000 - 400 - 40);
+        assert_eq!(state.balance(&receiver), 400);
+        assert_eq!(state.balance(&miner), 90);
+        assert_eq!(state.nonce(&sender), 2);
+
+        state.revert(&undo);
+        assert_eq!(state.balance(&sender), 1_000);
+        assert_eq!(state.nonce(&sender), 0);
+        // Accounts the block created are gone, not left at zero
+        assert!(!state.balances.contains_key(&receiver));
+        assert!(!state.nonces.contains_key(&sender));
+        assert_eq!(state.state_root(), before);
+    }
+
+    #[test]
+    fn test_address_history_pages_newest_first() {
+        let (alice, bob, carol) = ([1u8; 32], [2u8; 32], [3u8; 32]);
+        let mut state = State::new();
+        state.credit(alice, 1_000);
+        state.credit(bob, 1_000);
+
+        // Two blocks of transfers, each applied then indexed at its place
+        let blocks = [
+            vec![
+                Transaction::new(alice, bob, 100, 1, 0, vec![], vec![]),
+                Transaction::new(bob, carol, 50, 1, 0, vec![], vec![]),
+            ],
+            vec![
+                Transaction::new(alice, carol, 10, 1, 1, vec![], vec![]),
+                Transaction::new(alice, alice, 5, 1, 2, vec![], vec![]),
+            ],
+        ];
+        let mut undos = Vec::new();
+        for (height, txs) in blocks.iter().enumerate() {
+            state.begin_journal();
+            for (index, tx) in txs.iter().enumerate() {
+                state.apply_tx(tx).unwrap();
+                let location = TxLocation { height: height as u64 + 1, index: index as u32 + 1 };
+                state.index_tx(tx, location);
+            }
+            undos.push(state.take_journal());
+        }
+        let at = |height, index| TxLocation { height, index };
+
+        // A transfer to oneself is listed once
+        assert_eq!(state.history_len(&alice), 3);
+        assert_eq!(state.address_history(&alice, 0, 10), vec![at(2, 2), at(2, 1), at(1, 1)]);
+        assert_eq!(state.address_history(&alice, 0, 2), vec![at(2, 2), at(2, 1)]);
+        assert_eq!(state.address_history(&alice, 2, 2), vec![at(1, 1)]);
+        assert!(state.address_history(&alice, 3, 2).is_empty());
+        assert_eq!(state.address_history(&bob, 0, 10), vec![at(1, 2), at(1, 1)]);
+        assert_eq!(state.address_history(&carol, 1, 1), vec![at(1, 2)]);
+        assert!(state.address_history(&[9u8; 32], 0, 10).is_empty());
+
+        // Reverting the last block takes its entries off the front
+        state.revert(&undos.pop().unwrap());
+        assert_eq!(state.address_history(&alice, 0, 10), vec![at(1, 1)]);
+        assert_eq!(state.address_history(&carol, 0, 10), vec![at(1, 2)]);
+        assert_eq!(state.history_len(&bob), 2);
+    }
+
+    #[test]
+    fn test_get_accounts_matches_individual_lookups() {
+        let (alice, bob, unknown) = ([1u8; 32], [2u8; 32], [9u8; 32]);
+        let mut state = State::new();
+        state.credit(alice, 1_000);
+        state.apply_tx(&Transaction::new(alice, bob, 250, 10, 0, vec![], vec![])).unwrap();
+
+        let addrs = [alice, bob, unknown, alice];
+        let accounts = state.get_accounts(&addrs);
+        assert_eq!(accounts.len(), 3);
+        for addr in &addrs {
+            assert_eq!(accounts[addr], AccountInfo { balance: state.balance(addr), nonce: state.nonce(addr) });
+        }
+        assert_eq!(accounts[&alice], AccountInfo { balance: 740, nonce: 1 });
+        assert_eq!(accounts[&unknown], AccountInfo::default());
+        assert!(!state.balances.contains_key(&unknown));
+    }
+
+    #[test]
+    fn test_audit_supply_reports_exact_delta() {
+        let reward = economics::block_reward(1, 0);
+        let mut state = State::new();
+        state.credit([1u8; 32], 500);
+        state.credit([2u8; 32], reward * 2 - 40);
+        // Two blocks mined, 40 burned, 500 allocated at genesis
+        assert_eq!(state.audit_supply(2, 40, 500), Ok(()));
+
+        state.credit([3u8; 32], 7);
+        let mismatch = state.audit_supply(2, 40, 500).unwrap_err();
+        assert_eq!(mismatch.delta, 7);
+        assert_eq!(mismatch.actual, mismatch.expected + 7);
+        assert!(state.audit_supply(3, 40, 500).unwrap_err().delta < 0);
+    }
+
+    #[test]
+    fn test_amount_plus_fee_overflow_rejected() {
+        let sender = [1u8; 32];
+        let mut state = State::new();
+        state.credit(sender, u64::MAX);
+        let before = state.state_root();
+
+        let tx = Transaction::new(sender, [2u8; 32], u64::MAX, 1, 0, vec![], vec![]);
+        assert_eq!(tx.validate(u64::MAX), Err("Amount overflow"));
+        assert_eq!(state.apply_tx(&tx), Err("Amount overflow"));
+        assert_eq!(state.state_root(), before);
+    }
+}
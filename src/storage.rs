@@ -1,38 +1,103 @@
-use std::fs::File;
-use std::io::{Read, Write};
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, Read, Seek, SeekFrom, Write};
+use std::ops::{Bound, RangeBounds};
+use std::path::{Path, PathBuf};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
 use crate::block::Block;
+use crate::config::PruningMode;
+use crate::snapshot::Snapshot;
+use crate::state::State;
+use crate::transaction::Transaction;
+use crate::error::{AxiomError, Result};
 
 const DB_PATH: &str = "axiom_chain.dat";
+const BLOCK_LOG_PATH: &str = "axiom_blocks.log";
 
-/// ATOMIC SAVE: Writes the entire chain to disk.
-/// Uses a temporary file strategy to ensure that a crash during saving
-/// does not corrupt the existing blockchain data.
-pub fn save_chain(blocks: &[Block]) {
-    let encoded = match bincode::serialize(blocks) {
-        Ok(data) => data,
-        Err(e) => {
-            eprintln!("❌ STORAGE ERROR: Serialization failed: {}", e);
-            return;
-        }
-    };
+/// Upper bound on a single block log record; larger length prefixes are treated as corruption
+const MAX_RECORD_SIZE: usize = 64 * 1024 * 1024;
+const CHECKPOINT_PATH: &str = "axiom_checkpoints.dat";
+const MEMPOOL_PATH: &str = "axiom_mempool.dat";
+const STATE_PATH: &str = "axiom_state.dat";
+const SNAPSHOT_PATH: &str = "axiom_snapshot.dat";
 
-    // Use a temporary file to prevent corruption during an interrupted write
-    let temp_path = format!("{}.tmp", DB_PATH);
-    match File::create(&temp_path) {
-        Ok(mut file) => {
-            if file.write_all(&encoded).is_ok() {
-                // Atomic rename: This is the moment the "Self-Healing" is locked in
-                let _ = std::fs::rename(temp_path, DB_PATH);
-            }
-        }
-        Err(e) => eprintln!("❌ STORAGE ERROR: Could not write to disk: {}", e),
+/// Blocks between automatic checkpoints
+pub const CHECKPOINT_INTERVAL: u64 = 1_000;
+
+/// ATOMIC SAVE: Rewrites the entire chain to disk.
+/// Used when the chain is replaced (sync/reorg); single new blocks should
+/// go through `append_block` instead. The block log is rewritten via a
+/// temporary file so a crash during saving does not corrupt existing data.
+pub fn save_chain(blocks: &[Block]) {
+    if let Err(e) = BlockStore::default().write_all(blocks) {
+        eprintln!("❌ STORAGE ERROR: Could not write to disk: {}", e);
     }
 }
 
+/// Move the default block log aside (see `BlockStore::set_aside`)
+pub fn set_aside_chain() -> Result<Option<PathBuf>> {
+    BlockStore::default().set_aside()
+}
+
+/// Append a single accepted block to the block log
+pub fn append_block(block: &Block) -> Result<()> {
+    BlockStore::default().append(block)
+}
+
+/// Prune transaction bodies from the default block log (see `BlockStore::prune`)
+pub fn prune(mode: PruningMode, keep_depth: u64) -> Result<usize> {
+    BlockStore::default().prune(mode, keep_depth)
+}
+
+/// Lazily stream blocks in `range` from the default block log, in height order
+pub fn iter_blocks(range: impl RangeBounds<u64>) -> BlockIter {
+    BlockStore::default().iter(range)
+}
+
+/// Random access to a single block in the default block log
+pub fn block_at(height: u64) -> Result<Block> {
+    BlockStore::default().block_at(height)
+}
+
+/// Height of the last block in the default block log
+pub fn tip_height() -> Result<u64> {
+    BlockStore::default().tip_height()
+}
+
 /// SELF-HEALING LOAD: Recovers the chain from the binary database.
 /// If corruption is detected (e.g., via Injection Attack), it returns None
 /// to trigger a fresh sync from the decentralized P2P network.
+/// Pruned logs cannot be replayed in full and also load as None; see
+/// `load_records`.
 pub fn load_chain() -> Option<Vec<Block>> {
+    let records = load_records()?;
+    if records.iter().any(|r| r.pruned) {
+        eprintln!("⚠️ STORAGE WARNING: Block bodies are pruned; full replay unavailable. Resyncing.");
+        return None;
+    }
+    Some(records.into_iter().map(|r| r.block).collect())
+}
+
+/// Load every record of the block log, pruned headers included, with the
+/// same self-healing as `load_chain`
+pub fn load_records() -> Option<Vec<StoredBlock>> {
+    let store = BlockStore::default();
+    if store.path().exists() {
+        return match store.load() {
+            Ok(stored) if !stored.is_empty() => {
+                println!("✅ STORAGE: Loaded {} blocks. Integrity verified.", stored.len());
+                Some(stored)
+            }
+            Ok(_) => None,
+            Err(e) => {
+                eprintln!("⚠️ STORAGE WARNING: Failed to decode chain ({}). Corruption detected. Starting fresh.", e);
+                let _ = std::fs::remove_file(store.path());
+                None
+            }
+        };
+    }
+
+    // Legacy single-file format
     let mut file = match File::open(DB_PATH) {
         Ok(f) => f,
         Err(_) => return None, // Normal for first-time launch
@@ -51,7 +116,7 @@ pub fn load_chain() -> Option<Vec<Block>> {
     match bincode::deserialize::<Vec<Block>>(&content) {
         Ok(blocks) => {
             println!("✅ STORAGE: Loaded {} blocks. Integrity verified.", blocks.len());
-            Some(blocks)
+            Some(blocks.into_iter().map(StoredBlock::new).collect())
         },
         Err(e) => {
             eprintln!("⚠️ STORAGE WARNING: Failed to decode chain ({}). Corruption detected. Starting fresh.", e);
@@ -61,3 +126,803 @@ pub fn load_chain() -> Option<Vec<Block>> {
         }
     }
 }
+
+/// `keep_depth` for `prune` at chain height `tip`. Bodies are only dropped
+/// below the checkpoint of the saved snapshot, which a restart needs in
+/// their place, and never within `confirmation_depth` of the tip. Without
+/// a snapshot nothing is pruned.
+pub fn prune_depth(tip: u64, confirmation_depth: u64, snapshot_height: Option<u64>) -> u64 {
+    let unpruned = match snapshot_height {
+        Some(height) => tip + 1 - height.min(tip),
+        None => tip + 1,
+    };
+    unpruned.max(confirmation_depth)
+}
+
+// ==================== BLOCK LOG ====================
+
+/// A block as kept in the block log. `hash` is the hash of the full block
+/// and is retained after pruning so header linkage can still be verified.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StoredBlock {
+    pub hash: [u8; 32],
+    pub block: Block,
+    /// Transaction bodies have been discarded
+    pub pruned: bool,
+}
+
+impl StoredBlock {
+    pub fn new(block: Block) -> Self {
+        Self { hash: block.hash(), block, pruned: false }
+    }
+
+    /// Whether the stored body (if any) still matches the recorded hash
+    pub fn body_matches(&self) -> bool {
+        self.pruned || self.block.hash() == self.hash
+    }
+}
+
+/// Append-only block log: a sequence of length-prefixed bincode records,
+/// one per block in slot order. A sidecar `.idx` file holds the byte offset
+/// of each record (u64 LE) for random access; it is rebuilt from the log
+/// whenever it is missing or out of step.
+pub struct BlockStore {
+    path: PathBuf,
+}
+
+impl Default for BlockStore {
+    fn default() -> Self {
+        Self::open(BLOCK_LOG_PATH)
+    }
+}
+
+impl BlockStore {
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append one block to the end of the log
+    pub fn append(&self, block: &Block) -> Result<()> {
+        self.ensure_index()?;
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        let offset = file.metadata()?.len();
+        file.write_all(&encode_record(&StoredBlock::new(block.clone()))?)?;
+        file.sync_data()?;
+
+        let mut index = OpenOptions::new().create(true).append(true).open(self.index_path())?;
+        index.write_all(&offset.to_le_bytes())?;
+        Ok(())
+    }
+
+    /// Replace the whole log with `blocks`. Pruned records the new chain
+    /// still holds, whole or as the same header, are kept as they are so
+    /// their recorded hashes survive.
+    pub fn write_all(&self, blocks: &[Block]) -> Result<()> {
+        let mut existing = self.load().unwrap_or_default().into_iter();
+        let records: Vec<StoredBlock> = blocks
+            .iter()
+            .map(|block| match existing.next() {
+                Some(record) if record.pruned && (record.hash == block.hash() || record.block == *block) => record,
+                _ => StoredBlock::new(block.clone()),
+            })
+            .collect();
+        self.rewrite(&records)
+    }
+
+    /// Rename the log to `<log>.<unix time>.bak`, keeping a chain this
+    /// node cannot restore for inspection instead of overwriting it.
+    /// Returns the new path, or None if there is no log.
+    pub fn set_aside(&self) -> Result<Option<PathBuf>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let mut aside = self.path.clone().into_os_string();
+        aside.push(format!(".{}.bak", secs));
+        let aside = PathBuf::from(aside);
+        std::fs::rename(&self.path, &aside)?;
+        // The index is rebuilt from whatever log takes its place
+        let _ = std::fs::remove_file(self.index_path());
+        Ok(Some(aside))
+    }
+
+    fn rewrite(&self, records: &[StoredBlock]) -> Result<()> {
+        let mut encoded = Vec::new();
+        let mut index = Vec::with_capacity(records.len() * 8);
+        for record in records {
+            index.extend_from_slice(&(encoded.len() as u64).to_le_bytes());
+            encoded.extend_from_slice(&encode_record(record)?);
+        }
+        let temp_path = self.path.with_extension("tmp");
+        std::fs::write(&temp_path, encoded)?;
+        std::fs::rename(temp_path, &self.path)?;
+        std::fs::write(self.index_path(), index)?;
+        Ok(())
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.path.with_extension("idx")
+    }
+
+    /// Make sure the offset index covers exactly the records in the log.
+    /// The last indexed record must end at the end of the log; otherwise the
+    /// index is rebuilt by walking the length prefixes.
+    fn ensure_index(&self) -> Result<()> {
+        let log_len = match std::fs::metadata(&self.path) {
+            Ok(meta) => meta.len(),
+            Err(_) => {
+                let _ = std::fs::remove_file(self.index_path());
+                return Ok(());
+            }
+        };
+        let index_len = std::fs::metadata(self.index_path()).map(|m| m.len()).unwrap_or(0);
+
+        let consistent = if index_len == 0 || !index_len.is_multiple_of(8) {
+            log_len == 0 && index_len == 0
+        } else {
+            let last = self.read_offset(index_len / 8 - 1)?;
+            let mut file = File::open(&self.path)?;
+            file.seek(SeekFrom::Start(last))?;
+            let mut len = [0u8; 4];
+            file.read_exact(&mut len).is_ok() && last + 4 + u32::from_le_bytes(len) as u64 == log_len
+        };
+        if consistent {
+            return Ok(());
+        }
+
+        let mut file = BufReader::new(File::open(&self.path)?);
+        let mut index = Vec::new();
+        let mut offset = 0u64;
+        while offset < log_len {
+            let mut len = [0u8; 4];
+            file.read_exact(&mut len)?;
+            let len = u32::from_le_bytes(len) as u64;
+            if offset + 4 + len > log_len {
+                return Err(AxiomError::StateCorruption(format!("truncated block record at offset {}", offset)));
+            }
+            index.extend_from_slice(&offset.to_le_bytes());
+            file.seek_relative(len as i64)?;
+            offset += 4 + len;
+        }
+        std::fs::write(self.index_path(), index)?;
+        Ok(())
+    }
+
+    fn read_offset(&self, height: u64) -> Result<u64> {
+        let mut index = File::open(self.index_path())?;
+        index.seek(SeekFrom::Start(height * 8))?;
+        let mut offset = [0u8; 8];
+        index.read_exact(&mut offset)?;
+        Ok(u64::from_le_bytes(offset))
+    }
+
+    /// Number of blocks in the log
+    pub fn len(&self) -> Result<u64> {
+        self.ensure_index()?;
+        Ok(std::fs::metadata(self.index_path()).map(|m| m.len() / 8).unwrap_or(0))
+    }
+
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Height of the last stored block
+    pub fn tip_height(&self) -> Result<u64> {
+        self.len()?
+            .checked_sub(1)
+            .ok_or_else(|| AxiomError::BlockNotFound("empty block log".to_string()))
+    }
+
+    /// Read a single block by height
+    pub fn block_at(&self, height: u64) -> Result<Block> {
+        self.iter(height..=height)
+            .next()
+            .unwrap_or_else(|| Err(AxiomError::BlockNotFound(format!("height {}", height))))
+    }
+
+    /// Lazily stream blocks in `range`, clamped to the stored chain.
+    /// Each record is checked against its stored hash; the iterator yields an
+    /// error for a corrupted record and stops.
+    pub fn iter(&self, range: impl RangeBounds<u64>) -> BlockIter {
+        let start = match range.start_bound() {
+            Bound::Included(&h) => h,
+            Bound::Excluded(&h) => h.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&h) => h.saturating_add(1),
+            Bound::Excluded(&h) => h,
+            Bound::Unbounded => u64::MAX,
+        };
+        match self.open_at(start, end) {
+            Ok(iter) => iter,
+            Err(e) => BlockIter { reader: None, remaining: 1, error: Some(e) },
+        }
+    }
+
+    fn open_at(&self, start: u64, end: u64) -> Result<BlockIter> {
+        let len = self.len()?;
+        let end = end.min(len);
+        if start >= end {
+            return Ok(BlockIter { reader: None, remaining: 0, error: None });
+        }
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(self.read_offset(start)?))?;
+        Ok(BlockIter { reader: Some(BufReader::new(file)), remaining: end - start, error: None })
+    }
+
+    fn record_at(&self, height: u64) -> Result<StoredBlock> {
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(self.read_offset(height)?))?;
+        read_record(&mut BufReader::new(file))?
+            .ok_or_else(|| AxiomError::BlockNotFound(format!("height {}", height)))
+    }
+
+    /// Read every record in the log
+    pub fn load(&self) -> Result<Vec<StoredBlock>> {
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let mut records = Vec::new();
+        while let Some(record) = read_record(&mut reader)? {
+            records.push(record);
+        }
+        Ok(records)
+    }
+
+    /// Discard transaction bodies of every block deeper than `keep_depth`
+    /// below the tip, keeping headers. `PruningMode::Archive` keeps everything.
+    /// Returns the number of blocks newly pruned.
+    pub fn prune(&self, mode: PruningMode, keep_depth: u64) -> Result<usize> {
+        if mode == PruningMode::Archive {
+            return Ok(0);
+        }
+        // Pruning always takes a prefix: if the record just below the
+        // cutoff is pruned, so is everything under it
+        let cutoff = self.len()?.saturating_sub(keep_depth);
+        if cutoff == 0 || self.record_at(cutoff - 1)?.pruned {
+            return Ok(0);
+        }
+        let mut records = self.load()?;
+
+        let mut pruned = 0;
+        for record in records.iter_mut().take(cutoff as usize).filter(|r| !r.pruned) {
+            record.block.transactions.clear();
+            record.pruned = true;
+            pruned += 1;
+        }
+        if pruned > 0 {
+            self.rewrite(&records)?;
+        }
+        Ok(pruned)
+    }
+
+    /// Verify header linkage of the stored chain using retained hashes.
+    /// Returns the tip height.
+    pub fn verify_headers(&self) -> Result<u64> {
+        let records = self.load()?;
+        for (height, record) in records.iter().enumerate() {
+            if record.block.slot != height as u64 {
+                return Err(AxiomError::InvalidBlock(format!("slot mismatch at height {}", height)));
+            }
+            if !record.body_matches() {
+                return Err(AxiomError::InvalidBlock(format!("hash mismatch at height {}", height)));
+            }
+            if height > 0 && record.block.parent != records[height - 1].hash {
+                return Err(AxiomError::InvalidBlock(format!("broken parent link at height {}", height)));
+            }
+        }
+        records
+            .len()
+            .checked_sub(1)
+            .map(|tip| tip as u64)
+            .ok_or_else(|| AxiomError::BlockNotFound("empty block log".to_string()))
+    }
+}
+
+/// Streaming iterator over a height range of the block log
+pub struct BlockIter {
+    reader: Option<BufReader<File>>,
+    remaining: u64,
+    error: Option<AxiomError>,
+}
+
+impl Iterator for BlockIter {
+    type Item = Result<Block>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        if let Some(e) = self.error.take() {
+            self.remaining = 0;
+            return Some(Err(e));
+        }
+
+        let record = match read_record(self.reader.as_mut()?) {
+            Ok(Some(record)) if record.body_matches() => return Some(Ok(record.block)),
+            Ok(Some(record)) => Err(AxiomError::StateCorruption(format!("hash mismatch at height {}", record.block.slot))),
+            Ok(None) => Err(AxiomError::StateCorruption("block log ended early".to_string())),
+            Err(e) => Err(e),
+        };
+        self.remaining = 0;
+        Some(record)
+    }
+}
+
+fn encode_record(record: &StoredBlock) -> Result<Vec<u8>> {
+    let body = bincode::serialize(record)?;
+    let mut out = (body.len() as u32).to_le_bytes().to_vec();
+    out.extend_from_slice(&body);
+    Ok(out)
+}
+
+/// Read the next record, or `None` at a clean end of file
+fn read_record(reader: &mut impl Read) -> Result<Option<StoredBlock>> {
+    let mut len = [0u8; 4];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_RECORD_SIZE {
+        return Err(AxiomError::StateCorruption(format!("block record too large ({} bytes)", len)));
+    }
+    let mut body = vec![0u8; len];
+    reader.read_exact(&mut body)?;
+    bincode::deserialize(&body).map(Some).map_err(|e| AxiomError::DeserializationError(e.to_string()))
+}
+
+// ==================== MEMPOOL ====================
+
+/// Persist pending transactions to the default mempool file (used on shutdown)
+pub fn save_mempool(txs: &[Transaction]) -> Result<()> {
+    save_mempool_at(MEMPOOL_PATH, txs)
+}
+
+/// Persist pending transactions to `path`, atomically
+pub fn save_mempool_at(path: &str, txs: &[Transaction]) -> Result<()> {
+    let encoded = bincode::serialize(txs)?;
+    let temp_path = format!("{}.tmp", path);
+    std::fs::write(&temp_path, encoded)?;
+    std::fs::rename(temp_path, path)?;
+    Ok(())
+}
+
+/// Load transactions saved by `save_mempool`. They must be re-validated
+/// against the current chain before use.
+pub fn load_mempool() -> Vec<Transaction> {
+    load_mempool_at(MEMPOOL_PATH)
+}
+
+/// Load transactions saved at `path`; a missing or corrupt file yields none
+pub fn load_mempool_at(path: &str) -> Vec<Transaction> {
+    let Ok(data) = std::fs::read(path) else { return Vec::new() };
+    bincode::deserialize(&data).unwrap_or_else(|e| {
+        eprintln!("⚠️ STORAGE WARNING: Failed to decode mempool ({}). Ignoring.", e);
+        Vec::new()
+    })
+}
+
+// ==================== STATE INDEX ====================
+
+/// Account index as of the block `block_hash`, written next to the block
+/// log so readers such as the explorer can look up balances without
+/// replaying the chain. The node itself always rebuilds state from blocks.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct StateIndex {
+    pub height: u64,
+    pub block_hash: [u8; 32],
+    pub state: State,
+}
+
+/// Persist the account index to the default state file
+pub fn save_state(height: u64, block_hash: [u8; 32], state: &State) -> Result<()> {
+    save_state_at(STATE_PATH, height, block_hash, state)
+}
+
+/// Persist the account index to `path`, atomically
+pub fn save_state_at(path: &str, height: u64, block_hash: [u8; 32], state: &State) -> Result<()> {
+    let encoded = bincode::serialize(&StateIndex { height, block_hash, state: state.clone() })?;
+    let temp_path = format!("{}.tmp", path);
+    std::fs::write(&temp_path, encoded)?;
+    std::fs::rename(temp_path, path)?;
+    Ok(())
+}
+
+/// Load the account index from the default state file
+pub fn load_state() -> Option<StateIndex> {
+    load_state_at(STATE_PATH)
+}
+
+/// Load the account index saved at `path`; a missing or corrupt file yields none
+pub fn load_state_at(path: &str) -> Option<StateIndex> {
+    let data = std::fs::read(path).ok()?;
+    bincode::deserialize(&data)
+        .map_err(|e| eprintln!("⚠️ STORAGE WARNING: Failed to decode state index ({}). Ignoring.", e))
+        .ok()
+}
+
+// ==================== CHECKPOINTS ====================
+
+/// A signed commitment to the chain at `height`. Nodes that trust the
+/// signer can skip full validation of every block up to this point.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub height: u64,
+    pub block_hash: [u8; 32],
+    pub state_root: [u8; 32],
+    /// Difficulty in effect after `height`, so validation can resume
+    pub difficulty: u64,
+    pub signer: [u8; 32],
+    pub signature: Vec<u8>,
+}
+
+impl Checkpoint {
+    /// Create a checkpoint signed by `key`
+    pub fn sign(height: u64, block_hash: [u8; 32], state_root: [u8; 32], difficulty: u64, key: &SigningKey) -> Self {
+        let mut checkpoint = Self {
+            height,
+            block_hash,
+            state_root,
+            difficulty,
+            signer: key.verifying_key().to_bytes(),
+            signature: Vec::new(),
+        };
+        checkpoint.signature = key.sign(&checkpoint.signing_bytes()).to_bytes().to_vec();
+        checkpoint
+    }
+
+    fn signing_bytes(&self) -> Vec<u8> {
+        let mut msg = b"AXIOM_CHECKPOINT".to_vec();
+        msg.extend_from_slice(&self.height.to_le_bytes());
+        msg.extend_from_slice(&self.block_hash);
+        msg.extend_from_slice(&self.state_root);
+        msg.extend_from_slice(&self.difficulty.to_le_bytes());
+        msg
+    }
+
+    /// Check the signature against the embedded signer key
+    pub fn verify(&self) -> bool {
+        let Ok(key) = VerifyingKey::from_bytes(&self.signer) else { return false };
+        let Ok(signature) = Signature::from_slice(&self.signature) else { return false };
+        key.verify(&self.signing_bytes(), &signature).is_ok()
+    }
+
+    /// A checkpoint is trusted when it is validly signed by one of `trusted_signers`
+    pub fn is_trusted(&self, trusted_signers: &[[u8; 32]]) -> bool {
+        trusted_signers.contains(&self.signer) && self.verify()
+    }
+}
+
+/// Record a checkpoint in the default checkpoint file
+pub fn save_checkpoint(checkpoint: &Checkpoint) -> Result<()> {
+    save_checkpoint_at(CHECKPOINT_PATH, checkpoint)
+}
+
+/// Record a checkpoint in `path`, replacing any existing one at the same height.
+/// Checkpoints are kept sorted by height and written atomically.
+pub fn save_checkpoint_at(path: &str, checkpoint: &Checkpoint) -> Result<()> {
+    let mut checkpoints = load_checkpoints_at(path);
+    checkpoints.retain(|c| c.height != checkpoint.height);
+    checkpoints.push(checkpoint.clone());
+    checkpoints.sort_by_key(|c| c.height);
+
+    let encoded = bincode::serialize(&checkpoints)?;
+    let temp_path = format!("{}.tmp", path);
+    std::fs::write(&temp_path, encoded)?;
+    std::fs::rename(temp_path, path)?;
+    Ok(())
+}
+
+/// Load all checkpoints from the default checkpoint file
+pub fn load_checkpoints() -> Vec<Checkpoint> {
+    load_checkpoints_at(CHECKPOINT_PATH)
+}
+
+/// Load all checkpoints from `path`, lowest height first.
+/// A missing or unreadable file yields no checkpoints.
+pub fn load_checkpoints_at(path: &str) -> Vec<Checkpoint> {
+    let Ok(data) = std::fs::read(path) else { return Vec::new() };
+    match bincode::deserialize::<Vec<Checkpoint>>(&data) {
+        Ok(checkpoints) => checkpoints,
+        Err(e) => {
+            eprintln!("⚠️ STORAGE WARNING: Failed to decode checkpoints ({}). Ignoring.", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Latest checkpoint in `checkpoints` that is trusted and not above `max_height`
+pub fn latest_trusted<'a>(checkpoints: &'a [Checkpoint], trusted_signers: &[[u8; 32]], max_height: u64) -> Option<&'a Checkpoint> {
+    checkpoints
+        .iter()
+        .rev()
+        .find(|c| c.height <= max_height && c.is_trusted(trusted_signers))
+}
+
+/// Keep `snapshot` as the one served to snap-syncing peers
+pub fn save_snapshot(snapshot: &Snapshot) -> Result<()> {
+    save_snapshot_at(SNAPSHOT_PATH, snapshot)
+}
+
+/// Write `snapshot` to `path` atomically, replacing any earlier one
+pub fn save_snapshot_at(path: &str, snapshot: &Snapshot) -> Result<()> {
+    let encoded = bincode::serialize(snapshot)?;
+    let temp_path = format!("{}.tmp", path);
+    std::fs::write(&temp_path, encoded)?;
+    std::fs::rename(temp_path, path)?;
+    Ok(())
+}
+
+/// Load the snapshot saved in the default snapshot file
+pub fn load_snapshot() -> Option<Snapshot> {
+    load_snapshot_at(SNAPSHOT_PATH)
+}
+
+/// Load the snapshot at `path`; `None` if it is missing or unreadable
+pub fn load_snapshot_at(path: &str) -> Option<Snapshot> {
+    let data = std::fs::read(path).ok()?;
+    match bincode::deserialize::<Snapshot>(&data) {
+        Ok(snapshot) => Some(snapshot),
+        Err(e) => {
+            eprintln!("⚠️ STORAGE WARNING: Failed to decode state snapshot ({}). Ignoring.", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("axiom_{}_{}", name, std::process::id()))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn sample_chain(len: u64) -> Vec<Block> {
+        let mut blocks: Vec<Block> = Vec::new();
+        for slot in 0..len {
+            let parent = blocks.last().map(|b| b.hash()).unwrap_or([0u8; 32]);
+            let tx = crate::transaction::Transaction::new([1u8; 32], [2u8; 32], 10, 1, slot, vec![], vec![]);
+            blocks.push(Block::new(parent, slot, [3u8; 32], vec![tx], [0u8; 32], vec![0u8; 128], slot));
+        }
+        blocks
+    }
+
+    #[test]
+    fn test_block_log_append_and_load() {
+        let store = BlockStore::open(temp_path("append.log"));
+        let blocks = sample_chain(5);
+
+        store.write_all(&blocks[..3]).unwrap();
+        store.append(&blocks[3]).unwrap();
+        store.append(&blocks[4]).unwrap();
+
+        let loaded = store.load().unwrap();
+        assert_eq!(loaded.len(), 5);
+        assert!(loaded.iter().zip(&blocks).all(|(s, b)| s.hash == b.hash() && s.body_matches()));
+        assert_eq!(store.verify_headers().unwrap(), 4);
+
+        let _ = std::fs::remove_file(store.path());
+        let _ = std::fs::remove_file(store.index_path());
+    }
+
+    #[test]
+    fn test_set_aside_keeps_old_log() {
+        let store = BlockStore::open(temp_path("aside.log"));
+        assert_eq!(store.set_aside().unwrap(), None);
+
+        let blocks = sample_chain(3);
+        store.write_all(&blocks).unwrap();
+        let aside = store.set_aside().unwrap().unwrap();
+        assert!(!store.path().exists());
+        assert!(!store.index_path().exists());
+
+        // A new chain starts a fresh log; the old one is untouched
+        store.write_all(&blocks[..1]).unwrap();
+        assert_eq!(store.load().unwrap().len(), 1);
+        assert_eq!(BlockStore::open(&aside).load().unwrap().len(), 3);
+
+        let _ = std::fs::remove_file(&aside);
+        let _ = std::fs::remove_file(store.path());
+        let _ = std::fs::remove_file(store.index_path());
+    }
+
+    #[test]
+    fn test_prune_keeps_headers() {
+        let store = BlockStore::open(temp_path("prune.log"));
+        let blocks = sample_chain(10);
+        store.write_all(&blocks).unwrap();
+
+        assert_eq!(store.prune(PruningMode::Archive, 2).unwrap(), 0);
+        assert_eq!(store.prune(PruningMode::Light, 3).unwrap(), 7);
+        assert_eq!(store.prune(PruningMode::Full, 3).unwrap(), 0);
+
+        let loaded = store.load().unwrap();
+        assert_eq!(loaded.len(), 10);
+        for (height, record) in loaded.iter().enumerate() {
+            // Headers remain queryable at every height
+            assert_eq!(record.block.slot, height as u64);
+            assert_eq!(record.hash, blocks[height].hash());
+            assert_eq!(record.pruned, height < 7);
+            assert_eq!(record.block.transactions.is_empty(), height < 7);
+        }
+
+        // Linkage still validates from retained hashes
+        assert_eq!(store.verify_headers().unwrap(), 9);
+
+        // Rewriting the chain, whole or as loaded, keeps the pruned headers
+        store.write_all(&blocks).unwrap();
+        let headers: Vec<Block> = loaded.iter().map(|r| r.block.clone()).collect();
+        store.write_all(&headers).unwrap();
+        assert_eq!(store.load().unwrap().iter().filter(|r| r.pruned).count(), 7);
+        assert_eq!(store.verify_headers().unwrap(), 9);
+
+        let _ = std::fs::remove_file(store.path());
+        let _ = std::fs::remove_file(store.index_path());
+    }
+
+    #[test]
+    fn test_prune_depth_stops_at_snapshot() {
+        // Nothing goes without a snapshot to restart from
+        assert_eq!(prune_depth(2_500, 6, None), 2_501);
+        // Bodies below the snapshot's checkpoint go, the checkpoint's stays
+        assert_eq!(prune_depth(2_500, 6, Some(2_000)), 501);
+        // Never within the confirmation depth
+        assert_eq!(prune_depth(2_003, 6, Some(2_000)), 6);
+        assert_eq!(prune_depth(10, 6, Some(20)), 6);
+    }
+
+    #[test]
+    fn test_iterate_block_range() {
+        let store = BlockStore::open(temp_path("iter.log"));
+        let blocks = sample_chain(8);
+        store.write_all(&blocks[..4]).unwrap();
+        for block in &blocks[4..] {
+            store.append(block).unwrap();
+        }
+
+        assert_eq!(store.tip_height().unwrap(), 7);
+
+        let slots: Vec<u64> = store.iter(2..6).map(|b| b.unwrap().slot).collect();
+        assert_eq!(slots, vec![2, 3, 4, 5]);
+        assert_eq!(store.iter(..).count(), 8);
+        assert_eq!(store.iter(6..100).count(), 2);
+        assert_eq!(store.iter(20..).count(), 0);
+
+        // Random access from both the rewritten and appended parts
+        assert_eq!(store.block_at(3).unwrap().hash(), blocks[3].hash());
+        assert_eq!(store.block_at(5).unwrap().hash(), blocks[5].hash());
+        assert!(matches!(store.block_at(8), Err(AxiomError::BlockNotFound(_))));
+
+        // A lost index is rebuilt from the log
+        let _ = std::fs::remove_file(store.index_path());
+        assert_eq!(store.block_at(6).unwrap().hash(), blocks[6].hash());
+
+        let _ = std::fs::remove_file(store.path());
+        let _ = std::fs::remove_file(store.index_path());
+    }
+
+    #[test]
+    fn test_iterator_reports_corruption() {
+        let store = BlockStore::open(temp_path("corrupt.log"));
+        let blocks = sample_chain(5);
+        store.write_all(&blocks).unwrap();
+
+        // Flip a byte inside the stored hash of block 2
+        let offset = store.read_offset(2).unwrap() as usize;
+        let mut data = std::fs::read(store.path()).unwrap();
+        data[offset + 4 + 5] ^= 0xFF;
+        std::fs::write(store.path(), &data).unwrap();
+
+        let results: Vec<_> = store.iter(..).collect();
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok() && results[1].is_ok());
+        assert!(results[2].is_err());
+        assert!(store.block_at(2).is_err());
+        assert!(store.block_at(4).is_ok());
+
+        // A bogus length prefix is an error, not a giant allocation
+        data[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        std::fs::write(store.path(), &data).unwrap();
+        assert!(store.iter(2..3).next().unwrap().is_err());
+
+        let _ = std::fs::remove_file(store.path());
+        let _ = std::fs::remove_file(store.index_path());
+    }
+
+    #[test]
+    fn test_shutdown_flush_round_trip() {
+        // What the node persists on SIGINT/SIGTERM: full chain + pending txs
+        let store = BlockStore::open(temp_path("shutdown.log"));
+        let mempool_path = temp_path("shutdown_mempool");
+        let blocks = sample_chain(6);
+        let pending = blocks[5].transactions.clone();
+
+        store.write_all(&blocks[..3]).unwrap();
+        for block in &blocks[3..] {
+            store.append(block).unwrap();
+        }
+        save_mempool_at(&mempool_path, &pending).unwrap();
+
+        // Reloading yields the in-memory tip and mempool
+        let reloaded = store.load().unwrap();
+        assert_eq!(reloaded.last().unwrap().hash, blocks.last().unwrap().hash());
+        assert_eq!(store.tip_height().unwrap(), 5);
+        assert_eq!(load_mempool_at(&mempool_path), pending);
+
+        assert!(load_mempool_at(&temp_path("missing_mempool")).is_empty());
+
+        let _ = std::fs::remove_file(store.path());
+        let _ = std::fs::remove_file(store.index_path());
+        let _ = std::fs::remove_file(&mempool_path);
+    }
+
+    #[test]
+    fn test_checkpoint_signature() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let checkpoint = Checkpoint::sign(10, [1u8; 32], [2u8; 32], 1000, &key);
+        let signer = key.verifying_key().to_bytes();
+
+        assert!(checkpoint.verify());
+        assert!(checkpoint.is_trusted(&[signer]));
+        assert!(!checkpoint.is_trusted(&[[9u8; 32]]));
+
+        let mut forged = checkpoint.clone();
+        forged.state_root = [3u8; 32];
+        assert!(!forged.verify());
+    }
+
+    #[test]
+    fn test_checkpoint_persistence() {
+        let path = temp_path("checkpoints");
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let signer = key.verifying_key().to_bytes();
+
+        assert!(load_checkpoints_at(&path).is_empty());
+
+        save_checkpoint_at(&path, &Checkpoint::sign(20, [2u8; 32], [0u8; 32], 1000, &key)).unwrap();
+        save_checkpoint_at(&path, &Checkpoint::sign(10, [1u8; 32], [0u8; 32], 1000, &key)).unwrap();
+        save_checkpoint_at(&path, &Checkpoint::sign(20, [3u8; 32], [0u8; 32], 1000, &key)).unwrap();
+
+        let loaded = load_checkpoints_at(&path);
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].height, 10);
+        assert_eq!(loaded[1].block_hash, [3u8; 32]);
+
+        assert_eq!(latest_trusted(&loaded, &[signer], 15).unwrap().height, 10);
+        assert_eq!(latest_trusted(&loaded, &[signer], 100).unwrap().height, 20);
+        assert!(latest_trusted(&loaded, &[[9u8; 32]], 100).is_none());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_state_index_round_trip() {
+        let path = temp_path("state.dat");
+        assert!(load_state_at(&path).is_none());
+
+        let mut state = State::new();
+        state.credit([5u8; 32], 700);
+        state.nonces.insert([5u8; 32], 2);
+        save_state_at(&path, 4, [9u8; 32], &state).unwrap();
+
+        let index = load_state_at(&path).unwrap();
+        assert_eq!((index.height, index.block_hash), (4, [9u8; 32]));
+        assert_eq!(index.state.balance(&[5u8; 32]), 700);
+        assert_eq!(index.state.nonce(&[5u8; 32]), 2);
+        assert_eq!(index.state.state_root(), state.state_root());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
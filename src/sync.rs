@@ -0,0 +1,277 @@
+//! Chain sync progress.
+//!
+//! Peers reveal their height whenever they send us blocks: a gossiped
+//! block, a full-chain broadcast or a request-response batch. The
+//! `SyncTracker` keeps the latest height seen from each connected peer and
+//! compares the highest against the local tip. Until the node has caught up
+//! it reports `Syncing` and does not mine.
+//!
+//! Peers answer a block request with at most `max_blocks_per_response`
+//! blocks and their tip height. A `BlockDownload` follows one peer through
+//! successive ranges until the local tip reaches that peer's, and is
+//! cancelled in favour of a peer that turns out to be further ahead.
+
+use std::collections::HashMap;
+
+use libp2p::PeerId;
+use serde::Serialize;
+
+use crate::network::ChainRequest;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum SyncStatus {
+    /// Local tip at `current`, best known peer tip at `target`
+    Syncing { current: u64, target: u64 },
+    Synced,
+}
+
+impl SyncStatus {
+    pub fn is_synced(&self) -> bool {
+        *self == SyncStatus::Synced
+    }
+
+    /// Fraction of the target height reached, 1.0 once synced
+    pub fn progress(&self) -> f64 {
+        match self {
+            SyncStatus::Syncing { current, target } if *target > 0 => *current as f64 / *target as f64,
+            _ => 1.0,
+        }
+    }
+}
+
+/// Heights announced by connected peers
+#[derive(Debug, Default)]
+pub struct SyncTracker {
+    peer_heights: HashMap<PeerId, u64>,
+    download: Option<DownloadProgress>,
+}
+
+impl SyncTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that `peer` has a chain at least `height` blocks high
+    pub fn observe(&mut self, peer: PeerId, height: u64) {
+        let known = self.peer_heights.entry(peer).or_insert(0);
+        *known = (*known).max(height);
+    }
+
+    /// Forget a disconnected peer's height
+    pub fn remove_peer(&mut self, peer: &PeerId) {
+        self.peer_heights.remove(peer);
+    }
+
+    /// Highest height announced by a connected peer
+    pub fn best_peer_height(&self) -> Option<u64> {
+        self.peer_heights.values().copied().max()
+    }
+
+    /// Status of a node whose tip is at `local_height`. With no peer
+    /// heights known there is nothing to catch up to.
+    pub fn status(&self, local_height: u64) -> SyncStatus {
+        match self.best_peer_height() {
+            Some(target) if target > local_height => SyncStatus::Syncing { current: local_height, target },
+            _ => SyncStatus::Synced,
+        }
+    }
+
+    /// Progress of the block download under way, if any
+    pub fn download(&self) -> Option<DownloadProgress> {
+        self.download
+    }
+
+    pub fn set_download(&mut self, progress: Option<DownloadProgress>) {
+        self.download = progress;
+    }
+}
+
+/// Blocks fetched by a download, out of the `total` it needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DownloadProgress {
+    pub fetched: u64,
+    pub total: u64,
+}
+
+/// A chain download from one peer, one capped batch at a time
+#[derive(Debug, Clone)]
+pub struct BlockDownload {
+    peer: PeerId,
+    /// Local tip when the download started
+    start_height: u64,
+    /// Local tip after the latest batch
+    height: u64,
+    /// Peer's tip, as of its latest response
+    target: u64,
+    cancelled: bool,
+}
+
+impl BlockDownload {
+    /// Download from `peer`, whose tip is at `target`, onto a local tip at
+    /// `local_height`
+    pub fn new(peer: PeerId, local_height: u64, target: u64) -> Self {
+        Self { peer, start_height: local_height, height: local_height, target, cancelled: false }
+    }
+
+    pub fn peer(&self) -> PeerId {
+        self.peer
+    }
+
+    /// Request for the next range after the local tip
+    pub fn request(&self) -> ChainRequest {
+        ChainRequest::blocks_from(self.height + 1)
+    }
+
+    /// Record a batch from the download's peer, after which the local tip
+    /// is at `local_height` and the peer reports its tip at `peer_tip`.
+    /// Returns the request for the next range, or `None` once the download
+    /// is over: caught up, cancelled, or stalled on a batch that did not
+    /// extend the chain.
+    pub fn on_batch(&mut self, local_height: u64, peer_tip: u64) -> Option<ChainRequest> {
+        if self.cancelled {
+            return None;
+        }
+        let advanced = local_height > self.height;
+        self.height = self.height.max(local_height);
+        self.target = self.target.max(peer_tip);
+        (advanced && self.height < self.target).then(|| self.request())
+    }
+
+    /// Whether `peer` with its tip at `height` should take over
+    pub fn is_beaten_by(&self, peer: PeerId, height: u64) -> bool {
+        peer != self.peer && height > self.target
+    }
+
+    /// Stop asking for further ranges
+    pub fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    pub fn progress(&self) -> DownloadProgress {
+        DownloadProgress {
+            fetched: self.height - self.start_height,
+            total: self.target.saturating_sub(self.start_height),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::Timechain;
+    use crate::network::ChainResponse;
+    use crate::genesis;
+    use crate::wallet::Wallet;
+
+    fn mine(chain: &mut Timechain, count: usize) {
+        let wallet = Wallet::from_secret_key([2u8; 32]);
+        for _ in 0..count {
+            let mut block = chain.next_block(&wallet, vec![]);
+            block.vdf_proof = crate::main_helper::compute_vdf(
+                crate::vdf::evaluate(block.parent, block.slot),
+                chain.vdf_steps,
+            );
+            while !block.meets_difficulty() {
+                block.nonce += 1;
+            }
+            chain.add_block(block, chain.block_time).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_syncing_until_caught_up() {
+        let mut peer_chain = Timechain::new(genesis::genesis());
+        mine(&mut peer_chain, 4);
+        let mut local = peer_chain.genesis_only();
+        let peer = PeerId::random();
+
+        let mut tracker = SyncTracker::new();
+        assert_eq!(tracker.status(0), SyncStatus::Synced);
+
+        tracker.observe(peer, peer_chain.blocks.len() as u64 - 1);
+        let status = tracker.status(local.blocks.len() as u64 - 1);
+        assert_eq!(status, SyncStatus::Syncing { current: 0, target: 4 });
+        assert!(!status.is_synced());
+
+        // Fetch the missing blocks
+        for (i, block) in peer_chain.blocks[1..].iter().enumerate() {
+            local.add_block(block.clone(), local.block_time).unwrap();
+            let status = tracker.status(local.blocks.len() as u64 - 1);
+            if i < 3 {
+                assert_eq!(status.progress(), (i + 1) as f64 / 4.0);
+            } else {
+                assert_eq!(status, SyncStatus::Synced);
+            }
+        }
+
+        // A lower announcement never lowers the target; disconnecting does
+        tracker.observe(peer, 2);
+        assert_eq!(tracker.best_peer_height(), Some(4));
+        tracker.remove_peer(&peer);
+        assert_eq!(tracker.best_peer_height(), None);
+    }
+
+    fn served_chain(tip: u64) -> Vec<crate::block::Block> {
+        (0..=tip).map(|slot| crate::block::Block::new([0u8; 32], slot, [1u8; 32], vec![], [0u8; 32], vec![], 0)).collect()
+    }
+
+    #[test]
+    fn test_large_gap_fetched_in_capped_batches() {
+        let served = served_chain(1000);
+        let mut download = BlockDownload::new(PeerId::random(), 0, 1000);
+        let mut local_height = 0;
+        let mut batches = 0;
+        let mut last = download.progress();
+        assert_eq!(last, DownloadProgress { fetched: 0, total: 1000 });
+
+        let mut request = Some(download.request());
+        while let Some(next) = request {
+            let response = ChainResponse::batch(&served, next.start_height, 128);
+            assert!(response.blocks.len() <= 128);
+            assert_eq!(response.blocks[0].slot, local_height + 1);
+            local_height = response.blocks.last().unwrap().slot;
+            batches += 1;
+
+            request = download.on_batch(local_height, response.tip_height.unwrap());
+            let progress = download.progress();
+            assert!(progress.fetched > last.fetched);
+            assert_eq!(progress.total, 1000);
+            last = progress;
+        }
+        assert_eq!(batches, 8);
+        assert_eq!(local_height, 1000);
+        assert_eq!(last, DownloadProgress { fetched: 1000, total: 1000 });
+    }
+
+    #[test]
+    fn test_cancelled_download_stops_requesting() {
+        let served = served_chain(1000);
+        let (slow, better) = (PeerId::random(), PeerId::random());
+        let mut download = BlockDownload::new(slow, 0, 1000);
+        let first = ChainResponse::batch(&served, download.request().start_height, 100);
+        let next = download.on_batch(first.blocks.last().unwrap().slot, 1000);
+        assert_eq!(next.map(|r| r.start_height), Some(101));
+
+        // Only another peer that is further ahead takes over
+        assert!(!download.is_beaten_by(slow, 2000));
+        assert!(!download.is_beaten_by(better, 1000));
+        assert!(download.is_beaten_by(better, 1200));
+        download.cancel();
+        assert!(download.is_cancelled());
+
+        // The answer already in flight from the old peer asks for nothing more
+        assert!(download.on_batch(200, 1000).is_none());
+        assert_eq!(download.progress().fetched, 100);
+        let takeover = BlockDownload::new(better, 100, 1200);
+        assert_eq!(takeover.request().start_height, 101);
+
+        // A batch that does not extend the chain ends the download too
+        let mut stalled = BlockDownload::new(slow, 0, 1000);
+        assert!(stalled.on_batch(0, 1000).is_none());
+    }
+}
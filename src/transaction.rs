@@ -1,10 +1,42 @@
+//! Transfers and their canonical encoding.
+//!
+//! A transaction's ID and signature both cover its canonical bytes, so any
+//! implementation (node, SDK, wallet) that produces the same bytes agrees on
+//! hashes and signatures regardless of how it represents the fields:
+//!
+//! | field       | encoding                                   |
+//! |-------------|--------------------------------------------|
+//! | `from`      | 32 raw bytes                               |
+//! | `to`        | 32 raw bytes                               |
+//! | `amount`    | u64, little-endian                         |
+//! | `fee`       | u64, little-endian                         |
+//! | `nonce`     | u64, little-endian                         |
+//! | `chain_id`  | u8                                         |
+//! | `zk_proof`  | u64 little-endian length, then the bytes   |
+//! | `signature` | u64 little-endian length, then the bytes   |
+//!
+//! The TXID is SHA-256 of these bytes under the `hashing::TX` tag (see
+//! [`crate::hashing`]); the signed message is the same
+//! bytes with an empty signature. Hex-encoded addresses are decoded to
+//! their 32 bytes first. This is the layout bincode produces for
+//! `Transaction`.
+//!
+//! `chain_id` is the `network.network_id` the transaction is meant for.
+//! Since the signature covers it, a transaction signed for one network is
+//! rejected by the nodes of every other instead of being replayable there.
+
 use serde::{Serialize, Deserialize};
-use sha2::{Sha256, Digest};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
 use crate::genesis;
+use crate::error::AxiomError;
+use crate::multisig::MultisigSignature;
 
 /// Type alias for 32-byte public addresses
 pub type Address = [u8; 32];
 
+/// Sender of coinbase transactions and genesis allocations
+pub const COINBASE_SENDER: Address = [0u8; 32];
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Transaction {
     pub from: Address,
@@ -12,25 +44,22 @@ pub struct Transaction {
     pub amount: u64,
     pub fee: u64,
     pub nonce: u64,
+    /// Network the transaction is signed for (`network.network_id`)
+    pub chain_id: u8,
     pub zk_proof: Vec<u8>,
     pub signature: Vec<u8>,
 }
 
 #[allow(dead_code)]
 impl Transaction {
-    /// Generates a unique transaction identifier (TXID).
+    /// Generates a unique transaction identifier (TXID): SHA-256 of the
+    /// canonical bytes under the `hashing::TX` tag.
     pub fn hash(&self) -> [u8; 32] {
-        let serialized = bincode::serialize(self).expect("CRITICAL: Transaction serialization failed");
-        let mut hasher = Sha256::new();
-        hasher.update(&serialized);
-        let result = hasher.finalize();
-
-        let mut hash = [0u8; 32];
-        hash.copy_from_slice(&result);
-        hash
+        crate::hashing::tagged_sha256(crate::hashing::TX, &[&self.canonical_bytes()])
     }
 
-    /// Helper to create a new transaction.
+    /// Helper to create a new transaction, for mainnet unless
+    /// `with_chain_id` says otherwise.
     pub fn new(
         from: Address,
         to: Address,
@@ -46,33 +75,135 @@ impl Transaction {
             amount,
             fee,
             nonce,
+            chain_id: crate::config::MAINNET_NETWORK_ID,
             zk_proof,
             signature,
         }
     }
 
+    /// Sign for the network with `network.network_id` `chain_id` instead
+    pub fn with_chain_id(mut self, chain_id: u8) -> Self {
+        self.chain_id = chain_id;
+        self
+    }
+
+    /// Reward transaction paying `miner` the subsidy for `height` plus
+    /// `fees`. It is unsigned, carries the height as its nonce and must be
+    /// the first transaction of the block at `height`.
+    pub fn coinbase(miner: Address, height: u64, fees: u64) -> Self {
+        let amount = crate::economics::get_mining_reward(height).saturating_add(fees);
+        Self::new(COINBASE_SENDER, miner, amount, 0, height, vec![], vec![])
+    }
+
+    /// Whether this is a coinbase (or, in the genesis block, an allocation)
+    pub fn is_coinbase(&self) -> bool {
+        self.from == COINBASE_SENDER
+    }
+
+    /// Serialized size in bytes, the basis of the fee-rate policy
+    pub fn size(&self) -> usize {
+        bincode::serialized_size(self).expect("CRITICAL: Transaction serialization failed") as usize
+    }
+
+    /// Fee paid per 1000 bytes of serialized size
+    pub fn fee_rate(&self) -> u64 {
+        let rate = self.fee as u128 * 1000 / self.size() as u128;
+        u64::try_from(rate).unwrap_or(u64::MAX)
+    }
+
+    /// Smallest fee this transaction may pay at `fee_per_byte`
+    pub fn min_fee(&self, fee_per_byte: u64) -> u64 {
+        fee_per_byte.saturating_mul(self.size() as u64)
+    }
+
+    /// Reject amounts below `limit`. A zero amount passes only with
+    /// `allow_zero`, for transactions that exist to carry data.
+    pub fn check_dust(&self, limit: u64, allow_zero: bool) -> Result<(), AxiomError> {
+        if self.amount < limit && !(allow_zero && self.amount == 0) {
+            return Err(AxiomError::Dust { amount: self.amount, limit });
+        }
+        Ok(())
+    }
+
     /// Validate transaction ZK proof and signature
     pub fn validate(&self, sender_balance: u64) -> Result<(), &'static str> {
+        self.validate_checking_proof(sender_balance, true)
+    }
+
+    /// `validate` minus the ZK proof, for tests that have no Groth16 keys
+    #[cfg(test)]
+    pub(crate) fn validate_unproven(&self, sender_balance: u64) -> Result<(), &'static str> {
+        self.validate_checking_proof(sender_balance, false)
+    }
+
+    fn validate_checking_proof(&self, sender_balance: u64, check_proof: bool) -> Result<(), &'static str> {
         // Check if sender has sufficient balance
-        if sender_balance < self.amount + self.fee {
+        let cost = self.amount.checked_add(self.fee).ok_or("Amount overflow")?;
+        if sender_balance < cost {
             return Err("Insufficient balance");
         }
 
         // Verify ZK proof
-        match genesis::verify_transaction_proof(&self.zk_proof, &self.from, self.amount, self.fee) {
-            Ok(true) => {},
-            Ok(false) => return Err("Invalid ZK proof"),
-            Err(_) => return Err("ZK proof verification failed"),
+        if check_proof {
+            match genesis::verify_transaction_proof(&self.zk_proof, &self.from, self.amount, self.fee) {
+                Ok(true) => {},
+                Ok(false) => return Err("Invalid ZK proof"),
+                Err(_) => return Err("ZK proof verification failed"),
+            }
         }
 
-        // Verify signature (simplified for now)
         if self.signature.is_empty() {
             return Err("Missing signature");
         }
+        self.verify_signature().map_err(|_| "Invalid signature")?;
 
         Ok(())
     }
 
+    /// Canonical encoding (see the module docs)
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        self.encode(&self.signature)
+    }
+
+    /// Bytes covered by the signature: the canonical bytes with the
+    /// signature cleared
+    pub fn signing_message(&self) -> Vec<u8> {
+        self.encode(&[])
+    }
+
+    fn encode(&self, signature: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(32 + 32 + 3 * 8 + 1 + 2 * 8 + self.zk_proof.len() + signature.len());
+        out.extend_from_slice(&self.from);
+        out.extend_from_slice(&self.to);
+        out.extend_from_slice(&self.amount.to_le_bytes());
+        out.extend_from_slice(&self.fee.to_le_bytes());
+        out.extend_from_slice(&self.nonce.to_le_bytes());
+        out.push(self.chain_id);
+        for field in [self.zk_proof.as_slice(), signature] {
+            out.extend_from_slice(&(field.len() as u64).to_le_bytes());
+            out.extend_from_slice(field);
+        }
+        out
+    }
+
+    /// Verify the Ed25519 signature against the sender. Addresses are the
+    /// sender's public key, so the key is recovered directly from `from`.
+    /// Multisig addresses instead carry a signature set checked against the
+    /// policy they hash from (see [`crate::multisig`]).
+    pub fn verify_signature(&self) -> Result<(), AxiomError> {
+        if let Some(multisig) = MultisigSignature::decode(&self.signature) {
+            return multisig.verify(&self.from, &self.signing_message());
+        }
+        let verifying_key = VerifyingKey::from_bytes(&self.from)
+            .map_err(|_| AxiomError::InvalidSignature("sender address is not a valid public key".to_string()))?;
+        let signature_bytes: [u8; 64] = self.signature.as_slice().try_into().map_err(|_| {
+            AxiomError::InvalidSignature(format!("expected 64 bytes, got {}", self.signature.len()))
+        })?;
+        verifying_key
+            .verify(&self.signing_message(), &Signature::from_bytes(&signature_bytes))
+            .map_err(|_| AxiomError::InvalidSignature("signature does not match sender".to_string()))
+    }
+
     /// Calculate transaction fee
     pub fn fee(&self) -> u64 {
         self.fee
@@ -83,3 +214,50 @@ impl Transaction {
         self.amount
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Transaction {
+        Transaction::new([1u8; 32], [2u8; 32], 1_000, 10, 7, vec![0xAB; 3], vec![0xCD; 2])
+    }
+
+    #[test]
+    fn test_canonical_hash_vector() {
+        // Reference vector for other implementations of the encoding
+        let tx = sample();
+        assert_eq!(tx.canonical_bytes().len(), 110);
+        assert_eq!(
+            hex::encode(tx.hash()),
+            "9bf3e0bbf1bce91d96b5e385034bcab13f2e9d0d22dbb7befff25592f8bfd45c"
+        );
+
+        // Same TXID when built from hex fields, as a JSON client would send them
+        let from: Address = hex::decode("01".repeat(32)).unwrap().try_into().unwrap();
+        let to: Address = hex::decode("02".repeat(32)).unwrap().try_into().unwrap();
+        let rebuilt = Transaction::new(from, to, 1_000, 10, 7, hex::decode("ababab").unwrap(), hex::decode("cdcd").unwrap());
+        assert_eq!(rebuilt.hash(), tx.hash());
+    }
+
+    #[test]
+    fn test_canonical_bytes_match_bincode_layout() {
+        let tx = sample();
+        assert_eq!(tx.canonical_bytes(), bincode::serialize(&tx).unwrap());
+
+        let mut unsigned = tx.clone();
+        unsigned.signature.clear();
+        assert_eq!(tx.signing_message(), unsigned.canonical_bytes());
+        assert_ne!(tx.signing_message(), tx.canonical_bytes());
+    }
+
+    #[test]
+    fn test_chain_id_is_signed() {
+        let mainnet = sample();
+        let testnet = sample().with_chain_id(2);
+        assert_eq!(mainnet.chain_id, crate::config::MAINNET_NETWORK_ID);
+        assert_ne!(mainnet.signing_message(), testnet.signing_message());
+        assert_ne!(mainnet.hash(), testnet.hash());
+        assert_eq!(testnet.canonical_bytes(), bincode::serialize(&testnet).unwrap());
+    }
+}
@@ -250,15 +250,11 @@ pub fn wesolowski_verify_with_proof(
     
     y.clone().modulo(n) == rhs
 }
-use sha2::{Sha256, Digest};
 
 /// EVALUATE: Creates the seed for the VDF chain.
 /// This links the current block to the parent and the specific time-slot.
 pub fn evaluate(parent_hash: [u8; 32], slot: u64) -> [u8; 32] {
-    let mut hasher = Sha256::new();
-    hasher.update(parent_hash);
-    hasher.update(slot.to_le_bytes());
-    hasher.finalize().into()
+    crate::hashing::tagged_sha256(crate::hashing::VDF, &[&parent_hash, &slot.to_le_bytes()])
 }
 
 /// VERIFY: Recomputes the sequential chain to ensure the time-lock was respected.
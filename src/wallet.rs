@@ -3,8 +3,121 @@ use ed25519_dalek::{SigningKey, VerifyingKey, Signature, Signer, Verifier};
 use rand::rngs::OsRng;
 use rand_core::RngCore;
 use serde::{Serialize, Deserialize};
+use aes_gcm::aead::generic_array::GenericArray;
+use aes_gcm::aead::Aead;
+use aes_gcm::{Aes256Gcm, KeyInit};
+use argon2::{Algorithm, Argon2, Params, Version};
+use ctr::cipher::{KeyIvInit, StreamCipher};
+use sha3::{Digest, Keccak256};
 use std::fs;
+use std::path::Path;
+use crate::error::{AxiomError, Result as AxiomResult};
 use crate::genesis;
+use crate::hd;
+use crate::rpc::RpcClient;
+
+/// Default wallet file, relative to the working directory
+pub const WALLET_FILE: &str = "wallet.dat";
+
+/// Environment variable holding the wallet password on headless nodes
+pub const WALLET_PASSWORD_ENV: &str = "AXIOM_WALLET_PASSWORD";
+
+/// Prefix marking an encrypted wallet file
+const ENCRYPTED_MAGIC: &[u8; 4] = b"AXWE";
+
+/// Encrypted wallet: Argon2id derives an AES-256-GCM key from the password,
+/// which seals the bincode-encoded `Wallet`
+#[derive(Serialize, Deserialize)]
+struct EncryptedWalletFile {
+    version: u8,
+    /// Argon2id memory cost (KiB)
+    m_cost: u32,
+    /// Argon2id iterations
+    t_cost: u32,
+    salt: [u8; 16],
+    nonce: [u8; 12],
+    ciphertext: Vec<u8>,
+}
+
+/// Web3 Secret Storage (keystore v3) document. The cipher and MAC follow
+/// the standard (AES-128-CTR, Keccak-256 over the second half of the
+/// derived key and the ciphertext); the KDF is Argon2id as for `wallet.dat`.
+#[derive(Serialize, Deserialize)]
+struct KeystoreV3 {
+    version: u8,
+    id: String,
+    /// Hex address, kept readable so tooling can tell keystores apart
+    address: String,
+    crypto: KeystoreCrypto,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeystoreCrypto {
+    cipher: String,
+    cipherparams: KeystoreCipherParams,
+    ciphertext: String,
+    kdf: String,
+    kdfparams: KeystoreKdfParams,
+    mac: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeystoreCipherParams {
+    iv: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeystoreKdfParams {
+    dklen: u32,
+    salt: String,
+    /// Argon2id memory cost (KiB)
+    m_cost: u32,
+    /// Argon2id iterations
+    t_cost: u32,
+    p_cost: u32,
+}
+
+const KEYSTORE_CIPHER: &str = "aes-128-ctr";
+const KEYSTORE_KDF: &str = "argon2id";
+
+type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+
+fn keystore_mac(derived_key: &[u8; 32], ciphertext: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(&derived_key[16..]);
+    hasher.update(ciphertext);
+    hasher.finalize().into()
+}
+
+fn keystore_hex<const N: usize>(value: &str, field: &str) -> AxiomResult<[u8; N]> {
+    hex::decode(value.trim_start_matches("0x"))
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| AxiomError::KeystoreError(format!("invalid keystore {}", field)))
+}
+
+fn derive_wallet_key(password: &str, salt: &[u8], m_cost: u32, t_cost: u32) -> AxiomResult<[u8; 32]> {
+    let params = Params::new(m_cost, t_cost, 1, Some(32))
+        .map_err(|e| AxiomError::KeyDerivationFailed(e.to_string()))?;
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| AxiomError::KeyDerivationFailed(e.to_string()))?;
+    Ok(key)
+}
+
+/// A plaintext wallet is exactly one bincode-encoded `Wallet` (64 bytes)
+fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(ENCRYPTED_MAGIC) && data.len() != 64
+}
+
+fn is_encrypted_file(path: &Path) -> bool {
+    fs::read(path).map(|data| is_encrypted(&data)).unwrap_or(false)
+}
+
+fn env_password() -> Option<String> {
+    std::env::var(WALLET_PASSWORD_ENV).ok().filter(|p| !p.is_empty())
+}
 
 #[derive(Serialize, Deserialize)]
 pub struct Wallet {
@@ -13,33 +126,211 @@ pub struct Wallet {
 }
 
 impl Wallet {
+    /// Watch `address` without holding its key: balance and history only,
+    /// and every request to sign fails with `AxiomError::CannotSign`
+    pub fn watch_only(address: Address) -> WatchWallet {
+        WatchWallet { address }
+    }
+
     /// Generates or loads a wallet from local storage.
     /// This keeps your identity strictly off-chain and local.
+    ///
+    /// With `AXIOM_WALLET_PASSWORD` set the wallet is kept encrypted, and an
+    /// existing plaintext wallet is encrypted in place on first load.
     pub fn load_or_create() -> Self {
-        if let Ok(data) = fs::read("wallet.dat") {
-            if let Ok(w) = bincode::deserialize::<Wallet>(&data) {
-                return w;
+        let path = Path::new(WALLET_FILE);
+        if is_encrypted_file(path) {
+            return Self::open(path).unwrap_or_else(|e| panic!("Cannot unlock {}: {}", WALLET_FILE, e));
+        }
+        if let Ok(w) = Self::load_from(path) {
+            if let Some(password) = env_password() {
+                match w.save_encrypted(path, &password) {
+                    Ok(()) => println!("🔒 Encrypted existing {}", WALLET_FILE),
+                    Err(e) => eprintln!("⚠️  Could not encrypt {}: {}", WALLET_FILE, e),
+                }
             }
+            return w;
         }
 
-        let mut rng = OsRng;
+        let wallet = Self::generate();
+        wallet.store(path).expect("Failed to secure wallet file");
+        wallet
+    }
+
+    /// Fresh random keypair; not written anywhere
+    pub fn generate() -> Self {
         let mut seed = [0u8; 32];
-        rng.fill_bytes(&mut seed);
-        let signing_key = SigningKey::from_bytes(&seed);
-        let verifying_key = VerifyingKey::from(&signing_key);
-        let address: Address = verifying_key.to_bytes();
-
-        let wallet = Wallet {
-            secret_key: signing_key.to_bytes(),
-            address,
+        OsRng.fill_bytes(&mut seed);
+        Self::from_secret_key(seed)
+    }
+
+    /// Keypair from a 32-byte secret key
+    pub fn from_secret_key(secret_key: [u8; 32]) -> Self {
+        let signing_key = SigningKey::from_bytes(&secret_key);
+        Wallet {
+            secret_key,
+            address: VerifyingKey::from(&signing_key).to_bytes(),
+        }
+    }
+
+    /// `index`-th HD child of this wallet, at `m/44'/84000'/0'/0'/index'`
+    /// with this wallet's secret key as the seed (see [`crate::hd`]). The
+    /// same wallet derives the same children on every machine.
+    pub fn derive_child(&self, index: u32) -> Wallet {
+        Self::from_secret_key(hd::derive_address_key(&self.secret_key, index))
+    }
+
+    /// Address of the `index`-th HD child
+    pub fn address_at(&self, index: u32) -> Address {
+        self.derive_child(index).address
+    }
+
+    /// Read a plaintext wallet file written by `save_to`
+    pub fn load_from(path: impl AsRef<Path>) -> AxiomResult<Self> {
+        let path = path.as_ref();
+        let data = fs::read(path).map_err(|_| AxiomError::WalletNotFound(path.display().to_string()))?;
+        if is_encrypted(&data) {
+            return Err(AxiomError::WalletLocked);
+        }
+        Ok(bincode::deserialize(&data)?)
+    }
+
+    /// Write the wallet to `path` in plaintext, replacing any existing file
+    pub fn save_to(&self, path: impl AsRef<Path>) -> AxiomResult<()> {
+        fs::write(path, bincode::serialize(self)?)?;
+        Ok(())
+    }
+
+    /// Write the wallet encrypted under `password`, replacing any existing
+    /// file (including a plaintext one)
+    pub fn save_encrypted(&self, path: impl AsRef<Path>, password: &str) -> AxiomResult<()> {
+        self.save_encrypted_with(path, password, Params::DEFAULT_M_COST, Params::DEFAULT_T_COST)
+    }
+
+    fn save_encrypted_with(&self, path: impl AsRef<Path>, password: &str, m_cost: u32, t_cost: u32) -> AxiomResult<()> {
+        let mut salt = [0u8; 16];
+        let mut nonce = [0u8; 12];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+
+        let key = derive_wallet_key(password, &salt, m_cost, t_cost)?;
+        let plaintext = bincode::serialize(self)?;
+        let ciphertext = Aes256Gcm::new(GenericArray::from_slice(&key))
+            .encrypt(GenericArray::from_slice(&nonce), plaintext.as_ref())
+            .map_err(|_| AxiomError::KeystoreError("encryption failed".to_string()))?;
+
+        let file = EncryptedWalletFile { version: 1, m_cost, t_cost, salt, nonce, ciphertext };
+        let mut data = ENCRYPTED_MAGIC.to_vec();
+        data.extend(bincode::serialize(&file)?);
+        fs::write(path, data)?;
+        Ok(())
+    }
+
+    /// Read a wallet written by `save_encrypted`. A wrong password is
+    /// reported as `InvalidPassword`, never as a garbage wallet.
+    pub fn load_encrypted(path: impl AsRef<Path>, password: &str) -> AxiomResult<Self> {
+        let path = path.as_ref();
+        let data = fs::read(path).map_err(|_| AxiomError::WalletNotFound(path.display().to_string()))?;
+        if !is_encrypted(&data) {
+            return Err(AxiomError::KeystoreError(format!("{} is not encrypted", path.display())));
+        }
+        let file: EncryptedWalletFile = bincode::deserialize(&data[ENCRYPTED_MAGIC.len()..])
+            .map_err(|e| AxiomError::KeystoreError(format!("malformed wallet file: {}", e)))?;
+        if file.version != 1 {
+            return Err(AxiomError::KeystoreError(format!("unsupported wallet version {}", file.version)));
+        }
+
+        let key = derive_wallet_key(password, &file.salt, file.m_cost, file.t_cost)?;
+        let plaintext = Aes256Gcm::new(GenericArray::from_slice(&key))
+            .decrypt(GenericArray::from_slice(&file.nonce), file.ciphertext.as_ref())
+            .map_err(|_| AxiomError::InvalidPassword)?;
+        Ok(bincode::deserialize(&plaintext)?)
+    }
+
+    /// Open a wallet file of either kind. Encrypted files are unlocked with
+    /// `AXIOM_WALLET_PASSWORD`, or fail with `WalletLocked` if it is unset.
+    pub fn open(path: impl AsRef<Path>) -> AxiomResult<Self> {
+        let path = path.as_ref();
+        if !is_encrypted_file(path) {
+            return Self::load_from(path);
+        }
+        let password = env_password().ok_or(AxiomError::WalletLocked)?;
+        Self::load_encrypted(path, &password)
+    }
+
+    /// Save encrypted if `AXIOM_WALLET_PASSWORD` is set, otherwise plaintext
+    pub fn store(&self, path: impl AsRef<Path>) -> AxiomResult<()> {
+        match env_password() {
+            Some(password) => self.save_encrypted(path, &password),
+            None => self.save_to(path),
+        }
+    }
+
+    /// Export as a Web3 keystore v3 JSON document encrypted under `password`
+    pub fn to_keystore_json(&self, password: &str) -> AxiomResult<String> {
+        self.to_keystore_json_with(password, Params::DEFAULT_M_COST, Params::DEFAULT_T_COST)
+    }
+
+    fn to_keystore_json_with(&self, password: &str, m_cost: u32, t_cost: u32) -> AxiomResult<String> {
+        let mut salt = [0u8; 32];
+        let mut iv = [0u8; 16];
+        let mut id = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut iv);
+        OsRng.fill_bytes(&mut id);
+
+        let key = derive_wallet_key(password, &salt, m_cost, t_cost)?;
+        let mut ciphertext = self.secret_key.to_vec();
+        Aes128Ctr::new(GenericArray::from_slice(&key[..16]), GenericArray::from_slice(&iv)).apply_keystream(&mut ciphertext);
+
+        let id = hex::encode(id);
+        let keystore = KeystoreV3 {
+            version: 3,
+            id: format!("{}-{}-{}-{}-{}", &id[..8], &id[8..12], &id[12..16], &id[16..20], &id[20..]),
+            address: hex::encode(self.address),
+            crypto: KeystoreCrypto {
+                cipher: KEYSTORE_CIPHER.to_string(),
+                cipherparams: KeystoreCipherParams { iv: hex::encode(iv) },
+                mac: hex::encode(keystore_mac(&key, &ciphertext)),
+                ciphertext: hex::encode(ciphertext),
+                kdf: KEYSTORE_KDF.to_string(),
+                kdfparams: KeystoreKdfParams { dklen: 32, salt: hex::encode(salt), m_cost, t_cost, p_cost: 1 },
+            },
         };
+        serde_json::to_string_pretty(&keystore).map_err(|e| AxiomError::KeystoreError(e.to_string()))
+    }
 
-        let encoded = bincode::serialize(&wallet).unwrap();
-        fs::write("wallet.dat", encoded).expect("Failed to secure wallet file");
-        wallet
+    /// Import a keystore written by `to_keystore_json`. A wrong password
+    /// fails the MAC check and is reported as `InvalidPassword`.
+    pub fn from_keystore_json(json: &str, password: &str) -> AxiomResult<Self> {
+        let keystore: KeystoreV3 = serde_json::from_str(json)
+            .map_err(|e| AxiomError::KeystoreError(format!("malformed keystore: {}", e)))?;
+        let crypto = &keystore.crypto;
+        if keystore.version != 3 || crypto.cipher != KEYSTORE_CIPHER || crypto.kdf != KEYSTORE_KDF {
+            return Err(AxiomError::KeystoreError(format!(
+                "unsupported keystore (version {}, cipher {}, kdf {})",
+                keystore.version, crypto.cipher, crypto.kdf
+            )));
+        }
+        let salt: [u8; 32] = keystore_hex(&crypto.kdfparams.salt, "salt")?;
+        let iv: [u8; 16] = keystore_hex(&crypto.cipherparams.iv, "iv")?;
+        let mac: [u8; 32] = keystore_hex(&crypto.mac, "mac")?;
+        let mut secret_key: [u8; 32] = keystore_hex(&crypto.ciphertext, "ciphertext")?;
+
+        let key = derive_wallet_key(password, &salt, crypto.kdfparams.m_cost, crypto.kdfparams.t_cost)?;
+        if !crate::crypto::ct_eq(&keystore_mac(&key, &secret_key), &mac) {
+            return Err(AxiomError::InvalidPassword);
+        }
+        Aes128Ctr::new(GenericArray::from_slice(&key[..16]), GenericArray::from_slice(&iv)).apply_keystream(&mut secret_key);
+
+        let wallet = Self::from_secret_key(secret_key);
+        if hex::encode(wallet.address) != keystore.address.trim_start_matches("0x").to_lowercase() {
+            return Err(AxiomError::KeystoreError("keystore address does not match its key".to_string()));
+        }
+        Ok(wallet)
     }
 
-    /// Create a signed transaction with ZK proof
+    /// Create a signed mainnet transaction with ZK proof
     pub fn create_transaction(
         &self,
         to: Address,
@@ -47,6 +338,20 @@ impl Wallet {
         fee: u64,
         nonce: u64,
         current_balance: u64,
+    ) -> Result<Transaction, Box<dyn std::error::Error>> {
+        self.create_transaction_for(crate::config::MAINNET_NETWORK_ID, to, amount, fee, nonce, current_balance)
+    }
+
+    /// Create a signed transaction with ZK proof for the network with
+    /// `network.network_id` `chain_id`; no other network accepts it
+    pub fn create_transaction_for(
+        &self,
+        chain_id: u8,
+        to: Address,
+        amount: u64,
+        fee: u64,
+        nonce: u64,
+        current_balance: u64,
     ) -> Result<Transaction, Box<dyn std::error::Error>> {
         // Generate ZK proof
         let zk_proof = genesis::generate_transaction_proof(
@@ -65,21 +370,14 @@ impl Wallet {
             nonce,
             zk_proof,
             vec![], // Empty signature for now
-        );
+        )
+        .with_chain_id(chain_id);
 
         // Sign the transaction
         let signature = self.sign_transaction(&tx_data)?;
 
         // Create final transaction with signature
-        Ok(Transaction::new(
-            self.address,
-            to,
-            amount,
-            fee,
-            nonce,
-            tx_data.zk_proof,
-            signature,
-        ))
+        Ok(Transaction { signature, ..tx_data })
     }
 
     /// Sign transaction data
@@ -136,3 +434,211 @@ impl Wallet {
         signature.to_bytes().to_vec()
     }
 }
+
+/// An address monitored without its private key, e.g. by a dashboard
+/// server. Made with [`Wallet::watch_only`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchWallet {
+    pub address: Address,
+}
+
+impl WatchWallet {
+    /// Get wallet address as hex string
+    pub fn address_hex(&self) -> String {
+        hex::encode(self.address)
+    }
+
+    /// Get balance from chain state
+    pub fn get_balance(&self, chain: &crate::chain::Timechain) -> u64 {
+        chain.balance(&self.address)
+    }
+
+    /// Balance as reported by the node behind `client`
+    pub async fn balance(&self, client: &RpcClient) -> AxiomResult<u64> {
+        client.balance(self.address).await
+    }
+
+    /// `get_address_history` of this address from the node behind `client`
+    pub async fn history(&self, client: &RpcClient, offset: u64, limit: u64) -> AxiomResult<serde_json::Value> {
+        client.address_history(self.address, offset, limit).await
+    }
+
+    /// Always `AxiomError::CannotSign`: there is no key to sign with
+    pub fn create_transaction(
+        &self,
+        _to: Address,
+        _amount: u64,
+        _fee: u64,
+        _nonce: u64,
+        _current_balance: u64,
+    ) -> AxiomResult<Transaction> {
+        Err(self.cannot_sign())
+    }
+
+    /// Always `AxiomError::CannotSign`: there is no key to sign with
+    pub fn sign_message(&self, _message: &[u8]) -> AxiomResult<Vec<u8>> {
+        Err(self.cannot_sign())
+    }
+
+    fn cannot_sign(&self) -> AxiomError {
+        AxiomError::CannotSign(self.address_hex())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Light Argon2 parameters keep debug-build tests fast
+    const TEST_M_COST: u32 = 64;
+    const TEST_T_COST: u32 = 1;
+
+    fn temp_wallet(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("axiom_wallet_{}_{}.dat", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_hd_children() {
+        let wallet = Wallet::from_secret_key([5u8; 32]);
+        let addresses: Vec<Address> = (0..5).map(|i| wallet.address_at(i)).collect();
+
+        // Deterministic: the same seed always gives the same sequence
+        let restored = Wallet::from_secret_key([5u8; 32]);
+        assert_eq!((0..5).map(|i| restored.address_at(i)).collect::<Vec<_>>(), addresses);
+
+        // Distinct from each other and from the parent
+        let mut unique = addresses.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), addresses.len());
+        assert!(!addresses.contains(&wallet.address));
+        assert_ne!(Wallet::from_secret_key([6u8; 32]).address_at(0), addresses[0]);
+
+        // Each child signs for its own address
+        for i in [0, 3] {
+            let child = wallet.derive_child(i);
+            let message = b"axiom hd";
+            let signature = Signature::from_slice(&child.sign_message(message)).unwrap();
+            let key = VerifyingKey::from_bytes(&addresses[i as usize]).unwrap();
+            assert!(key.verify(message, &signature).is_ok());
+            assert!(VerifyingKey::from_bytes(&addresses[1]).unwrap().verify(message, &signature).is_err());
+        }
+    }
+
+    #[test]
+    fn test_encrypted_round_trip() {
+        let path = temp_wallet("round_trip");
+        let wallet = Wallet::generate();
+        wallet.save_encrypted_with(&path, "correct horse", TEST_M_COST, TEST_T_COST).unwrap();
+
+        let data = fs::read(&path).unwrap();
+        assert!(!data.windows(32).any(|w| w == wallet.secret_key), "secret key stored in the clear");
+
+        let loaded = Wallet::load_encrypted(&path, "correct horse").unwrap();
+        assert_eq!(loaded.secret_key, wallet.secret_key);
+        assert_eq!(loaded.address, wallet.address);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_wrong_password_is_distinct_error() {
+        let path = temp_wallet("wrong_password");
+        Wallet::generate().save_encrypted_with(&path, "right", TEST_M_COST, TEST_T_COST).unwrap();
+
+        assert!(matches!(Wallet::load_encrypted(&path, "wrong"), Err(AxiomError::InvalidPassword)));
+        // Plaintext loading refuses instead of misreading ciphertext as keys
+        assert!(matches!(Wallet::load_from(&path), Err(AxiomError::WalletLocked)));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_plaintext_wallet_migrates() {
+        let path = temp_wallet("migrate");
+        let wallet = Wallet::generate();
+        wallet.save_to(&path).unwrap();
+        assert!(!is_encrypted_file(&path));
+        assert!(matches!(Wallet::load_encrypted(&path, "pw"), Err(AxiomError::KeystoreError(_))));
+
+        Wallet::load_from(&path).unwrap().save_encrypted_with(&path, "pw", TEST_M_COST, TEST_T_COST).unwrap();
+        assert!(is_encrypted_file(&path));
+        assert_eq!(Wallet::load_encrypted(&path, "pw").unwrap().address, wallet.address);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_keystore_json_round_trip() {
+        let wallet = Wallet::generate();
+        let json = wallet.to_keystore_json_with("hunter2", TEST_M_COST, TEST_T_COST).unwrap();
+        assert!(!json.contains(&hex::encode(wallet.secret_key)), "secret key stored in the clear");
+
+        let document: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(document["version"], 3);
+        assert_eq!(document["address"], hex::encode(wallet.address));
+        assert_eq!(document["crypto"]["cipher"], "aes-128-ctr");
+
+        let restored = Wallet::from_keystore_json(&json, "hunter2").unwrap();
+        assert_eq!(restored.secret_key, wallet.secret_key);
+        assert_eq!(restored.address, wallet.address);
+
+        // The recovered key signs exactly like the original (Ed25519 is deterministic)
+        let message = b"axiom keystore";
+        assert_eq!(restored.sign_message(message), wallet.sign_message(message));
+    }
+
+    #[test]
+    fn test_keystore_json_wrong_password() {
+        let wallet = Wallet::generate();
+        let json = wallet.to_keystore_json_with("right", TEST_M_COST, TEST_T_COST).unwrap();
+        assert!(matches!(Wallet::from_keystore_json(&json, "wrong"), Err(AxiomError::InvalidPassword)));
+
+        // A MAC differing only in its last byte is as wrong as any other
+        let keystore: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let mac = keystore["crypto"]["mac"].as_str().unwrap();
+        let last = if mac.ends_with('0') { "1" } else { "0" };
+        let forged = json.replace(mac, &format!("{}{}", &mac[..mac.len() - 1], last));
+        assert!(matches!(Wallet::from_keystore_json(&forged, "right"), Err(AxiomError::InvalidPassword)));
+
+        // Metadata pointing at another address is refused
+        let other = hex::encode(Wallet::generate().address);
+        let relabeled = json.replace(&hex::encode(wallet.address), &other);
+        assert!(matches!(Wallet::from_keystore_json(&relabeled, "right"), Err(AxiomError::KeystoreError(_))));
+    }
+
+    #[test]
+    fn test_transactions_signed_for_chain() {
+        use crate::config::{MAINNET_NETWORK_ID, REGTEST_NETWORK_ID};
+        let wallet = Wallet::from_secret_key([7u8; 32]);
+        let mainnet_tx = wallet.create_transaction([2u8; 32], 100, 10, 0, 1_000).unwrap();
+        let regtest_tx = wallet.create_transaction_for(REGTEST_NETWORK_ID, [2u8; 32], 100, 10, 0, 1_000).unwrap();
+        assert_eq!(mainnet_tx.chain_id, MAINNET_NETWORK_ID);
+        assert_eq!(regtest_tx.chain_id, REGTEST_NETWORK_ID);
+        assert!(Wallet::verify_transaction_signature(&regtest_tx).unwrap());
+
+        // A regtest node authorizes its own transaction and refuses the
+        // mainnet one
+        let mut regtest = crate::chain::Timechain::new(genesis::genesis())
+            .genesis_only()
+            .with_network_id(REGTEST_NETWORK_ID);
+        regtest.state.credit(wallet.address, 1_000);
+        assert!(regtest.check_transaction_authorization(&regtest_tx).is_ok());
+        assert!(matches!(regtest.validate_transaction(&mainnet_tx), Err(AxiomError::WrongChainId { .. })));
+    }
+
+    #[test]
+    fn test_watch_only_cannot_sign() {
+        let wallet = Wallet::from_secret_key([7u8; 32]);
+        let watch = Wallet::watch_only(wallet.address);
+        assert_eq!(watch.address_hex(), wallet.address_hex());
+
+        let mut chain = crate::chain::Timechain::new(genesis::genesis()).genesis_only();
+        chain.state.credit(wallet.address, 1_000);
+        assert_eq!(watch.get_balance(&chain), 1_000);
+
+        let expected = wallet.address_hex();
+        assert!(matches!(
+            watch.create_transaction([2u8; 32], 100, 10, 0, 1_000),
+            Err(AxiomError::CannotSign(address)) if address == expected
+        ));
+        assert!(matches!(watch.sign_message(b"axiom"), Err(AxiomError::CannotSign(_))));
+    }
+}
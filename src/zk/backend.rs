@@ -0,0 +1,333 @@
+//! Backend-neutral interface over the two privacy proof systems.
+//!
+//! `zk::circuit` (Groth16 over BLS12-381) and `crypto::quantum_safe_stark`
+//! prove the same private transfer with different inputs and proof types.
+//! Both implement `PrivateTransactionProver`/`PrivateTransactionVerifier`
+//! here, taking a `PrivateTransferWitness` and producing opaque proof bytes
+//! that are checked against the `PrivateTransferInputs` derived from it, so a
+//! transaction only needs to carry a `ProofSystem` tag next to its proof.
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::Proof;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::quantum_safe_stark::{
+    quantum_safe_hash, PublicInputs, QuantumSafeHash, QuantumSafeStarkProver,
+    QuantumSafeStarkVerifier, StarkProof, TransactionWitness,
+};
+use crate::zk::circuit::{bytes_to_fr, generate_commitment, ZkProofSystem};
+
+/// Serialized proof produced by either backend
+pub type ProofBytes = Vec<u8>;
+
+/// Which proof system a proof was produced with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ProofSystem {
+    /// Groth16 SNARK (`zk::circuit::ZkProofSystem`), trusted setup
+    Groth16,
+    /// Hash-based STARK (`crypto::quantum_safe_stark`), no trusted setup
+    Stark,
+}
+
+/// Private data of a transfer, known only to the sender
+#[derive(Debug, Clone)]
+pub struct PrivateTransferWitness {
+    pub secret_key: [u8; 32],
+    pub sender: [u8; 32],
+    pub receiver: [u8; 32],
+    pub balance: u64,
+    pub amount: u64,
+    pub fee: u64,
+    pub nonce: u64,
+    pub signature: [u8; 64],
+}
+
+/// Public statement a proof is verified against
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrivateTransferInputs {
+    pub sender: [u8; 32],
+    pub receiver: [u8; 32],
+    pub amount: u64,
+    pub fee: u64,
+    /// Field encoding of `secret_key + nonce`
+    pub commitment: [u8; 32],
+    /// Field encoding of `secret_key + (balance - amount - fee)`
+    pub new_balance_commitment: [u8; 32],
+}
+
+impl PrivateTransferWitness {
+    /// Public inputs this witness proves
+    pub fn public_inputs(&self) -> PrivateTransferInputs {
+        let new_balance = self.balance.saturating_sub(self.amount).saturating_sub(self.fee);
+        PrivateTransferInputs {
+            sender: self.sender,
+            receiver: self.receiver,
+            amount: self.amount,
+            fee: self.fee,
+            commitment: fr_to_bytes(&generate_commitment(&self.secret_key, self.nonce)),
+            new_balance_commitment: fr_to_bytes(&generate_commitment(&self.secret_key, new_balance)),
+        }
+    }
+}
+
+impl PrivateTransferInputs {
+    /// Groth16 public inputs, in circuit order
+    fn groth16_inputs(&self) -> Option<Vec<Fr>> {
+        Some(vec![
+            fr_from_bytes(&self.commitment)?,
+            Fr::from(self.amount),
+            Fr::from(self.fee),
+            fr_from_bytes(&self.new_balance_commitment)?,
+        ])
+    }
+
+    /// STARK public inputs
+    fn stark_inputs(&self) -> PublicInputs {
+        PublicInputs {
+            sender_hash: quantum_safe_hash(&self.sender),
+            receiver_hash: quantum_safe_hash(&self.receiver),
+            amount_commitment: quantum_safe_hash(&self.amount.to_le_bytes()),
+        }
+    }
+
+    /// Digest binding a STARK proof to the whole statement
+    fn digest(&self) -> QuantumSafeHash {
+        quantum_safe_hash(&bincode::serialize(self).unwrap_or_default())
+    }
+}
+
+fn fr_to_bytes(value: &Fr) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    value
+        .serialize_compressed(&mut bytes[..])
+        .expect("BLS12-381 scalars serialize to 32 bytes");
+    bytes
+}
+
+fn fr_from_bytes(bytes: &[u8; 32]) -> Option<Fr> {
+    Fr::deserialize_compressed(&bytes[..]).ok()
+}
+
+/// Produces proofs of a private transfer
+pub trait PrivateTransactionProver {
+    /// Backend tag stored alongside the proof
+    fn system(&self) -> ProofSystem;
+
+    /// Prove `witness`; the proof verifies against `witness.public_inputs()`
+    fn prove(&self, witness: &PrivateTransferWitness) -> Result<ProofBytes, String>;
+}
+
+/// Checks proofs of a private transfer
+pub trait PrivateTransactionVerifier {
+    /// Backend whose proofs this verifier accepts
+    fn system(&self) -> ProofSystem;
+
+    /// Whether `proof` proves `public_inputs`. Malformed proofs are rejected.
+    fn verify(&self, proof: &[u8], public_inputs: &PrivateTransferInputs) -> bool;
+}
+
+/// A proof tagged with the backend that produced it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrivateTransactionProof {
+    pub system: ProofSystem,
+    pub proof: ProofBytes,
+}
+
+impl PrivateTransactionProof {
+    /// Prove `witness` with `prover`, recording its backend
+    pub fn create(
+        prover: &dyn PrivateTransactionProver,
+        witness: &PrivateTransferWitness,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            system: prover.system(),
+            proof: prover.prove(witness)?,
+        })
+    }
+
+    /// Verify with whichever of `verifiers` handles this proof's backend
+    pub fn verify(
+        &self,
+        verifiers: &[&dyn PrivateTransactionVerifier],
+        public_inputs: &PrivateTransferInputs,
+    ) -> bool {
+        verifiers
+            .iter()
+            .find(|v| v.system() == self.system)
+            .is_some_and(|v| v.verify(&self.proof, public_inputs))
+    }
+}
+
+// ==================== GROTH16 ====================
+
+impl PrivateTransactionProver for ZkProofSystem {
+    fn system(&self) -> ProofSystem {
+        ProofSystem::Groth16
+    }
+
+    fn prove(&self, witness: &PrivateTransferWitness) -> Result<ProofBytes, String> {
+        let (proof, _) = ZkProofSystem::prove(
+            self,
+            bytes_to_fr(&witness.secret_key),
+            Fr::from(witness.balance),
+            Fr::from(witness.nonce),
+            Fr::from(witness.amount),
+            Fr::from(witness.fee),
+        )?;
+        let mut bytes = Vec::new();
+        proof
+            .serialize_compressed(&mut bytes)
+            .map_err(|e| format!("Proof serialization failed: {:?}", e))?;
+        Ok(bytes)
+    }
+}
+
+impl PrivateTransactionVerifier for ZkProofSystem {
+    fn system(&self) -> ProofSystem {
+        ProofSystem::Groth16
+    }
+
+    fn verify(&self, proof: &[u8], public_inputs: &PrivateTransferInputs) -> bool {
+        let Ok(proof) = Proof::<Bls12_381>::deserialize_compressed(proof) else {
+            return false;
+        };
+        let Some(inputs) = public_inputs.groth16_inputs() else {
+            return false;
+        };
+        ZkProofSystem::verify(self, &proof, &inputs).unwrap_or(false)
+    }
+}
+
+// ==================== STARK ====================
+
+/// Wire form of a STARK proof. The STARK only constrains sender, receiver
+/// and amount, so the full statement digest is carried alongside it.
+#[derive(Serialize, Deserialize)]
+struct StarkEnvelope {
+    statement: QuantumSafeHash,
+    proof: StarkProof,
+}
+
+impl PrivateTransactionProver for QuantumSafeStarkProver {
+    fn system(&self) -> ProofSystem {
+        ProofSystem::Stark
+    }
+
+    fn prove(&self, witness: &PrivateTransferWitness) -> Result<ProofBytes, String> {
+        let cost = witness
+            .amount
+            .checked_add(witness.fee)
+            .ok_or_else(|| "Amount overflow: amount + fee exceeds u64".to_string())?;
+        if witness.balance < cost {
+            return Err(format!("Insufficient balance: have {}, need {}", witness.balance, cost));
+        }
+        let public_inputs = witness.public_inputs();
+        let stark_witness = TransactionWitness {
+            sender: witness.sender,
+            receiver: witness.receiver,
+            amount: witness.amount,
+            nonce: witness.nonce,
+            signature: witness.signature,
+        };
+        let proof = QuantumSafeStarkProver::prove(self, &stark_witness, &public_inputs.stark_inputs())
+            .map_err(|e| e.to_string())?;
+        bincode::serialize(&StarkEnvelope {
+            statement: public_inputs.digest(),
+            proof,
+        })
+        .map_err(|e| format!("Proof serialization failed: {}", e))
+    }
+}
+
+impl PrivateTransactionVerifier for QuantumSafeStarkVerifier {
+    fn system(&self) -> ProofSystem {
+        ProofSystem::Stark
+    }
+
+    fn verify(&self, proof: &[u8], public_inputs: &PrivateTransferInputs) -> bool {
+        let Ok(envelope) = bincode::deserialize::<StarkEnvelope>(proof) else {
+            return false;
+        };
+        envelope.statement == public_inputs.digest()
+            && QuantumSafeStarkVerifier::verify(self, &envelope.proof, &public_inputs.stark_inputs())
+                .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn witness() -> PrivateTransferWitness {
+        PrivateTransferWitness {
+            secret_key: [7u8; 32],
+            sender: [1u8; 32],
+            receiver: [2u8; 32],
+            balance: 1000,
+            amount: 100,
+            fee: 10,
+            nonce: 1,
+            signature: [3u8; 64],
+        }
+    }
+
+    fn backends() -> Vec<(Box<dyn PrivateTransactionProver>, Box<dyn PrivateTransactionVerifier>)> {
+        let groth16 = ZkProofSystem::setup().unwrap();
+        let groth16_verifier = ZkProofSystem {
+            proving_key: groth16.proving_key.clone(),
+            verifying_key: groth16.verifying_key.clone(),
+            pvk: groth16.pvk.clone(),
+        };
+        vec![
+            (Box::new(groth16), Box::new(groth16_verifier)),
+            (
+                Box::new(QuantumSafeStarkProver::new(256, 256, 4)),
+                Box::new(QuantumSafeStarkVerifier::new(256)),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_same_transfer_through_either_backend() {
+        let witness = witness();
+        let inputs = witness.public_inputs();
+
+        for (prover, verifier) in backends() {
+            assert_eq!(prover.system(), verifier.system());
+            let proof = PrivateTransactionProof::create(prover.as_ref(), &witness).unwrap();
+            assert_eq!(proof.system, prover.system());
+            assert!(proof.verify(&[verifier.as_ref()], &inputs), "{:?}", proof.system);
+
+            // Different statement
+            let mut other = inputs.clone();
+            other.amount += 1;
+            assert!(!verifier.verify(&proof.proof, &other), "{:?}", proof.system);
+
+            // Garbage bytes
+            assert!(!verifier.verify(&[0u8; 16], &inputs), "{:?}", proof.system);
+        }
+    }
+
+    #[test]
+    fn test_tag_selects_verifier() {
+        let witness = witness();
+        let inputs = witness.public_inputs();
+        let stark_verifier = QuantumSafeStarkVerifier::new(256);
+        let proof =
+            PrivateTransactionProof::create(&QuantumSafeStarkProver::new(256, 256, 4), &witness).unwrap();
+
+        assert!(proof.verify(&[&stark_verifier], &inputs));
+        // No verifier registered for the tagged backend
+        let retagged = PrivateTransactionProof { system: ProofSystem::Groth16, ..proof };
+        assert!(!retagged.verify(&[&stark_verifier], &inputs));
+    }
+
+    #[test]
+    fn test_insufficient_balance_rejected_by_both() {
+        let poor = PrivateTransferWitness { balance: 50, ..witness() };
+        for (prover, _) in backends() {
+            assert!(prover.prove(&poor).is_err(), "{:?}", prover.system());
+        }
+    }
+}
@@ -12,6 +12,11 @@ use std::path::Path;
 use ark_relations::r1cs::Variable;
 use ark_std::One;
 
+/// Whether a field element is the encoding of a `u64`
+fn fits_u64(value: &Fr) -> bool {
+    value.into_bigint().0[1..].iter().all(|&limb| limb == 0)
+}
+
 /// Axiom Transaction Circuit - Proves ownership and solvency without revealing private data
 /// 
 /// This circuit proves:
@@ -212,6 +217,19 @@ impl ZkProofSystem {
             pvk,
         })
     }
+    /// Load keys from `keys_dir`, running setup and saving the result only
+    /// when no keys are there yet. Keys that exist but fail to load are an
+    /// error rather than being silently replaced.
+    pub fn load_or_setup(keys_dir: &str) -> Result<Self, String> {
+        let pk_path = Path::new(keys_dir).join("proving.key");
+        let vk_path = Path::new(keys_dir).join("verifying.key");
+        if pk_path.exists() || vk_path.exists() {
+            return Self::load_keys(keys_dir);
+        }
+        let system = Self::setup()?;
+        system.save_keys(keys_dir)?;
+        Ok(system)
+    }
     /// Generate a proof for a transaction
     pub fn prove(
         &self,
@@ -221,6 +239,14 @@ impl ZkProofSystem {
         transfer_amount: Fr,
         fee: Fr,
     ) -> Result<(Proof<Bls12_381>, Vec<Fr>), String> {
+        // Amounts are u64 on chain; larger field elements could wrap mod p
+        // in the sum below and pass the solvency check
+        for (name, value) in [("balance", current_balance), ("amount", transfer_amount), ("fee", fee)] {
+            if !fits_u64(&value) {
+                return Err(format!("Amount overflow: {} exceeds u64", name));
+            }
+        }
+
         // Pre-check: fail fast if balance is insufficient
         // This prevents wasting time on proof generation for invalid transactions
         if current_balance < transfer_amount + fee {
@@ -322,6 +348,32 @@ mod tests {
         assert!(valid, "Proof should be valid");
     }
     
+    #[test]
+    fn test_saved_keys_load_into_another_system() {
+        let dir = std::env::temp_dir().join(format!("axiom_zk_keys_{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        let _ = fs::remove_dir_all(dir);
+
+        let first = ZkProofSystem::load_or_setup(dir).unwrap();
+        let second = ZkProofSystem::load_keys(dir).unwrap();
+        assert_eq!(first.verifying_key, second.verifying_key);
+
+        let (proof, inputs) = first
+            .prove(Fr::from(42u64), Fr::from(500u64), Fr::from(3u64), Fr::from(50u64), Fr::from(5u64))
+            .unwrap();
+        assert!(second.verify(&proof, &inputs).unwrap());
+
+        // A later start reuses the saved keys instead of a new setup
+        let reloaded = ZkProofSystem::load_or_setup(dir).unwrap();
+        assert_eq!(reloaded.verifying_key, first.verifying_key);
+
+        // Proofs from an unrelated setup do not verify
+        let other = ZkProofSystem::setup().unwrap();
+        assert!(!other.verify(&proof, &inputs).unwrap_or(false));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+    
     #[test]
     fn test_insufficient_balance_fails() {
         let system = ZkProofSystem::setup().unwrap();
@@ -274,5 +274,6 @@ pub fn verify_zk_pass(miner_address: &[u8; 32], _parent: &[u8; 32], proof: &[u8]
     hasher.update(b"mining_proof");
     let expected_hash = hasher.finalize();
 
-    proof[..32] == expected_hash[..32]
+    crate::crypto::ct_eq(&proof[..32], &expected_hash[..32])
 }pub mod circuit;
+pub mod backend;
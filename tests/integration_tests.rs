@@ -127,7 +127,7 @@ mod tests {
         chain.difficulty = 10;
 
         let vdf_seed = vdf::evaluate(parent_hash, current_slot);
-        let vdf_proof = main_helper::compute_vdf(vdf_seed, chain.difficulty as u32);
+        let vdf_proof = main_helper::compute_vdf(vdf_seed, chain.vdf_steps);
         let zk_pass = genesis::generate_zk_pass(&wallet, parent_hash);
 
         // Try to find a valid nonce
@@ -135,17 +135,21 @@ mod tests {
         let mut found = false;
 
         while !found && nonce < 10000 {
+            let coinbase = vec![chain.expected_coinbase(wallet.address, current_slot, 0)];
             let block = Block {
                 parent: parent_hash,
                 slot: current_slot,
+                timestamp: 0,
+                difficulty: chain.difficulty,
                 miner: wallet.address,
-                transactions: vec![],
+                transactions: coinbase.clone(),
+                merkle_root: Block::merkle_root_of(&coinbase),
                 vdf_proof,
                 zk_proof: zk_pass.clone(),
                 nonce,
             };
 
-            if block.meets_difficulty(chain.difficulty) {
+            if block.meets_difficulty() {
                 println!("Found valid nonce: {} for difficulty {}", nonce, chain.difficulty);
                 if chain.add_block(block.clone(), 3600).is_ok() {
                     println!("Block added successfully!");
@@ -0,0 +1,890 @@
+// JSON-RPC server tests: start the HTTP server on a live chain and drive it over the wire
+use std::sync::{Arc, Mutex};
+
+use axiom_core::block::Block;
+use axiom_core::chain::Timechain;
+use axiom_core::config::{RpcConfig, MAINNET_NETWORK_ID};
+use axiom_core::error::AxiomError;
+use axiom_core::genesis;
+use axiom_core::mempool::Mempool;
+use axiom_core::rpc::{self, RpcClient, RpcState};
+use axiom_core::transaction::Transaction;
+use serde_json::{json, Value};
+
+const MINER: [u8; 32] = [7u8; 32];
+
+fn mine_next(chain: &Timechain) -> Block {
+    let parent = chain.blocks.last().unwrap().hash();
+    let slot = chain.blocks.len() as u64;
+    let vdf_proof = axiom_core::main_helper::compute_vdf(
+        axiom_core::vdf::evaluate(parent, slot),
+        chain.vdf_steps,
+    );
+    let coinbase = chain.coinbase_for(MINER, &[]);
+    let mut block = Block::new(parent, slot, MINER, vec![coinbase], vdf_proof, vec![1u8; 128], 0).with_difficulty(chain.difficulty);
+    while !block.meets_difficulty() {
+        block.nonce += 1;
+    }
+    block
+}
+
+fn start_node(blocks: usize) -> (RpcState, String) {
+    let mut chain = Timechain::new(genesis::genesis());
+    for _ in 0..blocks {
+        let block = mine_next(&chain);
+        chain.add_block(block, 1800).unwrap();
+    }
+    let state = RpcState::new(Arc::new(Mutex::new(chain)), Arc::new(Mutex::new(Mempool::new())));
+
+    let config = RpcConfig { listen_address: "127.0.0.1:0".to_string(), ..RpcConfig::default() };
+    let (server, addr) = rpc::serve(&config, state.clone()).expect("bind rpc server");
+    tokio::spawn(server);
+    (state, format!("http://{}/rpc", addr))
+}
+
+async fn call(url: &str, method: &str, params: Value) -> Value {
+    reqwest::Client::new()
+        .post(url)
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+        .send()
+        .await
+        .expect("rpc request")
+        .json()
+        .await
+        .expect("json response")
+}
+
+#[tokio::test]
+async fn test_rpc_chain_queries() {
+    let (state, url) = start_node(2);
+
+    let info = call(&url, "get_chain_info", json!([])).await;
+    assert_eq!(info["result"]["height"], 2);
+    assert_eq!(info["id"], 1);
+
+    let balance = call(&url, "get_balance", json!([hex::encode(MINER)])).await;
+    let expected = state.chain.lock().unwrap().balance(&MINER);
+    assert!(expected > 0);
+    assert_eq!(balance["result"]["balance"], expected);
+
+    // Named params work too
+    let nonce = call(&url, "get_nonce", json!({ "address": hex::encode(MINER) })).await;
+    assert_eq!(nonce["result"]["nonce"], 0);
+
+    let tip = call(&url, "get_latest_block", json!([])).await;
+    let block = call(&url, "get_block", json!([2])).await;
+    assert_eq!(tip["result"], block["result"]);
+    let tip_hash = hex::encode(state.chain.lock().unwrap().blocks[2].hash());
+    assert_eq!(block["result"]["hash"], tip_hash);
+
+    let missing = call(&url, "get_block", json!([99])).await;
+    assert_eq!(missing["error"]["code"], rpc::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_rpc_audit_supply() {
+    let (state, url) = start_node(2);
+
+    let clean = call(&url, "audit_supply", json!([])).await;
+    assert_eq!(clean["result"]["ok"], true);
+    assert_eq!(clean["result"]["height"], 2);
+    assert_eq!(clean["result"]["delta"], 0);
+
+    state.chain.lock().unwrap().state.credit([4u8; 32], 99);
+    let corrupted = call(&url, "audit_supply", json!([])).await;
+    assert_eq!(corrupted["result"]["ok"], false);
+    assert_eq!(corrupted["result"]["delta"], 99);
+}
+
+#[tokio::test]
+async fn test_client_pinned_to_genesis() {
+    let (state, url) = start_node(1);
+    let genesis = state.chain.lock().unwrap().blocks[0].hash();
+
+    let info = RpcClient::new(url.clone()).chain_info().await.unwrap();
+    assert_eq!(info["genesis_hash"], hex::encode(genesis));
+    assert_eq!(info["network_id"], MAINNET_NETWORK_ID);
+
+    // Pinned to this node's genesis, calls go through; node errors still
+    // come back as errors
+    let pinned = RpcClient::new_pinned(url.clone(), genesis);
+    assert_eq!(pinned.call("get_block", json!([1])).await.unwrap()["height"], 1);
+    assert!(matches!(pinned.call("get_block", json!([99])).await, Err(AxiomError::RpcError(_))));
+
+    // Pinned to another network, the first call is refused before it is
+    // sent, and so is every call after it
+    let elsewhere = RpcClient::new_pinned(url, [0xAB; 32]);
+    let tx = Transaction::new(MINER, [2u8; 32], 5, 1, 0, vec![], vec![]);
+    match elsewhere.call("broadcast_transaction", json!([tx])).await {
+        Err(AxiomError::GenesisMismatch { expected, actual }) => {
+            assert_eq!(expected, hex::encode([0xAB; 32]));
+            assert_eq!(actual, hex::encode(genesis));
+        }
+        other => panic!("expected a genesis mismatch, got {:?}", other),
+    }
+    assert!(matches!(elsewhere.chain_info().await, Err(AxiomError::GenesisMismatch { .. })));
+    assert!(state.mempool.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_rpc_transactions_and_errors() {
+    let (state, url) = start_node(0);
+
+    // Pending transactions are found in the mempool
+    let tx = Transaction::new([1u8; 32], [2u8; 32], 5, 1, 0, vec![], vec![]);
+    state.mempool.lock().unwrap().add(tx.clone(), &axiom_core::state::State::new()).unwrap();
+    let found = call(&url, "get_transaction", json!([hex::encode(tx.hash())])).await;
+    assert_eq!(found["result"]["status"], "pending");
+    assert_eq!(found["result"]["transaction"]["amount"], 5);
+
+    let unknown = call(&url, "get_transaction", json!([hex::encode([9u8; 32])])).await;
+    assert_eq!(unknown["error"]["code"], rpc::NOT_FOUND);
+
+    // Unfunded sender is rejected and never reaches the mempool
+    let unfunded = Transaction::new([3u8; 32], [2u8; 32], 5, 1, 0, vec![], vec![]);
+    let rejected = call(&url, "broadcast_transaction", json!([unfunded])).await;
+    assert_eq!(rejected["error"]["code"], rpc::TX_REJECTED);
+    assert_eq!(state.mempool.lock().unwrap().len(), 1);
+
+    let bad_params = call(&url, "get_balance", json!(["not-hex"])).await;
+    assert_eq!(bad_params["error"]["code"], rpc::INVALID_PARAMS);
+
+    let no_method = call(&url, "mine_me_a_block", json!([])).await;
+    assert_eq!(no_method["error"]["code"], rpc::METHOD_NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_rpc_simulate_transaction() {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    let (state, url) = start_node(0);
+    let key = SigningKey::from_bytes(&[9u8; 32]);
+    let sender = key.verifying_key().to_bytes();
+    {
+        let mut chain = state.chain.lock().unwrap();
+        chain.state.credit(sender, 1_000);
+        chain.state.nonces.insert(sender, 2);
+    }
+    let signed = |amount: u64, nonce: u64| {
+        let mut tx = Transaction::new(sender, [2u8; 32], amount, 10, nonce, vec![], vec![]);
+        tx.signature = key.sign(&tx.signing_message()).to_bytes().to_vec();
+        tx
+    };
+    let failed = |result: &Value| -> Vec<String> {
+        result["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|check| check["passed"] == false)
+            .map(|check| check["check"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let ok = call(&url, "simulate_transaction", json!([signed(990, 2)])).await["result"].clone();
+    assert_eq!(ok["would_succeed"], true);
+    assert_eq!(ok["failure"], Value::Null);
+    assert_eq!(ok["balance_delta"][hex::encode(sender)], -1_000);
+    assert_eq!(ok["balance_delta"][hex::encode([2u8; 32])], 990);
+
+    let broke = call(&url, "simulate_transaction", json!([signed(991, 2)])).await["result"].clone();
+    assert_eq!(broke["would_succeed"], false);
+    assert_eq!(failed(&broke), ["balance"]);
+    assert!(broke["checks"][2]["error"].as_str().unwrap().starts_with("Insufficient balance"));
+
+    let stale = call(&url, "simulate_transaction", json!({ "transaction": signed(5, 1) })).await["result"].clone();
+    assert_eq!(stale["failure"], "nonce");
+    assert_eq!(failed(&stale), ["nonce"]);
+    assert!(stale["checks"][1]["error"].as_str().unwrap().contains("expected 2, got 1"));
+
+    // A dry run never touches the mempool or the chain
+    assert!(state.mempool.lock().unwrap().is_empty());
+    assert_eq!(state.chain.lock().unwrap().balance(&sender), 1_000);
+}
+
+#[tokio::test]
+async fn test_rpc_batch_balances() {
+    let (state, url) = start_node(1);
+    let miner_balance = state.chain.lock().unwrap().balance(&MINER);
+
+    let addresses = [hex::encode(MINER), hex::encode([0x42u8; 32]), "zz".to_string(), hex::encode(MINER)];
+    let ids: Vec<Value> = vec![json!(10), json!("b"), json!(12), json!(13)];
+    let mut batch: Vec<Value> = addresses
+        .iter()
+        .zip(&ids)
+        .map(|(addr, id)| json!({ "jsonrpc": "2.0", "id": id, "method": "get_balance", "params": [addr] }))
+        .collect();
+    // A not-found lookup and a malformed entry ride along in the same batch
+    batch.push(json!({ "jsonrpc": "2.0", "id": 14, "method": "get_block", "params": [99] }));
+    batch.push(json!({ "id": 15, "params": [] }));
+    // Send in reverse so the demux cannot rely on ordering
+    batch.reverse();
+    let all_ids: Vec<Value> = ids.iter().cloned().chain([json!(14), json!(15)]).collect();
+
+    let responses: Vec<rpc::RpcResponse> = reqwest::Client::new()
+        .post(&url)
+        .json(&batch)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(responses.len(), 6);
+
+    let results = rpc::demux_batch(&all_ids, responses);
+    assert_eq!(results[0].as_ref().unwrap()["balance"], miner_balance);
+    assert_eq!(results[1].as_ref().unwrap()["balance"], 0);
+    assert_eq!(results[2].as_ref().unwrap_err().code, rpc::INVALID_PARAMS);
+    assert_eq!(results[3].as_ref().unwrap()["balance"], miner_balance);
+    assert_eq!(results[4].as_ref().unwrap_err().code, rpc::NOT_FOUND);
+    assert_eq!(results[5].as_ref().unwrap_err().code, rpc::INVALID_REQUEST);
+
+    // Empty batches are rejected as a whole
+    let empty: Value = reqwest::Client::new().post(&url).json(&json!([])).send().await.unwrap().json().await.unwrap();
+    assert_eq!(empty["error"]["code"], rpc::INVALID_REQUEST);
+}
+
+#[tokio::test]
+async fn test_ws_subscriptions() {
+    use futures::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+
+    let (events, _) = tokio::sync::broadcast::channel(16);
+    let chain = Arc::new(Mutex::new(Timechain::new(genesis::genesis())));
+    let state = RpcState::new(chain.clone(), Arc::new(Mutex::new(Mempool::new()))).with_events(events);
+    let config = RpcConfig { listen_address: "127.0.0.1:0".to_string(), ..RpcConfig::default() };
+    let (server, addr) = rpc::serve(&config, state.clone()).unwrap();
+    tokio::spawn(server);
+
+    let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr)).await.unwrap();
+    async fn next_json(socket: &mut (impl StreamExt<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin)) -> Value {
+        loop {
+            if let Message::Text(text) = socket.next().await.unwrap().unwrap() {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    let subscribe = |id: u64, topic: &str| {
+        Message::Text(json!({ "jsonrpc": "2.0", "id": id, "method": "subscribe", "params": [topic] }).to_string())
+    };
+    socket.send(subscribe(1, "newHeads")).await.unwrap();
+    let heads = next_json(&mut socket).await["result"].as_str().unwrap().to_string();
+    socket.send(subscribe(2, "pendingTransactions")).await.unwrap();
+    let pending = next_json(&mut socket).await["result"].as_str().unwrap().to_string();
+    assert_ne!(heads, pending);
+
+    // Mine a block and announce it the way the node's accept path does
+    let block = {
+        let mut chain = chain.lock().unwrap();
+        let block = mine_next(&chain);
+        chain.add_block(block.clone(), 1800).unwrap();
+        block
+    };
+    state.notify(rpc::NodeEvent::NewBlock(block.clone()));
+
+    let note = next_json(&mut socket).await;
+    assert_eq!(note["method"], "subscription");
+    assert_eq!(note["params"]["subscription"], heads.as_str());
+    assert_eq!(note["params"]["result"]["height"], 1);
+    assert_eq!(note["params"]["result"]["hash"], hex::encode(block.hash()));
+
+    let tx = Transaction::new([1u8; 32], [2u8; 32], 5, 1, 0, vec![], vec![]);
+    state.notify(rpc::NodeEvent::PendingTransaction(tx.clone()));
+    let note = next_json(&mut socket).await;
+    assert_eq!(note["params"]["subscription"], pending.as_str());
+    assert_eq!(note["params"]["result"]["hash"], hex::encode(tx.hash()));
+
+    // After unsubscribing, only the remaining subscription fires
+    socket
+        .send(Message::Text(json!({ "jsonrpc": "2.0", "id": 3, "method": "unsubscribe", "params": [heads] }).to_string()))
+        .await
+        .unwrap();
+    assert_eq!(next_json(&mut socket).await["result"], true);
+    state.notify(rpc::NodeEvent::NewBlock(block));
+    state.notify(rpc::NodeEvent::PendingTransaction(tx));
+    let note = next_json(&mut socket).await;
+    assert_eq!(note["params"]["subscription"], pending.as_str());
+
+    // Regular methods work over the socket too
+    socket
+        .send(Message::Text(json!({ "jsonrpc": "2.0", "id": 4, "method": "get_chain_info", "params": [] }).to_string()))
+        .await
+        .unwrap();
+    assert_eq!(next_json(&mut socket).await["result"]["height"], 1);
+}
+
+#[tokio::test]
+async fn test_ws_mempool_events() {
+    use futures::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+
+    // The pool publishes on the node's channel, as the node sets it up
+    let (events, _) = tokio::sync::broadcast::channel(16);
+    let chain = Arc::new(Mutex::new(Timechain::new(genesis::genesis())));
+    let pool = Arc::new(Mutex::new(Mempool::new().with_events(events.clone())));
+    let state = RpcState::new(chain, pool.clone()).with_events(events);
+    let config = RpcConfig { listen_address: "127.0.0.1:0".to_string(), ..RpcConfig::default() };
+    let (server, addr) = rpc::serve(&config, state.clone()).unwrap();
+    tokio::spawn(server);
+
+    let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr)).await.unwrap();
+    async fn recv(socket: &mut (impl StreamExt<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin)) -> Value {
+        loop {
+            if let Message::Text(text) = socket.next().await.unwrap().unwrap() {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+    socket
+        .send(Message::Text(json!({ "jsonrpc": "2.0", "id": 1, "method": "subscribe", "params": ["mempool"] }).to_string()))
+        .await
+        .unwrap();
+    assert!(recv(&mut socket).await["result"].is_string());
+
+    let old = Transaction::new([1u8; 32], [2u8; 32], 5, 10, 0, vec![], vec![]);
+    let new = Transaction::new([1u8; 32], [2u8; 32], 5, 20, 0, vec![], vec![]);
+    let chain_state = axiom_core::state::State::new();
+    pool.lock().unwrap().add(old.clone(), &chain_state).unwrap();
+    pool.lock().unwrap().add(new.clone(), &chain_state).unwrap();
+
+    let added = recv(&mut socket).await;
+    assert_eq!(added["params"]["result"]["event"], "added");
+    assert_eq!(added["params"]["result"]["tx"]["hash"], hex::encode(old.hash()));
+    let replaced = recv(&mut socket).await["params"]["result"].clone();
+    assert_eq!(replaced["event"], "replaced");
+    assert_eq!(replaced["old"], hex::encode(old.hash()));
+    assert_eq!(replaced["new"]["hash"], hex::encode(new.hash()));
+    assert_eq!(replaced["new"]["fee"], 20);
+
+    let block = Block::new([0u8; 32], 3, MINER, vec![new.clone()], [0u8; 32], vec![], 0);
+    pool.lock().unwrap().remove_mined(&block);
+    let mined = recv(&mut socket).await["params"]["result"].clone();
+    assert_eq!(mined, json!({ "event": "mined", "hash": hex::encode(new.hash()), "height": 3 }));
+}
+
+#[tokio::test]
+async fn test_rpc_rate_limit() {
+    let state = RpcState::new(
+        Arc::new(Mutex::new(Timechain::new(genesis::genesis()))),
+        Arc::new(Mutex::new(Mempool::new())),
+    );
+    let config = RpcConfig { listen_address: "127.0.0.1:0".to_string(), rate_limit: 3, ..RpcConfig::default() };
+    let (server, addr) = rpc::serve(&config, state).unwrap();
+    tokio::spawn(server);
+    let url = format!("http://{}/rpc", addr);
+
+    for _ in 0..3 {
+        assert_eq!(call(&url, "get_chain_info", json!([])).await["result"]["height"], 0);
+    }
+
+    let throttled = reqwest::Client::new()
+        .post(&url)
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "get_chain_info", "params": [] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(throttled.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+    // One token refills every 20s at 3/min
+    assert_eq!(throttled.headers()["retry-after"], "20");
+    let body: Value = throttled.json().await.unwrap();
+    assert_eq!(body["error"]["code"], rpc::RATE_LIMITED);
+}
+
+#[tokio::test]
+async fn test_rpc_merkle_proof() {
+    let (state, url) = start_node(1);
+
+    // Confirmed transactions only reach the chain with valid ZK proofs, so
+    // place a multi-transaction block at the tip directly
+    let txs: Vec<Transaction> =
+        (0..5u8).map(|i| Transaction::new([i; 32], [9u8; 32], 10 + i as u64, 1, 0, vec![], vec![])).collect();
+    let block = {
+        let mut chain = state.chain.lock().unwrap();
+        let parent = chain.blocks.last().unwrap().hash();
+        let block = Block::new(parent, 2, MINER, txs.clone(), [0u8; 32], vec![], 0);
+        chain.blocks.push(block.clone());
+        block
+    };
+    let root = block.compute_merkle_root();
+
+    let tx_hash = txs[3].hash();
+    let response = call(&url, "get_merkle_proof", json!([hex::encode(tx_hash)])).await;
+    let result = &response["result"];
+    assert_eq!(result["block_height"], 2);
+    assert_eq!(result["merkle_root"], hex::encode(root));
+
+    let proof = rpc::merkle_proof_from_json(&result["proof"]).unwrap();
+    assert_eq!(proof.index, 3);
+    assert!(proof.verify(tx_hash, root));
+
+    // A tampered path no longer reaches the root
+    let mut tampered = proof.clone();
+    tampered.siblings[0][0] ^= 0xFF;
+    assert!(!tampered.verify(tx_hash, root));
+    assert!(!proof.verify(txs[2].hash(), root));
+
+    let pending = call(&url, "get_merkle_proof", json!([hex::encode([0xAB; 32])])).await;
+    assert_eq!(pending["error"]["code"], rpc::NOT_FOUND);
+
+    // The lookup was cached; a reorg replacing the block invalidates it
+    let hits = state.cache.lock().unwrap().stats().hits;
+    let found = call(&url, "get_transaction", json!([hex::encode(tx_hash)])).await;
+    assert_eq!(found["result"]["block_height"], 2);
+    assert_eq!(state.cache.lock().unwrap().stats().hits, hits + 1);
+    {
+        let mut chain = state.chain.lock().unwrap();
+        let parent = chain.blocks[1].hash();
+        chain.blocks[2] = Block::new(parent, 2, [8u8; 32], vec![], [0u8; 32], vec![], 0);
+    }
+    let reorged = call(&url, "get_transaction", json!([hex::encode(tx_hash)])).await;
+    assert_eq!(reorged["error"]["code"], rpc::NOT_FOUND);
+    assert_eq!(state.cache.lock().unwrap().stats().transactions, 0);
+}
+
+#[tokio::test]
+async fn test_rpc_generate_zk_proof() {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_groth16::Proof;
+    use ark_serialize::CanonicalDeserialize;
+    use axiom_core::zk::circuit::ZkProofSystem;
+
+    let (_, url_without_keys) = start_node(0);
+    let missing = call(&url_without_keys, "generate_zk_proof", json!([hex::encode([1u8; 32]), 100, 0, 10, 1])).await;
+    assert_eq!(missing["error"]["code"], rpc::INTERNAL_ERROR);
+
+    let zk = Arc::new(ZkProofSystem::setup().unwrap());
+    let chain = Timechain::new(genesis::genesis());
+    let state = RpcState::new(Arc::new(Mutex::new(chain)), Arc::new(Mutex::new(Mempool::new())))
+        .with_zk_system(zk.clone());
+    let config = RpcConfig { listen_address: "127.0.0.1:0".to_string(), ..RpcConfig::default() };
+    let (server, addr) = rpc::serve(&config, state).expect("bind rpc server");
+    tokio::spawn(server);
+    let url = format!("http://{}/rpc", addr);
+
+    let params = json!({ "secret_key": hex::encode([1u8; 32]), "balance": 100, "nonce": 0, "amount": 10, "fee": 1 });
+    let response = call(&url, "generate_zk_proof", params).await;
+    let result = &response["result"];
+    let decode = |value: &Value| hex::decode(value.as_str().unwrap()).unwrap();
+    let proof = Proof::<Bls12_381>::deserialize_compressed(&decode(&result["proof"])[..]).unwrap();
+    let inputs: Vec<Fr> = result["public_inputs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|input| Fr::deserialize_compressed(&decode(input)[..]).unwrap())
+        .collect();
+    assert!(zk.verify(&proof, &inputs).unwrap());
+
+    let overspend = call(&url, "generate_zk_proof", json!([hex::encode([1u8; 32]), 5, 0, 10, 1])).await;
+    assert_eq!(overspend["error"]["code"], rpc::INVALID_PARAMS);
+}
+
+#[tokio::test]
+async fn test_rpc_proof_requests_past_limit_are_rejected() {
+    use axiom_core::zk::circuit::ZkProofSystem;
+
+    let zk = Arc::new(ZkProofSystem::setup().unwrap());
+    let chain = Timechain::new(genesis::genesis());
+    let state = RpcState::new(Arc::new(Mutex::new(chain)), Arc::new(Mutex::new(Mempool::new())))
+        .with_zk_system(zk)
+        .with_proof_limits(1, 0);
+    let config = RpcConfig { listen_address: "127.0.0.1:0".to_string(), ..RpcConfig::default() };
+    let (server, addr) = rpc::serve(&config, state.clone()).expect("bind rpc server");
+    tokio::spawn(server);
+    let url = format!("http://{}/rpc", addr);
+    let params = json!([hex::encode([1u8; 32]), 100, 0, 10, 1]);
+
+    // A proof in progress fills the only slot; with no queue, more are refused
+    let running = state.proofs.acquire().unwrap();
+    let requests = (0..3).map(|_| call(&url, "generate_zk_proof", params.clone()));
+    for busy in futures::future::join_all(requests).await {
+        assert_eq!(busy["error"]["code"], rpc::SERVER_BUSY);
+    }
+    // Other methods are still served meanwhile
+    let info = call(&url, "get_chain_info", json!([])).await;
+    assert_eq!(info["result"]["height"], 0);
+
+    drop(running);
+    let response = call(&url, "generate_zk_proof", params).await;
+    assert!(response["result"]["proof"].is_string());
+    assert_eq!(state.proofs.depth(), (0, 0));
+}
+
+#[tokio::test]
+async fn test_rpc_address_history_paging() {
+    let (state, url) = start_node(1);
+    let other = [9u8; 32];
+
+    // Transfers between the miner and `other` over two blocks, indexed by a
+    // replay of the chain
+    let sent = vec![
+        vec![
+            Transaction::new(MINER, other, 100, 1, 0, vec![], vec![]),
+            Transaction::new(MINER, other, 200, 1, 1, vec![], vec![]),
+        ],
+        vec![
+            Transaction::new(MINER, other, 300, 1, 2, vec![], vec![]),
+            Transaction::new(other, MINER, 50, 1, 0, vec![], vec![]),
+        ],
+    ];
+    {
+        let mut chain = state.chain.lock().unwrap();
+        for txs in &sent {
+            let parent = chain.blocks.last().unwrap().hash();
+            let slot = chain.blocks.len() as u64;
+            chain.blocks.push(Block::new(parent, slot, MINER, txs.clone(), [0u8; 32], vec![], 0));
+        }
+        chain.rebuild_state();
+        assert_eq!(chain.balance(&other), 549);
+    }
+    let newest_first: Vec<String> = sent.iter().flatten().rev().map(|tx| hex::encode(tx.hash())).collect();
+    let hashes = |response: &Value| -> Vec<String> {
+        let txs = response["result"]["transactions"].as_array().unwrap();
+        txs.iter().map(|tx| tx["hash"].as_str().unwrap().to_string()).collect()
+    };
+
+    let all = call(&url, "get_address_history", json!([hex::encode(other)])).await;
+    assert_eq!(all["result"]["total"], 4);
+    assert_eq!(hashes(&all), newest_first);
+    assert_eq!(all["result"]["transactions"][0]["block_height"], 3);
+    assert_eq!(all["result"]["transactions"][3]["block_height"], 2);
+
+    let first = call(&url, "get_address_history", json!([hex::encode(other), 0, 3])).await;
+    assert_eq!(hashes(&first), newest_first[..3].to_vec());
+    let page = json!({ "address": hex::encode(other), "offset": 3, "limit": 3 });
+    let second = call(&url, "get_address_history", page).await;
+    assert_eq!(hashes(&second), newest_first[3..].to_vec());
+    let past_end = call(&url, "get_address_history", json!([hex::encode(other), 4, 3])).await;
+    assert!(hashes(&past_end).is_empty());
+    assert_eq!(past_end["result"]["total"], 4);
+
+    let unknown = call(&url, "get_address_history", json!([hex::encode([4u8; 32])])).await;
+    assert_eq!(unknown["result"]["total"], 0);
+    for bad_limit in [0, rpc::MAX_HISTORY_LIMIT + 1] {
+        let response = call(&url, "get_address_history", json!([hex::encode(other), 0, bad_limit])).await;
+        assert_eq!(response["error"]["code"], rpc::INVALID_PARAMS);
+    }
+}
+
+#[tokio::test]
+async fn test_rpc_address_history_of_coinbases() {
+    // Every block pays MINER through its coinbase
+    let (state, url) = start_node(3);
+    let coinbases: Vec<String> = {
+        let chain = state.chain.lock().unwrap();
+        chain.blocks[1..].iter().rev().map(|block| hex::encode(block.transactions[0].hash())).collect()
+    };
+    let hashes = |response: &Value| -> Vec<String> {
+        let txs = response["result"]["transactions"].as_array().unwrap();
+        txs.iter().map(|tx| tx["hash"].as_str().unwrap().to_string()).collect()
+    };
+
+    let all = call(&url, "get_address_history", json!([hex::encode(MINER)])).await;
+    assert_eq!(all["result"]["total"], 3);
+    assert_eq!(hashes(&all), coinbases);
+    let heights: Vec<u64> =
+        all["result"]["transactions"].as_array().unwrap().iter().map(|tx| tx["block_height"].as_u64().unwrap()).collect();
+    assert_eq!(heights, vec![3, 2, 1]);
+
+    let page = call(&url, "get_address_history", json!({ "address": hex::encode(MINER), "offset": 1, "limit": 1 })).await;
+    assert_eq!(hashes(&page), coinbases[1..2].to_vec());
+    assert_eq!(page["result"]["offset"], 1);
+
+    // The coinbase sender has no history of its own
+    let sender = call(&url, "get_address_history", json!([hex::encode(axiom_core::transaction::COINBASE_SENDER)])).await;
+    assert_eq!(sender["result"]["total"], 0);
+}
+
+#[tokio::test]
+async fn test_rpc_regtest_generate_blocks() {
+    use axiom_core::wallet::Wallet;
+
+    // Disabled unless the node runs regtest
+    let (_, mainnet_url) = start_node(0);
+    let disabled = call(&mainnet_url, "generate_blocks", json!([5])).await;
+    assert_eq!(disabled["error"]["code"], rpc::METHOD_NOT_FOUND);
+
+    let chain = Timechain::with_genesis(&genesis::GenesisConfig::regtest()).unwrap();
+    let miner = Arc::new(Wallet::from_secret_key([9u8; 32]));
+    let (relay, mut relayed) = tokio::sync::mpsc::unbounded_channel();
+    let state = RpcState::new(Arc::new(Mutex::new(chain)), Arc::new(Mutex::new(Mempool::new())))
+        .with_regtest_miner(miner.clone())
+        .with_block_relay(relay);
+    let config = RpcConfig { listen_address: "127.0.0.1:0".to_string(), ..RpcConfig::default() };
+    let (server, addr) = rpc::serve(&config, state.clone()).expect("bind rpc server");
+    tokio::spawn(server);
+    let url = format!("http://{}/rpc", addr);
+
+    let response = call(&url, "generate_blocks", json!([5])).await;
+    assert_eq!(response["result"]["height"], 5);
+    assert_eq!(response["result"]["hashes"].as_array().unwrap().len(), 5);
+    let too_many = call(&url, "generate_blocks", json!({ "count": rpc::MAX_GENERATE_BLOCKS + 1 })).await;
+    assert_eq!(too_many["error"]["code"], rpc::INVALID_PARAMS);
+
+    // Exactly five blocks, each valid on a fresh copy of the chain
+    let chain = state.chain.lock().unwrap();
+    assert_eq!(chain.blocks.len(), 6);
+    let mut replay = chain.genesis_only();
+    for block in &chain.blocks[1..] {
+        replay.add_block(block.clone(), 1800).unwrap();
+        assert_eq!(relayed.try_recv().unwrap().hash(), block.hash());
+    }
+    assert_eq!(replay.balance(&miner.address), chain.balance(&miner.address));
+    assert!(chain.balance(&miner.address) > 0);
+    drop(chain);
+
+    // Even when enabled, the mainnet chain is never extended
+    let mainnet = RpcState::new(
+        Arc::new(Mutex::new(Timechain::new(genesis::genesis()))),
+        Arc::new(Mutex::new(Mempool::new())),
+    )
+    .with_regtest_miner(miner);
+    let request = rpc::RpcRequest {
+        jsonrpc: "2.0".to_string(),
+        method: "generate_blocks".to_string(),
+        params: json!([1]),
+        id: json!(1),
+    };
+    assert_eq!(rpc::dispatch(&mainnet, &request).error.unwrap().code, rpc::INVALID_REQUEST);
+}
+
+#[tokio::test]
+async fn test_rpc_block_template_round_trip() {
+    let (state, url) = start_node(1);
+    let miner = [5u8; 32];
+
+    let template = call(&url, "get_block_template", json!([hex::encode(miner)])).await["result"].clone();
+    assert_eq!(template["height"], 2);
+    assert_eq!(template["coinbase"]["to"], hex::encode(miner));
+    let reward = axiom_core::economics::get_mining_reward(2);
+    assert_eq!(template["coinbase"]["amount"], reward);
+
+    // Complete the template the way an external miner would
+    let decode = |value: &Value| hex::decode(value.as_str().unwrap()).unwrap();
+    let transactions: Vec<Transaction> = template["transactions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tx| bincode::deserialize(&decode(tx)).unwrap())
+        .collect();
+    let parent: [u8; 32] = decode(&template["parent"]).try_into().unwrap();
+    let seed: [u8; 32] = decode(&template["vdf_seed"]).try_into().unwrap();
+    let difficulty = template["difficulty"].as_u64().unwrap();
+    let vdf_proof = axiom_core::main_helper::compute_vdf(seed, template["vdf_iterations"].as_u64().unwrap() as u32);
+    let mut block = Block::new(parent, 2, miner, transactions, vdf_proof, vec![1u8; 128], 0)
+        .with_timestamp(template["timestamp"].as_u64().unwrap())
+        .with_difficulty(difficulty);
+    assert_eq!(hex::encode(block.merkle_root), template["merkle_root"]);
+    while !block.meets_difficulty() {
+        block.nonce += 1;
+    }
+
+    let encoded = hex::encode(bincode::serialize(&block).unwrap());
+    let submitted = call(&url, "submit_block", json!([encoded])).await;
+    assert_eq!(submitted["result"]["height"], 2);
+    assert_eq!(submitted["result"]["hash"], hex::encode(block.hash()));
+    assert_eq!(state.chain.lock().unwrap().balance(&miner), reward);
+
+    // The same block cannot be submitted twice, and garbage is not a block
+    let again = call(&url, "submit_block", json!({ "block": encoded })).await;
+    assert_eq!(again["error"]["code"], rpc::BLOCK_REJECTED);
+    let garbage = call(&url, "submit_block", json!(["zz"])).await;
+    assert_eq!(garbage["error"]["code"], rpc::INVALID_PARAMS);
+}
+
+#[tokio::test]
+#[ignore] // Skip in CI - requires valid ZK keys
+async fn test_rpc_block_template_selects_affordable() {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    let (state, url) = start_node(1);
+    let key = SigningKey::from_bytes(&[9u8; 32]);
+    let sender = key.verifying_key().to_bytes();
+    state.chain.lock().unwrap().state.credit(sender, 500);
+    let signed = |amount: u64, nonce: u64| {
+        let mut tx = Transaction::new(sender, [2u8; 32], amount, 10, nonce, vec![], vec![]);
+        tx.signature = key.sign(&tx.signing_message()).to_bytes().to_vec();
+        tx
+    };
+
+    // Each is valid alone: two spend nonce 0, and the third overspends
+    // once the first is applied
+    let first = signed(300, 0);
+    let conflicting = signed(100, 0);
+    let overspend = signed(300, 1);
+    {
+        let chain = state.chain.lock().unwrap();
+        let mut mempool = state.mempool.lock().unwrap();
+        for tx in [first.clone(), conflicting, overspend] {
+            mempool.add(tx, &chain.state).unwrap();
+        }
+    }
+
+    let template = call(&url, "get_block_template", json!([hex::encode(MINER)])).await["result"].clone();
+    let transactions: Vec<Transaction> = template["transactions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tx| bincode::deserialize(&hex::decode(tx.as_str().unwrap()).unwrap()).unwrap())
+        .collect();
+    assert_eq!(transactions.len(), 2);
+    assert!(transactions[0].is_coinbase());
+    assert_eq!(transactions[1], first);
+    assert_eq!(template["coinbase"]["amount"], axiom_core::economics::get_mining_reward(2) + 10);
+}
+
+#[tokio::test]
+async fn test_rpc_sync_status() {
+    use axiom_core::sync::SyncTracker;
+
+    // Without peer heights the node counts as synced
+    let (_, url) = start_node(2);
+    let status = call(&url, "get_sync_status", json!([])).await;
+    assert_eq!(status["result"]["synced"], true);
+    assert_eq!(status["result"]["current"], 2);
+
+    let chain = Timechain::new(genesis::genesis());
+    let tracker = Arc::new(Mutex::new(SyncTracker::new()));
+    tracker.lock().unwrap().observe(libp2p::PeerId::random(), 4);
+    let state = RpcState::new(Arc::new(Mutex::new(chain)), Arc::new(Mutex::new(Mempool::new())))
+        .with_sync(tracker.clone());
+    let request = rpc::RpcRequest {
+        jsonrpc: "2.0".to_string(),
+        method: "get_sync_status".to_string(),
+        params: json!([]),
+        id: json!(1),
+    };
+    let behind = rpc::dispatch(&state, &request).result.unwrap();
+    assert_eq!(behind, json!({ "synced": false, "current": 0, "target": 4, "progress": 0.0, "download": null }));
+
+    // Catching up to the peer's tip flips the flag
+    for _ in 0..4 {
+        let mut chain = state.chain.lock().unwrap();
+        let block = mine_next(&chain);
+        chain.add_block(block, 1800).unwrap();
+    }
+    assert_eq!(rpc::dispatch(&state, &request).result.unwrap()["synced"], true);
+}
+
+#[tokio::test]
+async fn test_health_probes() {
+    use axiom_core::sync::SyncTracker;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let tracker = Arc::new(Mutex::new(SyncTracker::new()));
+    let peers = Arc::new(AtomicUsize::new(0));
+    let peer = libp2p::PeerId::random();
+    tracker.lock().unwrap().observe(peer, 3);
+    let state = RpcState::new(
+        Arc::new(Mutex::new(Timechain::new(genesis::genesis()))),
+        Arc::new(Mutex::new(Mempool::new())),
+    )
+    .with_sync(tracker.clone())
+    .with_peers(peers.clone(), 1);
+
+    let config = RpcConfig { listen_address: "127.0.0.1:0".to_string(), ..RpcConfig::default() };
+    let (server, addr) = rpc::serve(&config, state.clone()).expect("bind rpc server");
+    tokio::spawn(server);
+    let probe = |path: &'static str| async move {
+        let response = reqwest::get(format!("http://{}{}", addr, path)).await.expect("probe request");
+        let status = response.status().as_u16();
+        (status, response.json::<Value>().await.expect("json body"))
+    };
+
+    assert_eq!(probe("/health/live").await.0, 200);
+
+    // Behind a peer: live but not ready
+    let (status, body) = probe("/health/ready").await;
+    assert_eq!(status, 503);
+    assert_eq!(body["ready"], false);
+    assert_eq!(body["height"], 0);
+    assert_eq!(body["sync"], json!({ "state": "syncing", "current": 0, "target": 3 }));
+
+    // Synced, but the only peer is gone
+    tracker.lock().unwrap().remove_peer(&peer);
+    let (status, body) = probe("/health/ready").await;
+    assert_eq!(status, 503);
+    assert_eq!(body["sync"]["state"], "synced");
+    assert_eq!(body["peers"], 0);
+
+    // Synced and peered
+    peers.store(1, Ordering::Relaxed);
+    let (status, body) = probe("/health/ready").await;
+    assert_eq!(status, 200);
+    assert_eq!(body["ready"], true);
+    assert_eq!(body["min_peers"], 1);
+}
+
+#[tokio::test]
+async fn test_large_stark_proof_fetched_in_chunks() {
+    use axiom_core::crypto::quantum_safe_stark::{QuantumSafeStarkProver, QuantumSafeStarkVerifier};
+    use axiom_core::zk::backend::{PrivateTransactionProof, PrivateTransactionVerifier, PrivateTransferWitness};
+
+    let state = RpcState::new(
+        Arc::new(Mutex::new(Timechain::new(genesis::genesis()))),
+        Arc::new(Mutex::new(Mempool::new())),
+    )
+    .with_proof_store(4, 256);
+    let config = RpcConfig { listen_address: "127.0.0.1:0".to_string(), ..RpcConfig::default() };
+    let (server, addr) = rpc::serve(&config, state.clone()).expect("bind rpc server");
+    tokio::spawn(server);
+    let url = format!("http://{}/rpc", addr);
+
+    let witness = PrivateTransferWitness {
+        secret_key: [1u8; 32],
+        sender: [2u8; 32],
+        receiver: [3u8; 32],
+        balance: 1_000,
+        amount: 100,
+        fee: 1,
+        nonce: 0,
+        signature: [4u8; 64],
+    };
+    let params = json!({
+        "secret_key": hex::encode(witness.secret_key),
+        "sender": hex::encode(witness.sender),
+        "receiver": hex::encode(witness.receiver),
+        "balance": witness.balance,
+        "amount": witness.amount,
+        "fee": witness.fee,
+        "nonce": witness.nonce,
+        "signature": hex::encode(witness.signature),
+    });
+    let response = call(&url, "generate_stark_proof", params).await;
+    let result = &response["result"];
+    assert_eq!(result["chunk_size"], 256);
+    assert!(result["proof"].is_null(), "a large proof is not returned inline");
+    let id: [u8; 32] = hex::decode(result["proof_id"].as_str().unwrap()).unwrap().try_into().unwrap();
+
+    let fetched = RpcClient::new(url.clone()).fetch_proof(id).await.unwrap();
+    assert_eq!(fetched.len() as u64, result["size"].as_u64().unwrap());
+    assert!(fetched.len() > 256);
+    let local = PrivateTransactionProof::create(&QuantumSafeStarkProver::new(256, 256, 4), &witness).unwrap();
+    assert_eq!(fetched, local.proof);
+    let verifier = QuantumSafeStarkVerifier::new(256);
+    assert!(PrivateTransactionVerifier::verify(&verifier, &fetched, &witness.public_inputs()));
+
+    // Offsets off a chunk boundary and unknown ids are refused
+    let misaligned = call(&url, "get_proof_chunk", json!([hex::encode(id), 100])).await;
+    assert_eq!(misaligned["error"]["code"], rpc::INVALID_PARAMS);
+    let unknown = call(&url, "get_proof_chunk", json!([hex::encode([9u8; 32]), 0])).await;
+    assert_eq!(unknown["error"]["code"], rpc::NOT_FOUND);
+    assert!(matches!(RpcClient::new(url).fetch_proof([9u8; 32]).await, Err(AxiomError::RpcError(_))));
+}
+
+#[tokio::test]
+async fn test_watch_only_wallet_reads_over_rpc() {
+    use axiom_core::wallet::Wallet;
+
+    let (state, url) = start_node(2);
+    let expected = state.chain.lock().unwrap().balance(&MINER);
+    assert!(expected > 0);
+
+    let client = RpcClient::new(url);
+    let watch = Wallet::watch_only(MINER);
+    assert_eq!(watch.balance(&client).await.unwrap(), expected);
+    let history = watch.history(&client, 0, 10).await.unwrap();
+    assert_eq!(history["address"], hex::encode(MINER));
+
+    assert!(matches!(watch.create_transaction([2u8; 32], 1, 1, 0, expected), Err(AxiomError::CannotSign(_))));
+}
@@ -25,14 +25,17 @@ mod security_tests {
         chain.difficulty = 10;
         
         let vdf_seed = vdf::evaluate(parent_hash, current_slot);
-        let vdf_proof = main_helper::compute_vdf(vdf_seed, chain.difficulty as u32);
+        let vdf_proof = main_helper::compute_vdf(vdf_seed, chain.vdf_steps);
         let zk_pass = genesis::generate_zk_pass(&wallet, parent_hash);
         
         let block = Block {
             parent: parent_hash,
             slot: current_slot,
+            timestamp: 0,
+            difficulty: chain.difficulty,
             miner: wallet.address,
             transactions: vec![tx.clone(), tx.clone()], // Same transaction twice!
+            merkle_root: Block::merkle_root_of(&[tx.clone(), tx.clone()]),
             vdf_proof,
             zk_proof: zk_pass,
             nonce: 0,
@@ -64,21 +67,24 @@ mod security_tests {
         chain.difficulty = 10;
         
         let vdf_seed = vdf::evaluate(parent_hash, current_slot);
-        let vdf_proof = main_helper::compute_vdf(vdf_seed, chain.difficulty as u32);
+        let vdf_proof = main_helper::compute_vdf(vdf_seed, chain.vdf_steps);
         let zk_pass = genesis::generate_zk_pass(&wallet, parent_hash);
         
         let mut block1 = Block {
             parent: parent_hash,
             slot: current_slot,
+            timestamp: 0,
+            difficulty: chain.difficulty,
             miner: wallet.address,
             transactions: vec![tx.clone()],
+            merkle_root: Block::merkle_root_of(std::slice::from_ref(&tx)),
             vdf_proof,
             zk_proof: zk_pass.clone(),
             nonce: 0,
         };
         
         // Find valid nonce for block 1
-        while !block1.meets_difficulty(chain.difficulty) && block1.nonce < 10000 {
+        while !block1.meets_difficulty() && block1.nonce < 10000 {
             block1.nonce += 1;
         }
         
@@ -90,20 +96,23 @@ mod security_tests {
         let current_slot2 = chain.blocks.len() as u64;
         
         let vdf_seed2 = vdf::evaluate(parent_hash2, current_slot2);
-        let vdf_proof2 = main_helper::compute_vdf(vdf_seed2, chain.difficulty as u32);
+        let vdf_proof2 = main_helper::compute_vdf(vdf_seed2, chain.vdf_steps);
         
         let mut block2 = Block {
             parent: parent_hash2,
             slot: current_slot2,
+            timestamp: 0,
+            difficulty: chain.difficulty,
             miner: wallet.address,
             transactions: vec![tx.clone()], // Same transaction again!
+            merkle_root: Block::merkle_root_of(std::slice::from_ref(&tx)),
             vdf_proof: vdf_proof2,
             zk_proof: zk_pass,
             nonce: 0,
         };
         
         // Find valid nonce for block 2
-        while !block2.meets_difficulty(chain.difficulty) && block2.nonce < 10000 {
+        while !block2.meets_difficulty() && block2.nonce < 10000 {
             block2.nonce += 1;
         }
         
@@ -158,20 +167,23 @@ mod security_tests {
         chain.difficulty = 10;
         
         let vdf_seed = vdf::evaluate(parent_hash, current_slot);
-        let vdf_proof = main_helper::compute_vdf(vdf_seed, chain.difficulty as u32);
+        let vdf_proof = main_helper::compute_vdf(vdf_seed, chain.vdf_steps);
         let zk_pass = genesis::generate_zk_pass(&wallet, parent_hash);
         
         let mut block1 = Block {
             parent: parent_hash,
             slot: current_slot,
+            timestamp: 0,
+            difficulty: chain.difficulty,
             miner: wallet.address,
             transactions: vec![tx1.clone()],
+            merkle_root: Block::merkle_root_of(std::slice::from_ref(&tx1)),
             vdf_proof,
             zk_proof: zk_pass,
             nonce: 0,
         };
         
-        while !block1.meets_difficulty(chain.difficulty) && block1.nonce < 10000 {
+        while !block1.meets_difficulty() && block1.nonce < 10000 {
             block1.nonce += 1;
         }
         
@@ -256,20 +268,24 @@ mod security_tests {
         chain.difficulty = 10;
         
         let vdf_seed = vdf::evaluate(parent_hash, correct_slot);
-        let vdf_proof = main_helper::compute_vdf(vdf_seed, chain.difficulty as u32);
+        let vdf_proof = main_helper::compute_vdf(vdf_seed, chain.vdf_steps);
         let zk_pass = genesis::generate_zk_pass(&wallet, parent_hash);
         
+        let coinbase = vec![chain.expected_coinbase(wallet.address, correct_slot, 0)];
         let mut block1 = Block {
             parent: parent_hash,
             slot: correct_slot,
+            timestamp: 0,
+            difficulty: chain.difficulty,
             miner: wallet.address,
-            transactions: vec![],
+            transactions: coinbase.clone(),
+            merkle_root: Block::merkle_root_of(&coinbase),
             vdf_proof,
             zk_proof: zk_pass.clone(),
             nonce: 0,
         };
         
-        while !block1.meets_difficulty(chain.difficulty) && block1.nonce < 10000 {
+        while !block1.meets_difficulty() && block1.nonce < 10000 {
             block1.nonce += 1;
         }
         
@@ -282,19 +298,22 @@ mod security_tests {
         let wrong_slot = chain.blocks.len() as u64 + 5; // Skip ahead!
         
         let vdf_seed2 = vdf::evaluate(parent_hash2, wrong_slot);
-        let vdf_proof2 = main_helper::compute_vdf(vdf_seed2, chain.difficulty as u32);
+        let vdf_proof2 = main_helper::compute_vdf(vdf_seed2, chain.vdf_steps);
         
         let mut block_wrong = Block {
             parent: parent_hash2,
             slot: wrong_slot,
+            timestamp: 0,
+            difficulty: chain.difficulty,
             miner: wallet.address,
             transactions: vec![],
+            merkle_root: [0u8; 32],
             vdf_proof: vdf_proof2,
             zk_proof: zk_pass,
             nonce: 0,
         };
         
-        while !block_wrong.meets_difficulty(chain.difficulty) && block_wrong.nonce < 10000 {
+        while !block_wrong.meets_difficulty() && block_wrong.nonce < 10000 {
             block_wrong.nonce += 1;
         }
         
@@ -317,20 +336,23 @@ mod security_tests {
         let current_slot = chain.blocks.len() as u64;
         
         let vdf_seed = vdf::evaluate(wrong_parent, current_slot);
-        let vdf_proof = main_helper::compute_vdf(vdf_seed, chain.difficulty as u32);
+        let vdf_proof = main_helper::compute_vdf(vdf_seed, chain.vdf_steps);
         let zk_pass = genesis::generate_zk_pass(&wallet, wrong_parent);
         
         let mut block = Block {
             parent: wrong_parent,
             slot: current_slot,
+            timestamp: 0,
+            difficulty: chain.difficulty,
             miner: wallet.address,
             transactions: vec![],
+            merkle_root: [0u8; 32],
             vdf_proof,
             zk_proof: zk_pass,
             nonce: 0,
         };
         
-        while !block.meets_difficulty(chain.difficulty) && block.nonce < 10000 {
+        while !block.meets_difficulty() && block.nonce < 10000 {
             block.nonce += 1;
         }
         
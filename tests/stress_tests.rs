@@ -26,7 +26,7 @@ mod stress_tests {
             let current_slot = chain.blocks.len() as u64;
             
             let vdf_seed = vdf::evaluate(parent_hash, current_slot);
-            let vdf_proof = main_helper::compute_vdf(vdf_seed, chain.difficulty as u32);
+            let vdf_proof = main_helper::compute_vdf(vdf_seed, chain.vdf_steps);
             let zk_pass = genesis::generate_zk_pass(&wallet, parent_hash);
             
             let start = Instant::now();
@@ -37,14 +37,17 @@ mod stress_tests {
                 let block = Block {
                     parent: parent_hash,
                     slot: current_slot,
+                    timestamp: 0,
+                    difficulty: chain.difficulty,
                     miner: wallet.address,
                     transactions: vec![],
+                    merkle_root: [0u8; 32],
                     vdf_proof,
                     zk_proof: zk_pass.clone(),
                     nonce,
                 };
                 
-                if block.meets_difficulty(chain.difficulty) {
+                if block.meets_difficulty() {
                     found = true;
                 }
                 nonce += 1;
@@ -126,21 +129,25 @@ mod stress_tests {
             let current_slot = chain.blocks.len() as u64;
             
             let vdf_seed = vdf::evaluate(parent_hash, current_slot);
-            let vdf_proof = main_helper::compute_vdf(vdf_seed, chain.difficulty as u32);
+            let vdf_proof = main_helper::compute_vdf(vdf_seed, chain.vdf_steps);
             let zk_pass = genesis::generate_zk_pass(&wallet, parent_hash);
             
+            let coinbase = vec![chain.expected_coinbase(wallet.address, current_slot, 0)];
             let mut block = Block {
                 parent: parent_hash,
                 slot: current_slot,
+                timestamp: 0,
+                difficulty: chain.difficulty,
                 miner: wallet.address,
-                transactions: vec![],
+                transactions: coinbase.clone(),
+                merkle_root: Block::merkle_root_of(&coinbase),
                 vdf_proof,
                 zk_proof: zk_pass,
                 nonce: 0,
             };
             
             // Find valid nonce
-            while !block.meets_difficulty(chain.difficulty) && block.nonce < 50000 {
+            while !block.meets_difficulty() && block.nonce < 50000 {
                 block.nonce += 1;
             }
             
@@ -241,20 +248,24 @@ mod stress_tests {
             let current_slot = (blocks.len() + 1) as u64;
             
             let vdf_seed = vdf::evaluate(parent_hash, current_slot);
-            let vdf_proof = main_helper::compute_vdf(vdf_seed, chain.difficulty as u32);
+            let vdf_proof = main_helper::compute_vdf(vdf_seed, chain.vdf_steps);
             let zk_pass = genesis::generate_zk_pass(&wallet, parent_hash);
             
+            let coinbase = vec![chain.expected_coinbase(wallet.address, current_slot, 0)];
             let mut block = Block {
                 parent: parent_hash,
                 slot: current_slot,
+                timestamp: 0,
+                difficulty: chain.difficulty,
                 miner: wallet.address,
-                transactions: vec![],
+                transactions: coinbase.clone(),
+                merkle_root: Block::merkle_root_of(&coinbase),
                 vdf_proof,
                 zk_proof: zk_pass,
                 nonce: 0,
             };
             
-            while !block.meets_difficulty(chain.difficulty) && block.nonce < 50000 {
+            while !block.meets_difficulty() && block.nonce < 50000 {
                 block.nonce += 1;
             }
             
@@ -304,20 +315,24 @@ mod stress_tests {
             let current_slot = chain1.blocks.len() as u64;
             
             let vdf_seed = vdf::evaluate(parent_hash, current_slot);
-            let vdf_proof = main_helper::compute_vdf(vdf_seed, chain1.difficulty as u32);
+            let vdf_proof = main_helper::compute_vdf(vdf_seed, chain1.vdf_steps);
             let zk_pass = genesis::generate_zk_pass(&wallet, parent_hash);
             
+            let coinbase = vec![chain1.expected_coinbase(wallet.address, current_slot, 0)];
             let mut block = Block {
                 parent: parent_hash,
                 slot: current_slot,
+                timestamp: 0,
+                difficulty: chain1.difficulty,
                 miner: wallet.address,
-                transactions: vec![],
+                transactions: coinbase.clone(),
+                merkle_root: Block::merkle_root_of(&coinbase),
                 vdf_proof,
                 zk_proof: zk_pass,
                 nonce: i * 100, // Different nonces for uniqueness
             };
             
-            while !block.meets_difficulty(chain1.difficulty) && block.nonce < 50000 {
+            while !block.meets_difficulty() && block.nonce < 50000 {
                 block.nonce += 1;
             }
             
@@ -332,20 +347,24 @@ mod stress_tests {
             let current_slot = chain2.blocks.len() as u64;
             
             let vdf_seed = vdf::evaluate(parent_hash, current_slot);
-            let vdf_proof = main_helper::compute_vdf(vdf_seed, chain2.difficulty as u32);
+            let vdf_proof = main_helper::compute_vdf(vdf_seed, chain2.vdf_steps);
             let zk_pass = genesis::generate_zk_pass(&wallet, parent_hash);
             
+            let coinbase = vec![chain2.expected_coinbase(wallet.address, current_slot, 0)];
             let mut block = Block {
                 parent: parent_hash,
                 slot: current_slot,
+                timestamp: 0,
+                difficulty: chain2.difficulty,
                 miner: wallet.address,
-                transactions: vec![],
+                transactions: coinbase.clone(),
+                merkle_root: Block::merkle_root_of(&coinbase),
                 vdf_proof,
                 zk_proof: zk_pass,
                 nonce: i * 200 + 50000, // Different nonces from chain1
             };
             
-            while !block.meets_difficulty(chain2.difficulty) && block.nonce < 100000 {
+            while !block.meets_difficulty() && block.nonce < 100000 {
                 block.nonce += 1;
             }
             